@@ -11,7 +11,7 @@ use http_protocol::http_client::HttpClient;
 use http_protocol::parameter::{DumpParam, SqlParam, WriteParam};
 use http_protocol::status_code::OK;
 use reqwest::header::{HeaderMap, ACCEPT_ENCODING, CONTENT_ENCODING};
-use reqwest::Response;
+use reqwest::{RequestBuilder, Response};
 use tokio::sync::mpsc;
 
 use crate::config::ConfigOptions;
@@ -95,6 +95,11 @@ impl SessionConfig {
         self
     }
 
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.user_info.token = token;
+        self
+    }
+
     pub fn with_tenant(mut self, tenant: String) -> Self {
         self.tenant = tenant;
         self
@@ -210,6 +215,8 @@ pub struct UserInfo {
     pub user: String,
     pub password: Option<String>,
     pub private_key: Option<String>,
+    /// An API token or a JWT, it's used instead of the username and password
+    pub token: Option<String>,
 }
 
 impl Default for UserInfo {
@@ -218,6 +225,16 @@ impl Default for UserInfo {
             user: DEFAULT_USER.to_string(),
             password: None,
             private_key: None,
+            token: None,
+        }
+    }
+}
+
+impl UserInfo {
+    fn authenticate(&self, builder: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder.basic_auth::<&str, &str>(&self.user, self.password.as_deref()),
         }
     }
 }
@@ -299,13 +316,13 @@ impl SessionContext {
             chunked: Some(chunked),
             target_partitions,
             stream_trigger_interval,
+            consistency: None,
+            follower_read_max_lag: None,
         };
 
         // let param = &[("db", &self.session_config.database)];
-        let mut builder = self
-            .http_client
-            .post(API_V1_SQL_PATH)
-            .basic_auth::<&str, &str>(&user_info.user, user_info.password.as_deref())
+        let mut builder = user_info
+            .authenticate(self.http_client.post(API_V1_SQL_PATH))
             .header(ACCEPT, self.session_config.fmt.get_http_content_type());
 
         if let Some(encoding) = self.session_config.accept_encoding {
@@ -419,12 +436,11 @@ impl SessionContext {
             precision: Some(precision),
             tenant: Some(tenant),
             db: Some(db),
+            consistency: None,
         };
 
-        let mut builder = self
-            .http_client
-            .post(API_V1_WRITE_PATH)
-            .basic_auth::<&str, &str>(&user_info.user, user_info.password.as_deref())
+        let mut builder = user_info
+            .authenticate(self.http_client.post(API_V1_WRITE_PATH))
             .query(&param);
 
         if let Some(encoding) = self.session_config.content_encoding {
@@ -476,10 +492,8 @@ impl SessionContext {
 
         for tenant in tenants {
             let param = DumpParam { tenant };
-            let mut builder = self
-                .http_client
-                .get(API_V1_DUMP_SQL_DDL_PATH)
-                .basic_auth::<&str, &str>(&user_info.user, user_info.password.as_deref());
+            let mut builder =
+                user_info.authenticate(self.http_client.get(API_V1_DUMP_SQL_DDL_PATH));
             builder = if let Some(key) = &user_info.private_key {
                 let key = BASE64_STANDARD.encode(key);
                 builder.header(PRIVATE_KEY, key)
@@ -21,12 +21,16 @@ pub enum Function {
     DescribeDatabase,
     Insert,
     DropTable,
+    CreateFunction,
+    DropFunction,
 }
 
-const ALL_FUNCTIONS: [Function; 9] = [
+const ALL_FUNCTIONS: [Function; 11] = [
     Function::CreateTable,
     Function::CreateTableAs,
     Function::DropTable,
+    Function::CreateFunction,
+    Function::DropFunction,
     Function::Explain,
     Function::Insert,
     Function::Select,
@@ -150,6 +154,25 @@ Command:     DROP TABLE
 Description: remove a table
 Syntax:
 DROP TABLE [ IF EXISTS ] name [, ...]
+"#
+            }
+            Function::CreateFunction => {
+                r#"
+Command:     CREATE FUNCTION
+Description: define a new function in the tenant
+Syntax:
+CREATE [ OR REPLACE ] [ AGGREGATE ] FUNCTION name ( [ argument_name argument_type [, ...] ] )
+    RETURNS return_type
+    [ LANGUAGE { SQL | WASM } ]
+    AS 'definition'
+"#
+            }
+            Function::DropFunction => {
+                r#"
+Command:     DROP FUNCTION
+Description: remove a function
+Syntax:
+DROP FUNCTION [ IF EXISTS ] name
 "#
             }
         };
@@ -169,6 +192,8 @@ impl FromStr for Function {
             "CREATE TABLE AS" => Self::CreateTableAs,
             "INSERT" => Self::Insert,
             "DROP TABLE" => Self::DropTable,
+            "CREATE FUNCTION" => Self::CreateFunction,
+            "DROP FUNCTION" => Self::DropFunction,
             _ => return Err(()),
         })
     }
@@ -186,6 +211,8 @@ impl fmt::Display for Function {
             Function::DescribeDatabase => write!(f, "DESCRIBE DATABASE"),
             Function::Insert => write!(f, "INSERT"),
             Function::DropTable => write!(f, "DROP TABLE"),
+            Function::CreateFunction => write!(f, "CREATE FUNCTION"),
+            Function::DropFunction => write!(f, "DROP FUNCTION"),
         }
     }
 }
@@ -44,6 +44,11 @@ struct CliArgs {
     #[arg(long)]
     private_key_path: Option<String>,
 
+    /// Path of the file holding an API token or a JWT, used as the bearer token
+    /// to connect to the CnosDB instead of the username and password
+    #[arg(long)]
+    token_file: Option<String>,
+
     /// Default database to connect to the CnosDB.
     #[arg(short, long, default_value = "public")]
     database: String,
@@ -211,10 +216,19 @@ pub async fn main() -> Result<(), anyhow::Error> {
         fs::read_to_string(p).expect("Read private key file.")
     });
 
+    let token = args.token_file.as_ref().map(|p| {
+        let p = Path::new(p);
+        fs::read_to_string(p)
+            .expect("Read token file.")
+            .trim()
+            .to_string()
+    });
+
     let session_config = args
         .to_session_config()
         .with_password(password)
-        .with_private_key(private_key);
+        .with_private_key(private_key)
+        .with_token(token);
 
     let mut ctx = SessionContext::new(session_config);
     if let Some(ref path) = args.write_line_protocol {
@@ -4,6 +4,8 @@ pub use reqwest::header::{HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 // header
 // privateKey
 pub const PRIVATE_KEY: &str = "X-CnosDB-PrivateKey";
+// the client address behind a proxy
+pub const X_FORWARDED_FOR: &str = "X-Forwarded-For";
 
 // value
 pub const APPLICATION_PREFIX: &str = "application/";
@@ -26,6 +28,8 @@ pub const DB: &str = "db";
 pub const TABLE: &str = "table";
 pub const TARGET_PARTITIONS: &str = "target_partitions";
 pub const STREAM_TRIGGER_INTERVAL: &str = "stream_trigger_interval";
+pub const CONSISTENCY: &str = "consistency";
+pub const FOLLOWER_READ_MAX_LAG: &str = "follower_read_max_lag";
 
 // encoding
 pub const GZIP: &str = "gzip";
@@ -9,6 +9,18 @@ pub struct SqlParam {
     // Number of partitions for query execution. Increasing partitions can increase concurrency.
     pub target_partitions: Option<usize>,
     pub stream_trigger_interval: Option<String>,
+    // Write consistency level of INSERT statements: any, one, quorum or all.
+    pub consistency: Option<String>,
+    // Allow reading from followers lagging behind the leader by at most this many entries or this duration, e.g. 100 or 5s.
+    pub follower_read_max_lag: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct AsyncQueryParam {
+    pub tenant: Option<String>,
+    // Index of the result page to fetch, starting from 0.
+    pub page: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -17,6 +29,21 @@ pub struct WriteParam {
     pub precision: Option<String>,
     pub tenant: Option<String>,
     pub db: Option<String>,
+    // Write consistency level: any, one, quorum or all.
+    pub consistency: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct ChangeFeedParam {
+    pub tenant: Option<String>,
+    pub db: Option<String>,
+    // Only return the changes of this table.
+    pub table: Option<String>,
+    // Position returned by a previous read, read from the oldest change kept if absent.
+    pub position: Option<String>,
+    // Keep the response open and wait for new changes.
+    pub follow: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
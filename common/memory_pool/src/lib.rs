@@ -82,6 +82,83 @@ impl MemoryPool for GreedyMemoryPool {
     }
 }
 
+/// A [`MemoryPool`] that limits the memory used by one query, the memory is allocated
+/// from the shared `inner` pool.
+#[derive(Debug)]
+pub struct QuotaMemoryPool {
+    inner: MemoryPoolRef,
+    quota: usize,
+    used: AtomicUsize,
+}
+
+impl QuotaMemoryPool {
+    pub fn new(inner: MemoryPoolRef, quota: usize) -> Self {
+        Self {
+            inner,
+            quota,
+            used: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl MemoryPool for QuotaMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.used.fetch_add(additional, Ordering::Relaxed);
+        self.inner.grow(reservation, additional)
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.used.fetch_sub(shrink, Ordering::Relaxed);
+        self.inner.shrink(reservation, shrink)
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                let new_used = used + additional;
+                (new_used <= self.quota).then_some(new_used)
+            })
+            .map_err(|_| {
+                DataFusionError::External(Box::new(MemoryQuotaExceeded { quota: self.quota }))
+            })?;
+
+        self.inner.try_grow(reservation, additional).map_err(|err| {
+            self.used.fetch_sub(additional, Ordering::Relaxed);
+            err
+        })
+    }
+
+    fn reserved(&self) -> usize {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+/// The error of a [`QuotaMemoryPool`] running out of its quota
+#[derive(Debug)]
+pub struct MemoryQuotaExceeded {
+    pub quota: usize,
+}
+
+impl std::fmt::Display for MemoryQuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The query exceeded its memory quota of {} bytes",
+            self.quota
+        )
+    }
+}
+
+impl std::error::Error for MemoryQuotaExceeded {}
+
 fn insufficient_capacity_err(
     reservation: &MemoryReservation,
     additional: usize,
@@ -126,4 +203,29 @@ mod tests {
         a2.try_grow(25).unwrap();
         assert_eq!(pool.reserved(), 25);
     }
+
+    #[test]
+    fn test_quota() {
+        let shared = Arc::new(GreedyMemoryPool::new(100)) as MemoryPoolRef;
+        let pool = Arc::new(QuotaMemoryPool::new(shared.clone(), 50)) as MemoryPoolRef;
+        let mut a1 = MemoryConsumer::new("a1").register(&pool);
+
+        a1.try_grow(40).unwrap();
+        assert_eq!(pool.reserved(), 40);
+        assert_eq!(shared.reserved(), 40);
+
+        let err = a1.try_grow(20).unwrap_err();
+        assert!(matches!(err, DataFusionError::External(e) if e.is::<MemoryQuotaExceeded>()));
+        assert_eq!(shared.reserved(), 40);
+
+        // the shared pool runs out first
+        let mut a2 = MemoryConsumer::new("a2").register(&shared);
+        a2.try_grow(55).unwrap();
+        a1.try_grow(10).unwrap_err();
+        assert_eq!(pool.reserved(), 40);
+
+        drop(a1);
+        assert_eq!(pool.reserved(), 0);
+        assert_eq!(shared.reserved(), 55);
+    }
 }
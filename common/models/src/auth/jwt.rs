@@ -0,0 +1,376 @@
+use base64::prelude::{Engine, BASE64_URL_SAFE_NO_PAD};
+use openssl::bn::BigNum;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::ecdsa::EcdsaSig;
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Public};
+use openssl::rsa::Rsa;
+use openssl::sign::Verifier;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::{AuthError, AuthResult};
+use crate::utils::now_timestamp_millis;
+
+pub type JwtClaims = Map<String, Value>;
+
+/// The checks of the registered claims of a JWT besides its signature
+#[derive(Debug, Clone, Default)]
+pub struct JwtValidation {
+    /// The `iss` claim must be equal to it
+    pub issuer: String,
+    /// The `aud` claim must contain it
+    pub audience: String,
+    /// The clock skew allowed when checking `exp` and `nbf`
+    pub leeway_secs: i64,
+}
+
+/// A JSON Web Key Set, the public keys verifying the signatures of the JWTs
+pub struct Jwks {
+    keys: Vec<JwtKey>,
+}
+
+struct JwtKey {
+    kid: Option<String>,
+    alg: Option<String>,
+    key: PKey<Public>,
+}
+
+#[derive(Deserialize)]
+struct JwkSet {
+    keys: Vec<Jwk>,
+}
+
+#[derive(Deserialize)]
+struct Jwk {
+    kty: String,
+    kid: Option<String>,
+    alg: Option<String>,
+    // RSA
+    n: Option<String>,
+    e: Option<String>,
+    // EC
+    crv: Option<String>,
+    x: Option<String>,
+    y: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+/// Whether the bearer token looks like a JWT, i.e. `<header>.<claims>.<signature>`
+pub fn is_jwt(token: &str) -> bool {
+    token.starts_with("eyJ") && token.split('.').count() == 3
+}
+
+/// The `kid` in the header of the JWT
+pub fn jwt_kid(token: &str) -> Option<String> {
+    let header = token.split('.').next()?;
+    decode_json::<JwtHeader>(header).ok()?.kid
+}
+
+impl Jwks {
+    /// Parses a JWKS document, the keys with unsupported types are skipped
+    pub fn from_json(json: &[u8]) -> AuthResult<Self> {
+        let set: JwkSet = serde_json::from_slice(json).map_err(|e| AuthError::Jwt {
+            reason: format!("invalid JWKS: {e}"),
+        })?;
+
+        let keys = set
+            .keys
+            .into_iter()
+            .filter_map(|jwk| {
+                let key = jwk.public_key().ok()??;
+                Some(JwtKey {
+                    kid: jwk.kid,
+                    alg: jwk.alg,
+                    key,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Self { keys })
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains_kid(&self, kid: &str) -> bool {
+        self.keys.iter().any(|k| k.kid.as_deref() == Some(kid))
+    }
+
+    /// Verifies the signature and the registered claims of the JWT, returns its claims
+    pub fn verify(&self, token: &str, validation: &JwtValidation) -> AuthResult<JwtClaims> {
+        let mut parts = token.split('.');
+        let (header, claims, signature) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(h), Some(c), Some(s)) if parts.next().is_none() => (h, c, s),
+            _ => return Err(jwt_error("malformed token")),
+        };
+
+        let jwt_header = decode_json::<JwtHeader>(header)?;
+        let signature = BASE64_URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| jwt_error("malformed signature"))?;
+        let message = &token[..header.len() + 1 + claims.len()];
+
+        let candidates = self
+            .keys
+            .iter()
+            .filter(|k| match (&jwt_header.kid, &k.kid) {
+                (Some(kid), Some(key_kid)) => kid == key_kid,
+                _ => true,
+            });
+        let mut verified = false;
+        for key in candidates {
+            if key.alg.as_ref().is_some_and(|alg| alg != &jwt_header.alg) {
+                continue;
+            }
+            if verify_signature(&jwt_header.alg, &key.key, message.as_bytes(), &signature)? {
+                verified = true;
+                break;
+            }
+        }
+        if !verified {
+            return Err(jwt_error("signature verification failed"));
+        }
+
+        let claims = decode_json::<JwtClaims>(claims)?;
+        validate_claims(&claims, validation)?;
+
+        Ok(claims)
+    }
+}
+
+impl Jwk {
+    fn public_key(&self) -> AuthResult<Option<PKey<Public>>> {
+        let key = match self.kty.as_str() {
+            "RSA" => {
+                let (Some(n), Some(e)) = (&self.n, &self.e) else {
+                    return Ok(None);
+                };
+                let rsa = Rsa::from_public_components(decode_big_num(n)?, decode_big_num(e)?)
+                    .map_err(jwt_error)?;
+                PKey::from_rsa(rsa).map_err(jwt_error)?
+            }
+            "EC" => {
+                let (Some(crv), Some(x), Some(y)) = (&self.crv, &self.x, &self.y) else {
+                    return Ok(None);
+                };
+                let nid = match crv.as_str() {
+                    "P-256" => Nid::X9_62_PRIME256V1,
+                    "P-384" => Nid::SECP384R1,
+                    _ => return Ok(None),
+                };
+                let group = EcGroup::from_curve_name(nid).map_err(jwt_error)?;
+                let ec = EcKey::from_public_key_affine_coordinates(
+                    &group,
+                    &decode_big_num(x)?,
+                    &decode_big_num(y)?,
+                )
+                .map_err(jwt_error)?;
+                PKey::from_ec_key(ec).map_err(jwt_error)?
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(key))
+    }
+}
+
+fn verify_signature(
+    alg: &str,
+    key: &PKey<Public>,
+    message: &[u8],
+    signature: &[u8],
+) -> AuthResult<bool> {
+    let (digest, ec_size) = match alg {
+        "RS256" => (MessageDigest::sha256(), None),
+        "RS384" => (MessageDigest::sha384(), None),
+        "RS512" => (MessageDigest::sha512(), None),
+        "ES256" => (MessageDigest::sha256(), Some(32)),
+        "ES384" => (MessageDigest::sha384(), Some(48)),
+        _ => return Err(jwt_error(format!("unsupported algorithm {alg}"))),
+    };
+
+    // the key type must match the algorithm, e.g. a HS256 token can't be verified by a RSA key
+    let is_rsa = key.rsa().is_ok();
+    if is_rsa == ec_size.is_some() {
+        return Ok(false);
+    }
+
+    // JWS encodes an ECDSA signature as r || s, openssl expects DER
+    let der;
+    let signature = match ec_size {
+        Some(size) => {
+            if signature.len() != size * 2 {
+                return Ok(false);
+            }
+            let r = BigNum::from_slice(&signature[..size]).map_err(jwt_error)?;
+            let s = BigNum::from_slice(&signature[size..]).map_err(jwt_error)?;
+            der = EcdsaSig::from_private_components(r, s)
+                .and_then(|sig| sig.to_der())
+                .map_err(jwt_error)?;
+            der.as_slice()
+        }
+        None => signature,
+    };
+
+    let mut verifier = Verifier::new(digest, key).map_err(jwt_error)?;
+    verifier.update(message).map_err(jwt_error)?;
+    Ok(verifier.verify(signature).unwrap_or(false))
+}
+
+fn validate_claims(claims: &JwtClaims, validation: &JwtValidation) -> AuthResult<()> {
+    let now = now_timestamp_millis() / 1000;
+
+    match claims.get("exp").and_then(Value::as_i64) {
+        Some(exp) if exp + validation.leeway_secs < now => {
+            return Err(jwt_error("token has expired"));
+        }
+        Some(_) => {}
+        None => return Err(jwt_error("missing claim exp")),
+    }
+    if let Some(nbf) = claims.get("nbf").and_then(Value::as_i64) {
+        if nbf - validation.leeway_secs > now {
+            return Err(jwt_error("token is not valid yet"));
+        }
+    }
+
+    if claims.get("iss").and_then(Value::as_str) != Some(validation.issuer.as_str()) {
+        return Err(jwt_error("invalid issuer"));
+    }
+
+    // `aud` is either a string or an array of strings
+    let audience = validation.audience.as_str();
+    let matched = match claims.get("aud") {
+        Some(Value::String(aud)) => aud == audience,
+        Some(Value::Array(auds)) => auds.iter().any(|aud| aud.as_str() == Some(audience)),
+        _ => false,
+    };
+    if !matched {
+        return Err(jwt_error("invalid audience"));
+    }
+
+    Ok(())
+}
+
+fn decode_json<T: serde::de::DeserializeOwned>(part: &str) -> AuthResult<T> {
+    let bytes = BASE64_URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| jwt_error("malformed token"))?;
+    serde_json::from_slice(&bytes).map_err(|_| jwt_error("malformed token"))
+}
+
+fn decode_big_num(value: &str) -> AuthResult<BigNum> {
+    let bytes = BASE64_URL_SAFE_NO_PAD
+        .decode(value)
+        .map_err(|_| jwt_error("malformed key"))?;
+    BigNum::from_slice(&bytes).map_err(jwt_error)
+}
+
+fn jwt_error(reason: impl ToString) -> AuthError {
+    AuthError::Jwt {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use openssl::pkey::Private;
+    use openssl::sign::Signer;
+    use serde_json::json;
+
+    use super::*;
+
+    fn sign(key: &PKey<Private>, header: Value, claims: Value) -> String {
+        let message = format!(
+            "{}.{}",
+            BASE64_URL_SAFE_NO_PAD.encode(header.to_string()),
+            BASE64_URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let mut signer = Signer::new(MessageDigest::sha256(), key).unwrap();
+        signer.update(message.as_bytes()).unwrap();
+        let signature = signer.sign_to_vec().unwrap();
+        format!("{message}.{}", BASE64_URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    #[test]
+    fn test_verify_rs256() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let jwks = json!({
+            "keys": [{
+                "kty": "RSA",
+                "kid": "k1",
+                "alg": "RS256",
+                "n": BASE64_URL_SAFE_NO_PAD.encode(rsa.n().to_vec()),
+                "e": BASE64_URL_SAFE_NO_PAD.encode(rsa.e().to_vec()),
+            }, {
+                "kty": "oct",
+                "k": "c2VjcmV0",
+            }]
+        });
+        let jwks = Jwks::from_json(jwks.to_string().as_bytes()).unwrap();
+        assert_eq!(jwks.len(), 1);
+        assert!(jwks.contains_kid("k1"));
+
+        let key = PKey::from_rsa(rsa).unwrap();
+        let header = json!({"alg": "RS256", "kid": "k1"});
+        let exp = now_timestamp_millis() / 1000 + 60;
+        let validation = JwtValidation {
+            issuer: "https://sso.example.com".to_string(),
+            audience: "cnosdb".to_string(),
+            leeway_secs: 0,
+        };
+
+        let token = sign(
+            &key,
+            header.clone(),
+            json!({"sub": "alice", "iss": "https://sso.example.com", "aud": ["cnosdb"], "exp": exp}),
+        );
+        assert!(is_jwt(&token));
+        assert_eq!(jwt_kid(&token).as_deref(), Some("k1"));
+        let claims = jwks.verify(&token, &validation).unwrap();
+        assert_eq!(claims.get("sub"), Some(&json!("alice")));
+
+        // tampered claims
+        let mut parts = token.split('.').collect::<Vec<_>>();
+        let forged = BASE64_URL_SAFE_NO_PAD.encode(
+            json!({"sub": "root", "iss": "https://sso.example.com", "aud": "cnosdb", "exp": exp})
+                .to_string(),
+        );
+        parts[1] = &forged;
+        assert!(jwks.verify(&parts.join("."), &validation).is_err());
+
+        let expired = sign(
+            &key,
+            header.clone(),
+            json!({"sub": "alice", "iss": "https://sso.example.com", "aud": "cnosdb", "exp": exp - 120}),
+        );
+        assert!(jwks.verify(&expired, &validation).is_err());
+
+        let other_audience = sign(
+            &key,
+            header,
+            json!({"sub": "alice", "iss": "https://sso.example.com", "aud": "other", "exp": exp}),
+        );
+        assert!(jwks.verify(&other_audience, &validation).is_err());
+
+        let without_issuer = sign(
+            &key,
+            json!({"alg": "RS256", "kid": "k1"}),
+            json!({"sub": "alice", "aud": "cnosdb", "exp": exp}),
+        );
+        assert!(jwks.verify(&without_issuer, &validation).is_err());
+
+        assert!(!is_jwt("cnos_1_secret"));
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use config::common::PasswordPolicyConfig;
+use parking_lot::Mutex;
+
+/// Tracks the consecutive failed logins of the users, and locks the users
+/// failing too many times.
+///
+/// The state is kept in the memory of the node only, it isn't shared with the
+/// other nodes and is lost when the node restarts.
+#[derive(Debug, Default)]
+pub struct LoginLockout {
+    users: Mutex<HashMap<String, FailedLogins>>,
+}
+
+#[derive(Debug, Default)]
+struct FailedLogins {
+    // the failed logins since the last lockout
+    count: u32,
+    // the lockouts since the last successful login
+    lockouts: u32,
+    locked_until: Option<Instant>,
+}
+
+impl LoginLockout {
+    /// The remaining time of the lockout of the user, None if the user isn't locked.
+    pub fn locked_for(&self, user_name: &str) -> Option<Duration> {
+        let users = self.users.lock();
+        let locked_until = users.get(user_name)?.locked_until?;
+        locked_until
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Records a failed login of the user, returns the duration of the lockout
+    /// if the user is locked by it.
+    pub fn login_failed(&self, user_name: &str, policy: &PasswordPolicyConfig) -> Option<Duration> {
+        if policy.max_failed_logins == 0 {
+            return None;
+        }
+
+        let mut users = self.users.lock();
+        let failed_logins = users.entry(user_name.to_string()).or_default();
+        failed_logins.count += 1;
+        if failed_logins.count < policy.max_failed_logins {
+            return None;
+        }
+
+        failed_logins.count = 0;
+        failed_logins.lockouts = failed_logins.lockouts.saturating_add(1);
+        let duration = policy.lockout_duration_of(failed_logins.lockouts);
+        failed_logins.locked_until = Some(Instant::now() + duration);
+        Some(duration)
+    }
+
+    pub fn login_succeeded(&self, user_name: &str) {
+        self.users.lock().remove(user_name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use config::common::PasswordPolicyConfig;
+
+    use super::LoginLockout;
+
+    #[test]
+    fn test_login_lockout() {
+        let policy = PasswordPolicyConfig {
+            max_failed_logins: 3,
+            lockout_duration: Duration::from_secs(60),
+            max_lockout_duration: Duration::from_secs(600),
+            ..Default::default()
+        };
+        let lockout = LoginLockout::default();
+
+        assert_eq!(lockout.login_failed("u1", &policy), None);
+        assert_eq!(lockout.login_failed("u1", &policy), None);
+        assert!(lockout.locked_for("u1").is_none());
+        assert_eq!(
+            lockout.login_failed("u1", &policy),
+            Some(Duration::from_secs(60))
+        );
+        assert!(lockout.locked_for("u1").is_some());
+        assert!(lockout.locked_for("u2").is_none());
+
+        // the next lockout lasts twice as long
+        for _ in 0..2 {
+            lockout.login_failed("u1", &policy);
+        }
+        assert_eq!(
+            lockout.login_failed("u1", &policy),
+            Some(Duration::from_secs(120))
+        );
+
+        lockout.login_succeeded("u1");
+        assert!(lockout.locked_for("u1").is_none());
+    }
+}
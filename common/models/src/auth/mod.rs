@@ -1,15 +1,22 @@
 use bcrypt::BcryptError;
 use openssl::error::ErrorStack;
-pub use password::{bcrypt_hash, bcrypt_verify};
+pub use password::{
+    bcrypt_hash, bcrypt_verify, check_password_history, check_password_strength,
+    is_password_expired,
+};
 use snafu::{Backtrace, Location, Snafu};
 
 use crate::auth::privilege::DatabasePrivilege;
 
 pub mod auth_cache;
+pub mod jwt;
+pub mod lockout;
 mod password;
+pub mod policy;
 pub mod privilege;
 pub mod role;
 pub mod rsa_utils;
+pub mod token;
 pub mod user;
 
 pub type AuthResult<T> = std::result::Result<T, AuthError>;
@@ -60,6 +67,9 @@ pub enum AuthError {
         role: String,
     },
 
+    #[snafu(display("The privilege {} not found in the role {}", privilege, role))]
+    TablePrivilegeNotFound { privilege: String, role: String },
+
     #[snafu(display("The user {} already exists", user))]
     UserAlreadyExists { user: String },
 
@@ -69,6 +79,22 @@ pub enum AuthError {
     #[snafu(display("{}", err))]
     Metadata { err: String },
 
+    #[snafu(display("Invalid JWT: {}", reason))]
+    Jwt { reason: String },
+
+    #[snafu(display("LDAP error: {}", reason))]
+    Ldap { reason: String },
+
+    #[snafu(display("The password doesn't meet the password policy: {}", reason))]
+    PasswordPolicy { reason: String },
+
+    #[snafu(display(
+        "The user {} is locked for {} seconds because of too many failed logins",
+        user_name,
+        seconds
+    ))]
+    UserLocked { user_name: String, seconds: u64 },
+
     #[snafu(display("Bcrypt Error:{}", source))]
     Bcrypt { source: BcryptError },
 
@@ -1,3 +1,6 @@
+use config::common::PasswordPolicyConfig;
+
+use crate::auth::user::UserOptions;
 use crate::auth::AuthError;
 
 pub fn bcrypt_hash(password: &str) -> Result<String, AuthError> {
@@ -7,3 +10,128 @@ pub fn bcrypt_hash(password: &str) -> Result<String, AuthError> {
 pub fn bcrypt_verify(password: &str, hash_password: &str) -> Result<bool, AuthError> {
     Ok(bcrypt::verify(password, hash_password)?)
 }
+
+/// Checks the length and the character classes of a new password.
+pub fn check_password_strength(
+    policy: &PasswordPolicyConfig,
+    password: &str,
+) -> Result<(), AuthError> {
+    if password.chars().count() < policy.min_length {
+        return Err(AuthError::PasswordPolicy {
+            reason: format!(
+                "the password should contain at least {} characters",
+                policy.min_length
+            ),
+        });
+    }
+
+    let char_classes = [
+        password.chars().any(|c| c.is_lowercase()),
+        password.chars().any(|c| c.is_uppercase()),
+        password.chars().any(|c| c.is_ascii_digit()),
+        password.chars().any(|c| !c.is_alphanumeric()),
+    ]
+    .into_iter()
+    .filter(|contained| *contained)
+    .count();
+    if char_classes < policy.min_char_classes {
+        return Err(AuthError::PasswordPolicy {
+            reason: format!(
+                "the password should contain at least {} of lowercase letters, uppercase letters, digits and other characters",
+                policy.min_char_classes
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks the new password is not one of the previous passwords in `options`.
+pub fn check_password_history(
+    policy: &PasswordPolicyConfig,
+    password: &str,
+    options: &UserOptions,
+) -> Result<(), AuthError> {
+    for hash_password in options.password_history().iter().take(policy.history) {
+        // If an error is reported, we treat the password as inconsistent
+        if bcrypt_verify(password, hash_password).is_ok_and(|x| x) {
+            return Err(AuthError::PasswordPolicy {
+                reason: format!(
+                    "the password should be different from the last {} passwords",
+                    policy.history
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the password should be changed, the passwords set without a policy never expire.
+pub fn is_password_expired(
+    policy: &PasswordPolicyConfig,
+    options: &UserOptions,
+    now_secs: i64,
+) -> bool {
+    if policy.expire_after.is_zero() {
+        return false;
+    }
+    options.password_changed_at().is_some_and(|changed_at| {
+        now_secs.saturating_sub(changed_at) >= policy.expire_after.as_secs() as i64
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use config::common::PasswordPolicyConfig;
+
+    use super::*;
+    use crate::auth::user::UserOptionsBuilder;
+
+    #[test]
+    fn test_password_strength() {
+        let policy = PasswordPolicyConfig::default();
+
+        assert!(check_password_strength(&policy, "Ab1!").is_err());
+        assert!(check_password_strength(&policy, "abcdefgh").is_err());
+        assert!(check_password_strength(&policy, "abcdefgH").is_err());
+        assert!(check_password_strength(&policy, "abcdefH1").is_ok());
+        assert!(check_password_strength(&policy, "abcdef_1").is_ok());
+    }
+
+    #[test]
+    fn test_password_history_and_expiration() {
+        let policy = PasswordPolicyConfig {
+            history: 2,
+            expire_after: Duration::from_secs(100),
+            ..Default::default()
+        };
+
+        let mut options = UserOptionsBuilder::default()
+            .password("Password1")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(!is_password_expired(&policy, &options, 1000));
+
+        for (i, password) in ["Password2", "Password3", "Password4"].iter().enumerate() {
+            let mut new_options = UserOptionsBuilder::default()
+                .password(*password)
+                .unwrap()
+                .build()
+                .unwrap();
+            new_options.rotate_password(&options, 1000 + i as i64, policy.history);
+            options = new_options.merge(options);
+        }
+
+        assert_eq!(options.password_history().len(), 2);
+        assert!(check_password_history(&policy, "Password3", &options).is_err());
+        assert!(check_password_history(&policy, "Password2", &options).is_err());
+        assert!(check_password_history(&policy, "Password1", &options).is_ok());
+
+        assert!(!is_password_expired(&policy, &options, 1050));
+        assert!(is_password_expired(&policy, &options, 1102));
+    }
+}
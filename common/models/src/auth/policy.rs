@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+/// A row-level security policy or a column masking policy of a table.
+///
+/// The queries, deletes and updates of the members of `role` can only see the rows of the table
+/// for which `using` is true, e.g. `tag_customer = current_user()`.
+///
+/// If the policy masks a column, the members of `role` see the value of `using` instead of
+/// the value of the column in the query results, e.g. `mask_partial(tag_phone, 3, 4)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDesc {
+    name: String,
+    database: String,
+    table: String,
+    // the name of the tenant role
+    role: String,
+    // sql expression
+    using: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    masked_column: Option<String>,
+}
+
+impl PolicyDesc {
+    pub fn new(name: String, database: String, table: String, role: String, using: String) -> Self {
+        Self {
+            name,
+            database,
+            table,
+            role,
+            using,
+            masked_column: None,
+        }
+    }
+
+    pub fn with_masked_column(mut self, column: String) -> Self {
+        self.masked_column = Some(column);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    pub fn using(&self) -> &str {
+        &self.using
+    }
+
+    pub fn masked_column(&self) -> Option<&str> {
+        self.masked_column.as_deref()
+    }
+
+    /// Whether the policy restricts the rows of the table for the members of the role
+    pub fn applies_to(&self, database: &str, table: &str, role: &str) -> bool {
+        self.masked_column.is_none() && self.on(database, table, role)
+    }
+
+    /// The column of the table masked by the policy for the members of the role
+    pub fn masks(&self, database: &str, table: &str, role: &str) -> Option<&str> {
+        self.masked_column
+            .as_deref()
+            .filter(|_| self.on(database, table, role))
+    }
+
+    /// Whether the policy restricts the rows or masks a column of the table for the members of the role
+    pub fn on(&self, database: &str, table: &str, role: &str) -> bool {
+        self.database == database && self.table == table && self.role == role
+    }
+}
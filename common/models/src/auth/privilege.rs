@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::hash::Hash;
 
@@ -120,6 +121,8 @@ pub enum TenantObjectPrivilege {
     // T: database_name
     // None: all databases in this tenant
     Database(DatabasePrivilege, Option<String>),
+    // The privilege on a table, or on some columns of the table
+    Table(TablePrivilege),
 }
 
 impl Display for TenantObjectPrivilege {
@@ -142,6 +145,9 @@ impl Display for TenantObjectPrivilege {
                     write!(f, "{:?} on all databases", p)
                 }
             },
+            Self::Table(p) => {
+                write!(f, "{}", p)
+            }
         }
     }
 }
@@ -156,11 +162,90 @@ impl PrivilegeChecker for TenantObjectPrivilege {
             (Self::Database(s, Some(s_t)), Self::Database(o, Some(o_t))) => {
                 s_t == o_t && s.check_privilege(o)
             }
+            (Self::Database(s, None), Self::Table(o)) => s.check_privilege(o.privilege()),
+            (Self::Database(s, Some(s_t)), Self::Table(o)) => {
+                s_t == o.database() && s.check_privilege(o.privilege())
+            }
+            (Self::Table(s), Self::Table(o)) => s.check_privilege(o),
             (l, r) => l == r,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TablePrivilege {
+    privilege: DatabasePrivilege,
+    database: String,
+    table: String,
+    // Some: only these columns of the table
+    // None: all columns of the table
+    columns: Option<BTreeSet<String>>,
+}
+
+impl TablePrivilege {
+    pub fn new(
+        privilege: DatabasePrivilege,
+        database: String,
+        table: String,
+        columns: Option<BTreeSet<String>>,
+    ) -> Self {
+        Self {
+            privilege,
+            database,
+            table,
+            columns,
+        }
+    }
+
+    pub fn privilege(&self) -> &DatabasePrivilege {
+        &self.privilege
+    }
+
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    pub fn columns(&self) -> Option<&BTreeSet<String>> {
+        self.columns.as_ref()
+    }
+
+    pub fn is_same_table(&self, other: &Self) -> bool {
+        self.database == other.database && self.table == other.table
+    }
+}
+
+impl Display for TablePrivilege {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} on table {}.{}",
+            self.privilege, self.database, self.table
+        )?;
+        if let Some(columns) = &self.columns {
+            let columns = columns.iter().cloned().collect::<Vec<_>>().join(", ");
+            write!(f, " ({})", columns)?;
+        }
+        Ok(())
+    }
+}
+
+impl PrivilegeChecker for TablePrivilege {
+    fn check_privilege(&self, other: &Self) -> bool {
+        let columns_covered = match (&self.columns, &other.columns) {
+            (None, _) => true,
+            (Some(s), Some(o)) => o.is_subset(s),
+            (Some(_), None) => false,
+        };
+        self.is_same_table(other)
+            && self.privilege.check_privilege(&other.privilege)
+            && columns_covered
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DatabasePrivilege {
     Read,
@@ -188,3 +273,38 @@ impl PrivilegeChecker for DatabasePrivilege {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn table_privilege(
+        privilege: DatabasePrivilege,
+        columns: Option<&[&str]>,
+    ) -> TenantObjectPrivilege {
+        TenantObjectPrivilege::Table(TablePrivilege::new(
+            privilege,
+            "db1".to_string(),
+            "t1".to_string(),
+            columns.map(|c| c.iter().map(|c| c.to_string()).collect()),
+        ))
+    }
+
+    #[test]
+    fn test_check_table_privilege() {
+        let database = TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some("db1".into()));
+        assert!(database.check_privilege(&table_privilege(DatabasePrivilege::Read, None)));
+        assert!(!database.check_privilege(&table_privilege(DatabasePrivilege::Write, None)));
+
+        let other_database =
+            TenantObjectPrivilege::Database(DatabasePrivilege::Full, Some("db2".into()));
+        assert!(!other_database.check_privilege(&table_privilege(DatabasePrivilege::Read, None)));
+
+        let columns = table_privilege(DatabasePrivilege::Write, Some(&["c1", "c2"]));
+        assert!(columns.check_privilege(&table_privilege(DatabasePrivilege::Read, Some(&["c1"]))));
+        assert!(!columns.check_privilege(&table_privilege(DatabasePrivilege::Read, Some(&["c3"]))));
+        assert!(!columns.check_privilege(&table_privilege(DatabasePrivilege::Read, None)));
+        assert!(!columns.check_privilege(&table_privilege(DatabasePrivilege::Full, Some(&["c1"]))));
+        assert!(!columns.check_privilege(&database));
+    }
+}
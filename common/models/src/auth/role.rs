@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::hash::Hash;
 use std::sync::Arc;
@@ -6,7 +6,9 @@ use std::sync::Arc;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
-use super::privilege::{DatabasePrivilege, GlobalPrivilege, Privilege, TenantObjectPrivilege};
+use super::privilege::{
+    DatabasePrivilege, GlobalPrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
+};
 use super::AuthResult;
 use crate::auth::AuthError;
 use crate::oid::{Id, Identifier};
@@ -161,6 +163,36 @@ pub struct CustomTenantRole<T> {
     // database_name -> privileges
     // only add database privilege
     additional_privileges: HashMap<String, DatabasePrivilege>,
+    // at most one privilege for each table
+    #[serde(default)]
+    table_privileges: Vec<TablePrivilege>,
+    #[serde(default)]
+    quota: RoleQuota,
+}
+
+/// The resources each member of a custom role can use, unlimited if absent.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleQuota {
+    /// The batch queries a member can run at the same time on each query node,
+    /// every node counts the queries it runs on its own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_queries: Option<usize>,
+    /// The memory a query can use, in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_query_memory: Option<usize>,
+    /// The bytes a query can read from the table scans: the bytes of the pages read from
+    /// the tsm files by tskv and the tag values returned by the tag scans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_scan_bytes: Option<u64>,
+    /// The rows a query can return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_result_rows: Option<u64>,
+}
+
+impl RoleQuota {
+    pub fn is_unlimited(&self) -> bool {
+        self == &Self::default()
+    }
 }
 
 impl<T> CustomTenantRole<T> {
@@ -177,6 +209,8 @@ impl<T> CustomTenantRole<T> {
             name,
             system_role,
             additional_privileges,
+            table_privileges: vec![],
+            quota: RoleQuota::default(),
         }
     }
 
@@ -187,6 +221,18 @@ impl<T> CustomTenantRole<T> {
     pub fn additional_privileges(&self) -> &HashMap<String, DatabasePrivilege> {
         &self.additional_privileges
     }
+
+    pub fn table_privileges(&self) -> &[TablePrivilege] {
+        &self.table_privileges
+    }
+
+    pub fn quota(&self) -> &RoleQuota {
+        &self.quota
+    }
+
+    pub fn set_quota(&mut self, quota: RoleQuota) {
+        self.quota = quota;
+    }
 }
 
 impl<T: Id> CustomTenantRole<T> {
@@ -205,6 +251,12 @@ impl<T: Id> CustomTenantRole<T> {
                     Some(tenant_id.clone()),
                 )
             })
+            .chain(self.table_privileges.iter().map(|privilege| {
+                Privilege::TenantObject(
+                    TenantObjectPrivilege::Table(privilege.clone()),
+                    Some(tenant_id.clone()),
+                )
+            }))
             .collect::<HashSet<Privilege<T>>>();
 
         privileges.union(&additiona_privileges).cloned().collect()
@@ -243,6 +295,68 @@ impl<T: Id> CustomTenantRole<T> {
             })
         }
     }
+
+    /// Grant the privilege on the table, the columns are merged into
+    /// the existing privilege of the same level on the table.
+    pub fn grant_table_privilege(&mut self, privilege: TablePrivilege) -> AuthResult<()> {
+        match self
+            .table_privileges
+            .iter_mut()
+            .find(|e| e.is_same_table(&privilege))
+        {
+            Some(existing) if existing.privilege() == privilege.privilege() => {
+                let columns = match (existing.columns(), privilege.columns()) {
+                    (Some(l), Some(r)) => Some(l.union(r).cloned().collect()),
+                    (_, _) => None,
+                };
+                *existing = TablePrivilege::new(
+                    privilege.privilege().clone(),
+                    privilege.database().to_string(),
+                    privilege.table().to_string(),
+                    columns,
+                );
+            }
+            Some(existing) => *existing = privilege,
+            None => self.table_privileges.push(privilege),
+        }
+
+        Ok(())
+    }
+
+    /// Revoke the privilege on the table, or only on some of the granted columns of the table.
+    pub fn revoke_table_privilege(&mut self, privilege: &TablePrivilege) -> AuthResult<()> {
+        let not_found = || AuthError::TablePrivilegeNotFound {
+            privilege: privilege.to_string(),
+            role: self.name.to_owned(),
+        };
+
+        let idx = self
+            .table_privileges
+            .iter()
+            .position(|e| e.is_same_table(privilege) && e.privilege() == privilege.privilege())
+            .ok_or_else(not_found)?;
+
+        let remaining = match (self.table_privileges[idx].columns(), privilege.columns()) {
+            (_, None) => BTreeSet::new(),
+            (Some(granted), Some(revoked)) if revoked.is_subset(granted) => {
+                granted.difference(revoked).cloned().collect()
+            }
+            (_, _) => return Err(not_found()),
+        };
+
+        if remaining.is_empty() {
+            self.table_privileges.remove(idx);
+        } else {
+            self.table_privileges[idx] = TablePrivilege::new(
+                privilege.privilege().clone(),
+                privilege.database().to_string(),
+                privilege.table().to_string(),
+                Some(remaining),
+            );
+        }
+
+        Ok(())
+    }
 }
 
 impl<T> Identifier<T> for CustomTenantRole<T> {
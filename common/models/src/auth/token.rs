@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use openssl::memcmp;
+use openssl::sha::sha256;
+use serde::{Deserialize, Serialize};
+
+use super::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
+use crate::oid::{Oid, UuidGenerator};
+use crate::utils::now_timestamp_millis;
+
+/// The prefix of the API tokens, a token looks like `cnos_<token id>_<secret>`
+pub const TOKEN_PREFIX: &str = "cnos_";
+
+/// An API token of a user, only the hash of the secret is stored.
+///
+/// The token can only be used to access the databases of its privileges,
+/// which are further limited by the privileges of the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenDesc {
+    id: Oid,
+    name: String,
+    user_id: Oid,
+    user_name: String,
+    // (privilege, database)
+    privileges: Vec<(DatabasePrivilege, String)>,
+    // hex encoded sha256 of the secret
+    hash: String,
+    // milliseconds since the unix epoch
+    created_at: i64,
+    expires_at: Option<i64>,
+}
+
+impl TokenDesc {
+    /// Generates a token, returns its description and the plain token which is only shown once
+    pub fn generate(
+        name: String,
+        user_id: Oid,
+        user_name: String,
+        privileges: Vec<(DatabasePrivilege, String)>,
+        expires_at: Option<i64>,
+    ) -> (Self, String) {
+        let id = UuidGenerator::default().next_id();
+        let secret = to_hex(&rand::random::<[u8; 32]>());
+        let token = format!("{TOKEN_PREFIX}{id:032x}_{secret}");
+        let desc = Self {
+            id,
+            name,
+            user_id,
+            user_name,
+            privileges,
+            hash: hash_secret(&secret),
+            created_at: now_timestamp_millis(),
+            expires_at,
+        };
+
+        (desc, token)
+    }
+
+    /// Splits a plain token into its id and secret
+    pub fn parse(token: &str) -> Option<(Oid, &str)> {
+        let (id, secret) = token.strip_prefix(TOKEN_PREFIX)?.split_once('_')?;
+        let id = Oid::from_str_radix(id, 16).ok()?;
+        Some((id, secret))
+    }
+
+    pub fn id(&self) -> &Oid {
+        &self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn user_id(&self) -> &Oid {
+        &self.user_id
+    }
+
+    pub fn user_name(&self) -> &str {
+        &self.user_name
+    }
+
+    pub fn privileges(&self) -> &[(DatabasePrivilege, String)] {
+        &self.privileges
+    }
+
+    pub fn created_at(&self) -> i64 {
+        self.created_at
+    }
+
+    pub fn expires_at(&self) -> Option<i64> {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= now_timestamp_millis())
+    }
+
+    /// Compares the hashes in constant time
+    pub fn verify(&self, secret: &str) -> bool {
+        let hash = hash_secret(secret);
+        self.hash.len() == hash.len() && memcmp::eq(self.hash.as_bytes(), hash.as_bytes())
+    }
+
+    /// The privileges of the token in the tenant
+    pub fn to_privileges(&self, tenant_id: Oid) -> HashSet<Privilege<Oid>> {
+        self.privileges
+            .iter()
+            .map(|(privilege, database)| {
+                Privilege::TenantObject(
+                    TenantObjectPrivilege::Database(privilege.clone(), Some(database.clone())),
+                    Some(tenant_id),
+                )
+            })
+            .collect()
+    }
+
+    /// e.g. `Write on db1, Read on db2`
+    pub fn privileges_string(&self) -> String {
+        self.privileges
+            .iter()
+            .map(|(privilege, database)| format!("{} on {}", privilege.as_str(), database))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    to_hex(&sha256(secret.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut s, b| {
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        let (desc, token) = TokenDesc::generate(
+            "telegraf".to_string(),
+            1,
+            "writer".to_string(),
+            vec![(DatabasePrivilege::Write, "db1".to_string())],
+            None,
+        );
+
+        let (id, secret) = TokenDesc::parse(&token).unwrap();
+        assert_eq!(&id, desc.id());
+        assert!(desc.verify(secret));
+        assert!(!desc.verify("secret"));
+        assert!(!desc.is_expired());
+        assert_eq!(desc.privileges_string(), "Write on db1");
+
+        assert!(TokenDesc::parse("Basic xxx").is_none());
+        assert!(TokenDesc::parse("cnos_xyz_secret").is_none());
+
+        let (desc, _) = TokenDesc::generate(
+            "expired".to_string(),
+            1,
+            "writer".to_string(),
+            vec![],
+            Some(now_timestamp_millis() - 1),
+        );
+        assert!(desc.is_expired());
+    }
+}
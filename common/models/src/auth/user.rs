@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::fmt::Display;
 
 use base64::prelude::{Engine, BASE64_STANDARD};
@@ -6,7 +6,8 @@ use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
 use super::privilege::{
-    DatabasePrivilege, GlobalPrivilege, Privilege, PrivilegeChecker, TenantObjectPrivilege,
+    DatabasePrivilege, GlobalPrivilege, Privilege, PrivilegeChecker, TablePrivilege,
+    TenantObjectPrivilege,
 };
 use super::role::{TenantRoleIdentifier, UserRole};
 use super::{rsa_utils, AuthError, AuthResult};
@@ -21,6 +22,10 @@ pub struct User {
     desc: UserDesc,
     privileges: HashSet<Privilege<Oid>>,
     role: Option<TenantRoleIdentifier>,
+    // The privileges of the token the user authenticated with, the user can only
+    // use the privileges that are also granted to the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<HashSet<Privilege<Oid>>>,
 }
 
 impl User {
@@ -36,9 +41,20 @@ impl User {
             desc,
             privileges,
             role,
+            scope: None,
         }
     }
 
+    /// Limits the privileges of the user to the privileges of a token
+    pub fn with_scope(mut self, scope: HashSet<Privilege<Oid>>) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    pub fn scope(&self) -> Option<&HashSet<Privilege<Oid>>> {
+        self.scope.as_ref()
+    }
+
     pub fn role(&self) -> Option<&TenantRoleIdentifier> {
         self.role.as_ref()
     }
@@ -47,8 +63,23 @@ impl User {
         &self.desc
     }
 
+    /// Whether the user acts as an admin, a user authenticated with a token
+    /// is limited to the privileges of the token.
+    pub fn is_admin(&self) -> bool {
+        self.scope.is_none() && self.desc.is_admin()
+    }
+
+    /// The user can do nothing but changing the password, until the password is changed.
+    pub fn expire_password(mut self) -> Self {
+        self.desc.options.must_change_password = Some(true);
+        self
+    }
+
     pub fn check_privilege(&self, privilege: &Privilege<Oid>) -> bool {
-        self.privileges.iter().any(|e| e.check_privilege(privilege))
+        let in_scope = self.scope.as_ref().map_or(true, |scope| {
+            scope.iter().any(|e| e.check_privilege(privilege))
+        });
+        in_scope && self.privileges.iter().any(|e| e.check_privilege(privilege))
     }
 
     pub fn can_access_system(&self, tenant_id: Oid) -> bool {
@@ -71,6 +102,63 @@ impl User {
         );
         self.check_privilege(&privilege)
     }
+
+    /// Whether the user is granted privileges on some tables of the database
+    pub fn has_table_privileges(&self, tenant_id: Oid, database_name: &str) -> bool {
+        self.privileges.iter().any(|p| match p {
+            Privilege::TenantObject(TenantObjectPrivilege::Table(t), Some(t_id)) => {
+                *t_id == tenant_id && t.database() == database_name
+            }
+            _ => false,
+        })
+    }
+
+    /// The columns of the table the user can read,
+    /// None if the user can read all the columns of the table.
+    pub fn readable_columns(
+        &self,
+        tenant_id: Oid,
+        database_name: &str,
+        table_name: &str,
+    ) -> Option<BTreeSet<String>> {
+        let read_privilege = |columns| {
+            Privilege::TenantObject(
+                TenantObjectPrivilege::Table(TablePrivilege::new(
+                    DatabasePrivilege::Read,
+                    database_name.to_string(),
+                    table_name.to_string(),
+                    columns,
+                )),
+                Some(tenant_id),
+            )
+        };
+
+        if self.check_privilege(&read_privilege(None)) {
+            return None;
+        }
+
+        let columns = self
+            .privileges
+            .iter()
+            .filter_map(|p| match p {
+                Privilege::TenantObject(TenantObjectPrivilege::Table(t), Some(t_id))
+                    if *t_id == tenant_id
+                        && t.database() == database_name
+                        && t.table() == table_name =>
+                {
+                    t.columns()
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter(|c| {
+                self.check_privilege(&read_privilege(Some(BTreeSet::from([c.to_string()]))))
+            })
+            .cloned()
+            .collect();
+
+        Some(columns)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +238,15 @@ pub struct UserOptions {
     comment: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     granted_admin: Option<bool>,
+    /// Seconds since the unix epoch, only recorded when a password policy is in effect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password_changed_at: Option<i64>,
+    /// The hashes of the previous passwords, the most recent first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password_history: Option<Vec<String>>,
+    /// The password is verified by LDAP instead of the local one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ldap_auth: Option<bool>,
 }
 
 impl UserOptions {
@@ -168,6 +265,15 @@ impl UserOptions {
     pub fn granted_admin(&self) -> Option<bool> {
         self.granted_admin
     }
+    pub fn password_changed_at(&self) -> Option<i64> {
+        self.password_changed_at
+    }
+    pub fn password_history(&self) -> &[String] {
+        self.password_history.as_deref().unwrap_or_default()
+    }
+    pub fn ldap_auth(&self) -> Option<bool> {
+        self.ldap_auth
+    }
 
     pub fn merge(self, other: Self) -> Self {
         Self {
@@ -176,16 +282,35 @@ impl UserOptions {
             rsa_public_key: self.rsa_public_key.or(other.rsa_public_key),
             comment: self.comment.or(other.comment),
             granted_admin: self.granted_admin.or(other.granted_admin),
+            password_changed_at: self.password_changed_at.or(other.password_changed_at),
+            password_history: self.password_history.or(other.password_history),
+            ldap_auth: self.ldap_auth.or(other.ldap_auth),
         }
     }
     pub fn hidden_password(&mut self) {
         self.hash_password.replace("*****".to_string());
+        self.password_history = None;
     }
 
     // when user change password, turn must_change_password to false
     pub fn change_password(&mut self) {
         self.must_change_password = Some(false);
     }
+
+    /// Records the time the password is set and the previous passwords, `old_options`
+    /// are the options before the change, `history` is the number of the previous
+    /// passwords to keep.
+    pub fn rotate_password(&mut self, old_options: &UserOptions, now_secs: i64, history: usize) {
+        self.password_changed_at = Some(now_secs);
+        let previous = old_options
+            .hash_password()
+            .into_iter()
+            .chain(old_options.password_history().iter().map(|h| h.as_str()))
+            .take(history)
+            .map(|h| h.to_string())
+            .collect();
+        self.password_history = Some(previous);
+    }
 }
 
 impl UserOptionsBuilder {
@@ -214,6 +339,10 @@ impl Display for UserOptions {
             write!(f, "granted_admin={},", e)?;
         }
 
+        if let Some(ref e) = self.ldap_auth {
+            write!(f, "ldap_auth={},", e)?;
+        }
+
         Ok(())
     }
 }
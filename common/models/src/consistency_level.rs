@@ -1,12 +1,88 @@
-#[allow(dead_code)]
-#[derive(Debug)]
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of replicas that must acknowledge a write before it is reported as
+/// successful to the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ConsistencyLevel {
     /// allows for hinted handoff, potentially no write happened yet.
     Any,
     /// at least one data node acknowledged a write or read.
     One,
     /// a quorum of data nodes to acknowledge a write or read.
+    #[default]
     Quorum,
     /// requires all data nodes to acknowledge a write or read.
     All,
 }
+
+impl Display for ConsistencyLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConsistencyLevel::Any => write!(f, "any"),
+            ConsistencyLevel::One => write!(f, "one"),
+            ConsistencyLevel::Quorum => write!(f, "quorum"),
+            ConsistencyLevel::All => write!(f, "all"),
+        }
+    }
+}
+
+impl FromStr for ConsistencyLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "any" => Ok(ConsistencyLevel::Any),
+            "one" => Ok(ConsistencyLevel::One),
+            "quorum" => Ok(ConsistencyLevel::Quorum),
+            "all" => Ok(ConsistencyLevel::All),
+            _ => Err(format!(
+                "invalid consistency level '{}', expected one of: any, one, quorum, all",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::ConsistencyLevel;
+
+    #[test]
+    fn test_parse_consistency_level() {
+        assert_eq!(
+            ConsistencyLevel::from_str("ANY").unwrap(),
+            ConsistencyLevel::Any
+        );
+        assert_eq!(
+            ConsistencyLevel::from_str("one").unwrap(),
+            ConsistencyLevel::One
+        );
+        assert_eq!(
+            ConsistencyLevel::from_str(" Quorum ").unwrap(),
+            ConsistencyLevel::Quorum
+        );
+        assert_eq!(
+            ConsistencyLevel::from_str("all").unwrap(),
+            ConsistencyLevel::All
+        );
+        assert!(ConsistencyLevel::from_str("two").is_err());
+
+        for level in [
+            ConsistencyLevel::Any,
+            ConsistencyLevel::One,
+            ConsistencyLevel::Quorum,
+            ConsistencyLevel::All,
+        ] {
+            assert_eq!(
+                ConsistencyLevel::from_str(&level.to_string()).unwrap(),
+                level
+            );
+        }
+        assert_eq!(ConsistencyLevel::default(), ConsistencyLevel::Quorum);
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Max replication lag of a follower vnode that may serve reads instead of
+/// the leader of its replication set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowerReadLag {
+    /// Number of raft entries the follower may be behind the leader.
+    Entries(u64),
+    /// Time the follower may be behind the leader.
+    Time(Duration),
+}
+
+impl FollowerReadLag {
+    /// Check the lag of a follower, measured on the leader, against this bound.
+    /// A lag in time that is unknown never satisfies a time bound.
+    pub fn allows(&self, entries: u64, millis: Option<u64>) -> bool {
+        match self {
+            FollowerReadLag::Entries(max) => entries <= *max,
+            FollowerReadLag::Time(max) => {
+                millis.is_some_and(|millis| u128::from(millis) <= max.as_millis())
+            }
+        }
+    }
+}
+
+impl Display for FollowerReadLag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FollowerReadLag::Entries(entries) => write!(f, "{}", entries),
+            FollowerReadLag::Time(duration) => {
+                write!(f, "{}", humantime::format_duration(*duration))
+            }
+        }
+    }
+}
+
+impl FromStr for FollowerReadLag {
+    type Err = String;
+
+    /// A plain number is a lag in entries, anything else is parsed as a
+    /// duration, e.g. `500ms` or `5s`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(entries) = s.parse::<u64>() {
+            return Ok(FollowerReadLag::Entries(entries));
+        }
+
+        humantime::parse_duration(s)
+            .map(FollowerReadLag::Time)
+            .map_err(|_| {
+                format!(
+                    "invalid follower read max lag '{}', expected a number of entries or a duration like '5s'",
+                    s
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use super::FollowerReadLag;
+
+    #[test]
+    fn test_parse_follower_read_lag() {
+        assert_eq!(
+            FollowerReadLag::from_str("100").unwrap(),
+            FollowerReadLag::Entries(100)
+        );
+        assert_eq!(
+            FollowerReadLag::from_str(" 5s ").unwrap(),
+            FollowerReadLag::Time(Duration::from_secs(5))
+        );
+        assert_eq!(
+            FollowerReadLag::from_str("500ms").unwrap(),
+            FollowerReadLag::Time(Duration::from_millis(500))
+        );
+        assert!(FollowerReadLag::from_str("-1").is_err());
+        assert!(FollowerReadLag::from_str("latest").is_err());
+    }
+
+    #[test]
+    fn test_follower_read_lag_allows() {
+        let entries = FollowerReadLag::Entries(10);
+        assert!(entries.allows(10, None));
+        assert!(!entries.allows(11, Some(0)));
+
+        let time = FollowerReadLag::Time(Duration::from_secs(1));
+        assert!(time.allows(1000, Some(1000)));
+        assert!(!time.allows(0, Some(1001)));
+        assert!(!time.allows(0, None));
+    }
+}
@@ -11,6 +11,7 @@ pub use value_type::{PhysicalDType, ValueType};
 pub mod codec;
 pub mod consistency_level;
 pub mod errors;
+pub mod follower_read;
 pub mod meta_data;
 pub mod node_info;
 mod series_info;
@@ -3,13 +3,17 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::auth::policy::PolicyDesc;
 use crate::auth::role::{CustomTenantRole, TenantRoleIdentifier};
+use crate::auth::token::TokenDesc;
 use crate::node_info::NodeStatus;
 use crate::oid::Oid;
 use crate::predicate::domain::TimeRange;
 use crate::schema::database_schema::DatabaseSchema;
+use crate::schema::function_schema::FunctionSchema;
 use crate::schema::resource_info::ResourceInfo;
 use crate::schema::table_schema::TableSchema;
+use crate::schema::tskv_table_schema::TskvTableSchema;
 
 pub type VnodeId = u32;
 pub type NodeId = u64;
@@ -178,6 +182,31 @@ pub struct ReplicaAllInfo {
     pub replica_set: ReplicationSet,
 }
 
+/// Vnodes found on a data node, used to rebuild the meta data when the
+/// meta cluster lost its storage.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct NodeInventory {
+    pub node_id: NodeId,
+    pub vnodes: Vec<VnodeInventory>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct VnodeInventory {
+    pub tenant: String,
+    pub db_name: String,
+    pub vnode_id: VnodeId,
+    pub replica_id: ReplicationSetId,
+    // leader of the replication set known by the local raft node
+    pub leader_vnode_id: Option<VnodeId>,
+    // time range of the data in TSM files
+    pub time_range: Option<TimeRange>,
+    // newest table schemas in TSM files
+    pub tables: Vec<TskvTableSchema>,
+    // still cached by the meta client of the data node
+    pub db_schema: Option<DatabaseSchema>,
+    pub replica_info: Option<ReplicaAllInfo>,
+}
+
 impl VnodeAllInfo {
     pub fn set_status(&mut self, status: VnodeStatus) {
         self.status = status;
@@ -243,6 +272,15 @@ pub struct TenantMetaData {
     pub dbs: HashMap<String, DatabaseInfo>,
     pub roles: HashMap<String, CustomTenantRole<Oid>>,
     pub members: HashMap<String, TenantRoleIdentifier>,
+    // function_name -> user-defined function
+    #[serde(default)]
+    pub functions: HashMap<String, FunctionSchema>,
+    // token_name -> api token
+    #[serde(default)]
+    pub tokens: HashMap<String, TokenDesc>,
+    // policy_name -> row-level security policy
+    #[serde(default)]
+    pub policies: HashMap<String, PolicyDesc>,
 }
 
 impl TenantMetaData {
@@ -252,6 +290,9 @@ impl TenantMetaData {
             dbs: HashMap::new(),
             roles: HashMap::new(),
             members: HashMap::new(),
+            functions: HashMap::new(),
+            tokens: HashMap::new(),
+            policies: HashMap::new(),
         }
     }
 
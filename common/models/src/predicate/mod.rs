@@ -140,4 +140,8 @@ impl PlacedSplit {
     pub fn replica_id(&self) -> ReplicationSetId {
         self.repl_set.id
     }
+
+    pub fn replica_set(&self) -> &ReplicationSet {
+        &self.repl_set
+    }
 }
@@ -111,6 +111,9 @@ pub struct DatabaseOptionsBuilder {
     shard_num: Option<u64>,
     vnode_duration: Option<CnosDuration>,
     replica: Option<u64>,
+    replicate_to: Option<String>,
+    standby: Option<bool>,
+    change_feed: Option<bool>,
 }
 
 impl Default for DatabaseOptionsBuilder {
@@ -126,6 +129,9 @@ impl DatabaseOptionsBuilder {
             shard_num: None,
             vnode_duration: None,
             replica: None,
+            replicate_to: None,
+            standby: None,
+            change_feed: None,
         }
     }
 
@@ -149,6 +155,22 @@ impl DatabaseOptionsBuilder {
         self
     }
 
+    /// An empty address list turns cross-cluster replication off.
+    pub fn with_replicate_to(&mut self, replicate_to: String) -> &mut Self {
+        self.replicate_to = Some(replicate_to);
+        self
+    }
+
+    pub fn with_standby(&mut self, standby: bool) -> &mut Self {
+        self.standby = Some(standby);
+        self
+    }
+
+    pub fn with_change_feed(&mut self, change_feed: bool) -> &mut Self {
+        self.change_feed = Some(change_feed);
+        self
+    }
+
     pub fn build(self) -> DatabaseOptions {
         let ttl = self.ttl.unwrap_or(DatabaseOptions::DEFAULT_TTL);
         let shard_num = self.shard_num.unwrap_or(DatabaseOptions::DEFAULT_SHARD_NUM);
@@ -156,7 +178,17 @@ impl DatabaseOptionsBuilder {
             .vnode_duration
             .unwrap_or(DatabaseOptions::DEFAULT_VNODE_DURATION);
         let replica = self.replica.unwrap_or(DatabaseOptions::DEFAULT_REPLICA);
-        DatabaseOptions::new(ttl, shard_num, vnode_duration, replica)
+        let mut options = DatabaseOptions::new(ttl, shard_num, vnode_duration, replica);
+        if let Some(replicate_to) = self.replicate_to {
+            options.set_replicate_to(replicate_to);
+        }
+        if let Some(standby) = self.standby {
+            options.set_standby(standby);
+        }
+        if let Some(change_feed) = self.change_feed {
+            options.set_change_feed(change_feed);
+        }
+        options
     }
 }
 
@@ -166,6 +198,15 @@ pub struct DatabaseOptions {
     shard_num: u64,
     vnode_duration: CnosDuration,
     replica: u64,
+    // gRPC addresses of the data nodes of a remote cluster to ship committed writes to
+    #[serde(default)]
+    replicate_to: Option<String>,
+    // accepts writes replicated from a remote cluster only
+    #[serde(default)]
+    standby: bool,
+    // keeps applied writes, deletes and drops for change data capture
+    #[serde(default)]
+    change_feed: bool,
 }
 
 impl DatabaseOptions {
@@ -185,6 +226,9 @@ impl DatabaseOptions {
             shard_num,
             vnode_duration,
             replica,
+            replicate_to: None,
+            standby: false,
+            change_feed: false,
         }
     }
 
@@ -220,6 +264,48 @@ impl DatabaseOptions {
         self.replica = replica;
     }
 
+    pub fn replicate_to(&self) -> Option<&str> {
+        self.replicate_to.as_deref()
+    }
+
+    /// Get the gRPC addresses of the remote cluster to replicate to.
+    pub fn replicate_to_addrs(&self) -> Vec<&str> {
+        self.replicate_to
+            .as_deref()
+            .map(|addrs| {
+                addrs
+                    .split(',')
+                    .map(|addr| addr.trim())
+                    .filter(|addr| !addr.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn set_replicate_to(&mut self, replicate_to: String) {
+        if replicate_to.trim().is_empty() {
+            self.replicate_to = None;
+        } else {
+            self.replicate_to = Some(replicate_to);
+        }
+    }
+
+    pub fn standby(&self) -> bool {
+        self.standby
+    }
+
+    pub fn set_standby(&mut self, standby: bool) {
+        self.standby = standby;
+    }
+
+    pub fn change_feed(&self) -> bool {
+        self.change_feed
+    }
+
+    pub fn set_change_feed(&mut self, change_feed: bool) {
+        self.change_feed = change_feed;
+    }
+
     pub fn apply_builder(&mut self, builder: &DatabaseOptionsBuilder) {
         if let Some(ref ttl) = builder.ttl {
             self.ttl = ttl.clone();
@@ -233,6 +319,15 @@ impl DatabaseOptions {
         if let Some(replica) = builder.replica {
             self.replica = replica;
         }
+        if let Some(ref replicate_to) = builder.replicate_to {
+            self.set_replicate_to(replicate_to.clone());
+        }
+        if let Some(standby) = builder.standby {
+            self.standby = standby;
+        }
+        if let Some(change_feed) = builder.change_feed {
+            self.change_feed = change_feed;
+        }
     }
 }
 
@@ -243,6 +338,9 @@ impl Default for DatabaseOptions {
             shard_num: DatabaseOptions::DEFAULT_SHARD_NUM,
             vnode_duration: DatabaseOptions::DEFAULT_VNODE_DURATION,
             replica: DatabaseOptions::DEFAULT_REPLICA,
+            replicate_to: None,
+            standby: false,
+            change_feed: false,
         }
     }
 }
@@ -0,0 +1,128 @@
+use std::fmt::Display;
+
+use datafusion::arrow::datatypes::DataType;
+use serde::{Deserialize, Serialize};
+
+/// A user-defined function of a tenant.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSchema {
+    tenant: String,
+    name: String,
+    kind: FunctionKind,
+    args: Vec<FunctionArg>,
+    return_type: DataType,
+    body: FunctionBody,
+}
+
+impl FunctionSchema {
+    pub fn new(
+        tenant: impl Into<String>,
+        name: impl Into<String>,
+        kind: FunctionKind,
+        args: Vec<FunctionArg>,
+        return_type: DataType,
+        body: FunctionBody,
+    ) -> Self {
+        Self {
+            tenant: tenant.into(),
+            name: name.into(),
+            kind,
+            args,
+            return_type,
+            body,
+        }
+    }
+
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> FunctionKind {
+        self.kind
+    }
+
+    pub fn args(&self) -> &[FunctionArg] {
+        &self.args
+    }
+
+    pub fn arg_types(&self) -> Vec<DataType> {
+        self.args.iter().map(|e| e.data_type.clone()).collect()
+    }
+
+    pub fn return_type(&self) -> &DataType {
+        &self.return_type
+    }
+
+    pub fn body(&self) -> &FunctionBody {
+        &self.body
+    }
+
+    /// e.g. `a BIGINT, b DOUBLE`
+    pub fn signature(&self) -> String {
+        self.args
+            .iter()
+            .map(|e| format!("{} {}", e.name, e.data_type))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    Scalar,
+    Aggregate,
+}
+
+impl Display for FunctionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scalar => write!(f, "SCALAR"),
+            Self::Aggregate => write!(f, "AGGREGATE"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FunctionArg {
+    pub name: String,
+    pub data_type: DataType,
+}
+
+impl FunctionArg {
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum FunctionBody {
+    /// A sql expression over the arguments
+    Sql(String),
+    /// A WebAssembly module, see the query server for the exports it must provide
+    Wasm(Vec<u8>),
+}
+
+impl FunctionBody {
+    pub fn language(&self) -> &'static str {
+        match self {
+            Self::Sql(_) => "SQL",
+            Self::Wasm(_) => "WASM",
+        }
+    }
+}
+
+impl Display for FunctionBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sql(sql) => write!(f, "{sql}"),
+            Self::Wasm(module) => write!(f, "<wasm module, {} bytes>", module.len()),
+        }
+    }
+}
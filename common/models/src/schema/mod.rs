@@ -9,12 +9,14 @@
 
 pub mod database_schema;
 pub mod external_table_schema;
+pub mod function_schema;
 pub mod query_info;
 pub mod resource_info;
 pub mod stream_table_schema;
 pub mod table_schema;
 pub mod tenant;
 pub mod tskv_table_schema;
+pub mod view_schema;
 
 pub const TIME_FIELD_NAME: &str = "time";
 pub const IS_TAG: &str = "_is_tag";
@@ -67,6 +67,9 @@ pub struct QueryInfo {
     database_name: String,
     user: User,
     pub node_id: NodeId,
+    /// The trigger interval of a stream query, used when the query is re-executed
+    #[serde(default)]
+    stream_trigger_interval: Option<String>,
 }
 
 impl QueryInfo {
@@ -87,9 +90,15 @@ impl QueryInfo {
             database_name,
             user,
             node_id,
+            stream_trigger_interval: None,
         }
     }
 
+    pub fn with_stream_trigger_interval(mut self, interval: Option<String>) -> Self {
+        self.stream_trigger_interval = interval;
+        self
+    }
+
     pub fn query_id(&self) -> QueryId {
         self.query_id
     }
@@ -121,4 +130,8 @@ impl QueryInfo {
     pub fn user_name(&self) -> &str {
         self.user.desc().name()
     }
+
+    pub fn stream_trigger_interval(&self) -> Option<&str> {
+        self.stream_trigger_interval.as_deref()
+    }
 }
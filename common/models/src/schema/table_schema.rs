@@ -6,12 +6,14 @@ use serde::{Deserialize, Serialize};
 use crate::schema::external_table_schema::ExternalTableSchema;
 use crate::schema::stream_table_schema::StreamTable;
 use crate::schema::tskv_table_schema::TskvTableSchemaRef;
+use crate::schema::view_schema::ViewSchema;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum TableSchema {
     TsKvTableSchema(TskvTableSchemaRef),
     ExternalTableSchema(Arc<ExternalTableSchema>),
     StreamTableSchema(Arc<StreamTable>),
+    ViewTableSchema(Arc<ViewSchema>),
 }
 
 impl TableSchema {
@@ -20,6 +22,7 @@ impl TableSchema {
             TableSchema::TsKvTableSchema(schema) => schema.name.as_str(),
             TableSchema::ExternalTableSchema(schema) => schema.name.as_str(),
             TableSchema::StreamTableSchema(schema) => schema.name(),
+            TableSchema::ViewTableSchema(schema) => schema.name(),
         }
     }
 
@@ -28,6 +31,7 @@ impl TableSchema {
             TableSchema::TsKvTableSchema(schema) => schema.db.as_str(),
             TableSchema::ExternalTableSchema(schema) => schema.db.as_str(),
             TableSchema::StreamTableSchema(schema) => schema.db(),
+            TableSchema::ViewTableSchema(schema) => schema.db(),
         }
     }
 
@@ -36,6 +40,7 @@ impl TableSchema {
             TableSchema::TsKvTableSchema(_) => "TSKV",
             TableSchema::ExternalTableSchema(_) => "EXTERNAL",
             TableSchema::StreamTableSchema(_) => "STREAM",
+            TableSchema::ViewTableSchema(_) => "VIEW",
         }
     }
 
@@ -44,6 +49,7 @@ impl TableSchema {
             Self::ExternalTableSchema(e) => Arc::new(e.schema.clone()),
             Self::TsKvTableSchema(e) => e.to_arrow_schema(),
             Self::StreamTableSchema(e) => e.schema(),
+            Self::ViewTableSchema(e) => e.schema(),
         }
     }
 }
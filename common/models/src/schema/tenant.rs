@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
-use config::common::{RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig};
+use config::common::{
+    PasswordPolicyConfig, RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig,
+};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use utils::duration::CnosDuration;
@@ -45,6 +47,8 @@ pub struct TenantOptions {
     pub limiter_config: Option<TenantLimiterConfig>,
     pub drop_after: Option<CnosDuration>,
     pub tenant_is_hidden: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_policy: Option<PasswordPolicyConfig>,
 }
 
 impl From<TenantOptions> for TenantOptionsBuilder {
@@ -59,6 +63,9 @@ impl From<TenantOptions> for TenantOptionsBuilder {
         if let Some(drop_after) = value.get_drop_after() {
             builder.drop_after(drop_after);
         }
+        if let Some(password_policy) = value.password_policy.clone() {
+            builder.password_policy(password_policy);
+        }
         builder.tenant_is_hidden(false);
         builder
     }
@@ -74,6 +81,9 @@ impl TenantOptionsBuilder {
     pub fn unset_drop_after(&mut self) {
         self.drop_after = None;
     }
+    pub fn unset_password_policy(&mut self) {
+        self.password_policy = None;
+    }
 }
 
 impl TenantOptions {
@@ -102,6 +112,10 @@ impl TenantOptions {
     pub fn get_drop_after(&self) -> Option<CnosDuration> {
         self.drop_after.clone()
     }
+
+    pub fn password_policy(&self) -> Option<&PasswordPolicyConfig> {
+        self.password_policy.as_ref()
+    }
 }
 
 impl Display for TenantOptions {
@@ -116,6 +130,10 @@ impl Display for TenantOptions {
             write!(f, "limiter=None,")?;
         }
 
+        if let Some(ref e) = self.password_policy {
+            write!(f, "password_policy={e:?},")?;
+        }
+
         Ok(())
     }
 }
@@ -301,6 +301,18 @@ impl TskvTableSchema {
         Arc::new(Schema::new_with_metadata(fields, self.meta()))
     }
 
+    /// The schema of the tag columns in the order of the table columns, the tag filter
+    /// of a delete predicate is evaluated against the tags of the series in this schema.
+    pub fn to_tag_arrow_schema(&self) -> SchemaRef {
+        let fields: Vec<ArrowField> = self
+            .columns
+            .iter()
+            .filter(|column| column.column_type.is_tag())
+            .map(|column| ArrowField::new(&column.name, ArrowDataType::Utf8, true))
+            .collect();
+        Arc::new(Schema::new(fields))
+    }
+
     pub fn to_df_schema(&self) -> Result<DFSchemaRef, DataFusionError> {
         let fields: Vec<DFField> = self
             .columns
@@ -0,0 +1,147 @@
+use std::time::Duration as StdDuration;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::query_info::QueryId;
+
+/// Prefix of the tskv table that stores the rows of a materialized view.
+pub const MATERIALIZED_VIEW_STORAGE_PREFIX: &str = "__mv_storage_";
+/// Prefix of the stream table that reads the new writes of the source table of a materialized view.
+pub const MATERIALIZED_VIEW_SOURCE_PREFIX: &str = "__mv_source_";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ViewSchema {
+    tenant: String,
+    db: String,
+    name: String,
+    /// The query of the view, unqualified tables belong to the database of the view
+    query: String,
+    /// The output schema of the query at the time the view was created
+    schema: SchemaRef,
+    materialized: Option<MaterializedView>,
+}
+
+impl ViewSchema {
+    pub fn new(
+        tenant: impl Into<String>,
+        db: impl Into<String>,
+        name: impl Into<String>,
+        query: impl Into<String>,
+        schema: SchemaRef,
+        materialized: Option<MaterializedView>,
+    ) -> Self {
+        Self {
+            tenant: tenant.into(),
+            db: db.into(),
+            name: name.into(),
+            query: query.into(),
+            schema,
+            materialized,
+        }
+    }
+
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+
+    pub fn db(&self) -> &str {
+        &self.db
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    pub fn materialized(&self) -> Option<&MaterializedView> {
+        self.materialized.as_ref()
+    }
+}
+
+/// The tables maintained for a materialized view.
+///
+/// New writes to `base_table` are read through the stream table `source_table`,
+/// and `refresh_sql` inserts the result of the view query over them into `storage_table`
+/// every `refresh_interval`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MaterializedView {
+    base_table: String,
+    storage_table: String,
+    source_table: String,
+    refresh_sql: String,
+    refresh_interval: StdDuration,
+    watermark_delay: StdDuration,
+    /// The running query of `refresh_sql`, set once it is started.
+    #[serde(default)]
+    refresh_query_id: Option<QueryId>,
+}
+
+impl MaterializedView {
+    pub fn new(
+        base_table: impl Into<String>,
+        storage_table: impl Into<String>,
+        source_table: impl Into<String>,
+        refresh_sql: impl Into<String>,
+        refresh_interval: StdDuration,
+        watermark_delay: StdDuration,
+    ) -> Self {
+        Self {
+            base_table: base_table.into(),
+            storage_table: storage_table.into(),
+            source_table: source_table.into(),
+            refresh_sql: refresh_sql.into(),
+            refresh_interval,
+            watermark_delay,
+            refresh_query_id: None,
+        }
+    }
+
+    pub fn with_refresh_query_id(mut self, query_id: QueryId) -> Self {
+        self.refresh_query_id = Some(query_id);
+        self
+    }
+
+    pub fn storage_table_name(view_name: &str) -> String {
+        format!("{MATERIALIZED_VIEW_STORAGE_PREFIX}{view_name}")
+    }
+
+    pub fn source_table_name(view_name: &str) -> String {
+        format!("{MATERIALIZED_VIEW_SOURCE_PREFIX}{view_name}")
+    }
+
+    pub fn base_table(&self) -> &str {
+        &self.base_table
+    }
+
+    pub fn storage_table(&self) -> &str {
+        &self.storage_table
+    }
+
+    pub fn source_table(&self) -> &str {
+        &self.source_table
+    }
+
+    pub fn refresh_sql(&self) -> &str {
+        &self.refresh_sql
+    }
+
+    pub fn refresh_interval(&self) -> StdDuration {
+        self.refresh_interval
+    }
+
+    pub fn watermark_delay(&self) -> StdDuration {
+        self.watermark_delay
+    }
+
+    pub fn refresh_query_id(&self) -> Option<QueryId> {
+        self.refresh_query_id
+    }
+}
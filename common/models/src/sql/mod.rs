@@ -16,6 +16,7 @@ use crate::schema::stream_table_schema::StreamTable;
 use crate::schema::table_schema::TableSchema;
 use crate::schema::tenant::Tenant;
 use crate::schema::tskv_table_schema::{ColumnType, TskvTableSchema};
+use crate::schema::view_schema::ViewSchema;
 use crate::ModelError;
 
 type Result<T, E = ModelError> = std::result::Result<T, E>;
@@ -114,6 +115,9 @@ impl ToDDLSql for UserDesc {
         let granted_admin = option
             .granted_admin()
             .map(|v| ("granted_admin", SqlParserValue::Boolean(v)));
+        let ldap_auth = option
+            .ldap_auth()
+            .map(|v| ("ldap_auth", SqlParserValue::Boolean(v)));
 
         let sql_opts = vec![
             hash_password,
@@ -121,6 +125,7 @@ impl ToDDLSql for UserDesc {
             must_change_password,
             rsa_public_key,
             granted_admin,
+            ldap_auth,
         ];
         let opt_sql = sql_option_to_sql_str(sql_opts);
         if !opt_sql.is_empty() {
@@ -218,6 +223,23 @@ pub fn role_to_sql(role: &CustomTenantRole<Oid>) -> Result<Vec<String>> {
 // GRANT privilege
 pub fn privilege_to_sql(role: &CustomTenantRole<Oid>) -> Vec<String> {
     let privileges = role.additional_privileges();
+    let table_privileges = role.table_privileges().iter().map(|p| {
+        let columns = p
+            .columns()
+            .map(|c| {
+                let columns = c.iter().map(|c| format!("\"{}\"", c)).collect::<Vec<_>>();
+                format!(" ({})", columns.join(", "))
+            })
+            .unwrap_or_default();
+        format!(
+            "grant {} on table \"{}\".\"{}\"{} to \"{}\";",
+            p.privilege().as_str(),
+            p.database(),
+            p.table(),
+            columns,
+            role.name()
+        )
+    });
     privileges
         .iter()
         .map(|(d, p)| {
@@ -228,6 +250,7 @@ pub fn privilege_to_sql(role: &CustomTenantRole<Oid>) -> Vec<String> {
                 role.name()
             )
         })
+        .chain(table_privileges)
         .collect()
 }
 
@@ -246,14 +269,25 @@ pub fn create_table_sqls(tables: &[TableSchema], if_not_exists: bool) -> Result<
     let mut ts_table = vec![];
     let mut ex_table = vec![];
     let mut stream_table = vec![];
+    let mut view_table = vec![];
     for table in tables {
         match table {
             TableSchema::TsKvTableSchema(t) => ts_table.push(t),
             TableSchema::ExternalTableSchema(t) => ex_table.push(t),
             TableSchema::StreamTableSchema(s) => stream_table.push(s),
+            TableSchema::ViewTableSchema(v) => view_table.push(v),
         }
     }
 
+    // the tables of materialized views are created by the views
+    let view_owned_tables = view_table
+        .iter()
+        .filter_map(|v| v.materialized())
+        .flat_map(|m| [m.storage_table(), m.source_table()])
+        .collect::<Vec<_>>();
+    ts_table.retain(|t| !view_owned_tables.contains(&t.name.as_str()));
+    stream_table.retain(|t| !view_owned_tables.contains(&t.name()));
+
     for ts in ts_table.into_iter() {
         res.push(ts.to_ddl_sql(if_not_exists)?)
     }
@@ -266,6 +300,10 @@ pub fn create_table_sqls(tables: &[TableSchema], if_not_exists: bool) -> Result<
         res.push(stream.to_ddl_sql(if_not_exists)?)
     }
 
+    for view in view_table.into_iter() {
+        res.push(view.to_ddl_sql(if_not_exists)?)
+    }
+
     Ok(res)
 }
 
@@ -276,6 +314,7 @@ impl ToDDLSql for TableSchema {
             TableSchema::TsKvTableSchema(t) => t.to_ddl_sql(if_not_exists),
             TableSchema::ExternalTableSchema(t) => t.to_ddl_sql(if_not_exists),
             TableSchema::StreamTableSchema(t) => t.to_ddl_sql(if_not_exists),
+            TableSchema::ViewTableSchema(t) => t.to_ddl_sql(if_not_exists),
         }
     }
 }
@@ -493,6 +532,49 @@ impl ToDDLSql for StreamTable {
     }
 }
 
+// CREATE VIEW
+impl ToDDLSql for ViewSchema {
+    fn to_ddl_sql(&self, if_not_exists: bool) -> Result<String> {
+        let mut res = String::new();
+        match self.materialized() {
+            Some(materialized) => {
+                res.push_str("create materialized view ");
+                res.push_str(format!("\"{}\".\"{}\" ", self.db(), self.name()).as_str());
+                let mut options = vec![format!(
+                    "refresh_interval={}",
+                    SqlParserValue::SingleQuotedString(format!(
+                        "{}ms",
+                        materialized.refresh_interval().as_millis()
+                    ))
+                )];
+                if materialized.watermark_delay().as_millis() > 0 {
+                    options.push(format!(
+                        "watermark_delay={}",
+                        SqlParserValue::SingleQuotedString(format!(
+                            "{}ms",
+                            materialized.watermark_delay().as_millis()
+                        ))
+                    ));
+                }
+                res.push_str(format!("with ({}) ", options.join(", ")).as_str());
+            }
+            None => {
+                // CREATE VIEW has no IF NOT EXISTS
+                if if_not_exists {
+                    res.push_str("create or replace view ");
+                } else {
+                    res.push_str("create view ");
+                }
+                res.push_str(format!("\"{}\".\"{}\" ", self.db(), self.name()).as_str());
+            }
+        }
+        res.push_str("as ");
+        res.push_str(self.query());
+        res.push(';');
+        Ok(res)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -509,6 +591,7 @@ mod test {
     use crate::schema::stream_table_schema::{StreamTable, Watermark};
     use crate::schema::tenant::{Tenant, TenantOptionsBuilder};
     use crate::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use crate::schema::view_schema::{MaterializedView, ViewSchema};
     use crate::sql::ToDDLSql;
     use crate::ValueType;
 
@@ -700,4 +783,44 @@ remote_bucket = {max = 100, initial = 0, refill = 100, interval = 100}
             r#"create stream table "test"."test_stream" ("visibility" DOUBLE, "temperature" DOUBLE, "pressure" DOUBLE, "station" STRING) with (db='test', table='air', event_time_column='time') engine = tskv;"#
         );
     }
+
+    #[test]
+    fn create_view() {
+        let schema = SchemaRef::new(Schema::new(Fields::from(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("station", DataType::Utf8, true),
+            Field::new("temperature", DataType::Float64, true),
+        ])));
+        let query = "SELECT date_bin(INTERVAL '1 minute', time) AS time, station, avg(temperature) AS temperature FROM air GROUP BY 1, station";
+
+        let view = ViewSchema::new("", "test", "air_view", query, schema.clone(), None);
+        assert_eq!(
+            view.to_ddl_sql(false).unwrap(),
+            format!(r#"create view "test"."air_view" as {query};"#)
+        );
+        assert_eq!(
+            view.to_ddl_sql(true).unwrap(),
+            format!(r#"create or replace view "test"."air_view" as {query};"#)
+        );
+
+        let materialized = MaterializedView::new(
+            "air",
+            MaterializedView::storage_table_name("air_view"),
+            MaterializedView::source_table_name("air_view"),
+            "",
+            std::time::Duration::from_secs(60),
+            std::time::Duration::from_secs(5),
+        );
+        let view = ViewSchema::new("", "test", "air_view", query, schema, Some(materialized));
+        assert_eq!(
+            view.to_ddl_sql(true).unwrap(),
+            format!(
+                r#"create materialized view "test"."air_view" with (refresh_interval='60000ms', watermark_delay='5000ms') as {query};"#
+            )
+        );
+    }
 }
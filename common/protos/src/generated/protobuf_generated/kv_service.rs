@@ -38,6 +38,27 @@ pub struct BatchBytesResponse {
     pub code: i32,
     #[prost(bytes = "vec", tag = "2")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// Only set in the responses without data of a table scan,
+    /// the statistics since the previous one
+    #[prost(message, optional, tag = "3")]
+    pub scan_stats: ::core::option::Option<ScanStats>,
+}
+/// Storage I/O statistics of a table scan on a data node
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScanStats {
+    #[prost(uint64, tag = "1")]
+    pub files_opened: u64,
+    #[prost(uint64, tag = "2")]
+    pub pages_read: u64,
+    #[prost(uint64, tag = "3")]
+    pub pages_skipped: u64,
+    #[prost(uint64, tag = "4")]
+    pub bytes_decoded: u64,
+    #[prost(uint64, tag = "5")]
+    pub memcache_rows: u64,
+    #[prost(uint64, tag = "6")]
+    pub series_matched: u64,
 }
 /// --------------------------------------------------------------------
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -222,10 +243,36 @@ pub struct BuildRaftGroupRequest {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchFollowerLagRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub replica_id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchChangeLogRequest {
+    #[prost(string, tag = "1")]
+    pub db_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub vnode_id: u32,
+    #[prost(uint64, tag = "3")]
+    pub after_index: u64,
+    #[prost(uint64, tag = "4")]
+    pub max_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchVnodeVersionRequest {
+    #[prost(uint32, repeated, tag = "1")]
+    pub vnode_ids: ::prost::alloc::vec::Vec<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AdminCommand {
     #[prost(string, tag = "1")]
     pub tenant: ::prost::alloc::string::String,
-    #[prost(oneof = "admin_command::Command", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11")]
+    #[prost(oneof = "admin_command::Command", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14")]
     pub command: ::core::option::Option<admin_command::Command>,
 }
 /// Nested message and enum types in `AdminCommand`.
@@ -253,6 +300,12 @@ pub mod admin_command {
         LearnerToFollower(super::LearnerToFollowerRequest),
         #[prost(message, tag = "11")]
         BuildRaftGroup(super::BuildRaftGroupRequest),
+        #[prost(message, tag = "12")]
+        FetchFollowerLag(super::FetchFollowerLagRequest),
+        #[prost(message, tag = "13")]
+        FetchChangeLog(super::FetchChangeLogRequest),
+        #[prost(message, tag = "14")]
+        FetchVnodeVersion(super::FetchVnodeVersionRequest),
     }
 }
 /// --------------------------------------------------------------------
@@ -271,7 +324,14 @@ pub struct QueryRecordBatchRequest {
     pub expr: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes = "vec", tag = "3")]
     pub aggs: ::prost::alloc::vec::Vec<u8>,
+    /// Send the scan statistics before the batches read along with them
+    /// and in the last response
+    #[prost(bool, tag = "4")]
+    pub with_scan_stats: bool,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FetchInventoryRequest {}
 /// Generated client implementations.
 pub mod tskv_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -505,6 +565,31 @@ pub mod tskv_service_client {
                 .insert(GrpcMethod::new("kv_service.TSKVService", "AdminRequest"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn fetch_inventory(
+            &mut self,
+            request: impl tonic::IntoRequest<super::FetchInventoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BatchBytesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/kv_service.TSKVService/FetchInventory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("kv_service.TSKVService", "FetchInventory"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -568,6 +653,13 @@ pub mod tskv_service_server {
             tonic::Response<super::BatchBytesResponse>,
             tonic::Status,
         >;
+        async fn fetch_inventory(
+            &self,
+            request: tonic::Request<super::FetchInventoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::BatchBytesResponse>,
+            tonic::Status,
+        >;
     }
     /// --------------------------------------------------------------------
     #[derive(Debug)]
@@ -920,6 +1012,50 @@ pub mod tskv_service_server {
                     };
                     Box::pin(fut)
                 }
+"/kv_service.TSKVService/FetchInventory" => {
+                    #[allow(non_camel_case_types)]
+                    struct FetchInventorySvc<T: TskvService>(pub Arc<T>);
+                    impl<T: TskvService> tonic::server::UnaryService<super::FetchInventoryRequest>
+                    for FetchInventorySvc<T> {
+                        type Response = super::BatchBytesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::FetchInventoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).fetch_inventory(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = FetchInventorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
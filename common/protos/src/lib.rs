@@ -4,6 +4,7 @@ use tonic::codec::CompressionEncoding;
 pub mod models_helper;
 pub mod prompb;
 pub mod test_helper;
+pub mod tls;
 
 use core::time;
 use std::fmt::{Display, Formatter};
@@ -11,7 +12,7 @@ use std::time::Duration;
 
 use flatbuffers::{ForwardsUOffset, Vector};
 use snafu::{Backtrace, Location, OptionExt, Snafu};
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::Channel;
 use tower::timeout::Timeout;
 
 use crate::kv_service::tskv_service_client::TskvServiceClient;
@@ -305,8 +306,11 @@ pub fn raft_service_time_out_client(
     }
 }
 
-pub async fn tskv_service_ping(addr: &str) -> Result<(), String> {
-    let connector = Endpoint::from_shared(format!("http://{}", addr)).map_err(|e| e.to_string())?;
+pub async fn tskv_service_ping(
+    addr: &str,
+    internal_tls: Option<&config::common::InternalTlsConfig>,
+) -> Result<(), String> {
+    let connector = tls::endpoint(addr, internal_tls).map_err(|e| e.to_string())?;
     let channel = connector
         .connect()
         .await
@@ -0,0 +1,305 @@
+//! Mutual TLS of the traffic between the nodes of a cluster.
+//!
+//! Every node holds a certificate signed by the cluster CA, a connection is only
+//! established when both sides present such a certificate.
+
+use std::fs;
+use std::io::{self, BufReader};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use config::common::InternalTlsConfig;
+use openssl::x509::X509;
+use parking_lot::RwLock;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Certificate as CaCertificate, ClientTlsConfig, Endpoint, Identity};
+use trace::{info, warn};
+
+/// Decides whether the peer, given the subject alternative names of its
+/// certificate, is a node of the cluster.
+pub type PeerValidator = Arc<dyn Fn(&[String]) -> bool + Send + Sync>;
+
+/// The scheme of the urls of the other nodes.
+pub fn scheme(tls: Option<&InternalTlsConfig>) -> &'static str {
+    if tls.is_some() {
+        "https"
+    } else {
+        "http"
+    }
+}
+
+/// The host of an address formatted as `host:port`.
+pub fn addr_host(addr: &str) -> &str {
+    let host = addr.rsplit_once(':').map(|(host, _)| host).unwrap_or(addr);
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+/// Create the endpoint of a channel to another node, `addr` is formatted as `host:port`.
+pub fn endpoint(addr: &str, tls: Option<&InternalTlsConfig>) -> io::Result<Endpoint> {
+    let invalid_input = |e: tonic::transport::Error| io::Error::new(io::ErrorKind::InvalidInput, e);
+
+    let endpoint =
+        Endpoint::from_shared(format!("{}://{}", scheme(tls), addr)).map_err(invalid_input)?;
+    match tls {
+        None => Ok(endpoint),
+        Some(tls) => endpoint
+            .tls_config(client_tls_config(tls)?)
+            .map_err(invalid_input),
+    }
+}
+
+/// The tls config of the grpc clients, the files are read on every call so that
+/// a new channel always uses the latest certificates.
+pub fn client_tls_config(tls: &InternalTlsConfig) -> io::Result<ClientTlsConfig> {
+    Ok(ClientTlsConfig::new()
+        .ca_certificate(CaCertificate::from_pem(fs::read(&tls.ca_certificate)?))
+        .identity(Identity::from_pem(
+            fs::read(&tls.certificate)?,
+            fs::read(&tls.private_key)?,
+        )))
+}
+
+/// The certificate and the private key of the http clients, in PEM.
+pub fn client_identity_pem(tls: &InternalTlsConfig) -> io::Result<Vec<u8>> {
+    let mut pem = fs::read(&tls.certificate)?;
+    pem.push(b'\n');
+    pem.extend(fs::read(&tls.private_key)?);
+    Ok(pem)
+}
+
+/// Accepts the TLS connections of the other nodes, the certificates are
+/// reloaded when their files are modified.
+pub struct InternalTlsAcceptor {
+    config: InternalTlsConfig,
+    // The acceptor and the modified times of the files it is loaded from.
+    current: RwLock<(TlsAcceptor, Vec<Option<SystemTime>>)>,
+}
+
+impl InternalTlsAcceptor {
+    pub fn try_new(config: InternalTlsConfig) -> io::Result<Arc<Self>> {
+        let modified = modified_times(&config);
+        let acceptor = load_acceptor(&config)?;
+        Ok(Arc::new(Self {
+            config,
+            current: RwLock::new((acceptor, modified)),
+        }))
+    }
+
+    /// Reload the certificates if any of the files is modified, returns whether
+    /// they are reloaded. Established connections are not affected.
+    pub fn reload_if_modified(&self) -> io::Result<bool> {
+        let modified = modified_times(&self.config);
+        if self.current.read().1 == modified {
+            return Ok(false);
+        }
+        let acceptor = load_acceptor(&self.config)?;
+        *self.current.write() = (acceptor, modified);
+        Ok(true)
+    }
+
+    fn acceptor(&self) -> TlsAcceptor {
+        self.current.read().0.clone()
+    }
+
+    /// Accept the connections on `listener`, the handshakes failed and the peers
+    /// rejected by `validator` are logged and dropped.
+    pub fn incoming(
+        self: Arc<Self>,
+        listener: TcpListener,
+        validator: PeerValidator,
+    ) -> ReceiverStream<io::Result<TlsStream<TcpStream>>> {
+        let (sender, receiver) = mpsc::channel(128);
+        tokio::spawn(async move {
+            let mut reload_ticker = tokio::time::interval(self.config.reload_interval);
+            loop {
+                tokio::select! {
+                    _ = sender.closed() => break,
+                    _ = reload_ticker.tick() => match self.reload_if_modified() {
+                        Ok(true) => info!("Reloaded the certificates of internal tls"),
+                        Ok(false) => {}
+                        Err(e) => warn!("Failed to reload the certificates of internal tls: {}", e),
+                    },
+                    accepted = listener.accept() => {
+                        let (stream, peer_addr) = match accepted {
+                            Ok(accepted) => accepted,
+                            Err(e) => {
+                                warn!("Failed to accept an internal connection: {}", e);
+                                continue;
+                            }
+                        };
+                        let acceptor = self.acceptor();
+                        let validator = validator.clone();
+                        let sender = sender.clone();
+                        tokio::spawn(async move {
+                            match accept_peer(acceptor, stream, &validator).await {
+                                Ok(stream) => {
+                                    let _ = sender.send(Ok(stream)).await;
+                                }
+                                Err(e) => warn!("Rejected the connection from {}: {}", peer_addr, e),
+                            }
+                        });
+                    }
+                }
+            }
+        });
+        ReceiverStream::new(receiver)
+    }
+}
+
+async fn accept_peer(
+    acceptor: TlsAcceptor,
+    stream: TcpStream,
+    validator: &PeerValidator,
+) -> io::Result<TlsStream<TcpStream>> {
+    let stream = acceptor.accept(stream).await?;
+    let names = match stream.get_ref().1.peer_certificates() {
+        Some([cert, ..]) => certificate_names(&cert.0)?,
+        _ => vec![],
+    };
+    if validator(&names) {
+        Ok(stream)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("peer {:?} is not a node of the cluster", names),
+        ))
+    }
+}
+
+fn load_acceptor(config: &InternalTlsConfig) -> io::Result<TlsAcceptor> {
+    let invalid_data = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+
+    let mut roots = RootCertStore::empty();
+    for cert in read_certificates(&config.ca_certificate)? {
+        roots.add(&cert).map_err(|e| invalid_data(e.to_string()))?;
+    }
+    let certs = read_certificates(&config.certificate)?;
+    let key = read_private_key(&config.private_key)?;
+
+    let mut server_config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+        .with_single_cert(certs, key)
+        .map_err(|e| invalid_data(e.to_string()))?;
+    // grpc requires http2, the http api of meta is also served over http1.
+    server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+fn read_certificates(path: impl AsRef<Path>) -> io::Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn read_private_key(path: impl AsRef<Path>) -> io::Result<PrivateKey> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    for item in rustls_pemfile::read_all(&mut reader)? {
+        match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => return Ok(PrivateKey(key)),
+            _ => {}
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("no private key found in {}", path.display()),
+    ))
+}
+
+/// The modified times of the certificate files, used to detect the changes of them.
+pub fn modified_times(config: &InternalTlsConfig) -> Vec<Option<SystemTime>> {
+    [
+        &config.ca_certificate,
+        &config.certificate,
+        &config.private_key,
+    ]
+    .iter()
+    .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+    .collect()
+}
+
+/// The dns names and ip addresses in the subject alternative names of a certificate.
+fn certificate_names(der: &[u8]) -> io::Result<Vec<String>> {
+    let cert = X509::from_der(der).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let names = cert
+        .subject_alt_names()
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| {
+                    name.dnsname()
+                        .map(|dns| dns.to_string())
+                        .or_else(|| name.ipaddress().and_then(ip_address))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(names)
+}
+
+fn ip_address(octets: &[u8]) -> Option<String> {
+    let ip = match octets.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(octets).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(octets).ok()?),
+        _ => return None,
+    };
+    Some(ip.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::x509::extension::SubjectAlternativeName;
+    use openssl::x509::{X509NameBuilder, X509};
+
+    use super::certificate_names;
+
+    #[test]
+    fn test_certificate_names() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "data-node-1").unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        let san = SubjectAlternativeName::new()
+            .dns("data-node-1")
+            .ip("10.0.0.1")
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(san).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        let cert = builder.build();
+
+        let names = certificate_names(&cert.to_der().unwrap()).unwrap();
+        assert_eq!(
+            names,
+            vec!["data-node-1".to_string(), "10.0.0.1".to_string()]
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use macros::EnvKeys;
+use serde::{Deserialize, Serialize};
+
+use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::codec::duration;
+
+/// Mutual TLS of the traffic between the nodes of the cluster,
+/// every node presents a certificate issued by the cluster CA.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct InternalTlsConfig {
+    /// The CA certificate issuing the certificates of all the nodes.
+    #[serde(default = "InternalTlsConfig::default_ca_certificate")]
+    pub ca_certificate: String,
+    /// The certificate of this node, its subject alternative names should contain the host of the node.
+    #[serde(default = "InternalTlsConfig::default_certificate")]
+    pub certificate: String,
+    #[serde(default = "InternalTlsConfig::default_private_key")]
+    pub private_key: String,
+    /// The files are checked at this interval, the certificates are reloaded if they are changed.
+    #[serde(
+        with = "duration",
+        default = "InternalTlsConfig::default_reload_interval"
+    )]
+    pub reload_interval: Duration,
+}
+
+impl InternalTlsConfig {
+    fn default_ca_certificate() -> String {
+        "/etc/cnosdb/tls/ca.crt".to_string()
+    }
+
+    fn default_certificate() -> String {
+        "/etc/cnosdb/tls/node.crt".to_string()
+    }
+
+    fn default_private_key() -> String {
+        "/etc/cnosdb/tls/node.key".to_string()
+    }
+
+    fn default_reload_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+}
+
+impl Default for InternalTlsConfig {
+    fn default() -> Self {
+        Self {
+            ca_certificate: Self::default_ca_certificate(),
+            certificate: Self::default_certificate(),
+            private_key: Self::default_private_key(),
+            reload_interval: Self::default_reload_interval(),
+        }
+    }
+}
+
+impl CheckConfig for InternalTlsConfig {
+    fn check(&self, _: &crate::tskv::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("security.internal_tls".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        for (item, path) in [
+            ("ca_certificate", &self.ca_certificate),
+            ("certificate", &self.certificate),
+            ("private_key", &self.private_key),
+        ] {
+            if path.is_empty() {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: item.to_string(),
+                    message: format!("'{item}' is empty"),
+                });
+            }
+        }
+        if self.reload_interval.is_zero() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "reload_interval".to_string(),
+                message: "'reload_interval' should be greater than 0".to_string(),
+            });
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
@@ -1,5 +1,9 @@
+mod internal_tls_config;
 mod limiter_config;
 mod log_config;
+mod password_policy_config;
 
+pub use internal_tls_config::*;
 pub use limiter_config::*;
 pub use log_config::*;
+pub use password_policy_config::*;
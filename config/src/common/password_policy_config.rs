@@ -0,0 +1,156 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use macros::EnvKeys;
+use serde::{Deserialize, Serialize};
+
+use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::codec::duration;
+
+/// The rules of the user passwords and the lockout of the failed logins,
+/// set for the whole system in `[security.password_policy]` or for a tenant
+/// by its `password_policy` option.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct PasswordPolicyConfig {
+    /// The minimum number of characters of a password.
+    #[serde(default = "PasswordPolicyConfig::default_min_length")]
+    pub min_length: usize,
+    /// The minimum number of character classes (lowercase letters, uppercase letters,
+    /// digits and the others) a password contains.
+    #[serde(default = "PasswordPolicyConfig::default_min_char_classes")]
+    pub min_char_classes: usize,
+    /// A password must be changed after this time since it's set, never expires if it's 0.
+    #[serde(
+        with = "duration",
+        default = "PasswordPolicyConfig::default_expire_after"
+    )]
+    pub expire_after: Duration,
+    /// The number of the previous passwords that can't be reused.
+    #[serde(default)]
+    pub history: usize,
+    /// The user is locked after this number of consecutive failed logins, never locked if it's 0.
+    /// The failed logins are counted by every query node in memory and reset when it restarts,
+    /// root is never locked.
+    #[serde(default = "PasswordPolicyConfig::default_max_failed_logins")]
+    pub max_failed_logins: u32,
+    /// The duration of the first lockout, every following lockout lasts twice
+    /// as long as the previous one, up to `max_lockout_duration`.
+    #[serde(
+        with = "duration",
+        default = "PasswordPolicyConfig::default_lockout_duration"
+    )]
+    pub lockout_duration: Duration,
+    #[serde(
+        with = "duration",
+        default = "PasswordPolicyConfig::default_max_lockout_duration"
+    )]
+    pub max_lockout_duration: Duration,
+}
+
+impl PasswordPolicyConfig {
+    fn default_min_length() -> usize {
+        8
+    }
+
+    fn default_min_char_classes() -> usize {
+        3
+    }
+
+    fn default_expire_after() -> Duration {
+        Duration::ZERO
+    }
+
+    fn default_max_failed_logins() -> u32 {
+        5
+    }
+
+    fn default_lockout_duration() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_max_lockout_duration() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    /// The duration of the n-th (starting from 1) consecutive lockout.
+    pub fn lockout_duration_of(&self, n: u32) -> Duration {
+        let factor = 1_u32.checked_shl(n.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.lockout_duration
+            .saturating_mul(factor)
+            .min(self.max_lockout_duration)
+    }
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: Self::default_min_length(),
+            min_char_classes: Self::default_min_char_classes(),
+            expire_after: Self::default_expire_after(),
+            history: 0,
+            max_failed_logins: Self::default_max_failed_logins(),
+            lockout_duration: Self::default_lockout_duration(),
+            max_lockout_duration: Self::default_max_lockout_duration(),
+        }
+    }
+}
+
+impl CheckConfig for PasswordPolicyConfig {
+    fn check(&self, _: &crate::tskv::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("security.password_policy".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if self.min_char_classes > 4 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "min_char_classes".to_string(),
+                message: "'min_char_classes' should not be greater than 4".to_string(),
+            });
+        }
+        if self.max_failed_logins > 0 && self.lockout_duration.is_zero() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "lockout_duration".to_string(),
+                message: "'lockout_duration' should be greater than 0".to_string(),
+            });
+        }
+        if self.lockout_duration > self.max_lockout_duration {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "max_lockout_duration".to_string(),
+                message: "'max_lockout_duration' should not be less than 'lockout_duration'"
+                    .to_string(),
+            });
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::PasswordPolicyConfig;
+
+    #[test]
+    fn test_lockout_duration() {
+        let config_str = r#"
+min_length = 12
+lockout_duration = "1m"
+max_lockout_duration = "5m"
+"#;
+        let policy: PasswordPolicyConfig = toml::from_str(config_str).unwrap();
+        assert_eq!(policy.min_length, 12);
+        assert_eq!(policy.min_char_classes, 3);
+        assert_eq!(policy.lockout_duration_of(1), Duration::from_secs(60));
+        assert_eq!(policy.lockout_duration_of(2), Duration::from_secs(120));
+        assert_eq!(policy.lockout_duration_of(3), Duration::from_secs(240));
+        assert_eq!(policy.lockout_duration_of(4), Duration::from_secs(300));
+        assert_eq!(policy.lockout_duration_of(40), Duration::from_secs(300));
+    }
+}
@@ -1,6 +1,7 @@
 mod cluster_config;
 mod global_config;
 mod heart_beat_config;
+mod security_config;
 mod sys_config;
 
 use std::collections::HashMap;
@@ -16,6 +17,7 @@ use serde::{Deserialize, Serialize};
 use crate::common::LogConfig;
 use crate::meta::cluster_config::MetaClusterConfig;
 use crate::meta::global_config::MetaGlobalConfig;
+use crate::meta::security_config::MetaSecurityConfig;
 use crate::meta::sys_config::SysConfig;
 use crate::EnvKeys as _;
 
@@ -33,6 +35,8 @@ pub struct Opt {
     pub log: LogConfig,
     #[serde(default)]
     pub heartbeat: HeartBeatConfig,
+    #[serde(default)]
+    pub security: MetaSecurityConfig,
 }
 
 impl Opt {
@@ -111,10 +115,17 @@ path = "/tmp/cnosdb/logs"
 [heartbeat]
 heartbeat_recheck_interval = 30
 heartbeat_expired_interval = 60
+
+[security.internal_tls]
+ca_certificate = "/etc/cnosdb/tls/ca.crt"
+reload_interval = "30s"
 "#;
 
         let config: Opt = toml::from_str(config_str).unwrap();
         assert!(toml::to_string_pretty(&config).is_ok());
+        let internal_tls = config.security.internal_tls.as_ref().unwrap();
+        assert_eq!(internal_tls.certificate, "/etc/cnosdb/tls/node.crt");
+        assert_eq!(internal_tls.reload_interval.as_secs(), 30);
         dbg!(config);
     }
 }
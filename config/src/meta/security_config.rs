@@ -0,0 +1,15 @@
+use macros::EnvKeys;
+use serde::{Deserialize, Serialize};
+
+use crate::common::InternalTlsConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys, Default)]
+pub struct MetaSecurityConfig {
+    /// Mutual TLS of the traffic between the meta nodes and the data nodes.
+    pub internal_tls: Option<InternalTlsConfig>,
+    /// The names in the certificates of the peers accepted by the internal tls
+    /// besides the registered data nodes and the meta members, such as the nodes
+    /// joining the cluster and the hosts running the administration tools.
+    #[serde(default)]
+    pub trusted_peers: Vec<String>,
+}
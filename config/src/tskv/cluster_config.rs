@@ -47,6 +47,41 @@ pub struct ClusterConfig {
         default = "ClusterConfig::default_install_snapshot_timeout"
     )]
     pub install_snapshot_timeout: Duration, //ms
+
+    #[serde(default = "ClusterConfig::default_hinted_handoff_max_entries")]
+    pub hinted_handoff_max_entries: usize,
+
+    #[serde(
+        with = "duration",
+        default = "ClusterConfig::default_hinted_handoff_retry_interval"
+    )]
+    pub hinted_handoff_retry_interval: Duration,
+
+    #[serde(
+        with = "duration",
+        default = "ClusterConfig::default_change_log_ship_interval"
+    )]
+    pub change_log_ship_interval: Duration,
+
+    #[serde(
+        with = "bytes_num",
+        default = "ClusterConfig::default_change_log_max_size"
+    )]
+    pub change_log_max_size: u64,
+
+    /// The clusters allowed to replicate their writes to the standby databases of this cluster.
+    #[serde(default)]
+    pub replication_sources: Vec<String>,
+
+    /// The secret shared with the clusters replicated to or from, sent with every
+    /// replicated write and checked by the receiving cluster.
+    #[serde(default)]
+    pub replication_secret: Option<String>,
+
+    /// The names in the certificates of the nodes of `replication_sources`,
+    /// accepted by the internal tls besides the nodes of this cluster.
+    #[serde(default)]
+    pub replication_peers: Vec<String>,
 }
 
 impl ClusterConfig {
@@ -77,6 +112,22 @@ impl ClusterConfig {
     fn default_install_snapshot_timeout() -> Duration {
         Duration::from_millis(3_600_000)
     }
+
+    fn default_hinted_handoff_max_entries() -> usize {
+        10_000
+    }
+
+    fn default_hinted_handoff_retry_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_change_log_ship_interval() -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn default_change_log_max_size() -> u64 {
+        1024 * 1024 * 1024
+    }
 }
 
 impl Default for ClusterConfig {
@@ -89,6 +140,13 @@ impl Default for ClusterConfig {
             trigger_snapshot_interval: ClusterConfig::default_trigger_snapshot_interval(),
             send_append_entries_timeout: ClusterConfig::default_send_append_entries_timeout(),
             install_snapshot_timeout: ClusterConfig::default_install_snapshot_timeout(),
+            hinted_handoff_max_entries: ClusterConfig::default_hinted_handoff_max_entries(),
+            hinted_handoff_retry_interval: ClusterConfig::default_hinted_handoff_retry_interval(),
+            change_log_ship_interval: ClusterConfig::default_change_log_ship_interval(),
+            change_log_max_size: ClusterConfig::default_change_log_max_size(),
+            replication_sources: vec![],
+            replication_secret: None,
+            replication_peers: vec![],
         }
     }
 }
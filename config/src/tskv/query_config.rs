@@ -27,6 +27,40 @@ pub struct QueryConfig {
     pub stream_executor_cpu: usize,
     #[serde(with = "duration", default = "QueryConfig::default_sql_record_timeout")]
     pub sql_record_timeout: Duration,
+    /// Where the results of async queries are spooled: a local directory, or an
+    /// object store url like `s3://bucket/prefix`, `gs://bucket/prefix` or `az://container/prefix`.
+    #[serde(default = "QueryConfig::default_async_result_dir")]
+    pub async_result_dir: String,
+    #[serde(with = "duration", default = "QueryConfig::default_async_result_ttl")]
+    pub async_result_ttl: Duration,
+    #[serde(default = "QueryConfig::default_async_result_page_rows")]
+    pub async_result_page_rows: usize,
+    /// Maximum number of async queries kept for a user, running or with results not expired.
+    #[serde(default = "QueryConfig::default_async_max_queries_per_user")]
+    pub async_max_queries_per_user: usize,
+    /// Maximum number of async queries kept for a tenant.
+    #[serde(default = "QueryConfig::default_async_max_queries_per_tenant")]
+    pub async_max_queries_per_tenant: usize,
+    /// Cache the results of queries over tskv tables, until the data of the vnodes
+    /// they read changes.
+    #[serde(default = "QueryConfig::default_result_cache_enabled")]
+    pub result_cache_enabled: bool,
+    /// Maximum number of query results in the cache.
+    #[serde(default = "QueryConfig::default_result_cache_capacity")]
+    pub result_cache_capacity: usize,
+    /// Results larger than this are not cached.
+    #[serde(
+        with = "bytes_num",
+        default = "QueryConfig::default_result_cache_max_result_size"
+    )]
+    pub result_cache_max_result_size: u64,
+    /// How long the data versions of vnodes are reused to look up cached results,
+    /// a cached result may miss the writes of this period. 0 fetches them for every query.
+    #[serde(
+        with = "duration",
+        default = "QueryConfig::default_result_cache_version_ttl"
+    )]
+    pub result_cache_version_ttl: Duration,
 }
 
 impl QueryConfig {
@@ -65,6 +99,42 @@ impl QueryConfig {
     fn default_sql_record_timeout() -> Duration {
         Duration::from_secs(10)
     }
+
+    fn default_async_result_dir() -> String {
+        "/var/lib/cnosdb/async_result".to_string()
+    }
+
+    fn default_async_result_ttl() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    fn default_async_result_page_rows() -> usize {
+        65536
+    }
+
+    fn default_async_max_queries_per_user() -> usize {
+        16
+    }
+
+    fn default_async_max_queries_per_tenant() -> usize {
+        256
+    }
+
+    fn default_result_cache_enabled() -> bool {
+        false
+    }
+
+    fn default_result_cache_capacity() -> usize {
+        1024
+    }
+
+    fn default_result_cache_max_result_size() -> u64 {
+        4 * 1024 * 1024
+    }
+
+    fn default_result_cache_version_ttl() -> Duration {
+        Duration::from_secs(1)
+    }
 }
 
 impl Default for QueryConfig {
@@ -79,6 +149,15 @@ impl Default for QueryConfig {
             stream_trigger_cpu: Self::default_stream_trigger_cpu(),
             stream_executor_cpu: Self::default_stream_executor_cpu(),
             sql_record_timeout: Self::default_sql_record_timeout(),
+            async_result_dir: Self::default_async_result_dir(),
+            async_result_ttl: Self::default_async_result_ttl(),
+            async_result_page_rows: Self::default_async_result_page_rows(),
+            async_max_queries_per_user: Self::default_async_max_queries_per_user(),
+            async_max_queries_per_tenant: Self::default_async_max_queries_per_tenant(),
+            result_cache_enabled: Self::default_result_cache_enabled(),
+            result_cache_capacity: Self::default_result_cache_capacity(),
+            result_cache_max_result_size: Self::default_result_cache_max_result_size(),
+            result_cache_version_ttl: Self::default_result_cache_version_ttl(),
         }
     }
 }
@@ -143,12 +222,43 @@ impl CheckConfig for QueryConfig {
 
         if self.sql_record_timeout.as_secs() < 1 {
             ret.add_warn(CheckConfigItemResult {
-                config: config_name,
+                config: config_name.clone(),
                 item: "sql_record_timeout".to_string(),
                 message: "'sql_record_timeout' maybe too small(less than 1)".to_string(),
             })
         }
 
+        if self.async_result_page_rows == 0 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "async_result_page_rows".to_string(),
+                message: "'async_result_page_rows' must be greater than 0".to_string(),
+            })
+        }
+        if self.async_max_queries_per_user == 0 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "async_max_queries_per_user".to_string(),
+                message: "'async_max_queries_per_user' must be greater than 0".to_string(),
+            })
+        }
+        if self.async_max_queries_per_tenant < self.async_max_queries_per_user {
+            ret.add_warn(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "async_max_queries_per_tenant".to_string(),
+                message: "'async_max_queries_per_tenant' is less than 'async_max_queries_per_user'"
+                    .to_string(),
+            })
+        }
+
+        if self.result_cache_enabled && self.result_cache_capacity == 0 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "result_cache_capacity".to_string(),
+                message: "'result_cache_capacity' must be greater than 0".to_string(),
+            })
+        }
+
         if ret.is_empty() {
             None
         } else {
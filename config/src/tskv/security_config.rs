@@ -1,13 +1,29 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use macros::EnvKeys;
 use serde::{Deserialize, Serialize};
 
 use crate::check::{CheckConfig, CheckConfigItemResult, CheckConfigResult};
+use crate::codec::{bytes_num, duration};
+use crate::common::{InternalTlsConfig, PasswordPolicyConfig};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
 pub struct SecurityConfig {
     pub tls_config: Option<TLSConfig>,
+    /// Authenticate the bearer JWTs issued by an OIDC provider.
+    pub jwt: Option<JwtConfig>,
+    /// Record the DDL, DCL and data access statements to the audit log.
+    pub audit: Option<AuditConfig>,
+    /// Mutual TLS of the traffic between the data nodes and the meta nodes.
+    pub internal_tls: Option<InternalTlsConfig>,
+    /// The password policy of all the tenants, unless a tenant sets its own.
+    pub password_policy: Option<PasswordPolicyConfig>,
+    /// Verify the passwords of the users against an LDAP server.
+    pub ldap: Option<LdapConfig>,
+    /// The secret keying the hashes of `mask_hash`, it should be the same on all the
+    /// nodes of the cluster, `mask_hash` fails if it's not set.
+    pub masking_secret: Option<String>,
 }
 
 impl CheckConfig for SecurityConfig {
@@ -19,6 +35,31 @@ impl CheckConfig for SecurityConfig {
                 ret.add_all(r);
             }
         }
+        if let Some(ref jwt) = self.jwt {
+            if let Some(r) = jwt.check(all_config) {
+                ret.add_all(r);
+            }
+        }
+        if let Some(ref audit) = self.audit {
+            if let Some(r) = audit.check(all_config) {
+                ret.add_all(r);
+            }
+        }
+        if let Some(ref internal_tls) = self.internal_tls {
+            if let Some(r) = internal_tls.check(all_config) {
+                ret.add_all(r);
+            }
+        }
+        if let Some(ref password_policy) = self.password_policy {
+            if let Some(r) = password_policy.check(all_config) {
+                ret.add_all(r);
+            }
+        }
+        if let Some(ref ldap) = self.ldap {
+            if let Some(r) = ldap.check(all_config) {
+                ret.add_all(r);
+            }
+        }
 
         if ret.is_empty() {
             Some(ret)
@@ -82,3 +123,365 @@ impl CheckConfig for TLSConfig {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct JwtConfig {
+    /// The JWKS verifying the signatures, loaded from a local file or an url like
+    /// `https://sso.example.com/.well-known/jwks.json`, one of them is required.
+    #[serde(default)]
+    pub jwks_file: String,
+    #[serde(default)]
+    pub jwks_url: String,
+    /// The JWKS is reloaded after this interval, or when a token is signed by an unknown key.
+    #[serde(
+        with = "duration",
+        default = "JwtConfig::default_jwks_refresh_interval"
+    )]
+    pub jwks_refresh_interval: Duration,
+    /// The `iss` claim must be equal to the issuer, and the `aud` claim must contain the audience.
+    #[serde(default)]
+    pub issuer: String,
+    #[serde(default)]
+    pub audience: String,
+    /// The clock skew allowed when checking `exp` and `nbf`.
+    #[serde(with = "duration", default = "JwtConfig::default_leeway")]
+    pub leeway: Duration,
+    /// The claim holding the name of the user.
+    #[serde(default = "JwtConfig::default_user_claim")]
+    pub user_claim: String,
+    /// The claim holding the tenant, the token can only be used in this tenant.
+    #[serde(default = "JwtConfig::default_tenant_claim")]
+    pub tenant_claim: String,
+    /// The claim holding the tenant role of the user, used by the auto-provisioned users.
+    #[serde(default = "JwtConfig::default_role_claim")]
+    pub role_claim: String,
+    /// Create the users that don't exist yet, and add them to the tenant with their role.
+    #[serde(default = "JwtConfig::default_auto_provision")]
+    pub auto_provision: bool,
+}
+
+impl JwtConfig {
+    fn default_jwks_refresh_interval() -> Duration {
+        Duration::from_secs(60 * 60)
+    }
+
+    fn default_leeway() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_user_claim() -> String {
+        "sub".to_string()
+    }
+
+    fn default_tenant_claim() -> String {
+        "tenant".to_string()
+    }
+
+    fn default_role_claim() -> String {
+        "role".to_string()
+    }
+
+    fn default_auto_provision() -> bool {
+        false
+    }
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        Self {
+            jwks_file: String::new(),
+            jwks_url: String::new(),
+            jwks_refresh_interval: Self::default_jwks_refresh_interval(),
+            issuer: String::new(),
+            audience: String::new(),
+            leeway: Self::default_leeway(),
+            user_claim: Self::default_user_claim(),
+            tenant_claim: Self::default_tenant_claim(),
+            role_claim: Self::default_role_claim(),
+            auto_provision: Self::default_auto_provision(),
+        }
+    }
+}
+
+impl CheckConfig for JwtConfig {
+    fn check(&self, _: &super::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("security.jwt".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if self.jwks_file.is_empty() == self.jwks_url.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "jwks_file".to_string(),
+                message: "exactly one of 'jwks_file' and 'jwks_url' should be set".to_string(),
+            });
+        }
+        if self.issuer.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "issuer".to_string(),
+                message: "'issuer' is empty".to_string(),
+            });
+        }
+        if self.audience.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "audience".to_string(),
+                message: "'audience' is empty".to_string(),
+            });
+        }
+        if self.user_claim.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "user_claim".to_string(),
+                message: "'user_claim' is empty".to_string(),
+            });
+        }
+        if self.tenant_claim.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "tenant_claim".to_string(),
+                message: "'tenant_claim' is empty".to_string(),
+            });
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct LdapConfig {
+    /// The url of the LDAP server, like `ldap://ldap.example.com:389` or `ldaps://ldap.example.com:636`.
+    #[serde(default = "LdapConfig::default_url")]
+    pub url: String,
+    /// Upgrade the `ldap://` connections with StartTLS.
+    #[serde(default)]
+    pub start_tls: bool,
+    /// The timeout of connecting to the LDAP server and of every operation.
+    #[serde(with = "duration", default = "LdapConfig::default_timeout")]
+    pub timeout: Duration,
+    /// Bind mode: the DN of a user, `{user}` is replaced by the user name,
+    /// like `uid={user},ou=people,dc=example,dc=com`.
+    #[serde(default)]
+    pub bind_dn_template: String,
+    /// Search+bind mode, used if `bind_dn_template` is empty: the user is searched
+    /// under `base_dn` by `user_filter` as the service account `bind_dn`,
+    /// then bound with the DN found.
+    #[serde(default)]
+    pub bind_dn: String,
+    #[serde(default)]
+    pub bind_password: String,
+    #[serde(default)]
+    pub base_dn: String,
+    #[serde(default = "LdapConfig::default_user_filter")]
+    pub user_filter: String,
+    /// The groups of the user are searched under `group_base_dn` by `group_filter`,
+    /// `{dn}` is replaced by the DN of the user and `{user}` by the user name.
+    /// The groups are not searched if `group_base_dn` is empty.
+    #[serde(default)]
+    pub group_base_dn: String,
+    #[serde(default = "LdapConfig::default_group_filter")]
+    pub group_filter: String,
+    /// The attribute holding the name of a group.
+    #[serde(default = "LdapConfig::default_group_name_attribute")]
+    pub group_name_attribute: String,
+    /// The tenant roles of the members of the groups, the first mapping matched is used.
+    #[serde(default)]
+    pub role_mappings: Vec<LdapRoleMapping>,
+    /// Create the users that don't exist yet, and keep their tenant roles
+    /// in sync with `role_mappings`. The existing users are only authenticated by LDAP
+    /// if they are created by it or altered with `ldap_auth = true`.
+    #[serde(default)]
+    pub auto_provision: bool,
+}
+
+impl LdapConfig {
+    fn default_url() -> String {
+        "ldap://127.0.0.1:389".to_string()
+    }
+
+    fn default_timeout() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_user_filter() -> String {
+        "(uid={user})".to_string()
+    }
+
+    fn default_group_filter() -> String {
+        "(member={dn})".to_string()
+    }
+
+    fn default_group_name_attribute() -> String {
+        "cn".to_string()
+    }
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            url: Self::default_url(),
+            start_tls: false,
+            timeout: Self::default_timeout(),
+            bind_dn_template: String::new(),
+            bind_dn: String::new(),
+            bind_password: String::new(),
+            base_dn: String::new(),
+            user_filter: Self::default_user_filter(),
+            group_base_dn: String::new(),
+            group_filter: Self::default_group_filter(),
+            group_name_attribute: Self::default_group_name_attribute(),
+            role_mappings: vec![],
+            auto_provision: false,
+        }
+    }
+}
+
+impl CheckConfig for LdapConfig {
+    fn check(&self, _: &super::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("security.ldap".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if self.url.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "url".to_string(),
+                message: "'url' is empty".to_string(),
+            });
+        }
+        if self.bind_dn_template.is_empty() && self.base_dn.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "base_dn".to_string(),
+                message: "one of 'bind_dn_template' and 'base_dn' should be set".to_string(),
+            });
+        }
+        if !self.bind_dn_template.is_empty() && !self.bind_dn_template.contains("{user}") {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "bind_dn_template".to_string(),
+                message: "'bind_dn_template' should contain '{user}'".to_string(),
+            });
+        }
+        if !self.role_mappings.is_empty() && self.group_base_dn.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name,
+                item: "group_base_dn".to_string(),
+                message: "'group_base_dn' is required by 'role_mappings'".to_string(),
+            });
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct LdapRoleMapping {
+    /// The name of the LDAP group.
+    pub group: String,
+    pub tenant: String,
+    /// A system role (`owner` or `member`) or a custom role of the tenant.
+    pub role: String,
+}
+
+/// The statement categories that can be audited.
+pub const AUDIT_CATEGORIES: [&str; 4] = ["ddl", "dcl", "dml", "query"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, EnvKeys)]
+pub struct AuditConfig {
+    /// The directory of the audit log files.
+    #[serde(default = "AuditConfig::default_path")]
+    pub path: String,
+    /// The audit log file is rotated when it exceeds this size.
+    #[serde(with = "bytes_num", default = "AuditConfig::default_max_file_size")]
+    pub max_file_size: u64,
+    /// The number of rotated files to keep, the oldest ones are removed.
+    #[serde(default = "AuditConfig::default_max_files")]
+    pub max_files: usize,
+    /// The audited statement categories: `ddl`, `dcl`, `dml` (DELETE and UPDATE) and `query` (SELECT).
+    #[serde(default = "AuditConfig::default_categories")]
+    pub categories: Vec<String>,
+    /// Only audit the statements of these tenants, all tenants if it's empty.
+    #[serde(default)]
+    pub tenants: Vec<String>,
+    /// Only audit the statements of these users, all users if it's empty.
+    #[serde(default)]
+    pub users: Vec<String>,
+}
+
+impl AuditConfig {
+    fn default_path() -> String {
+        "/var/log/cnosdb/audit".to_string()
+    }
+
+    fn default_max_file_size() -> u64 {
+        64 * 1024 * 1024
+    }
+
+    fn default_max_files() -> usize {
+        10
+    }
+
+    fn default_categories() -> Vec<String> {
+        vec!["ddl".to_string(), "dcl".to_string(), "dml".to_string()]
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            path: Self::default_path(),
+            max_file_size: Self::default_max_file_size(),
+            max_files: Self::default_max_files(),
+            categories: Self::default_categories(),
+            tenants: vec![],
+            users: vec![],
+        }
+    }
+}
+
+impl CheckConfig for AuditConfig {
+    fn check(&self, _: &super::Config) -> Option<CheckConfigResult> {
+        let config_name = Arc::new("security.audit".to_string());
+        let mut ret = CheckConfigResult::default();
+
+        if self.path.is_empty() {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "path".to_string(),
+                message: "'path' is empty".to_string(),
+            });
+        }
+        if self.max_file_size == 0 {
+            ret.add_error(CheckConfigItemResult {
+                config: config_name.clone(),
+                item: "max_file_size".to_string(),
+                message: "'max_file_size' should be greater than 0".to_string(),
+            });
+        }
+        for category in &self.categories {
+            if !AUDIT_CATEGORIES.contains(&category.to_ascii_lowercase().as_str()) {
+                ret.add_error(CheckConfigItemResult {
+                    config: config_name.clone(),
+                    item: "categories".to_string(),
+                    message: format!(
+                        "unknown category '{category}', expected one of {AUDIT_CATEGORIES:?}"
+                    ),
+                });
+            }
+        }
+
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use models::meta_data::ReplicationSetId;
+use models::predicate::domain::{ResolvedPredicate, TimeRange};
+use models::SeriesKey;
+use protos::kv_service::{raft_write_command, RaftWriteCommand};
+use protos::FieldValue;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::cross_cluster::points_to_lines;
+use crate::errors::{BincodeSerdeSnafu, CommonSnafu, CoordinatorResult, InvalidFlatbufferSnafu};
+
+/// Position of a change feed consumer, the raft log index of the last entry
+/// read from each replication set. Clients only see it as an opaque token.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeFeedPosition(BTreeMap<ReplicationSetId, u64>);
+
+impl ChangeFeedPosition {
+    pub fn get(&self, replica_id: ReplicationSetId) -> Option<u64> {
+        self.0.get(&replica_id).cloned()
+    }
+
+    pub fn advance(&mut self, replica_id: ReplicationSetId, index: u64) {
+        let entry = self.0.entry(replica_id).or_default();
+        *entry = (*entry).max(index);
+    }
+}
+
+impl Display for ChangeFeedPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let data = serde_json::to_vec(&self.0).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", hex::encode(data))
+    }
+}
+
+impl FromStr for ChangeFeedPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hex::decode(s.trim())
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .map(ChangeFeedPosition)
+            .ok_or_else(|| format!("invalid change feed position '{}'", s))
+    }
+}
+
+/// A raft write command applied to a replication set of the database.
+#[derive(Debug, Clone)]
+pub struct ChangeFeedRecord {
+    pub replica_id: ReplicationSetId,
+    pub index: u64,
+    /// Time the command was applied, in milliseconds.
+    pub apply_time: i64,
+    pub command: RaftWriteCommand,
+}
+
+impl ChangeFeedRecord {
+    pub fn events(&self) -> CoordinatorResult<Vec<ChangeEvent>> {
+        match &self.command.command {
+            Some(command) => decode_change_events(command),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    Write {
+        table: String,
+        time: i64,
+        tags: BTreeMap<String, String>,
+        fields: BTreeMap<String, serde_json::Value>,
+    },
+    Delete {
+        table: String,
+        time_ranges: Vec<TimeRange>,
+        tags_filter: String,
+    },
+    UpdateTags {
+        table: String,
+        new_tags: BTreeMap<String, Option<String>>,
+        series: Vec<BTreeMap<String, String>>,
+    },
+    DropTable {
+        table: String,
+    },
+    DropColumn {
+        table: String,
+        column: String,
+    },
+}
+
+impl ChangeEvent {
+    pub fn table(&self) -> &str {
+        match self {
+            ChangeEvent::Write { table, .. }
+            | ChangeEvent::Delete { table, .. }
+            | ChangeEvent::UpdateTags { table, .. }
+            | ChangeEvent::DropTable { table }
+            | ChangeEvent::DropColumn { table, .. } => table,
+        }
+    }
+}
+
+/// Decode a raft write command into the changes it makes, a write becomes one
+/// event per row.
+pub fn decode_change_events(
+    command: &raft_write_command::Command,
+) -> CoordinatorResult<Vec<ChangeEvent>> {
+    let events = match command {
+        raft_write_command::Command::WriteData(request) => {
+            let points = flatbuffers::root::<protos::models::Points>(&request.data)
+                .context(InvalidFlatbufferSnafu)?;
+            points_to_lines(&points)?
+                .into_iter()
+                .map(|line| ChangeEvent::Write {
+                    table: line.table.to_string(),
+                    time: line.timestamp,
+                    tags: line
+                        .tags
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                    fields: line
+                        .fields
+                        .into_iter()
+                        .map(|(k, v)| (k.to_string(), field_value_to_json(v)))
+                        .collect(),
+                })
+                .collect()
+        }
+
+        raft_write_command::Command::DeleteFromTable(request) => {
+            let predicate = bincode::deserialize::<ResolvedPredicate>(&request.predicate)
+                .context(BincodeSerdeSnafu)?;
+            vec![ChangeEvent::Delete {
+                table: request.table.clone(),
+                time_ranges: predicate.time_ranges().time_ranges().collect(),
+                tags_filter: format!("{:?}", predicate.tags_filter()),
+            }]
+        }
+
+        raft_write_command::Command::UpdateTags(request) => {
+            let mut series = Vec::with_capacity(request.matched_series.len());
+            let mut table = None;
+            for key in request.matched_series.iter() {
+                let key = SeriesKey::decode(key).map_err(|e| {
+                    CommonSnafu {
+                        msg: format!("decode series key failed: {}", e),
+                    }
+                    .build()
+                })?;
+                table.get_or_insert_with(|| key.table().clone());
+                series.push(
+                    key.tags()
+                        .iter()
+                        .map(|tag| {
+                            (
+                                String::from_utf8_lossy(&tag.key).to_string(),
+                                String::from_utf8_lossy(&tag.value).to_string(),
+                            )
+                        })
+                        .collect(),
+                );
+            }
+
+            match table {
+                Some(table) => vec![ChangeEvent::UpdateTags {
+                    table,
+                    new_tags: request
+                        .new_tags
+                        .iter()
+                        .map(|tag| {
+                            (
+                                String::from_utf8_lossy(&tag.key).to_string(),
+                                tag.value
+                                    .as_ref()
+                                    .map(|v| String::from_utf8_lossy(v).to_string()),
+                            )
+                        })
+                        .collect(),
+                    series,
+                }],
+                None => vec![],
+            }
+        }
+
+        raft_write_command::Command::DropTable(request) => vec![ChangeEvent::DropTable {
+            table: request.table.clone(),
+        }],
+
+        raft_write_command::Command::DropColumn(request) => vec![ChangeEvent::DropColumn {
+            table: request.table.clone(),
+            column: request.column.clone(),
+        }],
+    };
+
+    Ok(events)
+}
+
+fn field_value_to_json(value: FieldValue) -> serde_json::Value {
+    match value {
+        FieldValue::U64(v) => serde_json::Value::from(v),
+        FieldValue::I64(v) => serde_json::Value::from(v),
+        FieldValue::F64(v) => serde_json::Number::from_f64(v)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        FieldValue::Str(v) => serde_json::Value::from(String::from_utf8_lossy(&v).to_string()),
+        FieldValue::Bool(v) => serde_json::Value::from(v),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use protos::kv_service::{raft_write_command, DropColumnRequest};
+
+    use super::{decode_change_events, ChangeEvent, ChangeFeedPosition};
+
+    #[test]
+    fn test_change_feed_position() {
+        let mut position = ChangeFeedPosition::default();
+        position.advance(1, 10);
+        position.advance(2, 5);
+        position.advance(1, 8);
+        assert_eq!(position.get(1), Some(10));
+        assert_eq!(position.get(3), None);
+
+        let token = position.to_string();
+        assert_eq!(ChangeFeedPosition::from_str(&token).unwrap(), position);
+        assert!(ChangeFeedPosition::from_str("not a token").is_err());
+    }
+
+    #[test]
+    fn test_decode_change_events() {
+        let command = raft_write_command::Command::DropColumn(DropColumnRequest {
+            db: "db".to_string(),
+            table: "cpu".to_string(),
+            column: "usage".to_string(),
+        });
+        let events = decode_change_events(&command).unwrap();
+        assert_eq!(
+            events,
+            vec![ChangeEvent::DropColumn {
+                table: "cpu".to_string(),
+                column: "usage".to_string(),
+            }]
+        );
+        assert_eq!(events[0].table(), "cpu");
+        assert_eq!(
+            serde_json::to_string(&events[0]).unwrap(),
+            r#"{"op":"drop_column","table":"cpu","column":"usage"}"#
+        );
+    }
+}
@@ -0,0 +1,447 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use trace::warn;
+
+use crate::errors::{CoordinatorResult, IOErrorsSnafu};
+
+const SEGMENT_EXTENSION: &str = "log";
+const CHECKPOINT_FILE: &str = "checkpoint";
+const MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+/// index(8) + previous index(8) + apply time in milliseconds(8) + data length(4)
+const RECORD_HEADER_SIZE: usize = 28;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeLogEntry {
+    /// Raft log index of the write command.
+    pub index: u64,
+    /// Index of the entry appended right before this one, 0 for the first
+    /// entry of the log. A reader detects the entries it missed by it.
+    pub prev_index: u64,
+    /// Time the write command was applied, in milliseconds.
+    pub apply_time: i64,
+    /// Encoded `RaftWriteCommand`.
+    pub data: Vec<u8>,
+}
+
+/// Entries read from a change log with the index of the oldest entry kept,
+/// entries before it are gone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChangeLogSlice {
+    pub first_index: Option<u64>,
+    /// Index of the last entry shipped to the remote cluster.
+    pub checkpoint: u64,
+    pub entries: Vec<ChangeLogEntry>,
+}
+
+/// Durable log of the raft write commands applied by a vnode, in raft log
+/// index order. Entries up to the checkpoint have been shipped to the remote
+/// cluster, they are kept for the change feed until the log gets too large.
+/// The entries of a shipped log are never dropped before they are shipped.
+pub struct ChangeLog {
+    dir: PathBuf,
+    max_size: u64,
+    inner: Mutex<ChangeLogInner>,
+}
+
+struct ChangeLogInner {
+    /// First index of a segment -> size of the segment.
+    segments: BTreeMap<u64, u64>,
+    writer: Option<File>,
+    last_index: u64,
+    checkpoint: u64,
+}
+
+impl ChangeLog {
+    pub async fn open(dir: impl AsRef<Path>, max_size: u64) -> CoordinatorResult<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).await.context(IOErrorsSnafu)?;
+
+        let checkpoint = match fs::read(dir.join(CHECKPOINT_FILE)).await {
+            Ok(data) if data.len() == 8 => u64::from_le_bytes(data.try_into().unwrap_or_default()),
+            Ok(_) => 0,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(err) => return Err(err).context(IOErrorsSnafu),
+        };
+
+        let mut segments = BTreeMap::new();
+        let mut read_dir = fs::read_dir(&dir).await.context(IOErrorsSnafu)?;
+        while let Some(entry) = read_dir.next_entry().await.context(IOErrorsSnafu)? {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION) {
+                continue;
+            }
+            let first_index = match path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+            {
+                Some(index) => index,
+                None => continue,
+            };
+            let size = entry.metadata().await.context(IOErrorsSnafu)?.len();
+            segments.insert(first_index, size);
+        }
+
+        // Drop the incomplete record left at the end of the last segment by a crash.
+        // The last segment is never dropped, it holds the last appended entry, the
+        // checkpoint may be copied from another replica and is not used for it.
+        let mut last_index = 0;
+        if let Some((&first_index, size)) = segments.iter_mut().next_back() {
+            let path = segment_path(&dir, first_index);
+            let data = fs::read(&path).await.context(IOErrorsSnafu)?;
+            let (entries, valid_len) = decode_entries(&data);
+            if let Some(entry) = entries.last() {
+                last_index = entry.index;
+            }
+            if (valid_len as u64) < *size {
+                warn!(
+                    "truncate change log segment {:?} from {} to {} bytes",
+                    path, size, valid_len
+                );
+                let file = OpenOptions::new()
+                    .write(true)
+                    .open(&path)
+                    .await
+                    .context(IOErrorsSnafu)?;
+                file.set_len(valid_len as u64)
+                    .await
+                    .context(IOErrorsSnafu)?;
+                *size = valid_len as u64;
+            }
+        }
+
+        Ok(Self {
+            dir,
+            max_size,
+            inner: Mutex::new(ChangeLogInner {
+                segments,
+                writer: None,
+                last_index,
+                checkpoint,
+            }),
+        })
+    }
+
+    /// Append a write command applied at raft log `index`, commands at or
+    /// before the last appended index are ignored. The entries not shipped yet
+    /// are kept beyond the max size if the log is `shipped`.
+    pub async fn append(
+        &self,
+        index: u64,
+        apply_time: i64,
+        data: &[u8],
+        shipped: bool,
+    ) -> CoordinatorResult<()> {
+        let mut inner = self.inner.lock().await;
+        if index <= inner.last_index {
+            return Ok(());
+        }
+
+        let record_size = (RECORD_HEADER_SIZE + data.len()) as u64;
+        let active = inner.segments.iter().next_back().map(|(k, v)| (*k, *v));
+        let first_index = match active {
+            Some((first_index, size)) if size + record_size <= MAX_SEGMENT_SIZE => {
+                if inner.writer.is_none() {
+                    let file = OpenOptions::new()
+                        .append(true)
+                        .open(segment_path(&self.dir, first_index))
+                        .await
+                        .context(IOErrorsSnafu)?;
+                    inner.writer = Some(file);
+                }
+                first_index
+            }
+            _ => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(segment_path(&self.dir, index))
+                    .await
+                    .context(IOErrorsSnafu)?;
+                inner.writer = Some(file);
+                inner.segments.insert(index, 0);
+                index
+            }
+        };
+
+        let mut record = Vec::with_capacity(record_size as usize);
+        record.extend_from_slice(&index.to_le_bytes());
+        record.extend_from_slice(&inner.last_index.to_le_bytes());
+        record.extend_from_slice(&apply_time.to_le_bytes());
+        record.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        record.extend_from_slice(data);
+        if let Some(writer) = inner.writer.as_mut() {
+            writer.write_all(&record).await.context(IOErrorsSnafu)?;
+            writer.flush().await.context(IOErrorsSnafu)?;
+        }
+
+        if let Some(size) = inner.segments.get_mut(&first_index) {
+            *size += record_size;
+        }
+        inner.last_index = index;
+
+        self.enforce_max_size(&mut inner, shipped).await
+    }
+
+    /// Read entries after `index`, stop once `max_bytes` of data is read.
+    pub async fn read_after(
+        &self,
+        index: u64,
+        max_bytes: usize,
+    ) -> CoordinatorResult<Vec<ChangeLogEntry>> {
+        let first_indexes = {
+            let inner = self.inner.lock().await;
+            let start = inner
+                .segments
+                .range(..=index + 1)
+                .next_back()
+                .map(|(first_index, _)| *first_index)
+                .unwrap_or(0);
+            inner
+                .segments
+                .range(start..)
+                .map(|(first_index, _)| *first_index)
+                .collect::<Vec<_>>()
+        };
+
+        let mut entries = Vec::new();
+        let mut read_bytes = 0;
+        for first_index in first_indexes {
+            let data = match fs::read(segment_path(&self.dir, first_index)).await {
+                Ok(data) => data,
+                // The segment was dropped after the checkpoint moved on.
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err).context(IOErrorsSnafu),
+            };
+
+            for entry in decode_entries(&data).0 {
+                if entry.index <= index {
+                    continue;
+                }
+                read_bytes += entry.data.len();
+                entries.push(entry);
+                if read_bytes >= max_bytes {
+                    return Ok(entries);
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Persist the index of the last entry shipped to the remote cluster, and
+    /// drop the segments holding only entries up to it if `drop_shipped`.
+    pub async fn save_checkpoint(&self, index: u64, drop_shipped: bool) -> CoordinatorResult<()> {
+        let mut inner = self.inner.lock().await;
+        if index <= inner.checkpoint {
+            return Ok(());
+        }
+
+        let tmp_path = self.dir.join(format!("{}.tmp", CHECKPOINT_FILE));
+        fs::write(&tmp_path, index.to_le_bytes())
+            .await
+            .context(IOErrorsSnafu)?;
+        fs::rename(&tmp_path, self.dir.join(CHECKPOINT_FILE))
+            .await
+            .context(IOErrorsSnafu)?;
+        inner.checkpoint = index;
+        if !drop_shipped {
+            return Ok(());
+        }
+
+        let first_indexes = inner.segments.keys().cloned().collect::<Vec<_>>();
+        for pair in first_indexes.windows(2) {
+            // A segment ends right before the first index of the next one.
+            if pair[1] > index + 1 {
+                break;
+            }
+            self.remove_segment(&mut inner, pair[0]).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the entries not shipped yet take more than the max size, the
+    /// writes are refused until they are shipped.
+    pub async fn is_full(&self) -> bool {
+        let inner = self.inner.lock().await;
+        let checkpoint = inner.checkpoint;
+        let first_indexes = inner.segments.keys().cloned().collect::<Vec<_>>();
+        let mut unshipped = 0;
+        for (i, (first_index, size)) in inner.segments.iter().enumerate() {
+            let shipped = match first_indexes.get(i + 1) {
+                Some(next_index) => *next_index <= checkpoint + 1,
+                None => *first_index <= checkpoint && inner.last_index <= checkpoint,
+            };
+            if !shipped {
+                unshipped += size;
+            }
+        }
+
+        unshipped > self.max_size
+    }
+
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    pub async fn checkpoint(&self) -> u64 {
+        self.inner.lock().await.checkpoint
+    }
+
+    pub async fn last_index(&self) -> u64 {
+        self.inner.lock().await.last_index
+    }
+
+    /// Index of the oldest entry kept, None if the log is empty.
+    pub async fn first_index(&self) -> Option<u64> {
+        self.inner.lock().await.segments.keys().next().cloned()
+    }
+
+    /// Drop the oldest segments while the log is too large, the segments
+    /// holding entries not shipped yet are kept if the log is `shipped`.
+    async fn enforce_max_size(
+        &self,
+        inner: &mut ChangeLogInner,
+        shipped: bool,
+    ) -> CoordinatorResult<()> {
+        while inner.segments.values().sum::<u64>() > self.max_size {
+            let mut first_indexes = inner.segments.keys();
+            let (first_index, next_index) = match (first_indexes.next(), first_indexes.next()) {
+                (Some(first_index), Some(next_index)) => (*first_index, *next_index),
+                _ => break,
+            };
+            // A segment ends right before the first index of the next one.
+            if shipped && next_index > inner.checkpoint + 1 {
+                break;
+            }
+            self.remove_segment(inner, first_index).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn remove_segment(
+        &self,
+        inner: &mut ChangeLogInner,
+        first_index: u64,
+    ) -> CoordinatorResult<()> {
+        inner.segments.remove(&first_index);
+        match fs::remove_file(segment_path(&self.dir, first_index)).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err).context(IOErrorsSnafu),
+        }
+    }
+}
+
+fn segment_path(dir: &Path, first_index: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", first_index, SEGMENT_EXTENSION))
+}
+
+/// Decode the complete records in `data`, returns them with the length of
+/// the bytes they take.
+fn decode_entries(data: &[u8]) -> (Vec<ChangeLogEntry>, usize) {
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while pos + RECORD_HEADER_SIZE <= data.len() {
+        let index = u64::from_le_bytes(data[pos..pos + 8].try_into().unwrap_or_default());
+        let prev_index = u64::from_le_bytes(data[pos + 8..pos + 16].try_into().unwrap_or_default());
+        let apply_time =
+            i64::from_le_bytes(data[pos + 16..pos + 24].try_into().unwrap_or_default());
+        let len = u32::from_le_bytes(data[pos + 24..pos + 28].try_into().unwrap_or_default());
+        let end = pos + RECORD_HEADER_SIZE + len as usize;
+        if end > data.len() {
+            break;
+        }
+
+        entries.push(ChangeLogEntry {
+            index,
+            prev_index,
+            apply_time,
+            data: data[pos + RECORD_HEADER_SIZE..end].to_vec(),
+        });
+        pos = end;
+    }
+
+    (entries, pos)
+}
+
+#[cfg(test)]
+mod test {
+    use super::ChangeLog;
+
+    #[tokio::test]
+    async fn test_change_log() {
+        let dir = "/tmp/test/coordinator/change_log";
+        let _ = std::fs::remove_dir_all(dir);
+
+        let log = ChangeLog::open(dir, 1024 * 1024).await.unwrap();
+        for index in 1..=5 {
+            log.append(
+                index,
+                index as i64 * 10,
+                format!("data-{}", index).as_bytes(),
+                true,
+            )
+            .await
+            .unwrap();
+        }
+        // Entries replayed after a restart are ignored.
+        log.append(3, 30, b"data-3", true).await.unwrap();
+        assert_eq!(log.last_index().await, 5);
+
+        let entries = log.read_after(2, usize::MAX).await.unwrap();
+        assert_eq!(
+            entries.iter().map(|e| e.index).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+        assert_eq!(entries[0].apply_time, 30);
+        assert_eq!(entries[0].prev_index, 2);
+        assert_eq!(entries[0].data, b"data-3".to_vec());
+
+        let entries = log.read_after(0, 1).await.unwrap();
+        assert_eq!(entries.len(), 1);
+
+        log.save_checkpoint(4, true).await.unwrap();
+        drop(log);
+
+        let log = ChangeLog::open(dir, 1024 * 1024).await.unwrap();
+        assert_eq!(log.checkpoint().await, 4);
+        assert_eq!(log.last_index().await, 5);
+        let entries = log
+            .read_after(log.checkpoint().await, usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].index, 5);
+        assert!(!log.is_full().await);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_change_log_full() {
+        let dir = "/tmp/test/coordinator/change_log_full";
+        let _ = std::fs::remove_dir_all(dir);
+
+        let log = ChangeLog::open(dir, 64).await.unwrap();
+        for index in 1..=5 {
+            log.append(index, 0, &[0; 32], true).await.unwrap();
+        }
+        // The entries not shipped are kept beyond the max size.
+        assert!(log.is_full().await);
+        assert_eq!(log.read_after(0, usize::MAX).await.unwrap().len(), 5);
+
+        log.save_checkpoint(5, false).await.unwrap();
+        assert!(!log.is_full().await);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,607 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use config::tskv::{ClusterConfig, Config};
+use meta::model::MetaRef;
+use metrics::gauge::U64Gauge;
+use metrics::metric_register::MetricsRegister;
+use models::meta_data::{NodeId, VnodeId};
+use models::schema::database_schema::DatabaseOptions;
+use models::utils::now_timestamp_millis;
+use openssl::memcmp;
+use protocol_parser::Line;
+use protos::kv_service::admin_command::Command::FetchChangeLog;
+use protos::kv_service::{AdminCommand, FetchChangeLogRequest, RaftWriteCommand};
+use protos::models::{ColumnType, FieldType, Points};
+use protos::models_helper::parse_prost_bytes;
+use protos::{tskv_service_time_out_client, FieldValue, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
+use replication::state_store::RaftNodeSummary;
+use snafu::{OptionExt, ResultExt};
+use tokio::sync::Mutex;
+use tonic::metadata::AsciiMetadataValue;
+use tonic::transport::Channel;
+use trace::{debug, error, warn};
+
+use self::change_log::{ChangeLog, ChangeLogEntry, ChangeLogSlice};
+use crate::errors::{
+    decode_grpc_response, BincodeSerdeSnafu, CommonSnafu, CoordinatorError, CoordinatorResult,
+    IOErrorsSnafu, PointsSnafu,
+};
+use crate::raft::manager::RaftNodesManager;
+use crate::tskv_executor::TskvAdminRequest;
+
+pub mod change_log;
+
+/// gRPC metadata key marking a raft write shipped from another cluster, the
+/// value is the name of the source cluster.
+pub const CROSS_CLUSTER_METADATA_KEY: &str = "cnosdb-cross-cluster";
+/// gRPC metadata key carrying the `replication_secret` of the source cluster.
+pub const CROSS_CLUSTER_SECRET_METADATA_KEY: &str = "cnosdb-cross-cluster-secret";
+
+const SHIP_BATCH_BYTES: usize = 4 * 1024 * 1024;
+/// The followers copy the checkpoint of their leader every this many rounds,
+/// a new leader then resumes shipping close to where the old one stopped.
+const FOLLOW_CHECKPOINT_ROUNDS: u64 = 10;
+
+/// Change logs of the vnodes on this node, opened on first use.
+pub struct ChangeLogs {
+    dir: PathBuf,
+    max_size: u64,
+    logs: Mutex<HashMap<VnodeId, Arc<ChangeLog>>>,
+}
+
+impl ChangeLogs {
+    pub fn new(dir: PathBuf, max_size: u64) -> Self {
+        Self {
+            dir,
+            max_size,
+            logs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_or_open(&self, vnode_id: VnodeId) -> CoordinatorResult<Arc<ChangeLog>> {
+        let mut logs = self.logs.lock().await;
+        if let Some(log) = logs.get(&vnode_id) {
+            return Ok(log.clone());
+        }
+
+        let log = ChangeLog::open(self.dir.join(vnode_id.to_string()), self.max_size).await?;
+        let log = Arc::new(log);
+        logs.insert(vnode_id, log.clone());
+
+        Ok(log)
+    }
+
+    /// Read the entries of a vnode after `index`, stop once `max_bytes` of data
+    /// is read. No entry is read if `max_bytes` is 0.
+    pub async fn read(
+        &self,
+        vnode_id: VnodeId,
+        index: u64,
+        max_bytes: usize,
+    ) -> CoordinatorResult<ChangeLogSlice> {
+        let log = self.get_or_open(vnode_id).await?;
+        let first_index = log.first_index().await;
+        let checkpoint = log.checkpoint().await;
+        let entries = if max_bytes == 0 {
+            vec![]
+        } else {
+            log.read_after(index, max_bytes).await?
+        };
+
+        Ok(ChangeLogSlice {
+            first_index,
+            checkpoint,
+            entries,
+        })
+    }
+
+    /// Drop the change log of a vnode removed from this node.
+    pub async fn remove(&self, vnode_id: VnodeId) -> CoordinatorResult<()> {
+        self.logs.lock().await.remove(&vnode_id);
+        match tokio::fs::remove_dir_all(self.dir.join(vnode_id.to_string())).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err).context(IOErrorsSnafu),
+        }
+    }
+}
+
+/// Ships the change logs of the vnodes led by this node to the clusters set
+/// by the `REPLICATE_TO` option of their databases.
+pub struct ChangeLogShipper {
+    meta: MetaRef,
+    config: Config,
+    raft_manager: Arc<RaftNodesManager>,
+    register: Arc<MetricsRegister>,
+    channels: HashMap<String, Channel>,
+    /// The term in which the checkpoint of a vnode led by this node was synced
+    /// with the other replicas.
+    synced_terms: HashMap<VnodeId, u64>,
+}
+
+impl ChangeLogShipper {
+    pub fn new(
+        meta: MetaRef,
+        config: Config,
+        raft_manager: Arc<RaftNodesManager>,
+        register: Arc<MetricsRegister>,
+    ) -> Self {
+        Self {
+            meta,
+            config,
+            raft_manager,
+            register,
+            channels: HashMap::new(),
+            synced_terms: HashMap::new(),
+        }
+    }
+
+    pub async fn run(mut self) {
+        let interval = self.config.cluster.change_log_ship_interval;
+        let mut round = 0_u64;
+        loop {
+            tokio::time::sleep(interval).await;
+            round += 1;
+
+            let summaries = match self.raft_manager.nodes_summary_with_leader_term().await {
+                Ok(summaries) => summaries,
+                Err(err) => {
+                    error!(
+                        "list raft nodes for cross cluster replication failed: {}",
+                        err
+                    );
+                    continue;
+                }
+            };
+
+            let leaders = summaries
+                .iter()
+                .filter_map(|(summary, term)| term.map(|_| summary.raft_id as VnodeId))
+                .collect::<Vec<_>>();
+            self.synced_terms
+                .retain(|vnode_id, _| leaders.contains(vnode_id));
+
+            for (summary, leader_term) in summaries {
+                let result = match leader_term {
+                    Some(term) => self.ship_vnode(&summary, term).await,
+                    None if round % FOLLOW_CHECKPOINT_ROUNDS == 0 => {
+                        self.follow_checkpoint(&summary).await
+                    }
+                    None => Ok(()),
+                };
+                if let Err(err) = result {
+                    error!(
+                        "ship change log of vnode {} in {}.{} failed: {}",
+                        summary.raft_id, summary.tenant, summary.db_name, err
+                    );
+                }
+            }
+        }
+    }
+
+    /// Options of the database of the vnode if it is replicated to a remote cluster.
+    async fn replicated_options(&self, summary: &RaftNodeSummary) -> Option<DatabaseOptions> {
+        let client = self.meta.tenant_meta(&summary.tenant).await?;
+        let options = client
+            .get_db_schema(&summary.db_name)
+            .ok()
+            .flatten()?
+            .options()
+            .clone();
+        options.replicate_to().is_some().then_some(options)
+    }
+
+    async fn ship_vnode(&mut self, summary: &RaftNodeSummary, term: u64) -> CoordinatorResult<()> {
+        let Some(options) = self.replicated_options(summary).await else {
+            return Ok(());
+        };
+        let addrs = options
+            .replicate_to_addrs()
+            .into_iter()
+            .map(|addr| addr.to_string())
+            .collect::<Vec<_>>();
+        if addrs.is_empty() {
+            return Ok(());
+        }
+
+        let vnode_id = summary.raft_id as VnodeId;
+        let log = self
+            .raft_manager
+            .change_logs()
+            .get_or_open(vnode_id)
+            .await?;
+        if self.synced_terms.get(&vnode_id) != Some(&term) {
+            // The previous leader may have shipped beyond the local checkpoint.
+            self.sync_checkpoint(summary, &log, options.change_feed())
+                .await?;
+            self.synced_terms.insert(vnode_id, term);
+        }
+        let entries = log
+            .read_after(log.checkpoint().await, SHIP_BATCH_BYTES)
+            .await?;
+
+        let mut result = Ok(());
+        let mut shipped = None;
+        for entry in entries.iter() {
+            if let Err(err) = self.ship_entry(&addrs, entry).await {
+                result = Err(err);
+                break;
+            }
+            shipped = Some(entry.index);
+        }
+        if let Some(index) = shipped {
+            // Shipped entries are still read by the change feed.
+            log.save_checkpoint(index, !options.change_feed()).await?;
+            debug!("shipped change log of vnode {} up to {}", vnode_id, index);
+        }
+
+        self.update_lag_metrics(summary, &log).await?;
+
+        result
+    }
+
+    /// Move the checkpoint of a vnode led by this node to the latest one of the
+    /// replicas, the replicas not reachable are skipped.
+    async fn sync_checkpoint(
+        &self,
+        summary: &RaftNodeSummary,
+        log: &ChangeLog,
+        change_feed: bool,
+    ) -> CoordinatorResult<()> {
+        let replica = crate::get_replica_by_meta(
+            self.meta.clone(),
+            &summary.tenant,
+            &summary.db_name,
+            summary.group_id,
+        )
+        .await?;
+
+        let mut checkpoint = log.checkpoint().await;
+        for vnode in replica.vnodes.iter() {
+            if vnode.node_id == self.config.global.node_id {
+                continue;
+            }
+            match self
+                .fetch_checkpoint(summary, vnode.id, vnode.node_id)
+                .await
+            {
+                Ok(index) => checkpoint = checkpoint.max(index),
+                Err(err) => warn!(
+                    "fetch change log checkpoint of vnode {} from node {} failed: {}",
+                    vnode.id, vnode.node_id, err
+                ),
+            }
+        }
+
+        log.save_checkpoint(checkpoint, !change_feed).await
+    }
+
+    /// Copy the checkpoint of the leader to the vnode following it on this node.
+    async fn follow_checkpoint(&self, summary: &RaftNodeSummary) -> CoordinatorResult<()> {
+        let Some(options) = self.replicated_options(summary).await else {
+            return Ok(());
+        };
+        let replica = crate::get_replica_by_meta(
+            self.meta.clone(),
+            &summary.tenant,
+            &summary.db_name,
+            summary.group_id,
+        )
+        .await?;
+        if replica.leader_node_id == self.config.global.node_id {
+            return Ok(());
+        }
+
+        let checkpoint = self
+            .fetch_checkpoint(summary, replica.leader_vnode_id, replica.leader_node_id)
+            .await?;
+        self.raft_manager
+            .change_logs()
+            .get_or_open(summary.raft_id as VnodeId)
+            .await?
+            .save_checkpoint(checkpoint, !options.change_feed())
+            .await
+    }
+
+    async fn fetch_checkpoint(
+        &self,
+        summary: &RaftNodeSummary,
+        vnode_id: VnodeId,
+        node_id: NodeId,
+    ) -> CoordinatorResult<u64> {
+        let caller = TskvAdminRequest {
+            request: AdminCommand {
+                tenant: summary.tenant.clone(),
+                command: Some(FetchChangeLog(FetchChangeLogRequest {
+                    db_name: summary.db_name.clone(),
+                    vnode_id,
+                    after_index: 0,
+                    max_bytes: 0,
+                })),
+            },
+            meta: self.meta.clone(),
+            timeout: self.config.query.read_timeout,
+            enable_gzip: self.config.service.grpc_enable_gzip,
+        };
+        let data = caller.do_request(node_id).await?;
+        let slice = bincode::deserialize::<ChangeLogSlice>(&data).context(BincodeSerdeSnafu)?;
+
+        Ok(slice.checkpoint)
+    }
+
+    async fn ship_entry(
+        &mut self,
+        addrs: &[String],
+        entry: &ChangeLogEntry,
+    ) -> CoordinatorResult<()> {
+        let request = parse_prost_bytes::<RaftWriteCommand>(&entry.data).map_err(|e| {
+            CommonSnafu {
+                msg: format!("decode change log entry {} failed: {}", entry.index, e),
+            }
+            .build()
+        })?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            match self.send_to(addr, request.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    self.channels.remove(addr);
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            CommonSnafu {
+                msg: "no remote cluster to replicate to".to_string(),
+            }
+            .build()
+        }))
+    }
+
+    async fn send_to(&mut self, addr: &str, request: RaftWriteCommand) -> CoordinatorResult<()> {
+        let channel = match self.channels.get(addr) {
+            Some(channel) => channel.clone(),
+            None => {
+                let channel =
+                    protos::tls::endpoint(addr, self.config.security.internal_tls.as_ref())
+                        .context(IOErrorsSnafu)?
+                        .connect()
+                        .await
+                        .map_err(|e| {
+                            CommonSnafu {
+                                msg: format!("connect to remote cluster {} failed: {}", addr, e),
+                            }
+                            .build()
+                        })?;
+                self.channels.insert(addr.to_string(), channel.clone());
+                channel
+            }
+        };
+        let mut client = tskv_service_time_out_client(
+            channel,
+            self.config.query.write_timeout,
+            DEFAULT_GRPC_SERVER_MESSAGE_LEN,
+            self.config.service.grpc_enable_gzip,
+        );
+
+        let mut cmd = tonic::Request::new(request);
+        let value = AsciiMetadataValue::try_from(self.config.global.cluster_name.as_str())
+            .map_err(|e| CommonSnafu { msg: e.to_string() }.build())?;
+        cmd.metadata_mut().insert(CROSS_CLUSTER_METADATA_KEY, value);
+        if let Some(secret) = &self.config.cluster.replication_secret {
+            let value = AsciiMetadataValue::try_from(secret.as_str())
+                .map_err(|e| CommonSnafu { msg: e.to_string() }.build())?;
+            cmd.metadata_mut()
+                .insert(CROSS_CLUSTER_SECRET_METADATA_KEY, value);
+        }
+        let response = client.raft_write(cmd).await?.into_inner();
+        decode_grpc_response(response)?;
+
+        Ok(())
+    }
+
+    /// Set the number of entries not yet shipped and the age in milliseconds
+    /// of the oldest of them.
+    async fn update_lag_metrics(
+        &self,
+        summary: &RaftNodeSummary,
+        log: &ChangeLog,
+    ) -> CoordinatorResult<()> {
+        let checkpoint = log.checkpoint().await;
+        let lag_entries = log.last_index().await.saturating_sub(checkpoint);
+        let lag_millis = if lag_entries == 0 {
+            0
+        } else {
+            log.read_after(checkpoint, 1)
+                .await?
+                .first()
+                .map(|entry| (now_timestamp_millis() - entry.apply_time).max(0) as u64)
+                .unwrap_or_default()
+        };
+
+        let vnode_id = summary.raft_id.to_string();
+        let labels = [
+            ("tenant", summary.tenant.as_str()),
+            ("database", summary.db_name.as_str()),
+            ("vnode_id", vnode_id.as_str()),
+        ];
+        self.register
+            .metric::<U64Gauge>(
+                "cross_cluster_lag_entries",
+                "change log entries not yet shipped to the remote cluster",
+            )
+            .recorder(labels)
+            .set(lag_entries);
+        self.register
+            .metric::<U64Gauge>(
+                "cross_cluster_lag_millis",
+                "age of the oldest change log entry not yet shipped to the remote cluster",
+            )
+            .recorder(labels)
+            .set(lag_millis);
+
+        Ok(())
+    }
+}
+
+/// Check a write shipped from cluster `source` with `secret`, the source must
+/// be one of the `replication_sources` and share the `replication_secret`.
+pub fn authenticate_source(
+    config: &ClusterConfig,
+    source: &str,
+    secret: Option<&str>,
+) -> CoordinatorResult<()> {
+    let unauthorized = |reason: &str| CoordinatorError::CrossClusterUnauthorized {
+        source_cluster: source.to_string(),
+        reason: reason.to_string(),
+    };
+
+    if !config.replication_sources.iter().any(|name| name == source) {
+        return Err(unauthorized("not one of the replication sources"));
+    }
+    let expected = config
+        .replication_secret
+        .as_deref()
+        .ok_or_else(|| unauthorized("replication secret is not set"))?;
+    match secret {
+        Some(secret)
+            if secret.len() == expected.len()
+                && memcmp::eq(secret.as_bytes(), expected.as_bytes()) =>
+        {
+            Ok(())
+        }
+        _ => Err(unauthorized("replication secret mismatch")),
+    }
+}
+
+/// Convert the points of a shipped write back to lines, so that they are
+/// placed by the buckets and replication sets of the local cluster.
+pub fn points_to_lines<'a>(points: &Points<'a>) -> CoordinatorResult<Vec<Line<'a>>> {
+    let mut lines = Vec::new();
+    let tables = points.tables().context(PointsSnafu { msg: "tables" })?;
+    for table in tables {
+        let table_name = table.tab().context(PointsSnafu { msg: "table name" })?;
+        let columns = table.columns().context(PointsSnafu { msg: "columns" })?;
+        let num_rows = table.num_rows() as usize;
+        let mut table_lines = (0..num_rows)
+            .map(|_| Line {
+                hash_id: 0,
+                table: Cow::Borrowed(table_name),
+                tags: vec![],
+                fields: vec![],
+                timestamp: 0,
+            })
+            .collect::<Vec<_>>();
+
+        for column in columns {
+            let name = column.name().context(PointsSnafu { msg: "column name" })?;
+            let values = column.col_values().context(PointsSnafu {
+                msg: "column values",
+            })?;
+            let nullbits = column.nullbits().context(PointsSnafu {
+                msg: "column nullbits",
+            })?;
+            let nullbits = nullbits.bytes();
+            let is_valid = |row: usize| {
+                nullbits
+                    .get(row / 8)
+                    .is_some_and(|bits| bits & (1 << (row % 8)) != 0)
+            };
+
+            for (idx, line) in table_lines.iter_mut().enumerate() {
+                if !is_valid(idx) {
+                    continue;
+                }
+
+                match column.column_type() {
+                    ColumnType::Time => {
+                        let ts = values.int_value().context(PointsSnafu { msg: "time" })?;
+                        line.timestamp = ts.get(idx);
+                    }
+                    ColumnType::Tag => {
+                        let tags = values.string_value().context(PointsSnafu { msg: "tag" })?;
+                        line.tags
+                            .push((Cow::Borrowed(name), Cow::Borrowed(tags.get(idx))));
+                    }
+                    ColumnType::Field => {
+                        let value = match column.field_type() {
+                            FieldType::Float => FieldValue::F64(
+                                values
+                                    .float_value()
+                                    .context(PointsSnafu { msg: name })?
+                                    .get(idx),
+                            ),
+                            FieldType::Integer => FieldValue::I64(
+                                values
+                                    .int_value()
+                                    .context(PointsSnafu { msg: name })?
+                                    .get(idx),
+                            ),
+                            FieldType::Unsigned => FieldValue::U64(
+                                values
+                                    .uint_value()
+                                    .context(PointsSnafu { msg: name })?
+                                    .get(idx),
+                            ),
+                            FieldType::Boolean => FieldValue::Bool(
+                                values
+                                    .bool_value()
+                                    .context(PointsSnafu { msg: name })?
+                                    .get(idx),
+                            ),
+                            FieldType::String => FieldValue::Str(
+                                values
+                                    .string_value()
+                                    .context(PointsSnafu { msg: name })?
+                                    .get(idx)
+                                    .as_bytes()
+                                    .to_vec(),
+                            ),
+                            _ => continue,
+                        };
+                        line.fields.push((Cow::Borrowed(name), value));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for mut line in table_lines {
+            if line.fields.is_empty() {
+                continue;
+            }
+            line.sort_dedup_and_hash();
+            lines.push(line);
+        }
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use config::tskv::ClusterConfig;
+
+    use super::authenticate_source;
+
+    #[test]
+    fn test_authenticate_source() {
+        let mut config = ClusterConfig {
+            replication_sources: vec!["primary".to_string()],
+            ..Default::default()
+        };
+        // No secret configured, every source is refused.
+        assert!(authenticate_source(&config, "primary", Some("secret")).is_err());
+
+        config.replication_secret = Some("secret".to_string());
+        assert!(authenticate_source(&config, "primary", Some("secret")).is_ok());
+        assert!(authenticate_source(&config, "primary", Some("secreT")).is_err());
+        assert!(authenticate_source(&config, "primary", None).is_err());
+        assert!(authenticate_source(&config, "other", Some("secret")).is_err());
+    }
+}
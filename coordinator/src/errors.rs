@@ -295,6 +295,70 @@ pub enum CoordinatorError {
     ReplicaCannotRemove {
         replica_id: ReplicationSetId,
     },
+
+    #[snafu(display("Hinted handoff queue is full ({} entries), retry later", max_entries))]
+    #[error_code(code = 38)]
+    HintedHandoffFull {
+        max_entries: usize,
+    },
+
+    #[snafu(display(
+        "Database {} is a standby, it only accepts writes replicated from the primary cluster",
+        database
+    ))]
+    #[error_code(code = 39)]
+    DatabaseIsStandby {
+        database: String,
+    },
+
+    #[snafu(display(
+        "Database {} is not a standby, refuse writes replicated from another cluster",
+        database
+    ))]
+    #[error_code(code = 40)]
+    DatabaseNotStandby {
+        database: String,
+    },
+
+    #[snafu(display(
+        "Change feed of database {} is not enabled, set option CHANGE_FEED 'true'",
+        database
+    ))]
+    #[error_code(code = 41)]
+    ChangeFeedNotEnabled {
+        database: String,
+    },
+
+    #[snafu(display(
+        "Change feed position {} of replica {} has expired, the oldest kept is {}",
+        position,
+        replica_id,
+        first_index
+    ))]
+    #[error_code(code = 42)]
+    ChangeFeedPositionExpired {
+        replica_id: ReplicationSetId,
+        position: u64,
+        first_index: u64,
+    },
+
+    #[snafu(display(
+        "Change log of vnode {} exceeds {} bytes not yet shipped to the remote cluster, retry later",
+        vnode_id,
+        max_size
+    ))]
+    #[error_code(code = 43)]
+    ChangeLogFull {
+        vnode_id: VnodeId,
+        max_size: u64,
+    },
+
+    #[snafu(display("Refuse writes replicated from cluster {}: {}", source_cluster, reason))]
+    #[error_code(code = 44)]
+    CrossClusterUnauthorized {
+        source_cluster: String,
+        reason: String,
+    },
 }
 
 impl From<ArrowError> for CoordinatorError {
@@ -361,6 +425,7 @@ pub fn encode_grpc_response(
         Ok(data) => tonic::Response::new(protos::kv_service::BatchBytesResponse {
             data,
             code: SUCCESS_RESPONSE_CODE,
+            scan_stats: None,
         }),
 
         Err(err) => {
@@ -372,11 +437,13 @@ pub fn encode_grpc_response(
                 tonic::Response::new(protos::kv_service::BatchBytesResponse {
                     data: format!("{}-{}", replica_id, new_leader).into(),
                     code: FORWARD_TO_LEADER_CODE,
+                    scan_stats: None,
                 })
             } else {
                 tonic::Response::new(protos::kv_service::BatchBytesResponse {
                     data: err.to_string().into_bytes(),
                     code: FAILED_RESPONSE_CODE,
+                    scan_stats: None,
                 })
             }
         }
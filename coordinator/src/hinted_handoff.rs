@@ -0,0 +1,263 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use models::meta_data::ReplicationSet;
+use protos::kv_service::RaftWriteCommand;
+use protos::models_helper::{parse_prost_bytes, to_prost_bytes};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tokio::fs;
+use tokio::sync::Mutex;
+use trace::warn;
+
+use crate::errors::{BincodeSerdeSnafu, CoordinatorError, CoordinatorResult, IOErrorsSnafu};
+
+const HINT_EXTENSION: &str = "hint";
+/// Hints that kept failing are moved here to be inspected, they are not replayed.
+const FAILED_DIR: &str = "failed";
+
+/// A write accepted with `ConsistencyLevel::Any` while the leader of its
+/// replication set was unreachable.
+#[derive(Debug, Clone)]
+pub struct HintedWrite {
+    /// Position of the hint in the queue, also the name of its file.
+    pub seq: u64,
+    pub replica: ReplicationSet,
+    pub request: RaftWriteCommand,
+    /// Number of replays that failed for a reason other than an unreachable leader.
+    pub attempts: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HintRecord {
+    replica: ReplicationSet,
+    /// Encoded `RaftWriteCommand`.
+    request: Vec<u8>,
+}
+
+/// Bounded queue of hinted writes, replayed in arrival order by `CoordService`
+/// once the leader is reachable again. Every hint is kept in a file until it is
+/// replayed, so the hints survive a restart of the node.
+#[derive(Debug)]
+pub struct HintedHandoffQueue {
+    dir: PathBuf,
+    max_entries: usize,
+    inner: Mutex<HintsInner>,
+}
+
+#[derive(Debug)]
+struct HintsInner {
+    next_seq: u64,
+    hints: VecDeque<HintedWrite>,
+}
+
+impl HintedHandoffQueue {
+    pub async fn open(dir: impl AsRef<Path>, max_entries: usize) -> CoordinatorResult<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).await.context(IOErrorsSnafu)?;
+
+        let mut files = vec![];
+        let mut read_dir = fs::read_dir(&dir).await.context(IOErrorsSnafu)?;
+        while let Some(entry) = read_dir.next_entry().await.context(IOErrorsSnafu)? {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(HINT_EXTENSION) {
+                continue;
+            }
+            if let Some(seq) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+            {
+                files.push((seq, path));
+            }
+        }
+        files.sort_by_key(|(seq, _)| *seq);
+
+        let mut hints = VecDeque::with_capacity(files.len());
+        for (seq, path) in files {
+            let data = fs::read(&path).await.context(IOErrorsSnafu)?;
+            let decoded = bincode::deserialize::<HintRecord>(&data)
+                .ok()
+                .and_then(|record| {
+                    parse_prost_bytes::<RaftWriteCommand>(&record.request)
+                        .ok()
+                        .map(|request| (record.replica, request))
+                });
+            match decoded {
+                Some((replica, request)) => hints.push_back(HintedWrite {
+                    seq,
+                    replica,
+                    request,
+                    attempts: 0,
+                }),
+                None => {
+                    // a hint left incomplete by a crash, the write was not acknowledged
+                    warn!("remove corrupted hinted write {:?}", path);
+                    fs::remove_file(&path).await.context(IOErrorsSnafu)?;
+                }
+            }
+        }
+
+        let next_seq = hints.back().map_or(0, |hint| hint.seq + 1);
+        Ok(Self {
+            dir,
+            max_entries,
+            inner: Mutex::new(HintsInner { next_seq, hints }),
+        })
+    }
+
+    pub async fn push(
+        &self,
+        replica: ReplicationSet,
+        request: RaftWriteCommand,
+    ) -> CoordinatorResult<()> {
+        let mut inner = self.inner.lock().await;
+        if inner.hints.len() >= self.max_entries {
+            return Err(CoordinatorError::HintedHandoffFull {
+                max_entries: self.max_entries,
+            });
+        }
+
+        let seq = inner.next_seq;
+        let record = HintRecord {
+            replica,
+            request: to_prost_bytes(&request),
+        };
+        let data = bincode::serialize(&record).context(BincodeSerdeSnafu)?;
+        let tmp_path = self.dir.join(format!("{:020}.tmp", seq));
+        fs::write(&tmp_path, data).await.context(IOErrorsSnafu)?;
+        fs::rename(&tmp_path, hint_path(&self.dir, seq))
+            .await
+            .context(IOErrorsSnafu)?;
+
+        inner.next_seq += 1;
+        inner.hints.push_back(HintedWrite {
+            seq,
+            replica: record.replica,
+            request,
+            attempts: 0,
+        });
+        Ok(())
+    }
+
+    /// The oldest hinted write, it stays in the queue until it is removed.
+    pub async fn front(&self) -> Option<HintedWrite> {
+        self.inner.lock().await.hints.front().cloned()
+    }
+
+    /// Remove the oldest hinted write once it is replayed.
+    pub async fn pop_front(&self) -> CoordinatorResult<()> {
+        let mut inner = self.inner.lock().await;
+        if let Some(hint) = inner.hints.pop_front() {
+            remove_if_exists(&hint_path(&self.dir, hint.seq)).await?;
+        }
+        Ok(())
+    }
+
+    /// Count a failed replay of the oldest hinted write, returns the number of failures.
+    pub async fn fail_front(&self) -> usize {
+        let mut inner = self.inner.lock().await;
+        match inner.hints.front_mut() {
+            Some(hint) => {
+                hint.attempts += 1;
+                hint.attempts
+            }
+            None => 0,
+        }
+    }
+
+    /// Stop replaying the oldest hinted write, its file is moved to the failed
+    /// directory. Returns the path of the moved file.
+    pub async fn discard_front(&self) -> CoordinatorResult<Option<PathBuf>> {
+        let mut inner = self.inner.lock().await;
+        let Some(hint) = inner.hints.pop_front() else {
+            return Ok(None);
+        };
+
+        let failed_dir = self.dir.join(FAILED_DIR);
+        fs::create_dir_all(&failed_dir)
+            .await
+            .context(IOErrorsSnafu)?;
+        let failed_path = hint_path(&failed_dir, hint.seq);
+        fs::rename(hint_path(&self.dir, hint.seq), &failed_path)
+            .await
+            .context(IOErrorsSnafu)?;
+
+        Ok(Some(failed_path))
+    }
+
+    pub async fn len(&self) -> usize {
+        self.inner.lock().await.hints.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.inner.lock().await.hints.is_empty()
+    }
+}
+
+fn hint_path(dir: &Path, seq: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", seq, HINT_EXTENSION))
+}
+
+async fn remove_if_exists(path: &Path) -> CoordinatorResult<()> {
+    match fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).context(IOErrorsSnafu),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use models::meta_data::ReplicationSet;
+    use protos::kv_service::RaftWriteCommand;
+
+    use super::HintedHandoffQueue;
+
+    fn request(replica_id: u32) -> RaftWriteCommand {
+        RaftWriteCommand {
+            replica_id,
+            tenant: "cnosdb".to_string(),
+            db_name: "public".to_string(),
+            command: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hinted_handoff_queue() {
+        let dir = "/tmp/test/coordinator/hinted_handoff";
+        let _ = std::fs::remove_dir_all(dir);
+
+        let queue = HintedHandoffQueue::open(dir, 3).await.unwrap();
+        let replica = ReplicationSet::new(1, 0, 0, vec![]);
+
+        queue.push(replica.clone(), request(1)).await.unwrap();
+        queue.push(replica.clone(), request(2)).await.unwrap();
+        queue.push(replica.clone(), request(3)).await.unwrap();
+        assert!(queue.push(replica.clone(), request(4)).await.is_err());
+
+        assert_eq!(queue.front().await.unwrap().request.replica_id, 1);
+        queue.pop_front().await.unwrap();
+        assert_eq!(queue.fail_front().await, 1);
+        assert_eq!(queue.fail_front().await, 2);
+        let failed = queue.discard_front().await.unwrap().unwrap();
+        assert!(failed.exists());
+        assert_eq!(queue.len().await, 1);
+        drop(queue);
+
+        // the hints not replayed are kept after a restart
+        let queue = HintedHandoffQueue::open(dir, 3).await.unwrap();
+        assert_eq!(queue.len().await, 1);
+        let hint = queue.front().await.unwrap();
+        assert_eq!(hint.request, request(3));
+        assert_eq!(hint.replica, replica);
+
+        queue.push(replica.clone(), request(5)).await.unwrap();
+        queue.pop_front().await.unwrap();
+        assert_eq!(queue.front().await.unwrap().request.replica_id, 5);
+        queue.pop_front().await.unwrap();
+        assert!(queue.is_empty().await);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,17 +1,21 @@
 #![recursion_limit = "256"]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 
+use change_feed::{ChangeFeedPosition, ChangeFeedRecord};
 use config::tskv::Config;
 use datafusion::arrow::record_batch::RecordBatch;
 use errors::CoordinatorError;
 use futures::Stream;
 use meta::model::{MetaClientRef, MetaRef};
+use models::consistency_level::ConsistencyLevel;
+use models::follower_read::FollowerReadLag;
 use models::meta_data::{
-    NodeId, ReplicaAllInfo, ReplicationSet, ReplicationSetId, VnodeAllInfo, VnodeId,
+    NodeId, ReplicaAllInfo, ReplicationSet, ReplicationSetId, VnodeAllInfo, VnodeId, VnodeInfo,
 };
 use models::object_reference::ResolvedTable;
 use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
@@ -29,7 +33,10 @@ use utils::precision::Precision;
 use crate::errors::{CoordinatorResult, MetaSnafu};
 use crate::service::CoordServiceMetrics;
 
+pub mod change_feed;
+pub mod cross_cluster;
 pub mod errors;
+pub mod hinted_handoff;
 pub mod metrics;
 pub mod raft;
 pub mod reader;
@@ -61,13 +68,18 @@ pub trait Coordinator: Send + Sync {
     fn raft_manager(&self) -> Arc<RaftNodesManager>;
     async fn tenant_meta(&self, tenant: &str) -> Option<MetaClientRef>;
 
-    fn tskv_raft_writer(&self, request: RaftWriteCommand) -> TskvRaftWriter;
+    fn tskv_raft_writer(
+        &self,
+        request: RaftWriteCommand,
+        consistency: ConsistencyLevel,
+    ) -> TskvRaftWriter;
 
     /// get all vnodes of a table to quering
     async fn table_vnodes(
         &self,
         table: &ResolvedTable,
         predicate: ResolvedPredicateRef,
+        follower_read_max_lag: Option<FollowerReadLag>,
     ) -> CoordinatorResult<Vec<ReplicationSet>>;
 
     async fn write_replica_by_raft(
@@ -77,12 +89,26 @@ pub trait Coordinator: Send + Sync {
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<()>;
 
+    /// Write a raft command shipped from the primary cluster of a standby database.
+    async fn write_replicated(&self, request: RaftWriteCommand) -> CoordinatorResult<()>;
+
+    /// Read the changes applied to a database after `position`, at most about
+    /// `max_bytes` from each replication set.
+    async fn read_change_feed(
+        &self,
+        tenant: &str,
+        db: &str,
+        position: &ChangeFeedPosition,
+        max_bytes: usize,
+    ) -> CoordinatorResult<Vec<ChangeFeedRecord>>;
+
     async fn write_lines<'a>(
         &self,
         tenant: &str,
         db: &str,
         precision: Precision,
         lines: Vec<Line<'a>>,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize>;
 
@@ -91,6 +117,7 @@ pub trait Coordinator: Send + Sync {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize>;
 
@@ -114,6 +141,14 @@ pub trait Coordinator: Send + Sync {
 
     async fn compact_vnodes(&self, tenant: &str, vnode_ids: Vec<VnodeId>) -> CoordinatorResult<()>;
 
+    /// Get the data versions of the vnodes from the nodes holding them, the version
+    /// of a vnode is None if the vnode does not exist on its node.
+    async fn vnode_data_versions(
+        &self,
+        tenant: &str,
+        vnodes: &[VnodeInfo],
+    ) -> CoordinatorResult<HashMap<VnodeId, Option<u64>>>;
+
     /// A manager to manage vnode.
     async fn replication_manager(
         &self,
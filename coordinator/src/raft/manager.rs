@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -6,36 +6,52 @@ use std::time::Duration;
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
 use models::meta_data::*;
+use models::predicate::domain::TimeRange;
 use models::schema::database_schema::make_owner;
+use models::schema::tskv_table_schema::TskvTableSchema;
 use openraft::SnapshotPolicy;
 use protos::kv_service::*;
 use replication::metrics::ReplicationMetrics;
 use replication::multi_raft::MultiRaft;
 use replication::node_store::NodeStorage;
-use replication::raft_node::RaftNode;
+use replication::raft_node::{FollowerLag, RaftNode};
 use replication::state_store::{RaftNodeSummary, StateStorage};
 use replication::{ApplyStorageRef, EntryStorageRef, RaftNodeId, RaftNodeInfo, ReplicationConfig};
+use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use tokio::runtime::Runtime;
 use tokio::sync::RwLock;
 use tracing::info;
+use tskv::tsm::reader::TsmReader;
 use tskv::wal::wal_store::RaftEntryStorage;
 use tskv::{wal, EngineRef};
 
 use super::TskvEngineStorage;
+use crate::cross_cluster::ChangeLogs;
 use crate::errors::{
-    CommonSnafu, CoordinatorError, CoordinatorResult, LeaderIsWrongSnafu, MetaSnafu,
+    CommonSnafu, CoordinatorError, CoordinatorResult, IOErrorsSnafu, LeaderIsWrongSnafu, MetaSnafu,
     RaftNodeNotFoundSnafu, ReplicatSnafu, TskvSnafu,
 };
 use crate::tskv_executor::{TskvAdminRequest, TskvLeaderExecutor};
 use crate::{get_replica_all_info, update_replication_set};
 
+/// Raft progress of a vnode of a replication set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicaProgress {
+    pub vnode_id: VnodeId,
+    /// Index of the last entry applied by the vnode.
+    pub applied: u64,
+    /// Replication lag of the followers keyed by vnode id, only on the leader.
+    pub followers_lag: Option<Vec<(VnodeId, FollowerLag)>>,
+}
+
 pub struct RaftNodesManager {
     meta: MetaRef,
     config: config::tskv::Config,
     kv_inst: Option<EngineRef>,
     raft_state: Arc<StateStorage>,
     raft_nodes: Arc<RwLock<MultiRaft>>,
+    change_logs: Arc<ChangeLogs>,
 
     register: Arc<MetricsRegister>,
 }
@@ -50,6 +66,10 @@ impl RaftNodesManager {
         let path = PathBuf::from(config.storage.path.clone()).join("raft-state");
         let state =
             StateStorage::open(path, config.cluster.lmdb_max_map_size.try_into().unwrap()).unwrap();
+        let change_logs = ChangeLogs::new(
+            PathBuf::from(config.storage.path.clone()).join("change-log"),
+            config.cluster.change_log_max_size,
+        );
 
         Self {
             meta,
@@ -58,6 +78,7 @@ impl RaftNodesManager {
             register,
             raft_state: Arc::new(state),
             raft_nodes: Arc::new(RwLock::new(MultiRaft::new())),
+            change_logs: Arc::new(change_logs),
         }
     }
 
@@ -69,6 +90,32 @@ impl RaftNodesManager {
         self.raft_nodes.clone()
     }
 
+    pub fn change_logs(&self) -> Arc<ChangeLogs> {
+        self.change_logs.clone()
+    }
+
+    /// Get the summaries of the raft nodes on this node which lead their group.
+    /// Summaries of the raft nodes on this node, with the term of the ones
+    /// leading their group, None for the followers.
+    pub async fn nodes_summary_with_leader_term(
+        &self,
+    ) -> CoordinatorResult<Vec<(RaftNodeSummary, Option<u64>)>> {
+        let summaries = self.raft_state.all_nodes_summary().context(ReplicatSnafu)?;
+        let nodes = self.raft_nodes.read().await;
+
+        let mut result = Vec::with_capacity(summaries.len());
+        for summary in summaries {
+            if let Ok(Some(node)) = nodes.get_node(summary.group_id) {
+                let metrics = node.raft_metrics();
+                let leader_term =
+                    (metrics.current_leader == Some(metrics.id)).then_some(metrics.current_term);
+                result.push((summary, leader_term));
+            }
+        }
+
+        Ok(result)
+    }
+
     pub async fn metrics(&self, group_id: u32) -> String {
         if let Ok(Some(node)) = self.raft_nodes.read().await.get_node(group_id) {
             let res = node.metrics().await;
@@ -83,6 +130,123 @@ impl RaftNodesManager {
         }
     }
 
+    /// Get the raft progress of the vnode of a replication set on this node,
+    /// with the replication lag of the followers if the vnode is the leader.
+    pub async fn replica_progress(
+        &self,
+        replica_id: ReplicationSetId,
+    ) -> CoordinatorResult<ReplicaProgress> {
+        let node = self
+            .raft_nodes
+            .read()
+            .await
+            .get_node(replica_id)
+            .context(ReplicatSnafu)?
+            .ok_or_else(|| CoordinatorError::ReplicationSetNotFound { id: replica_id })?;
+
+        let followers_lag = node.followers_lag().map(|lags| {
+            lags.into_iter()
+                .map(|(id, lag)| (id as VnodeId, lag))
+                .collect()
+        });
+
+        Ok(ReplicaProgress {
+            vnode_id: node.raft_id() as VnodeId,
+            applied: node.applied_index(),
+            followers_lag,
+        })
+    }
+
+    /// Collect the vnodes on this node with the table schemas and time range
+    /// of their TSM files, so that the meta data can be rebuilt from them.
+    pub async fn node_inventory(&self) -> CoordinatorResult<NodeInventory> {
+        let summaries = self.raft_state.all_nodes_summary().context(ReplicatSnafu)?;
+        let storage = tskv::kv_option::StorageOptions::from(&self.config);
+
+        let mut vnodes = Vec::with_capacity(summaries.len());
+        for summary in summaries {
+            let vnode_id = summary.raft_id as VnodeId;
+            let leader_vnode_id = match self.raft_nodes.read().await.get_node(summary.group_id) {
+                Ok(Some(node)) => node.raft_metrics().current_leader.map(|id| id as VnodeId),
+                _ => None,
+            };
+
+            let owner = make_owner(&summary.tenant, &summary.db_name);
+            let mut time_range: Option<TimeRange> = None;
+            let mut tables: HashMap<String, TskvTableSchema> = HashMap::new();
+            for dir in [
+                storage.tsm_dir(&owner, vnode_id),
+                storage.delta_dir(&owner, vnode_id),
+            ] {
+                if !tokio::fs::try_exists(&dir).await.context(IOErrorsSnafu)? {
+                    continue;
+                }
+
+                let mut read_dir = tokio::fs::read_dir(&dir).await.context(IOErrorsSnafu)?;
+                while let Some(entry) = read_dir.next_entry().await.context(IOErrorsSnafu)? {
+                    let path = entry.path();
+                    if !matches!(
+                        path.extension().and_then(|ext| ext.to_str()),
+                        Some("tsm") | Some("delta")
+                    ) {
+                        continue;
+                    }
+
+                    // The file is read by the thread pool of the tskv file system.
+                    let reader = match TsmReader::open(&path).await {
+                        Ok(reader) => reader,
+                        Err(err) => {
+                            info!("skip unreadable tsm file {}: {}", path.display(), err);
+                            continue;
+                        }
+                    };
+
+                    let file_range = *reader.footer().time_range();
+                    match time_range.as_mut() {
+                        Some(range) => range.merge(&file_range),
+                        None => time_range = Some(file_range),
+                    }
+
+                    for name in reader.chunk_group_meta().tables().keys() {
+                        if let Some(schema) = reader.table_schema(name) {
+                            match tables.get(name) {
+                                Some(old) if old.schema_version >= schema.schema_version => {}
+                                _ => {
+                                    tables.insert(name.clone(), schema.as_ref().clone());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            let client = self.meta.tenant_meta(&summary.tenant).await;
+            let db_schema = client
+                .as_ref()
+                .and_then(|client| client.get_db_schema(&summary.db_name).ok().flatten());
+            let replica_info = client
+                .as_ref()
+                .and_then(|client| client.get_replica_all_info(summary.group_id));
+
+            vnodes.push(VnodeInventory {
+                tenant: summary.tenant,
+                db_name: summary.db_name,
+                vnode_id,
+                replica_id: summary.group_id,
+                leader_vnode_id,
+                time_range,
+                tables: tables.into_values().collect(),
+                db_schema,
+                replica_info,
+            });
+        }
+
+        Ok(NodeInventory {
+            node_id: self.node_id(),
+            vnodes,
+        })
+    }
+
     pub async fn start_all_raft_node(
         runtime: Arc<Runtime>,
         manager: Arc<RaftNodesManager>,
@@ -662,6 +826,7 @@ impl RaftNodesManager {
             self.meta.clone(),
             vnode_store.clone(),
             storage,
+            self.change_logs.clone(),
             self.config.service.grpc_enable_gzip,
         );
 
@@ -685,6 +850,7 @@ impl RaftNodesManager {
                 as u64,
             install_snapshot_timeout: self.config.cluster.install_snapshot_timeout.as_millis()
                 as u64,
+            internal_tls: self.config.security.internal_tls.clone(),
         }
     }
 
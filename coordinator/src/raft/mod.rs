@@ -1,8 +1,10 @@
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 use meta::model::MetaRef;
 use models::meta_data::VnodeId;
+use models::utils::now_timestamp_millis;
 use protos::kv_service::tskv_service_client::TskvServiceClient;
 use protos::kv_service::{DownloadFileRequest, RaftWriteCommand};
 use protos::models_helper::parse_prost_bytes;
@@ -10,7 +12,7 @@ use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use replication::errors::{
     IOErrSnafu, MsgInvalidSnafu, ReplicationError, ReplicationResult, SnapshotErrSnafu,
 };
-use replication::{ApplyContext, ApplyStorage, EngineMetrics};
+use replication::{ApplyContext, ApplyStorage, EngineMetrics, APPLY_TYPE_WRITE};
 use snafu::ResultExt;
 use tokio::io::AsyncWriteExt;
 use tokio_stream::StreamExt;
@@ -23,6 +25,7 @@ use tskv::kv_option::DATA_PATH;
 use tskv::vnode_store::VnodeStorage;
 use tskv::VnodeSnapshot;
 
+use crate::cross_cluster::ChangeLogs;
 use crate::errors::{CommonSnafu, CoordinatorResult, IOErrorsSnafu, MetaSnafu};
 
 pub mod manager;
@@ -36,6 +39,7 @@ pub struct TskvEngineStorage {
     meta: MetaRef,
     vnode: VnodeStorage,
     storage: tskv::EngineRef,
+    change_logs: Arc<ChangeLogs>,
     grpc_enable_gzip: bool,
 }
 
@@ -47,6 +51,7 @@ impl TskvEngineStorage {
         meta: MetaRef,
         vnode: VnodeStorage,
         storage: tskv::EngineRef,
+        change_logs: Arc<ChangeLogs>,
         grpc_enable_gzip: bool,
     ) -> Self {
         Self {
@@ -54,6 +59,7 @@ impl TskvEngineStorage {
             vnode_id,
             storage,
             vnode,
+            change_logs,
             tenant: tenant.to_owned(),
             db_name: db_name.to_owned(),
             grpc_enable_gzip,
@@ -182,6 +188,26 @@ impl TskvEngineStorage {
 
         Ok(vec![])
     }
+
+    /// Whether the database of the vnode keeps a change log, for the change
+    /// feed or for a remote cluster, and whether the log is shipped to the remote
+    /// cluster. None if no change log is kept.
+    async fn change_log_shipped(&self) -> Option<bool> {
+        let client = self.meta.tenant_meta(&self.tenant).await?;
+        let schema = client.get_db_schema(&self.db_name).ok().flatten()?;
+        let shipped = schema.options().replicate_to().is_some();
+        (shipped || schema.options().change_feed()).then_some(shipped)
+    }
+
+    async fn append_change_log(&self, index: u64, req: &[u8]) -> CoordinatorResult<()> {
+        let Some(shipped) = self.change_log_shipped().await else {
+            return Ok(());
+        };
+        let change_log = self.change_logs.get_or_open(self.vnode_id).await?;
+        change_log
+            .append(index, now_timestamp_millis(), req, shipped)
+            .await
+    }
 }
 
 #[async_trait::async_trait]
@@ -191,6 +217,19 @@ impl ApplyStorage for TskvEngineStorage {
         ctx: &ApplyContext,
         req: &replication::Request,
     ) -> ReplicationResult<replication::Response> {
+        // A write missing from the change log would never reach the remote
+        // cluster nor the change feed, stop applying instead of skipping it.
+        if ctx.apply_type == APPLY_TYPE_WRITE {
+            self.append_change_log(ctx.index, req)
+                .await
+                .map_err(|err| ReplicationError::ApplyEngineErr {
+                    msg: format!(
+                        "append raft entry {} of vnode {} to change log failed: {}",
+                        ctx.index, self.vnode_id, err
+                    ),
+                })?;
+        }
+
         let apply_result = self.exec_apply(ctx, req).await;
         if let Err(err) = &apply_result {
             error!("replication apply failed: {:?}; {:?}", ctx, err);
@@ -273,6 +312,12 @@ impl ApplyStorage for TskvEngineStorage {
             .map_err(|err| ReplicationError::DestoryRaftNodeErr {
                 msg: err.to_string(),
             })?;
+        self.change_logs
+            .remove(self.vnode_id)
+            .await
+            .map_err(|err| ReplicationError::DestoryRaftNodeErr {
+                msg: err.to_string(),
+            })?;
 
         Ok(())
     }
@@ -4,18 +4,23 @@ use std::time::Duration;
 
 use memory_pool::MemoryPoolRef;
 use meta::model::MetaRef;
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::*;
 use protos::kv_service::{raft_write_command, RaftWriteCommand};
 use protos::models_helper::to_prost_bytes;
 use protos::{tskv_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
 use replication::raft_node::RaftNode;
 use snafu::{OptionExt, ResultExt};
-use trace::debug;
+use tonic::metadata::AsciiMetadataValue;
+use trace::{debug, error};
 
 use super::manager::RaftNodesManager;
 use crate::errors::*;
 use crate::TskvLeaderCaller;
 
+/// gRPC metadata key carrying the consistency level of a forwarded raft write.
+pub const WRITE_CONSISTENCY_METADATA_KEY: &str = "cnosdb-write-consistency";
+
 pub struct TskvRaftWriter {
     pub meta: MetaRef,
     pub node_id: NodeId,
@@ -26,6 +31,7 @@ pub struct TskvRaftWriter {
     pub raft_manager: Arc<RaftNodesManager>,
 
     pub request: RaftWriteCommand,
+    pub consistency: ConsistencyLevel,
 
     pub counter: Arc<AtomicUsize>,
 }
@@ -40,6 +46,7 @@ impl TskvRaftWriter {
         memory_pool: MemoryPoolRef,
         raft_manager: Arc<RaftNodesManager>,
         request: RaftWriteCommand,
+        consistency: ConsistencyLevel,
         counter: Arc<AtomicUsize>,
     ) -> TskvRaftWriter {
         counter.fetch_add(1, Ordering::SeqCst);
@@ -52,6 +59,7 @@ impl TskvRaftWriter {
             memory_pool,
             raft_manager,
             request,
+            consistency,
             counter,
         }
     }
@@ -83,6 +91,39 @@ impl TskvRaftWriter {
         Ok(())
     }
 
+    /// Refuse the write while the change log of a database replicated to a remote
+    /// cluster holds too many entries not shipped yet, instead of dropping them.
+    async fn check_change_log(&self, replica: &ReplicationSet) -> CoordinatorResult<()> {
+        let replicated = match self.meta.tenant_meta(&self.request.tenant).await {
+            Some(client) => client
+                .get_db_schema(&self.request.db_name)
+                .ok()
+                .flatten()
+                .is_some_and(|schema| schema.options().replicate_to().is_some()),
+            None => false,
+        };
+        if !replicated {
+            return Ok(());
+        }
+        let Some(vnode) = replica.vnodes.iter().find(|v| v.node_id == self.node_id) else {
+            return Ok(());
+        };
+
+        let change_log = self
+            .raft_manager
+            .change_logs()
+            .get_or_open(vnode.id)
+            .await?;
+        if change_log.is_full().await {
+            return Err(CoordinatorError::ChangeLogFull {
+                vnode_id: vnode.id,
+                max_size: change_log.max_size(),
+            });
+        }
+
+        Ok(())
+    }
+
     async fn write_to_remote(&self, leader_id: u64) -> CoordinatorResult<()> {
         let channel = self.meta.get_node_conn(leader_id).await.map_err(|error| {
             CoordinatorError::PreExecution {
@@ -96,7 +137,13 @@ impl TskvRaftWriter {
             self.enable_gzip,
         );
 
-        let cmd = tonic::Request::new(self.request.clone());
+        let mut cmd = tonic::Request::new(self.request.clone());
+        if self.consistency != ConsistencyLevel::default() {
+            let value = AsciiMetadataValue::try_from(self.consistency.to_string())
+                .map_err(|e| CommonSnafu { msg: e.to_string() }.build())?;
+            cmd.metadata_mut()
+                .insert(WRITE_CONSISTENCY_METADATA_KEY, value);
+        }
         let begin_time = models::utils::now_timestamp_millis();
         let response = client.raft_write(cmd).await?.into_inner();
 
@@ -113,6 +160,27 @@ impl TskvRaftWriter {
     }
 
     async fn write_to_raft(&self, raft: Arc<RaftNode>, data: Vec<u8>) -> CoordinatorResult<()> {
+        match self.consistency {
+            ConsistencyLevel::Quorum => {
+                Self::commit_to_raft(raft, data).await?;
+            }
+
+            ConsistencyLevel::All => {
+                let log_index = Self::commit_to_raft(raft.clone(), data).await?;
+                self.wait_replicated_to_all(&raft, log_index).await?;
+            }
+
+            ConsistencyLevel::One | ConsistencyLevel::Any => {
+                self.append_to_raft(raft, data).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write to raft and wait until the entry is committed (acknowledged by a
+    /// quorum of the group) and applied, returns the index of the entry.
+    async fn commit_to_raft(raft: Arc<RaftNode>, data: Vec<u8>) -> CoordinatorResult<u64> {
         match raft.raw_raft().client_write(data).await {
             Err(err) => {
                 if let Some(openraft::error::ForwardToLeader {
@@ -143,12 +211,115 @@ impl TskvRaftWriter {
                         .context(BincodeSerdeSnafu)?;
 
                 let _data = apply_result.map_err(|e| CommonSnafu { msg: e }.build())?;
+                raft.record_applied(resp.log_id.index);
 
-                Ok(())
+                Ok(resp.log_id.index)
             }
         }
     }
 
+    /// Wait until every voter of the raft group has replicated the entry.
+    async fn wait_replicated_to_all(
+        &self,
+        raft: &RaftNode,
+        log_index: u64,
+    ) -> CoordinatorResult<()> {
+        raft.wait_condition(
+            move |metrics| {
+                let replication = match &metrics.replication {
+                    Some(replication) => replication,
+                    None => return false,
+                };
+
+                metrics
+                    .membership_config
+                    .membership()
+                    .voter_ids()
+                    .filter(|id| *id != metrics.id)
+                    .all(|id| {
+                        replication
+                            .get(&id)
+                            .and_then(|log_id| log_id.as_ref())
+                            .is_some_and(|log_id| log_id.index >= log_index)
+                    })
+            },
+            self.timeout,
+            format!(
+                "replica: {} replicate log {} to all voters",
+                raft.group_id(),
+                log_index
+            ),
+        )
+        .await
+        .map_err(|err| {
+            RaftWriteSnafu {
+                msg: err.to_string(),
+            }
+            .build()
+        })?;
+
+        Ok(())
+    }
+
+    /// Hand the entry to the leader and return once it is appended to the leader's
+    /// local log, without waiting for the entry to be committed. The commit
+    /// continues in background. On a node that is not the leader this falls
+    /// back to `commit_to_raft`, so the caller still gets `RaftForwardToLeader`.
+    async fn append_to_raft(&self, raft: Arc<RaftNode>, data: Vec<u8>) -> CoordinatorResult<()> {
+        let metrics = raft.raft_metrics();
+        if metrics.current_leader != Some(metrics.id) {
+            Self::commit_to_raft(raft, data).await?;
+            return Ok(());
+        }
+
+        // wait for this entry, the log may also grow by the entries of other writers
+        let appended = raft.wait_appended(&data);
+        let mut commit = tokio::spawn({
+            let raft = raft.clone();
+            async move {
+                let (group_id, raft_id) = (raft.group_id(), raft.raft_id());
+                let result = Self::commit_to_raft(raft, data).await;
+                if let Err(err) = &result {
+                    error!(
+                        "commit write to replica: {}, id: {} in background failed: {}",
+                        group_id, raft_id, err
+                    );
+                }
+                result
+            }
+        });
+
+        tokio::select! {
+            result = &mut commit => {
+                result.map_err(|e| CommonSnafu { msg: e.to_string() }.build())??;
+            }
+            result = tokio::time::timeout(self.timeout, appended) => {
+                let index = result
+                    .map_err(|_| {
+                        RaftWriteSnafu {
+                            msg: format!(
+                                "replica: {} append log on leader timeout",
+                                raft.group_id()
+                            ),
+                        }
+                        .build()
+                    })?
+                    .map_err(|_| {
+                        RaftWriteSnafu {
+                            msg: format!(
+                                "replica: {} append log on leader cancelled",
+                                raft.group_id()
+                            ),
+                        }
+                        .build()
+                    })?;
+                debug!("replica: {} appended log index: {}", raft.group_id(), index);
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn write_to_local(&self, replica: &ReplicationSet) -> CoordinatorResult<Vec<u8>> {
         let raft = self
             .raft_manager
@@ -156,6 +327,7 @@ impl TskvRaftWriter {
             .await?;
 
         self.pre_check_write_to_raft(&self.request).await?;
+        self.check_change_log(replica).await?;
         let raft_data = to_prost_bytes(&self.request);
         self.write_to_raft(raft, raft_data).await?;
 
@@ -1,21 +1,40 @@
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::{ready, Stream, StreamExt};
 use models::record_batch_decode;
 use protos::kv_service::BatchBytesResponse;
 use tonic::Streaming;
+use tskv::reader::ScanStats;
 
 use crate::errors::{CoordinatorError, CoordinatorResult};
 
 pub struct TonicRecordBatchDecoder {
     stream: Streaming<BatchBytesResponse>,
+    /// Statistics reported by the remote node, and the time the request was sent
+    scan_stats: Option<(ScanStats, Instant)>,
 }
 
 impl TonicRecordBatchDecoder {
     pub fn new(stream: Streaming<BatchBytesResponse>) -> Self {
-        Self { stream }
+        Self {
+            stream,
+            scan_stats: None,
+        }
+    }
+
+    pub fn with_scan_stats(mut self, scan_stats: ScanStats, start: Instant) -> Self {
+        self.scan_stats = Some((scan_stats, start));
+        self
+    }
+
+    /// Record the time of the request once, when the stream ends, fails or is dropped.
+    fn record_rpc_time(&mut self) {
+        if let Some((scan_stats, start)) = self.scan_stats.take() {
+            scan_stats.rpc_time().add_elapsed(start);
+        }
     }
 }
 
@@ -23,15 +42,37 @@ impl Stream for TonicRecordBatchDecoder {
     type Item = CoordinatorResult<RecordBatch>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match ready!(self.stream.poll_next_unpin(cx)) {
-            Some(Ok(received)) => match record_batch_decode(&received.data) {
-                Ok(batch) => Poll::Ready(Some(Ok(batch))),
-                Err(err) => Poll::Ready(Some(Err(err.into()))),
-            },
-            Some(Err(err)) => Poll::Ready(Some(Err(CoordinatorError::TskvError {
-                source: err.into(),
-            }))),
-            None => Poll::Ready(None),
+        loop {
+            match ready!(self.stream.poll_next_unpin(cx)) {
+                Some(Ok(received)) => {
+                    if let Some(stats) = &received.scan_stats {
+                        if let Some((scan_stats, _)) = &self.scan_stats {
+                            scan_stats.merge_proto(stats);
+                        }
+                        continue;
+                    }
+                    return match record_batch_decode(&received.data) {
+                        Ok(batch) => Poll::Ready(Some(Ok(batch))),
+                        Err(err) => Poll::Ready(Some(Err(err.into()))),
+                    };
+                }
+                Some(Err(err)) => {
+                    self.record_rpc_time();
+                    return Poll::Ready(Some(Err(CoordinatorError::TskvError {
+                        source: err.into(),
+                    })));
+                }
+                None => {
+                    self.record_rpc_time();
+                    return Poll::Ready(None);
+                }
+            }
         }
     }
 }
+
+impl Drop for TonicRecordBatchDecoder {
+    fn drop(&mut self) {
+        self.record_rpc_time();
+    }
+}
@@ -89,6 +89,10 @@ impl<O: VnodeOpener> CheckedCoordinatorRecordBatchStream<O> {
                 }
                 StreamState::Idle => {
                     // TODO record time used
+                    self.option
+                        .scan_stats
+                        .vnode_id()
+                        .set(self.vnode.id as usize);
                     let future = match self.opener.open(&self.vnode, &self.option) {
                         Ok(future) => future,
                         Err(err) => return Poll::Ready(Some(Err(err))),
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use config::tskv::QueryConfig;
 use futures::TryStreamExt;
@@ -82,6 +83,8 @@ impl VnodeOpener for TemporaryTableScanOpener {
                 Ok(Box::pin(stream) as SendableCoordinatorRecordBatchStream)
             } else {
                 // 路由到远程的引擎
+                let start = Instant::now();
+                let scan_stats = option.scan_stats.clone();
                 let mut request = {
                     let vnode_ids = vec![vnode_id];
                     let req = option
@@ -114,8 +117,9 @@ impl VnodeOpener for TemporaryTableScanOpener {
                     client.query_record_batch(request).await?.into_inner()
                 };
 
-                Ok(Box::pin(TonicRecordBatchDecoder::new(resp_stream))
-                    as SendableCoordinatorRecordBatchStream)
+                Ok(Box::pin(
+                    TonicRecordBatchDecoder::new(resp_stream).with_scan_stats(scan_stats, start),
+                ) as SendableCoordinatorRecordBatchStream)
             }
         };
 
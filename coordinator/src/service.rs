@@ -1,8 +1,9 @@
 #![allow(clippy::type_complexity)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::future::Future;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
@@ -25,12 +26,15 @@ use metrics::count::U64Counter;
 use metrics::label::Labels;
 use metrics::metric::Metric;
 use metrics::metric_register::MetricsRegister;
+use models::consistency_level::ConsistencyLevel;
+use models::follower_read::FollowerReadLag;
 use models::meta_data::{
-    ExpiredBucketInfo, NodeId, ReplicationSet, ReplicationSetId, VnodeId, VnodeStatus,
+    ExpiredBucketInfo, NodeId, ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo, VnodeStatus,
 };
 use models::object_reference::ResolvedTable;
 use models::oid::Identifier;
 use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef, TimeRange, TimeRanges};
+use models::schema::database_schema::DatabaseSchema;
 use models::schema::resource_info::{ResourceInfo, ResourceOperator};
 use models::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
 use models::schema::{DEFAULT_CATALOG, TIME_FIELD_NAME, USAGE_SCHEMA};
@@ -42,21 +46,27 @@ use protocol_parser::lines_convert::{
 use protocol_parser::Line;
 use protos::kv_service::admin_command::Command::*;
 use protos::kv_service::*;
+use protos::models_helper::parse_prost_bytes;
+use rand::seq::SliceRandom;
 use replication::multi_raft::MultiRaft;
 use snafu::{IntoError, OptionExt, ResultExt};
 use tokio::runtime::Runtime;
 use trace::span_ext::SpanExt;
-use trace::{debug, error, info, Span, SpanContext};
+use trace::{debug, error, info, warn, Span, SpanContext};
 use tskv::EngineRef;
 use utils::precision::{timestamp_convert, Precision};
 use utils::BkdrHasher;
 
+use crate::change_feed::{ChangeFeedPosition, ChangeFeedRecord};
+use crate::cross_cluster::change_log::ChangeLogSlice;
+use crate::cross_cluster::{points_to_lines, ChangeLogShipper};
 use crate::errors::{
     ArrowSnafu, BincodeSerdeSnafu, ColumnNotFoundSnafu, CommonSnafu, CoordinatorError,
-    CoordinatorResult, FieldsIsEmptySnafu, MetaSnafu,
+    CoordinatorResult, FieldsIsEmptySnafu, InvalidFlatbufferSnafu, MetaSnafu, TskvSnafu,
 };
+use crate::hinted_handoff::HintedHandoffQueue;
 use crate::metrics::LPReporter;
-use crate::raft::manager::RaftNodesManager;
+use crate::raft::manager::{RaftNodesManager, ReplicaProgress};
 use crate::raft::writer::TskvRaftWriter;
 use crate::reader::table_scan::opener::TemporaryTableScanOpener;
 use crate::reader::tag_scan::opener::TemporaryTagScanOpener;
@@ -70,6 +80,9 @@ use crate::{
 
 pub type CoordinatorRef = Arc<dyn Coordinator>;
 
+/// Times a hinted write is replayed before it is moved aside.
+const MAX_HINT_ATTEMPTS: usize = 10;
+
 #[derive(Clone)]
 pub struct CoordService {
     node_id: u64,
@@ -82,6 +95,7 @@ pub struct CoordService {
     memory_pool: MemoryPoolRef,
     metrics: Arc<CoordServiceMetrics>,
     raft_manager: Arc<RaftNodesManager>,
+    hinted_handoff: Arc<HintedHandoffQueue>,
 }
 
 #[derive(Debug)]
@@ -98,6 +112,8 @@ pub struct CoordServiceMetrics {
     write_lines_prepare: Metric<U64Average>,
     write_batch_prepare: Metric<U64Average>,
     write_replica_duration: Metric<U64Average>,
+    write_hinted_handoff: Metric<U64Counter>,
+    write_hinted_handoff_failed: Metric<U64Counter>,
 }
 
 macro_rules! generate_coord_metrics_gets {
@@ -119,6 +135,8 @@ generate_coord_metrics_gets!(sql_points_data_in, U64Counter);
 generate_coord_metrics_gets!(write_lines_prepare, U64Average);
 generate_coord_metrics_gets!(write_batch_prepare, U64Average);
 generate_coord_metrics_gets!(write_replica_duration, U64Average);
+generate_coord_metrics_gets!(write_hinted_handoff, U64Counter);
+generate_coord_metrics_gets!(write_hinted_handoff_failed, U64Counter);
 
 impl CoordServiceMetrics {
     pub fn new(register: &MetricsRegister) -> Self {
@@ -135,6 +153,14 @@ impl CoordServiceMetrics {
         let write_batch_prepare = register.metric("write_batch_prepare", "write batch prepare");
         let write_replica_duration =
             register.metric("write_replica_duration", "write replica duration");
+        let write_hinted_handoff = register.metric(
+            "write_hinted_handoff",
+            "writes buffered by hinted handoff while the leader is unreachable",
+        );
+        let write_hinted_handoff_failed = register.metric(
+            "write_hinted_handoff_failed",
+            "hinted writes given up after failing to replay",
+        );
 
         Self {
             coord_data_in,
@@ -148,6 +174,8 @@ impl CoordServiceMetrics {
             write_lines_prepare,
             write_batch_prepare,
             write_replica_duration,
+            write_hinted_handoff,
+            write_hinted_handoff_failed,
         }
     }
 
@@ -174,6 +202,12 @@ impl CoordService {
         RaftNodesManager::start_all_raft_node(runtime.clone(), raft_manager.clone())
             .await
             .unwrap();
+        let hinted_handoff = HintedHandoffQueue::open(
+            PathBuf::from(config.storage.path.clone()).join("hinted-handoff"),
+            config.cluster.hinted_handoff_max_entries,
+        )
+        .await
+        .unwrap();
 
         tokio::spawn(MultiRaft::raft_nodes_manager(
             raft_manager.multi_raft(),
@@ -190,9 +224,20 @@ impl CoordService {
             node_id: config.global.node_id,
             metrics: Arc::new(CoordServiceMetrics::new(metrics_register.as_ref())),
             writer_count: Arc::new(AtomicUsize::new(0)),
+            hinted_handoff: Arc::new(hinted_handoff),
         });
 
         tokio::spawn(CoordService::db_ttl_service(coord.clone()));
+        tokio::spawn(CoordService::hinted_handoff_service(coord.clone()));
+        tokio::spawn(
+            ChangeLogShipper::new(
+                meta.clone(),
+                config.clone(),
+                coord.raft_manager.clone(),
+                metrics_register.clone(),
+            )
+            .run(),
+        );
 
         if config.global.pre_create_bucket {
             tokio::spawn(CoordService::pre_create_bucket_service(coord.clone()));
@@ -222,6 +267,77 @@ impl CoordService {
         }
     }
 
+    /// Replay the writes accepted with `ConsistencyLevel::Any` while their
+    /// leader was unreachable, keeping the arrival order. A write failing for
+    /// another reason is retried, it is moved aside after `MAX_HINT_ATTEMPTS`.
+    async fn hinted_handoff_service(coord: Arc<CoordService>) {
+        let interval = coord.config.cluster.hinted_handoff_retry_interval;
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let mut replayed = 0;
+            while let Some(hint) = coord.hinted_handoff.front().await {
+                let result = coord
+                    .write_replica(
+                        hint.replica.clone(),
+                        hint.request.clone(),
+                        ConsistencyLevel::Quorum,
+                        None,
+                    )
+                    .await;
+                match result {
+                    Ok(()) => {
+                        if let Err(err) = coord.hinted_handoff.pop_front().await {
+                            error!("remove replayed hinted write failed: {}", err);
+                            break;
+                        }
+                        replayed += 1;
+                    }
+                    Err(err) if is_leader_unreachable(&err) => {
+                        debug!("replay hinted write failed, retry later: {}", err);
+                        break;
+                    }
+                    Err(err) => {
+                        let attempts = coord.hinted_handoff.fail_front().await;
+                        if attempts < MAX_HINT_ATTEMPTS {
+                            warn!(
+                                "replay hinted write to replica {} failed {} times, retry later: {}",
+                                hint.request.replica_id, attempts, err
+                            );
+                            break;
+                        }
+
+                        coord
+                            .metrics
+                            .write_hinted_handoff_failed(
+                                &hint.request.tenant,
+                                &hint.request.db_name,
+                            )
+                            .inc_one();
+                        match coord.hinted_handoff.discard_front().await {
+                            Ok(path) => error!(
+                                "replay hinted write to replica {} failed {} times, give up and keep it in {:?}: {}",
+                                hint.request.replica_id, attempts, path, err
+                            ),
+                            Err(io_err) => {
+                                error!("move failed hinted write aside failed: {}", io_err);
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if replayed > 0 {
+                info!(
+                    "hinted handoff replayed {} writes, {} pending",
+                    replayed,
+                    coord.hinted_handoff.len().await
+                );
+            }
+        }
+    }
+
     async fn pre_create_bucket_service(coord: Arc<CoordService>) {
         loop {
             let interval = 5 * 60;
@@ -272,7 +388,14 @@ impl CoordService {
 
             let lines = lines_buffer.iter().map(|l| l.to_line()).collect::<Vec<_>>();
             if let Err(e) = coord
-                .write_lines(DEFAULT_CATALOG, USAGE_SCHEMA, Precision::NS, lines, None)
+                .write_lines(
+                    DEFAULT_CATALOG,
+                    USAGE_SCHEMA,
+                    Precision::NS,
+                    lines,
+                    ConsistencyLevel::default(),
+                    None,
+                )
                 .await
             {
                 error!("write metrics to {DEFAULT_CATALOG} fail. {e}")
@@ -341,6 +464,127 @@ impl CoordService {
         Box::pin(checker)
     }
 
+    /// Get the followers of the replication set that are within `max_lag` of
+    /// the leader, nothing if the lag can't be fetched from the leader. The lag
+    /// is measured on the leader from the entries replicated to a follower, the
+    /// follower is only readable once it has applied them.
+    async fn readable_followers(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        replica: &ReplicationSet,
+        max_lag: FollowerReadLag,
+    ) -> HashSet<VnodeId> {
+        let progresses = futures::future::join_all(
+            replica
+                .vnodes
+                .iter()
+                .map(|vnode| self.replica_progress(tenant, db_name, replica.id, vnode.node_id)),
+        )
+        .await;
+
+        let mut applied = HashMap::new();
+        let mut lags = None;
+        for progress in progresses {
+            match progress {
+                Ok(progress) => {
+                    if progress.vnode_id == replica.leader_vnode_id {
+                        lags = progress.followers_lag;
+                    }
+                    applied.insert(progress.vnode_id, progress.applied);
+                }
+                Err(err) => debug!(
+                    "fetch raft progress of replica {} failed: {}",
+                    replica.id, err
+                ),
+            }
+        }
+
+        let Some(lags) = lags else {
+            debug!(
+                "fetch follower lag of replica {} failed, read from leader",
+                replica.id
+            );
+            return HashSet::new();
+        };
+
+        lags.into_iter()
+            .filter(|(vnode_id, lag)| {
+                max_lag.allows(lag.entries, lag.millis)
+                    && applied
+                        .get(vnode_id)
+                        .is_some_and(|applied| *applied >= lag.matched)
+            })
+            .map(|(vnode_id, _)| vnode_id)
+            .collect()
+    }
+
+    /// Get the raft progress of the vnode of the replication set on a node.
+    async fn replica_progress(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        replica_id: ReplicationSetId,
+        node_id: NodeId,
+    ) -> CoordinatorResult<ReplicaProgress> {
+        if node_id == self.node_id {
+            return self.raft_manager.replica_progress(replica_id).await;
+        }
+
+        let caller = TskvAdminRequest {
+            request: AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(FetchFollowerLag(FetchFollowerLagRequest {
+                    db_name: db_name.to_string(),
+                    replica_id,
+                })),
+            },
+            meta: self.meta.clone(),
+            timeout: self.config.query.read_timeout,
+            enable_gzip: self.config.service.grpc_enable_gzip,
+        };
+        let data = caller.do_request(node_id).await?;
+
+        bincode::deserialize::<ReplicaProgress>(&data).context(BincodeSerdeSnafu)
+    }
+
+    /// Read the change log of the replication set from a vnode on this node,
+    /// or from the leader vnode if there is none.
+    async fn fetch_change_log(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        replica: &ReplicationSet,
+        after_index: u64,
+        max_bytes: usize,
+    ) -> CoordinatorResult<ChangeLogSlice> {
+        if let Some(vnode) = replica.vnodes.iter().find(|v| v.node_id == self.node_id) {
+            return self
+                .raft_manager
+                .change_logs()
+                .read(vnode.id, after_index, max_bytes)
+                .await;
+        }
+
+        let caller = TskvAdminRequest {
+            request: AdminCommand {
+                tenant: tenant.to_string(),
+                command: Some(FetchChangeLog(FetchChangeLogRequest {
+                    db_name: db_name.to_string(),
+                    vnode_id: replica.leader_vnode_id,
+                    after_index,
+                    max_bytes: max_bytes as u64,
+                })),
+            },
+            meta: self.meta.clone(),
+            timeout: self.config.query.read_timeout,
+            enable_gzip: self.config.service.grpc_enable_gzip,
+        };
+        let data = caller.do_request(replica.leader_node_id).await?;
+
+        bincode::deserialize::<ChangeLogSlice>(&data).context(BincodeSerdeSnafu)
+    }
+
     async fn vnode_checksum_on_node(
         &self,
         tenant: &str,
@@ -368,6 +612,7 @@ impl CoordService {
         precision: Precision,
         info: ReplicationSet,
         points: Arc<Vec<u8>>,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&'a SpanContext>,
     ) -> CoordinatorResult<Vec<impl Future<Output = CoordinatorResult<()>> + Sized + 'a>> {
         {
@@ -408,12 +653,141 @@ impl CoordService {
             command: Some(raft_write_command::Command::WriteData(request)),
         };
 
-        let request = self.write_replica_by_raft(info.clone(), request, span_ctx);
+        let request = self.write_replica(info.clone(), request, consistency, span_ctx);
         requests.push(Box::pin(request));
 
         Ok(requests)
     }
 
+    async fn write_replica(
+        &self,
+        replica: ReplicationSet,
+        request: RaftWriteCommand,
+        consistency: ConsistencyLevel,
+        _span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<()> {
+        let tenant = request.tenant.clone();
+        let writer = self.tskv_raft_writer(request, consistency);
+        let executor = TskvLeaderExecutor {
+            meta: self.meta.clone(),
+        };
+
+        match executor.do_request(&tenant, &replica, &writer).await {
+            Ok(_) => Ok(()),
+            Err(err) if consistency == ConsistencyLevel::Any && is_leader_unreachable(&err) => {
+                debug!(
+                    "leader of replica {} is unreachable, hand off write: {}",
+                    replica.id, err
+                );
+                let request = writer.request.clone();
+                self.metrics
+                    .write_hinted_handoff(&request.tenant, &request.db_name)
+                    .inc_one();
+                self.hinted_handoff.push(replica, request).await
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Write lines of a client, or of the primary cluster when `replicated`
+    /// is set, only a standby database accepts the latter.
+    #[allow(clippy::too_many_arguments)]
+    async fn write_lines_to_replicas<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        lines: Vec<Line<'a>>,
+        consistency: ConsistencyLevel,
+        replicated: bool,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        let pre_write_start = std::time::Instant::now();
+        let mut write_bytes: usize = 0;
+        let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+            CoordinatorError::TenantNotFound {
+                name: tenant.to_string(),
+            }
+        })?;
+        let mut map_lines: HashMap<ReplicationSetId, VnodeLines> = HashMap::new();
+        let db_schema = meta_client
+            .get_db_schema(db)
+            .context(MetaSnafu)?
+            .ok_or_else(|| MetaError::DatabaseNotFound {
+                database: db.to_string(),
+            })
+            .context(MetaSnafu)?;
+        if db_schema.is_hidden() {
+            return Err(CoordinatorError::Meta {
+                source: MetaError::DatabaseNotFound {
+                    database: db.to_string(),
+                },
+            });
+        }
+        check_standby(&db_schema, replicated)?;
+
+        let db_precision = db_schema.config.precision();
+        for line in lines {
+            let ts =
+                timestamp_convert(precision, *db_precision, line.timestamp).ok_or_else(|| {
+                    CommonSnafu {
+                        msg: "timestamp overflow".to_string(),
+                    }
+                    .build()
+                })?;
+            let info = meta_client
+                .locate_replication_set_for_write(db, line.hash_id, ts)
+                .await
+                .context(MetaSnafu)?;
+            let lines_entry = map_lines.entry(info.id).or_insert(VnodeLines::new(info));
+            lines_entry.add_line(line)
+        }
+
+        let mut requests = Vec::new();
+        for lines in map_lines.into_values() {
+            let batches = line_to_batches(&lines.lines).map_err(|e| {
+                CommonSnafu {
+                    msg: format!("line to batch error: {}", e),
+                }
+                .build()
+            })?;
+            let points = Arc::new(mutable_batches_to_point(db, batches));
+            write_bytes += points.len();
+            requests.extend(
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    lines.info,
+                    points,
+                    consistency,
+                    span_ctx,
+                )
+                .await?,
+            );
+        }
+
+        self.metrics
+            .write_lines_prepare(tenant, db)
+            .add(pre_write_start.elapsed().as_millis() as u64);
+
+        let now = tokio::time::Instant::now();
+        for res in futures::future::join_all(requests).await {
+            debug!(
+                "Parallel write points on vnode over, start at: {:?}, elapsed: {} millis, result: {:?}",
+                now,
+                now.elapsed().as_millis(),
+                res
+            );
+            res?
+        }
+        self.metrics
+            .write_replica_duration(tenant, db)
+            .add(now.elapsed().as_millis() as u64);
+
+        Ok(write_bytes)
+    }
+
     async fn admin_command_on_leader(
         &self,
         replica: ReplicationSet,
@@ -496,7 +870,11 @@ impl Coordinator for CoordService {
         self.meta.tenant_meta(tenant).await
     }
 
-    fn tskv_raft_writer(&self, request: RaftWriteCommand) -> TskvRaftWriter {
+    fn tskv_raft_writer(
+        &self,
+        request: RaftWriteCommand,
+        consistency: ConsistencyLevel,
+    ) -> TskvRaftWriter {
         TskvRaftWriter::new(
             self.meta.clone(),
             self.node_id,
@@ -506,6 +884,7 @@ impl Coordinator for CoordService {
             self.memory_pool.clone(),
             self.raft_manager.clone(),
             request,
+            consistency,
             self.writer_count.clone(),
         )
     }
@@ -514,6 +893,7 @@ impl Coordinator for CoordService {
         &self,
         table: &ResolvedTable,
         predicate: ResolvedPredicateRef,
+        follower_read_max_lag: Option<FollowerReadLag>,
     ) -> CoordinatorResult<Vec<ReplicationSet>> {
         // 1. 根据传入的过滤条件获取表的分片信息（包括副本）
         let mut replica_sets = self
@@ -525,10 +905,29 @@ impl Coordinator for CoordService {
             .await?;
 
         // 2. 选择最优的副本
-        for replica_set in replica_sets.iter_mut() {
+        let readable_followers = match follower_read_max_lag {
+            // Fetch the lag of all the replication sets at the same time
+            Some(max_lag) => {
+                futures::future::join_all(replica_sets.iter().map(|replica_set| {
+                    self.readable_followers(table.tenant(), table.database(), replica_set, max_lag)
+                }))
+                .await
+            }
+            None => vec![HashSet::new(); replica_sets.len()],
+        };
+        for (replica_set, readable_followers) in
+            replica_sets.iter_mut().zip(readable_followers.into_iter())
+        {
+            // Followers close enough to the leader are as good as the leader,
+            // shuffle them so that reads are spread over all of them.
+            if !readable_followers.is_empty() {
+                replica_set.vnodes.shuffle(&mut rand::thread_rng());
+            }
+
             replica_set.vnodes.sort_by_key(|vnode| {
                 // The smaller the score, the easier it is to be selected
-                if vnode.id == replica_set.leader_vnode_id {
+                if vnode.id == replica_set.leader_vnode_id || readable_followers.contains(&vnode.id)
+                {
                     0
                 } else {
                     match vnode.status {
@@ -553,35 +952,87 @@ impl Coordinator for CoordService {
         &self,
         replica: ReplicationSet,
         request: RaftWriteCommand,
-        _span_ctx: Option<&SpanContext>,
+        span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<()> {
-        let tenant = request.tenant.clone();
-        let writer = self.tskv_raft_writer(request);
-        let executor = TskvLeaderExecutor {
-            meta: self.meta.clone(),
+        self.write_replica(replica, request, ConsistencyLevel::default(), span_ctx)
+            .await
+    }
+
+    async fn write_replicated(&self, request: RaftWriteCommand) -> CoordinatorResult<()> {
+        let command = match request.command {
+            Some(command) => command,
+            None => return Ok(()),
         };
 
-        executor.do_request(&tenant, &replica, &writer).await?;
+        let (tenant, db) = (request.tenant.as_str(), request.db_name.as_str());
+        match command {
+            raft_write_command::Command::WriteData(cmd) => {
+                let fb_points = flatbuffers::root::<protos::models::Points>(&cmd.data)
+                    .context(InvalidFlatbufferSnafu)?;
+                let lines = points_to_lines(&fb_points)?;
+                let precision = Precision::from(cmd.precision as u8);
+                self.write_lines_to_replicas(
+                    tenant,
+                    db,
+                    precision,
+                    lines,
+                    ConsistencyLevel::default(),
+                    true,
+                    None,
+                )
+                .await?;
+            }
+
+            // Other commands apply to the whole database, send them to every replication set.
+            command => {
+                let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
+                    CoordinatorError::TenantNotFound {
+                        name: tenant.to_string(),
+                    }
+                })?;
+                let db_schema = meta_client
+                    .get_db_schema(db)
+                    .context(MetaSnafu)?
+                    .ok_or_else(|| MetaError::DatabaseNotFound {
+                        database: db.to_string(),
+                    })
+                    .context(MetaSnafu)?;
+                check_standby(&db_schema, true)?;
+
+                let buckets = meta_client
+                    .mapping_bucket(db, i64::MIN, i64::MAX)
+                    .context(MetaSnafu)?;
+                for replica in buckets.into_iter().flat_map(|b| b.shard_group) {
+                    let mut command = command.clone();
+                    if let raft_write_command::Command::DeleteFromTable(cmd) = &mut command {
+                        cmd.vnode_id = replica.leader_vnode_id;
+                    }
+                    let request = RaftWriteCommand {
+                        replica_id: replica.id,
+                        tenant: tenant.to_string(),
+                        db_name: db.to_string(),
+                        command: Some(command),
+                    };
+                    self.write_replica_by_raft(replica, request, None).await?;
+                }
+            }
+        }
 
         Ok(())
     }
 
-    async fn write_lines<'a>(
+    async fn read_change_feed(
         &self,
         tenant: &str,
         db: &str,
-        precision: Precision,
-        lines: Vec<Line<'a>>,
-        span_ctx: Option<&SpanContext>,
-    ) -> CoordinatorResult<usize> {
-        let pre_write_start = std::time::Instant::now();
-        let mut write_bytes: usize = 0;
+        position: &ChangeFeedPosition,
+        max_bytes: usize,
+    ) -> CoordinatorResult<Vec<ChangeFeedRecord>> {
         let meta_client = self.meta.tenant_meta(tenant).await.ok_or_else(|| {
             CoordinatorError::TenantNotFound {
                 name: tenant.to_string(),
             }
         })?;
-        let mut map_lines: HashMap<ReplicationSetId, VnodeLines> = HashMap::new();
         let db_schema = meta_client
             .get_db_schema(db)
             .context(MetaSnafu)?
@@ -589,66 +1040,64 @@ impl Coordinator for CoordService {
                 database: db.to_string(),
             })
             .context(MetaSnafu)?;
-        if db_schema.is_hidden() {
-            return Err(CoordinatorError::Meta {
-                source: MetaError::DatabaseNotFound {
-                    database: db.to_string(),
-                },
+        if !db_schema.options().change_feed() {
+            return Err(CoordinatorError::ChangeFeedNotEnabled {
+                database: db.to_string(),
             });
         }
 
-        let db_precision = db_schema.config.precision();
-        for line in lines {
-            let ts =
-                timestamp_convert(precision, *db_precision, line.timestamp).ok_or_else(|| {
+        let buckets = meta_client
+            .mapping_bucket(db, i64::MIN, i64::MAX)
+            .context(MetaSnafu)?;
+        let mut records = Vec::new();
+        for replica in buckets.into_iter().flat_map(|b| b.shard_group) {
+            let after = position.get(replica.id);
+            let slice = self
+                .fetch_change_log(tenant, db, &replica, after.unwrap_or(0), max_bytes)
+                .await?;
+            // The raft log indexes of the entries are not contiguous, an entry
+            // refers to the one appended before it, any other than the position
+            // means the entries in between are gone.
+            if let (Some(after), Some(first)) = (after, slice.entries.first()) {
+                if first.prev_index != after {
+                    return Err(CoordinatorError::ChangeFeedPositionExpired {
+                        replica_id: replica.id,
+                        position: after,
+                        first_index: slice.first_index.unwrap_or(first.index),
+                    });
+                }
+            }
+
+            for entry in slice.entries {
+                let command = parse_prost_bytes::<RaftWriteCommand>(&entry.data).map_err(|e| {
                     CommonSnafu {
-                        msg: "timestamp overflow".to_string(),
+                        msg: format!("decode change log entry {} failed: {}", entry.index, e),
                     }
                     .build()
                 })?;
-            let info = meta_client
-                .locate_replication_set_for_write(db, line.hash_id, ts)
-                .await
-                .context(MetaSnafu)?;
-            let lines_entry = map_lines.entry(info.id).or_insert(VnodeLines::new(info));
-            lines_entry.add_line(line)
-        }
-
-        let mut requests = Vec::new();
-        for lines in map_lines.into_values() {
-            let batches = line_to_batches(&lines.lines).map_err(|e| {
-                CommonSnafu {
-                    msg: format!("line to batch error: {}", e),
-                }
-                .build()
-            })?;
-            let points = Arc::new(mutable_batches_to_point(db, batches));
-            write_bytes += points.len();
-            requests.extend(
-                self.push_points_to_requests(tenant, db, precision, lines.info, points, span_ctx)
-                    .await?,
-            );
+                records.push(ChangeFeedRecord {
+                    replica_id: replica.id,
+                    index: entry.index,
+                    apply_time: entry.apply_time,
+                    command,
+                });
+            }
         }
 
-        self.metrics
-            .write_lines_prepare(tenant, db)
-            .add(pre_write_start.elapsed().as_millis() as u64);
-
-        let now = tokio::time::Instant::now();
-        for res in futures::future::join_all(requests).await {
-            debug!(
-                "Parallel write points on vnode over, start at: {:?}, elapsed: {} millis, result: {:?}",
-                now,
-                now.elapsed().as_millis(),
-                res
-            );
-            res?
-        }
-        self.metrics
-            .write_replica_duration(tenant, db)
-            .add(now.elapsed().as_millis() as u64);
+        Ok(records)
+    }
 
-        Ok(write_bytes)
+    async fn write_lines<'a>(
+        &self,
+        tenant: &str,
+        db: &str,
+        precision: Precision,
+        lines: Vec<Line<'a>>,
+        consistency: ConsistencyLevel,
+        span_ctx: Option<&SpanContext>,
+    ) -> CoordinatorResult<usize> {
+        self.write_lines_to_replicas(tenant, db, precision, lines, consistency, false, span_ctx)
+            .await
     }
 
     async fn write_record_batch<'a>(
@@ -656,6 +1105,7 @@ impl Coordinator for CoordService {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         let pre_write_start = std::time::Instant::now();
@@ -670,6 +1120,10 @@ impl Coordinator for CoordService {
             }
         })?;
 
+        if let Some(db_schema) = meta_client.get_db_schema(db).context(MetaSnafu)? {
+            check_standby(&db_schema, false)?;
+        }
+
         let mut repl_idx: HashMap<ReplicationSet, Vec<u32>> = HashMap::new();
         let schema = record_batch.schema().fields.clone();
         let table_name = table_schema.name.as_str();
@@ -772,8 +1226,16 @@ impl Coordinator for CoordService {
             );
             write_bytes += points.len();
             requests.extend(
-                self.push_points_to_requests(tenant, db, precision, repl, points, span_ctx)
-                    .await?,
+                self.push_points_to_requests(
+                    tenant,
+                    db,
+                    precision,
+                    repl,
+                    points,
+                    consistency,
+                    span_ctx,
+                )
+                .await?,
             );
         }
         self.metrics
@@ -1005,6 +1467,57 @@ impl Coordinator for CoordService {
         return Ok(());
     }
 
+    async fn vnode_data_versions(
+        &self,
+        tenant: &str,
+        vnodes: &[VnodeInfo],
+    ) -> CoordinatorResult<HashMap<VnodeId, Option<u64>>> {
+        // Group vnode ids by node id.
+        let mut node_vnode_ids_map: HashMap<NodeId, Vec<VnodeId>> = HashMap::new();
+        for vnode in vnodes {
+            node_vnode_ids_map
+                .entry(vnode.node_id)
+                .or_default()
+                .push(vnode.id);
+        }
+
+        let mut versions = HashMap::with_capacity(vnodes.len());
+        let mut req_futures = vec![];
+        for (node_id, vnode_ids) in node_vnode_ids_map {
+            if node_id == self.node_id {
+                if let Some(kv_inst) = &self.kv_inst {
+                    for vnode_id in vnode_ids {
+                        let version = kv_inst
+                            .get_vnode_data_version(vnode_id)
+                            .await
+                            .context(TskvSnafu)?;
+                        versions.insert(vnode_id, version);
+                    }
+                    continue;
+                }
+            }
+
+            let caller = TskvAdminRequest {
+                request: AdminCommand {
+                    tenant: tenant.to_string(),
+                    command: Some(FetchVnodeVersion(FetchVnodeVersionRequest { vnode_ids })),
+                },
+                meta: self.meta.clone(),
+                timeout: self.config.query.read_timeout,
+                enable_gzip: self.config.service.grpc_enable_gzip,
+            };
+            req_futures.push(async move { caller.do_request(node_id).await });
+        }
+
+        for res in futures::future::join_all(req_futures).await {
+            let node_versions = bincode::deserialize::<Vec<(VnodeId, Option<u64>)>>(&res?)
+                .context(BincodeSerdeSnafu)?;
+            versions.extend(node_versions);
+        }
+
+        Ok(versions)
+    }
+
     async fn replica_checksum(
         &self,
         tenant: &str,
@@ -1206,6 +1719,26 @@ impl<'a> VnodeLines<'a> {
     }
 }
 
+/// Whether the error means no node of the replication set could be reached,
+/// in which case a write with `ConsistencyLevel::Any` is handed off.
+fn is_leader_unreachable(err: &CoordinatorError) -> bool {
+    matches!(
+        err,
+        CoordinatorError::PreExecution { .. } | CoordinatorError::NoValidReplica { .. }
+    )
+}
+
+/// A standby database only accepts writes replicated from the primary cluster,
+/// other databases only accept writes of clients.
+fn check_standby(db_schema: &DatabaseSchema, replicated: bool) -> CoordinatorResult<()> {
+    let database = db_schema.database_name().to_string();
+    match (db_schema.options().standby(), replicated) {
+        (true, false) => Err(CoordinatorError::DatabaseIsStandby { database }),
+        (false, true) => Err(CoordinatorError::DatabaseNotStandby { database }),
+        _ => Ok(()),
+    }
+}
+
 fn get_precision_and_value_from_arrow_column(
     column: &ArrayRef,
     idx: usize,
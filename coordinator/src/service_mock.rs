@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
@@ -10,6 +11,8 @@ use datafusion::arrow::record_batch::RecordBatch;
 use meta::model::meta_admin::AdminMeta;
 use meta::model::meta_tenant::TenantMeta;
 use meta::model::{MetaClientRef, MetaRef};
+use models::consistency_level::ConsistencyLevel;
+use models::follower_read::FollowerReadLag;
 use models::meta_data::{ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo, VnodeStatus};
 use models::object_reference::ResolvedTable;
 use models::predicate::domain::{ResolvedPredicate, ResolvedPredicateRef};
@@ -22,6 +25,7 @@ use tskv::reader::QueryOption;
 use tskv::EngineRef;
 use utils::precision::Precision;
 
+use crate::change_feed::{ChangeFeedPosition, ChangeFeedRecord};
 use crate::errors::CoordinatorResult;
 use crate::raft::manager::RaftNodesManager;
 use crate::raft::writer::TskvRaftWriter;
@@ -59,7 +63,19 @@ impl Coordinator for MockCoordinator {
         todo!()
     }
 
-    fn tskv_raft_writer(&self, request: RaftWriteCommand) -> TskvRaftWriter {
+    async fn vnode_data_versions(
+        &self,
+        tenant: &str,
+        vnodes: &[VnodeInfo],
+    ) -> CoordinatorResult<HashMap<VnodeId, Option<u64>>> {
+        Ok(HashMap::new())
+    }
+
+    fn tskv_raft_writer(
+        &self,
+        request: RaftWriteCommand,
+        consistency: ConsistencyLevel,
+    ) -> TskvRaftWriter {
         todo!()
     }
 
@@ -76,6 +92,7 @@ impl Coordinator for MockCoordinator {
         &self,
         table: &ResolvedTable,
         _predicate: ResolvedPredicateRef,
+        _follower_read_max_lag: Option<FollowerReadLag>,
     ) -> CoordinatorResult<Vec<ReplicationSet>> {
         if table.database() == WITH_NONEMPTY_DATABASE_FOR_TEST {
             return Ok(vec![
@@ -164,12 +181,27 @@ impl Coordinator for MockCoordinator {
         Ok(vec![])
     }
 
+    async fn write_replicated(&self, request: RaftWriteCommand) -> CoordinatorResult<()> {
+        todo!()
+    }
+
+    async fn read_change_feed(
+        &self,
+        tenant: &str,
+        db: &str,
+        position: &ChangeFeedPosition,
+        max_bytes: usize,
+    ) -> CoordinatorResult<Vec<ChangeFeedRecord>> {
+        todo!()
+    }
+
     async fn write_lines<'a>(
         &self,
         tenant: &str,
         db: &str,
         precision: Precision,
         line: Vec<Line<'a>>,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         todo!()
@@ -180,6 +212,7 @@ impl Coordinator for MockCoordinator {
         table_schema: TskvTableSchemaRef,
         record_batch: RecordBatch,
         db_precision: Precision,
+        consistency: ConsistencyLevel,
         span_ctx: Option<&SpanContext>,
     ) -> CoordinatorResult<usize> {
         todo!()
@@ -30,8 +30,9 @@ impl CallHeaderAuthenticator for BasicCallHeaderAuthenticator {
         let authorization = utils::get_value_from_auth_header(req_headers, "")
             .ok_or_else(|| Status::unauthenticated("authorization field not present"))?;
         let private_key = utils::get_value_from_header(req_headers, PRIVATE_KEY, "");
+        let tenant = utils::get_value_from_header(req_headers, header::TENANT, "");
 
-        let user_info = Header::with_private_key(
+        let header = Header::with_private_key(
             None,
             None,
             None,
@@ -40,11 +41,38 @@ impl CallHeaderAuthenticator for BasicCallHeaderAuthenticator {
             None,
             None,
             None,
-        )
-        .try_get_basic_auth()
-        .map_err(|e| Status::invalid_argument(e.to_string()))?;
-
-        let tenant = utils::get_value_from_header(req_headers, header::TENANT, "");
+        );
+
+        if let Some(token) = header.get_api_token() {
+            let user = self
+                .instance
+                .authenticate_token(token, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
+                .await
+                .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+            debug!(
+                "authenticate success, token of user: {}",
+                user.desc().name()
+            );
+
+            return Ok(CommonAuthResult { user });
+        }
+
+        if let Some(token) = header.get_jwt() {
+            let user = self
+                .instance
+                .authenticate_jwt(token, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
+                .await
+                .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+            debug!("authenticate success, JWT of user: {}", user.desc().name());
+
+            return Ok(CommonAuthResult { user });
+        }
+
+        let user_info = header
+            .try_get_basic_auth()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
 
         let user = self
             .instance
@@ -1,6 +1,8 @@
 use std::time::Duration;
 
 use http_protocol::header::BEARER_PREFIX;
+use models::auth::jwt::is_jwt;
+use models::auth::token::TOKEN_PREFIX;
 use models::auth::user::User;
 use models::oid::UuidGenerator;
 use moka::sync::Cache;
@@ -55,6 +57,16 @@ where
 
         // Check if headers contain a bearer token and if so, validate the token.
         if let Some(bearer_token) = utils::get_value_from_auth_header(req_headers, BEARER_PREFIX) {
+            // API tokens and JWTs are checked by the initial_authenticator on every call,
+            // no bearer token is generated for them.
+            if bearer_token.starts_with(TOKEN_PREFIX) || is_jwt(&bearer_token) {
+                let auth_result = self.initial_authenticator.authenticate(req_headers).await?;
+                return Ok(GeneratedBearerTokenAuthResult {
+                    user: auth_result.identity(),
+                    bearer_token: None,
+                });
+            }
+
             // get user_info from cache by token
             let user = self
                 .bearer_to_identifier
@@ -178,4 +190,24 @@ mod test {
 
         assert!(utils::get_value_from_auth_header(&req_headers, BEARER_PREFIX).is_some());
     }
+
+    #[tokio::test]
+    async fn test_api_token() {
+        let authenticator = GeneratedBearerTokenAuthenticator::new(CallHeaderAuthenticatorMock {});
+
+        let mut req_headers = MetadataMap::default();
+        let val = AsciiMetadataValue::from_static("Bearer cnos_1_secret");
+        req_headers.insert(AUTHORIZATION.as_str(), val);
+
+        let mut resp_headers = MetadataMap::default();
+        authenticator
+            .authenticate(&req_headers)
+            .await
+            .expect("authenticate")
+            .append_to_outgoing_headers(&mut resp_headers)
+            .expect("append_to_outgoing_headers");
+
+        // the API token is used as it is, no bearer token is generated
+        assert!(resp_headers.is_empty());
+    }
 }
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
 use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::sql::metadata::{SqlInfoData, SqlInfoDataBuilder};
 use arrow_flight::sql::server::FlightSqlService;
 use arrow_flight::sql::{
     ActionBeginSavepointRequest, ActionBeginSavepointResult, ActionBeginTransactionRequest,
@@ -20,11 +22,25 @@ use arrow_flight::{
     utils as flight_utils, Action, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
     HandshakeRequest, HandshakeResponse, IpcMessage, Ticket,
 };
-use datafusion::arrow::datatypes::{Schema, SchemaRef, ToByteSlice};
+use coordinator::service::CoordinatorRef;
+use datafusion::arrow::array::{Array, ArrayRef, BinaryArray, StringArray};
+use datafusion::arrow::buffer::Buffer;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, ToByteSlice};
+use datafusion::arrow::ipc::{self, MessageHeader};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::scalar::ScalarValue;
 use futures::Stream;
-use http_protocol::header::{DB, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT};
+use http_protocol::header::{
+    CONSISTENCY, DB, FOLLOWER_READ_MAX_LAG, STREAM_TRIGGER_INTERVAL, TARGET_PARTITIONS, TENANT,
+};
+use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
 use models::auth::user::User;
+use models::consistency_level::ConsistencyLevel;
+use models::follower_read::FollowerReadLag;
 use models::oid::UuidGenerator;
+use models::schema::tskv_table_schema::{ColumnType, TskvTableSchema};
+use models::schema::DEFAULT_CATALOG;
 use moka::sync::Cache;
 use prost::bytes::Bytes;
 use prost::Message;
@@ -39,32 +55,66 @@ use trace::span_ext::SpanExt;
 use trace::{debug, Span, SpanContext};
 
 use super::auth_middleware::CallHeaderAuthenticator;
+use super::ingest::CommandStatementIngest;
 use crate::flight_sql::auth_middleware::AuthResult;
 use crate::flight_sql::utils;
-use crate::status;
+use crate::{status, VERSION};
 
 const UNKNOWN_AFFECTED_ROWS_COUNT: i64 = -1;
 
+const CATALOGS_SQL: &str = "SELECT
+        TENANT_NAME AS CATALOG_NAME
+    FROM
+        CLUSTER_SCHEMA.TENANTS
+    ORDER BY
+        CATALOG_NAME";
+
+const TABLE_TYPES_SQL: &str = "SELECT TABLE_TYPE
+    FROM
+        (VALUES('TABLE'),('VIEW'),('LOCAL TEMPORARY')) t(TABLE_TYPE)";
+
+/// Column appended to the result of [`CommandGetTables`] when `include_schema` is set.
+const TABLE_SCHEMA_COL_NAME: &str = "table_schema";
+/// A prepared statement is released once it is not used for this long, if the
+/// client never closes it.
+const PREPARED_STATEMENT_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
 pub struct FlightSqlServiceImpl<T> {
     instance: DBMSRef,
+    coord: CoordinatorRef,
     authenticator: T,
     id_generator: UuidGenerator,
     result_cache: Cache<Vec<u8>, (Option<Plan>, QueryStateMachineRef)>,
+    /// Plans of the prepared statements, each execution runs as a new query.
+    prepared_statements: Cache<Vec<u8>, (Option<Plan>, QueryStateMachineRef)>,
+    /// Parameter values bound to prepared statements by [`FlightSqlService::do_put_prepared_statement_query`]
+    prepared_params: Cache<Vec<u8>, Vec<ScalarValue>>,
+    sql_info: SqlInfoData,
 }
 
 impl<T> FlightSqlServiceImpl<T> {
-    pub fn new(instance: DBMSRef, authenticator: T) -> Self {
+    pub fn new(instance: DBMSRef, coord: CoordinatorRef, authenticator: T) -> Self {
         let result_cache = Cache::builder()
             // Time to live (TTL): 2 minutes
             // The query results are only cached for 2 minutes and expire after 2 minutes
             .time_to_live(Duration::from_secs(2 * 60))
             .build();
+        let prepared_statements = Cache::builder()
+            .time_to_idle(PREPARED_STATEMENT_IDLE_TIMEOUT)
+            .build();
+        let prepared_params = Cache::builder()
+            .time_to_idle(PREPARED_STATEMENT_IDLE_TIMEOUT)
+            .build();
 
         Self {
             instance,
+            coord,
             authenticator,
             id_generator: Default::default(),
             result_cache,
+            prepared_statements,
+            prepared_params,
+            sql_info: build_sql_info(),
         }
     }
 }
@@ -201,11 +251,30 @@ where
                         STREAM_TRIGGER_INTERVAL, e
                     ))
                 })?;
+        let consistency = utils::get_value_from_header(metadata, CONSISTENCY, "")
+            .map(|e| e.parse::<ConsistencyLevel>())
+            .transpose()
+            .map_err(|e| {
+                Status::invalid_argument(format!("parse {} failed, error: {}", CONSISTENCY, e))
+            })?;
+        let follower_read_max_lag =
+            utils::get_value_from_header(metadata, FOLLOWER_READ_MAX_LAG, "")
+                .map(|e| e.parse::<FollowerReadLag>())
+                .transpose()
+                .map_err(|e| {
+                    Status::invalid_argument(format!(
+                        "parse {} failed, error: {}",
+                        FOLLOWER_READ_MAX_LAG, e
+                    ))
+                })?;
         let ctx = ContextBuilder::new(user)
             .with_tenant(tenant)
             .with_database(db)
             .with_target_partitions(target_partitions)
             .with_stream_trigger_interval(stream_trigger_interval)
+            .with_write_consistency(consistency)
+            .with_follower_read_max_lag(follower_read_max_lag)
+            .with_client_addr(utils::get_client_addr(metadata))
             .build();
 
         Ok(ctx)
@@ -276,33 +345,288 @@ where
         Ok((logical_plan, query_state_machine))
     }
 
-    async fn execute_and_fetch_result_set(
+    fn get_prepared_statement(
         &self,
         statement_handle: &[u8],
-        span_ctx: Option<&SpanContext>,
-    ) -> Result<<Self as FlightService>::DoGetStream, Status> {
-        let (logical_plan, query_state_machine) =
-            self.get_plan_and_qsm(statement_handle, span_ctx.cloned())?;
+    ) -> Result<(Option<Plan>, QueryStateMachineRef), Status> {
+        self.prepared_statements
+            .get(statement_handle)
+            .ok_or_else(|| {
+                Status::internal(format!(
+                    "The prepared statement({:?}) does not exist or has been closed",
+                    statement_handle
+                ))
+            })
+    }
+
+    /// Get the plan of a prepared statement with the parameters previously put
+    /// by the client bound to its placeholders, and a new query to execute it.
+    async fn get_prepared_plan_and_qsm(
+        &self,
+        statement_handle: &[u8],
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(Option<Plan>, QueryStateMachineRef), Status> {
+        let (logical_plan, planned) = self.get_prepared_statement(statement_handle)?;
 
+        let query_state_machine = self
+            .instance
+            .build_query_state_machine(planned.query.clone(), span_ctx.as_ref())
+            .await
+            .map_err(|e| status!("Build query state machine", e))?;
+        query_state_machine.inherit_audit_entry(&planned);
+
+        let params = self
+            .prepared_params
+            .get(statement_handle)
+            .unwrap_or_default();
+        let logical_plan = bind_parameters(logical_plan, params)?;
+
+        Ok((logical_plan, query_state_machine))
+    }
+
+    async fn execute_and_fetch_result_set(
+        &self,
+        logical_plan: Option<Plan>,
+        query_state_machine: QueryStateMachineRef,
+    ) -> Result<<Self as FlightService>::DoGetStream, Status> {
         // execute plan
         let query_result = self
             .execute_logical_plan(logical_plan, query_state_machine)
             .await?;
         let output = query_result.result();
 
-        let schema = (*output.schema()).clone();
+        let schema = output.schema();
+        let batches = output
+            .chunk_result()
+            .await
+            .map_err(|e| status!("Could not chunk result", e))?;
+
+        batches_to_stream(schema, batches)
+    }
+
+    /// Plan a metadata query, only to get the schema of its result set.
+    async fn metadata_query_schema(
+        &self,
+        sql: impl Into<String>,
+        req_headers: &MetadataMap,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<SchemaRef, Status> {
+        let (logical_plan, _) = self
+            .pre_precess_statement_query_req(sql, req_headers, span_ctx)
+            .await?;
+
+        Ok(logical_plan
+            .map(|e| e.schema())
+            .unwrap_or(Arc::new(Schema::empty())))
+    }
+
+    /// Plan and execute a metadata query, the results are small enough to be collected.
+    async fn execute_metadata_query(
+        &self,
+        sql: impl Into<String>,
+        req_headers: &MetadataMap,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<(SchemaRef, Vec<RecordBatch>), Status> {
+        let (logical_plan, query_state_machine) = self
+            .pre_precess_statement_query_req(sql, req_headers, span_ctx)
+            .await?;
+
+        let query_result = self
+            .execute_logical_plan(logical_plan, query_state_machine)
+            .await?;
+        let output = query_result.result();
+
+        let schema = output.schema();
         let batches = output
             .chunk_result()
             .await
             .map_err(|e| status!("Could not chunk result", e))?;
 
-        let flight_data = flight_utils::batches_to_flight_data(schema, batches)
-            .map_err(|e| status!("Could not convert batches", e))?
-            .into_iter()
-            .map(Ok);
-        let stream: Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send>> =
-            Box::pin(futures::stream::iter(flight_data));
-        Ok(stream)
+        Ok((schema, batches))
+    }
+
+    /// Metadata commands are re-planned when fetched,
+    /// so the command itself is returned as the ticket instead of caching the plan.
+    async fn precess_metadata_flight_info_req(
+        &self,
+        sql: impl Into<String>,
+        command: impl ProstMessageExt,
+        request: Request<FlightDescriptor>,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = self
+            .metadata_query_schema(sql, request.metadata(), span_ctx)
+            .await?;
+
+        let flight_info = self.construct_flight_info(
+            command.as_any().encode_to_vec(),
+            schema.as_ref(),
+            UNKNOWN_AFFECTED_ROWS_COUNT,
+            request.into_inner(),
+        )?;
+
+        Ok(Response::new(flight_info))
+    }
+
+    /// Append the ipc encoded schema of each table to the result of [`tables_sql`].
+    ///
+    /// The schemas are read from meta, only the tables not kept in meta, such as
+    /// the ones of `information_schema`, are planned to get their schemas.
+    async fn append_table_schemas(
+        &self,
+        batches: Vec<RecordBatch>,
+        req_headers: &MetadataMap,
+        span_ctx: Option<&SpanContext>,
+    ) -> Result<Vec<RecordBatch>, Status> {
+        let tenant = utils::get_value_from_header(req_headers, TENANT, "")
+            .unwrap_or_else(|| DEFAULT_CATALOG.to_string());
+        let meta_client = self.coord.meta_manager().tenant_meta(&tenant).await;
+
+        let mut result = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let db_names = string_column(&batch, 1)?;
+            let table_names = string_column(&batch, 2)?;
+
+            let mut table_schemas = Vec::with_capacity(batch.num_rows());
+            for row in 0..batch.num_rows() {
+                let (db_name, table_name) = (db_names.value(row), table_names.value(row));
+                let table_schema = meta_client
+                    .as_ref()
+                    .and_then(|client| client.get_table_schema(db_name, table_name).ok())
+                    .flatten();
+                let schema = match table_schema {
+                    Some(table_schema) => table_schema.to_arrow_schema(),
+                    None => {
+                        let sql = format!(
+                            "SELECT * FROM {}.{}",
+                            quote_identifier(db_name),
+                            quote_identifier(table_name)
+                        );
+                        self.metadata_query_schema(sql, req_headers, span_ctx)
+                            .await?
+                    }
+                };
+                let IpcMessage(table_schema) = utils::schema_to_ipc_message(schema.as_ref())
+                    .map_err(|e| status!("Schema to ipc message", e))?;
+                table_schemas.push(table_schema);
+            }
+
+            let mut columns = batch.columns().to_vec();
+            columns.push(Arc::new(BinaryArray::from_iter_values(
+                table_schemas.iter(),
+            )));
+
+            let batch = RecordBatch::try_new(with_table_schema_field(&batch.schema()), columns)
+                .map_err(|e| status!("Append table schema", e))?;
+            result.push(batch);
+        }
+
+        Ok(result)
+    }
+
+    /// Bulk ingest the record batches of the stream into an existing tskv table.
+    ///
+    /// Columns are mapped to the tags and fields of the table by name and casted to their types,
+    /// the time column is required.
+    pub(crate) async fn do_put_statement_ingest(
+        &self,
+        command: CommandStatementIngest,
+        first: FlightData,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<i64, Status> {
+        debug!(
+            "do_put_statement_ingest: command: {:?}, request: {:?}",
+            command, request
+        );
+
+        let span = get_span(request.extensions(), "flight sql do_put_statement_ingest");
+        let span_ctx = span.context();
+
+        if command.temporary {
+            return Err(Status::invalid_argument(
+                "Ingest into temporary table is not supported",
+            ));
+        }
+        if command.transaction_id.is_some() {
+            return Err(Status::invalid_argument("Transaction is not supported"));
+        }
+
+        let req_headers = request.metadata();
+        let auth_result = {
+            let _span = Span::from_context("authenticate", span_ctx.as_ref());
+            self.authenticator.authenticate(req_headers).await?
+        };
+        let ctx = self.construct_context(auth_result.identity(), req_headers)?;
+        let tenant = command.catalog.as_deref().unwrap_or(ctx.tenant());
+        let db = command.schema.as_deref().unwrap_or(ctx.database());
+        let table = command.table.as_str();
+
+        let meta = self
+            .coord
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| Status::not_found(format!("Tenant {tenant} not found")))?;
+
+        let privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(db.to_string())),
+            Some(*meta.tenant().id()),
+        );
+        if !ctx.user().check_privilege(&privilege) {
+            return Err(Status::permission_denied(format!(
+                "Insufficient privileges, expected [{privilege}]"
+            )));
+        }
+
+        let db_schema = meta
+            .get_db_schema(db)
+            .map_err(|e| status!("Get database schema", e))?
+            .filter(|e| !e.is_hidden())
+            .ok_or_else(|| Status::not_found(format!("Database {db} not found")))?;
+        let table_schema = meta
+            .get_tskv_table_schema(db, table)
+            .map_err(|e| status!("Get table schema", e))?
+            .ok_or_else(|| Status::not_found(format!("Table {db}.{table} not found")))?;
+        let db_precision = *db_schema.config.precision();
+
+        // the first message carries the schema of the stream along with the descriptor
+        let mut schema = if first.data_header.is_empty() {
+            Arc::new(Schema::empty())
+        } else {
+            Arc::new(
+                Schema::try_from(&first)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid schema: {e}")))?,
+            )
+        };
+        let mut dictionaries_by_id = HashMap::new();
+        let mut stream = request.into_inner();
+
+        let mut record_count = 0;
+        while let Some(batch) =
+            next_record_batch(&mut stream, &mut schema, &mut dictionaries_by_id).await?
+        {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            let batch = to_tskv_record_batch(&table_schema, &batch)?;
+            let num_rows = batch.num_rows();
+            let batch_size = batch.get_array_memory_size() as u64;
+
+            self.coord
+                .write_record_batch(
+                    table_schema.clone(),
+                    batch,
+                    db_precision,
+                    ctx.write_consistency(),
+                    span_ctx.as_ref(),
+                )
+                .await
+                .map_err(|e| status!("Write record batch", e))?;
+            self.coord.metrics().sql_data_in(tenant, db).inc(batch_size);
+
+            record_count += num_rows as i64;
+        }
+
+        Ok(record_count)
     }
 }
 
@@ -429,7 +753,7 @@ where
         );
 
         let statement_handle = query.prepared_statement_handle.to_byte_slice();
-        let (plan, _) = self.get_plan_and_qsm(statement_handle, span.context())?;
+        let (plan, _) = self.get_prepared_statement(statement_handle)?;
         let schema = plan
             .map(|e| e.schema())
             .unwrap_or(Arc::new(Schema::empty()));
@@ -457,17 +781,8 @@ where
 
         let span = get_span(request.extensions(), "flight sql get_flight_info_catalogs");
 
-        self.precess_flight_info_req(
-            "SELECT 
-                TENANT_NAME AS CATALOG_NAME 
-            FROM 
-                CLUSTER_SCHEMA.TENANTS 
-            ORDER BY 
-                CATALOG_NAME",
-            request,
-            span.context().as_ref(),
-        )
-        .await
+        self.precess_metadata_flight_info_req(CATALOGS_SQL, query, request, span.context().as_ref())
+            .await
     }
 
     async fn get_flight_info_schemas(
@@ -480,35 +795,11 @@ where
             query, request
         );
 
-        let span = get_span(request.extensions(), "flight sql get_flight_info_catalogs");
-
-        let CommandGetDbSchemas {
-            catalog,
-            db_schema_filter_pattern,
-        } = query;
-
-        let mut filters = vec![];
-        let _ = catalog.map(|e| filters.push(format!("TENANT_NAME = '{}'", e)));
-        let _ =
-            db_schema_filter_pattern.map(|e| filters.push(format!("DATABASE_NAME LIKE '{e}'",)));
-
-        let filter = if filters.is_empty() {
-            "".to_string()
-        } else {
-            format!("WHERE {}", filters.join(" AND "))
-        };
+        let span = get_span(request.extensions(), "flight sql get_flight_info_schemas");
 
-        self.precess_flight_info_req(
-            format!(
-                "SELECT
-                    DATABASE_NAME AS DB_SCHEMA_NAME,
-                    TENANT_NAME AS CATALOG_NAME
-                FROM
-                    INFORMATION_SCHEMA.DATABASES
-                {filter}
-                ORDER BY
-                    CATALOG_NAME, DB_SCHEMA_NAME"
-            ),
+        self.precess_metadata_flight_info_req(
+            db_schemas_sql(&query),
+            query,
             request,
             span.context().as_ref(),
         )
@@ -527,49 +818,25 @@ where
 
         let span = get_span(request.extensions(), "flight sql get_flight_info_tables");
 
-        let CommandGetTables {
-            catalog,
-            db_schema_filter_pattern,
-            table_name_filter_pattern,
-            table_types,
-            include_schema: _,
-        } = query;
-
-        let mut filters = vec![];
-        let _ = catalog.map(|e| filters.push(format!("TABLE_TENANT = '{}'", e)));
-        let _ =
-            db_schema_filter_pattern.map(|e| filters.push(format!("TABLE_DATABASE LIKE '{e}'")));
-        let _ = table_name_filter_pattern.map(|e| filters.push(format!("TABLE_NAME LIKE '{e}'")));
-        if !table_types.is_empty() {
-            let table_types = table_types
-                .iter()
-                .map(|e| format!("'{}'", e))
-                .collect::<Vec<_>>()
-                .join(",");
-            filters.push(format!("TABLE_TYPE IN ({})", table_types));
+        let mut schema = self
+            .metadata_query_schema(
+                tables_sql(&query),
+                request.metadata(),
+                span.context().as_ref(),
+            )
+            .await?;
+        if query.include_schema {
+            schema = with_table_schema_field(&schema);
         }
 
-        let filter = if filters.is_empty() {
-            "".to_string()
-        } else {
-            format!("WHERE {}", filters.join(" AND "))
-        };
-
-        let sql = format!(
-            "SELECT
-                TABLE_TENANT as CATALOG_NAME,
-                TABLE_DATABASE as DB_SCHEMA_NAME,
-                TABLE_NAME,
-                TABLE_TYPE
-            FROM
-                INFORMATION_SCHEMA.TABLES
-            {filter}
-            ORDER BY
-                TABLE_TYPE, CATALOG_NAME, DB_SCHEMA_NAME, TABLE_NAME"
-        );
+        let flight_info = self.construct_flight_info(
+            query.as_any().encode_to_vec(),
+            schema.as_ref(),
+            UNKNOWN_AFFECTED_ROWS_COUNT,
+            request.into_inner(),
+        )?;
 
-        self.precess_flight_info_req(sql, request, span.context().as_ref())
-            .await
+        Ok(Response::new(flight_info))
     }
 
     async fn get_flight_info_table_types(
@@ -587,17 +854,15 @@ where
             "flight sql get_flight_info_table_types",
         );
 
-        self.precess_flight_info_req(
-            "SELECT TABLE_TYPE 
-            FROM 
-                (VALUES('TABLE'),('VIEW'),('LOCAL TEMPORARY')) t(TABLE_TYPE)",
+        self.precess_metadata_flight_info_req(
+            TABLE_TYPES_SQL,
+            query,
             request,
             span.context().as_ref(),
         )
         .await
     }
 
-    /// not support
     async fn get_flight_info_sql_info(
         &self,
         query: CommandGetSqlInfo,
@@ -608,9 +873,22 @@ where
             query, request
         );
 
-        Err(Status::unimplemented(
-            "get_flight_info_sql_info not implemented",
-        ))
+        self.authenticator.authenticate(request.metadata()).await?;
+
+        let schema = self
+            .sql_info
+            .record_batch(query.info.clone())
+            .map_err(|e| status!("Build sql info", e))?
+            .schema();
+
+        let flight_info = self.construct_flight_info(
+            query.as_any().encode_to_vec(),
+            schema.as_ref(),
+            UNKNOWN_AFFECTED_ROWS_COUNT,
+            request.into_inner(),
+        )?;
+
+        Ok(Response::new(flight_info))
     }
 
     /// not support
@@ -694,8 +972,10 @@ where
 
         let TicketStatementQuery { statement_handle } = ticket;
 
+        let (logical_plan, query_state_machine) =
+            self.get_plan_and_qsm(&statement_handle, span.context())?;
         let output = self
-            .execute_and_fetch_result_set(&statement_handle, span.context().as_ref())
+            .execute_and_fetch_result_set(logical_plan, query_state_machine)
             .await?;
 
         // clear cache of this query
@@ -705,7 +985,8 @@ where
         Ok(Response::new(output))
     }
 
-    /// Fetch the prepared SQL query's result set
+    /// Fetch the prepared SQL query's result set, with the parameters bound by
+    /// [`Self::do_put_prepared_statement_query`].
     ///
     /// [`CommandPreparedStatementQuery`] is the result obtained after calling [`Self::get_flight_info_prepared_statement`]
     async fn do_get_prepared_statement(
@@ -722,19 +1003,17 @@ where
 
         let prepared_statement_handle = query.prepared_statement_handle.to_byte_slice();
 
+        // the prepared statement is kept until it is closed
+        let (logical_plan, query_state_machine) = self
+            .get_prepared_plan_and_qsm(prepared_statement_handle, span.context())
+            .await?;
         let output = self
-            .execute_and_fetch_result_set(prepared_statement_handle, span.context().as_ref())
+            .execute_and_fetch_result_set(logical_plan, query_state_machine)
             .await?;
 
-        // clear cache of this query
-        self.result_cache
-            .invalidate(prepared_statement_handle.to_byte_slice());
-
         Ok(Response::new(output))
     }
 
-    /// TODO support
-    /// wait for <https://github.com/cnosdb/cnosdb/issues/642>
     async fn do_get_catalogs(
         &self,
         query: CommandGetCatalogs,
@@ -745,11 +1024,15 @@ where
             query, request
         );
 
-        Err(Status::unimplemented("do_get_catalogs not implemented"))
+        let span = get_span(request.extensions(), "flight sql do_get_catalogs");
+
+        let (schema, batches) = self
+            .execute_metadata_query(CATALOGS_SQL, request.metadata(), span.context().as_ref())
+            .await?;
+
+        Ok(Response::new(batches_to_stream(schema, batches)?))
     }
 
-    /// TODO support
-    /// wait for <https://github.com/cnosdb/cnosdb/issues/642>
     async fn do_get_schemas(
         &self,
         query: CommandGetDbSchemas,
@@ -757,11 +1040,19 @@ where
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         debug!("do_get_schemas: query: {:?}, request: {:?}", query, request);
 
-        Err(Status::unimplemented("do_get_schemas not implemented"))
+        let span = get_span(request.extensions(), "flight sql do_get_schemas");
+
+        let (schema, batches) = self
+            .execute_metadata_query(
+                db_schemas_sql(&query),
+                request.metadata(),
+                span.context().as_ref(),
+            )
+            .await?;
+
+        Ok(Response::new(batches_to_stream(schema, batches)?))
     }
 
-    /// TODO support
-    /// wait for `<https://github.com/cnosdb/cnosdb/issues/642>`
     async fn do_get_tables(
         &self,
         query: CommandGetTables,
@@ -769,11 +1060,23 @@ where
     ) -> Result<Response<<Self as FlightService>::DoGetStream>, Status> {
         debug!("do_get_tables: query: {:?}, request: {:?}", query, request);
 
-        Err(Status::unimplemented("do_get_tables not implemented"))
+        let span = get_span(request.extensions(), "flight sql do_get_tables");
+        let span_ctx = span.context();
+        let req_headers = request.metadata();
+
+        let (mut schema, mut batches) = self
+            .execute_metadata_query(tables_sql(&query), req_headers, span_ctx.as_ref())
+            .await?;
+        if query.include_schema {
+            schema = with_table_schema_field(&schema);
+            batches = self
+                .append_table_schemas(batches, req_headers, span_ctx.as_ref())
+                .await?;
+        }
+
+        Ok(Response::new(batches_to_stream(schema, batches)?))
     }
 
-    /// TODO support
-    /// wait for <https://github.com/cnosdb/cnosdb/issues/642>
     async fn do_get_table_types(
         &self,
         query: CommandGetTableTypes,
@@ -784,10 +1087,15 @@ where
             query, request
         );
 
-        Err(Status::unimplemented("do_get_table_types not implemented"))
+        let span = get_span(request.extensions(), "flight sql do_get_table_types");
+
+        let (schema, batches) = self
+            .execute_metadata_query(TABLE_TYPES_SQL, request.metadata(), span.context().as_ref())
+            .await?;
+
+        Ok(Response::new(batches_to_stream(schema, batches)?))
     }
 
-    /// not support
     async fn do_get_sql_info(
         &self,
         query: CommandGetSqlInfo,
@@ -798,7 +1106,17 @@ where
             query, request
         );
 
-        Err(Status::unimplemented("do_get_sql_info not implemented"))
+        self.authenticator.authenticate(request.metadata()).await?;
+
+        let batch = self
+            .sql_info
+            .record_batch(query.info)
+            .map_err(|e| status!("Build sql info", e))?;
+
+        Ok(Response::new(batches_to_stream(
+            batch.schema(),
+            vec![batch],
+        )?))
     }
 
     /// not support
@@ -884,7 +1202,10 @@ where
         Ok(affected_rows)
     }
 
-    /// not support
+    /// Bind parameters to the `$1`-style placeholders of a prepared statement.
+    ///
+    /// The first row of the last record batch put by the client is used as the parameter values,
+    /// the columns are bound to `$1`, `$2`, ... in order.
     async fn do_put_prepared_statement_query(
         &self,
         query: CommandPreparedStatementQuery,
@@ -895,9 +1216,35 @@ where
             query, request
         );
 
-        Err(Status::unimplemented(
-            "do_put_prepared_statement_query not implemented",
-        ))
+        let span = get_span(
+            request.extensions(),
+            "flight sql do_put_prepared_statement_query",
+        );
+
+        let prepared_statement_handle = query.prepared_statement_handle.to_vec();
+        let (plan, _) = self.get_prepared_statement(&prepared_statement_handle)?;
+        let parameter_schema = Arc::new(plan_parameter_schema(plan.as_ref())?);
+
+        let mut schema = parameter_schema.clone();
+        let mut dictionaries_by_id = HashMap::new();
+        let mut parameters = None;
+
+        let mut stream = request.into_inner();
+        while let Some(batch) =
+            next_record_batch(&mut stream, &mut schema, &mut dictionaries_by_id).await?
+        {
+            parameters = Some(batch);
+        }
+
+        let values = match parameters {
+            Some(batch) => parameter_values(&batch, &parameter_schema)?,
+            None => vec![],
+        };
+        self.prepared_params
+            .insert(prepared_statement_handle, values);
+
+        let output: <Self as FlightService>::DoPutStream = Box::pin(futures::stream::empty());
+        Ok(Response::new(output))
     }
 
     /// Execute the query and return the number of affected rows.
@@ -919,17 +1266,19 @@ where
             request.extensions(),
             "flight sql do_put_prepared_statement_update",
         );
-        let (plan, query_machine) =
-            self.get_plan_and_qsm(prepared_statement_ident, span.context())?;
+        let (plan, query_machine) = self
+            .get_prepared_plan_and_qsm(prepared_statement_ident, span.context())
+            .await?;
         // execute plan
         let query_result = self.execute_logical_plan(plan, query_machine).await?;
         let output = query_result.result();
         Ok(output.affected_rows().await)
     }
 
-    /// Prepared statement is not supported,
-    /// because ad-hoc statement of flight jdbc needs to call this interface,
-    /// so directly return the sql as the result.
+    /// Create a prepared statement.
+    ///
+    /// The `$1`-style placeholders of the statement are announced as `parameter_schema`,
+    /// the placeholders whose type can not be inferred are announced as `Utf8`.
     async fn do_action_create_prepared_statement(
         &self,
         query: ActionCreatePreparedStatementRequest,
@@ -947,31 +1296,36 @@ where
         // ignore transaction_id
         let ActionCreatePreparedStatementRequest { query: sql, .. } = query;
 
-        let (result_ident, schema) = self
-            .pre_precess_statement_query_req_and_save(
-                sql,
-                request.metadata(),
-                span.context().as_ref(),
-            )
+        let (plan, query_state_machine) = self
+            .pre_precess_statement_query_req(sql, request.metadata(), span.context().as_ref())
             .await?;
 
+        let schema = plan
+            .as_ref()
+            .map(|e| e.schema())
+            .unwrap_or(Arc::new(Schema::empty()));
         let IpcMessage(dataset_schema) = utils::schema_to_ipc_message(schema.as_ref())
             .map_err(|e| status!("Schema to ipc message", e))?;
+        let IpcMessage(parameter_schema) =
+            utils::schema_to_ipc_message(&plan_parameter_schema(plan.as_ref())?)
+                .map_err(|e| status!("Schema to ipc message", e))?;
+
+        let result_ident = self.id_generator.next_id().to_le_bytes().to_vec();
+        self.prepared_statements
+            .insert(result_ident.clone(), (plan, query_state_machine));
         // JDBC:
         //    - schema.getFields().isEmpty() ? StatementType.UPDATE : StatementType.SELECT;
         //    - long updateCount = statementType.equals(StatementType.UPDATE) ? preparedStatement.executeUpdate() : -1L;
         let result = ActionCreatePreparedStatementResult {
             prepared_statement_handle: result_ident.into(),
             dataset_schema,
-            ..Default::default()
+            parameter_schema,
         };
 
         Ok(result)
     }
 
-    /// Close a previously created prepared statement.
-    ///
-    /// Empty logic, because we not save created prepared statement.
+    /// Close a previously created prepared statement and release its plan and parameters.
     async fn do_action_close_prepared_statement(
         &self,
         query: ActionClosePreparedStatementRequest,
@@ -982,6 +1336,11 @@ where
             query, request
         );
 
+        let prepared_statement_handle = query.prepared_statement_handle.to_byte_slice();
+        self.prepared_statements
+            .invalidate(prepared_statement_handle);
+        self.prepared_params.invalidate(prepared_statement_handle);
+
         Ok(())
     }
 
@@ -1088,6 +1447,304 @@ fn get_span(extensions: &Extensions, child_span_name: &'static str) -> Span {
     Span::from_context(child_span_name, span_context)
 }
 
+/// Decode the next record batch of the stream,
+/// the schema and dictionary messages on the way are applied to `schema` and `dictionaries_by_id`.
+async fn next_record_batch(
+    stream: &mut Streaming<FlightData>,
+    schema: &mut SchemaRef,
+    dictionaries_by_id: &mut HashMap<i64, ArrayRef>,
+) -> Result<Option<RecordBatch>, Status> {
+    while let Some(data) = stream.message().await? {
+        if data.data_header.is_empty() {
+            continue;
+        }
+
+        let message = ipc::root_as_message(&data.data_header[..])
+            .map_err(|e| Status::invalid_argument(format!("Invalid ipc message: {e}")))?;
+        match message.header_type() {
+            MessageHeader::Schema => {
+                *schema = Arc::new(
+                    Schema::try_from(&data)
+                        .map_err(|e| status!("Could not convert to Schema", e))?,
+                );
+            }
+            MessageHeader::DictionaryBatch => {
+                utils::dictionary_from_message(
+                    message,
+                    &Buffer::from(data.data_body),
+                    schema.clone(),
+                    dictionaries_by_id,
+                )?;
+            }
+            MessageHeader::RecordBatch => {
+                let batch = utils::record_batch_from_message(
+                    message,
+                    &Buffer::from(data.data_body),
+                    schema.clone(),
+                    dictionaries_by_id,
+                )?;
+                return Ok(Some(batch));
+            }
+            t => {
+                return Err(Status::invalid_argument(format!(
+                    "Unexpected ipc message: {t:?}"
+                )))
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Map the columns of `batch` to the columns of the table by name, casted to their types.
+fn to_tskv_record_batch(
+    table_schema: &TskvTableSchema,
+    batch: &RecordBatch,
+) -> Result<RecordBatch, Status> {
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+    let mut has_time = false;
+
+    for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+        let column = table_schema.column(field.name()).ok_or_else(|| {
+            Status::invalid_argument(format!(
+                "Column {} not found in table {}",
+                field.name(),
+                table_schema.name
+            ))
+        })?;
+        let target = Field::from(column);
+        let array = cast(array, target.data_type()).map_err(|e| {
+            Status::invalid_argument(format!(
+                "Could not cast column {} to {}: {e}",
+                field.name(),
+                target.data_type()
+            ))
+        })?;
+
+        if let ColumnType::Time(_) = column.column_type {
+            if array.null_count() > 0 {
+                return Err(Status::invalid_argument(format!(
+                    "Column {} can not be null",
+                    column.name
+                )));
+            }
+            has_time = true;
+        }
+
+        fields.push(target);
+        columns.push(array);
+    }
+
+    if !has_time {
+        return Err(Status::invalid_argument(format!(
+            "Column {} not found in record batch",
+            table_schema.time_column().name
+        )));
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| Status::invalid_argument(format!("Invalid record batch: {e}")))
+}
+
+fn build_sql_info() -> SqlInfoData {
+    let mut builder = SqlInfoDataBuilder::new();
+    builder.append(SqlInfo::FlightSqlServerName, "CnosDB");
+    builder.append(SqlInfo::FlightSqlServerVersion, VERSION.as_str());
+    builder.append(SqlInfo::FlightSqlServerReadOnly, false);
+    builder.append(SqlInfo::SqlIdentifierQuoteChar, "\"");
+    builder.append(SqlInfo::SqlDdlCatalog, false);
+    builder.append(SqlInfo::SqlDdlSchema, true);
+    builder.append(SqlInfo::SqlDdlTable, true);
+    builder.build().expect("build flight sql info")
+}
+
+fn batches_to_stream(
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+) -> Result<Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send>>, Status> {
+    let flight_data = flight_utils::batches_to_flight_data(schema.as_ref().clone(), batches)
+        .map_err(|e| status!("Could not convert batches", e))?
+        .into_iter()
+        .map(Ok);
+    let stream: Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send>> =
+        Box::pin(futures::stream::iter(flight_data));
+    Ok(stream)
+}
+
+/// Schema of the `$1`-style placeholders of the plan, ordered by their index.
+fn plan_parameter_schema(plan: Option<&Plan>) -> Result<Schema, Status> {
+    let parameter_types = match plan {
+        Some(Plan::Query(plan)) => plan
+            .df_plan
+            .get_parameter_types()
+            .map_err(|e| status!("Get parameter types", e))?,
+        _ => return Ok(Schema::empty()),
+    };
+
+    let mut parameters = parameter_types
+        .into_iter()
+        .filter_map(|(id, data_type)| {
+            let idx = id.strip_prefix('$')?.parse::<usize>().ok()?;
+            Some((idx, id, data_type))
+        })
+        .collect::<Vec<_>>();
+    parameters.sort_by_key(|(idx, ..)| *idx);
+
+    let fields = parameters
+        .into_iter()
+        .map(|(_, id, data_type)| Field::new(id, data_type.unwrap_or(DataType::Utf8), true))
+        .collect::<Vec<_>>();
+
+    Ok(Schema::new(fields))
+}
+
+/// Take the first row of `batch` as parameter values, casted to the types of `parameter_schema`.
+fn parameter_values(
+    batch: &RecordBatch,
+    parameter_schema: &Schema,
+) -> Result<Vec<ScalarValue>, Status> {
+    match batch.num_rows() {
+        0 => return Ok(vec![]),
+        1 => {}
+        n => {
+            return Err(Status::invalid_argument(format!(
+                "Only one row of parameters can be bound, but got {n}"
+            )))
+        }
+    }
+
+    batch
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            let column = match parameter_schema.fields().get(idx) {
+                Some(field) => cast(column, field.data_type()).map_err(|e| {
+                    Status::invalid_argument(format!(
+                        "Could not cast parameter {} to {}: {e}",
+                        field.name(),
+                        field.data_type()
+                    ))
+                })?,
+                None => column.clone(),
+            };
+            ScalarValue::try_from_array(&column, 0).map_err(|e| status!("Read parameter", e))
+        })
+        .collect()
+}
+
+fn bind_parameters(plan: Option<Plan>, params: Vec<ScalarValue>) -> Result<Option<Plan>, Status> {
+    if params.is_empty() {
+        return Ok(plan);
+    }
+
+    match plan {
+        Some(Plan::Query(mut plan)) => {
+            plan.df_plan = plan
+                .df_plan
+                .with_param_values(params)
+                .map_err(|e| Status::invalid_argument(format!("Bind parameters: {e}")))?;
+            Ok(Some(Plan::Query(plan)))
+        }
+        _ => Err(Status::invalid_argument(
+            "Parameters can only be bound to queries",
+        )),
+    }
+}
+
+fn string_column(batch: &RecordBatch, idx: usize) -> Result<&StringArray, Status> {
+    batch
+        .column(idx)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| Status::internal(format!("Column {idx} is not a string column")))
+}
+
+fn with_table_schema_field(schema: &Schema) -> SchemaRef {
+    let mut fields = schema
+        .fields()
+        .iter()
+        .map(|f| f.as_ref().clone())
+        .collect::<Vec<_>>();
+    fields.push(Field::new(TABLE_SCHEMA_COL_NAME, DataType::Binary, false));
+    Arc::new(Schema::new(fields))
+}
+
+fn quote_identifier(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn db_schemas_sql(query: &CommandGetDbSchemas) -> String {
+    let mut filters = vec![];
+    if let Some(catalog) = &query.catalog {
+        filters.push(format!("TENANT_NAME = {}", quote_literal(catalog)));
+    }
+    if let Some(pattern) = &query.db_schema_filter_pattern {
+        filters.push(format!("DATABASE_NAME LIKE {}", quote_literal(pattern)));
+    }
+
+    format!(
+        "SELECT
+            TENANT_NAME AS CATALOG_NAME,
+            DATABASE_NAME AS DB_SCHEMA_NAME
+        FROM
+            INFORMATION_SCHEMA.DATABASES
+        {}
+        ORDER BY
+            CATALOG_NAME, DB_SCHEMA_NAME",
+        where_clause(filters)
+    )
+}
+
+fn tables_sql(query: &CommandGetTables) -> String {
+    let mut filters = vec![];
+    if let Some(catalog) = &query.catalog {
+        filters.push(format!("TABLE_TENANT = {}", quote_literal(catalog)));
+    }
+    if let Some(pattern) = &query.db_schema_filter_pattern {
+        filters.push(format!("TABLE_DATABASE LIKE {}", quote_literal(pattern)));
+    }
+    if let Some(pattern) = &query.table_name_filter_pattern {
+        filters.push(format!("TABLE_NAME LIKE {}", quote_literal(pattern)));
+    }
+    if !query.table_types.is_empty() {
+        let table_types = query
+            .table_types
+            .iter()
+            .map(|e| quote_literal(e))
+            .collect::<Vec<_>>()
+            .join(",");
+        filters.push(format!("TABLE_TYPE IN ({})", table_types));
+    }
+
+    format!(
+        "SELECT
+            TABLE_TENANT AS CATALOG_NAME,
+            TABLE_DATABASE AS DB_SCHEMA_NAME,
+            TABLE_NAME,
+            TABLE_TYPE
+        FROM
+            INFORMATION_SCHEMA.TABLES
+        {}
+        ORDER BY
+            CATALOG_NAME, DB_SCHEMA_NAME, TABLE_NAME",
+        where_clause(filters)
+    )
+}
+
+fn where_clause(filters: Vec<String>) -> String {
+    if filters.is_empty() {
+        "".to_string()
+    } else {
+        format!("WHERE {}", filters.join(" AND "))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -1096,14 +1753,21 @@ mod test {
     use arrow_flight::flight_service_client::FlightServiceClient;
     use arrow_flight::flight_service_server::FlightServiceServer;
     use arrow_flight::sql::client::FlightSqlServiceClient;
-    use arrow_flight::sql::{Any, CommandStatementQuery};
+    use arrow_flight::sql::{Any, CommandGetTables, CommandStatementQuery};
     use arrow_flight::utils::flight_data_to_batches;
     use arrow_flight::{FlightDescriptor, HandshakeRequest, IpcMessage};
+    use coordinator::service_mock::MockCoordinator;
+    use datafusion::arrow::array::{Int64Array, StringArray};
     use datafusion::arrow::buffer::Buffer;
-    use datafusion::arrow::datatypes::Schema;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
     use datafusion::arrow::{self, ipc};
+    use datafusion::scalar::ScalarValue;
     use futures::{StreamExt, TryStreamExt};
     use http_protocol::header::AUTHORIZATION;
+    use models::codec::Encoding;
+    use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
+    use models::ValueType;
     use prost::Message;
     use spi::server::dbms::DatabaseManagerSystemMock;
     use tonic::metadata::MetadataValue;
@@ -1113,7 +1777,10 @@ mod test {
 
     use crate::flight_sql::auth_middleware::basic_call_header_authenticator::BasicCallHeaderAuthenticator;
     use crate::flight_sql::auth_middleware::generated_bearer_token_authenticator::GeneratedBearerTokenAuthenticator;
-    use crate::flight_sql::flight_sql_server::FlightSqlServiceImpl;
+    use crate::flight_sql::flight_sql_server::{
+        parameter_values, tables_sql, to_tskv_record_batch, FlightSqlServiceImpl,
+    };
+    use crate::flight_sql::ingest::FlightSqlIngestService;
     use crate::flight_sql::utils;
 
     async fn run_test_server() {
@@ -1124,7 +1791,11 @@ mod test {
             BasicCallHeaderAuthenticator::new(instance.clone()),
         );
 
-        let svc = FlightServiceServer::new(FlightSqlServiceImpl::new(instance, authenticator));
+        let svc = FlightServiceServer::new(FlightSqlIngestService::new(FlightSqlServiceImpl::new(
+            instance,
+            Arc::new(MockCoordinator::default()),
+            authenticator,
+        )));
 
         println!("Listening on {:?}", addr);
 
@@ -1252,4 +1923,106 @@ mod test {
             };
         }
     }
+
+    #[test]
+    fn test_tables_sql() {
+        let query = CommandGetTables {
+            catalog: Some("cnosdb".to_string()),
+            db_schema_filter_pattern: Some("it's%".to_string()),
+            table_name_filter_pattern: None,
+            table_types: vec!["TABLE".to_string()],
+            include_schema: false,
+        };
+        let sql = tables_sql(&query);
+        assert!(sql.contains("TABLE_TENANT = 'cnosdb'"));
+        assert!(sql.contains("TABLE_DATABASE LIKE 'it''s%'"));
+        assert!(sql.contains("TABLE_TYPE IN ('TABLE')"));
+        assert!(!sql.contains("TABLE_NAME LIKE"));
+    }
+
+    #[test]
+    fn test_parameter_values() {
+        let parameter_schema = Schema::new(vec![
+            Field::new("$1", DataType::Int64, true),
+            Field::new("$2", DataType::Utf8, true),
+        ]);
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "$1",
+                Arc::new(StringArray::from(vec!["42"])) as arrow::array::ArrayRef,
+            ),
+            ("$2", Arc::new(StringArray::from(vec!["air"]))),
+        ])
+        .unwrap();
+
+        let values = parameter_values(&batch, &parameter_schema).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                ScalarValue::Int64(Some(42)),
+                ScalarValue::Utf8(Some("air".to_string()))
+            ]
+        );
+
+        let batch = RecordBatch::try_from_iter(vec![(
+            "$1",
+            Arc::new(Int64Array::from(vec![1, 2])) as arrow::array::ArrayRef,
+        )])
+        .unwrap();
+        assert!(parameter_values(&batch, &parameter_schema).is_err());
+    }
+
+    #[test]
+    fn test_to_tskv_record_batch() {
+        let table_schema = TskvTableSchema::new(
+            "cnosdb".into(),
+            "public".into(),
+            "air".into(),
+            vec![
+                TableColumn::new_time_column(0, TimeUnit::Nanosecond),
+                TableColumn::new_tag_column(1, "station".into()),
+                TableColumn::new(
+                    2,
+                    "temperature".into(),
+                    ColumnType::Field(ValueType::Float),
+                    Encoding::Default,
+                ),
+            ],
+        );
+
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "station",
+                Arc::new(StringArray::from(vec!["XiaoMaiDao"])) as arrow::array::ArrayRef,
+            ),
+            ("temperature", Arc::new(Int64Array::from(vec![69]))),
+            (
+                "time",
+                Arc::new(Int64Array::from(vec![1666165200290401000])),
+            ),
+        ])
+        .unwrap();
+        let batch = to_tskv_record_batch(&table_schema, &batch).unwrap();
+        assert_eq!(batch.column(1).data_type(), &DataType::Float64);
+        assert_eq!(
+            batch.column(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None)
+        );
+
+        // unknown column
+        let batch = RecordBatch::try_from_iter(vec![(
+            "humidity",
+            Arc::new(Int64Array::from(vec![1])) as arrow::array::ArrayRef,
+        )])
+        .unwrap();
+        assert!(to_tskv_record_batch(&table_schema, &batch).is_err());
+
+        // missing time column
+        let batch = RecordBatch::try_from_iter(vec![(
+            "station",
+            Arc::new(StringArray::from(vec!["XiaoMaiDao"])) as arrow::array::ArrayRef,
+        )])
+        .unwrap();
+        assert!(to_tskv_record_batch(&table_schema, &batch).is_err());
+    }
 }
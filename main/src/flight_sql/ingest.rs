@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+
+use arrow_flight::flight_service_server::FlightService;
+use arrow_flight::sql::server::FlightSqlService;
+use arrow_flight::sql::{
+    Any, CommandPreparedStatementQuery, CommandPreparedStatementUpdate,
+    CommandStatementSubstraitPlan, CommandStatementUpdate, DoPutUpdateResult, ProstMessageExt,
+};
+use arrow_flight::{
+    Action, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo, HandshakeRequest, PutResult,
+    SchemaResult, Ticket,
+};
+use futures::Stream;
+use prost::bytes::Bytes;
+use prost::Message;
+use tonic::{Request, Response, Status, Streaming};
+
+use super::auth_middleware::CallHeaderAuthenticator;
+use super::flight_sql_server::FlightSqlServiceImpl;
+
+/// Bulk ingest of arrow record batches into a table.
+///
+/// Mirrors `CommandStatementIngest` of the Flight SQL protocol (field numbers included),
+/// which is not provided by the arrow-flight version we depend on.
+/// `table_definition_options` is not supported, the target table must exist.
+#[derive(Clone, PartialEq, Message)]
+pub struct CommandStatementIngest {
+    /// The table to load data into.
+    #[prost(string, tag = "2")]
+    pub table: String,
+    /// The database of the table, the database of the session is used if absent.
+    #[prost(string, optional, tag = "3")]
+    pub schema: Option<String>,
+    /// The tenant of the table, the tenant of the session is used if absent.
+    #[prost(string, optional, tag = "4")]
+    pub catalog: Option<String>,
+    /// Temporary tables are not supported.
+    #[prost(bool, tag = "5")]
+    pub temporary: bool,
+    /// Transactions are not supported.
+    #[prost(bytes = "bytes", optional, tag = "6")]
+    pub transaction_id: Option<Bytes>,
+    #[prost(map = "string, string", tag = "1000")]
+    pub options: HashMap<String, String>,
+}
+
+impl ProstMessageExt for CommandStatementIngest {
+    fn type_url() -> &'static str {
+        "type.googleapis.com/arrow.flight.protocol.sql.CommandStatementIngest"
+    }
+
+    fn as_any(&self) -> Any {
+        Any {
+            type_url: Self::type_url().to_string(),
+            value: self.encode_to_vec().into(),
+        }
+    }
+}
+
+type PutResultStream = Pin<Box<dyn Stream<Item = Result<PutResult, Status>> + Send>>;
+
+/// Routes `DoPut` with [`CommandStatementIngest`] to the bulk ingest,
+/// everything else is served by [`FlightSqlServiceImpl`].
+///
+/// The `DoPut` dispatcher of [`FlightSqlService`] rejects the commands it does not know,
+/// and the first message of the stream is consumed when the command is decoded,
+/// so `DoPut` is dispatched here as a whole.
+pub struct FlightSqlIngestService<T> {
+    inner: FlightSqlServiceImpl<T>,
+}
+
+impl<T> FlightSqlIngestService<T> {
+    pub fn new(inner: FlightSqlServiceImpl<T>) -> Self {
+        Self { inner }
+    }
+}
+
+#[tonic::async_trait]
+impl<T> FlightService for FlightSqlIngestService<T>
+where
+    T: CallHeaderAuthenticator + Send + Sync + 'static,
+{
+    type HandshakeStream = <FlightSqlServiceImpl<T> as FlightService>::HandshakeStream;
+    type ListFlightsStream = <FlightSqlServiceImpl<T> as FlightService>::ListFlightsStream;
+    type DoGetStream = <FlightSqlServiceImpl<T> as FlightService>::DoGetStream;
+    type DoPutStream = <FlightSqlServiceImpl<T> as FlightService>::DoPutStream;
+    type DoActionStream = <FlightSqlServiceImpl<T> as FlightService>::DoActionStream;
+    type ListActionsStream = <FlightSqlServiceImpl<T> as FlightService>::ListActionsStream;
+    type DoExchangeStream = <FlightSqlServiceImpl<T> as FlightService>::DoExchangeStream;
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        FlightService::handshake(&self.inner, request).await
+    }
+
+    async fn list_flights(
+        &self,
+        request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        FlightService::list_flights(&self.inner, request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        FlightService::get_flight_info(&self.inner, request).await
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        FlightService::get_schema(&self.inner, request).await
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        FlightService::do_get(&self.inner, request).await
+    }
+
+    async fn do_put(
+        &self,
+        mut request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        let first = request
+            .get_mut()
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("do_put: empty stream"))?;
+        let descriptor = first
+            .flight_descriptor
+            .as_ref()
+            .ok_or_else(|| Status::invalid_argument("do_put: missing flight descriptor"))?;
+        let message = Any::decode(&*descriptor.cmd)
+            .map_err(|e| Status::invalid_argument(format!("do_put: invalid command: {e}")))?;
+
+        if message.is::<CommandStatementIngest>() {
+            let command = unpack::<CommandStatementIngest>(message)?;
+            let record_count = self
+                .inner
+                .do_put_statement_ingest(command, first, request)
+                .await?;
+            return Ok(Response::new(update_result(record_count)));
+        }
+        if message.is::<CommandStatementUpdate>() {
+            let command = unpack::<CommandStatementUpdate>(message)?;
+            let record_count = self.inner.do_put_statement_update(command, request).await?;
+            return Ok(Response::new(update_result(record_count)));
+        }
+        if message.is::<CommandStatementSubstraitPlan>() {
+            let command = unpack::<CommandStatementSubstraitPlan>(message)?;
+            let record_count = self.inner.do_put_substrait_plan(command, request).await?;
+            return Ok(Response::new(update_result(record_count)));
+        }
+        if message.is::<CommandPreparedStatementQuery>() {
+            let command = unpack::<CommandPreparedStatementQuery>(message)?;
+            return self
+                .inner
+                .do_put_prepared_statement_query(command, request)
+                .await;
+        }
+        if message.is::<CommandPreparedStatementUpdate>() {
+            let command = unpack::<CommandPreparedStatementUpdate>(message)?;
+            let record_count = self
+                .inner
+                .do_put_prepared_statement_update(command, request)
+                .await?;
+            return Ok(Response::new(update_result(record_count)));
+        }
+
+        Err(Status::invalid_argument(format!(
+            "do_put: The defined request is invalid: {}",
+            message.type_url
+        )))
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        FlightService::do_action(&self.inner, request).await
+    }
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        FlightService::list_actions(&self.inner, request).await
+    }
+
+    async fn do_exchange(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        FlightService::do_exchange(&self.inner, request).await
+    }
+}
+
+fn unpack<M: ProstMessageExt>(message: Any) -> Result<M, Status> {
+    message
+        .unpack::<M>()
+        .map_err(|e| Status::invalid_argument(format!("do_put: invalid command: {e}")))?
+        .ok_or_else(|| Status::invalid_argument("do_put: unexpected command"))
+}
+
+fn update_result(record_count: i64) -> PutResultStream {
+    let result = DoPutUpdateResult { record_count };
+    let output = futures::stream::iter(vec![Ok(PutResult {
+        app_metadata: result.encode_to_vec().into(),
+    })]);
+    Box::pin(output)
+}
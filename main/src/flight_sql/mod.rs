@@ -2,6 +2,7 @@ use std::net::SocketAddr;
 
 use arrow_flight::flight_service_server::FlightServiceServer;
 use config::tskv::TLSConfig;
+use coordinator::service::CoordinatorRef;
 use spi::server::dbms::DBMSRef;
 use tokio::sync::oneshot;
 use tonic::transport::{Identity, Server, ServerTlsConfig};
@@ -9,6 +10,8 @@ use trace::http::tower_layer::TraceLayer;
 use trace::info;
 
 use self::flight_sql_server::FlightSqlServiceImpl;
+use self::ingest::FlightSqlIngestService;
+use self::utils::client_addr_interceptor;
 use crate::flight_sql::auth_middleware::basic_call_header_authenticator::BasicCallHeaderAuthenticator;
 use crate::flight_sql::auth_middleware::generated_bearer_token_authenticator::GeneratedBearerTokenAuthenticator;
 use crate::server::ServiceHandle;
@@ -16,10 +19,12 @@ use crate::spi::service::Service;
 
 mod auth_middleware;
 pub mod flight_sql_server;
+pub mod ingest;
 mod utils;
 
 pub struct FlightSqlServiceAdapter {
     dbms: DBMSRef,
+    coord: CoordinatorRef,
 
     addr: SocketAddr,
     tls_config: Option<TLSConfig>,
@@ -30,12 +35,14 @@ pub struct FlightSqlServiceAdapter {
 impl FlightSqlServiceAdapter {
     pub fn new(
         dbms: DBMSRef,
+        coord: CoordinatorRef,
         addr: SocketAddr,
         tls_config: Option<TLSConfig>,
         auto_generate_span: bool,
     ) -> Self {
         Self {
             dbms,
+            coord,
             addr,
             tls_config,
             auto_generate_span,
@@ -69,8 +76,14 @@ impl Service for FlightSqlServiceAdapter {
         let authenticator = GeneratedBearerTokenAuthenticator::new(
             BasicCallHeaderAuthenticator::new(self.dbms.clone()),
         );
-        let svc =
-            FlightServiceServer::new(FlightSqlServiceImpl::new(self.dbms.clone(), authenticator));
+        let svc = FlightServiceServer::with_interceptor(
+            FlightSqlIngestService::new(FlightSqlServiceImpl::new(
+                self.dbms.clone(),
+                self.coord.clone(),
+                authenticator,
+            )),
+            client_addr_interceptor,
+        );
 
         let server = server
             .layer(trace_layer)
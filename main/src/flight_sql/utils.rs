@@ -11,11 +11,13 @@ use datafusion::arrow::error::ArrowError;
 use datafusion::arrow::ipc::writer::IpcWriteOptions;
 use datafusion::arrow::ipc::{self, reader};
 use datafusion::arrow::record_batch::RecordBatch;
-use http_protocol::header::AUTHORIZATION;
+use http_protocol::header::{AUTHORIZATION, X_FORWARDED_FOR};
 use prost::Message;
 use tonic::metadata::{AsciiMetadataValue, MetadataMap};
 use tonic::{Request, Status};
 
+use crate::http::header::client_addr;
+
 #[macro_export]
 macro_rules! status_with_location {
     ($desc:expr, $err:expr) => {
@@ -92,6 +94,31 @@ where
     Ok(())
 }
 
+/// The internal header holding the peer address of the connection,
+/// it's always overwritten by [`client_addr_interceptor`]
+const CLIENT_ADDR: &str = "x-cnosdb-client-addr";
+
+/// Records the peer address of the connection to the metadata of the request
+pub fn client_addr_interceptor(mut request: Request<()>) -> Result<Request<()>, Status> {
+    let remote_addr = request
+        .remote_addr()
+        .and_then(|addr| AsciiMetadataValue::try_from(addr.to_string()).ok());
+    let metadata = request.metadata_mut();
+    metadata.remove(CLIENT_ADDR);
+    if let Some(addr) = remote_addr {
+        metadata.insert(CLIENT_ADDR, addr);
+    }
+    Ok(request)
+}
+
+/// The address of the client, see [`client_addr`]
+pub fn get_client_addr(headers: &MetadataMap) -> Option<String> {
+    client_addr(
+        get_value_from_header(headers, X_FORWARDED_FOR, "").as_deref(),
+        get_value_from_header(headers, CLIENT_ADDR, ""),
+    )
+}
+
 pub fn parse_authorization_header(
     request: &Request<FlightDescriptor>,
 ) -> std::result::Result<&str, String> {
@@ -9,6 +9,7 @@ pub enum HttpApiType {
     ApiV1PromWrite,
 
     ApiV1Sql,
+    ApiV1SqlAsync,
     ApiV1PromRead,
     ApiV1ESLogWrite,
 
@@ -22,6 +23,7 @@ pub enum HttpApiType {
     DebugJeprof,
     Metrics,
     ApiV1DumpSqlDdl,
+    ApiV1Cdc,
     ApiV1Traces,
     ApiTraces,
     ApiTracesID,
@@ -48,6 +50,9 @@ impl Display for HttpApiType {
             HttpApiType::ApiV1Sql => {
                 write!(f, "api/v1/sql")
             }
+            HttpApiType::ApiV1SqlAsync => {
+                write!(f, "api/v1/sql/async")
+            }
             HttpApiType::ApiV1PromRead => {
                 write!(f, "api/v1/prom/read")
             }
@@ -84,6 +89,9 @@ impl Display for HttpApiType {
             HttpApiType::ApiV1DumpSqlDdl => {
                 write!(f, "api/v1/dump/sql/ddl")
             }
+            HttpApiType::ApiV1Cdc => {
+                write!(f, "api/v1/cdc")
+            }
             HttpApiType::ApiV1Traces => {
                 write!(f, "api/v1/traces")
             }
@@ -115,6 +123,7 @@ pub fn metrics_record_db(api: &HttpApiType) -> bool {
         | HttpApiType::ApiV1PromWrite
         | HttpApiType::ApiV1ESLogWrite
         | HttpApiType::ApiV1PromRead
+        | HttpApiType::ApiV1Cdc
         | HttpApiType::ApiV1Traces
         | HttpApiType::ApiTraces
         | HttpApiType::ApiTracesID
@@ -122,6 +131,7 @@ pub fn metrics_record_db(api: &HttpApiType) -> bool {
         | HttpApiType::ApiOperations
         | HttpApiType::ApiServicesOperations => true,
         HttpApiType::ApiV1Sql
+        | HttpApiType::ApiV1SqlAsync
         | HttpApiType::ApiV1Ping
         | HttpApiType::DebugBacktrace
         | HttpApiType::Write
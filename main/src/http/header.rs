@@ -1,5 +1,7 @@
 use base64::prelude::{Engine, BASE64_STANDARD};
-use http_protocol::header::{APPLICATION_CSV, BASIC_PREFIX};
+use http_protocol::header::{APPLICATION_CSV, BASIC_PREFIX, BEARER_PREFIX};
+use models::auth::jwt::is_jwt;
+use models::auth::token::TOKEN_PREFIX;
 use models::auth::user::UserInfo;
 use warp::http::header::{HeaderName, HeaderValue};
 
@@ -15,6 +17,8 @@ pub struct Header {
     tenant: Option<String>,
     db: Option<String>,
     table: Option<String>,
+    client_addr: Option<String>,
+    consistency: Option<String>,
 }
 
 impl Header {
@@ -33,6 +37,8 @@ impl Header {
             tenant: None,
             db: None,
             table: None,
+            client_addr: None,
+            consistency: None,
         }
     }
 
@@ -55,9 +61,21 @@ impl Header {
             tenant,
             db,
             table,
+            client_addr: None,
+            consistency: None,
         }
     }
 
+    pub fn with_client_addr(mut self, client_addr: Option<String>) -> Self {
+        self.client_addr = client_addr;
+        self
+    }
+
+    pub fn with_consistency(mut self, consistency: Option<String>) -> Self {
+        self.consistency = consistency;
+        self
+    }
+
     pub fn get_accept(&self) -> &str {
         self.accept.as_deref().unwrap_or(APPLICATION_CSV)
     }
@@ -82,6 +100,16 @@ impl Header {
         self.table.clone()
     }
 
+    /// The write consistency level, the `consistency` parameter takes precedence
+    pub fn get_consistency(&self) -> Option<String> {
+        self.consistency.clone()
+    }
+
+    /// The address of the client, see [`client_addr`]
+    pub fn get_client_addr(&self) -> Option<String> {
+        self.client_addr.clone()
+    }
+
     pub fn try_get_basic_auth(&self) -> Result<UserInfo, HttpError> {
         let private_key = self
             .private_key
@@ -132,6 +160,31 @@ impl Header {
 
         get_err()
     }
+
+    /// The API token in `Authorization: Bearer cnos_...`
+    pub fn get_api_token(&self) -> Option<&str> {
+        self.authorization
+            .strip_prefix(BEARER_PREFIX)
+            .filter(|token| token.starts_with(TOKEN_PREFIX))
+    }
+
+    /// The JWT in `Authorization: Bearer <header>.<claims>.<signature>`
+    pub fn get_jwt(&self) -> Option<&str> {
+        self.authorization
+            .strip_prefix(BEARER_PREFIX)
+            .filter(|token| is_jwt(token))
+    }
+}
+
+/// The address of the client is appended to the `X-Forwarded-For` chain of the proxies,
+/// e.g. `203.0.113.7, 10.0.0.2:51234`, the last one is the peer of the connection.
+pub fn client_addr(forwarded_for: Option<&str>, remote_addr: Option<String>) -> Option<String> {
+    let forwarded_for = forwarded_for.map(str::trim).filter(|e| !e.is_empty());
+    match (forwarded_for, remote_addr) {
+        (Some(forwarded_for), Some(remote_addr)) => Some(format!("{forwarded_for}, {remote_addr}")),
+        (Some(forwarded_for), None) => Some(forwarded_for.to_string()),
+        (None, remote_addr) => remote_addr,
+    }
 }
 
 pub trait IntoHeaderValue: Sized {
@@ -224,4 +277,41 @@ mod tests {
         let header = Header::with(None, None, None, auth);
         assert!(header.try_get_basic_auth().is_err());
     }
+
+    #[test]
+    fn test_header_api_token() {
+        let auth = format!("{}cnos_1_secret", BEARER_PREFIX);
+        let header = Header::with(None, None, None, auth);
+        assert_eq!(header.get_api_token(), Some("cnos_1_secret"));
+
+        let auth = format!("{}generated", BEARER_PREFIX);
+        let header = Header::with(None, None, None, auth);
+        assert!(header.get_api_token().is_none());
+
+        let auth = format!("{}{}", BASIC_PREFIX, BASE64_STANDARD.encode("xx:xx"));
+        let header = Header::with(None, None, None, auth);
+        assert!(header.get_api_token().is_none());
+        assert!(header.get_jwt().is_none());
+
+        let auth = format!("{}eyJhbGciOiJSUzI1NiJ9.eyJzdWIiOiJhIn0.c2ln", BEARER_PREFIX);
+        let header = Header::with(None, None, None, auth);
+        assert!(header.get_api_token().is_none());
+        assert_eq!(
+            header.get_jwt(),
+            Some("eyJhbGciOiJSUzI1NiJ9.eyJzdWIiOiJhIn0.c2ln")
+        );
+    }
+
+    #[test]
+    fn test_client_addr() {
+        assert_eq!(
+            client_addr(None, Some("127.0.0.1:53412".to_string())),
+            Some("127.0.0.1:53412".to_string())
+        );
+        assert_eq!(
+            client_addr(Some("203.0.113.7"), Some("10.0.0.2:51234".to_string())),
+            Some("203.0.113.7, 10.0.0.2:51234".to_string())
+        );
+        assert_eq!(client_addr(Some(" "), None), None);
+    }
 }
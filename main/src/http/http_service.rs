@@ -7,29 +7,35 @@ use std::fmt::Display;
 use std::mem::size_of_val;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use config::tskv::TLSConfig;
+use coordinator::change_feed::{ChangeEvent, ChangeFeedPosition};
 use coordinator::service::CoordinatorRef;
 use datafusion::arrow::array::{Array, StringArray};
-use futures::TryStreamExt;
+use futures::{StreamExt, TryStreamExt};
 use http_protocol::encoding::Encoding;
 use http_protocol::header::{
-    ACCEPT, APPLICATION_JSON, AUTHORIZATION, DB, PRIVATE_KEY, TABLE, TENANT,
+    ACCEPT, APPLICATION_JSON, APPLICATION_NDJSON, AUTHORIZATION, CONSISTENCY, DB, PRIVATE_KEY,
+    TABLE, TENANT, X_FORWARDED_FOR,
 };
 use http_protocol::parameter::{
-    DebugParam, DumpParam, FindTracesParam, GetOperationParam, LogParam, SqlParam, WriteParam,
+    AsyncQueryParam, ChangeFeedParam, DebugParam, DumpParam, FindTracesParam, GetOperationParam,
+    LogParam, SqlParam, WriteParam,
 };
 use http_protocol::response::ErrorResponse;
 use http_protocol::status_code::OK;
-use meta::error::{MetaError, MetaResult};
+use meta::error::MetaError;
 use meta::limiter::RequestLimiter;
 use meta::model::MetaRef;
 use metrics::count::U64Counter;
 use metrics::metric_register::MetricsRegister;
 use metrics::prom_reporter::PromReporter;
 use models::auth::privilege::{DatabasePrivilege, Privilege, TenantObjectPrivilege};
+use models::auth::user::User;
+use models::consistency_level::ConsistencyLevel;
 use models::error_code::UnknownCodeWithMessage;
+use models::follower_read::FollowerReadLag;
 use models::oid::{Identifier, Oid};
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE};
 use models::utils::now_timestamp_nanos;
@@ -62,7 +68,7 @@ use warp::reject::{MethodNotAllowed, MissingHeader, PayloadTooLarge};
 use warp::reply::Response;
 use warp::{header, reject, Filter, Rejection, Reply};
 
-use super::header::Header;
+use super::header::{client_addr, Header};
 use super::{ContextSnafu, CoordinatorSnafu, DecodeRequestSnafu, Error as HttpError, MetaSnafu};
 use crate::http::api_type::{metrics_record_db, HttpApiType};
 use crate::http::encoding::{get_accept_encoding_from_header, get_content_encoding_from_header};
@@ -80,6 +86,9 @@ use crate::server::ServiceHandle;
 use crate::spi::service::Service;
 use crate::{server, VERSION};
 
+const CHANGE_FEED_CHUNK_BYTES: usize = 1024 * 1024;
+const CHANGE_FEED_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 pub enum ServerMode {
     Store,
     Query,
@@ -163,6 +172,9 @@ impl HttpService {
             .and(header::optional::<String>(TENANT))
             .and(header::optional::<String>(DB))
             .and(header::optional::<String>(TABLE))
+            .and(header::optional::<String>(CONSISTENCY))
+            .and(header::optional::<String>(X_FORWARDED_FOR))
+            .and(warp::addr::remote())
             .and_then(
                 |accept,
                  accept_encoding,
@@ -171,7 +183,14 @@ impl HttpService {
                  private_key,
                  tenant,
                  db,
-                 table| async move {
+                 table,
+                 consistency,
+                 forwarded_for: Option<String>,
+                 remote_addr: Option<SocketAddr>| async move {
+                    let client_addr = client_addr(
+                        forwarded_for.as_deref(),
+                        remote_addr.map(|addr| addr.to_string()),
+                    );
                     let res: Result<Header, warp::Rejection> = Ok(Header::with_private_key(
                         accept,
                         accept_encoding,
@@ -181,7 +200,9 @@ impl HttpService {
                         tenant,
                         db,
                         table,
-                    ));
+                    )
+                    .with_consistency(consistency)
+                    .with_client_addr(client_addr));
                     res
                 },
             )
@@ -257,6 +278,10 @@ impl HttpService {
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         self.ping()
             .or(self.query())
+            .or(self.submit_async_query())
+            .or(self.async_query_status())
+            .or(self.fetch_async_query_result())
+            .or(self.remove_async_query())
             .or(self.mock_influxdb_write())
             .or(self.metrics())
             .or(self.print_meta())
@@ -271,6 +296,7 @@ impl HttpService {
             .or(self.write_open_tsdb())
             .or(self.put_open_tsdb())
             .or(self.write_line_protocol())
+            .or(self.change_feed())
             .or(self.get_es_version())
             .or(self.get_es_empty())
             .or(self.get_es_license())
@@ -478,6 +504,225 @@ impl HttpService {
             )
     }
 
+    fn submit_async_query(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v1" / "sql" / "async")
+            .and(warp::post())
+            .and(warp::body::content_length_limit(self.query_body_limit))
+            .and(warp::body::bytes())
+            .and(self.handle_header())
+            .and(warp::query::<SqlParam>())
+            .and(self.with_dbms())
+            .and(self.with_meta())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and(self.handle_span_header())
+            .and_then(
+                |mut req: Bytes,
+                 header: Header,
+                 param: SqlParam,
+                 dbms: DBMSRef,
+                 meta: MetaRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String,
+                 parent_span_ctx: Option<SpanContext>| async move {
+                    let start = Instant::now();
+                    debug!(
+                        "Receive http async sql request, header: {:?}, param: {:?}",
+                        header, param
+                    );
+
+                    let span =
+                        Span::from_context("rest async sql request", parent_span_ctx.as_ref());
+                    let req_len = req.len();
+                    let content_encoding = get_content_encoding_from_header(&header)?;
+                    if let Some(encoding) = content_encoding {
+                        req = encoding.decode(req).map_err(|e| {
+                            error!("Failed to decode request, err: {:?}", e);
+                            reject::custom(HttpError::DecodeRequest { source: e })
+                        })?;
+                    }
+                    let query = {
+                        let mut span = Span::enter_with_parent("authenticate", &span);
+                        let query = construct_query(req, &header, param, dbms.clone(), coord)
+                            .await
+                            .map_err(|e| {
+                                error!("Failed to construct query, err: {:?}", e);
+                                reject::custom(e)
+                            })?;
+                        record_context_in_span(&mut span, query.context());
+                        query
+                    };
+                    http_limiter_check_query(&meta, query.context().tenant(), req_len)
+                        .await
+                        .map_err(|e| {
+                            error!("Failed to check query limiter, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+
+                    let status = {
+                        let span = Span::enter_with_parent("submit", &span);
+                        async {
+                            let query_id = dbms
+                                .execute_async(&query, span.context().as_ref())
+                                .await
+                                .context(QuerySnafu)?;
+                            dbms.async_query_status(query.context().user(), &query_id)
+                                .context(QuerySnafu)
+                        }
+                        .await
+                        .map_err(|e: HttpError| {
+                            span.error(e.to_string());
+                            error!("Failed to submit async sql request, err: {:?}", e);
+                            reject::custom(e)
+                        })?
+                    };
+
+                    http_record_query_metrics(
+                        &metrics,
+                        query.context(),
+                        &addr,
+                        req_len,
+                        start,
+                        HttpApiType::ApiV1SqlAsync,
+                    );
+                    Ok::<_, Rejection>(ResponseBuilder::new(OK).json(&status))
+                },
+            )
+    }
+
+    fn async_query_status(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v1" / "sql" / "async" / u64)
+            .and(warp::get())
+            .and(self.handle_header())
+            .and(warp::query::<AsyncQueryParam>())
+            .and(self.with_dbms())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and_then(
+                |query_id: u64,
+                 header: Header,
+                 param: AsyncQueryParam,
+                 dbms: DBMSRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String| async move {
+                    let start = Instant::now();
+                    let user = authenticate_user(&header, param.tenant.as_deref(), &dbms)
+                        .await
+                        .map_err(reject::custom)?;
+                    let status = dbms
+                        .async_query_status(&user, &query_id.into())
+                        .context(QuerySnafu)
+                        .map_err(reject::custom)?;
+
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        size_of_val(&status),
+                        start,
+                        HttpApiType::ApiV1SqlAsync,
+                    );
+                    Ok::<_, Rejection>(ResponseBuilder::new(OK).json(&status))
+                },
+            )
+    }
+
+    fn fetch_async_query_result(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v1" / "sql" / "async" / u64 / "result")
+            .and(warp::get())
+            .and(self.handle_header())
+            .and(warp::query::<AsyncQueryParam>())
+            .and(self.with_dbms())
+            .and(self.with_meta())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and_then(
+                |query_id: u64,
+                 header: Header,
+                 param: AsyncQueryParam,
+                 dbms: DBMSRef,
+                 meta: MetaRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String| async move {
+                    let start = Instant::now();
+                    let result_fmt = get_result_format_from_header(&header)?;
+                    let result_encoding = get_accept_encoding_from_header(&header)?;
+                    let tenant = param.tenant.as_deref().unwrap_or(DEFAULT_CATALOG);
+                    let user = authenticate_user(&header, Some(tenant), &dbms)
+                        .await
+                        .map_err(reject::custom)?;
+
+                    let result = async {
+                        let out = dbms
+                            .fetch_async_query_result(
+                                &user,
+                                &query_id.into(),
+                                param.page.unwrap_or_default(),
+                            )
+                            .await
+                            .context(QuerySnafu)?;
+                        let limiter = meta.limiter(tenant).await.context(MetaSnafu)?;
+                        let http_data_out = metrics.http_data_out(
+                            tenant,
+                            user.desc().name(),
+                            None,
+                            &addr,
+                            HttpApiType::ApiV1SqlAsync,
+                        );
+                        HttpResponse::new(out, result_fmt, result_encoding, http_data_out, limiter)
+                            .wrap_batches_to_response()
+                            .await
+                    }
+                    .await
+                    .map_err(|e| {
+                        error!("Failed to fetch async query result, err: {:?}", e);
+                        reject::custom(e)
+                    });
+
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        0,
+                        start,
+                        HttpApiType::ApiV1SqlAsync,
+                    );
+                    result
+                },
+            )
+    }
+
+    fn remove_async_query(
+        &self,
+    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+        warp::path!("api" / "v1" / "sql" / "async" / u64)
+            .and(warp::delete())
+            .and(self.handle_header())
+            .and(warp::query::<AsyncQueryParam>())
+            .and(self.with_dbms())
+            .and_then(
+                |query_id: u64, header: Header, param: AsyncQueryParam, dbms: DBMSRef| async move {
+                    let user = authenticate_user(&header, param.tenant.as_deref(), &dbms)
+                        .await
+                        .map_err(reject::custom)?;
+                    dbms.remove_async_query(&user, &query_id.into())
+                        .await
+                        .context(QuerySnafu)
+                        .map_err(|e| {
+                            error!("Failed to remove async query, err: {:?}", e);
+                            reject::custom(e)
+                        })?;
+                    Ok::<_, Rejection>(ResponseBuilder::ok())
+                },
+            )
+    }
+
     fn write_line_protocol(
         &self,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
@@ -567,6 +812,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_lines,
+                        ctx.write_consistency(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -633,6 +879,7 @@ impl HttpService {
                         db: Some(db),
                         precision: None,
                         tenant: None,
+                        consistency: None,
                     };
                     let precision = Precision::NS;
 
@@ -659,6 +906,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         lines,
+                        ctx.write_consistency(),
                         None,
                     )
                     .await;
@@ -765,6 +1013,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_req,
+                        ctx.write_consistency(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -888,6 +1137,7 @@ impl HttpService {
                         ctx.database(),
                         precision,
                         write_points_req,
+                        ctx.write_consistency(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -1334,6 +1584,7 @@ impl HttpService {
                         ctx.database(),
                         Precision::NS,
                         write_request,
+                        ctx.write_consistency(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -1369,44 +1620,16 @@ impl HttpService {
     }
 
     fn dump_ddl_sql(&self) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-        async fn dump_sql_ddl_impl(meta: MetaRef, tenant: Option<String>) -> MetaResult<String> {
-            let cluster = meta.cluster();
-            let leader = meta.meta_leader().await?;
-            let url = match tenant {
-                Some(t) => {
-                    format!("http://{}/{}/{cluster}/{t}", leader, "dump/sql/ddl")
-                }
-                None => {
-                    format!("http://{}/{}/{cluster}", leader, "dump/sql/ddl")
-                }
-            };
-
-            let resp = reqwest::get(url.clone())
-                .await
-                .map_err(|e| MetaError::MetaClientErr { msg: e.to_string() })?;
-            let status = resp.status();
-
-            let data = resp
-                .text()
-                .await
-                .map_err(|e| MetaError::MetaClientErr { msg: e.to_string() })?;
-
-            if !status.is_success() {
-                return Err(MetaError::MetaClientErr {
-                    msg: format!("httpcode: {}, response:{}", status, data),
-                });
-            }
-            Ok(data)
-        }
         warp::path!("api" / "v1" / "dump" / "sql" / "ddl")
             .and(self.with_meta())
             .and(warp::query::<DumpParam>())
             .and(self.with_http_metrics())
             .and(self.with_hostaddr())
             .and_then(
-                |meta, param: DumpParam, metrics: Arc<HttpMetrics>, addr: String| async move {
+                |meta: MetaRef, param: DumpParam, metrics: Arc<HttpMetrics>, addr: String| async move {
                     let start = Instant::now();
-                    let resp = dump_sql_ddl_impl(meta, param.tenant)
+                    let resp = meta
+                        .dump_sql_ddl(param.tenant.as_deref())
                         .await
                         .map(|r| r.into_bytes())
                         .map_err(|e| {
@@ -1432,6 +1655,104 @@ impl HttpService {
             )
     }
 
+    fn change_feed(&self) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+        warp::path!("api" / "v1" / "cdc")
+            .and(warp::get())
+            .and(self.handle_header())
+            .and(warp::query::<ChangeFeedParam>())
+            .and(self.with_dbms())
+            .and(self.with_coord())
+            .and(self.with_http_metrics())
+            .and(self.with_hostaddr())
+            .and_then(
+                |header: Header,
+                 param: ChangeFeedParam,
+                 dbms: DBMSRef,
+                 coord: CoordinatorRef,
+                 metrics: Arc<HttpMetrics>,
+                 addr: String| async move {
+                    let start = Instant::now();
+                    debug!(
+                        "Receive http change feed request, header: {:?}, param: {:?}",
+                        header, param
+                    );
+
+                    let position = param
+                        .position
+                        .as_deref()
+                        .map(|e| {
+                            e.parse::<ChangeFeedPosition>()
+                                .map_err(|reason| HttpError::InvalidHeader { reason })
+                        })
+                        .transpose()
+                        .map_err(reject::custom)?
+                        .unwrap_or_default();
+                    let ctx = construct_change_feed_context(
+                        &header,
+                        param.tenant,
+                        param.db,
+                        dbms,
+                        coord.clone(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        error!("Failed to construct change feed context, err: {:?}", e);
+                        reject::custom(e)
+                    })?;
+
+                    let mut reader = ChangeFeedReader {
+                        coord,
+                        tenant: ctx.tenant().to_string(),
+                        db: ctx.database().to_string(),
+                        table: param.table,
+                        position,
+                    };
+                    // Read the first chunk before responding, so that a failure gets an error status.
+                    let first = reader.read_chunk().await.map_err(|e| {
+                        error!("Failed to read change feed, err: {:?}", e);
+                        reject::custom(e)
+                    })?;
+                    let first = first.unwrap_or_else(|| reader.position_line());
+
+                    http_response_time_and_flow_metrics(
+                        &metrics,
+                        &addr,
+                        first.len(),
+                        start,
+                        HttpApiType::ApiV1Cdc,
+                    );
+
+                    let follow = param.follow.unwrap_or(false);
+                    let rest = futures::stream::unfold(Some(reader), move |reader| async move {
+                        let mut reader = reader?;
+                        loop {
+                            match reader.read_chunk().await {
+                                Ok(Some(chunk)) => return Some((chunk, Some(reader))),
+                                Ok(None) if follow => {
+                                    tokio::time::sleep(CHANGE_FEED_POLL_INTERVAL).await
+                                }
+                                Ok(None) => return None,
+                                Err(e) => {
+                                    error!("Failed to read change feed, err: {:?}", e);
+                                    let mut line = ErrorResponse::new(e.error_code()).to_vec();
+                                    line.push(b'\n');
+                                    return Some((line, None));
+                                }
+                            }
+                        }
+                    });
+                    let stream = futures::stream::once(async move { first })
+                        .chain(rest)
+                        .map(Ok::<_, Infallible>);
+
+                    let resp = ResponseBuilder::new(OK)
+                        .insert_header((CONTENT_TYPE, APPLICATION_NDJSON))
+                        .build_stream_response(Response::new(Body::wrap_stream(stream)));
+                    Ok::<_, Rejection>(resp)
+                },
+            )
+    }
+
     fn get_es_version(
         &self,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
@@ -1560,6 +1881,7 @@ impl HttpService {
                         precision: None,
                         tenant: param.tenant,
                         db: param.db,
+                        consistency: None,
                     };
 
                     if param.table.is_none() {
@@ -1616,6 +1938,7 @@ impl HttpService {
                         logs,
                         param.time_column,
                         param.tag_columns,
+                        ctx.write_consistency(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -1723,6 +2046,7 @@ impl HttpService {
                         precision: None,
                         tenant: header.get_tenant(),
                         db: header.get_db(),
+                        consistency: None,
                     };
                     let ctx = {
                         let mut span = Span::enter_with_parent("construct write context", &span);
@@ -1774,6 +2098,7 @@ impl HttpService {
                         logs,
                         Some(time_column),
                         Some(tag_columns),
+                        ctx.write_consistency(),
                         span_context.as_ref(),
                     )
                     .await;
@@ -1838,6 +2163,8 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
+                        follower_read_max_lag: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2082,6 +2409,8 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
+                        follower_read_max_lag: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2156,6 +2485,8 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
+                        follower_read_max_lag: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2282,6 +2613,8 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
+                        follower_read_max_lag: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2426,6 +2759,8 @@ impl HttpService {
                         chunked: None,
                         target_partitions: None,
                         stream_trigger_interval: None,
+                        consistency: None,
+                        follower_read_max_lag: None,
                     };
                     let _ = construct_read_context(&header, sql_param, dbms, coord.clone(), false)
                         .await
@@ -2626,6 +2961,32 @@ async fn construct_query(
     ))
 }
 
+/// Authenticates the request by its API token, JWT or basic auth
+async fn authenticate_user(
+    header: &Header,
+    tenant: Option<&str>,
+    dbms: &DBMSRef,
+) -> Result<User, HttpError> {
+    let tenant = tenant.unwrap_or(DEFAULT_CATALOG);
+    if let Some(token) = header.get_api_token() {
+        return dbms
+            .authenticate_token(token, tenant)
+            .await
+            .context(QuerySnafu);
+    }
+    if let Some(token) = header.get_jwt() {
+        return dbms
+            .authenticate_jwt(token, tenant)
+            .await
+            .context(QuerySnafu);
+    }
+
+    let user_info = header.try_get_basic_auth()?;
+    dbms.authenticate(&user_info, tenant)
+        .await
+        .context(QuerySnafu)
+}
+
 async fn construct_read_context(
     header: &Header,
     param: SqlParam,
@@ -2633,13 +2994,8 @@ async fn construct_read_context(
     coord: CoordinatorRef,
     is_sql: bool,
 ) -> Result<Context, HttpError> {
-    let user_info = header.try_get_basic_auth()?;
-
     let tenant = param.tenant;
-    let user = dbms
-        .authenticate(&user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
-        .await
-        .context(QuerySnafu)?;
+    let user = authenticate_user(header, tenant.as_deref(), &dbms).await?;
 
     if !is_sql
         && coord.get_config().query.auth_enabled
@@ -2659,6 +3015,7 @@ async fn construct_read_context(
     let context = ContextBuilder::new(user)
         .with_tenant(tenant)
         .with_database(param.db)
+        .with_client_addr(header.get_client_addr())
         .with_target_partitions(param.target_partitions)
         .with_chunked(param.chunked)
         .with_stream_trigger_interval(
@@ -2670,30 +3027,50 @@ async fn construct_read_context(
                 })
                 .transpose()?,
         )
+        .with_write_consistency(parse_consistency(
+            param.consistency.or_else(|| header.get_consistency()),
+        )?)
+        .with_follower_read_max_lag(
+            param
+                .follower_read_max_lag
+                .map(|ref e| {
+                    e.parse::<FollowerReadLag>()
+                        .map_err(|reason| HttpError::InvalidHeader { reason })
+                })
+                .transpose()?,
+        )
         .build();
 
     Ok(context)
 }
 
+fn parse_consistency(consistency: Option<String>) -> Result<Option<ConsistencyLevel>, HttpError> {
+    consistency
+        .map(|e| {
+            e.parse::<ConsistencyLevel>()
+                .map_err(|reason| HttpError::InvalidHeader { reason })
+        })
+        .transpose()
+}
+
 async fn construct_write_context(
     header: &Header,
     param: WriteParam,
     dbms: DBMSRef,
 ) -> Result<Context, HttpError> {
-    let user_info = header.try_get_basic_auth()?;
     let tenant = param.tenant;
     let db = param.db;
     let precision = param.precision;
+    let consistency = parse_consistency(param.consistency.or_else(|| header.get_consistency()))?;
 
-    let user = dbms
-        .authenticate(&user_info, tenant.as_deref().unwrap_or(DEFAULT_CATALOG))
-        .await
-        .context(QuerySnafu)?;
+    let user = authenticate_user(header, tenant.as_deref(), &dbms).await?;
 
     let context = ContextBuilder::new(user)
         .with_tenant(tenant)
         .with_database(db)
         .with_precision(precision)
+        .with_write_consistency(consistency)
+        .with_client_addr(header.get_client_addr())
         .build();
 
     Ok(context)
@@ -2756,6 +3133,112 @@ async fn construct_write_context_and_check_privilege(
     Ok(context)
 }
 
+/// Read the change feed of a database in chunks of NDJSON lines, each chunk
+/// ends with a line holding the position to resume from.
+struct ChangeFeedReader {
+    coord: CoordinatorRef,
+    tenant: String,
+    db: String,
+    table: Option<String>,
+    position: ChangeFeedPosition,
+}
+
+#[derive(serde::Serialize)]
+struct ChangeFeedLine<'a> {
+    replica_id: u32,
+    index: u64,
+    apply_time: i64,
+    #[serde(flatten)]
+    event: &'a ChangeEvent,
+}
+
+impl ChangeFeedReader {
+    /// Read the next changes, None if there are no new changes.
+    async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, HttpError> {
+        let records = self
+            .coord
+            .read_change_feed(
+                &self.tenant,
+                &self.db,
+                &self.position,
+                CHANGE_FEED_CHUNK_BYTES,
+            )
+            .await
+            .context(CoordinatorSnafu)?;
+        if records.is_empty() {
+            return Ok(None);
+        }
+
+        let mut buffer = Vec::new();
+        for record in records.iter() {
+            for event in record.events().context(CoordinatorSnafu)? {
+                if self.table.as_ref().is_some_and(|t| t != event.table()) {
+                    continue;
+                }
+                let line = ChangeFeedLine {
+                    replica_id: record.replica_id,
+                    index: record.index,
+                    apply_time: record.apply_time,
+                    event: &event,
+                };
+                serde_json::to_writer(&mut buffer, &line).map_err(|e| HttpError::FetchResult {
+                    reason: e.to_string(),
+                })?;
+                buffer.push(b'\n');
+            }
+            self.position.advance(record.replica_id, record.index);
+        }
+        buffer.extend(self.position_line());
+
+        Ok(Some(buffer))
+    }
+
+    fn position_line(&self) -> Vec<u8> {
+        format!("{{\"position\":\"{}\"}}\n", self.position).into_bytes()
+    }
+}
+
+async fn construct_change_feed_context(
+    header: &Header,
+    tenant: Option<String>,
+    db: Option<String>,
+    dbms: DBMSRef,
+    coord: CoordinatorRef,
+) -> Result<Context, HttpError> {
+    let user = authenticate_user(header, tenant.as_deref(), &dbms).await?;
+    let context = ContextBuilder::new(user)
+        .with_tenant(tenant)
+        .with_database(db)
+        .with_client_addr(header.get_client_addr())
+        .build();
+
+    let tenant_id = *coord
+        .tenant_meta(context.tenant())
+        .await
+        .ok_or_else(|| MetaError::TenantNotFound {
+            tenant: context.tenant().to_string(),
+        })
+        .context(MetaSnafu)?
+        .tenant()
+        .id();
+    let privilege = Privilege::TenantObject(
+        TenantObjectPrivilege::Database(
+            DatabasePrivilege::Read,
+            Some(context.database().to_string()),
+        ),
+        Some(tenant_id),
+    );
+    if !context.user().check_privilege(&privilege) {
+        return Err(HttpError::Query {
+            source: QueryError::InsufficientPrivileges {
+                privilege: format!("{privilege}"),
+            },
+        });
+    }
+
+    Ok(context)
+}
+
 fn try_parse_req_to_lines(req: &Bytes) -> Result<Vec<Line>, HttpError> {
     let lines = simdutf8::basic::from_utf8(req.as_ref())
         .map_err(|e| HttpError::InvalidUTF8 { source: e })?;
@@ -2848,6 +3331,7 @@ async fn coord_write_log(
     logs: Vec<JsonProtocol>,
     time_column: Option<String>,
     tag_columns: Option<String>,
+    consistency: ConsistencyLevel,
     span_context: Option<&SpanContext>,
 ) -> Result<Response, HttpError> {
     let span = Span::from_context("write points", span_context);
@@ -2864,7 +3348,14 @@ async fn coord_write_log(
     }
 
     coord
-        .write_lines(tenant, db, Precision::NS, lines, span.context().as_ref())
+        .write_lines(
+            tenant,
+            db,
+            Precision::NS,
+            lines,
+            consistency,
+            span.context().as_ref(),
+        )
         .await
         .inspect_err(|e| {
             span.error(e.to_string());
@@ -2880,6 +3371,7 @@ async fn coord_write_points_with_span_recorder(
     db: &str,
     precision: Precision,
     write_points_lines: Vec<Line<'_>>,
+    consistency: ConsistencyLevel,
     span_context: Option<&SpanContext>,
 ) -> Result<usize, HttpError> {
     let span = Span::from_context("write points", span_context);
@@ -2889,6 +3381,7 @@ async fn coord_write_points_with_span_recorder(
             db,
             precision,
             write_points_lines,
+            consistency,
             span.context().as_ref(),
         )
         .await
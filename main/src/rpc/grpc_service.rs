@@ -1,13 +1,16 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use config::common::InternalTlsConfig;
 use config::tskv::TLSConfig;
 use coordinator::service::CoordinatorRef;
 use metrics::metric_register::MetricsRegister;
 use protos::kv_service::tskv_service_server::TskvServiceServer;
 use protos::raft_service::raft_service_server::RaftServiceServer;
+use protos::tls::InternalTlsAcceptor;
 use protos::DEFAULT_GRPC_SERVER_MESSAGE_LEN;
 use replication::network_grpc::RaftCBServer;
+use tokio::net::TcpListener;
 use tokio::runtime::Runtime;
 use tokio::sync::oneshot;
 use tonic::codec::CompressionEncoding;
@@ -26,6 +29,7 @@ pub struct GrpcService {
     kv_inst: EngineRef,
     coord: CoordinatorRef,
     tls_config: Option<TLSConfig>,
+    internal_tls: Option<InternalTlsConfig>,
     metrics_register: Arc<MetricsRegister>,
     auto_generate_span: bool,
     handle: Option<ServiceHandle<Result<(), tonic::transport::Error>>>,
@@ -39,6 +43,7 @@ impl GrpcService {
         coord: CoordinatorRef,
         addr: SocketAddr,
         tls_config: Option<TLSConfig>,
+        internal_tls: Option<InternalTlsConfig>,
         metrics_register: Arc<MetricsRegister>,
         auto_generate_span: bool,
         enable_gzip: bool,
@@ -49,6 +54,7 @@ impl GrpcService {
             kv_inst,
             coord,
             tls_config,
+            internal_tls,
             metrics_register,
             auto_generate_span,
             handle: None,
@@ -105,17 +111,41 @@ impl Service for GrpcService {
                 .send_compressed(CompressionEncoding::Gzip);
         }
 
-        let mut grpc_builder =
-            build_grpc_server!(&self.tls_config, self.auto_generate_span, "grpc");
+        // The certificates of internal tls are verified by the acceptor.
+        let tls_config = match self.internal_tls {
+            Some(_) => None,
+            None => self.tls_config.clone(),
+        };
+        let mut grpc_builder = build_grpc_server!(&tls_config, self.auto_generate_span, "grpc");
         let grpc_router = grpc_builder
             .add_service(tskv_grpc_service)
             .add_service(raft_grpc_service);
-        let server = grpc_router.serve_with_shutdown(self.addr, async {
+        let signal = async {
             rx.await.ok();
             info!("grpc server graceful shutdown!");
-        });
+        };
         info!("grpc server start addr: {}", self.addr);
-        let grpc_handle = tokio::spawn(server);
+        let grpc_handle = match &self.internal_tls {
+            Some(internal_tls) => {
+                let acceptor = InternalTlsAcceptor::try_new(internal_tls.clone())?;
+                let std_listener = std::net::TcpListener::bind(self.addr)?;
+                std_listener.set_nonblocking(true)?;
+                let listener = TcpListener::from_std(std_listener)?;
+                // Only the nodes registered in meta and the nodes of the clusters
+                // replicating to this one are accepted.
+                let meta = self.coord.meta_manager();
+                let replication_peers = self.coord.get_config().cluster.replication_peers;
+                let incoming = acceptor.incoming(
+                    listener,
+                    Arc::new(move |names: &[String]| {
+                        meta.is_cluster_node(names)
+                            || names.iter().any(|name| replication_peers.contains(name))
+                    }),
+                );
+                tokio::spawn(grpc_router.serve_with_incoming_shutdown(incoming, signal))
+            }
+            None => tokio::spawn(grpc_router.serve_with_shutdown(self.addr, signal)),
+        };
         self.handle = Some(ServiceHandle::new(
             "grpc service".to_string(),
             grpc_handle,
@@ -1,13 +1,18 @@
 use std::pin::Pin;
 use std::sync::Arc;
 
+use coordinator::cross_cluster::{
+    authenticate_source, CROSS_CLUSTER_METADATA_KEY, CROSS_CLUSTER_SECRET_METADATA_KEY,
+};
 use coordinator::errors::{
-    encode_grpc_response, ArrowSnafu, CommonSnafu, CoordinatorResult, TskvSnafu,
+    encode_grpc_response, ArrowSnafu, BincodeSerdeSnafu, CommonSnafu, CoordinatorResult, TskvSnafu,
 };
+use coordinator::raft::writer::WRITE_CONSISTENCY_METADATA_KEY;
 use coordinator::service::CoordinatorRef;
 use futures::{Stream, TryStreamExt};
 use meta::model::MetaRef;
 use metrics::metric_register::MetricsRegister;
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::VnodeInfo;
 use models::predicate::domain::{self, PushedAggregateFunction, QueryArgs, QueryExpr};
 use models::record_batch_encode;
@@ -21,11 +26,11 @@ use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Extensions, Request, Response, Status};
 use trace::span_ext::SpanExt;
-use trace::{debug, error, info, Span, SpanContext};
+use trace::{debug, error, info, warn, Span, SpanContext};
 use tskv::error::TskvResult;
 use tskv::reader::query_executor::QueryExecutor;
 use tskv::reader::serialize::TonicRecordBatchEncoder;
-use tskv::reader::{QueryOption, SendableTskvRecordBatchStream};
+use tskv::reader::{QueryOption, ScanStats, SendableTskvRecordBatchStream};
 use tskv::EngineRef;
 
 type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send>>;
@@ -151,6 +156,42 @@ impl TskvServiceImpl {
                     .await?;
                 Ok(vec![])
             }
+            admin_command::Command::FetchFollowerLag(command) => {
+                let progress = self
+                    .coord
+                    .raft_manager()
+                    .replica_progress(command.replica_id)
+                    .await?;
+                let data = bincode::serialize(&progress).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::FetchChangeLog(command) => {
+                let slice = self
+                    .coord
+                    .raft_manager()
+                    .change_logs()
+                    .read(
+                        command.vnode_id,
+                        command.after_index,
+                        command.max_bytes as usize,
+                    )
+                    .await?;
+                let data = bincode::serialize(&slice).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
+            admin_command::Command::FetchVnodeVersion(command) => {
+                let mut versions = Vec::with_capacity(command.vnode_ids.len());
+                for vnode_id in command.vnode_ids.iter() {
+                    let version = self
+                        .kv_inst
+                        .get_vnode_data_version(*vnode_id)
+                        .await
+                        .context(TskvSnafu)?;
+                    versions.push((*vnode_id, version));
+                }
+                let data = bincode::serialize(&versions).context(BincodeSerdeSnafu)?;
+                Ok(data)
+            }
         }
     }
 
@@ -159,9 +200,10 @@ impl TskvServiceImpl {
         args: QueryArgs,
         expr: QueryExpr,
         aggs: Option<Vec<PushedAggregateFunction>>,
+        scan_stats: ScanStats,
         span_ctx: Option<&SpanContext>,
     ) -> TskvResult<SendableTskvRecordBatchStream> {
-        let option = QueryOption::new(
+        let mut option = QueryOption::new(
             args.batch_size,
             expr.split,
             aggs,
@@ -169,6 +211,7 @@ impl TskvServiceImpl {
             expr.table_schema,
             expr.schema_meta,
         );
+        option.scan_stats = scan_stats;
 
         let meta = self.coord.meta_manager();
         let node_id = meta.node_id();
@@ -246,6 +289,45 @@ impl TskvService for TskvServiceImpl {
         &self,
         request: tonic::Request<RaftWriteCommand>,
     ) -> Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
+        if let Some(source) = request.metadata().get(CROSS_CLUSTER_METADATA_KEY) {
+            let source = source
+                .to_str()
+                .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?
+                .to_string();
+            let secret = request
+                .metadata()
+                .get(CROSS_CLUSTER_SECRET_METADATA_KEY)
+                .and_then(|value| value.to_str().ok());
+            if let Err(err) = authenticate_source(&self.coord.get_config().cluster, &source, secret)
+            {
+                warn!(
+                    "refuse raft write replicated from cluster {}: {}",
+                    source, err
+                );
+                return Ok(encode_grpc_response(Err(err)));
+            }
+
+            debug!("receive raft write replicated from cluster {}", source);
+            let result = self
+                .coord
+                .write_replicated(request.into_inner())
+                .await
+                .map(|_| vec![]);
+            return Ok(encode_grpc_response(result));
+        }
+
+        let consistency = request
+            .metadata()
+            .get(WRITE_CONSISTENCY_METADATA_KEY)
+            .map(|value| {
+                value
+                    .to_str()
+                    .map_err(|e| e.to_string())
+                    .and_then(|value| value.parse::<ConsistencyLevel>())
+            })
+            .transpose()
+            .map_err(tonic::Status::invalid_argument)?
+            .unwrap_or_default();
         let inner = request.into_inner();
 
         let client = self.coord.tenant_meta(&inner.tenant).await.ok_or_else(|| {
@@ -260,7 +342,7 @@ impl TskvService for TskvServiceImpl {
                 self.internal_status(format!("Not Found Replica Set({})", inner.replica_id))
             })?;
 
-        let writer = self.coord.tskv_raft_writer(inner);
+        let writer = self.coord.tskv_raft_writer(inner, consistency);
         let result = writer.write_to_local(&replica).await;
 
         Ok(encode_grpc_response(result))
@@ -283,6 +365,26 @@ impl TskvService for TskvServiceImpl {
         }
     }
 
+    async fn fetch_inventory(
+        &self,
+        _request: tonic::Request<FetchInventoryRequest>,
+    ) -> std::result::Result<tonic::Response<BatchBytesResponse>, tonic::Status> {
+        let result = self
+            .coord
+            .raft_manager()
+            .node_inventory()
+            .await
+            .and_then(|inventory| {
+                serde_json::to_vec(&inventory).map_err(|err| {
+                    CommonSnafu {
+                        msg: format!("encode node inventory failed: {}", err),
+                    }
+                    .build()
+                })
+            });
+        Ok(encode_grpc_response(result))
+    }
+
     type DownloadFileStream = ResponseStream<BatchBytesResponse>;
     async fn download_file(
         &self,
@@ -306,6 +408,7 @@ impl TskvService for TskvServiceImpl {
                         .send(Ok(BatchBytesResponse {
                             code: coordinator::errors::SUCCESS_RESPONSE_CODE,
                             data: (buffer[0..len]).to_vec(),
+                            scan_stats: None,
                         }))
                         .await;
                 }
@@ -342,6 +445,7 @@ impl TskvService for TskvServiceImpl {
         };
 
         let service = self.clone();
+        let scan_stats = ScanStats::new();
 
         let encoded_stream = {
             let span = Span::enter_with_parent("RecordBatch encorder stream", &span);
@@ -351,9 +455,14 @@ impl TskvService for TskvServiceImpl {
                 args,
                 expr,
                 aggs,
+                scan_stats.clone(),
                 span.context().as_ref(),
             )?;
-            TonicRecordBatchEncoder::new(stream, span).map_err(Into::into)
+            let mut encoder = TonicRecordBatchEncoder::new(stream, span);
+            if inner.with_scan_stats {
+                encoder = encoder.with_scan_stats(scan_stats);
+            }
+            encoder.map_err(Into::into)
         };
 
         Ok(tonic::Response::new(Box::pin(encoded_stream)))
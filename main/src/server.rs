@@ -196,7 +196,9 @@ impl ServiceBuilder {
             server.add_service(Box::new(http_service));
         }
 
-        if let Some(flight_sql_service) = self.create_flight_sql_if_enabled(dbms.clone()) {
+        if let Some(flight_sql_service) =
+            self.create_flight_sql_if_enabled(dbms.clone(), coord.clone())
+        {
             server.add_service(Box::new(flight_sql_service));
         }
 
@@ -238,7 +240,9 @@ impl ServiceBuilder {
             server.add_service(Box::new(grpc_service));
         }
 
-        if let Some(flight_sql_service) = self.create_flight_sql_if_enabled(dbms.clone()) {
+        if let Some(flight_sql_service) =
+            self.create_flight_sql_if_enabled(dbms.clone(), coord.clone())
+        {
             server.add_service(Box::new(flight_sql_service));
         }
 
@@ -390,6 +394,7 @@ impl ServiceBuilder {
             coord,
             addr,
             None,
+            self.config.security.internal_tls.clone(),
             self.metrics_register.clone(),
             self.config.trace.auto_generate_span,
             self.config.service.grpc_enable_gzip,
@@ -405,7 +410,11 @@ impl ServiceBuilder {
         Some(TcpService::new(coord, default_tcp_addr))
     }
 
-    fn create_flight_sql_if_enabled(&self, dbms: DBMSRef) -> Option<FlightSqlServiceAdapter> {
+    fn create_flight_sql_if_enabled(
+        &self,
+        dbms: DBMSRef,
+        coord: CoordinatorRef,
+    ) -> Option<FlightSqlServiceAdapter> {
         let default_flight_sql_addr = match self.config.service.flight_rpc_listen_port {
             Some(port) => build_default_address(port),
             None => return None,
@@ -428,6 +437,7 @@ impl ServiceBuilder {
 
         Some(FlightSqlServiceAdapter::new(
             dbms,
+            coord,
             addr,
             tls_config,
             self.config.trace.auto_generate_span,
@@ -8,6 +8,7 @@ use meta::meta_cluster_command::add_node::add_node;
 use meta::meta_cluster_command::dump::dump;
 use meta::meta_cluster_command::dumpsql::dumpsql;
 use meta::meta_cluster_command::meta_init::meta_init;
+use meta::meta_cluster_command::rebuild::rebuild;
 use meta::meta_cluster_command::remove_node::remove_node;
 use meta::meta_cluster_command::restore::restore;
 use meta::meta_cluster_command::show_nodes::show_nodes;
@@ -22,7 +23,7 @@ struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Configuration file path
+    /// Configuration file path, `rebuild` reads the internal tls from it
     #[arg(short, long)]
     config: Option<String>,
 }
@@ -85,6 +86,18 @@ enum Commands {
         #[arg(long)]
         file: String,
     },
+    /// Rebuild the cluster's meta resources from the vnodes on data nodes
+    Rebuild {
+        /// Address of the cluster leader node
+        #[arg(long)]
+        bind: String,
+        /// Name of the cluster
+        #[arg(long)]
+        cluster: String,
+        /// gRPC addresses of the data nodes, separated by commas
+        #[arg(long, value_delimiter = ',')]
+        data_nodes: Vec<String>,
+    },
     /// List information about all nodes in the cluster
     ShowNodes {
         /// Address of the node
@@ -138,6 +151,26 @@ async fn main() {
                 eprintln!("Error exporting meta service: {}", e);
             }
         }
+        Some(Commands::Rebuild {
+            bind,
+            cluster,
+            data_nodes,
+        }) => {
+            // the internal tls of the meta and data nodes is read from the configuration
+            let internal_tls = match cli.config {
+                Some(config_path) => match config::meta::get_opt(Some(config_path)) {
+                    Ok(opt) => opt.security.internal_tls,
+                    Err(e) => {
+                        eprintln!("Error loading config: {}", e);
+                        process::exit(1);
+                    }
+                },
+                None => None,
+            };
+            if let Err(e) = rebuild(&bind, &cluster, &data_nodes, internal_tls.as_ref()).await {
+                eprintln!("Error rebuilding meta service: {}", e);
+            }
+        }
         Some(Commands::ShowNodes { bind }) => {
             if let Err(e) = show_nodes(&bind).await {
                 eprintln!("Error showing nodes: {}", e);
@@ -2,12 +2,13 @@ use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Instant;
 
+use config::common::InternalTlsConfig;
 use metrics::count::U64Counter;
 use metrics::duration::{DurationHistogram, DurationHistogramOptions};
 use metrics::metric_register::MetricsRegister;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::error::{MetaError, MetaResult};
 use crate::limiter::local_request_limiter::{LocalBucketRequest, LocalBucketResponse};
@@ -16,7 +17,8 @@ use crate::store::key_path::KeyPath;
 
 #[derive(Debug, Clone)]
 pub struct MetaHttpClient {
-    inner: Arc<reqwest::Client>,
+    inner: Arc<RwLock<reqwest::Client>>,
+    internal_tls: Option<InternalTlsConfig>,
     pub addrs: Arc<RwLock<Vec<String>>>,
     pub leader: Arc<RwLock<String>>,
     read_meta_count: U64Counter,
@@ -68,7 +70,8 @@ impl MetaHttpClient {
         let leader_addr = addrs[0].clone();
 
         Self {
-            inner: Arc::new(reqwest::Client::new()),
+            inner: Arc::new(RwLock::new(reqwest::Client::new())),
+            internal_tls: None,
             addrs: Arc::new(RwLock::new(addrs)),
             leader: Arc::new(RwLock::new(leader_addr)),
             read_meta_count,
@@ -80,6 +83,72 @@ impl MetaHttpClient {
         }
     }
 
+    /// Connect to the meta servers with mutual TLS, the certificates are reloaded
+    /// in background when their files are modified.
+    pub fn with_internal_tls(mut self, tls: Option<&InternalTlsConfig>) -> MetaResult<Self> {
+        let Some(tls) = tls else {
+            return Ok(self);
+        };
+        self.inner = Arc::new(RwLock::new(Self::build_tls_client(tls)?));
+        self.internal_tls = Some(tls.clone());
+
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let inner = Arc::downgrade(&self.inner);
+            let tls = tls.clone();
+            runtime.spawn(async move {
+                let mut modified = protos::tls::modified_times(&tls);
+                loop {
+                    tokio::time::sleep(tls.reload_interval).await;
+                    let Some(inner) = inner.upgrade() else {
+                        break;
+                    };
+                    let current = protos::tls::modified_times(&tls);
+                    if current == modified {
+                        continue;
+                    }
+                    match Self::build_tls_client(&tls) {
+                        Ok(client) => {
+                            *inner.write() = client;
+                            modified = current;
+                            info!("Reloaded the certificates of meta client");
+                        }
+                        Err(err) => {
+                            warn!("Failed to reload the certificates of meta client: {}", err)
+                        }
+                    }
+                }
+            });
+        }
+
+        Ok(self)
+    }
+
+    fn build_tls_client(tls: &InternalTlsConfig) -> MetaResult<reqwest::Client> {
+        Self::tls_client_builder(tls)?
+            .build()
+            .map_err(|e| MetaError::MetaClientErr { msg: e.to_string() })
+    }
+
+    /// A client builder trusting the cluster CA and presenting the certificate of this node.
+    pub(crate) fn tls_client_builder(
+        tls: &InternalTlsConfig,
+    ) -> MetaResult<reqwest::ClientBuilder> {
+        let client_err = |msg: String| MetaError::MetaClientErr { msg };
+
+        let ca = std::fs::read(&tls.ca_certificate).map_err(|e| client_err(e.to_string()))?;
+        let ca = reqwest::Certificate::from_pem(&ca).map_err(|e| client_err(e.to_string()))?;
+        let identity =
+            protos::tls::client_identity_pem(tls).map_err(|e| client_err(e.to_string()))?;
+        let identity =
+            reqwest::Identity::from_pem(&identity).map_err(|e| client_err(e.to_string()))?;
+
+        Ok(reqwest::Client::builder()
+            .use_rustls_tls()
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(ca)
+            .identity(identity))
+    }
+
     pub async fn read<T>(&self, req: &ReadCommand) -> MetaResult<T>
     where
         T: for<'a> Deserialize<'a>,
@@ -235,10 +304,15 @@ impl MetaHttpClient {
     where
         Req: Serialize + 'static,
     {
-        let url = format!("http://{}/{}", self.leader.read(), uri);
+        let url = format!(
+            "{}://{}/{}",
+            protos::tls::scheme(self.internal_tls.as_ref()),
+            self.leader.read(),
+            uri
+        );
 
-        let resp = self
-            .inner
+        let client = self.inner.read().clone();
+        let resp = client
             .post(url.clone())
             .json(req)
             .send()
@@ -261,6 +335,36 @@ impl MetaHttpClient {
         }
     }
 
+    /// Send a GET request to the meta server `addr`.
+    pub async fn get(&self, addr: &str, uri: &str) -> MetaResult<String> {
+        let url = format!(
+            "{}://{}/{}",
+            protos::tls::scheme(self.internal_tls.as_ref()),
+            addr,
+            uri
+        );
+
+        let client = self.inner.read().clone();
+        let resp = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| MetaError::MetaClientErr { msg: e.to_string() })?;
+        let status = resp.status();
+
+        let data = resp
+            .text()
+            .await
+            .map_err(|e| MetaError::MetaClientErr { msg: e.to_string() })?;
+
+        if !status.is_success() {
+            return Err(MetaError::MetaClientErr {
+                msg: format!("httpcode: {}, response:{}", status, data),
+            });
+        }
+        Ok(data)
+    }
+
     pub async fn limiter_request(
         &self,
         cluster: &str,
@@ -182,6 +182,30 @@ pub enum MetaError {
     #[snafu(display("cannot revoke the privilege {privilege} of role"))]
     #[error_code(code = 56)]
     PrivilegeCannotRevoke { privilege: TenantObjectPrivilege },
+
+    #[snafu(display("The function {} already exists", name))]
+    #[error_code(code = 57)]
+    FunctionAlreadyExists { name: String },
+
+    #[snafu(display("The function {} not found", name))]
+    #[error_code(code = 58)]
+    FunctionNotFound { name: String },
+
+    #[snafu(display("The token {} already exists", name))]
+    #[error_code(code = 59)]
+    TokenAlreadyExists { name: String },
+
+    #[snafu(display("The token {} not found", name))]
+    #[error_code(code = 60)]
+    TokenNotFound { name: String },
+
+    #[snafu(display("The policy {} already exists", name))]
+    #[error_code(code = 61)]
+    PolicyAlreadyExists { name: String },
+
+    #[snafu(display("The policy {} not found", name))]
+    #[error_code(code = 62)]
+    PolicyNotFound { name: String },
 }
 
 impl MetaError {
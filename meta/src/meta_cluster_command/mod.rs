@@ -3,6 +3,7 @@ pub mod dump;
 pub mod dumpsql;
 pub mod meta_http_client;
 pub mod meta_init;
+pub mod rebuild;
 pub mod remove_node;
 pub mod restore;
 pub mod show_nodes;
@@ -0,0 +1,411 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use config::common::InternalTlsConfig;
+use models::meta_data::{
+    get_time_range, BucketInfo, NodeInventory, ReplicationSet, ReplicationSetId, VnodeId, VnodeInfo,
+};
+use models::oid::UuidGenerator;
+use models::predicate::domain::TimeRange;
+use models::schema::database_schema::{DatabaseConfig, DatabaseOptions, DatabaseSchema};
+use models::schema::table_schema::TableSchema;
+use models::schema::tenant::{Tenant, TenantOptions};
+use models::schema::tskv_table_schema::TskvTableSchema;
+use protos::kv_service::FetchInventoryRequest;
+use reqwest::{Client, ClientBuilder};
+
+use crate::client::MetaHttpClient;
+use crate::error::MetaResult;
+use crate::store::key_path::KeyPath;
+use crate::store::storage::value_encode;
+
+// Response code of a successful tskv service request.
+const SUCCESS_RESPONSE_CODE: i32 = 1;
+
+/// Meta data rebuilt from the inventories of data nodes.
+#[derive(Debug, Default)]
+pub struct RebuiltMeta {
+    pub data: BTreeMap<String, String>,
+    // replication sets whose vnodes hold no data and whose bucket is unknown
+    pub unplaced: Vec<ReplicationSetId>,
+}
+
+#[derive(Default)]
+struct ReplicaEntry {
+    vnodes: Vec<VnodeInfo>,
+    leaders: Vec<VnodeId>,
+    time_range: Option<TimeRange>,
+    bucket: Option<(u32, i64, i64)>,
+}
+
+#[derive(Default)]
+struct DatabaseEntry {
+    schema: Option<DatabaseSchema>,
+    tables: HashMap<String, TskvTableSchema>,
+    replicas: BTreeMap<ReplicationSetId, ReplicaEntry>,
+}
+
+pub async fn rebuild(
+    bind: &str,
+    cluster: &str,
+    data_nodes: &[String],
+    internal_tls: Option<&InternalTlsConfig>,
+) -> Result<(), Box<dyn Error>> {
+    let mut inventories = Vec::with_capacity(data_nodes.len());
+    for addr in data_nodes {
+        let inventory = fetch_inventory(addr, internal_tls).await?;
+        println!(
+            "Data node {}({}): {} vnodes",
+            inventory.node_id,
+            addr,
+            inventory.vnodes.len()
+        );
+        inventories.push(inventory);
+    }
+
+    let rebuilt = rebuild_meta_data(cluster, &inventories)?;
+    for id in rebuilt.unplaced.iter() {
+        println!("Skip replication set {}: no data and unknown bucket", id);
+    }
+
+    // keep what the fresh meta cluster already has, e.g. the system tenant
+    let existing = fetch_dump(bind, internal_tls).await?;
+    let incr_id_key = KeyPath::incr_id(cluster);
+    let mut body = String::new();
+    for (key, val) in rebuilt.data.iter() {
+        if key == &incr_id_key {
+            let existing_id = existing
+                .get(key)
+                .and_then(|id| id.parse::<u32>().ok())
+                .unwrap_or(1);
+            let id = val.parse::<u32>().unwrap_or(1).max(existing_id);
+            body += &format!("{}: {}\n", key, id);
+        } else if !existing.contains_key(key) {
+            body += &format!("{}: {}\n", key, val);
+        }
+    }
+
+    let client = http_client_builder(internal_tls)?
+        .timeout(Duration::from_secs(600))
+        .build()?;
+    let url = format!("{}://{}/restore", protos::tls::scheme(internal_tls), bind);
+    let response = client.post(&url).body(body).send().await?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to rebuild meta data: {}", response.status()).into());
+    }
+
+    let response_body = response.text().await?;
+    if response_body.contains("Err") {
+        return Err(format!("Error in rebuild response: {:?}", response_body).into());
+    }
+    println!("{}", response_body);
+
+    Ok(())
+}
+
+fn http_client_builder(
+    internal_tls: Option<&InternalTlsConfig>,
+) -> Result<ClientBuilder, Box<dyn Error>> {
+    match internal_tls {
+        Some(tls) => Ok(MetaHttpClient::tls_client_builder(tls)?),
+        None => Ok(Client::builder()),
+    }
+}
+
+async fn fetch_inventory(
+    addr: &str,
+    internal_tls: Option<&InternalTlsConfig>,
+) -> Result<NodeInventory, Box<dyn Error>> {
+    let channel = protos::tls::endpoint(addr, internal_tls)?
+        .connect()
+        .await
+        .map_err(|e| format!("connect to {} failed: {}", addr, e))?;
+    let mut client =
+        protos::tskv_service_time_out_client(channel, Duration::from_secs(600), usize::MAX, false);
+
+    let response = client
+        .fetch_inventory(FetchInventoryRequest {})
+        .await?
+        .into_inner();
+    if response.code != SUCCESS_RESPONSE_CODE {
+        return Err(format!(
+            "Failed to fetch inventory from {}: {}",
+            addr,
+            String::from_utf8_lossy(&response.data)
+        )
+        .into());
+    }
+
+    Ok(serde_json::from_slice(&response.data)?)
+}
+
+async fn fetch_dump(
+    bind: &str,
+    internal_tls: Option<&InternalTlsConfig>,
+) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let url = format!("{}://{}/dump", protos::tls::scheme(internal_tls), bind);
+    let response = http_client_builder(internal_tls)?
+        .build()?
+        .post(&url)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Request to {} failed with status: {}",
+            url,
+            response.status()
+        )
+        .into());
+    }
+
+    let text = response.text().await?;
+    Ok(text
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(key, val)| (key.to_string(), val.to_string()))
+        .collect())
+}
+
+/// Rebuild tenants, databases, buckets and table schemas from the vnodes found
+/// on data nodes, as meta key-values in the format of `dump`.
+///
+/// Database options and buckets cached by the data nodes are used if any, or
+/// else the default options are used, with the replica and shard number
+/// derived from the replication sets, and each bucket is located by the time
+/// range of the data in it.
+pub fn rebuild_meta_data(cluster: &str, inventories: &[NodeInventory]) -> MetaResult<RebuiltMeta> {
+    let mut max_id = 0;
+    let mut dbs: BTreeMap<(String, String), DatabaseEntry> = BTreeMap::new();
+    for inventory in inventories {
+        for vnode in inventory.vnodes.iter() {
+            let db = dbs
+                .entry((vnode.tenant.clone(), vnode.db_name.clone()))
+                .or_default();
+            if db.schema.is_none() {
+                db.schema = vnode.db_schema.clone();
+            }
+            for table in vnode.tables.iter() {
+                match db.tables.get(&table.name) {
+                    Some(old) if old.schema_version >= table.schema_version => {}
+                    _ => {
+                        db.tables.insert(table.name.clone(), table.clone());
+                    }
+                }
+            }
+
+            let replica = db.replicas.entry(vnode.replica_id).or_default();
+            replica
+                .vnodes
+                .push(VnodeInfo::new(vnode.vnode_id, inventory.node_id));
+            replica.leaders.extend(vnode.leader_vnode_id);
+            if let Some(range) = vnode.time_range.as_ref() {
+                match replica.time_range.as_mut() {
+                    Some(time_range) => time_range.merge(range),
+                    None => replica.time_range = Some(*range),
+                }
+            }
+            if let Some(info) = vnode.replica_info.as_ref() {
+                replica.bucket = Some((info.bucket_id, info.start_time, info.end_time));
+                max_id = max_id.max(info.bucket_id);
+            }
+
+            max_id = max_id.max(vnode.vnode_id).max(vnode.replica_id);
+        }
+    }
+
+    let mut rebuilt = RebuiltMeta::default();
+    for ((tenant, db_name), db) in dbs {
+        let tenant_key = KeyPath::tenant(cluster, &tenant);
+        if !rebuilt.data.contains_key(&tenant_key) {
+            let oid = UuidGenerator::default().next_id();
+            let value = Tenant::new(oid, tenant.clone(), TenantOptions::default());
+            rebuilt.data.insert(tenant_key, value_encode(&value)?);
+        }
+
+        let cached = db.schema.is_some();
+        let mut schema = db.schema.unwrap_or_else(|| {
+            DatabaseSchema::new(
+                &tenant,
+                &db_name,
+                DatabaseOptions::default(),
+                Arc::new(DatabaseConfig::default()),
+            )
+        });
+        let duration = schema
+            .options
+            .vnode_duration()
+            .to_precision(*schema.config.precision());
+
+        let mut buckets: BTreeMap<(i64, i64), BucketInfo> = BTreeMap::new();
+        for (id, mut replica) in db.replicas {
+            let (bucket_id, start_time, end_time) = match (replica.bucket, replica.time_range) {
+                (Some(bucket), _) => bucket,
+                (None, Some(time_range)) => {
+                    let (start_time, end_time) = get_time_range(time_range.min_ts, duration);
+                    (0, start_time, end_time)
+                }
+                (None, None) => {
+                    rebuilt.unplaced.push(id);
+                    continue;
+                }
+            };
+
+            replica.vnodes.sort_by_key(|vnode| vnode.id);
+            let leader = replica
+                .vnodes
+                .iter()
+                .rev()
+                .max_by_key(|vnode| {
+                    replica
+                        .leaders
+                        .iter()
+                        .filter(|leader| **leader == vnode.id)
+                        .count()
+                })
+                .cloned()
+                .unwrap_or_default();
+
+            let bucket = buckets
+                .entry((start_time, end_time))
+                .or_insert_with(|| BucketInfo {
+                    id: bucket_id,
+                    start_time,
+                    end_time,
+                    shard_group: vec![],
+                });
+            if bucket.id == 0 {
+                bucket.id = bucket_id;
+            }
+            bucket.shard_group.push(ReplicationSet::new(
+                id,
+                leader.node_id,
+                leader.id,
+                replica.vnodes,
+            ));
+        }
+
+        for bucket in buckets.values_mut() {
+            if bucket.id == 0 {
+                max_id += 1;
+                bucket.id = max_id;
+            }
+        }
+
+        if !cached {
+            let replica = buckets
+                .values()
+                .flat_map(|bucket| bucket.shard_group.iter())
+                .map(|replica| replica.vnodes.len())
+                .max()
+                .unwrap_or(1);
+            let shard_num = buckets
+                .values()
+                .map(|bucket| bucket.shard_group.len())
+                .max()
+                .unwrap_or(1);
+            schema.options.set_replica(replica as u64);
+            schema.options.set_shard_num(shard_num as u64);
+        }
+
+        rebuilt.data.insert(
+            KeyPath::tenant_db_name(cluster, &tenant, &db_name),
+            value_encode(&schema)?,
+        );
+        for bucket in buckets.into_values() {
+            rebuilt.data.insert(
+                KeyPath::tenant_bucket_id(cluster, &tenant, &db_name, bucket.id),
+                value_encode(&bucket)?,
+            );
+        }
+        for (name, table) in db.tables {
+            let table = TableSchema::TsKvTableSchema(Arc::new(table));
+            rebuilt.data.insert(
+                KeyPath::tenant_schema_name(cluster, &tenant, &db_name, &name),
+                value_encode(&table)?,
+            );
+        }
+    }
+
+    rebuilt
+        .data
+        .insert(KeyPath::incr_id(cluster), (max_id + 1).to_string());
+
+    Ok(rebuilt)
+}
+
+#[cfg(test)]
+mod test {
+    use config::common::InternalTlsConfig;
+    use models::meta_data::{BucketInfo, NodeInventory, VnodeInventory};
+    use models::predicate::domain::TimeRange;
+    use models::schema::database_schema::DatabaseSchema;
+
+    use super::rebuild_meta_data;
+    use crate::store::key_path::KeyPath;
+
+    fn vnode(vnode_id: u32, replica_id: u32, leader: u32, min_ts: i64) -> VnodeInventory {
+        VnodeInventory {
+            tenant: "cnosdb".to_string(),
+            db_name: "db1".to_string(),
+            vnode_id,
+            replica_id,
+            leader_vnode_id: Some(leader),
+            time_range: Some(TimeRange::new(min_ts, min_ts + 10)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rebuild_meta_data() {
+        let inventories = vec![
+            NodeInventory {
+                node_id: 1001,
+                vnodes: vec![vnode(3, 2, 4, 100), vnode(6, 5, 6, 200)],
+            },
+            NodeInventory {
+                node_id: 1002,
+                vnodes: vec![vnode(4, 2, 4, 100), vnode(7, 5, 6, 200)],
+            },
+        ];
+
+        let rebuilt = rebuild_meta_data("cluster_xxx", &inventories).unwrap();
+        assert!(rebuilt.unplaced.is_empty());
+        assert!(rebuilt
+            .data
+            .contains_key(&KeyPath::tenant("cluster_xxx", "cnosdb")));
+        assert_eq!(
+            rebuilt.data.get(&KeyPath::incr_id("cluster_xxx")).unwrap(),
+            "9"
+        );
+
+        let schema: DatabaseSchema = serde_json::from_str(
+            rebuilt
+                .data
+                .get(&KeyPath::tenant_db_name("cluster_xxx", "cnosdb", "db1"))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(schema.options.replica(), 2);
+        assert_eq!(schema.options.shard_num(), 2);
+
+        let bucket: BucketInfo = serde_json::from_str(
+            rebuilt
+                .data
+                .get(&KeyPath::tenant_bucket_id(
+                    "cluster_xxx",
+                    "cnosdb",
+                    "db1",
+                    8,
+                ))
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(bucket.shard_group.len(), 2);
+        assert_eq!(bucket.shard_group[0].id, 2);
+        assert_eq!(bucket.shard_group[0].leader_vnode_id, 4);
+        assert_eq!(bucket.shard_group[0].leader_node_id, 1002);
+        assert_eq!(bucket.shard_group[1].leader_vnode_id, 6);
+    }
+}
@@ -5,7 +5,9 @@ use std::fmt::Debug;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
-use config::common::{RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig};
+use config::common::{
+    PasswordPolicyConfig, RequestLimiterConfig, TenantLimiterConfig, TenantObjectLimiterConfig,
+};
 use config::tskv::Config;
 use metrics::metric_register::MetricsRegister;
 use models::auth::user::{admin_user, User, UserDesc, UserOptions};
@@ -19,7 +21,7 @@ use models::schema::tenant::{Tenant, TenantOptions};
 use models::utils::{build_address_with_optional_addr, now_timestamp_secs};
 use parking_lot::{Mutex, RwLock};
 use tokio::sync::mpsc::{self, Receiver, Sender};
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::Channel;
 use trace::error;
 use tracing::info;
 
@@ -100,7 +102,9 @@ impl AdminMeta {
         let meta_url = meta_service_addr.join(";");
         let (watch_notify, receiver) = mpsc::channel(1024);
 
-        let client = MetaHttpClient::new(&meta_url, metrics_register.clone());
+        let client = MetaHttpClient::new(&meta_url, metrics_register.clone())
+            .with_internal_tls(config.security.internal_tls.as_ref())
+            .expect("create meta client with internal tls");
         let limiters = Arc::new(LimiterManager::new({
             let mut map = HashMap::new();
             map.insert(
@@ -150,6 +154,19 @@ impl AdminMeta {
         self.config.deployment.mode.clone()
     }
 
+    pub fn metrics_register(&self) -> Arc<MetricsRegister> {
+        self.metrics_register.clone()
+    }
+
+    /// The password policy of the tenant, or of the system if the tenant doesn't set one.
+    pub fn password_policy(&self, tenant: &Tenant) -> Option<PasswordPolicyConfig> {
+        tenant
+            .options()
+            .password_policy()
+            .or(self.config.security.password_policy.as_ref())
+            .cloned()
+    }
+
     fn meta_addrs(&self) -> String {
         self.config.meta.service_addr.join(";")
     }
@@ -158,6 +175,18 @@ impl AdminMeta {
         self.client.meta_leader().await
     }
 
+    /// Dump the DDL statements of the cluster, or of a tenant if given.
+    pub async fn dump_sql_ddl(&self, tenant: Option<&str>) -> MetaResult<String> {
+        let cluster = self.cluster();
+        let leader = self.meta_leader().await?;
+        let uri = match tenant {
+            Some(t) => format!("dump/sql/ddl/{cluster}/{t}"),
+            None => format!("dump/sql/ddl/{cluster}"),
+        };
+
+        self.client.get(&leader, &uri).await
+    }
+
     pub fn sys_info() -> SysInfo {
         let mut info = SysInfo::default();
 
@@ -190,11 +219,12 @@ impl AdminMeta {
         }
 
         let info = self.node_info_by_id(node_id).await?;
-        let connector = Endpoint::from_shared(format!("http://{}", info.grpc_addr.clone()))
-            .map_err(|err| MetaError::ConnectServerError {
-                addr: info.grpc_addr.clone(),
-                msg: err.to_string(),
-            })?;
+        let connector =
+            protos::tls::endpoint(&info.grpc_addr, self.config.security.internal_tls.as_ref())
+                .map_err(|err| MetaError::ConnectServerError {
+                    addr: info.grpc_addr.clone(),
+                    msg: err.to_string(),
+                })?;
 
         let channel = connector
             .connect()
@@ -209,6 +239,23 @@ impl AdminMeta {
         Ok(channel)
     }
 
+    /// Whether one of the names from the certificate of a peer is the host of a
+    /// data node or a meta node of the cluster.
+    pub fn is_cluster_node(&self, names: &[String]) -> bool {
+        let data_nodes = self.data_nodes.read();
+        let mut hosts = data_nodes
+            .values()
+            .map(|node| protos::tls::addr_host(&node.grpc_addr))
+            .chain(
+                self.config
+                    .meta
+                    .service_addr
+                    .iter()
+                    .map(|addr| protos::tls::addr_host(addr)),
+            );
+        hosts.any(|host| names.iter().any(|name| name == host))
+    }
+
     pub async fn retain_id(&self, count: u32) -> MetaResult<u32> {
         let req = command::WriteCommand::RetainID(self.config.global.cluster_name.clone(), count);
         let id = self.client.write::<u32>(&req).await?;
@@ -638,6 +685,7 @@ impl AdminMeta {
             self.cluster(),
             tenant_info,
             self.meta_addrs(),
+            self.config.security.internal_tls.as_ref(),
             self.metrics_register.clone(),
         )
         .await?;
@@ -706,6 +754,7 @@ impl AdminMeta {
                 comment: options.comment,
                 drop_after: options.drop_after,
                 tenant_is_hidden: options.tenant_is_hidden,
+                password_policy: options.password_policy,
                 limiter_config: match options.limiter_config {
                     Some(_) => Self::merge_limiter_config(
                         old_options.limiter_config,
@@ -5,15 +5,18 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 use client::MetaHttpClient;
-use config::common::TenantObjectLimiterConfig;
+use config::common::{InternalTlsConfig, TenantObjectLimiterConfig};
 use metrics::metric_register::MetricsRegister;
-use models::auth::privilege::{DatabasePrivilege, Privilege};
-use models::auth::role::{CustomTenantRole, SystemTenantRole, TenantRoleIdentifier};
+use models::auth::policy::PolicyDesc;
+use models::auth::privilege::{DatabasePrivilege, Privilege, TablePrivilege};
+use models::auth::role::{CustomTenantRole, RoleQuota, SystemTenantRole, TenantRoleIdentifier};
+use models::auth::token::TokenDesc;
 use models::auth::user::UserDesc;
 use models::meta_data::*;
 use models::oid::{Identifier, Oid};
 use models::schema::database_schema::DatabaseSchema;
 use models::schema::external_table_schema::ExternalTableSchema;
+use models::schema::function_schema::FunctionSchema;
 use models::schema::resource_info::ResourceInfo;
 use models::schema::table_schema::TableSchema;
 use models::schema::tenant::Tenant;
@@ -54,6 +57,7 @@ impl TenantMeta {
         cluster: String,
         tenant: Tenant,
         meta_url: String,
+        internal_tls: Option<&InternalTlsConfig>,
         metrics_register: Arc<MetricsRegister>,
     ) -> MetaResult<Arc<Self>> {
         let client = Arc::new(Self {
@@ -61,7 +65,8 @@ impl TenantMeta {
             tenant,
             meta_url: meta_url.clone(),
             data: RwLock::new(TenantMetaData::new()),
-            client: MetaHttpClient::new(&meta_url, metrics_register),
+            client: MetaHttpClient::new(&meta_url, metrics_register)
+                .with_internal_tls(internal_tls)?,
         });
 
         client.sync_all_tenant_metadata().await?;
@@ -326,11 +331,13 @@ impl TenantMeta {
     pub async fn grant_privilege_to_custom_role(
         &self,
         database_privileges: Vec<(DatabasePrivilege, String)>,
+        table_privileges: Vec<TablePrivilege>,
         role_name: &str,
     ) -> MetaResult<()> {
         let req = command::WriteCommand::GrantPrivileges(
             self.cluster.clone(),
             database_privileges,
+            table_privileges,
             role_name.to_string(),
             self.tenant_name(),
         );
@@ -341,11 +348,13 @@ impl TenantMeta {
     pub async fn revoke_privilege_from_custom_role(
         &self,
         database_privileges: Vec<(DatabasePrivilege, String)>,
+        table_privileges: Vec<TablePrivilege>,
         role_name: &str,
     ) -> MetaResult<()> {
         let req = command::WriteCommand::RevokePrivileges(
             self.cluster.clone(),
             database_privileges,
+            table_privileges,
             role_name.to_string(),
             self.tenant_name(),
         );
@@ -353,6 +362,31 @@ impl TenantMeta {
         self.client.write::<()>(&req).await
     }
 
+    pub async fn set_custom_role_quota(&self, quota: RoleQuota, role_name: &str) -> MetaResult<()> {
+        let req = command::WriteCommand::SetRoleQuota(
+            self.cluster.clone(),
+            quota,
+            role_name.to_string(),
+            self.tenant_name(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    /// The quota of the members of the role, the system roles are unlimited
+    pub fn role_quota(&self, role: &TenantRoleIdentifier) -> RoleQuota {
+        match role {
+            TenantRoleIdentifier::System(_) => RoleQuota::default(),
+            TenantRoleIdentifier::Custom(role_name) => self
+                .data
+                .read()
+                .roles
+                .get(role_name)
+                .map(|role| role.quota().clone())
+                .unwrap_or_default(),
+        }
+    }
+
     pub async fn drop_custom_role(&self, role_name: &str) -> MetaResult<bool> {
         let req = command::WriteCommand::DropRole(
             self.cluster.clone(),
@@ -369,6 +403,116 @@ impl TenantMeta {
     }
     // tenant role end
 
+    // tenant function start
+
+    pub async fn create_function(
+        &self,
+        function: &FunctionSchema,
+        or_replace: bool,
+    ) -> MetaResult<()> {
+        let req = command::WriteCommand::CreateFunction(
+            self.cluster.clone(),
+            self.tenant_name(),
+            function.clone(),
+            or_replace,
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn drop_function(&self, function_name: &str) -> MetaResult<()> {
+        let req = command::WriteCommand::DropFunction(
+            self.cluster.clone(),
+            self.tenant_name(),
+            function_name.to_string(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub fn function(&self, function_name: &str) -> Option<FunctionSchema> {
+        self.data.read().functions.get(function_name).cloned()
+    }
+
+    pub fn functions(&self) -> Vec<FunctionSchema> {
+        self.data.read().functions.values().cloned().collect()
+    }
+
+    // tenant function end
+
+    // tenant token start
+
+    pub async fn create_token(&self, token: &TokenDesc) -> MetaResult<()> {
+        let req = command::WriteCommand::CreateToken(
+            self.cluster.clone(),
+            self.tenant_name(),
+            token.clone(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn drop_token(&self, token_name: &str) -> MetaResult<()> {
+        let req = command::WriteCommand::DropToken(
+            self.cluster.clone(),
+            self.tenant_name(),
+            token_name.to_string(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub fn token(&self, token_name: &str) -> Option<TokenDesc> {
+        self.data.read().tokens.get(token_name).cloned()
+    }
+
+    pub fn token_by_id(&self, token_id: &Oid) -> Option<TokenDesc> {
+        self.data
+            .read()
+            .tokens
+            .values()
+            .find(|token| token.id() == token_id)
+            .cloned()
+    }
+
+    pub fn tokens(&self) -> Vec<TokenDesc> {
+        self.data.read().tokens.values().cloned().collect()
+    }
+
+    // tenant token end
+
+    // tenant policy start
+
+    pub async fn create_policy(&self, policy: &PolicyDesc) -> MetaResult<()> {
+        let req = command::WriteCommand::CreatePolicy(
+            self.cluster.clone(),
+            self.tenant_name(),
+            policy.clone(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub async fn drop_policy(&self, policy_name: &str) -> MetaResult<()> {
+        let req = command::WriteCommand::DropPolicy(
+            self.cluster.clone(),
+            self.tenant_name(),
+            policy_name.to_string(),
+        );
+
+        self.client.write::<()>(&req).await
+    }
+
+    pub fn policy(&self, policy_name: &str) -> Option<PolicyDesc> {
+        self.data.read().policies.get(policy_name).cloned()
+    }
+
+    pub fn policies(&self) -> Vec<PolicyDesc> {
+        self.data.read().policies.values().cloned().collect()
+    }
+
+    // tenant policy end
+
     async fn write_with_data(&self, req: &command::WriteCommand) -> MetaResult<()> {
         let rsp = self.client.write::<TenantMetaData>(req).await?;
 
@@ -932,6 +1076,33 @@ impl TenantMeta {
             } else if entry.tye == command::ENTRY_LOG_TYPE_DEL {
                 cache.roles.remove(key);
             }
+        } else if len == 6 && strs[4] == key_path::FUNCTIONS && strs[2] == key_path::TENANTS {
+            let key = strs[5];
+            if entry.tye == command::ENTRY_LOG_TYPE_SET {
+                if let Ok(info) = serde_json::from_str::<FunctionSchema>(&entry.val) {
+                    cache.functions.insert(key.to_owned(), info);
+                }
+            } else if entry.tye == command::ENTRY_LOG_TYPE_DEL {
+                cache.functions.remove(key);
+            }
+        } else if len == 6 && strs[4] == key_path::TOKENS && strs[2] == key_path::TENANTS {
+            let key = strs[5];
+            if entry.tye == command::ENTRY_LOG_TYPE_SET {
+                if let Ok(info) = serde_json::from_str::<TokenDesc>(&entry.val) {
+                    cache.tokens.insert(key.to_owned(), info);
+                }
+            } else if entry.tye == command::ENTRY_LOG_TYPE_DEL {
+                cache.tokens.remove(key);
+            }
+        } else if len == 6 && strs[4] == key_path::POLICIES && strs[2] == key_path::TENANTS {
+            let key = strs[5];
+            if entry.tye == command::ENTRY_LOG_TYPE_SET {
+                if let Ok(info) = serde_json::from_str::<PolicyDesc>(&entry.val) {
+                    cache.policies.insert(key.to_owned(), info);
+                }
+            } else if entry.tye == command::ENTRY_LOG_TYPE_DEL {
+                cache.policies.remove(key);
+            }
         }
 
         Ok(())
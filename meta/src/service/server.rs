@@ -1,9 +1,11 @@
+use std::collections::HashSet;
 use std::convert::Infallible as StdInfallible;
 use std::sync::Arc;
 use std::time::Duration;
 
+use config::common::InternalTlsConfig;
 use config::meta::HeartBeatConfig;
-use futures::TryFutureExt;
+use futures::{StreamExt, TryFutureExt, TryStreamExt};
 use metrics::metric_register::MetricsRegister;
 use models::meta_data::NodeMetrics;
 use models::node_info::NodeStatus;
@@ -11,6 +13,7 @@ use models::schema::database_schema::{DatabaseConfig, DatabaseOptions};
 use models::schema::DEFAULT_DATABASE;
 use openraft::SnapshotPolicy;
 use protos::raft_service::raft_service_server::RaftServiceServer;
+use protos::tls::{addr_host, InternalTlsAcceptor, PeerValidator};
 use replication::entry_store::HeedEntryStorage;
 use replication::metrics::ReplicationMetrics;
 use replication::multi_raft::MultiRaft;
@@ -20,7 +23,10 @@ use replication::node_store::NodeStorage;
 use replication::raft_node::RaftNode;
 use replication::state_store::StateStorage;
 use replication::{RaftNodeInfo, ReplicationConfig};
+use tokio::net::TcpListener;
 use tokio::sync::RwLock;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_util::either::Either;
 use tower::Service;
 use tracing::{info, warn};
 use warp::hyper;
@@ -31,6 +37,8 @@ use crate::store::command::*;
 use crate::store::key_path::KeyPath;
 use crate::store::storage::StateMachine;
 
+const DATA_NODE_HOSTS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
 pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
     info!("CnosDB meta config: {:?}", opt);
     let id = opt.global.node_id;
@@ -41,7 +49,8 @@ pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
     let max_size = opt.cluster.lmdb_max_map_size;
     let state = StateStorage::open(path.join(format!("{}_state", id)), max_size)?;
     let entry = HeedEntryStorage::open(path.join(format!("{}_entry", id)), max_size)?;
-    let engine = StateMachine::open(path.join(format!("{}_data", id)), max_size)?;
+    let engine = StateMachine::open(path.join(format!("{}_data", id)), max_size)?
+        .with_internal_tls(opt.security.internal_tls.clone());
 
     let state = Arc::new(state);
     let engine = Arc::new(RwLock::new(engine));
@@ -62,6 +71,7 @@ pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
         send_append_entries_timeout: opt.cluster.send_append_entries_timeout,
         install_snapshot_timeout: opt.cluster.install_snapshot_timeout,
         snapshot_policy: SnapshotPolicy::LogsSinceLast(opt.cluster.raft_logs_to_keep),
+        internal_tls: opt.security.internal_tls.clone(),
     };
 
     let mut db_opt = DatabaseOptions::default();
@@ -114,7 +124,14 @@ pub async fn start_raft_node(opt: config::meta::Opt) -> MetaResult<()> {
     ));
 
     let bind_addr = models::utils::build_address("0.0.0.0", opt.global.listen_port);
-    tokio::spawn(start_warp_grpc_server(bind_addr, node, engine));
+    tokio::spawn(start_warp_grpc_server(
+        bind_addr,
+        opt.global.cluster_name.clone(),
+        opt.security.internal_tls.clone(),
+        opt.security.trusted_peers.clone(),
+        node,
+        engine,
+    ));
 
     Ok(())
 }
@@ -167,13 +184,64 @@ async fn detect_node_heartbeat(
     }
 }
 
+/// Accept the peers whose certificates name the host of a data node registered
+/// in this cluster, the host of a meta member or one of the trusted peers.
+///
+/// The validator is called synchronously by the acceptor, so the hosts of the
+/// data nodes are cached and refreshed every `DATA_NODE_HOSTS_REFRESH_INTERVAL`.
+fn cluster_peer_validator(
+    cluster_name: String,
+    trusted_peers: Vec<String>,
+    node: Arc<RaftNode>,
+    storage: Arc<RwLock<StateMachine>>,
+) -> PeerValidator {
+    let data_node_hosts = Arc::new(parking_lot::RwLock::new(HashSet::<String>::new()));
+
+    let hosts = data_node_hosts.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DATA_NODE_HOSTS_REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            match storage.read().await.process_read_data_nodes(&cluster_name) {
+                Ok((nodes, _)) => {
+                    *hosts.write() = nodes
+                        .iter()
+                        .map(|node| addr_host(&node.grpc_addr).to_string())
+                        .collect();
+                }
+                Err(err) => warn!("read data nodes of {} failed: {}", cluster_name, err),
+            }
+        }
+    });
+
+    Arc::new(move |names: &[String]| {
+        let metrics = node.raft_metrics();
+        let mut meta_hosts = metrics
+            .membership_config
+            .membership()
+            .nodes()
+            .map(|(_, info)| addr_host(&info.address));
+        let data_node_hosts = data_node_hosts.read();
+
+        names
+            .iter()
+            .any(|name| data_node_hosts.contains(name) || trusted_peers.contains(name))
+            || meta_hosts.any(|host| names.iter().any(|name| name == host))
+    })
+}
+
 // **************************** http and grpc server ************************************** //
 async fn start_warp_grpc_server(
     addr: String,
+    cluster_name: String,
+    internal_tls: Option<InternalTlsConfig>,
+    trusted_peers: Vec<String>,
     node: RaftNode,
     storage: Arc<RwLock<StateMachine>>,
 ) -> MetaResult<()> {
     let node = Arc::new(node);
+    let peer_validator =
+        cluster_peer_validator(cluster_name, trusted_peers, node.clone(), storage.clone());
     let raft_admin = RaftHttpAdmin::new(node.clone());
     let http_server = super::http::HttpServer {
         node: node.clone(),
@@ -194,8 +262,27 @@ async fn start_warp_grpc_server(
     multi_raft.add_node(node, metrics);
     let nodes = Arc::new(RwLock::new(multi_raft));
 
-    let addr = addr.parse().unwrap();
-    hyper::Server::bind(&addr)
+    let listener = TcpListener::bind(&addr)
+        .await
+        .map_err(|err| MetaError::CommonError {
+            msg: format!("bind {} failed: {}", addr, err),
+        })?;
+    let incoming = match internal_tls {
+        // Only the registered data nodes, the meta members and the trusted peers
+        // are accepted.
+        Some(tls) => InternalTlsAcceptor::try_new(tls)
+            .map_err(|err| MetaError::CommonError {
+                msg: format!("load internal tls certificates failed: {}", err),
+            })?
+            .incoming(listener, peer_validator)
+            .map_ok(Either::Right)
+            .boxed(),
+        None => TcpListenerStream::new(listener)
+            .map_ok(Either::Left)
+            .boxed(),
+    };
+
+    hyper::Server::builder(hyper::server::accept::from_stream(incoming))
         .http1_max_buf_size(100 * 1024 * 1024)
         .serve(hyper::service::make_service_fn(move |_| {
             let mut http_service = warp::service(http_server.routes());
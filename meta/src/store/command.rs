@@ -2,12 +2,15 @@
 
 use std::collections::{HashMap, HashSet};
 
-use models::auth::privilege::DatabasePrivilege;
-use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
+use models::auth::policy::PolicyDesc;
+use models::auth::privilege::{DatabasePrivilege, TablePrivilege};
+use models::auth::role::{RoleQuota, SystemTenantRole, TenantRoleIdentifier};
+use models::auth::token::TokenDesc;
 use models::auth::user::{UserDesc, UserOptions};
 use models::meta_data::*;
 use models::oid::Oid;
 use models::schema::database_schema::DatabaseSchema;
+use models::schema::function_schema::FunctionSchema;
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::ResourceInfo;
 use models::schema::table_schema::TableSchema;
@@ -125,10 +128,39 @@ pub enum WriteCommand {
     ),
     // cluster, role_name, tenant_name
     DropRole(String, String, String),
-    // cluster, privileges, role_name, tenant_name
-    GrantPrivileges(String, Vec<(DatabasePrivilege, String)>, String, String),
-    // cluster, privileges, role_name, tenant_name
-    RevokePrivileges(String, Vec<(DatabasePrivilege, String)>, String, String),
+    // cluster, database privileges, table privileges, role_name, tenant_name
+    GrantPrivileges(
+        String,
+        Vec<(DatabasePrivilege, String)>,
+        Vec<TablePrivilege>,
+        String,
+        String,
+    ),
+    // cluster, database privileges, table privileges, role_name, tenant_name
+    RevokePrivileges(
+        String,
+        Vec<(DatabasePrivilege, String)>,
+        Vec<TablePrivilege>,
+        String,
+        String,
+    ),
+    // cluster, quota, role_name, tenant_name
+    SetRoleQuota(String, RoleQuota, String, String),
+
+    // cluster, tenant_name, function, or_replace
+    CreateFunction(String, String, FunctionSchema, bool),
+    // cluster, tenant_name, function_name
+    DropFunction(String, String, String),
+
+    // cluster, tenant_name, token
+    CreateToken(String, String, TokenDesc),
+    // cluster, tenant_name, token_name
+    DropToken(String, String, String),
+
+    // cluster, tenant_name, policy
+    CreatePolicy(String, String, PolicyDesc),
+    // cluster, tenant_name, policy_name
+    DropPolicy(String, String, String),
 
     Set {
         key: String,
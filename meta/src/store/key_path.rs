@@ -6,6 +6,9 @@ use models::oid::Oid;
 // **    /cluster_name/tenants/tenant/roles/roles ->
 // **    /cluster_name/tenants/tenant/members/user_id ->
 // **    /cluster_name/tenants/tenant/limiter ->
+// **    /cluster_name/tenants/tenant/functions/name -> [FunctionSchema]
+// **    /cluster_name/tenants/tenant/tokens/name -> [TokenDesc]
+// **    /cluster_name/tenants/tenant/policies/name -> [PolicyDesc]
 // **    /cluster_name/auto_incr_id -> id
 // **    /cluster_name/data_nodes/node_id -> [NodeInfo] 集群、数据节点等信息
 
@@ -21,6 +24,9 @@ pub const SCHEMAS: &str = "schemas";
 pub const TENANTS: &str = "tenants";
 pub const MEMBERS: &str = "members";
 pub const LIMITER: &str = "limiter";
+pub const FUNCTIONS: &str = "functions";
+pub const TOKENS: &str = "tokens";
+pub const POLICIES: &str = "policies";
 pub const DATA_NODES: &str = "data_nodes";
 pub const AUTO_INCR_ID: &str = "auto_incr_id";
 pub const DATA_NODES_METRICS: &str = "data_nodes_metrics";
@@ -128,6 +134,30 @@ impl KeyPath {
         format!("/{}/tenants/{}/members", cluster, tenant_name)
     }
 
+    pub fn function(cluster: &str, tenant_name: &str, function_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/functions/{function_name}")
+    }
+
+    pub fn functions(cluster: &str, tenant_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/functions")
+    }
+
+    pub fn token(cluster: &str, tenant_name: &str, token_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/tokens/{token_name}")
+    }
+
+    pub fn tokens(cluster: &str, tenant_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/tokens")
+    }
+
+    pub fn policy(cluster: &str, tenant_name: &str, policy_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/policies/{policy_name}")
+    }
+
+    pub fn policies(cluster: &str, tenant_name: &str) -> String {
+        format!("/{cluster}/tenants/{tenant_name}/policies")
+    }
+
     pub fn limiter(cluster: &str, tenant_name: &str) -> String {
         format!("/{cluster}/tenants/{tenant_name}/limiter")
     }
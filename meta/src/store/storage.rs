@@ -4,12 +4,16 @@ use std::fs;
 use std::path::Path;
 use std::sync::Arc;
 
-use models::auth::privilege::DatabasePrivilege;
-use models::auth::role::{CustomTenantRole, SystemTenantRole, TenantRoleIdentifier};
+use config::common::InternalTlsConfig;
+use models::auth::policy::PolicyDesc;
+use models::auth::privilege::{DatabasePrivilege, TablePrivilege, TenantObjectPrivilege};
+use models::auth::role::{CustomTenantRole, RoleQuota, SystemTenantRole, TenantRoleIdentifier};
+use models::auth::token::TokenDesc;
 use models::auth::user::{UserDesc, UserOptions};
 use models::meta_data::*;
 use models::oid::{Identifier, Oid, UuidGenerator};
 use models::schema::database_schema::DatabaseSchema;
+use models::schema::function_schema::FunctionSchema;
 use models::schema::query_info::QueryInfo;
 use models::schema::resource_info::ResourceInfo;
 use models::schema::table_schema::TableSchema;
@@ -56,6 +60,8 @@ pub struct StateMachine {
     db: heed::Database<heed::types::Str, heed::types::Str>,
     snapshot: Option<(Vec<u8>, u64)>,
     pub watch: Arc<Watch>,
+    /// Used to ping the data nodes before placing the buckets on them.
+    internal_tls: Option<InternalTlsConfig>,
 }
 
 #[async_trait::async_trait]
@@ -141,11 +147,17 @@ impl StateMachine {
             db,
             snapshot: None,
             watch: Arc::new(Watch::new()),
+            internal_tls: None,
         };
 
         Ok(storage)
     }
 
+    pub fn with_internal_tls(mut self, internal_tls: Option<InternalTlsConfig>) -> Self {
+        self.internal_tls = internal_tls;
+        self
+    }
+
     pub fn is_meta_init(&self) -> MetaResult<bool> {
         self.contains_key(&KeyPath::already_init())
     }
@@ -387,6 +399,10 @@ impl StateMachine {
             self.children_data::<CustomTenantRole<Oid>>(&KeyPath::roles(cluster, tenant))?;
         meta.members =
             self.children_data::<TenantRoleIdentifier>(&KeyPath::members(cluster, tenant))?;
+        meta.functions =
+            self.children_data::<FunctionSchema>(&KeyPath::functions(cluster, tenant))?;
+        meta.tokens = self.children_data::<TokenDesc>(&KeyPath::tokens(cluster, tenant))?;
+        meta.policies = self.children_data::<PolicyDesc>(&KeyPath::policies(cluster, tenant))?;
         let db_schemas =
             self.children_data::<DatabaseSchema>(&KeyPath::tenant_dbs(cluster, tenant))?;
 
@@ -757,21 +773,57 @@ impl StateMachine {
             WriteCommand::DropRole(cluster, role_name, tenant_name) => {
                 response_encode(self.process_drop_role(cluster, role_name, tenant_name))
             }
-            WriteCommand::GrantPrivileges(cluster, privileges, role_name, tenant_name) => {
-                response_encode(self.process_grant_privileges(
+            WriteCommand::GrantPrivileges(
+                cluster,
+                privileges,
+                table_privileges,
+                role_name,
+                tenant_name,
+            ) => response_encode(self.process_grant_privileges(
+                cluster,
+                privileges,
+                table_privileges,
+                role_name,
+                tenant_name,
+            )),
+            WriteCommand::RevokePrivileges(
+                cluster,
+                privileges,
+                table_privileges,
+                role_name,
+                tenant_name,
+            ) => response_encode(self.process_revoke_privileges(
+                cluster,
+                privileges,
+                table_privileges,
+                role_name,
+                tenant_name,
+            )),
+            WriteCommand::SetRoleQuota(cluster, quota, role_name, tenant_name) => {
+                response_encode(self.process_set_role_quota(cluster, quota, role_name, tenant_name))
+            }
+            WriteCommand::CreateFunction(cluster, tenant_name, function, or_replace) => {
+                response_encode(self.process_create_function(
                     cluster,
-                    privileges,
-                    role_name,
                     tenant_name,
+                    function,
+                    *or_replace,
                 ))
             }
-            WriteCommand::RevokePrivileges(cluster, privileges, role_name, tenant_name) => {
-                response_encode(self.process_revoke_privileges(
-                    cluster,
-                    privileges,
-                    role_name,
-                    tenant_name,
-                ))
+            WriteCommand::DropFunction(cluster, tenant_name, function_name) => {
+                response_encode(self.process_drop_function(cluster, tenant_name, function_name))
+            }
+            WriteCommand::CreateToken(cluster, tenant_name, token) => {
+                response_encode(self.process_create_token(cluster, tenant_name, token))
+            }
+            WriteCommand::DropToken(cluster, tenant_name, token_name) => {
+                response_encode(self.process_drop_token(cluster, tenant_name, token_name))
+            }
+            WriteCommand::CreatePolicy(cluster, tenant_name, policy) => {
+                response_encode(self.process_create_policy(cluster, tenant_name, policy))
+            }
+            WriteCommand::DropPolicy(cluster, tenant_name, policy_name) => {
+                response_encode(self.process_drop_policy(cluster, tenant_name, policy_name))
             }
             WriteCommand::RetainID(cluster, count) => {
                 response_encode(self.process_retain_id(cluster, *count))
@@ -1140,6 +1192,8 @@ impl StateMachine {
                         });
                     }
                 }
+                // CREATE OR REPLACE VIEW
+                (TableSchema::ViewTableSchema(_), TableSchema::ViewTableSchema(_)) => {}
                 _ => {
                     return Err(MetaError::NotSupport {
                         msg: "update external table".to_string(),
@@ -1194,7 +1248,7 @@ impl StateMachine {
             })?;
 
         let node_list = self.get_valid_node_list(cluster)?;
-        let node_list = ping_servers(&node_list).await;
+        let node_list = ping_servers(&node_list, self.internal_tls.as_ref()).await;
 
         check_node_enough(db_schema.options.replica(), &node_list)?;
 
@@ -1432,6 +1486,24 @@ impl StateMachine {
             self.process_drop_role(cluster, role.name(), name)?;
         }
 
+        // drop functions in the tenant
+        let functions = self.children_data::<FunctionSchema>(&KeyPath::functions(cluster, name))?;
+        for function_name in functions.keys() {
+            self.process_drop_function(cluster, name, function_name)?;
+        }
+
+        // drop tokens in the tenant
+        let tokens = self.children_data::<TokenDesc>(&KeyPath::tokens(cluster, name))?;
+        for token_name in tokens.keys() {
+            self.process_drop_token(cluster, name, token_name)?;
+        }
+
+        // drop policies in the tenant
+        let policies = self.children_data::<PolicyDesc>(&KeyPath::policies(cluster, name))?;
+        for policy_name in policies.keys() {
+            self.process_drop_policy(cluster, name, policy_name)?;
+        }
+
         // drop tenant meta
         let key = KeyPath::tenant(cluster, name);
         let limiter_key = KeyPath::limiter(cluster, name);
@@ -1554,10 +1626,114 @@ impl StateMachine {
         Ok(true)
     }
 
+    fn process_create_function(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        function: &FunctionSchema,
+        or_replace: bool,
+    ) -> MetaResult<()> {
+        let key = KeyPath::function(cluster, tenant_name, function.name());
+
+        if !or_replace && self.contains_key(&key)? {
+            return Err(MetaError::FunctionAlreadyExists {
+                name: function.name().to_string(),
+            });
+        }
+
+        self.insert(&key, &value_encode(function)?)
+    }
+
+    fn process_drop_function(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        function_name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::function(cluster, tenant_name, function_name);
+
+        if !self.contains_key(&key)? {
+            return Err(MetaError::FunctionNotFound {
+                name: function_name.to_string(),
+            });
+        }
+
+        self.remove(&key)
+    }
+
+    fn process_create_token(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        token: &TokenDesc,
+    ) -> MetaResult<()> {
+        let key = KeyPath::token(cluster, tenant_name, token.name());
+
+        if self.contains_key(&key)? {
+            return Err(MetaError::TokenAlreadyExists {
+                name: token.name().to_string(),
+            });
+        }
+
+        self.insert(&key, &value_encode(token)?)
+    }
+
+    fn process_drop_token(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        token_name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::token(cluster, tenant_name, token_name);
+
+        if !self.contains_key(&key)? {
+            return Err(MetaError::TokenNotFound {
+                name: token_name.to_string(),
+            });
+        }
+
+        self.remove(&key)
+    }
+
+    fn process_create_policy(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        policy: &PolicyDesc,
+    ) -> MetaResult<()> {
+        let key = KeyPath::policy(cluster, tenant_name, policy.name());
+
+        if self.contains_key(&key)? {
+            return Err(MetaError::PolicyAlreadyExists {
+                name: policy.name().to_string(),
+            });
+        }
+
+        self.insert(&key, &value_encode(policy)?)
+    }
+
+    fn process_drop_policy(
+        &self,
+        cluster: &str,
+        tenant_name: &str,
+        policy_name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::policy(cluster, tenant_name, policy_name);
+
+        if !self.contains_key(&key)? {
+            return Err(MetaError::PolicyNotFound {
+                name: policy_name.to_string(),
+            });
+        }
+
+        self.remove(&key)
+    }
+
     fn process_grant_privileges(
         &self,
         cluster: &str,
         privileges: &[(DatabasePrivilege, String)],
+        table_privileges: &[TablePrivilege],
         role_name: &str,
         tenant_name: &str,
     ) -> MetaResult<()> {
@@ -1572,6 +1748,20 @@ impl StateMachine {
                 }
                 let _ = role.grant_privilege(database_name.clone(), privilege.clone());
             }
+            for privilege in table_privileges {
+                let key = KeyPath::tenant_schema_name(
+                    cluster,
+                    tenant_name,
+                    privilege.database(),
+                    privilege.table(),
+                );
+                if !self.contains_key(&key)? {
+                    return Err(MetaError::TableNotFound {
+                        table: privilege.table().to_string(),
+                    });
+                }
+                let _ = role.grant_table_privilege(privilege.clone());
+            }
 
             Ok(self.insert(&key, &value_encode(&role)?)?)
         } else {
@@ -1585,6 +1775,7 @@ impl StateMachine {
         &self,
         cluster: &str,
         privileges: &[(DatabasePrivilege, String)],
+        table_privileges: &[TablePrivilege],
         role_name: &str,
         tenant_name: &str,
     ) -> MetaResult<()> {
@@ -1593,13 +1784,39 @@ impl StateMachine {
             for (privilege, database_name) in privileges {
                 if role.revoke_privilege(database_name, privilege).is_err() {
                     return Err(MetaError::PrivilegeCannotRevoke {
-                        privilege: models::auth::privilege::TenantObjectPrivilege::Database(
+                        privilege: TenantObjectPrivilege::Database(
                             privilege.clone(),
                             Some(database_name.to_string()),
                         ),
                     });
                 }
             }
+            for privilege in table_privileges {
+                if role.revoke_table_privilege(privilege).is_err() {
+                    return Err(MetaError::PrivilegeCannotRevoke {
+                        privilege: TenantObjectPrivilege::Table(privilege.clone()),
+                    });
+                }
+            }
+
+            Ok(self.insert(&key, &value_encode(&role)?)?)
+        } else {
+            Err(MetaError::RoleNotFound {
+                role: role_name.to_string(),
+            })
+        }
+    }
+
+    fn process_set_role_quota(
+        &self,
+        cluster: &str,
+        quota: &RoleQuota,
+        role_name: &str,
+        tenant_name: &str,
+    ) -> MetaResult<()> {
+        let key = KeyPath::role(cluster, tenant_name, role_name);
+        if let Some(mut role) = self.get_struct::<CustomTenantRole<Oid>>(&key)? {
+            role.set_quota(quota.clone());
 
             Ok(self.insert(&key, &value_encode(&role)?)?)
         } else {
@@ -1672,10 +1889,13 @@ impl StateMachine {
     }
 }
 
-async fn ping_servers(list: &[NodeInfo]) -> Vec<NodeInfo> {
+async fn ping_servers(
+    list: &[NodeInfo],
+    internal_tls: Option<&InternalTlsConfig>,
+) -> Vec<NodeInfo> {
     let mut requests = vec![];
     for item in list {
-        let request = protos::tskv_service_ping(&item.grpc_addr);
+        let request = protos::tskv_service_ping(&item.grpc_addr, internal_tls);
         requests.push(request);
     }
 
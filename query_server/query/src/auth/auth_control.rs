@@ -1,49 +1,213 @@
+use std::sync::Arc;
+
+use config::common::PasswordPolicyConfig;
 use meta::model::MetaRef;
-use models::auth::user::{AuthType, User, UserInfo};
+use metrics::count::U64Counter;
+use metrics::metric::Metric;
+use models::auth::is_password_expired;
+use models::auth::lockout::LoginLockout;
+use models::auth::role::TenantRoleIdentifier;
+use models::auth::token::TokenDesc;
+use models::auth::user::{AuthType, User, UserInfo, UserOptions, UserOptionsBuilder, ROOT};
 use models::auth::AuthError;
 use models::oid::{Identifier, Oid};
+use models::utils::now_timestamp_secs;
 use spi::query::auth::AccessControl;
-use trace::warn;
+use trace::{info, warn};
+
+use crate::auth::jwt::JwtAuthenticator;
+use crate::auth::ldap::LdapAuthenticator;
 
 pub type Result<T> = std::result::Result<T, AuthError>;
 
 #[derive(Clone)]
 pub struct AccessControlImpl {
     inner: AccessControlNoCheck,
+    // the failed logins are tracked in memory by every query node on its own,
+    // so a user failing on several nodes is locked on each of them separately,
+    // and the lockouts are reset when the node restarts
+    lockout: Arc<LoginLockout>,
+    failed_logins: Metric<U64Counter>,
 }
 
 impl AccessControlImpl {
     pub fn new(inner: AccessControlNoCheck) -> Self {
-        Self { inner }
+        let failed_logins = inner
+            .meta_manager
+            .metrics_register()
+            .metric::<U64Counter>("failed_logins", "the failed authentications of users");
+
+        Self {
+            inner,
+            lockout: Arc::new(LoginLockout::default()),
+            failed_logins,
+        }
+    }
+
+    // Not labeled by the user or the tenant, they can be anything sent by the clients
+    fn login_failed(&self, reason: &'static str) {
+        self.failed_logins.recorder([("reason", reason)]).inc_one();
+    }
+
+    /// Root is never locked, or anyone could lock the administrator out by
+    /// failing its password on purpose.
+    fn lockable(user_name: &str) -> bool {
+        user_name != ROOT
+    }
+
+    fn password_failed(&self, user_name: &str, policy: Option<&PasswordPolicyConfig>) {
+        self.login_failed("invalid_password");
+        if !Self::lockable(user_name) {
+            return;
+        }
+        if let Some(duration) =
+            policy.and_then(|policy| self.lockout.login_failed(user_name, policy))
+        {
+            warn!(
+                "User {} is locked for {:?} because of too many failed logins",
+                user_name, duration
+            );
+        }
+    }
+
+    /// The password is verified by LDAP and never expires, the lockout still applies
+    async fn ldap_check(
+        &self,
+        ldap: &LdapAuthenticator,
+        user_info: &UserInfo,
+        tenant_name: &str,
+    ) -> Result<User> {
+        match self.inner.ldap_check(ldap, user_info, tenant_name).await {
+            Ok(user) => {
+                self.lockout.login_succeeded(&user_info.user);
+                Ok(user)
+            }
+            // the LDAP server is unavailable
+            Err(err @ AuthError::Ldap { .. }) => {
+                self.login_failed("ldap_error");
+                Err(err)
+            }
+            Err(err) => {
+                // only the existing users are locked, like the local passwords
+                if matches!(err, AuthError::AccessDenied { .. })
+                    && self.inner.user_exists(&user_info.user).await
+                {
+                    let policy = self.inner.password_policy(tenant_name).await;
+                    self.password_failed(&user_info.user, policy.as_ref());
+                } else {
+                    self.login_failed("invalid_user");
+                }
+                Err(AuthError::AccessDenied {
+                    user_name: user_info.user.clone(),
+                    auth_type: "LDAP".to_owned(),
+                    err: "username or password invalid".to_owned(),
+                })
+            }
+        }
+    }
+
+    fn check_locked(&self, user_name: &str) -> Result<()> {
+        if !Self::lockable(user_name) {
+            return Ok(());
+        }
+        if let Some(remaining) = self.lockout.locked_for(user_name) {
+            self.login_failed("user_locked");
+            return Err(AuthError::UserLocked {
+                user_name: user_name.to_string(),
+                seconds: remaining.as_secs().max(1),
+            });
+        }
+        Ok(())
+    }
+
+    /// The user can only change the password if the password is expired
+    fn check_expired(user: User, policy: Option<&PasswordPolicyConfig>) -> User {
+        match policy {
+            Some(policy)
+                if is_password_expired(policy, user.desc().options(), now_timestamp_secs()) =>
+            {
+                user.expire_password()
+            }
+            _ => user,
+        }
     }
 }
 
 #[async_trait::async_trait]
 impl AccessControl for AccessControlImpl {
     async fn access_check(&self, user_info: &UserInfo, tenant_name: &str) -> Result<User> {
+        self.check_locked(&user_info.user)?;
+
+        if let Some(ldap) = self.inner.ldap_of(&user_info.user).await {
+            return self.ldap_check(ldap, user_info, tenant_name).await;
+        }
+
         let user = self
             .inner
             .access_check(user_info, tenant_name)
             .await
-            .map_err(|_err| AuthError::AccessDenied {
-                user_name: user_info.user.clone(),
-                auth_type: "xxx".to_owned(),
-                err: "username or password invalid".to_owned(),
+            .map_err(|_err| {
+                self.login_failed("invalid_user");
+                AuthError::AccessDenied {
+                    user_name: user_info.user.clone(),
+                    auth_type: "xxx".to_owned(),
+                    err: "username or password invalid".to_owned(),
+                }
             })?;
 
+        let policy = self.inner.password_policy(tenant_name).await;
         let user_options = user.desc().options();
         // access check
-        AuthType::from(user_options)
+        if AuthType::from(user_options)
             .access_check(user_info)
-            .map_err(|_err| AuthError::AccessDenied {
+            .is_err()
+        {
+            self.password_failed(&user_info.user, policy.as_ref());
+            return Err(AuthError::AccessDenied {
                 user_name: user_info.user.clone(),
                 auth_type: "xxx".to_owned(),
                 err: "username or password invalid".to_owned(),
-            })?;
+            });
+        }
+        self.lockout.login_succeeded(&user_info.user);
+
+        Ok(Self::check_expired(user, policy.as_ref()))
+    }
+
+    async fn recheck(&self, user: User, tenant_name: &str) -> Result<User> {
+        self.check_locked(user.desc().name())?;
+
+        let policy = self.inner.password_policy(tenant_name).await;
+        Ok(Self::check_expired(user, policy.as_ref()))
+    }
+
+    async fn token_check(&self, token: &str, tenant_name: &str) -> Result<User> {
+        let (desc, user) = self
+            .inner
+            .token_user(token, tenant_name)
+            .await
+            .inspect_err(|_| self.login_failed("invalid_token"))?;
+
+        let verified = TokenDesc::parse(token).is_some_and(|(_, secret)| desc.verify(secret));
+        if !verified || desc.is_expired() {
+            self.login_failed("invalid_token");
+            return Err(AuthError::AccessDenied {
+                user_name: desc.user_name().to_string(),
+                auth_type: "token".to_owned(),
+                err: "token invalid or expired".to_owned(),
+            });
+        }
 
         Ok(user)
     }
 
+    async fn jwt_check(&self, token: &str, tenant_name: &str) -> Result<User> {
+        self.inner
+            .jwt_check(token, tenant_name)
+            .await
+            .inspect_err(|_| self.login_failed("invalid_jwt"))
+    }
+
     async fn tenant_id(&self, tenant_name: &str) -> Result<Oid> {
         // 查询租户信息，不存在则直接报错
         // tenant(&self, tenant_name: &str) -> Result<Tenant>;
@@ -55,18 +219,194 @@ impl AccessControl for AccessControlImpl {
 #[derive(Clone)]
 pub struct AccessControlNoCheck {
     meta_manager: MetaRef,
+    jwt: Option<Arc<JwtAuthenticator>>,
+    ldap: Option<Arc<LdapAuthenticator>>,
 }
 
 impl AccessControlNoCheck {
     pub fn new(meta_manager: MetaRef) -> Self {
-        Self { meta_manager }
+        Self {
+            meta_manager,
+            jwt: None,
+            ldap: None,
+        }
     }
-}
 
-#[async_trait::async_trait]
-impl AccessControl for AccessControlNoCheck {
-    async fn access_check(&self, user_info: &UserInfo, tenant_name: &str) -> Result<User> {
-        let user_name = user_info.user.as_str();
+    pub fn with_jwt(mut self, jwt: Arc<JwtAuthenticator>) -> Self {
+        self.jwt = Some(jwt);
+        self
+    }
+
+    pub fn with_ldap(mut self, ldap: Arc<LdapAuthenticator>) -> Self {
+        self.ldap = Some(ldap);
+        self
+    }
+
+    /// Creates the user authenticated by `source` with `options` if it doesn't exist, and
+    /// adds it to the tenant with `role`, the role of an existing member is changed if `sync_role`.
+    /// The admin users can't be authenticated by `source`.
+    async fn provision_user(
+        &self,
+        user_name: &str,
+        options: UserOptions,
+        role: Option<&TenantRoleIdentifier>,
+        sync_role: bool,
+        source: &str,
+        tenant_name: &str,
+    ) -> Result<()> {
+        let metadata_err = |err: meta::error::MetaError| AuthError::Metadata {
+            err: err.to_string(),
+        };
+
+        let user = self
+            .meta_manager
+            .user(user_name)
+            .await
+            .map_err(metadata_err)?;
+        let user_id = match user {
+            Some(desc) if desc.is_admin() => return Err(admin_denied(user_name, source)),
+            Some(desc) => *desc.id(),
+            None => {
+                info!("Provision user {} of {}", user_name, source);
+                // without password, the user can only log in with the source
+                self.meta_manager
+                    .create_user(user_name.to_string(), options, false)
+                    .await
+                    .map_err(metadata_err)?
+            }
+        };
+        let Some(role) = role else {
+            return Ok(());
+        };
+
+        let tenant_client = self
+            .meta_manager
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| AuthError::TenantNotFound)?;
+        match tenant_client
+            .member_role(&user_id, true)
+            .await
+            .map_err(metadata_err)?
+        {
+            None => {
+                info!(
+                    "Add user {} of {} to tenant {} with role {}",
+                    user_name,
+                    source,
+                    tenant_name,
+                    role.name()
+                );
+                tenant_client
+                    .add_member_with_role(user_id, role.clone())
+                    .await
+                    .map_err(metadata_err)?;
+            }
+            Some(current) if sync_role && &current != role => {
+                info!(
+                    "Change the role of user {} of {} in tenant {} from {} to {}",
+                    user_name,
+                    source,
+                    tenant_name,
+                    current.name(),
+                    role.name()
+                );
+                tenant_client
+                    .reassign_member_role(user_id, role.clone())
+                    .await
+                    .map_err(metadata_err)?;
+            }
+            Some(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// The LDAP authenticator verifying the password of the user, None if the user is
+    /// authenticated by its local password. Only the users created by LDAP or altered
+    /// with `ldap_auth = true` are verified by LDAP, the unknown users only if they
+    /// can be provisioned.
+    async fn ldap_of(&self, user_name: &str) -> Option<&LdapAuthenticator> {
+        let ldap = self.ldap.as_deref()?;
+        match self.meta_manager.user(user_name).await {
+            Ok(Some(desc)) => (desc.options().ldap_auth() == Some(true)).then_some(ldap),
+            Ok(None) => ldap.auto_provision().then_some(ldap),
+            Err(_) => None,
+        }
+    }
+
+    async fn ldap_check(
+        &self,
+        ldap: &LdapAuthenticator,
+        user_info: &UserInfo,
+        tenant_name: &str,
+    ) -> Result<User> {
+        let identity = ldap
+            .authenticate(&user_info.user, &user_info.password, tenant_name)
+            .await?;
+        if ldap.auto_provision() {
+            let options = UserOptionsBuilder::default()
+                .ldap_auth(true)
+                .build()
+                .unwrap_or_default();
+            self.provision_user(
+                &identity.user,
+                options,
+                identity.role.as_ref(),
+                true,
+                "LDAP",
+                tenant_name,
+            )
+            .await?;
+        }
+
+        let user = self.access_check_user(&identity.user, tenant_name).await?;
+        if user.desc().is_admin() {
+            return Err(admin_denied(&identity.user, "LDAP"));
+        }
+
+        Ok(user)
+    }
+
+    async fn user_exists(&self, user_name: &str) -> bool {
+        matches!(self.meta_manager.user(user_name).await, Ok(Some(_)))
+    }
+
+    /// Finds the token by its id, returns it with its user scoped to the privileges of the token
+    async fn token_user(&self, token: &str, tenant_name: &str) -> Result<(TokenDesc, User)> {
+        let invalid = || AuthError::AccessDenied {
+            user_name: "".to_owned(),
+            auth_type: "token".to_owned(),
+            err: "token invalid or expired".to_owned(),
+        };
+        let (token_id, _) = TokenDesc::parse(token).ok_or_else(invalid)?;
+
+        let tenant_client = self
+            .meta_manager
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| AuthError::TenantNotFound)?;
+        let desc = tenant_client.token_by_id(&token_id).ok_or_else(invalid)?;
+
+        let user = self
+            .access_check_user(desc.user_name(), tenant_name)
+            .await?;
+        // the user is dropped and a new one is created with the same name
+        if user.desc().id() != desc.user_id() {
+            return Err(invalid());
+        }
+        let user = user.with_scope(desc.to_privileges(*tenant_client.tenant().id()));
+
+        Ok((desc, user))
+    }
+
+    /// The password policy in effect when logging in the tenant
+    async fn password_policy(&self, tenant_name: &str) -> Option<PasswordPolicyConfig> {
+        let tenant_client = self.meta_manager.tenant_meta(tenant_name).await?;
+        self.meta_manager.password_policy(tenant_client.tenant())
+    }
+
+    async fn access_check_user(&self, user_name: &str, tenant_name: &str) -> Result<User> {
         // only get user info with privileges
         self.meta_manager
             .user_with_privileges(user_name, tenant_name)
@@ -78,6 +418,45 @@ impl AccessControl for AccessControlNoCheck {
                 }
             })
     }
+}
+
+#[async_trait::async_trait]
+impl AccessControl for AccessControlNoCheck {
+    async fn access_check(&self, user_info: &UserInfo, tenant_name: &str) -> Result<User> {
+        self.access_check_user(user_info.user.as_str(), tenant_name)
+            .await
+    }
+
+    async fn token_check(&self, token: &str, tenant_name: &str) -> Result<User> {
+        let (_, user) = self.token_user(token, tenant_name).await?;
+        Ok(user)
+    }
+
+    async fn jwt_check(&self, token: &str, tenant_name: &str) -> Result<User> {
+        let jwt = self.jwt.as_ref().ok_or_else(|| AuthError::Jwt {
+            reason: "JWT authentication is not enabled".to_string(),
+        })?;
+
+        let identity = jwt.authenticate(token, tenant_name).await?;
+        if jwt.auto_provision() {
+            self.provision_user(
+                &identity.user,
+                UserOptions::default(),
+                Some(&identity.role),
+                false,
+                "JWT",
+                tenant_name,
+            )
+            .await?;
+        }
+
+        let user = self.access_check_user(&identity.user, tenant_name).await?;
+        if user.desc().is_admin() {
+            return Err(admin_denied(&identity.user, "JWT"));
+        }
+
+        Ok(user)
+    }
 
     async fn tenant_id(&self, tenant_name: &str) -> Result<Oid> {
         let tenant_client = self
@@ -93,3 +472,12 @@ impl AccessControl for AccessControlNoCheck {
         Ok(*tenant_client.tenant().id())
     }
 }
+
+/// The admin users can only log in with their local password or tokens
+fn admin_denied(user_name: &str, source: &str) -> AuthError {
+    AuthError::AccessDenied {
+        user_name: user_name.to_string(),
+        auth_type: source.to_string(),
+        err: format!("the admin users can not log in with {source}"),
+    }
+}
@@ -0,0 +1,194 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use config::tskv::JwtConfig;
+use models::auth::jwt::{jwt_kid, Jwks, JwtClaims, JwtValidation};
+use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
+use models::auth::{AuthError, AuthResult};
+use parking_lot::RwLock;
+use serde_json::Value;
+use trace::{info, warn};
+
+/// A token signed by an unknown key reloads the JWKS at most once in this interval
+const MIN_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The CnosDB identity of a verified JWT
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JwtIdentity {
+    pub user: String,
+    /// The role of the auto-provisioned user in the tenant
+    pub role: TenantRoleIdentifier,
+}
+
+/// Verifies the bearer JWTs against the JWKS of the OIDC provider,
+/// and maps their claims to the CnosDB users.
+pub struct JwtAuthenticator {
+    config: JwtConfig,
+    validation: JwtValidation,
+    // the JWKS and when it was loaded
+    jwks: RwLock<Option<(Arc<Jwks>, Instant)>>,
+    http_client: reqwest::Client,
+}
+
+impl JwtAuthenticator {
+    pub fn new(config: JwtConfig) -> Self {
+        let validation = JwtValidation {
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+            leeway_secs: config.leeway.as_secs() as i64,
+        };
+
+        Self {
+            config,
+            validation,
+            jwks: RwLock::new(None),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn auto_provision(&self) -> bool {
+        self.config.auto_provision
+    }
+
+    /// Verifies the JWT and maps its claims to the user logging in the tenant
+    pub async fn authenticate(&self, token: &str, tenant_name: &str) -> AuthResult<JwtIdentity> {
+        let jwks = self.jwks(jwt_kid(token).as_deref()).await?;
+        let claims = jwks.verify(token, &self.validation)?;
+        self.identity(&claims, tenant_name)
+    }
+
+    fn identity(&self, claims: &JwtClaims, tenant_name: &str) -> AuthResult<JwtIdentity> {
+        let user = claim_str(claims, &self.config.user_claim)
+            .ok_or_else(|| AuthError::Jwt {
+                reason: format!("missing claim {}", self.config.user_claim),
+            })?
+            .to_string();
+
+        // the token can only be used in the tenant it's issued for
+        if claim_str(claims, &self.config.tenant_claim) != Some(tenant_name) {
+            return Err(AuthError::AccessDenied {
+                user_name: user,
+                auth_type: "JWT".to_string(),
+                err: format!("the token is not issued for tenant {tenant_name}"),
+            });
+        }
+
+        let role = match claim_str(claims, &self.config.role_claim) {
+            Some(role) => SystemTenantRole::try_from(role)
+                .map(TenantRoleIdentifier::System)
+                .unwrap_or_else(|_| TenantRoleIdentifier::Custom(role.to_string())),
+            None => TenantRoleIdentifier::System(SystemTenantRole::Member),
+        };
+
+        Ok(JwtIdentity { user, role })
+    }
+
+    /// The cached JWKS, it's reloaded if it's stale or doesn't contain the key of the token
+    async fn jwks(&self, kid: Option<&str>) -> AuthResult<Arc<Jwks>> {
+        let cached = self.jwks.read().clone();
+        if let Some((jwks, loaded_at)) = &cached {
+            let elapsed = loaded_at.elapsed();
+            let fresh = elapsed < self.config.jwks_refresh_interval;
+            let unknown_kid = kid.is_some_and(|kid| !jwks.contains_kid(kid));
+            if fresh && (!unknown_kid || elapsed < MIN_RELOAD_INTERVAL) {
+                return Ok(jwks.clone());
+            }
+        }
+
+        match self.load_jwks().await {
+            Ok(jwks) => {
+                info!("Loaded JWKS with {} keys", jwks.len());
+                let jwks = Arc::new(jwks);
+                *self.jwks.write() = Some((jwks.clone(), Instant::now()));
+                Ok(jwks)
+            }
+            Err(err) => match cached {
+                // keep using the stale keys if the OIDC provider is unavailable
+                Some((jwks, _)) => {
+                    warn!("Failed to reload JWKS, error: {}", err);
+                    Ok(jwks)
+                }
+                None => Err(err),
+            },
+        }
+    }
+
+    async fn load_jwks(&self) -> AuthResult<Jwks> {
+        let load_error = |err: String| AuthError::Jwt {
+            reason: format!("load JWKS failed: {err}"),
+        };
+
+        let content = if !self.config.jwks_file.is_empty() {
+            tokio::fs::read(&self.config.jwks_file)
+                .await
+                .map_err(|e| load_error(e.to_string()))?
+        } else {
+            self.http_client
+                .get(&self.config.jwks_url)
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status())
+                .map_err(|e| load_error(e.to_string()))?
+                .bytes()
+                .await
+                .map_err(|e| load_error(e.to_string()))?
+                .to_vec()
+        };
+
+        Jwks::from_json(&content)
+    }
+}
+
+fn claim_str<'a>(claims: &'a JwtClaims, name: &str) -> Option<&'a str> {
+    claims.get(name).and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_identity() {
+        let authenticator = JwtAuthenticator::new(JwtConfig {
+            user_claim: "preferred_username".to_string(),
+            ..Default::default()
+        });
+
+        let claims = json!({"preferred_username": "alice", "tenant": "cnosdb", "role": "owner"});
+        let identity = authenticator
+            .identity(claims.as_object().unwrap(), "cnosdb")
+            .unwrap();
+        assert_eq!(identity.user, "alice");
+        assert_eq!(
+            identity.role,
+            TenantRoleIdentifier::System(SystemTenantRole::Owner)
+        );
+
+        // the token of another tenant
+        assert!(authenticator
+            .identity(claims.as_object().unwrap(), "tenant1")
+            .is_err());
+
+        // the token without tenant
+        let claims = json!({"preferred_username": "bob", "role": "analyst"});
+        assert!(authenticator
+            .identity(claims.as_object().unwrap(), "tenant1")
+            .is_err());
+
+        let claims = json!({"preferred_username": "bob", "tenant": "tenant1", "role": "analyst"});
+        let identity = authenticator
+            .identity(claims.as_object().unwrap(), "tenant1")
+            .unwrap();
+        assert_eq!(
+            identity.role,
+            TenantRoleIdentifier::Custom("analyst".to_string())
+        );
+
+        let claims = json!({"sub": "bob"});
+        assert!(authenticator
+            .identity(claims.as_object().unwrap(), "cnosdb")
+            .is_err());
+    }
+}
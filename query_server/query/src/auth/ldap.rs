@@ -0,0 +1,470 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use config::tskv::LdapConfig;
+use ldap3::{
+    dn_escape, ldap_escape, Ldap, LdapConnAsync, LdapConnSettings, LdapError, Scope, SearchEntry,
+};
+use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
+use models::auth::{AuthError, AuthResult};
+use trace::{debug, warn};
+
+/// Requests no attributes of the entries found
+const NO_ATTRIBUTES: &str = "1.1";
+
+/// The CnosDB identity of a user authenticated by LDAP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdapIdentity {
+    pub user: String,
+    /// The tenant role mapped from the groups of the user, None if none of them is mapped
+    pub role: Option<TenantRoleIdentifier>,
+}
+
+/// An entry found by a search, with the values of the requested attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdapEntry {
+    pub dn: String,
+    pub values: Vec<String>,
+}
+
+/// A connection to the LDAP server
+#[async_trait]
+pub trait LdapConnection: Send {
+    /// Binds as `dn`, returns false if the credentials are invalid
+    async fn bind(&mut self, dn: &str, password: &str) -> AuthResult<bool>;
+
+    /// Searches the subtree of `base`, returns the values of `attribute` of the entries found
+    async fn search(
+        &mut self,
+        base: &str,
+        filter: &str,
+        attribute: &str,
+    ) -> AuthResult<Vec<LdapEntry>>;
+
+    async fn unbind(&mut self) {}
+}
+
+#[async_trait]
+pub trait LdapConnector: Send + Sync {
+    async fn connect(&self) -> AuthResult<Box<dyn LdapConnection>>;
+}
+
+/// Verifies the passwords of the users by binding to the LDAP server,
+/// and maps their groups to the CnosDB tenant roles.
+pub struct LdapAuthenticator {
+    config: LdapConfig,
+    connector: Arc<dyn LdapConnector>,
+}
+
+impl LdapAuthenticator {
+    pub fn new(config: LdapConfig) -> Self {
+        let connector = Arc::new(Ldap3Connector {
+            url: config.url.clone(),
+            start_tls: config.start_tls,
+            timeout: config.timeout,
+        });
+        Self::with_connector(config, connector)
+    }
+
+    pub fn with_connector(config: LdapConfig, connector: Arc<dyn LdapConnector>) -> Self {
+        Self { config, connector }
+    }
+
+    pub fn auto_provision(&self) -> bool {
+        self.config.auto_provision
+    }
+
+    /// Verifies the password and maps the groups of the user logging in the tenant
+    pub async fn authenticate(
+        &self,
+        user: &str,
+        password: &str,
+        tenant_name: &str,
+    ) -> AuthResult<LdapIdentity> {
+        // a bind without password is an unauthenticated bind, which always succeeds
+        if password.is_empty() {
+            return Err(access_denied(user));
+        }
+
+        let mut conn = self.connector.connect().await?;
+        let result = self
+            .authenticate_with(conn.as_mut(), user, password, tenant_name)
+            .await;
+        conn.unbind().await;
+        result
+    }
+
+    async fn authenticate_with(
+        &self,
+        conn: &mut dyn LdapConnection,
+        user: &str,
+        password: &str,
+        tenant_name: &str,
+    ) -> AuthResult<LdapIdentity> {
+        let user_dn = if !self.config.bind_dn_template.is_empty() {
+            self.config
+                .bind_dn_template
+                .replace("{user}", &dn_escape(user))
+        } else {
+            self.bind_service_account(conn).await?;
+            let filter = self
+                .config
+                .user_filter
+                .replace("{user}", &ldap_escape(user));
+            let mut entries = conn
+                .search(&self.config.base_dn, &filter, NO_ATTRIBUTES)
+                .await?;
+            if entries.len() != 1 {
+                debug!("Found {} LDAP entries of user {}", entries.len(), user);
+                return Err(access_denied(user));
+            }
+            entries.remove(0).dn
+        };
+
+        if !conn.bind(&user_dn, password).await? {
+            return Err(access_denied(user));
+        }
+
+        let role = if self.config.group_base_dn.is_empty() {
+            None
+        } else {
+            // the groups may not be readable by the user
+            self.bind_service_account(conn).await?;
+            let filter = self
+                .config
+                .group_filter
+                .replace("{dn}", &ldap_escape(&user_dn))
+                .replace("{user}", &ldap_escape(user));
+            let groups = conn
+                .search(
+                    &self.config.group_base_dn,
+                    &filter,
+                    &self.config.group_name_attribute,
+                )
+                .await?
+                .into_iter()
+                .flat_map(|entry| entry.values)
+                .collect::<Vec<_>>();
+            debug!("LDAP groups of user {}: {:?}", user, groups);
+            self.mapped_role(&groups, tenant_name)
+        };
+
+        Ok(LdapIdentity {
+            user: user.to_string(),
+            role,
+        })
+    }
+
+    /// Binds as `bind_dn`, the searches are anonymous if it's empty
+    async fn bind_service_account(&self, conn: &mut dyn LdapConnection) -> AuthResult<()> {
+        if self.config.bind_dn.is_empty()
+            || conn
+                .bind(&self.config.bind_dn, &self.config.bind_password)
+                .await?
+        {
+            Ok(())
+        } else {
+            Err(AuthError::Ldap {
+                reason: format!("invalid credentials of {}", self.config.bind_dn),
+            })
+        }
+    }
+
+    fn mapped_role(&self, groups: &[String], tenant_name: &str) -> Option<TenantRoleIdentifier> {
+        self.config
+            .role_mappings
+            .iter()
+            .find(|mapping| {
+                mapping.tenant == tenant_name
+                    && groups
+                        .iter()
+                        .any(|g| g.eq_ignore_ascii_case(&mapping.group))
+            })
+            .map(|mapping| {
+                SystemTenantRole::try_from(mapping.role.as_str())
+                    .map(TenantRoleIdentifier::System)
+                    .unwrap_or_else(|_| TenantRoleIdentifier::Custom(mapping.role.clone()))
+            })
+    }
+}
+
+fn access_denied(user: &str) -> AuthError {
+    AuthError::AccessDenied {
+        user_name: user.to_string(),
+        auth_type: "LDAP".to_string(),
+        err: "username or password invalid".to_string(),
+    }
+}
+
+fn ldap_error(err: LdapError) -> AuthError {
+    AuthError::Ldap {
+        reason: err.to_string(),
+    }
+}
+
+struct Ldap3Connector {
+    url: String,
+    start_tls: bool,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl LdapConnector for Ldap3Connector {
+    async fn connect(&self) -> AuthResult<Box<dyn LdapConnection>> {
+        let settings = LdapConnSettings::new()
+            .set_conn_timeout(self.timeout)
+            .set_starttls(self.start_tls);
+        let (conn, ldap) = LdapConnAsync::with_settings(settings, &self.url)
+            .await
+            .map_err(ldap_error)?;
+        tokio::spawn(async move {
+            if let Err(err) = conn.drive().await {
+                warn!("LDAP connection error: {}", err);
+            }
+        });
+
+        Ok(Box::new(Ldap3Connection {
+            ldap,
+            timeout: self.timeout,
+        }))
+    }
+}
+
+struct Ldap3Connection {
+    ldap: Ldap,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl LdapConnection for Ldap3Connection {
+    async fn bind(&mut self, dn: &str, password: &str) -> AuthResult<bool> {
+        const INVALID_CREDENTIALS: u32 = 49;
+
+        let result = self
+            .ldap
+            .with_timeout(self.timeout)
+            .simple_bind(dn, password)
+            .await
+            .map_err(ldap_error)?;
+        match result.rc {
+            INVALID_CREDENTIALS => Ok(false),
+            _ => result.success().map(|_| true).map_err(ldap_error),
+        }
+    }
+
+    async fn search(
+        &mut self,
+        base: &str,
+        filter: &str,
+        attribute: &str,
+    ) -> AuthResult<Vec<LdapEntry>> {
+        let (entries, _) = self
+            .ldap
+            .with_timeout(self.timeout)
+            .search(base, Scope::Subtree, filter, vec![attribute])
+            .await
+            .and_then(|result| result.success())
+            .map_err(ldap_error)?;
+
+        Ok(entries
+            .into_iter()
+            .map(SearchEntry::construct)
+            .map(|entry| LdapEntry {
+                values: entry
+                    .attrs
+                    .into_iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(attribute))
+                    .map(|(_, values)| values)
+                    .unwrap_or_default(),
+                dn: entry.dn,
+            })
+            .collect())
+    }
+
+    async fn unbind(&mut self) {
+        if let Err(err) = self.ldap.unbind().await {
+            debug!("Failed to unbind from LDAP server: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use config::tskv::LdapRoleMapping;
+
+    use super::*;
+
+    /// A local stand-in of the LDAP server, the searches are answered by their base and filter
+    #[derive(Default, Clone)]
+    struct Directory {
+        passwords: HashMap<String, String>,
+        searches: HashMap<(String, String), Vec<LdapEntry>>,
+    }
+
+    impl Directory {
+        fn with_user(mut self, dn: &str, password: &str) -> Self {
+            self.passwords.insert(dn.to_string(), password.to_string());
+            self
+        }
+
+        fn with_search(mut self, base: &str, filter: &str, entries: &[(&str, &[&str])]) -> Self {
+            let entries = entries
+                .iter()
+                .map(|(dn, values)| LdapEntry {
+                    dn: dn.to_string(),
+                    values: values.iter().map(|v| v.to_string()).collect(),
+                })
+                .collect();
+            self.searches
+                .insert((base.to_string(), filter.to_string()), entries);
+            self
+        }
+    }
+
+    struct DirectoryConnection {
+        directory: Directory,
+        bound: bool,
+    }
+
+    #[async_trait]
+    impl LdapConnection for DirectoryConnection {
+        async fn bind(&mut self, dn: &str, password: &str) -> AuthResult<bool> {
+            self.bound = self.directory.passwords.get(dn).map(|p| p.as_str()) == Some(password);
+            Ok(self.bound)
+        }
+
+        async fn search(
+            &mut self,
+            base: &str,
+            filter: &str,
+            _attribute: &str,
+        ) -> AuthResult<Vec<LdapEntry>> {
+            if !self.bound {
+                return Err(AuthError::Ldap {
+                    reason: "anonymous search".to_string(),
+                });
+            }
+            let key = (base.to_string(), filter.to_string());
+            Ok(self
+                .directory
+                .searches
+                .get(&key)
+                .cloned()
+                .unwrap_or_default())
+        }
+    }
+
+    #[async_trait]
+    impl LdapConnector for Directory {
+        async fn connect(&self) -> AuthResult<Box<dyn LdapConnection>> {
+            Ok(Box::new(DirectoryConnection {
+                directory: self.clone(),
+                bound: false,
+            }))
+        }
+    }
+
+    const ALICE_DN: &str = "uid=alice,ou=people,dc=example,dc=com";
+
+    fn directory() -> Directory {
+        Directory::default()
+            .with_user(ALICE_DN, "secret")
+            .with_user("cn=cnosdb,dc=example,dc=com", "service")
+            .with_search(
+                "ou=people,dc=example,dc=com",
+                "(uid=alice)",
+                &[(ALICE_DN, &[])],
+            )
+            .with_search(
+                "ou=groups,dc=example,dc=com",
+                "(member=uid=alice,ou=people,dc=example,dc=com)",
+                &[
+                    ("cn=ops,ou=groups,dc=example,dc=com", &["ops"]),
+                    ("cn=analysts,ou=groups,dc=example,dc=com", &["analysts"]),
+                ],
+            )
+    }
+
+    fn role_mappings() -> Vec<LdapRoleMapping> {
+        vec![
+            LdapRoleMapping {
+                group: "ops".to_string(),
+                tenant: "cnosdb".to_string(),
+                role: "owner".to_string(),
+            },
+            LdapRoleMapping {
+                group: "analysts".to_string(),
+                tenant: "tenant1".to_string(),
+                role: "analyst".to_string(),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_bind() {
+        let config = LdapConfig {
+            bind_dn_template: "uid={user},ou=people,dc=example,dc=com".to_string(),
+            ..Default::default()
+        };
+        let ldap = LdapAuthenticator::with_connector(config, Arc::new(directory()));
+
+        let identity = ldap
+            .authenticate("alice", "secret", "cnosdb")
+            .await
+            .unwrap();
+        assert_eq!(identity.user, "alice");
+        assert_eq!(identity.role, None);
+
+        for password in ["wrong", ""] {
+            assert!(matches!(
+                ldap.authenticate("alice", password, "cnosdb").await,
+                Err(AuthError::AccessDenied { .. })
+            ));
+        }
+        assert!(ldap.authenticate("bob", "secret", "cnosdb").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_bind() {
+        let config = LdapConfig {
+            bind_dn: "cn=cnosdb,dc=example,dc=com".to_string(),
+            bind_password: "service".to_string(),
+            base_dn: "ou=people,dc=example,dc=com".to_string(),
+            group_base_dn: "ou=groups,dc=example,dc=com".to_string(),
+            role_mappings: role_mappings(),
+            ..Default::default()
+        };
+        let ldap = LdapAuthenticator::with_connector(config, Arc::new(directory()));
+
+        let identity = ldap
+            .authenticate("alice", "secret", "cnosdb")
+            .await
+            .unwrap();
+        assert_eq!(
+            identity.role,
+            Some(TenantRoleIdentifier::System(SystemTenantRole::Owner))
+        );
+        let identity = ldap
+            .authenticate("alice", "secret", "tenant1")
+            .await
+            .unwrap();
+        assert_eq!(
+            identity.role,
+            Some(TenantRoleIdentifier::Custom("analyst".to_string()))
+        );
+        let identity = ldap
+            .authenticate("alice", "secret", "tenant2")
+            .await
+            .unwrap();
+        assert_eq!(identity.role, None);
+
+        // not found by the search
+        assert!(matches!(
+            ldap.authenticate("bob", "secret", "cnosdb").await,
+            Err(AuthError::AccessDenied { .. })
+        ));
+    }
+}
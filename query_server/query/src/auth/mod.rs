@@ -1 +1,3 @@
 pub mod auth_control;
+pub mod jwt;
+pub mod ldap;
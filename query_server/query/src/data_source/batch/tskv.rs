@@ -1,10 +1,11 @@
 use std::any::Any;
+use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use coordinator::service::CoordinatorRef;
-use datafusion::arrow::array::Int64Array;
+use datafusion::arrow::array::{Int64Array, StringArray};
 use datafusion::arrow::datatypes::SchemaRef;
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::DFSchema;
@@ -20,7 +21,7 @@ use datafusion::optimizer::utils::{conjunction, split_conjunction};
 use datafusion::physical_expr::PhysicalExpr;
 use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::memory::MemoryExec;
-use datafusion::physical_plan::{project_schema, ExecutionPlan};
+use datafusion::physical_plan::{collect, project_schema, ExecutionPlan};
 use datafusion::prelude::Column;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
@@ -275,6 +276,39 @@ impl ClusterTable {
         self.schema.clone()
     }
 
+    /// Returns the distinct non-null values of the tag in ascending order.
+    pub async fn tag_values(&self, ctx: &SessionState, tag: &str) -> Result<Vec<String>> {
+        let column = match self.schema.column(tag) {
+            Some(column) if column.column_type.is_tag() => column,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "Column {} is not a tag of table {}",
+                    tag, self.schema.name
+                )))
+            }
+        };
+        let projected_schema = Arc::new(Schema::new(vec![Field::from(column)]));
+
+        let plan = self
+            .create_tag_scan_physical_plan(ctx, projected_schema, &[], None)
+            .await?;
+        let batches = collect(plan, ctx.task_ctx()).await?;
+
+        let mut values = BTreeSet::new();
+        for batch in batches {
+            let array = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| {
+                    DataFusionError::Internal(format!("Tag {} is not a string column", tag))
+                })?;
+            values.extend(array.iter().flatten().map(|v| v.to_string()));
+        }
+
+        Ok(values.into_iter().collect())
+    }
+
     // Check and return the projected schema
     fn project_schema(&self, projection: Option<&Vec<usize>>) -> Result<SchemaRef> {
         valid_project(&self.schema, projection)
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use coordinator::service::CoordinatorRef;
 use datafusion::execution::context::SessionState;
 use datafusion::sql::TableReference;
+use models::follower_read::FollowerReadLag;
 use models::object_reference::Resolve;
 use models::predicate::PlacedSplit;
 use snafu::ResultExt;
@@ -33,7 +34,7 @@ impl SplitManager {
 
     pub async fn splits(
         &self,
-        _ctx: &SessionState,
+        ctx: &SessionState,
         table_layout: TableLayoutHandle,
     ) -> QueryResult<Vec<PlacedSplit>> {
         let TableLayoutHandle {
@@ -53,9 +54,15 @@ impl SplitManager {
             .resolve(&table)
             .context(AnalyzePushedFilterSnafu)?;
 
+        let follower_read_max_lag = ctx.config().get_extension::<FollowerReadLag>().map(|e| *e);
+
         let shards = self
             .coord
-            .table_vnodes(&table_name, resolved_predicate.clone())
+            .table_vnodes(
+                &table_name,
+                resolved_predicate.clone(),
+                follower_read_max_lag,
+            )
             .await
             .context(CoordinatorSnafu)?;
 
@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::collections::BTreeSet;
 use std::fmt::Display;
 use std::sync::Arc;
 use std::write;
@@ -17,6 +18,7 @@ use datafusion::logical_expr::{
 use datafusion::physical_expr::PhysicalExpr;
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion::prelude::Expr;
+use models::schema::TIME_FIELD_NAME;
 use spi::query::datasource::stream::StreamProviderRef;
 use trace::{debug, warn};
 
@@ -32,6 +34,8 @@ pub struct TableSourceAdapter {
     database_name: String,
     table_name: String,
     table_handle: TableHandle,
+    // Some: only some columns of the table are exposed
+    projected_schema: Option<SchemaRef>,
 
     plan: LogicalPlan,
 }
@@ -85,10 +89,34 @@ impl TableSourceAdapter {
             database_name,
             table_name,
             table_handle,
+            projected_schema: None,
             plan,
         })
     }
 
+    /// Only expose the given columns of the table, the time column is always exposed.
+    pub fn with_columns(mut self, columns: &BTreeSet<String>) -> DFResult<Self> {
+        let schema = self.table_handle.schema();
+        let indices = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.name() == TIME_FIELD_NAME || columns.contains(f.name()))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        let exprs = indices
+            .iter()
+            .map(|i| Expr::Column(self.plan.schema().field(*i).qualified_column()))
+            .collect::<Vec<_>>();
+        self.plan = LogicalPlanBuilder::from(self.plan)
+            .project(exprs)?
+            .build()?;
+        self.projected_schema = Some(Arc::new(schema.project(&indices)?));
+
+        Ok(self)
+    }
+
     pub fn database_name(&self) -> &str {
         &self.database_name
     }
@@ -113,7 +141,9 @@ impl TableSource for TableSourceAdapter {
     }
 
     fn schema(&self) -> SchemaRef {
-        self.table_handle.schema()
+        self.projected_schema
+            .clone()
+            .unwrap_or_else(|| self.table_handle.schema())
     }
 
     fn supports_filters_pushdown(
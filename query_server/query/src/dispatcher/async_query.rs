@@ -0,0 +1,700 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::ipc::reader::FileReader;
+use datafusion::arrow::ipc::writer::FileWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
+use models::meta_data::NodeId;
+use models::schema::query_info::{QueryId, QueryInfo};
+use object_store::aws::AmazonS3Builder;
+use object_store::azure::MicrosoftAzureBuilder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use parking_lot::{Mutex, RwLock};
+use snafu::ResultExt;
+use spi::query::async_query::{AsyncQueryState, AsyncQueryStatus};
+use spi::query::execution::Output;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{ObjectStoreSnafu, QueryError, QueryResult, StdIoSnafu};
+use tokio::task::AbortHandle;
+use trace::{debug, info, warn};
+use tskv::kv_option::QueryOptions;
+
+const PAGE_FILE_EXTENSION: &str = "arrow";
+const MAX_EXPIRE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps the queries submitted through the async query api.
+///
+/// The results of a query are spooled as pages of arrow ipc files to a local directory or an
+/// object store under `{prefix}/{node_id}/{query_id}/{page}.arrow`, clients poll the status and
+/// fetch the pages later, until the results expire `ttl` after the query is done.
+///
+/// The queries are only kept in memory, so they are served by the node that accepted them and
+/// are lost when the node restarts.
+///
+/// A user or a tenant keeps a limited number of queries, when a new query would exceed the limit,
+/// the results of its oldest finished queries are removed before they expire.
+pub struct AsyncQueryManager {
+    node_id: NodeId,
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    ttl: Duration,
+    page_rows: usize,
+    max_queries_per_user: usize,
+    max_queries_per_tenant: usize,
+    queries: RwLock<HashMap<QueryId, Arc<AsyncQuery>>>,
+}
+
+impl AsyncQueryManager {
+    pub fn new(
+        node_id: NodeId,
+        store: Arc<dyn ObjectStore>,
+        prefix: Path,
+        ttl: Duration,
+        page_rows: usize,
+    ) -> Self {
+        Self {
+            node_id,
+            store,
+            prefix,
+            ttl,
+            page_rows: page_rows.max(1),
+            max_queries_per_user: usize::MAX,
+            max_queries_per_tenant: usize::MAX,
+            queries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_query_limits(mut self, per_user: usize, per_tenant: usize) -> Self {
+        self.max_queries_per_user = per_user.max(1);
+        self.max_queries_per_tenant = per_tenant.max(1);
+        self
+    }
+
+    pub fn try_new(options: &QueryOptions, node_id: NodeId) -> QueryResult<Self> {
+        let (store, prefix) = build_object_store(&options.async_result_dir)?;
+        Ok(Self::new(
+            node_id,
+            store,
+            prefix.child(node_id.to_string()),
+            options.async_result_ttl,
+            options.async_result_page_rows,
+        )
+        .with_query_limits(
+            options.async_max_queries_per_user,
+            options.async_max_queries_per_tenant,
+        ))
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Keep a new query before it is executed, the oldest finished queries of its user and
+    /// tenant are removed if it would exceed their limits.
+    ///
+    /// Errors:
+    ///     [`QueryError::AsyncQueryLimitExceeded`] if the user or tenant has too many running queries
+    pub async fn admit(&self, info: QueryInfo) -> QueryResult<Arc<AsyncQuery>> {
+        let query = Arc::new(AsyncQuery::new(info));
+        let evicted = {
+            let mut queries = self.queries.write();
+            let evicted = self.evict_for(&queries, &query.info)?;
+            for query_id in &evicted {
+                debug!("Async query {} evicted", query_id);
+            }
+            let evicted = evicted
+                .iter()
+                .filter_map(|query_id| queries.remove(query_id))
+                .collect::<Vec<_>>();
+            queries.insert(query.info.query_id(), query.clone());
+            evicted
+        };
+
+        for query in evicted {
+            self.discard(&query).await;
+        }
+
+        Ok(query)
+    }
+
+    /// Spool the results of an admitted query in the background.
+    pub fn submit(self: &Arc<Self>, query: Arc<AsyncQuery>, output: Output) {
+        let mut task = query.task.lock();
+        if self.query(&query.info.query_id()).is_none() {
+            // removed before it started, dropping the output cancels it
+            return;
+        }
+        *task = Some(tokio::spawn(self.clone().spool(query.clone(), output)).abort_handle());
+    }
+
+    pub fn query(&self, query_id: &QueryId) -> Option<Arc<AsyncQuery>> {
+        self.queries.read().get(query_id).cloned()
+    }
+
+    pub fn queries(&self) -> Vec<Arc<AsyncQuery>> {
+        self.queries.read().values().cloned().collect()
+    }
+
+    pub fn status(&self, query: &AsyncQuery) -> AsyncQueryStatus {
+        query.status(self.ttl)
+    }
+
+    /// Read a page of the results, the pages already spooled can be read while the query is running.
+    ///
+    /// Errors:
+    ///     [`QueryError::AsyncQueryResultNotReady`] if the page is not spooled yet
+    ///     [`QueryError::AsyncQueryPageNotFound`] if the query is done and has fewer pages
+    pub async fn fetch(&self, query_id: QueryId, page: usize) -> QueryResult<Output> {
+        let query = self
+            .query(&query_id)
+            .ok_or(QueryError::QueryNotFound { query_id })?;
+        let (state, pages) = {
+            let progress = query.progress.read();
+            (progress.state, progress.pages)
+        };
+        if page >= pages {
+            return if state.is_done() {
+                Err(QueryError::AsyncQueryPageNotFound {
+                    query_id,
+                    page,
+                    pages,
+                })
+            } else {
+                Err(QueryError::AsyncQueryResultNotReady { query_id, page })
+            };
+        }
+
+        let data = self
+            .store
+            .get(&self.page_path(query_id, page))
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?
+            .bytes()
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?;
+        let reader = FileReader::try_new(Cursor::new(data), None)?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Output::StreamData(Box::pin(RecordBatchStreamWrapper::new(
+            schema, batches,
+        ))))
+    }
+
+    /// Stop spooling the results of a query if it is still running, and delete its results.
+    pub async fn remove(&self, query_id: &QueryId) -> Option<Arc<AsyncQuery>> {
+        let query = self.queries.write().remove(query_id)?;
+        self.discard(&query).await;
+        Some(query)
+    }
+
+    async fn discard(&self, query: &AsyncQuery) {
+        if let Some(task) = query.task.lock().take() {
+            task.abort();
+        }
+        let pages = {
+            let mut progress = query.progress.write();
+            if !progress.state.is_done() {
+                progress.state = AsyncQueryState::Cancelled;
+                progress.finished_at = Some(Instant::now());
+            }
+            progress.pages
+        };
+
+        for page in 0..pages {
+            let path = self.page_path(query.info.query_id(), page);
+            if let Err(err) = self.store.delete(&path).await {
+                warn!("Delete async query result {} failed: {}", path, err);
+            }
+        }
+    }
+
+    /// The finished queries to remove so that a new query of `info` fits in the limits,
+    /// oldest first.
+    fn evict_for(
+        &self,
+        queries: &HashMap<QueryId, Arc<AsyncQuery>>,
+        info: &QueryInfo,
+    ) -> QueryResult<Vec<QueryId>> {
+        let mut kept = queries
+            .values()
+            .filter(|e| e.info.tenant_id() == info.tenant_id())
+            .collect::<Vec<_>>();
+        kept.sort_by_key(|e| e.submitted_at);
+
+        let limits = [
+            (
+                self.max_queries_per_user,
+                Some(info.user_id()),
+                info.user_name(),
+            ),
+            (self.max_queries_per_tenant, None, info.tenant_name()),
+        ];
+        let mut evicted = vec![];
+        for (limit, user_id, owner) in limits {
+            let owned =
+                |query: &AsyncQuery| user_id.is_none() || user_id == Some(query.info.user_id());
+            let mut count = kept.iter().filter(|e| owned(e)).count();
+            while count >= limit {
+                let position = kept
+                    .iter()
+                    .position(|e| owned(e) && e.is_done())
+                    .ok_or_else(|| QueryError::AsyncQueryLimitExceeded {
+                        owner: owner.to_string(),
+                        limit,
+                    })?;
+                evicted.push(kept.remove(position).info.query_id());
+                count -= 1;
+            }
+        }
+
+        Ok(evicted)
+    }
+
+    /// Remove the queries whose results have expired.
+    pub async fn expire(&self) {
+        let expired = self
+            .queries
+            .read()
+            .iter()
+            .filter(|(_, query)| query.is_expired(self.ttl))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+
+        for query_id in expired {
+            debug!("Async query {} expired", query_id);
+            self.remove(&query_id).await;
+        }
+    }
+
+    /// Delete the results left by a previous run of this node, then expire results periodically.
+    pub fn start_expire_task(self: &Arc<Self>) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            manager.remove_orphan_results().await;
+
+            let interval = manager
+                .ttl
+                .clamp(Duration::from_secs(1), MAX_EXPIRE_INTERVAL);
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                manager.expire().await;
+            }
+        });
+    }
+
+    async fn remove_orphan_results(&self) {
+        let orphans = match self.store.list(Some(&self.prefix)).await {
+            Ok(stream) => stream.try_collect::<Vec<_>>().await,
+            Err(err) => Err(err),
+        };
+        match orphans {
+            Ok(orphans) => {
+                if !orphans.is_empty() {
+                    info!(
+                        "Remove {} async query results of the last run",
+                        orphans.len()
+                    );
+                }
+                for meta in orphans {
+                    if let Err(err) = self.store.delete(&meta.location).await {
+                        warn!(
+                            "Delete async query result {} failed: {}",
+                            meta.location, err
+                        );
+                    }
+                }
+            }
+            Err(err) => warn!("List async query results failed: {}", err),
+        }
+    }
+
+    async fn spool(self: Arc<Self>, query: Arc<AsyncQuery>, mut output: Output) {
+        let query_id = query.info.query_id();
+        let result = self.write_pages(&query, &mut output).await;
+        // release the result stream, so that the query is no longer tracked as running
+        drop(output);
+
+        let mut progress = query.progress.write();
+        progress.finished_at = Some(Instant::now());
+        match result {
+            Ok(()) => progress.state = AsyncQueryState::Finished,
+            Err(QueryError::Cancel) => progress.state = AsyncQueryState::Cancelled,
+            Err(err) => {
+                warn!("Async query {} failed: {}", query_id, err);
+                progress.state = AsyncQueryState::Failed;
+                progress.error = Some(err.to_string());
+            }
+        }
+    }
+
+    async fn write_pages(&self, query: &AsyncQuery, output: &mut Output) -> QueryResult<()> {
+        let schema = output.schema();
+        let mut buffer = vec![];
+        let mut buffered_rows = 0;
+
+        while let Some(batch) = output.try_next().await? {
+            let mut offset = 0;
+            while offset < batch.num_rows() {
+                let len = (self.page_rows - buffered_rows).min(batch.num_rows() - offset);
+                buffer.push(batch.slice(offset, len));
+                offset += len;
+                buffered_rows += len;
+
+                if buffered_rows == self.page_rows {
+                    self.write_page(query, &schema, mem::take(&mut buffer))
+                        .await?;
+                    buffered_rows = 0;
+                }
+            }
+        }
+
+        // an empty result still has a page, which carries the schema
+        if buffered_rows > 0 || query.progress.read().pages == 0 {
+            self.write_page(query, &schema, buffer).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_page(
+        &self,
+        query: &AsyncQuery,
+        schema: &SchemaRef,
+        batches: Vec<RecordBatch>,
+    ) -> QueryResult<()> {
+        let mut writer = FileWriter::try_new(Vec::new(), schema)?;
+        let mut rows = 0;
+        for batch in &batches {
+            writer.write(batch)?;
+            rows += batch.num_rows();
+        }
+        writer.finish()?;
+        let data = writer.into_inner()?;
+
+        let page = query.progress.read().pages;
+        let path = self.page_path(query.info.query_id(), page);
+        self.store
+            .put(&path, Bytes::from(data))
+            .await
+            .map_err(|e| ObjectStoreSnafu { msg: e.to_string() }.build())?;
+        debug!("Spooled {} rows of async query to {}", rows, path);
+
+        let mut progress = query.progress.write();
+        progress.pages += 1;
+        progress.rows += rows as u64;
+
+        Ok(())
+    }
+
+    fn page_path(&self, query_id: QueryId, page: usize) -> Path {
+        self.prefix
+            .child(query_id.to_string())
+            .child(format!("{page}.{PAGE_FILE_EXTENSION}"))
+    }
+}
+
+pub struct AsyncQuery {
+    info: QueryInfo,
+    submitted_at: Instant,
+    progress: RwLock<Progress>,
+    task: Mutex<Option<AbortHandle>>,
+}
+
+struct Progress {
+    state: AsyncQueryState,
+    rows: u64,
+    pages: usize,
+    error: Option<String>,
+    finished_at: Option<Instant>,
+}
+
+impl AsyncQuery {
+    fn new(info: QueryInfo) -> Self {
+        Self {
+            info,
+            submitted_at: Instant::now(),
+            progress: RwLock::new(Progress {
+                state: AsyncQueryState::Running,
+                rows: 0,
+                pages: 0,
+                error: None,
+                finished_at: None,
+            }),
+            task: Mutex::new(None),
+        }
+    }
+
+    pub fn info(&self) -> &QueryInfo {
+        &self.info
+    }
+
+    fn status(&self, ttl: Duration) -> AsyncQueryStatus {
+        let progress = self.progress.read();
+        let duration = progress
+            .finished_at
+            .unwrap_or_else(Instant::now)
+            .duration_since(self.submitted_at);
+        let expires_in = progress.finished_at.map(|e| {
+            (e + ttl)
+                .saturating_duration_since(Instant::now())
+                .as_secs()
+        });
+
+        AsyncQueryStatus {
+            query_id: self.info.query_id(),
+            state: progress.state,
+            rows: progress.rows,
+            pages: progress.pages,
+            error: progress.error.clone(),
+            duration: duration.as_secs_f64(),
+            expires_in,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.progress.read().state.is_done()
+    }
+
+    fn is_expired(&self, ttl: Duration) -> bool {
+        self.progress
+            .read()
+            .finished_at
+            .is_some_and(|e| e.elapsed() >= ttl)
+    }
+}
+
+/// Build the object store of `dir`, returns the store and the path prefix of the results in it.
+fn build_object_store(dir: &str) -> QueryResult<(Arc<dyn ObjectStore>, Path)> {
+    let object_store_error =
+        |e: object_store::Error| ObjectStoreSnafu { msg: e.to_string() }.build();
+
+    let (scheme, rest) = match dir.split_once("://") {
+        Some((scheme, rest)) => (scheme, rest),
+        None => ("file", dir),
+    };
+    let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+    let url = format!("{scheme}://{bucket}");
+
+    let store: Arc<dyn ObjectStore> = match scheme {
+        "file" => {
+            std::fs::create_dir_all(rest).context(StdIoSnafu)?;
+            let store = LocalFileSystem::new_with_prefix(rest).map_err(object_store_error)?;
+            return Ok((Arc::new(store), Path::default()));
+        }
+        "s3" | "s3a" => Arc::new(
+            AmazonS3Builder::from_env()
+                .with_url(url)
+                .build()
+                .map_err(object_store_error)?,
+        ),
+        "gs" => Arc::new(
+            GoogleCloudStorageBuilder::from_env()
+                .with_url(url)
+                .build()
+                .map_err(object_store_error)?,
+        ),
+        "az" | "azure" | "abfs" | "abfss" => Arc::new(
+            MicrosoftAzureBuilder::from_env()
+                .with_url(url)
+                .build()
+                .map_err(object_store_error)?,
+        ),
+        _ => {
+            return Err(ObjectStoreSnafu {
+                msg: format!("unsupported async query result location: {dir}"),
+            }
+            .build())
+        }
+    };
+
+    Ok((store, Path::from(prefix)))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use models::auth::user::{User, UserDesc, UserOptions};
+    use models::schema::query_info::{QueryId, QueryInfo};
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use spi::query::async_query::AsyncQueryState;
+    use spi::query::execution::Output;
+    use spi::query::recordbatch::RecordBatchStreamWrapper;
+    use spi::QueryError;
+
+    use super::AsyncQueryManager;
+
+    fn query_info(query_id: QueryId) -> QueryInfo {
+        user_query_info(query_id, 0)
+    }
+
+    fn user_query_info(query_id: QueryId, user_id: u128) -> QueryInfo {
+        let desc = UserDesc::new(user_id, "user".to_string(), UserOptions::default(), false);
+        let user = User::new(desc, HashSet::new(), None);
+        QueryInfo::new(
+            query_id,
+            "select * from t".to_string(),
+            0_u128,
+            "cnosdb".to_string(),
+            "public".to_string(),
+            user,
+            0,
+        )
+    }
+
+    fn output(rows: Vec<Vec<i64>>) -> Output {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        let batches = rows
+            .into_iter()
+            .map(|e| {
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(Int64Array::from(e))]).unwrap()
+            })
+            .collect();
+        Output::StreamData(Box::pin(RecordBatchStreamWrapper::new(schema, batches)))
+    }
+
+    async fn wait_done(manager: &AsyncQueryManager, query_id: QueryId) {
+        for _ in 0..100 {
+            let query = manager.query(&query_id).unwrap();
+            if manager.status(&query).state.is_done() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("async query {query_id} not done");
+    }
+
+    #[tokio::test]
+    async fn test_spool_and_fetch_pages() {
+        let manager = Arc::new(AsyncQueryManager::new(
+            0,
+            Arc::new(InMemory::new()),
+            Path::from("results"),
+            Duration::from_secs(60),
+            3,
+        ));
+        let query_id = QueryId::next_id();
+        let query = manager.admit(query_info(query_id)).await.unwrap();
+        manager.submit(query, output(vec![vec![1, 2], vec![3, 4, 5, 6], vec![7]]));
+        wait_done(&manager, query_id).await;
+
+        let status = manager.status(&manager.query(&query_id).unwrap());
+        assert_eq!(status.state, AsyncQueryState::Finished);
+        assert_eq!(status.rows, 7);
+        assert_eq!(status.pages, 3);
+        assert!(status.expires_in.is_some());
+
+        let page_rows = |output: Output| async move { output.num_rows().await };
+        assert_eq!(
+            page_rows(manager.fetch(query_id, 0).await.unwrap()).await,
+            3
+        );
+        assert_eq!(
+            page_rows(manager.fetch(query_id, 1).await.unwrap()).await,
+            3
+        );
+        assert_eq!(
+            page_rows(manager.fetch(query_id, 2).await.unwrap()).await,
+            1
+        );
+        assert!(matches!(
+            manager.fetch(query_id, 3).await,
+            Err(QueryError::AsyncQueryPageNotFound { pages: 3, .. })
+        ));
+
+        assert!(manager.remove(&query_id).await.is_some());
+        assert!(matches!(
+            manager.fetch(query_id, 0).await,
+            Err(QueryError::QueryNotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_empty_result_and_expire() {
+        let manager = Arc::new(AsyncQueryManager::new(
+            0,
+            Arc::new(InMemory::new()),
+            Path::default(),
+            Duration::ZERO,
+            3,
+        ));
+        let query_id = QueryId::next_id();
+        let query = manager.admit(query_info(query_id)).await.unwrap();
+        manager.submit(query, output(vec![]));
+        wait_done(&manager, query_id).await;
+
+        let status = manager.status(&manager.query(&query_id).unwrap());
+        assert_eq!(status.rows, 0);
+        assert_eq!(status.pages, 1);
+        let page = manager.fetch(query_id, 0).await.unwrap();
+        assert_eq!(page.schema().fields().len(), 1);
+
+        manager.expire().await;
+        assert!(manager.query(&query_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_limits() {
+        let manager = Arc::new(
+            AsyncQueryManager::new(
+                0,
+                Arc::new(InMemory::new()),
+                Path::default(),
+                Duration::from_secs(60),
+                3,
+            )
+            .with_query_limits(2, 3),
+        );
+
+        // a running query is never evicted
+        let running = manager.admit(query_info(QueryId::next_id())).await.unwrap();
+        let finished = QueryId::next_id();
+        let query = manager.admit(query_info(finished)).await.unwrap();
+        manager.submit(query, output(vec![vec![1]]));
+        wait_done(&manager, finished).await;
+
+        // the oldest finished query of the user makes room for the new one
+        let next = QueryId::next_id();
+        manager.admit(query_info(next)).await.unwrap();
+        assert!(manager.query(&finished).is_none());
+        assert!(manager.query(&running.info().query_id()).is_some());
+
+        assert!(matches!(
+            manager.admit(query_info(QueryId::next_id())).await,
+            Err(QueryError::AsyncQueryLimitExceeded { limit: 2, .. })
+        ));
+
+        // another user of the tenant is limited by the tenant
+        manager
+            .admit(user_query_info(QueryId::next_id(), 1))
+            .await
+            .unwrap();
+        assert!(matches!(
+            manager.admit(user_query_info(QueryId::next_id(), 1)).await,
+            Err(QueryError::AsyncQueryLimitExceeded { limit: 3, .. })
+        ));
+
+        manager.remove(&next).await;
+        manager
+            .admit(user_query_info(QueryId::next_id(), 1))
+            .await
+            .unwrap();
+    }
+}
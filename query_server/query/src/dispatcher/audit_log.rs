@@ -0,0 +1,502 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{mpsc, Arc};
+use std::task::{Context, Poll};
+
+use config::tskv::AuditConfig;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+use datafusion::sql::sqlparser::tokenizer::{Token, Tokenizer};
+use futures::{Stream, StreamExt};
+use models::utils::now_timestamp_millis;
+use snafu::ResultExt;
+use spi::query::ast::ExtStatement;
+use spi::query::audit::{classify_statement, AuditEntry, AuditOutcome};
+use spi::query::execution::QueryStateMachine;
+use spi::{QueryError, QueryResult, StdIoSnafu};
+use tokio::sync::oneshot;
+use trace::warn;
+
+use crate::sql::dialect::CnosDBDialect;
+
+const AUDIT_LOG_FILE: &str = "audit.log";
+
+/// The values of the options whose names contain these words are not recorded,
+/// e.g. `PASSWORD = '...'` of CREATE USER and `secret_key = '...'` of COPY.
+const SECRET_OPTIONS: [&str; 5] = ["password", "secret", "token", "private_key", "access_key"];
+
+/// The audit log of the DDL, DCL and data access statements.
+///
+/// The records are appended as json lines to `{path}/audit.log` of the node executing the
+/// statements, the file is rotated to `audit.log.1`, `audit.log.2`, ... when it exceeds
+/// `max_file_size`, and only the latest `max_files` rotated files are kept.
+///
+/// The files are written and read by a background thread, the statements only queue
+/// their records to it.
+pub struct AuditLog {
+    config: Option<AuditConfig>,
+    writer: Option<mpsc::Sender<AuditCommand>>,
+}
+
+enum AuditCommand {
+    Append(AuditEntry),
+    Entries(oneshot::Sender<QueryResult<Vec<AuditEntry>>>),
+}
+
+impl AuditLog {
+    pub fn new(config: Option<AuditConfig>) -> Self {
+        let writer = config.clone().and_then(|config| {
+            let (sender, receiver) = mpsc::channel();
+            let writer = AuditWriter { config, file: None };
+            match std::thread::Builder::new()
+                .name("audit-log".to_string())
+                .spawn(move || writer.run(receiver))
+            {
+                Ok(_) => Some(sender),
+                Err(err) => {
+                    warn!("Failed to start the writer of audit log, error: {}", err);
+                    None
+                }
+            }
+        });
+
+        Self { config, writer }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// Begins the record of the statement, returns `None` if it's not audited
+    pub fn begin(
+        &self,
+        query_state_machine: &QueryStateMachine,
+        stmt: &ExtStatement,
+    ) -> Option<AuditEntry> {
+        let config = self.config.as_ref()?;
+        let (category, statement_type, objects) = classify_statement(stmt)?;
+
+        let session = &query_state_machine.session;
+        let tenant = session.tenant();
+        let user = session.user().desc().name();
+        let audited = config
+            .categories
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(category.as_str()))
+            && (config.tenants.is_empty() || config.tenants.iter().any(|t| t == tenant))
+            && (config.users.is_empty() || config.users.iter().any(|u| u == user));
+        if !audited {
+            return None;
+        }
+
+        let query = &query_state_machine.query;
+        Some(AuditEntry {
+            time: now_timestamp_millis(),
+            query_id: query_state_machine.query_id.to_string(),
+            tenant: tenant.to_string(),
+            user: user.to_string(),
+            client_addr: query.context().client_addr().map(ToString::to_string),
+            database: session.default_database().to_string(),
+            category,
+            statement_type,
+            objects,
+            privileges: vec![],
+            sql: redact_sql(query.content()),
+            outcome: AuditOutcome::Success,
+            error: None,
+        })
+    }
+
+    /// Finishes the record with the outcome of the statement and appends it to the log
+    pub fn finish(&self, mut entry: AuditEntry, result: Result<(), &QueryError>) {
+        if let Err(err) = result {
+            entry.outcome = match err {
+                QueryError::InsufficientPrivileges { privilege } => {
+                    if !entry.privileges.contains(privilege) {
+                        entry.privileges.push(privilege.clone());
+                    }
+                    AuditOutcome::Denied
+                }
+                _ => AuditOutcome::Failed,
+            };
+            entry.error = Some(err.to_string());
+        }
+
+        self.append(entry);
+    }
+
+    /// Finishes the record when the result of the statement is consumed
+    pub fn finish_stream(
+        self: &Arc<Self>,
+        entry: AuditEntry,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        Box::pin(AuditedRecordBatchStream {
+            inner: stream,
+            entry: Some(entry),
+            audit_log: self.clone(),
+        })
+    }
+
+    fn append(&self, entry: AuditEntry) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+        // the statement is not failed by the audit log
+        if let Err(mpsc::SendError(AuditCommand::Append(entry))) =
+            writer.send(AuditCommand::Append(entry))
+        {
+            warn!(
+                "Failed to write audit log of query {}, the writer is stopped",
+                entry.query_id
+            );
+        }
+    }
+
+    /// All the records kept in the log files, the oldest first
+    pub async fn entries(&self) -> QueryResult<Vec<AuditEntry>> {
+        let Some(writer) = &self.writer else {
+            return Ok(vec![]);
+        };
+
+        let stopped = || QueryError::Internal {
+            reason: "the writer of audit log is stopped".to_string(),
+        };
+        let (sender, receiver) = oneshot::channel();
+        writer
+            .send(AuditCommand::Entries(sender))
+            .map_err(|_| stopped())?;
+        receiver.await.map_err(|_| stopped())?
+    }
+}
+
+/// Owns the log files, the records are appended and read in the order they are queued
+struct AuditWriter {
+    config: AuditConfig,
+    // the current file and its size
+    file: Option<(File, u64)>,
+}
+
+impl AuditWriter {
+    fn run(mut self, receiver: mpsc::Receiver<AuditCommand>) {
+        for command in receiver {
+            match command {
+                AuditCommand::Append(entry) => {
+                    if let Err(err) = self.append(&entry) {
+                        warn!(
+                            "Failed to write audit log of query {}, error: {}",
+                            entry.query_id, err
+                        );
+                    }
+                }
+                AuditCommand::Entries(sender) => {
+                    let _ = sender.send(self.entries());
+                }
+            }
+        }
+    }
+
+    fn append(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        let dir = Path::new(&self.config.path);
+
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let (mut file, mut size) = match self.file.take() {
+            Some(file) => file,
+            None => open_log(dir)?,
+        };
+        if size > 0 && size + line.len() as u64 > self.config.max_file_size {
+            drop(file);
+            rotate(dir, self.config.max_files)?;
+            (file, size) = open_log(dir)?;
+        }
+        file.write_all(&line)?;
+        self.file = Some((file, size + line.len() as u64));
+
+        Ok(())
+    }
+
+    fn entries(&self) -> QueryResult<Vec<AuditEntry>> {
+        let dir = Path::new(&self.config.path);
+
+        let mut files = (1..=self.config.max_files)
+            .rev()
+            .map(|i| rotated_log(dir, i))
+            .collect::<Vec<_>>();
+        files.push(dir.join(AUDIT_LOG_FILE));
+
+        let mut entries = vec![];
+        for path in files {
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err).context(StdIoSnafu),
+            };
+            for line in BufReader::new(file).lines() {
+                let line = line.context(StdIoSnafu)?;
+                match serde_json::from_str::<AuditEntry>(&line) {
+                    Ok(entry) => entries.push(entry),
+                    // e.g. the last line partially written before a crash
+                    Err(err) => warn!("Skip invalid audit record in {:?}: {}", path, err),
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Finishes the audit record when the stream ends, fails, or is dropped before it ends
+struct AuditedRecordBatchStream {
+    inner: SendableRecordBatchStream,
+    entry: Option<AuditEntry>,
+    audit_log: Arc<AuditLog>,
+}
+
+impl AuditedRecordBatchStream {
+    fn finish(&mut self, outcome: AuditOutcome, error: Option<String>) {
+        if let Some(mut entry) = self.entry.take() {
+            entry.outcome = outcome;
+            entry.error = error;
+            self.audit_log.append(entry);
+        }
+    }
+}
+
+impl RecordBatchStream for AuditedRecordBatchStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Stream for AuditedRecordBatchStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.inner.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(Err(err))) => self.finish(AuditOutcome::Failed, Some(err.to_string())),
+            Poll::Ready(None) => self.finish(AuditOutcome::Success, None),
+            _ => {}
+        }
+        poll
+    }
+}
+
+impl Drop for AuditedRecordBatchStream {
+    fn drop(&mut self) {
+        self.finish(
+            AuditOutcome::Failed,
+            Some("the result is dropped before it is fully consumed".to_string()),
+        );
+    }
+}
+
+/// Replaces the values of the secret options in the statement with `***`
+fn redact_sql(sql: &str) -> String {
+    let Ok(tokens) = Tokenizer::new(&CnosDBDialect, sql).tokenize() else {
+        return "***".to_string();
+    };
+
+    let mut redacted = String::with_capacity(sql.len());
+    // the last word is the name of a secret option, and its value is not seen yet
+    let mut secret = false;
+    for token in tokens {
+        match &token {
+            Token::Whitespace(_) => {}
+            Token::Word(word) => {
+                let name = word.value.to_ascii_lowercase();
+                secret = SECRET_OPTIONS.iter().any(|option| name.contains(option));
+            }
+            Token::Eq if secret => {}
+            Token::SingleQuotedString(_)
+            | Token::DoubleQuotedString(_)
+            | Token::NationalStringLiteral(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::HexStringLiteral(_)
+                if secret =>
+            {
+                redacted.push_str("'***'");
+                secret = false;
+                continue;
+            }
+            _ => secret = false,
+        }
+        redacted.push_str(&token.to_string());
+    }
+
+    redacted
+}
+
+fn open_log(dir: &Path) -> io::Result<(File, u64)> {
+    fs::create_dir_all(dir)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(AUDIT_LOG_FILE))?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+}
+
+fn rotated_log(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{AUDIT_LOG_FILE}.{index}"))
+}
+
+/// `audit.log.{n}` is renamed to `audit.log.{n+1}`, the oldest one is removed
+fn rotate(dir: &Path, max_files: usize) -> io::Result<()> {
+    let current = dir.join(AUDIT_LOG_FILE);
+    if max_files == 0 {
+        return fs::remove_file(current);
+    }
+
+    if let Err(err) = fs::remove_file(rotated_log(dir, max_files)) {
+        if err.kind() != io::ErrorKind::NotFound {
+            return Err(err);
+        }
+    }
+    for index in (1..max_files).rev() {
+        let from = rotated_log(dir, index);
+        if from.exists() {
+            fs::rename(from, rotated_log(dir, index + 1))?;
+        }
+    }
+    fs::rename(current, rotated_log(dir, 1))
+}
+
+#[cfg(test)]
+mod test {
+    use spi::query::audit::AuditCategory;
+    use spi::query::parser::Parser;
+
+    use super::*;
+    use crate::sql::parser::DefaultParser;
+
+    fn classify(sql: &str) -> Option<(AuditCategory, String, Vec<String>)> {
+        let stmt = DefaultParser::default()
+            .parse(sql)
+            .unwrap()
+            .pop_front()
+            .unwrap();
+        classify_statement(&stmt)
+    }
+
+    #[test]
+    fn test_classify_statement() {
+        assert_eq!(
+            classify("DROP TABLE db1.t1"),
+            Some((
+                AuditCategory::Ddl,
+                "DROP TABLE".to_string(),
+                vec!["db1.t1".to_string()]
+            ))
+        );
+        assert_eq!(
+            classify("GRANT WRITE ON DATABASE db1 TO ROLE r1"),
+            Some((
+                AuditCategory::Dcl,
+                "GRANT".to_string(),
+                vec!["r1".to_string(), "db1".to_string()]
+            ))
+        );
+        assert_eq!(
+            classify("DELETE FROM t1 WHERE host = 'a'"),
+            Some((
+                AuditCategory::Dml,
+                "DELETE".to_string(),
+                vec!["t1".to_string()]
+            ))
+        );
+        assert_eq!(
+            classify("WITH w AS (SELECT * FROM t1) SELECT * FROM w JOIN t2 ON w.a = t2.a"),
+            Some((
+                AuditCategory::Query,
+                "SELECT".to_string(),
+                vec!["t1".to_string(), "t2".to_string()]
+            ))
+        );
+        assert_eq!(classify("INSERT INTO t1(time, a) VALUES (1, 1)"), None);
+        assert_eq!(classify("SHOW DATABASES"), None);
+    }
+
+    fn entry(query_id: usize) -> AuditEntry {
+        AuditEntry {
+            time: 0,
+            query_id: query_id.to_string(),
+            tenant: "cnosdb".to_string(),
+            user: "root".to_string(),
+            client_addr: Some("127.0.0.1:53412".to_string()),
+            database: "public".to_string(),
+            category: AuditCategory::Ddl,
+            statement_type: "DROP TABLE".to_string(),
+            objects: vec!["t1".to_string()],
+            privileges: vec![],
+            sql: "DROP TABLE t1".to_string(),
+            outcome: AuditOutcome::Success,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_redact_sql() {
+        assert_eq!(
+            redact_sql("CREATE USER u1 WITH PASSWORD='p@ss', COMMENT = 'c'"),
+            "CREATE USER u1 WITH PASSWORD='***', COMMENT = 'c'"
+        );
+        assert_eq!(
+            redact_sql("alter user u1 set password = 'p@ss'"),
+            "alter user u1 set password = '***'"
+        );
+        assert_eq!(
+            redact_sql(
+                "COPY INTO 's3://bucket/path' FROM t1 \
+                CONNECTION = (access_key_id = 'id', secret_key = 'key', token = 'tk')"
+            ),
+            "COPY INTO 's3://bucket/path' FROM t1 \
+            CONNECTION = (access_key_id = '***', secret_key = '***', token = '***')"
+        );
+        assert_eq!(
+            redact_sql("SELECT * FROM t1 WHERE host = 'a'"),
+            "SELECT * FROM t1 WHERE host = 'a'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit_log = AuditLog::new(Some(AuditConfig {
+            path: dir.path().to_string_lossy().to_string(),
+            max_file_size: 1,
+            max_files: 2,
+            ..Default::default()
+        }));
+
+        // each record is rotated to a file
+        for query_id in 0..5 {
+            audit_log.finish(entry(query_id), Ok(()));
+        }
+        // the records are written before they are read
+        let entries = audit_log.entries().await.unwrap();
+        assert!(rotated_log(dir.path(), 2).exists());
+        assert!(!rotated_log(dir.path(), 3).exists());
+        let query_ids = entries
+            .iter()
+            .map(|e| e.query_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(query_ids, vec!["2", "3", "4"]);
+
+        audit_log.finish(
+            entry(5),
+            Err(&QueryError::InsufficientPrivileges {
+                privilege: "Write on database db1".to_string(),
+            }),
+        );
+        let entry = audit_log.entries().await.unwrap().pop().unwrap();
+        assert_eq!(entry.outcome, AuditOutcome::Denied);
+        assert_eq!(entry.privileges, vec!["Write on database db1".to_string()]);
+    }
+}
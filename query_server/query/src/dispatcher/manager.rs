@@ -1,30 +1,39 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
 use coordinator::resource_manager::ResourceManager;
 use coordinator::service::CoordinatorRef;
-use memory_pool::MemoryPoolRef;
+use memory_pool::{MemoryPoolRef, QuotaMemoryPool};
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
 use models::auth::auth_cache::{AuthCache, AuthCacheKey};
+use models::auth::role::RoleQuota;
 use models::auth::user::User;
 use models::meta_data::{MetaModifyType, NodeId};
+use models::object_reference::ResolvedTable;
 use models::oid::Oid;
 use models::schema::query_info::{QueryId, QueryInfo};
 use models::schema::resource_info::{ResourceInfo, ResourceStatus};
+use models::schema::table_schema::TableSchema;
+use models::schema::view_schema::ViewSchema;
 use models::utils::now_timestamp_nanos;
 use snafu::ResultExt;
 use spi::query::ast::ExtStatement;
+use spi::query::config::StreamTriggerInterval;
 use spi::query::datasource::stream::StreamProviderManagerRef;
 use spi::query::dispatcher::{QueryDispatcher, QueryStatus};
 use spi::query::execution::{Output, QueryStateMachine};
 use spi::query::function::FuncMetaManagerRef;
-use spi::query::logical_planner::{LogicalPlanner, Plan};
+use spi::query::logical_planner::{
+    CreateView, DDLPlan, DatabaseObjectType, DropDatabaseObject, LogicalPlanner, Plan,
+    PlanWithPrivileges,
+};
 use spi::query::parser::Parser;
 use spi::query::session::{SessionCtx, SessionCtxFactory};
-use spi::service::protocol::{ContextBuilder, Query};
+use spi::service::protocol::{Context, ContextBuilder, Query};
 use spi::{MetaSnafu, QueryError, QueryResult};
 use tokio::sync::mpsc::Receiver;
 use tokio::task::JoinHandle;
@@ -35,6 +44,7 @@ use trace::span_ext::SpanExt;
 use trace::{error, info, Span, SpanContext};
 
 use super::query_tracker::QueryTracker;
+use super::quota::ResultRowsQuotaStream;
 use crate::data_source::split::SplitManagerRef;
 use crate::execution::factory::QueryExecutionFactoryRef;
 use crate::metadata::{
@@ -143,10 +153,23 @@ impl QueryDispatcher for SimpleQueryDispatcher {
 
         drop(span_recorder);
 
-        let logical_plan = self
-            .statement_to_logical_plan(stmt, &logical_planner, query_state_machine)
-            .await?;
-        Ok(Some(logical_plan))
+        // The record is finished when the plan is executed, or now if it can't be planned
+        let audit_log = self.query_tracker.audit_log();
+        let audit_entry = audit_log.begin(&query_state_machine, &stmt);
+        let result = self
+            .statement_to_logical_plan(stmt, &logical_planner, query_state_machine.clone())
+            .await;
+        if let Some(mut entry) = audit_entry {
+            match &result {
+                Ok(plan) => {
+                    entry.privileges = plan.privileges.iter().map(|p| p.to_string()).collect();
+                    query_state_machine.set_audit_entry(entry);
+                }
+                Err(err) => audit_log.finish(entry, Err(err)),
+            }
+        }
+
+        Ok(Some(result?.plan))
     }
 
     async fn execute_logical_plan(
@@ -166,11 +189,21 @@ impl QueryDispatcher for SimpleQueryDispatcher {
         span_ctx: Option<&SpanContext>,
         auth_cache: Arc<AuthCache<AuthCacheKey, User>>,
     ) -> QueryResult<Arc<QueryStateMachine>> {
+        let quota = self.user_quota(query.context()).await;
+        let memory_pool = match quota.max_query_memory {
+            Some(max_query_memory) => Arc::new(QuotaMemoryPool::new(
+                self.memory_pool.clone(),
+                max_query_memory,
+            )),
+            None => self.memory_pool.clone(),
+        };
+
         let session = self.session_factory.create_session_ctx(
             query_id.to_string(),
             query.context(),
             tenant_id,
-            self.memory_pool.clone(),
+            memory_pool,
+            quota,
             span_ctx.cloned(),
             self.coord.clone(),
         )?;
@@ -218,9 +251,13 @@ impl SimpleQueryDispatcher {
             let tenant_name = query.tenant_name();
             let tenant_id = query.tenant_id();
             let user = query.user().clone();
+            let stream_trigger_interval = query
+                .stream_trigger_interval()
+                .and_then(|e| StreamTriggerInterval::from_str(e).ok());
             let ctx = ContextBuilder::new(user)
                 .with_tenant(Some(tenant_name.to_owned()))
                 .with_database(Some(database_name.to_owned()))
+                .with_stream_trigger_interval(stream_trigger_interval)
                 .with_is_old(Some(true))
                 .build();
             let query = Query::new(ctx, sql.to_owned());
@@ -477,7 +514,7 @@ impl SimpleQueryDispatcher {
         stmt: ExtStatement,
         logical_planner: &DefaultLogicalPlanner<'_, S>,
         query_state_machine: Arc<QueryStateMachine>,
-    ) -> QueryResult<Plan> {
+    ) -> QueryResult<PlanWithPrivileges> {
         // begin analyze
         query_state_machine.begin_analyze();
         let logical_plan = logical_planner
@@ -496,18 +533,224 @@ impl SimpleQueryDispatcher {
         &self,
         logical_plan: Plan,
         query_state_machine: Arc<QueryStateMachine>,
+    ) -> QueryResult<Output> {
+        // The refresh query of a materialized view lives as long as the view
+        let refresh_to_start = match &logical_plan {
+            Plan::DDL(DDLPlan::CreateView(CreateView {
+                name,
+                materialized: Some(materialized),
+                ..
+            })) => Some((
+                name.clone(),
+                materialized.refresh_sql.clone(),
+                materialized.refresh_interval,
+            )),
+            _ => None,
+        };
+        let refresh_to_stop = match &logical_plan {
+            Plan::DDL(DDLPlan::DropDatabaseObject(DropDatabaseObject {
+                object_name,
+                obj_type: DatabaseObjectType::View,
+                ..
+            })) => self.materialized_view_refresh_query(object_name).await?,
+            _ => None,
+        };
+
+        let output = self
+            .track_and_start(logical_plan, query_state_machine.clone())
+            .await;
+        let output = match query_state_machine.take_audit_entry() {
+            // the record of a query is finished when its result is consumed
+            Some(entry) => match output {
+                Ok(Output::StreamData(stream)) => Ok(Output::StreamData(
+                    self.query_tracker.audit_log().finish_stream(entry, stream),
+                )),
+                output => {
+                    self.query_tracker
+                        .audit_log()
+                        .finish(entry, output.as_ref().map(|_| ()));
+                    output
+                }
+            },
+            None => output,
+        }?;
+
+        if let Some((view, refresh_sql, refresh_interval)) = refresh_to_start {
+            self.start_materialized_view_refresh(
+                &query_state_machine,
+                &view,
+                refresh_sql,
+                refresh_interval,
+            )
+            .await?;
+        }
+        if let Some(refresh_query_id) = refresh_to_stop {
+            self.stop_materialized_view_refresh(refresh_query_id);
+        }
+
+        Ok(output)
+    }
+
+    async fn track_and_start(
+        &self,
+        logical_plan: Plan,
+        query_state_machine: Arc<QueryStateMachine>,
     ) -> QueryResult<Output> {
         let execution = self
             .query_execution_factory
             .create_query_execution(logical_plan, query_state_machine.clone())
             .await?;
 
+        let quota = query_state_machine.session.quota();
+
         // TrackedQuery.drop() is called implicitly when the value goes out of scope,
-        self.query_tracker
-            .try_track_query(query_state_machine.query_id, execution)
+        let output = self
+            .query_tracker
+            .try_track_query(
+                query_state_machine.query_id,
+                execution,
+                quota.max_concurrent_queries,
+            )
             .await?
             .start()
+            .await?;
+
+        match (output, quota.max_result_rows) {
+            (Output::StreamData(stream), Some(max_result_rows)) => Ok(Output::StreamData(
+                Box::pin(ResultRowsQuotaStream::new(stream, max_result_rows)),
+            )),
+            (output, _) => Ok(output),
+        }
+    }
+
+    /// The quota of the role of the user in the tenant of the query
+    async fn user_quota(&self, context: &Context) -> RoleQuota {
+        let Some(role) = context.user().role() else {
+            return RoleQuota::default();
+        };
+
+        match self
+            .coord
+            .meta_manager()
+            .tenant_meta(context.tenant())
             .await
+        {
+            Some(client) => client.role_quota(role),
+            None => RoleQuota::default(),
+        }
+    }
+
+    /// The id of the refresh query of a materialized view, kept with the view.
+    async fn materialized_view_refresh_query(
+        &self,
+        view: &ResolvedTable,
+    ) -> QueryResult<Option<QueryId>> {
+        let Some(client) = self.coord.meta_manager().tenant_meta(view.tenant()).await else {
+            return Ok(None);
+        };
+        let refresh_query_id = match client
+            .get_table_schema(view.database(), view.table())
+            .context(MetaSnafu)?
+        {
+            Some(TableSchema::ViewTableSchema(view)) => view
+                .materialized()
+                .and_then(|materialized| materialized.refresh_query_id()),
+            _ => None,
+        };
+        Ok(refresh_query_id)
+    }
+
+    /// Start the stream query that refreshes a materialized view, as the user creating the view,
+    /// and keep the id of the query with the view.
+    async fn start_materialized_view_refresh(
+        &self,
+        creator: &QueryStateMachine,
+        view: &ResolvedTable,
+        refresh_sql: String,
+        refresh_interval: Duration,
+    ) -> QueryResult<()> {
+        let session = &creator.session;
+        let ctx = ContextBuilder::new(session.user().clone())
+            .with_tenant(Some(session.tenant().to_string()))
+            .with_database(Some(view.database().to_string()))
+            .with_stream_trigger_interval(Some(StreamTriggerInterval::Interval(refresh_interval)))
+            .build();
+        let query = Query::new(ctx, refresh_sql);
+
+        let query_state_machine = self
+            .build_query_state_machine(
+                *session.tenant_id(),
+                self.create_query_id(),
+                query,
+                self.span_ctx.as_ref(),
+                self.auth_cache.clone(),
+            )
+            .await?;
+        let query_id = query_state_machine.query_id;
+        if let Some(plan) = self.build_logical_plan(query_state_machine.clone()).await? {
+            self.track_and_start(plan, query_state_machine).await?;
+        }
+
+        if let Err(err) = self.save_refresh_query_id(view, query_id).await {
+            self.stop_materialized_view_refresh(query_id);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    async fn save_refresh_query_id(
+        &self,
+        view: &ResolvedTable,
+        query_id: QueryId,
+    ) -> QueryResult<()> {
+        let client = self
+            .coord
+            .meta_manager()
+            .tenant_meta(view.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: view.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+        let schema = match client
+            .get_table_schema(view.database(), view.table())
+            .context(MetaSnafu)?
+        {
+            Some(TableSchema::ViewTableSchema(schema)) => schema,
+            _ => {
+                return Err(MetaError::TableNotFound {
+                    table: view.to_string(),
+                })
+                .context(MetaSnafu)
+            }
+        };
+        let materialized = schema
+            .materialized()
+            .map(|materialized| materialized.clone().with_refresh_query_id(query_id));
+        let schema = ViewSchema::new(
+            schema.tenant(),
+            schema.db(),
+            schema.name(),
+            schema.query(),
+            schema.schema(),
+            materialized,
+        );
+        client
+            .update_table(&TableSchema::ViewTableSchema(Arc::new(schema)))
+            .await
+            .context(MetaSnafu)
+    }
+
+    fn stop_materialized_view_refresh(&self, refresh_query_id: QueryId) {
+        if let Some(query) = self.query_tracker.expire_query(&refresh_query_id) {
+            if let Err(err) = query.cancel() {
+                error!(
+                    "Failed to cancel refresh query {} of materialized view: {}",
+                    refresh_query_id, err
+                );
+            }
+        }
     }
 
     async fn build_scheme_provider(&self, session: &SessionCtx) -> QueryResult<MetadataProvider> {
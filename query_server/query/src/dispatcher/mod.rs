@@ -3,9 +3,12 @@ use models::meta_data::NodeId;
 use models::schema::query_info::{QueryId, QueryInfo};
 use spi::QueryResult;
 
+pub mod async_query;
+pub mod audit_log;
 pub mod manager;
 pub mod persister;
 pub mod query_tracker;
+pub mod quota;
 
 #[async_trait]
 pub trait QueryPersister {
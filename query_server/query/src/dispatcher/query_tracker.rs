@@ -12,6 +12,7 @@ use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::Result as DFResult;
 use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
 use futures::{Stream, StreamExt};
+use models::consistency_level::ConsistencyLevel;
 use models::meta_data::NodeId;
 use models::schema::query_info::{QueryId, QueryInfo};
 use models::schema::{CLUSTER_SCHEMA, DEFAULT_CATALOG};
@@ -25,6 +26,8 @@ use spi::{QueryError, QueryResult};
 use trace::{debug, warn};
 use utils::precision::Precision;
 
+use super::async_query::AsyncQueryManager;
+use super::audit_log::AuditLog;
 use super::persister::QueryPersisterRef;
 
 const SQL_HISTORY: &str = "sql_history";
@@ -34,6 +37,8 @@ pub struct QueryTracker {
     query_limit: usize,
     query_persister: QueryPersisterRef,
     coord: CoordinatorRef,
+    async_queries: Arc<AsyncQueryManager>,
+    audit_log: Arc<AuditLog>,
 }
 
 impl QueryTracker {
@@ -41,12 +46,16 @@ impl QueryTracker {
         query_limit: usize,
         query_persister: QueryPersisterRef,
         coord: CoordinatorRef,
+        async_queries: Arc<AsyncQueryManager>,
+        audit_log: Arc<AuditLog>,
     ) -> Self {
         Self {
             queries: RwLock::new(HashMap::new()),
             query_limit,
             query_persister,
             coord,
+            async_queries,
+            audit_log,
         }
     }
 }
@@ -56,12 +65,18 @@ impl QueryTracker {
     ///
     /// Returns [`TrackedQuery`], which holds a [`QueryExecutionTrackedProxy`] internally.
     ///
+    /// `user_query_limit` is the max number of batch queries the user of the query
+    /// can run in its tenant at the same time. The queries are only counted on this node,
+    /// so a user can run up to that many queries on every query node.
+    ///
     /// Errors:
     ///     [`QueryError::RequestLimit`]
+    ///     [`QueryError::ConcurrentQueryQuotaExceeded`]
     pub async fn try_track_query(
         self: &Arc<Self>,
         query_id: QueryId,
         query: Arc<dyn QueryExecution>,
+        user_query_limit: Option<usize>,
     ) -> QueryResult<TrackedQuery> {
         debug!(
             "total query count: {}, status {:?}",
@@ -76,7 +91,8 @@ impl QueryTracker {
         //     query.status(),
         // );
 
-        self.save_query(query_id, query.clone()).await?;
+        self.save_query(query_id, query.clone(), user_query_limit)
+            .await?;
 
         let query = match query.query_type() {
             // 封装一层代理，用于在释放query result stream时，从tracker中移除
@@ -107,6 +123,15 @@ impl QueryTracker {
         self.queries.read().values().cloned().collect()
     }
 
+    /// the queries submitted through the async query api, kept until their results expire
+    pub fn async_queries(&self) -> &Arc<AsyncQueryManager> {
+        &self.async_queries
+    }
+
+    pub fn audit_log(&self) -> &Arc<AuditLog> {
+        &self.audit_log
+    }
+
     /// all persistent queries
     pub async fn persistent_queries(&self, node_id: NodeId) -> QueryResult<Vec<QueryInfo>> {
         self.query_persister.queries(node_id).await
@@ -201,6 +226,7 @@ impl QueryTracker {
                             CLUSTER_SCHEMA,
                             Precision::NS,
                             vec![line],
+                            ConsistencyLevel::default(),
                             None,
                         )
                         .await
@@ -216,6 +242,7 @@ impl QueryTracker {
         &self,
         query_id: QueryId,
         query: Arc<dyn QueryExecution>,
+        user_query_limit: Option<usize>,
     ) -> QueryResult<()> {
         if self.queries.read().len() >= self.query_limit {
             warn!("simultaneous request limit exceeded - dropping request");
@@ -229,6 +256,25 @@ impl QueryTracker {
                 warn!("simultaneous request limit exceeded - dropping request");
                 return Err(QueryError::RequestLimit);
             }
+            if let Some(quota) = user_query_limit {
+                let info = query.info();
+                let running = wqueries
+                    .values()
+                    .filter(|e| matches!(e.query_type(), QueryType::Batch))
+                    .map(|e| e.info())
+                    .filter(|e| e.tenant_id() == info.tenant_id() && e.user_id() == info.user_id())
+                    .count();
+                if running >= quota {
+                    warn!(
+                        "concurrent query quota of user {} exceeded - dropping request",
+                        info.user_name()
+                    );
+                    return Err(QueryError::ConcurrentQueryQuotaExceeded {
+                        user: info.user_name().to_string(),
+                        quota,
+                    });
+                }
+            }
             let _ = wqueries.insert(query_id, query.clone());
         }
 
@@ -352,11 +398,15 @@ mod tests {
     use meta::model::meta_admin::AdminMeta;
     use models::auth::user::{User, UserDesc, UserOptions};
     use models::schema::query_info::{QueryId, QueryInfo};
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
     use spi::query::dispatcher::QueryStatus;
     use spi::query::execution::{Output, QueryExecution, QueryState, RUNNING};
     use spi::QueryError;
 
     use super::QueryTracker;
+    use crate::dispatcher::async_query::AsyncQueryManager;
+    use crate::dispatcher::audit_log::AuditLog;
     use crate::dispatcher::persister::MetaQueryPersister;
 
     struct QueryExecutionMock {}
@@ -398,6 +448,14 @@ mod tests {
             limit,
             Arc::new(MetaQueryPersister::new(Arc::new(AdminMeta::mock()))),
             Arc::new(MockCoordinator {}),
+            Arc::new(AsyncQueryManager::new(
+                1001,
+                Arc::new(InMemory::new()),
+                Path::default(),
+                Duration::from_secs(60),
+                1024,
+            )),
+            Arc::new(AuditLog::new(None)),
         )
     }
 
@@ -408,14 +466,14 @@ mod tests {
         let tracker = Arc::new(new_query_tracker(10));
 
         let _tq = tracker
-            .try_track_query(query_id, query.clone())
+            .try_track_query(query_id, query.clone(), None)
             .await
             .unwrap();
         assert_eq!(tracker._running_query_count(), 1);
 
         let query_id = QueryId::next_id();
         let _tq = tracker
-            .try_track_query(query_id, query.clone())
+            .try_track_query(query_id, query.clone(), None)
             .await
             .unwrap();
         assert_eq!(tracker._running_query_count(), 2);
@@ -424,7 +482,7 @@ mod tests {
             // 作用域结束时，不会结束对当前query的追踪
             let query_id = QueryId::next_id();
             let _tq = tracker
-                .try_track_query(query_id, query.clone())
+                .try_track_query(query_id, query.clone(), None)
                 .await
                 .unwrap();
             assert_eq!(tracker._running_query_count(), 3);
@@ -435,7 +493,10 @@ mod tests {
         assert_eq!(tracker._running_query_count(), 2);
 
         let query_id = QueryId::next_id();
-        let _tq = tracker.try_track_query(query_id, query).await.unwrap();
+        let _tq = tracker
+            .try_track_query(query_id, query, None)
+            .await
+            .unwrap();
         assert_eq!(tracker._running_query_count(), 3);
     }
 
@@ -447,7 +508,7 @@ mod tests {
         let output = {
             let query_id = QueryId::next_id();
             let tq = tracker
-                .try_track_query(query_id, query.clone())
+                .try_track_query(query_id, query.clone(), None)
                 .await
                 .unwrap();
             tq.start().await.unwrap()
@@ -465,20 +526,54 @@ mod tests {
         let tracker = Arc::new(new_query_tracker(2));
 
         let _tq = tracker
-            .try_track_query(query_id, query.clone())
+            .try_track_query(query_id, query.clone(), None)
             .await
             .unwrap();
         assert_eq!(tracker._running_query_count(), 1);
 
         let query_id = QueryId::next_id();
         let _tq = tracker
-            .try_track_query(query_id, query.clone())
+            .try_track_query(query_id, query.clone(), None)
             .await
             .unwrap();
         assert_eq!(tracker._running_query_count(), 2);
 
         let query_id = QueryId::next_id();
-        assert!(tracker.try_track_query(query_id, query).await.is_err())
+        assert!(tracker
+            .try_track_query(query_id, query, None)
+            .await
+            .is_err())
+    }
+
+    #[tokio::test]
+    async fn test_exceed_user_query_quota() {
+        let query = Arc::new(QueryExecutionMock {});
+        let tracker = Arc::new(new_query_tracker(10));
+
+        let query_id = QueryId::next_id();
+        let _tq = tracker
+            .try_track_query(query_id, query.clone(), Some(1))
+            .await
+            .unwrap();
+        assert_eq!(tracker._running_query_count(), 1);
+
+        let err = tracker
+            .try_track_query(QueryId::next_id(), query.clone(), Some(1))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            QueryError::ConcurrentQueryQuotaExceeded { quota: 1, .. }
+        ));
+
+        // the query of the user finished, a new one can be started
+        let _ = tracker.expire_query(&query_id);
+        let _tq = tracker
+            .try_track_query(QueryId::next_id(), query, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(tracker._running_query_count(), 1);
     }
 
     #[tokio::test]
@@ -488,7 +583,7 @@ mod tests {
         let tracker = Arc::new(new_query_tracker(2));
 
         let _tq = tracker
-            .try_track_query(query_id, query.clone())
+            .try_track_query(query_id, query.clone(), None)
             .await
             .unwrap();
 
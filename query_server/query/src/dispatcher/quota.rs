@@ -0,0 +1,101 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+use futures::{Stream, StreamExt};
+use spi::QueryError;
+
+/// Fails the query once it returned more rows than the quota of its user
+pub struct ResultRowsQuotaStream {
+    inner: SendableRecordBatchStream,
+    quota: u64,
+    returned_rows: u64,
+}
+
+impl ResultRowsQuotaStream {
+    pub fn new(inner: SendableRecordBatchStream, quota: u64) -> Self {
+        Self {
+            inner,
+            quota,
+            returned_rows: 0,
+        }
+    }
+}
+
+impl RecordBatchStream for ResultRowsQuotaStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl Stream for ResultRowsQuotaStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.returned_rows > self.quota {
+            return Poll::Ready(None);
+        }
+
+        match self.inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(batch))) => {
+                self.returned_rows += batch.num_rows() as u64;
+                if self.returned_rows > self.quota {
+                    let err = QueryError::ResultRowsQuotaExceeded { quota: self.quota };
+                    return Poll::Ready(Some(Err(DataFusionError::External(Box::new(err)))));
+                }
+                Poll::Ready(Some(Ok(batch)))
+            }
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::Int32Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+
+    use super::*;
+
+    fn stream_of(batches: Vec<usize>) -> SendableRecordBatchStream {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batches = batches
+            .into_iter()
+            .map(|rows| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(vec![0; rows]))],
+                )
+                .map_err(DataFusionError::from)
+            })
+            .collect::<Vec<_>>();
+
+        Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(batches),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_result_rows_quota() {
+        let mut stream = ResultRowsQuotaStream::new(stream_of(vec![2, 3]), 5);
+        assert_eq!(stream.next().await.unwrap().unwrap().num_rows(), 2);
+        assert_eq!(stream.next().await.unwrap().unwrap().num_rows(), 3);
+        assert!(stream.next().await.is_none());
+
+        let mut stream = ResultRowsQuotaStream::new(stream_of(vec![2, 3, 1]), 4);
+        assert_eq!(stream.next().await.unwrap().unwrap().num_rows(), 2);
+        let err = QueryError::from(stream.next().await.unwrap().unwrap_err());
+        assert!(matches!(
+            err,
+            QueryError::ResultRowsQuotaExceeded { quota: 4 }
+        ));
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::AlterRole;
+use spi::{MetaSnafu, QueryError, QueryResult};
+use trace::debug;
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct AlterRoleTask {
+    stmt: AlterRole,
+}
+
+impl AlterRoleTask {
+    pub fn new(stmt: AlterRole) -> AlterRoleTask {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for AlterRoleTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let AlterRole {
+            ref tenant_name,
+            ref role_name,
+            ref alter_role_action,
+        } = self.stmt;
+
+        let meta = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| QueryError::Meta {
+                source: MetaError::TenantNotFound {
+                    tenant: tenant_name.to_string(),
+                },
+            })?;
+
+        let role = meta
+            .custom_role(role_name)
+            .await
+            .context(MetaSnafu)?
+            .ok_or_else(|| QueryError::Meta {
+                source: MetaError::RoleNotFound {
+                    role: role_name.to_string(),
+                },
+            })?;
+
+        let mut quota = role.quota().clone();
+        alter_role_action.apply(&mut quota);
+
+        debug!(
+            "Set quota of role {} of tenant {} to {:?}",
+            role_name, tenant_name, quota
+        );
+
+        meta.set_custom_role_quota(quota, role_name)
+            .await
+            .context(MetaSnafu)?;
+
+        Ok(Output::Nil(()))
+    }
+}
@@ -0,0 +1,45 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreateFunction;
+use spi::{MetaSnafu, QueryResult};
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct CreateFunctionTask {
+    stmt: CreateFunction,
+}
+
+impl CreateFunctionTask {
+    pub fn new(stmt: CreateFunction) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateFunctionTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let CreateFunction {
+            ref or_replace,
+            ref function,
+        } = self.stmt;
+
+        let tenant = function.tenant();
+        let client = query_state_machine
+            .meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        client
+            .create_function(function, *or_replace)
+            .await
+            .context(MetaSnafu)?;
+
+        Ok(Output::Nil(()))
+    }
+}
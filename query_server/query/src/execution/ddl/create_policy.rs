@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use meta::error::MetaError;
+use models::auth::role::SystemTenantRole;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreatePolicy;
+use spi::{MetaSnafu, QueryResult};
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct CreatePolicyTask {
+    stmt: CreatePolicy,
+}
+
+impl CreatePolicyTask {
+    pub fn new(stmt: CreatePolicy) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreatePolicyTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let CreatePolicy {
+            ref tenant_name,
+            if_not_exists,
+            ref policy,
+        } = self.stmt;
+
+        let client = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant_name.to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        if client.policy(policy.name()).is_some() {
+            if if_not_exists {
+                return Ok(Output::Nil(()));
+            }
+            return Err(MetaError::PolicyAlreadyExists {
+                name: policy.name().to_string(),
+            })
+            .context(MetaSnafu);
+        }
+
+        // the policy applies to the members of a system role or an existing custom role
+        let role = policy.role();
+        if SystemTenantRole::try_from(role).is_err()
+            && client.custom_role(role).await.context(MetaSnafu)?.is_none()
+        {
+            return Err(MetaError::RoleNotFound {
+                role: role.to_string(),
+            })
+            .context(MetaSnafu);
+        }
+
+        client.create_policy(policy).await.context(MetaSnafu)?;
+
+        Ok(Output::Nil(()))
+    }
+}
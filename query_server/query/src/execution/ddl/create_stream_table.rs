@@ -70,7 +70,10 @@ impl DDLDefinitionTask for CreateStreamTableTask {
     }
 }
 
-async fn create_table(table: StreamTable, machine: QueryStateMachineRef) -> QueryResult<()> {
+pub(super) async fn create_table(
+    table: StreamTable,
+    machine: QueryStateMachineRef,
+) -> QueryResult<()> {
     machine
         .meta
         .tenant_meta(table.tenant())
@@ -84,7 +87,7 @@ async fn create_table(table: StreamTable, machine: QueryStateMachineRef) -> Quer
         .context(MetaSnafu)
 }
 
-fn build_table(stmt: &CreateStreamTable) -> StreamTable {
+pub(super) fn build_table(stmt: &CreateStreamTable) -> StreamTable {
     let CreateStreamTable {
         schema,
         name,
@@ -39,7 +39,10 @@ impl DDLDefinitionTask for CreateTableTask {
     }
 }
 
-async fn create_table(stmt: &CreateTable, machine: QueryStateMachineRef) -> QueryResult<()> {
+pub(super) async fn create_table(
+    stmt: &CreateTable,
+    machine: QueryStateMachineRef,
+) -> QueryResult<()> {
     let CreateTable { name, .. } = stmt;
 
     let client = machine
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::StringArray;
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use meta::error::MetaError;
+use models::auth::token::TokenDesc;
+use snafu::ResultExt;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::CreateToken;
+use spi::query::recordbatch::RecordBatchStreamWrapper;
+use spi::{MetaSnafu, QueryResult};
+
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct CreateTokenTask {
+    stmt: CreateToken,
+}
+
+impl CreateTokenTask {
+    pub fn new(stmt: CreateToken) -> Self {
+        Self { stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateTokenTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let CreateToken {
+            ref tenant_name,
+            ref name,
+            if_not_exists,
+            user_id,
+            ref user_name,
+            ref privileges,
+            expires_at,
+        } = self.stmt;
+
+        let client = query_state_machine
+            .meta
+            .tenant_meta(tenant_name)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant_name.to_string(),
+            })
+            .context(MetaSnafu)?;
+
+        if client.token(name).is_some() {
+            if if_not_exists {
+                return Ok(Output::Nil(()));
+            }
+            return Err(MetaError::TokenAlreadyExists { name: name.clone() }).context(MetaSnafu);
+        }
+
+        // The privileges of the token are not checked against the user here,
+        // a request authenticated by the token is limited by both of them.
+        let (token, plain) = TokenDesc::generate(
+            name.clone(),
+            user_id,
+            user_name.clone(),
+            privileges.clone(),
+            expires_at,
+        );
+        client.create_token(&token).await.context(MetaSnafu)?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("token_name", DataType::Utf8, false),
+            Field::new("token", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![name.as_str()])),
+                Arc::new(StringArray::from(vec![plain])),
+            ],
+        )?;
+
+        Ok(Output::StreamData(Box::pin(RecordBatchStreamWrapper::new(
+            schema,
+            vec![batch],
+        ))))
+    }
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use meta::error::MetaError;
+use models::schema::table_schema::TableSchema;
+use models::schema::view_schema::{MaterializedView, ViewSchema};
+use snafu::ResultExt;
+use spi::query::datasource::stream::checker::StreamTableCheckerRef;
+use spi::query::execution::{Output, QueryStateMachineRef};
+use spi::query::logical_planner::{CreateMaterializedView, CreateView};
+use spi::{MetaSnafu, QueryError, QueryResult};
+use trace::warn;
+
+use super::{create_stream_table, create_table, drop_database_object};
+use crate::execution::ddl::DDLDefinitionTask;
+
+pub struct CreateViewTask {
+    /// The checker of the source stream table of a materialized view
+    checker: Option<StreamTableCheckerRef>,
+    stmt: CreateView,
+}
+
+impl CreateViewTask {
+    pub fn new(checker: Option<StreamTableCheckerRef>, stmt: CreateView) -> Self {
+        Self { checker, stmt }
+    }
+}
+
+#[async_trait]
+impl DDLDefinitionTask for CreateViewTask {
+    async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
+        let CreateView {
+            ref name,
+            ref or_replace,
+            ref query,
+            ref schema,
+            ref materialized,
+        } = self.stmt;
+
+        let tenant = name.tenant();
+        let client = query_state_machine
+            .meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            })
+            .context(MetaSnafu)?;
+        let existing = client
+            .get_table_schema(name.database(), name.table())
+            .context(MetaSnafu)?;
+
+        let view = |materialized: Option<MaterializedView>| {
+            TableSchema::ViewTableSchema(Arc::new(ViewSchema::new(
+                name.tenant(),
+                name.database(),
+                name.table(),
+                query,
+                schema.clone(),
+                materialized,
+            )))
+        };
+
+        match (existing, materialized) {
+            // only a plain view is replaced
+            (Some(TableSchema::ViewTableSchema(existing)), None)
+                if *or_replace && existing.materialized().is_none() =>
+            {
+                client.update_table(&view(None)).await.context(MetaSnafu)?;
+            }
+            (Some(_), _) => Err(MetaError::TableAlreadyExists {
+                table_name: name.to_string(),
+            })
+            .context(MetaSnafu)?,
+            (None, None) => {
+                client.create_table(&view(None)).await.context(MetaSnafu)?;
+            }
+            (None, Some(materialized)) => {
+                // The view is created first, a view of the same name created
+                // meanwhile fails it before any table is created.
+                client
+                    .create_table(&view(Some(materialized_view(materialized))))
+                    .await
+                    .context(MetaSnafu)?;
+                if let Err(err) = self
+                    .create_materialized_tables(materialized, query_state_machine.clone())
+                    .await
+                {
+                    if let Err(drop_err) = client.drop_table(name.database(), name.table()).await {
+                        warn!(
+                            "Failed to roll back materialized view {}: {}",
+                            name, drop_err
+                        );
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(Output::Nil(()))
+    }
+}
+
+impl CreateViewTask {
+    /// Create the storage table and the source stream table of a materialized view,
+    /// the storage table is dropped if the source table can't be created.
+    async fn create_materialized_tables(
+        &self,
+        stmt: &CreateMaterializedView,
+        machine: QueryStateMachineRef,
+    ) -> QueryResult<()> {
+        let CreateMaterializedView {
+            storage_table,
+            source_table,
+            ..
+        } = stmt;
+
+        let source = create_stream_table::build_table(source_table);
+        let client = machine
+            .meta
+            .tenant_meta(source.tenant())
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: source.tenant().to_string(),
+            })
+            .context(MetaSnafu)?;
+        self.checker
+            .as_ref()
+            .ok_or_else(|| QueryError::UnsupportedStreamType {
+                stream_type: source.stream_type().to_string(),
+            })?
+            .check(&client, &source)?;
+
+        create_table::create_table(storage_table, machine.clone()).await?;
+        if let Err(err) = create_stream_table::create_table(source, machine.clone()).await {
+            let name = &storage_table.name;
+            if let Err(drop_err) = drop_database_object::drop_table(
+                &machine,
+                *client.tenant().id(),
+                name.tenant(),
+                name.database(),
+                name.table(),
+            )
+            .await
+            {
+                warn!("Failed to roll back storage table {}: {}", name, drop_err);
+            }
+            return Err(err);
+        }
+
+        Ok(())
+    }
+}
+
+fn materialized_view(stmt: &CreateMaterializedView) -> MaterializedView {
+    let CreateMaterializedView {
+        base_table,
+        storage_table,
+        source_table,
+        refresh_sql,
+        refresh_interval,
+    } = stmt;
+
+    MaterializedView::new(
+        base_table,
+        storage_table.name.table(),
+        source_table.name.table(),
+        refresh_sql,
+        *refresh_interval,
+        source_table.watermark.delay,
+    )
+}
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
 use coordinator::resource_manager::ResourceManager;
 use meta::error::MetaError;
-use models::oid::Identifier;
+use models::oid::{Identifier, Oid};
 use models::schema::resource_info::{ResourceInfo, ResourceOperator};
+use models::schema::table_schema::TableSchema;
 use snafu::ResultExt;
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::{DatabaseObjectType, DropDatabaseObject};
@@ -31,56 +32,95 @@ impl DDLDefinitionTask for DropDatabaseObjectTask {
             ref obj_type,
         } = self.stmt;
 
-        match obj_type {
-            DatabaseObjectType::Table => {
+        let tenant = object_name.tenant();
+        let client = query_state_machine
+            .meta
+            .tenant_meta(tenant)
+            .await
+            .ok_or_else(|| MetaError::TenantNotFound {
+                tenant: tenant.to_string(),
+            })
+            .context(MetaSnafu)?;
+        let table = client
+            .get_table_schema(object_name.database(), object_name.table())
+            .context(MetaSnafu)?;
+
+        match (obj_type, table) {
+            (_, None) => {
+                if *if_exist {
+                    return Ok(Output::Nil(()));
+                } else {
+                    return Err(QueryError::Meta {
+                        source: MetaError::TableNotFound {
+                            table: object_name.table().to_string(),
+                        },
+                    });
+                }
+            }
+            (DatabaseObjectType::Table, Some(TableSchema::ViewTableSchema(_))) => {
+                return Err(QueryError::Semantic {
+                    err: format!("{object_name} is a view, use DROP VIEW instead"),
+                });
+            }
+            (DatabaseObjectType::Table, Some(_)) => {
                 // TODO 删除指定租户下的表
                 info!("Drop table {}", object_name);
-                let tenant = object_name.tenant();
-                let client = query_state_machine
-                    .meta
-                    .tenant_meta(tenant)
-                    .await
-                    .ok_or_else(|| MetaError::TenantNotFound {
-                        tenant: tenant.to_string(),
-                    })
-                    .context(MetaSnafu)?;
-
-                if client
-                    .get_table_schema(object_name.database(), object_name.table())
-                    .is_ok_and(|opt| opt.is_none())
-                {
-                    if *if_exist {
-                        return Ok(Output::Nil(()));
-                    } else {
-                        return Err(QueryError::Meta {
-                            source: MetaError::TableNotFound {
-                                table: object_name.table().to_string(),
-                            },
-                        });
+                drop_table(
+                    &query_state_machine,
+                    *client.tenant().id(),
+                    object_name.tenant(),
+                    object_name.database(),
+                    object_name.table(),
+                )
+                .await?;
+            }
+            (DatabaseObjectType::View, Some(TableSchema::ViewTableSchema(view))) => {
+                info!("Drop view {}", object_name);
+                if let Some(materialized) = view.materialized() {
+                    for table in [materialized.source_table(), materialized.storage_table()] {
+                        drop_table(
+                            &query_state_machine,
+                            *client.tenant().id(),
+                            view.tenant(),
+                            view.db(),
+                            table,
+                        )
+                        .await?;
                     }
                 }
-
-                let resourceinfo = ResourceInfo::new(
-                    (*client.tenant().id(), object_name.database().to_string()),
-                    object_name.tenant().to_string()
-                        + "-"
-                        + object_name.database()
-                        + "-"
-                        + object_name.table(),
-                    ResourceOperator::DropTable(
-                        object_name.tenant().to_string(),
-                        object_name.database().to_string(),
-                        object_name.table().to_string(),
-                    ),
-                    &None,
-                    query_state_machine.coord.node_id(),
-                );
-                ResourceManager::add_resource_task(query_state_machine.coord.clone(), resourceinfo)
+                client
+                    .drop_table(view.db(), view.name())
                     .await
-                    .context(CoordinatorSnafu)?;
+                    .context(MetaSnafu)?;
+            }
+            (DatabaseObjectType::View, Some(_)) => {
+                return Err(QueryError::Semantic {
+                    err: format!("{object_name} is not a view"),
+                });
             }
         };
 
         Ok(Output::Nil(()))
     }
 }
+
+/// Drop the data of the table on the vnodes, and then the table itself.
+pub(super) async fn drop_table(
+    query_state_machine: &QueryStateMachineRef,
+    tenant_id: Oid,
+    tenant: &str,
+    database: &str,
+    table: &str,
+) -> QueryResult<()> {
+    let resourceinfo = ResourceInfo::new(
+        (tenant_id, database.to_string()),
+        tenant.to_string() + "-" + database + "-" + table,
+        ResourceOperator::DropTable(tenant.to_string(), database.to_string(), table.to_string()),
+        &None,
+        query_state_machine.coord.node_id(),
+    );
+    ResourceManager::add_resource_task(query_state_machine.coord.clone(), resourceinfo)
+        .await
+        .context(CoordinatorSnafu)?;
+    Ok(())
+}
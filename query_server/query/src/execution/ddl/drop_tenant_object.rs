@@ -104,6 +104,64 @@ impl DDLDefinitionTask for DropTenantObjectTask {
 
                 Ok(Output::Nil(()))
             }
+
+            TenantObjectType::Function => {
+                debug!("Drop function {} of tenant {}", name, tenant_name);
+                if meta.function(name).is_none() {
+                    if *if_exist {
+                        return Ok(Output::Nil(()));
+                    } else {
+                        return Err(QueryError::Meta {
+                            source: MetaError::FunctionNotFound {
+                                name: name.to_string(),
+                            },
+                        });
+                    }
+                }
+
+                meta.drop_function(name).await.context(MetaSnafu)?;
+
+                Ok(Output::Nil(()))
+            }
+
+            TenantObjectType::Token => {
+                debug!("Drop token {} of tenant {}", name, tenant_name);
+                if meta.token(name).is_none() {
+                    if *if_exist {
+                        return Ok(Output::Nil(()));
+                    } else {
+                        return Err(QueryError::Meta {
+                            source: MetaError::TokenNotFound {
+                                name: name.to_string(),
+                            },
+                        });
+                    }
+                }
+
+                // the tokens are checked on every request, dropping one revokes it immediately
+                meta.drop_token(name).await.context(MetaSnafu)?;
+
+                Ok(Output::Nil(()))
+            }
+
+            TenantObjectType::Policy => {
+                debug!("Drop policy {} of tenant {}", name, tenant_name);
+                if meta.policy(name).is_none() {
+                    if *if_exist {
+                        return Ok(Output::Nil(()));
+                    } else {
+                        return Err(QueryError::Meta {
+                            source: MetaError::PolicyNotFound {
+                                name: name.to_string(),
+                            },
+                        });
+                    }
+                }
+
+                meta.drop_policy(name).await.context(MetaSnafu)?;
+
+                Ok(Output::Nil(()))
+            }
         }
     }
 }
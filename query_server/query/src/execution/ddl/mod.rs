@@ -6,14 +6,19 @@ use spi::query::execution::{Output, QueryExecution, QueryStateMachineRef};
 use spi::query::logical_planner::DDLPlan;
 use spi::QueryResult;
 
+use self::alter_role::AlterRoleTask;
 use self::alter_tenant::AlterTenantTask;
 use self::alter_user::AlterUserTask;
 use self::create_external_table::CreateExternalTableTask;
+use self::create_function::CreateFunctionTask;
+use self::create_policy::CreatePolicyTask;
 use self::create_role::CreateRoleTask;
 use self::create_stream_table::CreateStreamTableTask;
 use self::create_table::CreateTableTask;
 use self::create_tenant::CreateTenantTask;
+use self::create_token::CreateTokenTask;
 use self::create_user::CreateUserTask;
+use self::create_view::CreateViewTask;
 use self::drop_database_object::DropDatabaseObjectTask;
 use self::drop_global_object::DropGlobalObjectTask;
 use self::drop_tenant_object::DropTenantObjectTask;
@@ -35,6 +40,7 @@ use crate::execution::ddl::drop_vnode::DropVnodeTask;
 use crate::execution::ddl::move_node::MoveVnodeTask;
 
 mod alter_database;
+mod alter_role;
 mod alter_table;
 mod alter_tenant;
 mod alter_user;
@@ -43,11 +49,15 @@ mod compact_vnode;
 mod copy_vnode;
 mod create_database;
 mod create_external_table;
+mod create_function;
+mod create_policy;
 mod create_role;
 mod create_stream_table;
 mod create_table;
 mod create_tenant;
+mod create_token;
 mod create_user;
+mod create_view;
 mod drop_database_object;
 mod drop_global_object;
 mod drop_tenant_object;
@@ -173,6 +183,7 @@ impl DDLDefinitionTaskFactory {
             DDLPlan::AlterTable(sub_plan) => Box::new(AlterTableTask::new(sub_plan.clone())),
             DDLPlan::AlterTenant(sub_plan) => Box::new(AlterTenantTask::new(sub_plan.clone())),
             DDLPlan::AlterUser(sub_plan) => Box::new(AlterUserTask::new(sub_plan.clone())),
+            DDLPlan::AlterRole(sub_plan) => Box::new(AlterRoleTask::new(sub_plan.clone())),
             DDLPlan::GrantRevoke(sub_plan) => Box::new(GrantRevokeTask::new(sub_plan.clone())),
             DDLPlan::DropVnode(sub_plan) => Box::new(DropVnodeTask::new(sub_plan.clone())),
             DDLPlan::CopyVnode(sub_plan) => Box::new(CopyVnodeTask::new(sub_plan.clone())),
@@ -186,6 +197,19 @@ impl DDLDefinitionTaskFactory {
 
                 Box::new(CreateStreamTableTask::new(checker, sub_plan.clone()))
             }
+            DDLPlan::CreateView(sub_plan) => {
+                let checker = sub_plan.materialized.as_ref().and_then(|e| {
+                    self.stream_checker_manager
+                        .checker(&e.source_table.stream_type)
+                });
+
+                Box::new(CreateViewTask::new(checker, sub_plan.clone()))
+            }
+            DDLPlan::CreateFunction(sub_plan) => {
+                Box::new(CreateFunctionTask::new(sub_plan.clone()))
+            }
+            DDLPlan::CreateToken(sub_plan) => Box::new(CreateTokenTask::new(sub_plan.clone())),
+            DDLPlan::CreatePolicy(sub_plan) => Box::new(CreatePolicyTask::new(sub_plan.clone())),
             DDLPlan::RecoverDatabase(sub_plan) => {
                 Box::new(RecoverDatabaseTask::new(sub_plan.clone()))
             }
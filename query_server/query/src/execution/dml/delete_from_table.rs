@@ -1,96 +1,413 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use meta::error::{DatabaseNotFoundSnafu, TenantNotFoundSnafu};
-use models::predicate::domain::{ColumnDomains, ResolvedPredicate, TimeRanges};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Int64Type};
+use datafusion::common::cast::{as_primitive_array, as_string_array};
+use datafusion::common::{Column, ToDFSchema};
+use datafusion::datasource::TableProvider;
+use datafusion::logical_expr::{lit, Expr};
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_expr::{create_physical_expr, PhysicalExpr};
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::{execute_stream, ExecutionPlan};
+use datafusion::scalar::ScalarValue;
+use futures::TryStreamExt;
+use meta::error::{DatabaseNotFoundSnafu, TableNotFoundSnafu, TenantNotFoundSnafu};
+use meta::model::MetaClientRef;
+use models::object_reference::ResolvedTable;
+use models::predicate::domain::{ColumnDomains, Domain, ResolvedPredicate, TimeRange, TimeRanges};
 use models::predicate::transformation::DeleteSelectionExpressionToDomainsVisitor;
 use models::predicate::utils::filter_to_time_ranges;
+use models::schema::tskv_table_schema::TskvTableSchemaRef;
 use snafu::ResultExt;
 use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::DeleteFromTable;
-use spi::{CoordinatorSnafu, InvalidParamSnafu, MetaSnafu, ModelsSnafu, QueryResult};
+use spi::{CoordinatorSnafu, InvalidParamSnafu, MetaSnafu, ModelsSnafu, QueryError, QueryResult};
 use utils::precision::{timestamp_convert, Precision};
 
 use super::DMLDefinitionTask;
+use crate::data_source::batch::tskv::ClusterTable;
+use crate::data_source::split::SplitManager;
 
 pub struct DeleteFromTableTask {
-    stmt: DeleteFromTable,
+    stmts: Vec<DeleteFromTable>,
 }
 
 impl DeleteFromTableTask {
-    pub fn new(stmt: DeleteFromTable) -> Self {
-        Self { stmt }
+    pub fn new(stmts: Vec<DeleteFromTable>) -> Self {
+        Self { stmts }
     }
 }
 
+/// The rows matched by field predicates that are buffered before they are deleted
+const DELETE_ROWS_BATCH_SIZE: usize = 64 * 1024;
+
 #[async_trait]
 impl DMLDefinitionTask for DeleteFromTableTask {
+    /// The tables are deleted one by one and the deletes are not atomic,
+    /// a failure reports the tables that have already been deleted.
     async fn execute(&self, query_state_machine: QueryStateMachineRef) -> QueryResult<Output> {
-        let DeleteFromTable {
-            table_name,
-            selection,
-        } = &self.stmt;
-
-        let db_schema = query_state_machine
-            .meta
-            .tenant_meta(table_name.tenant())
-            .await
-            .ok_or_else(|| {
-                TenantNotFoundSnafu {
-                    tenant: table_name.tenant().to_string(),
+        let mut deleted = Vec::with_capacity(self.stmts.len());
+        for stmt in &self.stmts {
+            if let Err(err) = delete_from_table(&query_state_machine, stmt).await {
+                if deleted.is_empty() {
+                    return Err(err);
                 }
-                .build()
-            })
-            .context(MetaSnafu)?
-            .get_db_schema(table_name.database())
+                return Err(QueryError::DeletePartiallyFailed {
+                    table: stmt.table_name.to_string(),
+                    deleted: deleted.join(", "),
+                    reason: err.to_string(),
+                });
+            }
+            deleted.push(stmt.table_name.to_string());
+        }
+
+        Ok(Output::Nil(()))
+    }
+}
+
+async fn delete_from_table(
+    query_state_machine: &QueryStateMachineRef,
+    stmt: &DeleteFromTable,
+) -> QueryResult<()> {
+    let DeleteFromTable {
+        table_name,
+        selection,
+    } = stmt;
+
+    let tenant_meta = query_state_machine
+        .meta
+        .tenant_meta(table_name.tenant())
+        .await
+        .ok_or_else(|| {
+            TenantNotFoundSnafu {
+                tenant: table_name.tenant().to_string(),
+            }
+            .build()
+        })
+        .context(MetaSnafu)?;
+    let db_schema = tenant_meta
+        .get_db_schema(table_name.database())
+        .context(MetaSnafu)?
+        .ok_or_else(|| {
+            DatabaseNotFoundSnafu {
+                database: table_name.to_string(),
+            }
+            .build()
+        })
+        .context(MetaSnafu)?;
+    let precision = *db_schema.config().precision();
+
+    if let Some(expr) = selection {
+        let table_schema = tenant_meta
+            .get_tskv_table_schema(table_name.database(), table_name.table())
             .context(MetaSnafu)?
             .ok_or_else(|| {
-                DatabaseNotFoundSnafu {
-                    database: table_name.to_string(),
+                TableNotFoundSnafu {
+                    table: table_name.to_string(),
                 }
                 .build()
             })
             .context(MetaSnafu)?;
-        let precision = *db_schema.config().precision();
-
-        let (tags_filter, time_ranges) = if let Some(expr) = selection {
-            let (tag, time) =
-                DeleteSelectionExpressionToDomainsVisitor::expr_to_tag_and_time_domains(expr)?;
-            let mut time_ranges = filter_to_time_ranges(&time);
-            for range in time_ranges.iter_mut() {
-                let min =
-                    timestamp_convert(Precision::NS, precision, range.min_ts).ok_or_else(|| {
-                        InvalidParamSnafu {
-                            reason: "Invalid timestamp".to_string(),
-                        }
-                        .build()
-                    })?;
-                let max =
-                    timestamp_convert(Precision::NS, precision, range.max_ts).ok_or_else(|| {
-                        InvalidParamSnafu {
-                            reason: "Invalid timestamp".to_string(),
-                        }
-                        .build()
-                    })?;
-                range.max_ts = max;
-                range.min_ts = min;
+        let filter_on_field = expr.to_columns()?.iter().any(|column| {
+            table_schema
+                .column(&column.name)
+                .map(|c| c.column_type.is_field())
+                .unwrap_or(false)
+        });
+        // Field predicates can only be evaluated on the rows, the matched rows are deleted
+        // by the tombstones of their series and timestamps.
+        if filter_on_field {
+            return delete_rows(
+                query_state_machine,
+                tenant_meta,
+                table_name,
+                table_schema,
+                expr,
+            )
+            .await;
+        }
+    }
+
+    let (tags_filter, time_ranges) = if let Some(expr) = selection {
+        let (tag, time) =
+            DeleteSelectionExpressionToDomainsVisitor::expr_to_tag_and_time_domains(expr)?;
+        let mut time_ranges = filter_to_time_ranges(&time);
+        for range in time_ranges.iter_mut() {
+            let min =
+                timestamp_convert(Precision::NS, precision, range.min_ts).ok_or_else(|| {
+                    InvalidParamSnafu {
+                        reason: "Invalid timestamp".to_string(),
+                    }
+                    .build()
+                })?;
+            let max =
+                timestamp_convert(Precision::NS, precision, range.max_ts).ok_or_else(|| {
+                    InvalidParamSnafu {
+                        reason: "Invalid timestamp".to_string(),
+                    }
+                    .build()
+                })?;
+            range.max_ts = max;
+            range.min_ts = min;
+        }
+        (tag, TimeRanges::new(time_ranges))
+    } else {
+        (ColumnDomains::all(), TimeRanges::all())
+    };
+
+    let predicate =
+        ResolvedPredicate::new(Arc::new(time_ranges), tags_filter, None).context(ModelsSnafu)?;
+
+    trace::info!("Delete from table: {table_name}, filter: {predicate:?}");
+
+    query_state_machine
+        .coord
+        .delete_from_table(table_name, &predicate)
+        .await
+        .context(CoordinatorSnafu)?;
+
+    Ok(())
+}
+
+/// Scans the rows matching the selection and deletes them every [`DELETE_ROWS_BATCH_SIZE`] rows,
+/// each series is identified by all of its tags and the rows by their timestamps.
+async fn delete_rows(
+    query_state_machine: &QueryStateMachineRef,
+    meta: MetaClientRef,
+    table_name: &ResolvedTable,
+    schema: TskvTableSchemaRef,
+    selection: &Expr,
+) -> QueryResult<()> {
+    let coord = query_state_machine.coord.clone();
+    let state = query_state_machine.session.inner();
+    let table = ClusterTable::new(
+        coord.clone(),
+        Arc::new(SplitManager::new(coord.clone())),
+        meta,
+        schema.clone(),
+    );
+
+    // time, tags and the columns referenced by the selection
+    let referenced = selection.to_columns()?;
+    let projection = schema
+        .columns()
+        .iter()
+        .enumerate()
+        .filter(|(_, column)| {
+            column.column_type.is_time()
+                || column.column_type.is_tag()
+                || referenced.iter().any(|c| c.name == column.name)
+        })
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    let scan = table
+        .scan(state, Some(&projection), &[selection.clone()], None, None)
+        .await?;
+    let scan_schema = scan.schema();
+    let predicate = create_physical_expr(
+        selection,
+        &scan_schema.clone().to_dfschema()?,
+        &scan_schema,
+        state.execution_props(),
+    )?;
+    let plan: Arc<dyn ExecutionPlan> = Arc::new(FilterExec::try_new(predicate, scan)?);
+
+    let time_name = schema.time_column().name;
+    let tag_names = schema
+        .columns()
+        .iter()
+        .filter(|column| column.column_type.is_tag())
+        .map(|column| column.name.clone())
+        .collect::<Vec<_>>();
+
+    let mut series_times: HashMap<Vec<Option<String>>, Vec<i64>> = HashMap::new();
+    let mut buffered_rows = 0;
+    let mut stream = execute_stream(plan, state.task_ctx())?;
+    while let Some(batch) = stream.try_next().await? {
+        let batch_schema = batch.schema();
+        let time = cast(
+            batch.column(batch_schema.index_of(&time_name)?),
+            &DataType::Int64,
+        )?;
+        let time = as_primitive_array::<Int64Type>(&time)?;
+        let tags = tag_names
+            .iter()
+            .map(|name| Ok(as_string_array(batch.column(batch_schema.index_of(name)?))?))
+            .collect::<QueryResult<Vec<_>>>()?;
+
+        for row in 0..batch.num_rows() {
+            let key = tags
+                .iter()
+                .map(|array| array.is_valid(row).then(|| array.value(row).to_string()))
+                .collect::<Vec<_>>();
+            series_times.entry(key).or_default().push(time.value(row));
+        }
+
+        buffered_rows += batch.num_rows();
+        if buffered_rows >= DELETE_ROWS_BATCH_SIZE {
+            delete_series_times(
+                query_state_machine,
+                table_name,
+                &schema,
+                &tag_names,
+                std::mem::take(&mut series_times),
+            )
+            .await?;
+            buffered_rows = 0;
+        }
+    }
+
+    delete_series_times(
+        query_state_machine,
+        table_name,
+        &schema,
+        &tag_names,
+        series_times,
+    )
+    .await
+}
+
+/// Deletes the rows of the series, the series whose rows have the same time ranges
+/// are deleted together.
+async fn delete_series_times(
+    query_state_machine: &QueryStateMachineRef,
+    table_name: &ResolvedTable,
+    schema: &TskvTableSchemaRef,
+    tag_names: &[String],
+    series_times: HashMap<Vec<Option<String>>, Vec<i64>>,
+) -> QueryResult<()> {
+    if series_times.is_empty() {
+        return Ok(());
+    }
+
+    let mut ranges_series: HashMap<Vec<(i64, i64)>, Vec<Vec<Option<String>>>> = HashMap::new();
+    for (tags, times) in series_times {
+        ranges_series
+            .entry(merge_timestamps(times))
+            .or_default()
+            .push(tags);
+    }
+
+    trace::info!(
+        "Delete rows from table: {table_name}, deletes: {}",
+        ranges_series.len()
+    );
+
+    let tag_schema = schema.to_tag_arrow_schema();
+    let tag_df_schema = tag_schema.clone().to_dfschema()?;
+    let props = ExecutionProps::new();
+    for (ranges, series) in ranges_series {
+        let time_ranges = TimeRanges::new(
+            ranges
+                .into_iter()
+                .map(|(min_ts, max_ts)| TimeRange::new(min_ts, max_ts))
+                .collect(),
+        );
+
+        // The domains find the series by their non-null tags, the filter excludes
+        // the series with other tags than the deleted ones.
+        let mut tags_filter: Option<ColumnDomains<String>> = None;
+        let mut filter: Option<Expr> = None;
+        for tags in series {
+            let (series_domains, series_filter) = series_filter(tag_names, tags);
+            match tags_filter.as_mut() {
+                Some(tags_filter) => tags_filter.column_wise_union(&series_domains),
+                None => tags_filter = Some(series_domains),
             }
-            (tag, TimeRanges::new(time_ranges))
-        } else {
-            (ColumnDomains::all(), TimeRanges::all())
+            filter = match (filter, series_filter) {
+                (Some(filter), Some(series_filter)) => Some(filter.or(series_filter)),
+                (filter, series_filter) => filter.or(series_filter),
+            };
+        }
+        let physical_expr: Option<Arc<dyn PhysicalExpr>> = match filter {
+            Some(filter) => Some(create_physical_expr(
+                &filter,
+                &tag_df_schema,
+                &tag_schema,
+                &props,
+            )?),
+            None => None,
         };
 
-        let predicate = ResolvedPredicate::new(Arc::new(time_ranges), tags_filter, None)
-            .context(ModelsSnafu)?;
-
-        trace::info!("Delete from table: {table_name}, filter: {predicate:?}");
+        let predicate = ResolvedPredicate::new(
+            Arc::new(time_ranges),
+            tags_filter.unwrap_or_else(ColumnDomains::all),
+            physical_expr,
+        )
+        .context(ModelsSnafu)?;
 
         query_state_machine
             .coord
             .delete_from_table(table_name, &predicate)
             .await
             .context(CoordinatorSnafu)?;
+    }
 
-        Ok(Output::Nil(()))
+    Ok(())
+}
+
+/// Returns the domains and the filter of the tags of a series.
+fn series_filter(
+    tag_names: &[String],
+    tags: Vec<Option<String>>,
+) -> (ColumnDomains<String>, Option<Expr>) {
+    let mut domains = ColumnDomains::all();
+    let mut filter: Option<Expr> = None;
+    for (name, value) in tag_names.iter().zip(tags) {
+        let column = Expr::Column(Column::from_name(name));
+        let expr = match value {
+            Some(value) => {
+                domains.insert_or_intersect(
+                    name.clone(),
+                    &Domain::of_values(
+                        &DataType::Utf8,
+                        true,
+                        &[&ScalarValue::Utf8(Some(value.clone()))],
+                    ),
+                );
+                column.eq(lit(value))
+            }
+            None => column.is_null(),
+        };
+        filter = Some(match filter {
+            Some(filter) => filter.and(expr),
+            None => expr,
+        });
+    }
+
+    (domains, filter)
+}
+
+/// Sorts the timestamps and merges the consecutive ones into ranges.
+fn merge_timestamps(mut times: Vec<i64>) -> Vec<(i64, i64)> {
+    times.sort_unstable();
+    let mut ranges: Vec<(i64, i64)> = Vec::new();
+    for ts in times {
+        match ranges.last_mut() {
+            Some((_, max_ts)) if ts <= max_ts.saturating_add(1) => *max_ts = ts,
+            _ => ranges.push((ts, ts)),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod test {
+    use super::merge_timestamps;
+
+    #[test]
+    fn test_merge_timestamps() {
+        assert!(merge_timestamps(vec![]).is_empty());
+        assert_eq!(
+            merge_timestamps(vec![5, 1, 2, 2, 3, 9, 10, 7]),
+            vec![(1, 3), (5, 5), (7, 7), (9, 10)]
+        );
+        assert_eq!(
+            merge_timestamps(vec![i64::MAX, i64::MAX - 1]),
+            vec![(i64::MAX - 1, i64::MAX)]
+        );
     }
 }
@@ -89,8 +89,8 @@ impl DMLDefinitionTaskFactory {
     // If you add DML operations, you usually need to modify here
     fn create_task(&self) -> Box<dyn DMLDefinitionTask> {
         match &self.plan {
-            DMLPlan::DeleteFromTable(sub_plan) => {
-                Box::new(DeleteFromTableTask::new(sub_plan.clone()))
+            DMLPlan::DeleteFromTable(sub_plans) => {
+                Box::new(DeleteFromTableTask::new(sub_plans.clone()))
             }
         }
     }
@@ -13,6 +13,7 @@ use tskv::kv_option::QueryOptions;
 
 use super::dml::DMLExecution;
 use super::query::SqlQueryExecution;
+use super::result_cache::{ResultCache, ResultCacheRef};
 use super::stream::trigger::executor::{TriggerExecutorFactory, TriggerExecutorFactoryRef};
 use super::stream::{MicroBatchStreamExecutionBuilder, MicroBatchStreamExecutionDesc};
 use super::sys::SystemExecution;
@@ -29,6 +30,7 @@ pub struct SqlQueryExecutionFactory {
     trigger_executor_factory: TriggerExecutorFactoryRef,
     runtime: Arc<DedicatedExecutor>,
     stream_checker_manager: StreamCheckerManagerRef,
+    result_cache: Option<ResultCacheRef>,
 }
 
 impl SqlQueryExecutionFactory {
@@ -53,6 +55,16 @@ impl SqlQueryExecutionFactory {
             config.stream_executor_cpu,
         ));
 
+        let result_cache = config.result_cache_enabled.then(|| {
+            Arc::new(
+                ResultCache::new(
+                    config.result_cache_capacity,
+                    config.result_cache_max_result_size as usize,
+                )
+                .with_version_ttl(config.result_cache_version_ttl),
+            )
+        });
+
         Self {
             optimizer,
             scheduler,
@@ -60,6 +72,7 @@ impl SqlQueryExecutionFactory {
             trigger_executor_factory,
             runtime,
             stream_checker_manager,
+            result_cache,
         }
     }
 }
@@ -89,6 +102,7 @@ impl QueryExecutionFactory for SqlQueryExecutionFactory {
                         query_plan,
                         self.optimizer.clone(),
                         self.scheduler.clone(),
+                        self.result_cache.clone(),
                     ))),
                     (true, false, true) => {
                         // 流操作
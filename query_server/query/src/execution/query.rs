@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use datafusion::physical_plan::ExecutionPlan;
 use futures::stream::AbortHandle;
 use models::schema::query_info::QueryInfo;
 use parking_lot::Mutex;
@@ -12,11 +13,14 @@ use spi::query::scheduler::SchedulerRef;
 use spi::{QueryError, QueryResult};
 use trace::debug;
 
+use super::result_cache::{collect_vnodes, is_cacheable, ResultCacheKey, ResultCacheRef};
+
 pub struct SqlQueryExecution {
     query_state_machine: QueryStateMachineRef,
     plan: QueryPlan,
     optimizer: Arc<dyn Optimizer + Send + Sync>,
     scheduler: SchedulerRef,
+    result_cache: Option<ResultCacheRef>,
 
     abort_handle: Mutex<Option<AbortHandle>>,
 }
@@ -27,12 +31,14 @@ impl SqlQueryExecution {
         plan: QueryPlan,
         optimizer: Arc<dyn Optimizer + Send + Sync>,
         scheduler: SchedulerRef,
+        result_cache: Option<ResultCacheRef>,
     ) -> Self {
         Self {
             query_state_machine,
             plan,
             optimizer,
             scheduler,
+            result_cache,
             abort_handle: Mutex::new(None),
         }
     }
@@ -46,9 +52,22 @@ impl SqlQueryExecution {
             .await?;
         self.query_state_machine.end_optimize();
 
+        let cache_key = self.result_cache_key(&physical_plan).await;
+        if let (Some(cache), Some(key)) = (&self.result_cache, &cache_key) {
+            if let Some(stream) = cache.get(key) {
+                debug!(
+                    "Hit result cache, query_id: {:?}",
+                    self.query_state_machine.query_id
+                );
+                self.query_state_machine.begin_schedule();
+                self.query_state_machine.end_schedule();
+                return Ok(Output::StreamData(stream));
+            }
+        }
+
         // begin schedule
         self.query_state_machine.begin_schedule();
-        let stream = self
+        let mut stream = self
             .scheduler
             .schedule(
                 physical_plan.clone(),
@@ -57,11 +76,63 @@ impl SqlQueryExecution {
             .await?
             .stream();
 
+        if let (Some(cache), Some(key)) = (&self.result_cache, cache_key) {
+            stream = cache.cache_stream(key, stream);
+        }
+
         debug!("Success build result stream.");
         self.query_state_machine.end_schedule();
 
         Ok(Output::StreamData(stream))
     }
+
+    /// Returns None if the result of this query should not be cached.
+    async fn result_cache_key(
+        &self,
+        physical_plan: &Arc<dyn ExecutionPlan>,
+    ) -> Option<ResultCacheKey> {
+        if self.result_cache.is_none() || !is_cacheable(&self.plan) {
+            return None;
+        }
+
+        let vnodes = collect_vnodes(physical_plan);
+        if vnodes.is_empty() {
+            return None;
+        }
+
+        let session = &self.query_state_machine.session;
+        let result_cache = self.result_cache.as_ref()?;
+        let (mut vnode_versions, missing) = result_cache.vnode_versions(vnodes);
+        if !missing.is_empty() {
+            let versions = match self
+                .query_state_machine
+                .coord
+                .vnode_data_versions(session.tenant(), &missing)
+                .await
+            {
+                Ok(versions) => versions,
+                Err(err) => {
+                    debug!("Failed to fetch vnode data versions: {}", err);
+                    return None;
+                }
+            };
+            result_cache.update_vnode_versions(&versions);
+
+            for vnode in missing {
+                // A vnode without a version can not be used to validate the cached result
+                let version = versions.get(&vnode.id).copied().flatten()?;
+                vnode_versions.insert(vnode.id, version);
+            }
+        }
+
+        Some(ResultCacheKey::new(
+            session.tenant(),
+            session.user().desc().name(),
+            session.default_database(),
+            &self.plan,
+            &vnode_versions,
+        ))
+    }
 }
 
 #[async_trait]
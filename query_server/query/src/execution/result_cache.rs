@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use cache::{ShardedSyncCache, SyncCache};
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::tree_node::{TreeNode, TreeNodeVisitor, VisitRecursion};
+use datafusion::error::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::{Expr, Extension, LogicalPlan, Subquery, TableScan, Volatility};
+use datafusion::physical_plan::memory::MemoryStream;
+use datafusion::physical_plan::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+use futures::{Stream, StreamExt};
+use models::meta_data::{VnodeId, VnodeInfo};
+use parking_lot::Mutex;
+use spi::query::logical_planner::QueryPlan;
+use trace::debug;
+
+use crate::data_source::source_downcast_adapter;
+use crate::data_source::table_source::TableHandle;
+use crate::extension::logical::plan_node::asof_join::AsofJoinNode;
+use crate::extension::logical::plan_node::expand::ExpandNode;
+use crate::extension::logical::plan_node::tag_scan::TagScanPlanNode;
+use crate::extension::logical::plan_node::ts_gen_func::TSGenFuncNode;
+use crate::extension::physical::plan_node::aggregate_filter_scan::AggregateFilterTskvExec;
+use crate::extension::physical::plan_node::tag_scan::TagScanExec;
+use crate::extension::physical::plan_node::tskv_exec::TskvExec;
+use crate::extension::utils::downcast_plan_node;
+
+/// Identifies a query result, the result becomes unreachable once any vnode it
+/// was read from changes its data version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResultCacheKey {
+    tenant: String,
+    user: String,
+    database: String,
+    plan: String,
+    vnode_versions: Vec<(VnodeId, u64)>,
+}
+
+impl ResultCacheKey {
+    pub fn new(
+        tenant: &str,
+        user: &str,
+        database: &str,
+        plan: &QueryPlan,
+        vnode_versions: &HashMap<VnodeId, u64>,
+    ) -> Self {
+        let mut vnode_versions = vnode_versions
+            .iter()
+            .map(|(id, version)| (*id, *version))
+            .collect::<Vec<_>>();
+        vnode_versions.sort_unstable();
+
+        Self {
+            tenant: tenant.to_string(),
+            user: user.to_string(),
+            database: database.to_string(),
+            plan: plan.df_plan.display_indent_schema().to_string(),
+            vnode_versions,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedResult {
+    schema: SchemaRef,
+    batches: Arc<Vec<RecordBatch>>,
+}
+
+/// Caches the results of queries over tskv tables on the query node.
+///
+/// The data versions of vnodes are kept for `version_ttl`, so that the queries in this period
+/// look up the cache without asking the data nodes for the versions.
+#[derive(Debug)]
+pub struct ResultCache {
+    cache: ShardedSyncCache<ResultCacheKey, CachedResult>,
+    max_result_size: usize,
+    version_ttl: Duration,
+    vnode_versions: Mutex<HashMap<VnodeId, (u64, Instant)>>,
+}
+
+pub type ResultCacheRef = Arc<ResultCache>;
+
+impl ResultCache {
+    pub fn new(capacity: usize, max_result_size: usize) -> Self {
+        Self {
+            cache: ShardedSyncCache::create_lru_sharded_cache(capacity),
+            max_result_size,
+            version_ttl: Duration::ZERO,
+            vnode_versions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_version_ttl(mut self, version_ttl: Duration) -> Self {
+        self.version_ttl = version_ttl;
+        self
+    }
+
+    /// Returns the known data versions of the vnodes, and the vnodes whose versions must be fetched.
+    pub fn vnode_versions(
+        &self,
+        vnodes: Vec<VnodeInfo>,
+    ) -> (HashMap<VnodeId, u64>, Vec<VnodeInfo>) {
+        let vnode_versions = self.vnode_versions.lock();
+        let mut versions = HashMap::with_capacity(vnodes.len());
+        let mut missing = vec![];
+        for vnode in vnodes {
+            match vnode_versions.get(&vnode.id) {
+                Some((version, fetched_at)) if fetched_at.elapsed() < self.version_ttl => {
+                    versions.insert(vnode.id, *version);
+                }
+                _ => missing.push(vnode),
+            }
+        }
+
+        (versions, missing)
+    }
+
+    /// Keep the fetched data versions of vnodes for `version_ttl`.
+    pub fn update_vnode_versions(&self, versions: &HashMap<VnodeId, Option<u64>>) {
+        if self.version_ttl.is_zero() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut vnode_versions = self.vnode_versions.lock();
+        vnode_versions.retain(|_, (_, fetched_at)| fetched_at.elapsed() < self.version_ttl);
+        for (vnode_id, version) in versions {
+            match version {
+                Some(version) => {
+                    vnode_versions.insert(*vnode_id, (*version, now));
+                }
+                None => {
+                    vnode_versions.remove(vnode_id);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, key: &ResultCacheKey) -> Option<SendableRecordBatchStream> {
+        let result = self.cache.get(key)?;
+        match MemoryStream::try_new(result.batches.to_vec(), result.schema, None) {
+            Ok(stream) => Some(Box::pin(stream)),
+            Err(err) => {
+                debug!("Failed to read cached query result: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Returns a stream yielding the same batches as `stream`, the result is cached
+    /// once the stream is fully consumed if it does not exceed the max result size.
+    pub fn cache_stream(
+        self: &Arc<Self>,
+        key: ResultCacheKey,
+        stream: SendableRecordBatchStream,
+    ) -> SendableRecordBatchStream {
+        Box::pin(CachingStream {
+            cache: self.clone(),
+            key: Some(key),
+            inner: stream,
+            batches: vec![],
+            size: 0,
+        })
+    }
+
+    fn insert(&self, key: ResultCacheKey, schema: SchemaRef, batches: Vec<RecordBatch>) {
+        self.cache.insert(
+            key,
+            CachedResult {
+                schema,
+                batches: Arc::new(batches),
+            },
+        );
+    }
+}
+
+struct CachingStream {
+    cache: ResultCacheRef,
+    /// Taken once the result turns out to be not cacheable or has been cached
+    key: Option<ResultCacheKey>,
+    inner: SendableRecordBatchStream,
+    batches: Vec<RecordBatch>,
+    size: usize,
+}
+
+impl Stream for CachingStream {
+    type Item = DFResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let poll = this.inner.poll_next_unpin(cx);
+        if this.key.is_none() {
+            return poll;
+        }
+
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                this.size += batch.get_array_memory_size();
+                if this.size > this.cache.max_result_size {
+                    this.key = None;
+                    this.batches.clear();
+                } else {
+                    this.batches.push(batch.clone());
+                }
+            }
+            Poll::Ready(Some(Err(_))) => {
+                this.key = None;
+                this.batches.clear();
+            }
+            Poll::Ready(None) => {
+                if let Some(key) = this.key.take() {
+                    let batches = std::mem::take(&mut this.batches);
+                    this.cache.insert(key, this.inner.schema(), batches);
+                }
+            }
+            Poll::Pending => {}
+        }
+
+        poll
+    }
+}
+
+impl RecordBatchStream for CachingStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+/// A query result can be cached if it only reads tskv tables and its result is fully
+/// determined by the data of these tables, e.g. a query using `now()` is not cacheable.
+pub fn is_cacheable(plan: &QueryPlan) -> bool {
+    !plan.is_explain() && is_cacheable_plan(&plan.df_plan)
+}
+
+fn is_cacheable_plan(plan: &LogicalPlan) -> bool {
+    let mut visitor = CacheableVisitor { cacheable: true };
+    let _ = plan.visit(&mut visitor);
+    visitor.cacheable
+}
+
+struct CacheableVisitor {
+    cacheable: bool,
+}
+
+impl TreeNodeVisitor for CacheableVisitor {
+    type N = LogicalPlan;
+
+    fn pre_visit(&mut self, plan: &LogicalPlan) -> DFResult<VisitRecursion> {
+        let cacheable_node = match plan {
+            LogicalPlan::TableScan(TableScan { source, .. }) => {
+                // Do not cache anything if the source can not be resolved
+                self.cacheable = false;
+                let adapter = source_downcast_adapter(source)
+                    .map_err(|err| DataFusionError::External(Box::new(err)))?;
+                matches!(adapter.table_handle(), TableHandle::Tskv(_))
+            }
+            LogicalPlan::Extension(Extension { node }) => {
+                // Writes and stream queries are never cached
+                let node = node.as_ref();
+                downcast_plan_node::<TagScanPlanNode>(node).is_some()
+                    || downcast_plan_node::<AsofJoinNode>(node).is_some()
+                    || downcast_plan_node::<TSGenFuncNode>(node).is_some()
+                    || downcast_plan_node::<ExpandNode>(node).is_some()
+            }
+            LogicalPlan::Dml(_) | LogicalPlan::Ddl(_) => false,
+            _ => true,
+        };
+        self.cacheable = cacheable_node && plan.expressions().iter().all(is_immutable_expr);
+
+        if self.cacheable {
+            Ok(VisitRecursion::Continue)
+        } else {
+            Ok(VisitRecursion::Stop)
+        }
+    }
+}
+
+fn is_immutable_expr(expr: &Expr) -> bool {
+    let mut immutable = true;
+    let _ = expr.apply(&mut |e| {
+        immutable = match e {
+            Expr::ScalarFunction(f) => f.fun.volatility() == Volatility::Immutable,
+            Expr::ScalarUDF(f) => f.fun.signature.volatility == Volatility::Immutable,
+            Expr::ScalarSubquery(Subquery { subquery, .. }) => is_cacheable_plan(subquery),
+            Expr::Exists(e) => is_cacheable_plan(&e.subquery.subquery),
+            Expr::InSubquery(e) => is_cacheable_plan(&e.subquery.subquery),
+            _ => true,
+        };
+
+        if immutable {
+            Ok(VisitRecursion::Continue)
+        } else {
+            Ok(VisitRecursion::Stop)
+        }
+    });
+
+    immutable
+}
+
+/// Returns all the vnodes the tskv scans of the physical plan may read from.
+pub fn collect_vnodes(plan: &Arc<dyn ExecutionPlan>) -> Vec<VnodeInfo> {
+    let mut vnodes = vec![];
+    collect_vnodes_inner(plan, &mut vnodes);
+    vnodes
+}
+
+fn collect_vnodes_inner(plan: &Arc<dyn ExecutionPlan>, vnodes: &mut Vec<VnodeInfo>) {
+    let any = plan.as_any();
+    let splits = if let Some(exec) = any.downcast_ref::<TskvExec>() {
+        exec.splits()
+    } else if let Some(exec) = any.downcast_ref::<AggregateFilterTskvExec>() {
+        exec.splits()
+    } else if let Some(exec) = any.downcast_ref::<TagScanExec>() {
+        exec.splits()
+    } else {
+        &[]
+    };
+
+    for split in splits {
+        vnodes.extend(split.replica_set().vnodes.iter().cloned());
+    }
+
+    for child in plan.children() {
+        collect_vnodes_inner(&child, vnodes);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use datafusion::arrow::array::Int64Array;
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::physical_plan::memory::MemoryStream;
+    use datafusion::physical_plan::SendableRecordBatchStream;
+    use futures::TryStreamExt;
+    use models::meta_data::VnodeInfo;
+
+    use super::{ResultCache, ResultCacheKey};
+
+    fn key(version: u64) -> ResultCacheKey {
+        ResultCacheKey {
+            tenant: "cnosdb".to_string(),
+            user: "root".to_string(),
+            database: "public".to_string(),
+            plan: "TableScan: t".to_string(),
+            vnode_versions: vec![(1, version)],
+        }
+    }
+
+    fn stream(rows: i64) -> SendableRecordBatchStream {
+        let schema = Arc::new(Schema::new(vec![Field::new("v", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int64Array::from_iter_values(0..rows))],
+        )
+        .unwrap();
+        Box::pin(MemoryStream::try_new(vec![batch], schema, None).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_result_cache() {
+        let cache = Arc::new(ResultCache::new(16, 64 * 1024));
+        assert!(cache.get(&key(1)).is_none());
+
+        let batches: Vec<RecordBatch> = cache
+            .cache_stream(key(1), stream(10))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(batches[0].num_rows(), 10);
+
+        let cached: Vec<RecordBatch> = cache.get(&key(1)).unwrap().try_collect().await.unwrap();
+        assert_eq!(cached, batches);
+        assert!(cache.get(&key(2)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_result_cache_max_size() {
+        let cache = Arc::new(ResultCache::new(16, 1024));
+
+        let batches: Vec<RecordBatch> = cache
+            .cache_stream(key(1), stream(10_000))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(batches[0].num_rows(), 10_000);
+        assert!(cache.get(&key(1)).is_none());
+    }
+
+    #[test]
+    fn test_vnode_versions() {
+        let vnodes = vec![VnodeInfo::new(1, 1), VnodeInfo::new(2, 2)];
+
+        let cache = ResultCache::new(16, 1024);
+        cache.update_vnode_versions(&HashMap::from([(1, Some(10))]));
+        let (versions, missing) = cache.vnode_versions(vnodes.clone());
+        assert!(versions.is_empty());
+        assert_eq!(missing.len(), 2);
+
+        let cache = ResultCache::new(16, 1024).with_version_ttl(Duration::from_secs(60));
+        cache.update_vnode_versions(&HashMap::from([(1, Some(10)), (2, None)]));
+        let (versions, missing) = cache.vnode_versions(vnodes);
+        assert_eq!(versions, HashMap::from([(1, 10)]));
+        assert_eq!(missing, vec![VnodeInfo::new(2, 2)]);
+    }
+}
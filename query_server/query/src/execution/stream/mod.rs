@@ -121,6 +121,7 @@ impl MicroBatchStreamExecutionBuilder {
             plan,
             stream_providers,
             scheduler,
+            trigger_interval,
             trigger_executor,
             watermark_tracker,
             offset_tracker: Arc::new(OffsetTracker::new()),
@@ -136,6 +137,7 @@ pub struct MicroBatchStreamExecution {
     plan: Arc<QueryPlan>,
     stream_providers: Vec<StreamProviderRef>,
     scheduler: SchedulerRef,
+    trigger_interval: StreamTriggerInterval,
     trigger_executor: TriggerExecutorRef,
     state_store_factory: Arc<MemoryStateStoreFactory>,
     watermark_tracker: WatermarkTrackerRef,
@@ -259,6 +261,7 @@ impl QueryExecution for MicroBatchStreamExecution {
             qsm.session.user().clone(),
             qsm.coord.node_id(),
         )
+        .with_stream_trigger_interval(Some(self.trigger_interval.to_string()))
     }
 
     fn status(&self) -> QueryStatus {
@@ -4,6 +4,7 @@ use datafusion::logical_expr::LogicalPlan;
 pub mod add_time_for_tsgenfunc;
 pub mod initial_plan_checker;
 pub mod stream_checker;
+pub mod transform_asof_join;
 pub mod transform_bottom_func_to_topk_node;
 pub mod transform_count_gen_time_col;
 pub mod transform_exact_count_to_count;
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use datafusion::common::scalar::{dt_to_nano, mdn_to_nano};
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::DFSchema;
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::logical_expr::expr::ScalarUDF as ScalarUDFExpr;
+use datafusion::logical_expr::utils::split_conjunction;
+use datafusion::logical_expr::{
+    BinaryExpr, Expr, Extension, Join, JoinType, LogicalPlan, Operator,
+};
+use datafusion::optimizer::analyzer::AnalyzerRule;
+use datafusion::scalar::ScalarValue;
+
+use crate::extension::expr::ASOF_MATCH;
+use crate::extension::logical::plan_node::asof_join::AsofJoinNode;
+
+/// Converts the joins created from `ASOF JOIN` statements, whose filter contains
+/// `asof_match(<time comparison>[, <tolerance>])`, into [`AsofJoinNode`]
+pub struct TransformAsofJoin;
+
+impl AnalyzerRule for TransformAsofJoin {
+    fn name(&self) -> &str {
+        "TransformAsofJoin"
+    }
+
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        plan.transform_up(&analyze_internal)
+    }
+}
+
+fn analyze_internal(plan: LogicalPlan) -> Result<Transformed<LogicalPlan>> {
+    let join = match &plan {
+        LogicalPlan::Join(join) => join,
+        _ => return Ok(Transformed::No(plan)),
+    };
+    let filter = match &join.filter {
+        Some(filter) => filter,
+        None => return Ok(Transformed::No(plan)),
+    };
+
+    let (matches, others): (Vec<&Expr>, Vec<&Expr>) = split_conjunction(filter)
+        .into_iter()
+        .partition(|e| is_asof_match(e));
+    let args = match matches.as_slice() {
+        [] => return Ok(Transformed::No(plan)),
+        [Expr::ScalarUDF(ScalarUDFExpr { args, .. })] => args,
+        _ => {
+            return Err(DataFusionError::Plan(
+                "ASOF JOIN supports only one MATCH_CONDITION".to_string(),
+            ))
+        }
+    };
+    if !others.is_empty() {
+        return Err(DataFusionError::Plan(format!(
+            "ASOF JOIN supports only equality conditions in ON clause, but found: {}",
+            others
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(" AND ")
+        )));
+    }
+    if !matches!(join.join_type, JoinType::Inner | JoinType::Left) {
+        return Err(DataFusionError::Plan(format!(
+            "ASOF JOIN does not support {} join",
+            join.join_type
+        )));
+    }
+
+    let (left_time, op, right_time) = match_condition(join, &args[0])?;
+    let tolerance = args.get(1).map(tolerance_nanos).transpose()?;
+
+    let node = AsofJoinNode::try_new(
+        join.left.clone(),
+        join.right.clone(),
+        join.on.clone(),
+        left_time,
+        op,
+        right_time,
+        tolerance,
+        join.join_type,
+    )?;
+
+    Ok(Transformed::Yes(LogicalPlan::Extension(Extension {
+        node: Arc::new(node),
+    })))
+}
+
+fn is_asof_match(expr: &Expr) -> bool {
+    matches!(expr, Expr::ScalarUDF(ScalarUDFExpr { fun, .. }) if fun.name == ASOF_MATCH)
+}
+
+/// Returns `(left time expression, operator, right time expression)`,
+/// the operands are swapped if the comparison is written as `right op left`.
+fn match_condition(join: &Join, condition: &Expr) -> Result<(Expr, Operator, Expr)> {
+    let invalid = || {
+        DataFusionError::Plan(format!(
+            "MATCH_CONDITION must compare a time of the left table with a time of the right table \
+            using one of >=, >, <=, <, =, but found: {condition}"
+        ))
+    };
+
+    let (l, op, r) = match condition {
+        Expr::BinaryExpr(BinaryExpr { left, op, right })
+            if matches!(
+                op,
+                Operator::Eq | Operator::Gt | Operator::GtEq | Operator::Lt | Operator::LtEq
+            ) =>
+        {
+            (left.as_ref(), *op, right.as_ref())
+        }
+        _ => return Err(invalid()),
+    };
+
+    let left_schema = join.left.schema();
+    let right_schema = join.right.schema();
+    if is_from_schema(l, left_schema)? && is_from_schema(r, right_schema)? {
+        Ok((l.clone(), op, r.clone()))
+    } else if is_from_schema(r, left_schema)? && is_from_schema(l, right_schema)? {
+        let op = op.swap().ok_or_else(invalid)?;
+        Ok((r.clone(), op, l.clone()))
+    } else {
+        Err(invalid())
+    }
+}
+
+fn is_from_schema(expr: &Expr, schema: &DFSchema) -> Result<bool> {
+    let columns = expr.to_columns()?;
+    Ok(!columns.is_empty() && columns.iter().all(|c| schema.index_of_column(c).is_ok()))
+}
+
+fn tolerance_nanos(expr: &Expr) -> Result<i64> {
+    let nanos = match expr {
+        Expr::Literal(ScalarValue::IntervalMonthDayNano(val)) => mdn_to_nano(val),
+        Expr::Literal(ScalarValue::IntervalDayTime(val)) => dt_to_nano(val),
+        _ => None,
+    };
+
+    match nanos {
+        Some(nanos) if nanos >= 0 => Ok(nanos as i64),
+        _ => Err(DataFusionError::Plan(format!(
+            "TOLERANCE of ASOF JOIN must be a non-negative interval, but found: {expr}"
+        ))),
+    }
+}
@@ -8,15 +8,19 @@ mod scalar_function;
 mod selector_function;
 mod session_function;
 mod ts_gen_func;
+mod user_function;
 mod window;
 
 use datafusion::arrow::datatypes::{DataType, IntervalUnit};
-pub use scalar_function::{INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL};
+pub use scalar_function::{register_mask_hash, ASOF_MATCH, INTERPOLATE, LOCF, TIME_WINDOW_GAPFILL};
 pub use selector_function::{BOTTOM, TOPK};
 pub use session_function::register_session_udfs;
 use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 pub use ts_gen_func::TSGenFunc;
+pub use user_function::{
+    create_user_udaf, create_user_udf, failed_user_udaf, failed_user_udf, MAX_FUNCTION_DEPTH,
+};
 pub use window::{
     ceil_sliding_window, floor_sliding_window, time_window_signature, DEFAULT_TIME_WINDOW_START,
     TIME_WINDOW, TIME_WINDOW_UDF, WINDOW_COL_NAME, WINDOW_END, WINDOW_START,
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::DataType;
+use datafusion::error::DataFusionError;
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature, TypeSignature,
+    Volatility,
+};
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::ASOF_MATCH;
+
+/// Marks the MATCH_CONDITION of an ASOF JOIN in the join filter,
+/// it is replaced by an AsofJoin node during analysis and is never evaluated.
+pub fn register_udf(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<ScalarUDF> {
+    let udf = new();
+    func_manager.register_udf(udf.clone())?;
+    Ok(udf)
+}
+
+fn new() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Boolean)));
+    let scalar_fn: ScalarFunctionImplementation = Arc::new(|_| {
+        Err(DataFusionError::Plan(format!(
+            "{ASOF_MATCH} can only be used as the MATCH_CONDITION of an ASOF JOIN"
+        )))
+    });
+    ScalarUDF::new(
+        ASOF_MATCH,
+        &Signature::one_of(
+            vec![TypeSignature::Any(1), TypeSignature::Any(2)],
+            Volatility::Immutable,
+        ),
+        &return_type_fn,
+        &scalar_fn,
+    )
+}
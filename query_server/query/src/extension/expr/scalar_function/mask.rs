@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    downcast_array, new_null_array, Array, ArrayRef, Int64Array, StringArray, StringBuilder,
+};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::{
+    ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature, TypeSignature,
+    Volatility,
+};
+use datafusion::physical_plan::functions::make_scalar_function;
+use spi::query::function::FunctionMetadataManager;
+use spi::QueryResult;
+
+use super::{MASK_HASH, MASK_NULL, MASK_PARTIAL};
+
+const DEFAULT_MASK_CHAR: char = '*';
+
+/// The context of the key of `mask_hash` derived from the masking secret
+const MASK_HASH_KEY_CONTEXT: &str = "cnosdb mask_hash";
+
+pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
+    func_manager.register_udf(new_mask_partial())?;
+    func_manager.register_udf(new_mask_hash(None))?;
+    func_manager.register_udf(new_mask_null())?;
+    Ok(())
+}
+
+/// Replaces `mask_hash` by the one keyed with the masking secret of the cluster
+pub fn register_mask_hash(
+    func_manager: &mut dyn FunctionMetadataManager,
+    masking_secret: &str,
+) -> QueryResult<()> {
+    let key = blake3::derive_key(MASK_HASH_KEY_CONTEXT, masking_secret.as_bytes());
+    func_manager.register_udf(new_mask_hash(Some(key)))
+}
+
+/// mask_partial(value, keep_prefix, keep_suffix[, mask_char]), e.g.
+/// mask_partial('13812345678', 3, 4) = '138****5678'
+fn new_mask_partial() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Utf8)));
+    let signature = vec![
+        TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64, DataType::Int64]),
+        TypeSignature::Exact(vec![
+            DataType::Utf8,
+            DataType::Int64,
+            DataType::Int64,
+            DataType::Utf8,
+        ]),
+    ];
+
+    ScalarUDF::new(
+        MASK_PARTIAL,
+        &Signature::one_of(signature, Volatility::Immutable),
+        &return_type_fn,
+        &make_scalar_function(mask_partial_implement),
+    )
+}
+
+fn mask_partial_implement(input: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let values = downcast_array::<StringArray>(input[0].as_ref());
+    let prefixes = downcast_array::<Int64Array>(input[1].as_ref());
+    let suffixes = downcast_array::<Int64Array>(input[2].as_ref());
+    let mask_chars = input
+        .get(3)
+        .map(|array| downcast_array::<StringArray>(array.as_ref()));
+
+    let keep = |array: &Int64Array, i: usize| {
+        if array.is_null(i) {
+            0
+        } else {
+            array.value(i).max(0) as usize
+        }
+    };
+
+    let mut builder = StringBuilder::with_capacity(values.len(), values.value_data().len());
+    for i in 0..values.len() {
+        if values.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+        let mask_char = mask_chars
+            .as_ref()
+            .filter(|array| !array.is_null(i))
+            .and_then(|array| array.value(i).chars().next())
+            .unwrap_or(DEFAULT_MASK_CHAR);
+        builder.append_value(mask_partial(
+            values.value(i),
+            keep(&prefixes, i),
+            keep(&suffixes, i),
+            mask_char,
+        ));
+    }
+
+    Ok(Arc::new(builder.finish()))
+}
+
+/// Keeps the first `prefix` and the last `suffix` characters, the value is masked entirely
+/// if it's too short to keep both of them.
+fn mask_partial(value: &str, prefix: usize, suffix: usize, mask_char: char) -> String {
+    let len = value.chars().count();
+    if prefix.saturating_add(suffix) >= len {
+        return std::iter::repeat(mask_char).take(len).collect();
+    }
+
+    value
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if i < prefix || i >= len - suffix {
+                c
+            } else {
+                mask_char
+            }
+        })
+        .collect()
+}
+
+/// mask_hash(value), the hex of the BLAKE3 hash of the value keyed by the masking secret,
+/// the masked values can still be grouped and joined but not looked up without the secret.
+fn new_mask_hash(key: Option<[u8; 32]>) -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Utf8)));
+    let fun = make_scalar_function(move |input: &[ArrayRef]| match &key {
+        Some(key) => mask_hash_implement(key, input),
+        None => Err(DataFusionError::Execution(format!(
+            "{} requires the masking secret of the cluster, set security.masking_secret",
+            MASK_HASH
+        ))),
+    });
+
+    ScalarUDF::new(
+        MASK_HASH,
+        &Signature::exact(vec![DataType::Utf8], Volatility::Immutable),
+        &return_type_fn,
+        &fun,
+    )
+}
+
+fn mask_hash_implement(key: &[u8; 32], input: &[ArrayRef]) -> DFResult<ArrayRef> {
+    let values = downcast_array::<StringArray>(input[0].as_ref());
+
+    let array = values
+        .iter()
+        .map(|value| value.map(|v| blake3::keyed_hash(key, v.as_bytes()).to_hex().to_string()))
+        .collect::<StringArray>();
+
+    Ok(Arc::new(array))
+}
+
+/// mask_null(value), a null of the type of the value.
+fn new_mask_null() -> ScalarUDF {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|args| Ok(Arc::new(args[0].clone())));
+    let fun: ScalarFunctionImplementation =
+        make_scalar_function(|input: &[ArrayRef]| -> DFResult<ArrayRef> {
+            Ok(new_null_array(input[0].data_type(), input[0].len()))
+        });
+
+    ScalarUDF::new(
+        MASK_NULL,
+        &Signature::any(1, Volatility::Immutable),
+        &return_type_fn,
+        &fun,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mask_partial() {
+        assert_eq!(mask_partial("13812345678", 3, 4, '*'), "138****5678");
+        assert_eq!(
+            mask_partial("LSVAU2180N2183294", 0, 6, '#'),
+            "###########183294"
+        );
+        assert_eq!(mask_partial("张三丰", 1, 0, '*'), "张**");
+        // too short to keep both ends
+        assert_eq!(mask_partial("1234", 2, 2, '*'), "****");
+        assert_eq!(mask_partial("", 1, 1, '*'), "");
+    }
+
+    #[test]
+    fn test_mask_implement() {
+        let values: ArrayRef = Arc::new(StringArray::from(vec![Some("13812345678"), None]));
+        let keep: ArrayRef = Arc::new(Int64Array::from(vec![3, 3]));
+
+        let masked = mask_partial_implement(&[values.clone(), keep.clone(), keep]).unwrap();
+        let masked = downcast_array::<StringArray>(masked.as_ref());
+        assert_eq!(masked.value(0), "138*****678");
+        assert!(masked.is_null(1));
+
+        let key = blake3::derive_key(MASK_HASH_KEY_CONTEXT, b"secret");
+        let hashed = mask_hash_implement(&key, &[values.clone()]).unwrap();
+        let hashed = downcast_array::<StringArray>(hashed.as_ref());
+        assert_eq!(hashed.value(0).len(), 64);
+        assert_ne!(hashed.value(0), "13812345678");
+        assert_ne!(
+            hashed.value(0),
+            blake3::hash(b"13812345678").to_hex().to_string()
+        );
+        assert!(hashed.is_null(1));
+
+        let other_key = blake3::derive_key(MASK_HASH_KEY_CONTEXT, b"another secret");
+        let rehashed = mask_hash_implement(&other_key, &[values]).unwrap();
+        let rehashed = downcast_array::<StringArray>(rehashed.as_ref());
+        assert_ne!(hashed.value(0), rehashed.value(0));
+    }
+}
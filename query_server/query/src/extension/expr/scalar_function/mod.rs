@@ -1,3 +1,4 @@
+mod asof_match;
 mod duration_in;
 #[cfg(test)]
 mod example;
@@ -6,6 +7,7 @@ mod gauge;
 mod gis;
 mod interpolate;
 mod locf;
+mod mask;
 mod state_at;
 mod utils;
 
@@ -13,6 +15,7 @@ use std::sync::Arc;
 
 use datafusion::error::DataFusionError;
 use datafusion::logical_expr::ScalarFunctionImplementation;
+pub use mask::register_mask_hash;
 use spi::query::function::FunctionMetadataManager;
 use spi::QueryResult;
 
@@ -23,11 +26,16 @@ pub const LOCF: &str = "locf";
 pub const INTERPOLATE: &str = "interpolate";
 pub const DURATION_IN: &str = "duration_in";
 pub const STATE_AT: &str = "state_at";
+pub const ASOF_MATCH: &str = "asof_match";
+pub const MASK_PARTIAL: &str = "mask_partial";
+pub const MASK_HASH: &str = "mask_hash";
+pub const MASK_NULL: &str = "mask_null";
 
 pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryResult<()> {
     // extend function...
     // eg.
     //   example::register_udf(func_manager)?;
+    asof_match::register_udf(func_manager)?;
     gapfill::register_udf(func_manager)?;
     locf::register_udf(func_manager)?;
     interpolate::register_udf(func_manager)?;
@@ -35,6 +43,7 @@ pub fn register_udfs(func_manager: &mut dyn FunctionMetadataManager) -> QueryRes
     duration_in::register_udf(func_manager)?;
     state_at::register_udf(func_manager)?;
     gis::register_udfs(func_manager)?;
+    mask::register_udfs(func_manager)?;
     TSGenFunc::register_all_udf(func_manager)?;
     Ok(())
 }
@@ -0,0 +1,86 @@
+//! The functions created by the users of a tenant, see [`FunctionSchema`].
+
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, ScalarUDF, Signature,
+    StateTypeFunction, Volatility,
+};
+use datafusion::physical_plan::functions::make_scalar_function;
+use datafusion::sql::planner::ContextProvider;
+use models::schema::function_schema::{FunctionBody, FunctionSchema};
+
+mod sql;
+mod wasm;
+
+/// The max depth of sql functions calling each other
+pub const MAX_FUNCTION_DEPTH: usize = 16;
+
+/// `provider` resolves the functions called in the body of a sql function
+pub fn create_user_udf<S: ContextProvider>(
+    function: &FunctionSchema,
+    provider: &S,
+) -> DFResult<ScalarUDF> {
+    match function.body() {
+        FunctionBody::Sql(sql) => sql::create_udf(function, sql, provider),
+        FunctionBody::Wasm(bytes) => wasm::create_udf(function, bytes),
+    }
+}
+
+pub fn create_user_udaf(function: &FunctionSchema) -> DFResult<AggregateUDF> {
+    match function.body() {
+        FunctionBody::Wasm(bytes) => wasm::create_udaf(function, bytes),
+        FunctionBody::Sql(_) => Err(DataFusionError::NotImplemented(format!(
+            "Aggregate function {} in SQL, use WASM instead",
+            function.name()
+        ))),
+    }
+}
+
+/// A scalar function that could not be planned, the error is reported when the
+/// function is called instead of reporting the function as not found.
+pub fn failed_user_udf(function: &FunctionSchema, error: DataFusionError) -> ScalarUDF {
+    let message = planning_error_message(function, error);
+    let return_type_fn: ReturnTypeFunction = {
+        let message = message.clone();
+        Arc::new(move |_| Err(DataFusionError::Plan(message.clone())))
+    };
+    let fun =
+        make_scalar_function(move |_: &[ArrayRef]| Err(DataFusionError::Plan(message.clone())));
+
+    ScalarUDF::new(
+        function.name(),
+        &Signature::exact(function.arg_types(), Volatility::Immutable),
+        &return_type_fn,
+        &fun,
+    )
+}
+
+/// An aggregate function that could not be planned, see [`failed_user_udf`].
+pub fn failed_user_udaf(function: &FunctionSchema, error: DataFusionError) -> AggregateUDF {
+    let message = planning_error_message(function, error);
+    let return_type_fn: ReturnTypeFunction = {
+        let message = message.clone();
+        Arc::new(move |_| Err(DataFusionError::Plan(message.clone())))
+    };
+    let state_type_fn: StateTypeFunction = {
+        let message = message.clone();
+        Arc::new(move |_, _| Err(DataFusionError::Plan(message.clone())))
+    };
+    let accumulator: AccumulatorFactoryFunction =
+        Arc::new(move |_, _| Err(DataFusionError::Plan(message.clone())));
+
+    AggregateUDF::new(
+        function.name(),
+        &Signature::exact(function.arg_types(), Volatility::Immutable),
+        &return_type_fn,
+        &accumulator,
+        &state_type_fn,
+    )
+}
+
+fn planning_error_message(function: &FunctionSchema, error: DataFusionError) -> String {
+    format!("Failed to plan function {}: {}", function.name(), error)
+}
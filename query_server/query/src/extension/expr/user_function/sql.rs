@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::arrow::datatypes::{Field, Schema};
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion::common::{DFSchema, DataFusionError, Result as DFResult};
+use datafusion::logical_expr::expr_rewriter::rewrite_preserving_name;
+use datafusion::logical_expr::{cast, ReturnTypeFunction, ScalarUDF, Signature, Volatility};
+use datafusion::optimizer::analyzer::type_coercion::TypeCoercionRewriter;
+use datafusion::physical_expr::create_physical_expr;
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use datafusion::physical_plan::functions::make_scalar_function;
+use datafusion::sql::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion::sql::sqlparser::ast::Expr;
+use datafusion::sql::sqlparser::parser::Parser;
+use datafusion::sql::sqlparser::tokenizer::Token;
+use models::schema::function_schema::FunctionSchema;
+
+use crate::sql::dialect::CnosDBDialect;
+
+/// A sql function is an expression over its arguments,
+/// the expression is planned once and evaluated on a batch made of the arguments.
+pub fn create_udf<S: ContextProvider>(
+    function: &FunctionSchema,
+    sql: &str,
+    provider: &S,
+) -> DFResult<ScalarUDF> {
+    let schema = Arc::new(Schema::new(
+        function
+            .args()
+            .iter()
+            .map(|e| Field::new(&e.name, e.data_type.clone(), true))
+            .collect::<Vec<_>>(),
+    ));
+    let df_schema = Arc::new(DFSchema::try_from(schema.as_ref().clone())?);
+
+    let expr = SqlToRel::new(provider).sql_to_expr(
+        parse_body(sql)?,
+        &df_schema,
+        &mut PlannerContext::new(),
+    )?;
+    let expr = rewrite_preserving_name(
+        cast(expr, function.return_type().clone()),
+        &mut TypeCoercionRewriter::new(df_schema.clone()),
+    )?;
+    let physical_expr = create_physical_expr(&expr, &df_schema, &schema, &ExecutionProps::new())?;
+
+    let fun = make_scalar_function(move |args: &[ArrayRef]| {
+        let num_rows = args.first().map(|e| e.len()).unwrap_or(1);
+        let options = RecordBatchOptions::new().with_row_count(Some(num_rows));
+        let batch = RecordBatch::try_new_with_options(schema.clone(), args.to_vec(), &options)?;
+
+        Ok(physical_expr.evaluate(&batch)?.into_array(num_rows))
+    });
+    let return_type = Arc::new(function.return_type().clone());
+    let return_type_fn: ReturnTypeFunction = Arc::new(move |_| Ok(return_type.clone()));
+
+    Ok(ScalarUDF::new(
+        function.name(),
+        &Signature::exact(function.arg_types(), Volatility::Immutable),
+        &return_type_fn,
+        &fun,
+    ))
+}
+
+fn parse_body(sql: &str) -> DFResult<Expr> {
+    let dialect = CnosDBDialect;
+    let mut parser = Parser::new(&dialect).try_with_sql(sql)?;
+    let expr = parser.parse_expr()?;
+
+    if parser.peek_token().token != Token::EOF {
+        return Err(DataFusionError::Plan(format!(
+            "The body of a sql function must be a single expression: {sql}"
+        )));
+    }
+
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array};
+    use datafusion::arrow::datatypes::DataType;
+    use datafusion::config::ConfigOptions;
+    use datafusion::error::Result;
+    use datafusion::logical_expr::{AggregateUDF, ScalarUDF, TableSource, WindowUDF};
+    use datafusion::physical_plan::ColumnarValue;
+    use datafusion::scalar::ScalarValue;
+    use datafusion::sql::planner::ContextProvider;
+    use datafusion::sql::TableReference;
+    use models::schema::function_schema::{
+        FunctionArg, FunctionBody, FunctionKind, FunctionSchema,
+    };
+
+    use super::create_udf;
+
+    #[derive(Default)]
+    struct EmptyContext {
+        options: ConfigOptions,
+    }
+
+    impl ContextProvider for EmptyContext {
+        fn get_table_provider(&self, name: TableReference) -> Result<Arc<dyn TableSource>> {
+            Err(datafusion::error::DataFusionError::Plan(format!(
+                "table {name} not found"
+            )))
+        }
+
+        fn get_function_meta(&self, _name: &str) -> Option<Arc<ScalarUDF>> {
+            None
+        }
+
+        fn get_aggregate_meta(&self, _name: &str) -> Option<Arc<AggregateUDF>> {
+            None
+        }
+
+        fn get_variable_type(&self, _: &[String]) -> Option<DataType> {
+            None
+        }
+
+        fn options(&self) -> &ConfigOptions {
+            &self.options
+        }
+
+        fn get_window_meta(&self, _name: &str) -> Option<Arc<WindowUDF>> {
+            None
+        }
+    }
+
+    fn function(args: Vec<FunctionArg>, body: &str) -> FunctionSchema {
+        FunctionSchema::new(
+            "cnosdb",
+            "f",
+            FunctionKind::Scalar,
+            args,
+            DataType::Float64,
+            FunctionBody::Sql(body.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_sql_function() {
+        let function = function(
+            vec![FunctionArg::new("c", DataType::Int64)],
+            "c * 9 / 5.0 + 32",
+        );
+        let udf = create_udf(&function, "c * 9 / 5.0 + 32", &EmptyContext::default()).unwrap();
+
+        let input: ArrayRef = Arc::new(Int64Array::from(vec![Some(100), None, Some(0)]));
+        let result = (udf.fun)(&[ColumnarValue::Array(input)])
+            .unwrap()
+            .into_array(3);
+        let expected: ArrayRef = Arc::new(Float64Array::from(vec![Some(212.0), None, Some(32.0)]));
+        assert_eq!(&result, &expected);
+
+        let result = (udf.fun)(&[ColumnarValue::Scalar(ScalarValue::Int64(Some(-40)))]).unwrap();
+        assert!(matches!(
+            result,
+            ColumnarValue::Scalar(ScalarValue::Float64(Some(v))) if v == -40.0
+        ));
+    }
+
+    #[test]
+    fn test_invalid_body() {
+        let function = function(vec![FunctionArg::new("c", DataType::Int64)], "d + 1");
+        assert!(create_udf(&function, "d + 1", &EmptyContext::default()).is_err());
+        assert!(create_udf(&function, "c + 1; drop table t", &EmptyContext::default()).is_err());
+    }
+}
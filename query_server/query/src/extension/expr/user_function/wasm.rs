@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{new_empty_array, Array, ArrayRef};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::{DataFusionError, Result as DFResult};
+use datafusion::logical_expr::{
+    AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, ScalarUDF, Signature,
+    StateTypeFunction, Volatility,
+};
+use datafusion::physical_plan::functions::make_scalar_function;
+use datafusion::physical_plan::Accumulator;
+use datafusion::scalar::ScalarValue;
+use models::schema::function_schema::FunctionSchema;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use wasmtime::{
+    Config, Engine, ExternType, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+    Val, ValType,
+};
+
+/// The instructions a function may execute on a batch before it is aborted
+const FUEL_PER_BATCH: u64 = 1_000_000_000;
+/// The linear memory a function instance may grow to
+const MAX_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+
+static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).expect("create wasm engine")
+});
+
+/// Compiled modules by (tenant, function name), recompiled when the function is replaced
+static MODULES: Lazy<Mutex<HashMap<(String, String), (Vec<u8>, Module)>>> =
+    Lazy::new(Default::default);
+
+/// A wasm scalar function exports a function named after it,
+/// which takes the arguments and returns the result.
+///
+/// Any null argument makes the result null.
+pub fn create_udf(function: &FunctionSchema, bytes: &[u8]) -> DFResult<ScalarUDF> {
+    let module = compile(function, bytes)?;
+    let name = function.name().to_string();
+    let return_type = function.return_type().clone();
+
+    let params = function
+        .arg_types()
+        .iter()
+        .map(wasm_type)
+        .collect::<DFResult<Vec<_>>>()?;
+    check_export(&module, &name, &params, &wasm_type(&return_type)?)?;
+
+    let fun = make_scalar_function(move |args: &[ArrayRef]| {
+        let num_rows = args.first().map(|e| e.len()).unwrap_or(1);
+        let mut instance = WasmInstance::try_new(&module)?;
+
+        let mut values = Vec::with_capacity(num_rows);
+        for row in 0..num_rows {
+            let value = match row_params(args, row)? {
+                Some(params) => instance.call(&name, &params, &return_type)?,
+                None => ScalarValue::try_from(&return_type)?,
+            };
+            values.push(value);
+        }
+
+        to_array(values, &return_type)
+    });
+    let return_type = Arc::new(function.return_type().clone());
+    let return_type_fn: ReturnTypeFunction = Arc::new(move |_| Ok(return_type.clone()));
+
+    Ok(ScalarUDF::new(
+        function.name(),
+        &Signature::exact(function.arg_types(), Volatility::Immutable),
+        &return_type_fn,
+        &fun,
+    ))
+}
+
+/// A wasm aggregate function keeps a state of its return type, it exports
+/// - `{name}_init() -> state`
+/// - `{name}_update(state, args...) -> state`
+/// - `{name}_merge(state, state) -> state`
+///
+/// The rows with a null argument are skipped, the result is the final state.
+pub fn create_udaf(function: &FunctionSchema, bytes: &[u8]) -> DFResult<AggregateUDF> {
+    let module = compile(function, bytes)?;
+    let name = function.name().to_string();
+    let state_type = function.return_type().clone();
+
+    let state = wasm_type(&state_type)?;
+    let mut params = vec![state.clone()];
+    for arg_type in function.arg_types() {
+        params.push(wasm_type(&arg_type)?);
+    }
+    check_export(&module, &format!("{name}_init"), &[], &state)?;
+    check_export(&module, &format!("{name}_update"), &params, &state)?;
+    check_export(
+        &module,
+        &format!("{name}_merge"),
+        &[state.clone(), state.clone()],
+        &state,
+    )?;
+
+    let return_type = Arc::new(state_type.clone());
+    let return_type_fn: ReturnTypeFunction = Arc::new(move |_| Ok(return_type.clone()));
+    let state_types = Arc::new(vec![state_type.clone()]);
+    let state_type_fn: StateTypeFunction = Arc::new(move |_, _| Ok(state_types.clone()));
+    let accumulator: AccumulatorFactoryFunction = Arc::new(move |_, _| {
+        Ok(Box::new(WasmAccumulator::try_new(
+            &module,
+            name.clone(),
+            state_type.clone(),
+        )?))
+    });
+
+    Ok(AggregateUDF::new(
+        function.name(),
+        &Signature::exact(function.arg_types(), Volatility::Immutable),
+        &return_type_fn,
+        &accumulator,
+        &state_type_fn,
+    ))
+}
+
+/// Check that the module of the function is valid wasm and sandboxed
+fn compile(function: &FunctionSchema, bytes: &[u8]) -> DFResult<Module> {
+    let key = (function.tenant().to_string(), function.name().to_string());
+    let mut modules = MODULES.lock();
+    if let Some((cached, module)) = modules.get(&key) {
+        if cached.as_slice() == bytes {
+            return Ok(module.clone());
+        }
+    }
+
+    let module = Module::new(&ENGINE, bytes).map_err(|e| {
+        DataFusionError::Plan(format!(
+            "Invalid wasm module of function {}: {e}",
+            function.name()
+        ))
+    })?;
+    if let Some(import) = module.imports().next() {
+        return Err(DataFusionError::Plan(format!(
+            "The wasm module of function {} can not import {}.{}",
+            function.name(),
+            import.module(),
+            import.name()
+        )));
+    }
+
+    modules.insert(key, (bytes.to_vec(), module.clone()));
+    Ok(module)
+}
+
+fn check_export(module: &Module, name: &str, params: &[ValType], result: &ValType) -> DFResult<()> {
+    let signature = || {
+        format!(
+            "{name}({}) -> {result}",
+            params
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+
+    match module.get_export(name) {
+        Some(ExternType::Func(func))
+            if func.params().eq(params.iter().cloned())
+                && func.results().eq(std::iter::once(result.clone())) =>
+        {
+            Ok(())
+        }
+        _ => Err(DataFusionError::Plan(format!(
+            "The wasm module must export function {}",
+            signature()
+        ))),
+    }
+}
+
+fn wasm_type(data_type: &DataType) -> DFResult<ValType> {
+    match data_type {
+        DataType::Int64 | DataType::UInt64 => Ok(ValType::I64),
+        DataType::Int32 | DataType::Boolean => Ok(ValType::I32),
+        DataType::Float64 => Ok(ValType::F64),
+        DataType::Float32 => Ok(ValType::F32),
+        _ => Err(DataFusionError::NotImplemented(format!(
+            "Type {data_type} in wasm functions"
+        ))),
+    }
+}
+
+fn to_val(value: &ScalarValue) -> Option<Val> {
+    match value {
+        ScalarValue::Int64(Some(v)) => Some(Val::I64(*v)),
+        ScalarValue::UInt64(Some(v)) => Some(Val::I64(*v as i64)),
+        ScalarValue::Int32(Some(v)) => Some(Val::I32(*v)),
+        ScalarValue::Boolean(Some(v)) => Some(Val::I32(*v as i32)),
+        ScalarValue::Float64(Some(v)) => Some(Val::F64(v.to_bits())),
+        ScalarValue::Float32(Some(v)) => Some(Val::F32(v.to_bits())),
+        _ => None,
+    }
+}
+
+fn from_val(val: &Val, data_type: &DataType) -> DFResult<ScalarValue> {
+    match (val, data_type) {
+        (Val::I64(v), DataType::Int64) => Ok(ScalarValue::Int64(Some(*v))),
+        (Val::I64(v), DataType::UInt64) => Ok(ScalarValue::UInt64(Some(*v as u64))),
+        (Val::I32(v), DataType::Int32) => Ok(ScalarValue::Int32(Some(*v))),
+        (Val::I32(v), DataType::Boolean) => Ok(ScalarValue::Boolean(Some(*v != 0))),
+        (Val::F64(v), DataType::Float64) => Ok(ScalarValue::Float64(Some(f64::from_bits(*v)))),
+        (Val::F32(v), DataType::Float32) => Ok(ScalarValue::Float32(Some(f32::from_bits(*v)))),
+        _ => Err(DataFusionError::Internal(format!(
+            "Wasm value {val:?} does not match type {data_type}"
+        ))),
+    }
+}
+
+/// The params of a row, `None` if any of them is null
+fn row_params(args: &[ArrayRef], row: usize) -> DFResult<Option<Vec<Val>>> {
+    let mut params = Vec::with_capacity(args.len());
+    for arg in args {
+        match to_val(&ScalarValue::try_from_array(arg, row)?) {
+            Some(val) => params.push(val),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(params))
+}
+
+fn to_array(values: Vec<ScalarValue>, data_type: &DataType) -> DFResult<ArrayRef> {
+    if values.is_empty() {
+        return Ok(new_empty_array(data_type));
+    }
+    ScalarValue::iter_to_array(values)
+}
+
+/// An instance of a module with its own memory, the fuel is refilled for each batch
+struct WasmInstance {
+    store: Store<StoreLimits>,
+    instance: Instance,
+}
+
+impl WasmInstance {
+    fn try_new(module: &Module) -> DFResult<Self> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_SIZE)
+            .build();
+        let mut store = Store::new(&ENGINE, limits);
+        store.limiter(|limits| limits);
+
+        let instance = Linker::new(&ENGINE)
+            .instantiate(&mut store, module)
+            .map_err(|e| DataFusionError::Execution(format!("Instantiate wasm module: {e}")))?;
+
+        let mut instance = Self { store, instance };
+        instance.refuel()?;
+        Ok(instance)
+    }
+
+    fn refuel(&mut self) -> DFResult<()> {
+        let refuel = |store: &mut Store<StoreLimits>| {
+            // consuming nothing returns the remaining fuel
+            let remaining = store.consume_fuel(0)?;
+            store.add_fuel(FUEL_PER_BATCH.saturating_sub(remaining))
+        };
+        refuel(&mut self.store).map_err(|e| DataFusionError::Execution(e.to_string()))
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        params: &[Val],
+        return_type: &DataType,
+    ) -> DFResult<ScalarValue> {
+        let func = self
+            .instance
+            .get_func(&mut self.store, name)
+            .ok_or_else(|| DataFusionError::Execution(format!("Wasm function {name} not found")))?;
+
+        let mut results = [Val::I32(0)];
+        func.call(&mut self.store, params, &mut results)
+            .map_err(|e| DataFusionError::Execution(format!("Call wasm function {name}: {e}")))?;
+
+        from_val(&results[0], return_type)
+    }
+}
+
+impl Debug for WasmInstance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmInstance").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+struct WasmAccumulator {
+    instance: WasmInstance,
+    name: String,
+    state_type: DataType,
+    state: ScalarValue,
+}
+
+impl WasmAccumulator {
+    fn try_new(module: &Module, name: String, state_type: DataType) -> DFResult<Self> {
+        let mut instance = WasmInstance::try_new(module)?;
+        let state = instance.call(&format!("{name}_init"), &[], &state_type)?;
+
+        Ok(Self {
+            instance,
+            name,
+            state_type,
+            state,
+        })
+    }
+
+    fn apply(&mut self, suffix: &str, args: &[ArrayRef], row: usize) -> DFResult<()> {
+        if let Some(mut params) = row_params(args, row)? {
+            let state = to_val(&self.state).ok_or_else(|| {
+                DataFusionError::Internal(format!("Null state of wasm function {}", self.name))
+            })?;
+            params.insert(0, state);
+
+            self.state = self.instance.call(
+                &format!("{}_{suffix}", self.name),
+                &params,
+                &self.state_type,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for WasmAccumulator {
+    fn state(&self) -> DFResult<Vec<ScalarValue>> {
+        Ok(vec![self.state.clone()])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DFResult<()> {
+        self.instance.refuel()?;
+        let num_rows = values.first().map(|e| e.len()).unwrap_or_default();
+        for row in 0..num_rows {
+            self.apply("update", values, row)?;
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DFResult<()> {
+        self.instance.refuel()?;
+        let num_rows = states.first().map(|e| e.len()).unwrap_or_default();
+        for row in 0..num_rows {
+            self.apply("merge", states, row)?;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DFResult<ScalarValue> {
+        Ok(self.state.clone())
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.state.size() - std::mem::size_of_val(&self.state)
+            + self.name.capacity()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{ArrayRef, Float64Array};
+    use datafusion::arrow::datatypes::DataType;
+    use datafusion::physical_plan::ColumnarValue;
+    use models::schema::function_schema::{
+        FunctionArg, FunctionBody, FunctionKind, FunctionSchema,
+    };
+
+    use super::{create_udaf, create_udf};
+
+    fn function(kind: FunctionKind, wat: &str) -> FunctionSchema {
+        // the text format is accepted as well as the binary format
+        let bytes = wat.as_bytes().to_vec();
+        FunctionSchema::new(
+            "cnosdb",
+            "f",
+            kind,
+            vec![FunctionArg::new("a", DataType::Float64)],
+            DataType::Float64,
+            FunctionBody::Wasm(bytes),
+        )
+    }
+
+    #[test]
+    fn test_scalar_function() {
+        let function = function(
+            FunctionKind::Scalar,
+            r#"(module (func (export "f") (param f64) (result f64)
+                local.get 0 local.get 0 f64.mul))"#,
+        );
+        let FunctionBody::Wasm(bytes) = function.body() else {
+            unreachable!()
+        };
+        let udf = create_udf(&function, bytes).unwrap();
+
+        let input: ArrayRef = Arc::new(Float64Array::from(vec![Some(2.0), None, Some(3.0)]));
+        let result = (udf.fun)(&[ColumnarValue::Array(input)])
+            .unwrap()
+            .into_array(3);
+        let expected: ArrayRef = Arc::new(Float64Array::from(vec![Some(4.0), None, Some(9.0)]));
+        assert_eq!(&result, &expected);
+    }
+
+    #[test]
+    fn test_aggregate_function() {
+        let function = function(
+            FunctionKind::Aggregate,
+            r#"(module
+                (func (export "f_init") (result f64) f64.const 0)
+                (func (export "f_update") (param f64 f64) (result f64)
+                    local.get 0 local.get 1 f64.add)
+                (func (export "f_merge") (param f64 f64) (result f64)
+                    local.get 0 local.get 1 f64.add))"#,
+        );
+        let FunctionBody::Wasm(bytes) = function.body() else {
+            unreachable!()
+        };
+        let udaf = create_udaf(&function, bytes).unwrap();
+
+        let mut accumulator = (udaf.accumulator)(&[DataType::Float64], &DataType::Float64).unwrap();
+        let input: ArrayRef = Arc::new(Float64Array::from(vec![Some(1.0), None, Some(2.5)]));
+        accumulator.update_batch(&[input]).unwrap();
+        assert_eq!(accumulator.evaluate().unwrap(), 3.5_f64.into());
+    }
+
+    #[test]
+    fn test_reject_imports() {
+        let function = function(
+            FunctionKind::Scalar,
+            r#"(module (import "env" "f" (func)) (func (export "f") (param f64) (result f64)
+                local.get 0))"#,
+        );
+        let FunctionBody::Wasm(bytes) = function.body() else {
+            unreachable!()
+        };
+        assert!(create_udf(&function, bytes).is_err());
+    }
+}
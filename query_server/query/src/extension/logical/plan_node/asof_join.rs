@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+
+use datafusion::common::DFSchemaRef;
+use datafusion::error::Result;
+use datafusion::logical_expr::{
+    build_join_schema, JoinType, LogicalPlan, Operator, UserDefinedLogicalNodeCore,
+};
+use datafusion::prelude::Expr;
+
+/// Joins every row of the left input with the row of the right input that has the same
+/// values of the `on` expressions and the closest time according to `op`:
+///
+/// - `left_time >= right_time`: the latest right row not after the left row
+/// - `left_time > right_time`: the latest right row before the left row
+/// - `left_time <= right_time`: the earliest right row not before the left row
+/// - `left_time < right_time`: the earliest right row after the left row
+/// - `left_time = right_time`: the nearest right row in either direction
+///
+/// Rows more than `tolerance` nanoseconds apart are never matched.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AsofJoinNode {
+    pub left: Arc<LogicalPlan>,
+    pub right: Arc<LogicalPlan>,
+    /// Equijoin clause expressed as pairs of (left, right) join expressions
+    pub on: Vec<(Expr, Expr)>,
+    pub left_time: Expr,
+    pub op: Operator,
+    pub right_time: Expr,
+    /// Max distance between the matched times in nanoseconds
+    pub tolerance: Option<i64>,
+    /// Only [`JoinType::Inner`] and [`JoinType::Left`] are supported
+    pub join_type: JoinType,
+    /// The schema description of the output
+    pub schema: DFSchemaRef,
+}
+
+impl AsofJoinNode {
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        left: Arc<LogicalPlan>,
+        right: Arc<LogicalPlan>,
+        on: Vec<(Expr, Expr)>,
+        left_time: Expr,
+        op: Operator,
+        right_time: Expr,
+        tolerance: Option<i64>,
+        join_type: JoinType,
+    ) -> Result<Self> {
+        let schema = Arc::new(build_join_schema(
+            left.schema(),
+            right.schema(),
+            &join_type,
+        )?);
+
+        Ok(Self {
+            left,
+            right,
+            on,
+            left_time,
+            op,
+            right_time,
+            tolerance,
+            join_type,
+            schema,
+        })
+    }
+}
+
+impl Debug for AsofJoinNode {
+    /// For AsofJoinNode, use explain format for the Debug format. Other types
+    /// of nodes may
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNodeCore for AsofJoinNode {
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![self.left.as_ref(), self.right.as_ref()]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    /// `[on left expressions..., on right expressions..., left_time, right_time]`
+    fn expressions(&self) -> Vec<Expr> {
+        self.on
+            .iter()
+            .map(|(l, _)| l.clone())
+            .chain(self.on.iter().map(|(_, r)| r.clone()))
+            .chain([self.left_time.clone(), self.right_time.clone()])
+            .collect()
+    }
+
+    /// Filters on the right input would change which row is the closest one, and filters
+    /// are pushed down to all the inputs of an extension node, so nothing is pushed down.
+    fn prevent_predicate_push_down_columns(&self) -> HashSet<String> {
+        self.schema
+            .fields()
+            .iter()
+            .map(|f| f.name())
+            .cloned()
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let on = self
+            .on
+            .iter()
+            .map(|(l, r)| format!("{l} = {r}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(
+            f,
+            "AsofJoin: type={}, on=[{}], match_condition={} {} {}",
+            self.join_type, on, self.left_time, self.op, self.right_time
+        )?;
+        if let Some(tolerance) = self.tolerance {
+            write!(f, ", tolerance={tolerance}ns")?;
+        }
+        Ok(())
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(inputs.len(), 2, "input size inconsistent");
+        assert_eq!(
+            exprs.len(),
+            self.on.len() * 2 + 2,
+            "expression size inconsistent"
+        );
+
+        let on_len = self.on.len();
+        let on = exprs[..on_len]
+            .iter()
+            .cloned()
+            .zip(exprs[on_len..on_len * 2].iter().cloned())
+            .collect();
+        let left = Arc::new(inputs[0].clone());
+        let right = Arc::new(inputs[1].clone());
+        let schema = build_join_schema(left.schema(), right.schema(), &self.join_type)
+            .map(Arc::new)
+            .unwrap_or_else(|_| self.schema.clone());
+
+        Self {
+            left,
+            right,
+            on,
+            left_time: exprs[on_len * 2].clone(),
+            op: self.op,
+            right_time: exprs[on_len * 2 + 1].clone(),
+            tolerance: self.tolerance,
+            join_type: self.join_type,
+            schema,
+        }
+    }
+
+    fn name(&self) -> &str {
+        "AsofJoin"
+    }
+}
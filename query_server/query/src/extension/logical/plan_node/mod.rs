@@ -6,6 +6,7 @@ use datafusion::prelude::Expr;
 
 use crate::extension::expr::expr_rewriter::ExprReplacer;
 
+pub mod asof_join;
 pub mod expand;
 pub mod stream_scan;
 pub mod table_writer;
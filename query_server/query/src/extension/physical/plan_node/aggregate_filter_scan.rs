@@ -16,6 +16,7 @@ use datafusion::physical_plan::{
 use models::predicate::domain::{PredicateRef, PushedAggregateFunction};
 use models::predicate::PlacedSplit;
 use models::schema::tskv_table_schema::TskvTableSchemaRef;
+use spi::query::session::ScanQuota;
 use trace::span_ext::SpanExt;
 use trace::{debug, Span, SpanContext};
 use tskv::reader::QueryOption;
@@ -53,6 +54,10 @@ impl AggregateFilterTskvExec {
             metrics: ExecutionPlanMetricsSet::new(),
         }
     }
+
+    pub fn splits(&self) -> &[PlacedSplit] {
+        &self.splits
+    }
 }
 
 impl ExecutionPlan for AggregateFilterTskvExec {
@@ -105,8 +110,9 @@ impl ExecutionPlan for AggregateFilterTskvExec {
         };
         debug!("Split of partition: {:?}", split);
 
-        let metrics = TableScanMetrics::new(&self.metrics, partition);
-        let query_opt = QueryOption::new(
+        let scan_quota = context.session_config().get_extension::<ScanQuota>();
+        let metrics = TableScanMetrics::new(&self.metrics, partition).with_scan_quota(scan_quota);
+        let mut query_opt = QueryOption::new(
             100_usize,
             split,
             Some(self.pushed_aggs.clone()),
@@ -114,6 +120,7 @@ impl ExecutionPlan for AggregateFilterTskvExec {
             self.table_schema.clone(),
             self.table_schema.meta(),
         );
+        query_opt.scan_stats = metrics.scan_stats().clone();
 
         let span_ctx = context.session_config().get_extension::<SpanContext>();
         let span = Span::from_context(
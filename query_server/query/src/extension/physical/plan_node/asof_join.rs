@@ -0,0 +1,553 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{new_null_array, Array, ArrayRef, Int64Array, UInt32Array};
+use datafusion::arrow::compute::{cast, concat_batches, take};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::arrow::row::{RowConverter, SortField};
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::TaskContext;
+use datafusion::execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion::logical_expr::{JoinType, Operator};
+use datafusion::physical_expr::PhysicalSortExpr;
+use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
+    SendableRecordBatchStream, Statistics,
+};
+use futures::{StreamExt, TryStreamExt};
+use trace::debug;
+
+/// Execution plan for an AsofJoin,
+/// see [`AsofJoinNode`](crate::extension::logical::plan_node::asof_join::AsofJoinNode).
+///
+/// The right input is collected and its rows are grouped by the join keys and sorted by
+/// time (skipped for the time ordered output of tskv scans), then the closest right
+/// row of every left row is found by binary search while streaming the left input.
+///
+/// Both inputs are hash partitioned by the join keys, so every partition only collects its
+/// share of the right input. The collected rows are accounted in the memory pool of the query.
+#[derive(Debug)]
+pub struct AsofJoinExec {
+    left: Arc<dyn ExecutionPlan>,
+    right: Arc<dyn ExecutionPlan>,
+    on: Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+    left_time: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    right_time: Arc<dyn PhysicalExpr>,
+    tolerance: Option<i64>,
+    join_type: JoinType,
+    schema: SchemaRef,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl AsofJoinExec {
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        on: Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+        left_time: Arc<dyn PhysicalExpr>,
+        op: Operator,
+        right_time: Arc<dyn PhysicalExpr>,
+        tolerance: Option<i64>,
+        join_type: JoinType,
+    ) -> Result<Self> {
+        if !matches!(join_type, JoinType::Inner | JoinType::Left) {
+            return Err(DataFusionError::Plan(format!(
+                "AsofJoinExec does not support {join_type} join"
+            )));
+        }
+
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let right_nullable = join_type == JoinType::Left;
+        let fields = left_schema
+            .fields()
+            .iter()
+            .map(|f| f.as_ref().clone())
+            .chain(right_schema.fields().iter().map(|f| {
+                let nullable = f.is_nullable() || right_nullable;
+                Field::new(f.name(), f.data_type().clone(), nullable)
+                    .with_metadata(f.metadata().clone())
+            }))
+            .collect::<Vec<_>>();
+        let schema = Arc::new(Schema::new(fields));
+
+        Ok(Self {
+            left,
+            right,
+            on,
+            left_time,
+            op,
+            right_time,
+            tolerance,
+            join_type,
+            schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+impl ExecutionPlan for AsofJoinExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        if self.on.is_empty() {
+            Partitioning::UnknownPartitioning(1)
+        } else {
+            self.left.output_partitioning()
+        }
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.on.is_empty() {
+            vec![Distribution::SinglePartition, Distribution::SinglePartition]
+        } else {
+            let (left, right) = self.on.iter().cloned().unzip();
+            vec![
+                Distribution::HashPartitioned(left),
+                Distribution::HashPartitioned(right),
+            ]
+        }
+    }
+
+    /// The left rows are output in their input order, followed by the right columns
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.left.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true, false]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.left.clone(), self.right.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(AsofJoinExec::try_new(
+            children[0].clone(),
+            children[1].clone(),
+            self.on.clone(),
+            self.left_time.clone(),
+            self.op,
+            self.right_time.clone(),
+            self.tolerance,
+            self.join_type,
+        )?))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!(
+            "Start AsofJoinExec::execute for partition {} of context session_id {} and task_id {:?}",
+            partition,
+            context.session_id(),
+            context.task_id()
+        );
+
+        let left = self.left.execute(partition, context.clone())?;
+        let right = self.right.execute(partition, context.clone())?;
+        let reservation = MemoryConsumer::new(format!("AsofJoinExec[{partition}]"))
+            .register(context.memory_pool());
+        let right_keys = self.on.iter().map(|(_, r)| r.clone()).collect::<Vec<_>>();
+        let right_time = self.right_time.clone();
+
+        let mut probe = AsofProbe {
+            keys: self.on.iter().map(|(l, _)| l.clone()).collect(),
+            time: self.left_time.clone(),
+            op: self.op,
+            tolerance: self.tolerance,
+            join_type: self.join_type,
+            schema: self.schema.clone(),
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        };
+
+        let stream = futures::stream::once(async move {
+            let mut index =
+                AsofIndex::try_new(right, &right_keys, &right_time, reservation).await?;
+            let stream = left.map(move |batch| {
+                let batch = batch?;
+                let output = probe.join(&batch, &mut index)?;
+                probe.baseline_metrics.record_output(output.num_rows());
+                Ok(output)
+            });
+            Ok::<_, DataFusionError>(stream)
+        })
+        .try_flatten();
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            stream,
+        )))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let on = self
+                    .on
+                    .iter()
+                    .map(|(l, r)| format!("({l}, {r})"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "AsofJoinExec: join_type={:?}, on=[{}], match_condition={} {} {}",
+                    self.join_type, on, self.left_time, self.op, self.right_time
+                )?;
+                if let Some(tolerance) = self.tolerance {
+                    write!(f, ", tolerance={tolerance}ns")?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// The rows of the right input grouped by the join keys
+struct AsofIndex {
+    batch: RecordBatch,
+    converter: Option<RowConverter>,
+    /// (time, row index) of each group, sorted by time
+    groups: HashMap<Vec<u8>, Vec<(i64, u32)>>,
+    /// The memory of the collected rows and the groups, released when the index is dropped
+    _reservation: MemoryReservation,
+}
+
+impl AsofIndex {
+    async fn try_new(
+        mut input: SendableRecordBatchStream,
+        keys: &[Arc<dyn PhysicalExpr>],
+        time: &Arc<dyn PhysicalExpr>,
+        mut reservation: MemoryReservation,
+    ) -> Result<Self> {
+        let schema = input.schema();
+        let mut batches = vec![];
+        while let Some(batch) = input.try_next().await? {
+            reservation.try_grow(batch.get_array_memory_size())?;
+            batches.push(batch);
+        }
+        // the batches are copied once more while they are concatenated
+        let batch = concat_batches(&schema, &batches)?;
+        reservation.try_grow(batch.get_array_memory_size())?;
+        drop(batches);
+
+        let mut converter = new_converter(keys, &schema)?;
+        let row_keys = evaluate_keys(&mut converter, keys, &batch)?;
+        let times = evaluate_time(time, &batch)?;
+
+        let mut groups: HashMap<Vec<u8>, Vec<(i64, u32)>> = HashMap::new();
+        for (idx, key) in row_keys.into_iter().enumerate() {
+            if let (Some(key), false) = (key, times.is_null(idx)) {
+                groups
+                    .entry(key)
+                    .or_default()
+                    .push((times.value(idx), idx as u32));
+            }
+        }
+        let mut groups_size = 0;
+        for (key, group) in groups.iter_mut() {
+            if !group.windows(2).all(|w| w[0].0 <= w[1].0) {
+                group.sort_by_key(|(time, _)| *time);
+            }
+            groups_size += key.capacity() + group.capacity() * size_of::<(i64, u32)>();
+        }
+        reservation.try_resize(batch.get_array_memory_size() + groups_size)?;
+
+        Ok(Self {
+            batch,
+            converter,
+            groups,
+            _reservation: reservation,
+        })
+    }
+}
+
+struct AsofProbe {
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    time: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    tolerance: Option<i64>,
+    join_type: JoinType,
+    schema: SchemaRef,
+    baseline_metrics: BaselineMetrics,
+}
+
+impl AsofProbe {
+    fn join(&self, batch: &RecordBatch, index: &mut AsofIndex) -> Result<RecordBatch> {
+        let _timer = self.baseline_metrics.elapsed_compute().timer();
+
+        let row_keys = evaluate_keys(&mut index.converter, &self.keys, batch)?;
+        let times = evaluate_time(&self.time, batch)?;
+
+        let mut left_indices = Vec::with_capacity(batch.num_rows());
+        let mut right_indices = Vec::with_capacity(batch.num_rows());
+        for (idx, key) in row_keys.into_iter().enumerate() {
+            let matched = match (key, times.is_null(idx)) {
+                (Some(key), false) => index.groups.get(&key).and_then(|group| {
+                    find_closest(group, times.value(idx), self.op, self.tolerance)
+                }),
+                _ => None,
+            };
+            if matched.is_some() || self.join_type == JoinType::Left {
+                left_indices.push(idx as u32);
+                right_indices.push(matched);
+            }
+        }
+
+        let left_indices = UInt32Array::from(left_indices);
+        let right_indices = UInt32Array::from(right_indices);
+        let right_len = index.batch.num_rows();
+
+        let mut columns = Vec::with_capacity(self.schema.fields().len());
+        for column in batch.columns() {
+            columns.push(take(column.as_ref(), &left_indices, None)?);
+        }
+        for column in index.batch.columns() {
+            if right_len == 0 {
+                columns.push(new_null_array(column.data_type(), right_indices.len()));
+            } else {
+                columns.push(take(column.as_ref(), &right_indices, None)?);
+            }
+        }
+
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+fn new_converter(keys: &[Arc<dyn PhysicalExpr>], schema: &Schema) -> Result<Option<RowConverter>> {
+    if keys.is_empty() {
+        return Ok(None);
+    }
+    let fields = keys
+        .iter()
+        .map(|k| Ok(SortField::new(k.data_type(schema)?)))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(RowConverter::new(fields)?))
+}
+
+/// Returns the encoded join keys of every row, None if any of the keys is null
+fn evaluate_keys(
+    converter: &mut Option<RowConverter>,
+    keys: &[Arc<dyn PhysicalExpr>],
+    batch: &RecordBatch,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let converter = match converter {
+        Some(converter) => converter,
+        None => return Ok(vec![Some(vec![]); batch.num_rows()]),
+    };
+
+    let arrays = keys
+        .iter()
+        .map(|k| k.evaluate(batch).map(|v| v.into_array(batch.num_rows())))
+        .collect::<Result<Vec<ArrayRef>>>()?;
+    let rows = converter.convert_columns(&arrays)?;
+
+    Ok((0..batch.num_rows())
+        .map(|idx| {
+            if arrays.iter().any(|a| a.is_null(idx)) {
+                None
+            } else {
+                Some(rows.row(idx).as_ref().to_vec())
+            }
+        })
+        .collect())
+}
+
+fn evaluate_time(time: &Arc<dyn PhysicalExpr>, batch: &RecordBatch) -> Result<Int64Array> {
+    let mut array = time.evaluate(batch)?.into_array(batch.num_rows());
+    // Compare timestamps of different units in nanoseconds, the unit of the tolerance
+    if let DataType::Timestamp(unit, tz) = array.data_type() {
+        if *unit != TimeUnit::Nanosecond {
+            let data_type = DataType::Timestamp(TimeUnit::Nanosecond, tz.clone());
+            array = cast(&array, &data_type)?;
+        }
+    }
+    let array = cast(&array, &DataType::Int64)?;
+    array
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .cloned()
+        .ok_or_else(|| {
+            DataFusionError::Internal("Failed to cast the time of ASOF JOIN to int64".to_string())
+        })
+}
+
+/// Finds the row of `group` closest to `time` that satisfies `time op row time`
+fn find_closest(
+    group: &[(i64, u32)],
+    time: i64,
+    op: Operator,
+    tolerance: Option<i64>,
+) -> Option<u32> {
+    let before = |inclusive: bool| {
+        let idx = group.partition_point(|(t, _)| if inclusive { *t <= time } else { *t < time });
+        idx.checked_sub(1).map(|idx| group[idx])
+    };
+    let after = |inclusive: bool| {
+        let idx = group.partition_point(|(t, _)| if inclusive { *t < time } else { *t <= time });
+        group.get(idx).copied()
+    };
+    let distance = |t: i64| (time as i128 - t as i128).unsigned_abs();
+
+    let found = match op {
+        Operator::GtEq => before(true),
+        Operator::Gt => before(false),
+        Operator::LtEq => after(true),
+        Operator::Lt => after(false),
+        Operator::Eq => match (before(true), after(true)) {
+            (Some(b), Some(a)) if distance(a.0) < distance(b.0) => Some(a),
+            (Some(b), _) => Some(b),
+            (None, a) => a,
+        },
+        _ => None,
+    }?;
+
+    match tolerance {
+        Some(tolerance) if distance(found.0) > tolerance as u128 => None,
+        _ => Some(found.1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::arrow::array::{Int64Array, StringArray, TimestampNanosecondArray};
+    use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+    use datafusion::arrow::record_batch::RecordBatch;
+    use datafusion::error::DataFusionError;
+    use datafusion::execution::context::SessionState;
+    use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use datafusion::logical_expr::{JoinType, Operator};
+    use datafusion::physical_plan::expressions::Column;
+    use datafusion::physical_plan::memory::MemoryExec;
+    use datafusion::physical_plan::{collect, ExecutionPlan};
+    use datafusion::prelude::{SessionConfig, SessionContext};
+
+    use super::{find_closest, AsofJoinExec};
+
+    #[test]
+    fn test_find_closest() {
+        let group = vec![(10, 0), (20, 1), (30, 2)];
+        assert_eq!(find_closest(&group, 20, Operator::GtEq, None), Some(1));
+        assert_eq!(find_closest(&group, 20, Operator::Gt, None), Some(0));
+        assert_eq!(find_closest(&group, 20, Operator::LtEq, None), Some(1));
+        assert_eq!(find_closest(&group, 20, Operator::Lt, None), Some(2));
+        assert_eq!(find_closest(&group, 5, Operator::GtEq, None), None);
+        assert_eq!(find_closest(&group, 35, Operator::LtEq, None), None);
+        assert_eq!(find_closest(&group, 24, Operator::Eq, None), Some(1));
+        assert_eq!(find_closest(&group, 26, Operator::Eq, None), Some(2));
+        assert_eq!(find_closest(&group, 25, Operator::Eq, None), Some(1));
+        assert_eq!(find_closest(&group, 26, Operator::GtEq, Some(5)), None);
+        assert_eq!(find_closest(&group, 26, Operator::Eq, Some(5)), Some(2));
+    }
+
+    fn memory_exec(tags: Vec<&str>, times: Vec<i64>, values: Vec<i64>) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("tag", DataType::Utf8, true),
+            Field::new("value", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(times)),
+                Arc::new(StringArray::from(tags)),
+                Arc::new(Int64Array::from(values)),
+            ],
+        )
+        .unwrap();
+        Arc::new(MemoryExec::try_new(&[vec![batch]], schema, None).unwrap())
+    }
+
+    fn asof_join_exec() -> AsofJoinExec {
+        let left = memory_exec(
+            vec!["a", "a", "b", "c"],
+            vec![10, 25, 25, 25],
+            vec![1, 2, 3, 4],
+        );
+        let right = memory_exec(
+            vec!["a", "a", "b", "b"],
+            vec![9, 20, 10, 30],
+            vec![10, 20, 30, 40],
+        );
+
+        AsofJoinExec::try_new(
+            left,
+            right,
+            vec![(
+                Arc::new(Column::new("tag", 1)),
+                Arc::new(Column::new("tag", 1)),
+            )],
+            Arc::new(Column::new("time", 0)),
+            Operator::GtEq,
+            Arc::new(Column::new("time", 0)),
+            None,
+            JoinType::Left,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_exec() {
+        let join = asof_join_exec();
+
+        let ctx = SessionContext::new();
+        let batches = collect(Arc::new(join), ctx.task_ctx()).await.unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_columns(), 6);
+
+        let values = batch
+            .column(5)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(
+            values.iter().collect::<Vec<_>>(),
+            vec![Some(10), Some(20), Some(30), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_asof_join_exec_memory_limit() {
+        let join = asof_join_exec();
+
+        let runtime = RuntimeEnv::new(RuntimeConfig::new().with_memory_limit(64, 1.0)).unwrap();
+        let state = SessionState::with_config_rt(SessionConfig::new(), Arc::new(runtime));
+        let ctx = SessionContext::with_state(state);
+        let err = collect(Arc::new(join), ctx.task_ctx()).await.unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+    }
+}
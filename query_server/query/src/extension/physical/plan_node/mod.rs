@@ -1,10 +1,15 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::task::Poll;
 
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::DataFusionError;
 use datafusion::physical_plan::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, Time};
+use spi::query::session::ScanQuota;
+use tskv::reader::ScanStats;
 
 pub mod aggregate_filter_scan;
+pub mod asof_join;
 pub mod assert;
 pub mod expand;
 pub mod state_restore;
@@ -22,6 +27,13 @@ pub mod watermark;
 #[derive(Debug)]
 pub struct TableScanMetrics {
     baseline_metrics: BaselineMetrics,
+    scan_stats: ScanStats,
+    scan_stats_recorded: AtomicBool,
+    scan_quota: Option<Arc<ScanQuota>>,
+    /// The bytes read by the scan already charged to the scan quota
+    charged_bytes: AtomicUsize,
+    metrics: ExecutionPlanMetricsSet,
+    partition: usize,
 }
 
 impl TableScanMetrics {
@@ -29,7 +41,52 @@ impl TableScanMetrics {
     pub fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
         let baseline_metrics = BaselineMetrics::new(metrics, partition);
 
-        Self { baseline_metrics }
+        Self {
+            baseline_metrics,
+            scan_stats: ScanStats::new(),
+            scan_stats_recorded: AtomicBool::new(false),
+            scan_quota: None,
+            charged_bytes: AtomicUsize::new(0),
+            metrics: metrics.clone(),
+            partition,
+        }
+    }
+
+    /// The scan fails once the query has read more bytes than the quota
+    pub fn with_scan_quota(mut self, scan_quota: Option<Arc<ScanQuota>>) -> Self {
+        self.scan_quota = scan_quota;
+        self
+    }
+
+    /// Charges the bytes of the pages read from the tsm files by tskv since the last call,
+    /// including those read by the remote nodes, to the scan quota of the query.
+    pub fn charge_read_bytes(&self) -> Result<(), DataFusionError> {
+        let read = self.scan_stats.bytes_decoded().value();
+        let charged = self.charged_bytes.swap(read, Ordering::SeqCst);
+        self.charge_bytes(read.saturating_sub(charged))
+    }
+
+    /// Charges the bytes read by the scan to the scan quota of the query
+    pub fn charge_bytes(&self, bytes: usize) -> Result<(), DataFusionError> {
+        match &self.scan_quota {
+            Some(quota) => quota
+                .consume(bytes as u64)
+                .map_err(|err| DataFusionError::External(Box::new(err))),
+            None => Ok(()),
+        }
+    }
+
+    /// return the storage statistics collected by the scan
+    pub fn scan_stats(&self) -> &ScanStats {
+        &self.scan_stats
+    }
+
+    /// Records the storage statistics of the scan, labeled by the scanned vnode.
+    /// Called after the scan is finished or cancelled, only the first call records.
+    pub fn record_scan_stats(&self) {
+        if !self.scan_stats_recorded.swap(true, Ordering::SeqCst) {
+            self.scan_stats.register(&self.metrics, self.partition)
+        }
     }
 
     /// return the metric for cpu time spend in this operator
@@ -22,6 +22,7 @@ use models::predicate::domain::PredicateRef;
 use models::predicate::PlacedSplit;
 use models::schema::tskv_table_schema::{TskvTableSchema, TskvTableSchemaRef};
 use snafu::ResultExt;
+use spi::query::session::ScanQuota;
 use spi::{CommonSnafu, CoordinatorSnafu, QueryError};
 use trace::span_ext::SpanExt;
 use trace::{debug, Span, SpanContext};
@@ -65,6 +66,10 @@ impl TagScanExec {
     pub fn predicate(&self) -> PredicateRef {
         self.predicate.clone()
     }
+
+    pub fn splits(&self) -> &[PlacedSplit] {
+        &self.splits
+    }
 }
 
 impl ExecutionPlan for TagScanExec {
@@ -123,7 +128,8 @@ impl ExecutionPlan for TagScanExec {
 
         let batch_size = context.session_config().batch_size();
 
-        let metrics = TableScanMetrics::new(&self.metrics, partition);
+        let scan_quota = context.session_config().get_extension::<ScanQuota>();
+        let metrics = TableScanMetrics::new(&self.metrics, partition).with_scan_quota(scan_quota);
         let span_ctx = context.session_config().get_extension::<SpanContext>();
 
         let tag_scan_stream = TagScanStream::new(
@@ -294,10 +300,17 @@ impl Stream for TagScanStream {
         let metrics = &this.metrics;
         let timer = metrics.elapsed_compute().timer();
 
-        let result = this
-            .stream
-            .poll_next_unpin(cx)
-            .map_err(|err| DataFusionError::External(Box::new(err)));
+        let result = match this.stream.poll_next_unpin(cx) {
+            // the series keys read from the index are not counted by the scan statistics,
+            // the tag values returned are charged instead
+            Poll::Ready(Some(Ok(batch))) => {
+                match metrics.charge_bytes(batch.get_array_memory_size()) {
+                    Ok(()) => Poll::Ready(Some(Ok(batch))),
+                    Err(err) => Poll::Ready(Some(Err(err))),
+                }
+            }
+            poll => poll.map_err(|err| DataFusionError::External(Box::new(err))),
+        };
 
         timer.done();
         metrics.record_poll(result)
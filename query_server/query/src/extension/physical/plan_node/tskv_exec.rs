@@ -25,6 +25,7 @@ use models::schema::tskv_table_schema::{
 };
 use models::schema::TIME_FIELD_NAME;
 use snafu::ResultExt;
+use spi::query::session::ScanQuota;
 use spi::{CommonSnafu, CoordinatorSnafu, QueryResult};
 use trace::span_ext::SpanExt;
 use trace::{debug, Span, SpanContext};
@@ -68,6 +69,10 @@ impl TskvExec {
     pub fn filter(&self) -> PredicateRef {
         self.filter.clone()
     }
+
+    pub fn splits(&self) -> &[PlacedSplit] {
+        &self.splits
+    }
 }
 
 impl ExecutionPlan for TskvExec {
@@ -126,7 +131,8 @@ impl ExecutionPlan for TskvExec {
 
         let batch_size = context.session_config().batch_size();
 
-        let metrics = TableScanMetrics::new(&self.metrics, partition);
+        let scan_quota = context.session_config().get_extension::<ScanQuota>();
+        let metrics = TableScanMetrics::new(&self.metrics, partition).with_scan_quota(scan_quota);
 
         let span_ctx = context.session_config().get_extension::<SpanContext>();
 
@@ -268,7 +274,7 @@ impl TableScanStream {
 
         let remain = split.limit();
 
-        let option = QueryOption::new(
+        let mut option = QueryOption::new(
             batch_size,
             split,
             None,
@@ -276,6 +282,7 @@ impl TableScanStream {
             proj_table_schema.into(),
             table_schema.meta(),
         );
+        option.scan_stats = metrics.scan_stats().clone();
 
         let span_ctx = span.context();
         let iterator = coord
@@ -326,15 +333,21 @@ impl Stream for TableScanStream {
         let timer = metrics.elapsed_compute().timer();
 
         let result = match this.iterator.poll_next_unpin(cx) {
-            Poll::Ready(Some(Ok(batch))) => {
-                Poll::Ready(limit_record_batch(this.remain.as_mut(), batch).map(Ok))
-            }
+            Poll::Ready(Some(Ok(batch))) => match metrics.charge_read_bytes() {
+                Ok(()) => Poll::Ready(limit_record_batch(this.remain.as_mut(), batch).map(Ok)),
+                Err(err) => Poll::Ready(Some(Err(err))),
+            },
             Poll::Ready(Some(Err(e))) => {
                 Poll::Ready(Some(Err(DataFusionError::External(Box::new(e)))))
             }
             Poll::Ready(None) => {
                 metrics.done();
-                Poll::Ready(None)
+                metrics.record_scan_stats();
+                // the statistics of the remote nodes may arrive with the end of their streams
+                match metrics.charge_read_bytes() {
+                    Ok(()) => Poll::Ready(None),
+                    Err(err) => Poll::Ready(Some(Err(err))),
+                }
             }
             Poll::Pending => Poll::Pending,
         };
@@ -349,6 +362,14 @@ impl Stream for TableScanStream {
     }
 }
 
+impl Drop for TableScanStream {
+    fn drop(&mut self) {
+        // the remote stream records its rpc time when dropped, drop it first
+        self.iterator = Box::pin(futures::stream::empty());
+        self.metrics.record_scan_stats();
+    }
+}
+
 impl RecordBatchStream for TableScanStream {
     fn schema(&self) -> SchemaRef {
         self.proj_schema.clone()
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::error::Result;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_planner::{ExtensionPlanner, PhysicalPlanner};
+
+use crate::extension::logical::plan_node::asof_join::AsofJoinNode;
+use crate::extension::physical::plan_node::asof_join::AsofJoinExec;
+
+/// Physical planner for AsofJoin nodes
+pub struct AsofJoinPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for AsofJoinPlanner {
+    async fn plan_extension(
+        &self,
+        planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        session_state: &SessionState,
+    ) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+        let node = match node.as_any().downcast_ref::<AsofJoinNode>() {
+            Some(node) => node,
+            None => return Ok(None),
+        };
+        assert_eq!(2, logical_inputs.len());
+        assert_eq!(2, physical_inputs.len());
+
+        let left = physical_inputs[0].clone();
+        let right = physical_inputs[1].clone();
+        let left_df_schema = logical_inputs[0].schema();
+        let right_df_schema = logical_inputs[1].schema();
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+
+        let on = node
+            .on
+            .iter()
+            .map(|(l, r)| {
+                Ok((
+                    planner.create_physical_expr(l, left_df_schema, &left_schema, session_state)?,
+                    planner.create_physical_expr(
+                        r,
+                        right_df_schema,
+                        &right_schema,
+                        session_state,
+                    )?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let left_time = planner.create_physical_expr(
+            &node.left_time,
+            left_df_schema,
+            &left_schema,
+            session_state,
+        )?;
+        let right_time = planner.create_physical_expr(
+            &node.right_time,
+            right_df_schema,
+            &right_schema,
+            session_state,
+        )?;
+
+        Ok(Some(Arc::new(AsofJoinExec::try_new(
+            left,
+            right,
+            on,
+            left_time,
+            node.op,
+            right_time,
+            node.tolerance,
+            node.join_type,
+        )?)))
+    }
+}
@@ -1,4 +1,5 @@
 //! logical paln to physical plan transform rule
+pub mod asof_join;
 pub mod expand;
 pub mod stream_scan;
 pub mod table_writer;
@@ -9,32 +9,37 @@ use meta::error::MetaError;
 use models::auth::auth_cache::{AuthCache, AuthCacheKey};
 use models::auth::user::{User, UserInfo};
 use models::auth::AuthError;
-use models::oid::Oid;
-use models::schema::query_info::QueryId;
+use models::oid::{Identifier, Oid};
+use models::schema::query_info::{QueryId, QueryInfo};
 use models::schema::DEFAULT_CATALOG;
 use snafu::ResultExt;
+use spi::query::async_query::AsyncQueryStatus;
 use spi::query::auth::AccessControlRef;
 use spi::query::datasource::stream::checker::StreamCheckerManager;
 use spi::query::datasource::stream::StreamProviderManager;
 use spi::query::dispatcher::QueryDispatcher;
-use spi::query::execution::QueryStateMachineRef;
+use spi::query::execution::{Output, QueryStateMachineRef};
 use spi::query::logical_planner::Plan;
 use spi::query::session::SessionCtxFactory;
 use spi::server::dbms::DatabaseManagerSystem;
 use spi::service::protocol::{Query, QueryHandle};
-use spi::{AuthSnafu, MetaSnafu, QueryResult};
+use spi::{AuthSnafu, MetaSnafu, QueryError, QueryResult};
 use trace::{debug, SpanContext};
 use tskv::kv_option::Options;
 
 use crate::auth::auth_control::{AccessControlImpl, AccessControlNoCheck};
+use crate::auth::jwt::JwtAuthenticator;
+use crate::auth::ldap::LdapAuthenticator;
 use crate::data_source::split::SplitManager;
 use crate::data_source::stream::tskv::factory::{TskvStreamProviderFactory, TSKV_STREAM_PROVIDER};
+use crate::dispatcher::async_query::{AsyncQuery, AsyncQueryManager};
+use crate::dispatcher::audit_log::AuditLog;
 use crate::dispatcher::manager::SimpleQueryDispatcherBuilder;
 use crate::dispatcher::persister::MetaQueryPersister;
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::execution::factory::SqlQueryExecutionFactory;
 use crate::execution::scheduler::local::LocalScheduler;
-use crate::extension::expr::{load_all_functions, register_session_udfs};
+use crate::extension::expr::{load_all_functions, register_mask_hash, register_session_udfs};
 use crate::extension::variable::load_all_system_vars;
 use crate::function::simple_func_manager::SimpleFunctionMetadataManager;
 use crate::metadata::BaseTableProvider;
@@ -52,6 +57,7 @@ pub struct Cnosdbms<D: QueryDispatcher> {
     // query dispatcher & query execution
     query_dispatcher: Arc<D>,
     auth_cache: Arc<AuthCache<AuthCacheKey, User>>,
+    async_queries: Arc<AsyncQueryManager>,
 }
 
 #[async_trait]
@@ -67,7 +73,11 @@ where
         let auth_cache_key = AuthCacheKey::new(user_info, tenant_name);
         if let Some(user) = self.auth_cache.get(&auth_cache_key) {
             debug!("Hit auth cache for user: {}", user.desc().name());
-            return Ok(user);
+            return self
+                .access_control
+                .recheck(user, tenant_name)
+                .await
+                .context(AuthSnafu);
         }
 
         let user = self
@@ -79,6 +89,21 @@ where
         Ok(user)
     }
 
+    async fn authenticate_token(&self, token: &str, tenant_name: &str) -> QueryResult<User> {
+        self.access_control
+            .token_check(token, tenant_name)
+            .await
+            .context(AuthSnafu)
+    }
+
+    async fn authenticate_jwt(&self, token: &str, tenant_name: &str) -> QueryResult<User> {
+        // Not cached, a cached user could outlive the expiration of the token
+        self.access_control
+            .jwt_check(token, tenant_name)
+            .await
+            .context(AuthSnafu)
+    }
+
     async fn execute(
         &self,
         query: &Query,
@@ -172,9 +197,80 @@ where
     fn cancel(&self, query_id: &QueryId) {
         self.query_dispatcher.cancel_query(query_id);
     }
+
+    async fn execute_async(
+        &self,
+        query: &Query,
+        span_context: Option<&SpanContext>,
+    ) -> QueryResult<QueryId> {
+        let tenant_id = self
+            .get_tenant_id(query.context().tenant())
+            .await
+            .context(AuthSnafu)?;
+        let query_id = self.query_dispatcher.create_query_id();
+
+        let ctx = query.context();
+        let info = QueryInfo::new(
+            query_id,
+            query.content().to_string(),
+            tenant_id,
+            ctx.tenant().to_string(),
+            ctx.database().to_string(),
+            ctx.user().clone(),
+            self.async_queries.node_id(),
+        );
+        let async_query = self.async_queries.admit(info).await?;
+
+        let result = match self
+            .query_dispatcher
+            .execute_query(tenant_id, query_id, query, span_context)
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                self.async_queries.remove(&query_id).await;
+                return Err(err);
+            }
+        };
+        self.async_queries.submit(async_query, result);
+
+        Ok(query_id)
+    }
+
+    fn async_query_status(&self, user: &User, query_id: &QueryId) -> QueryResult<AsyncQueryStatus> {
+        let query = self.visible_async_query(user, query_id)?;
+        Ok(self.async_queries.status(&query))
+    }
+
+    async fn fetch_async_query_result(
+        &self,
+        user: &User,
+        query_id: &QueryId,
+        page: usize,
+    ) -> QueryResult<Output> {
+        self.visible_async_query(user, query_id)?;
+        self.async_queries.fetch(*query_id, page).await
+    }
+
+    async fn remove_async_query(&self, user: &User, query_id: &QueryId) -> QueryResult<()> {
+        self.visible_async_query(user, query_id)?;
+        self.query_dispatcher.cancel_query(query_id);
+        self.async_queries.remove(query_id).await;
+        Ok(())
+    }
 }
 
 impl<D: QueryDispatcher> Cnosdbms<D> {
+    /// An async query is visible to the user who submitted it and to admins.
+    fn visible_async_query(&self, user: &User, query_id: &QueryId) -> QueryResult<Arc<AsyncQuery>> {
+        self.async_queries
+            .query(query_id)
+            .filter(|e| user.is_admin() || e.info().user_id() == *user.desc().id())
+            .ok_or(QueryError::QueryNotFound {
+                query_id: *query_id,
+            })
+    }
+
     pub(crate) async fn get_tenant_id(
         &self,
         tenant_name: &str,
@@ -195,6 +291,9 @@ pub async fn make_cnosdbms(
     // init Function Manager
     let mut func_manager = SimpleFunctionMetadataManager::default();
     load_all_functions(&mut func_manager)?;
+    if let Some(masking_secret) = &coord.get_config().security.masking_secret {
+        register_mask_hash(&mut func_manager, masking_secret)?;
+    }
     // init System Variable Manager
     let mut var_manager = SimpleSystemVarManager::default();
     load_all_system_vars(&mut var_manager, coord.clone())?;
@@ -229,11 +328,16 @@ pub async fn make_cnosdbms(
     stream_checker_manager
         .register_stream_checker(TSKV_STREAM_PROVIDER, tskv_stream_provider_factory)?;
 
+    let async_queries = Arc::new(AsyncQueryManager::try_new(&options.query, coord.node_id())?);
+    async_queries.start_expire_task();
+
     let query_persister = Arc::new(MetaQueryPersister::new(coord.meta_manager()));
     let query_tracker = Arc::new(QueryTracker::new(
         options.query.max_server_connections as usize,
         query_persister,
         coord.clone(),
+        async_queries.clone(),
+        Arc::new(AuditLog::new(options.query.audit.clone())),
     ));
 
     let query_execution_factory = Arc::new(SqlQueryExecutionFactory::new(
@@ -282,7 +386,17 @@ pub async fn make_cnosdbms(
 
     let mut builder = CnosdbmsBuilder::default();
 
-    let access_control_no_check = AccessControlNoCheck::new(meta_manager);
+    let mut access_control_no_check = AccessControlNoCheck::new(meta_manager);
+    if let Some(jwt) = &options.query.jwt {
+        debug!("build JWT authenticator");
+        access_control_no_check =
+            access_control_no_check.with_jwt(Arc::new(JwtAuthenticator::new(jwt.clone())));
+    }
+    if let Some(ldap) = &options.query.ldap {
+        debug!("build LDAP authenticator");
+        access_control_no_check =
+            access_control_no_check.with_ldap(Arc::new(LdapAuthenticator::new(ldap.clone())));
+    }
     if options.query.auth_enabled {
         debug!("build access control");
         builder.access_control(Arc::new(AccessControlImpl::new(access_control_no_check)))
@@ -294,6 +408,7 @@ pub async fn make_cnosdbms(
     let db_server = builder
         .query_dispatcher(query_dispatcher)
         .auth_cache(auth_cache.clone())
+        .async_queries(async_queries)
         .build()
         .expect("build db server");
 
@@ -93,6 +93,14 @@ impl TableHandleProvider for BaseTableProvider {
                     .create_provider(self.meta_client.clone(), table.as_ref())
                     .map_err(|e| DataFusionError::External(Box::new(e)))?
                     .into(),
+                TableSchema::ViewTableSchema(view) => {
+                    // Views are expanded by the planner, see `MetadataProvider::build_view_table`
+                    return Err(DataFusionError::Plan(format!(
+                        "View {}.{} can not be used as a table here",
+                        view.db(),
+                        view.name()
+                    )));
+                }
             },
             None => {
                 return Err(DataFusionError::External(Box::new(
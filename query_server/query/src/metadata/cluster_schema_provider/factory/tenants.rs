@@ -67,7 +67,7 @@ impl TableProvider for ClusterSchemaTenantsTable {
         let mut builder = ClusterSchemaTenantsBuilder::default();
 
         // Only visible to admin
-        if self.user.desc().is_admin() {
+        if self.user.is_admin() {
             let tenants =
                 self.metadata.tenants().await.map_err(|e| {
                     DataFusionError::Internal(format!("failed to list tenant {}", e))
@@ -66,7 +66,7 @@ impl TableProvider for ClusterSchemaUsersTable {
         let mut builder = ClusterSchemaUsersBuilder::default();
 
         // Only visible to admin
-        if self.user.desc().is_admin() {
+        if self.user.is_admin() {
             let users =
                 self.metadata.users().await.map_err(|e| {
                     DataFusionError::Internal(format!("Failed to get users: {:?}", e))
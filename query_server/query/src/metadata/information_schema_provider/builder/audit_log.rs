@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{StringBuilder, TimestampMillisecondBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+use spi::query::audit::AuditEntry;
+
+pub const AUDIT_LOG_TIME: &str = "time";
+
+lazy_static! {
+    pub static ref AUDIT_LOG_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(
+            AUDIT_LOG_TIME,
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            false
+        ),
+        Field::new("query_id", DataType::Utf8, false),
+        Field::new("tenant_name", DataType::Utf8, false),
+        Field::new("user_name", DataType::Utf8, false),
+        Field::new("client_addr", DataType::Utf8, true),
+        Field::new("database_name", DataType::Utf8, false),
+        Field::new("category", DataType::Utf8, false),
+        Field::new("statement_type", DataType::Utf8, false),
+        Field::new("objects", DataType::Utf8, false),
+        Field::new("privileges", DataType::Utf8, false),
+        Field::new("query", DataType::Utf8, false),
+        Field::new("outcome", DataType::Utf8, false),
+        Field::new("error", DataType::Utf8, true),
+    ]));
+}
+
+/// Builds the `information_schema.AUDIT_LOG` table row by row
+#[derive(Default)]
+pub struct InformationSchemaAuditLogBuilder {
+    times: TimestampMillisecondBuilder,
+    query_ids: StringBuilder,
+    tenant_names: StringBuilder,
+    user_names: StringBuilder,
+    client_addrs: StringBuilder,
+    database_names: StringBuilder,
+    categories: StringBuilder,
+    statement_types: StringBuilder,
+    objects: StringBuilder,
+    privileges: StringBuilder,
+    queries: StringBuilder,
+    outcomes: StringBuilder,
+    errors: StringBuilder,
+}
+
+impl InformationSchemaAuditLogBuilder {
+    pub fn append_row(&mut self, entry: &AuditEntry) {
+        // Note: append_value is actually infallable.
+        self.times.append_value(entry.time);
+        self.query_ids.append_value(&entry.query_id);
+        self.tenant_names.append_value(&entry.tenant);
+        self.user_names.append_value(&entry.user);
+        self.client_addrs
+            .append_option(entry.client_addr.as_deref());
+        self.database_names.append_value(&entry.database);
+        self.categories.append_value(entry.category.as_str());
+        self.statement_types.append_value(&entry.statement_type);
+        self.objects.append_value(entry.objects.join(", "));
+        self.privileges.append_value(entry.privileges.join(", "));
+        self.queries.append_value(&entry.sql);
+        self.outcomes.append_value(entry.outcome.as_str());
+        self.errors.append_option(entry.error.as_deref());
+    }
+}
+
+impl TryFrom<InformationSchemaAuditLogBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaAuditLogBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaAuditLogBuilder {
+            mut times,
+            mut query_ids,
+            mut tenant_names,
+            mut user_names,
+            mut client_addrs,
+            mut database_names,
+            mut categories,
+            mut statement_types,
+            mut objects,
+            mut privileges,
+            mut queries,
+            mut outcomes,
+            mut errors,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            AUDIT_LOG_SCHEMA.clone(),
+            vec![
+                Arc::new(times.finish()),
+                Arc::new(query_ids.finish()),
+                Arc::new(tenant_names.finish()),
+                Arc::new(user_names.finish()),
+                Arc::new(client_addrs.finish()),
+                Arc::new(database_names.finish()),
+                Arc::new(categories.finish()),
+                Arc::new(statement_types.finish()),
+                Arc::new(objects.finish()),
+                Arc::new(privileges.finish()),
+                Arc::new(queries.finish()),
+                Arc::new(outcomes.finish()),
+                Arc::new(errors.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::StringBuilder;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+pub const FUNCTIONS_FUNCTION_NAME: &str = "function_name";
+
+lazy_static! {
+    pub static ref FUNCTION_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(FUNCTIONS_FUNCTION_NAME, DataType::Utf8, false),
+        Field::new("function_type", DataType::Utf8, false),
+        Field::new("language", DataType::Utf8, false),
+        Field::new("arguments", DataType::Utf8, false),
+        Field::new("return_type", DataType::Utf8, false),
+        Field::new("definition", DataType::Utf8, false),
+    ]));
+}
+
+/// Builds the `information_schema.Functions` table row by row
+#[derive(Default)]
+pub struct InformationSchemaFunctionsBuilder {
+    function_names: StringBuilder,
+    function_types: StringBuilder,
+    languages: StringBuilder,
+    arguments: StringBuilder,
+    return_types: StringBuilder,
+    definitions: StringBuilder,
+}
+
+impl InformationSchemaFunctionsBuilder {
+    pub fn append_row(
+        &mut self,
+        function_name: impl AsRef<str>,
+        function_type: impl AsRef<str>,
+        language: impl AsRef<str>,
+        arguments: impl AsRef<str>,
+        return_type: impl AsRef<str>,
+        definition: impl AsRef<str>,
+    ) {
+        // Note: append_value is actually infallable.
+        self.function_names.append_value(function_name.as_ref());
+        self.function_types.append_value(function_type.as_ref());
+        self.languages.append_value(language.as_ref());
+        self.arguments.append_value(arguments.as_ref());
+        self.return_types.append_value(return_type.as_ref());
+        self.definitions.append_value(definition.as_ref());
+    }
+}
+
+impl TryFrom<InformationSchemaFunctionsBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaFunctionsBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaFunctionsBuilder {
+            mut function_names,
+            mut function_types,
+            mut languages,
+            mut arguments,
+            mut return_types,
+            mut definitions,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            FUNCTION_SCHEMA.clone(),
+            vec![
+                Arc::new(function_names.finish()),
+                Arc::new(function_types.finish()),
+                Arc::new(languages.finish()),
+                Arc::new(arguments.finish()),
+                Arc::new(return_types.finish()),
+                Arc::new(definitions.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
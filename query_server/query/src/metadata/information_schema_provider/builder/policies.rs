@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::StringBuilder;
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+pub const POLICIES_POLICY_NAME: &str = "policy_name";
+
+lazy_static! {
+    pub static ref POLICY_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(POLICIES_POLICY_NAME, DataType::Utf8, false),
+        Field::new("database_name", DataType::Utf8, false),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("role_name", DataType::Utf8, false),
+        Field::new("using", DataType::Utf8, false),
+        Field::new("masked_column", DataType::Utf8, true),
+    ]));
+}
+
+/// Builds the `information_schema.Policies` table row by row
+#[derive(Default)]
+pub struct InformationSchemaPoliciesBuilder {
+    policy_names: StringBuilder,
+    database_names: StringBuilder,
+    table_names: StringBuilder,
+    role_names: StringBuilder,
+    usings: StringBuilder,
+    masked_columns: StringBuilder,
+}
+
+impl InformationSchemaPoliciesBuilder {
+    pub fn append_row(
+        &mut self,
+        policy_name: impl AsRef<str>,
+        database_name: impl AsRef<str>,
+        table_name: impl AsRef<str>,
+        role_name: impl AsRef<str>,
+        using: impl AsRef<str>,
+        masked_column: Option<impl AsRef<str>>,
+    ) {
+        // Note: append_value is actually infallable.
+        self.policy_names.append_value(policy_name.as_ref());
+        self.database_names.append_value(database_name.as_ref());
+        self.table_names.append_value(table_name.as_ref());
+        self.role_names.append_value(role_name.as_ref());
+        self.usings.append_value(using.as_ref());
+        self.masked_columns.append_option(masked_column);
+    }
+}
+
+impl TryFrom<InformationSchemaPoliciesBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaPoliciesBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaPoliciesBuilder {
+            mut policy_names,
+            mut database_names,
+            mut table_names,
+            mut role_names,
+            mut usings,
+            mut masked_columns,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            POLICY_SCHEMA.clone(),
+            vec![
+                Arc::new(policy_names.finish()),
+                Arc::new(database_names.finish()),
+                Arc::new(table_names.finish()),
+                Arc::new(role_names.finish()),
+                Arc::new(usings.finish()),
+                Arc::new(masked_columns.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{StringBuilder, TimestampMillisecondBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::error::DataFusionError;
+use lazy_static::lazy_static;
+
+pub const TOKENS_TOKEN_NAME: &str = "token_name";
+
+lazy_static! {
+    pub static ref TOKEN_SCHEMA: SchemaRef = Arc::new(Schema::new(vec![
+        Field::new(TOKENS_TOKEN_NAME, DataType::Utf8, false),
+        Field::new("user_name", DataType::Utf8, false),
+        Field::new("privileges", DataType::Utf8, false),
+        Field::new(
+            "created_at",
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            false
+        ),
+        Field::new(
+            "expires_at",
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            true
+        ),
+    ]));
+}
+
+/// Builds the `information_schema.Tokens` table row by row
+#[derive(Default)]
+pub struct InformationSchemaTokensBuilder {
+    token_names: StringBuilder,
+    user_names: StringBuilder,
+    privileges: StringBuilder,
+    created_ats: TimestampMillisecondBuilder,
+    expires_ats: TimestampMillisecondBuilder,
+}
+
+impl InformationSchemaTokensBuilder {
+    pub fn append_row(
+        &mut self,
+        token_name: impl AsRef<str>,
+        user_name: impl AsRef<str>,
+        privileges: impl AsRef<str>,
+        created_at: i64,
+        expires_at: Option<i64>,
+    ) {
+        // Note: append_value is actually infallable.
+        self.token_names.append_value(token_name.as_ref());
+        self.user_names.append_value(user_name.as_ref());
+        self.privileges.append_value(privileges.as_ref());
+        self.created_ats.append_value(created_at);
+        self.expires_ats.append_option(expires_at);
+    }
+}
+
+impl TryFrom<InformationSchemaTokensBuilder> for RecordBatch {
+    type Error = DataFusionError;
+
+    fn try_from(value: InformationSchemaTokensBuilder) -> Result<Self, Self::Error> {
+        let InformationSchemaTokensBuilder {
+            mut token_names,
+            mut user_names,
+            mut privileges,
+            mut created_ats,
+            mut expires_ats,
+        } = value;
+
+        let batch = RecordBatch::try_new(
+            TOKEN_SCHEMA.clone(),
+            vec![
+                Arc::new(token_names.finish()),
+                Arc::new(user_names.finish()),
+                Arc::new(privileges.finish()),
+                Arc::new(created_ats.finish()),
+                Arc::new(expires_ats.finish()),
+            ],
+        )?;
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,117 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::Expr;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+use models::oid::Identifier;
+
+use crate::dispatcher::audit_log::AuditLog;
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::audit_log::{
+    InformationSchemaAuditLogBuilder, AUDIT_LOG_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_AUDIT_LOG: &str = "AUDIT_LOG";
+
+/// This view displays the audit records of the current tenant kept by the node serving the query.
+///
+/// All records of this view are visible to the Owner of the current tenant.
+///
+/// For non-Owner members, only the records of the current member are displayed.
+pub struct AuditLogFactory {}
+
+impl InformationSchemaTableFactory for AuditLogFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_AUDIT_LOG
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationAuditLogTable::new(
+            query_tracker.audit_log().clone(),
+            metadata,
+            user.clone(),
+        ))
+    }
+}
+
+pub struct InformationAuditLogTable {
+    user: User,
+    audit_log: Arc<AuditLog>,
+    metadata: MetaClientRef,
+}
+
+impl InformationAuditLogTable {
+    pub fn new(audit_log: Arc<AuditLog>, metadata: MetaClientRef, user: User) -> Self {
+        Self {
+            user,
+            audit_log,
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationAuditLogTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        AUDIT_LOG_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaAuditLogBuilder::default();
+
+        let tenant = self.metadata.tenant();
+        let see_all = self.user.is_admin() || self.user.can_access_system(*tenant.id());
+        let user_name = self.user.desc().name();
+
+        let entries = self
+            .audit_log
+            .entries()
+            .await
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        for entry in entries {
+            if entry.tenant != tenant.name() || (!see_all && entry.user != user_name) {
+                continue;
+            }
+            builder.append_row(&entry);
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
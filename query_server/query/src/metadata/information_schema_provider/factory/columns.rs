@@ -18,6 +18,7 @@ use models::schema::external_table_schema::ExternalTableSchema;
 use models::schema::stream_table_schema::StreamTable;
 use models::schema::table_schema::TableSchema;
 use models::schema::tskv_table_schema::{ColumnType, TskvTableSchemaRef};
+use models::schema::view_schema::ViewSchema;
 use models::ValueType;
 
 use crate::dispatcher::query_tracker::QueryTracker;
@@ -118,6 +119,9 @@ impl TableProvider for InformationColumnsTable {
                         TableSchema::StreamTableSchema(t) => {
                             append_stream_table(tenant_name, &db, t.clone(), &mut builder);
                         }
+                        TableSchema::ViewTableSchema(t) => {
+                            append_view_table(tenant_name, &db, t.clone(), &mut builder);
+                        }
                     }
                 }
             }
@@ -199,3 +203,26 @@ fn append_stream_table(
         );
     }
 }
+
+fn append_view_table(
+    tenant_name: &str,
+    database_name: &str,
+    table: Arc<ViewSchema>,
+    builder: &mut InformationSchemaColumnsBuilder,
+) {
+    for (idx, col) in table.schema().all_fields().iter().enumerate() {
+        builder.append_row(
+            tenant_name,
+            database_name,
+            table.name(),
+            col.name(),
+            // The columns of the view are all type FIELD
+            ColumnType::Field(ValueType::Unknown).as_column_type_str(),
+            idx as u64,
+            "UNKNOWN",
+            col.is_nullable(),
+            col.data_type().to_string(),
+            None::<String>,
+        );
+    }
+}
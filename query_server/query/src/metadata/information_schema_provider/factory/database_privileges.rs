@@ -99,6 +99,9 @@ impl TableProvider for InformationDatabasePrivilegesTable {
                 for (database_name, privilege) in role.additional_privileges() {
                     builder.append_row(tenant_name, database_name, privilege.as_str(), role.name())
                 }
+                for privilege in role.table_privileges() {
+                    builder.append_table_row(tenant_name, privilege, role.name())
+                }
             }
         } else {
             // For non-Owner members, only records corresponding to own role are accessed
@@ -131,6 +134,9 @@ impl TableProvider for InformationDatabasePrivilegesTable {
                                     role.name(),
                                 )
                             }
+                            for privilege in role.table_privileges() {
+                                builder.append_table_row(tenant_name, privilege, role.name())
+                            }
                         } else {
                             error!("The metadata is inconsistent, member {} of the tenant {} have the role {}, but this role does not exist",
                         user_name, tenant_name, role_name);
@@ -0,0 +1,97 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::Expr;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::functions::{
+    InformationSchemaFunctionsBuilder, FUNCTION_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_FUNCTIONS: &str = "FUNCTIONS";
+
+/// This view displays the functions created by the users of the current tenant.
+///
+/// All records of this view are visible to all members of the current tenant.
+pub struct FunctionsFactory {}
+
+impl InformationSchemaTableFactory for FunctionsFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_FUNCTIONS
+    }
+
+    fn create(
+        &self,
+        _user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationFunctionsTable::new(metadata))
+    }
+}
+
+pub struct InformationFunctionsTable {
+    metadata: MetaClientRef,
+}
+
+impl InformationFunctionsTable {
+    pub fn new(metadata: MetaClientRef) -> Self {
+        Self { metadata }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationFunctionsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        FUNCTION_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaFunctionsBuilder::default();
+
+        for function in self.metadata.functions() {
+            builder.append_row(
+                function.name(),
+                function.kind().to_string(),
+                function.body().language(),
+                function.signature(),
+                function.return_type().to_string(),
+                function.body().to_string(),
+            );
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
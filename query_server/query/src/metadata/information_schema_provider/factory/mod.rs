@@ -1,9 +1,13 @@
+pub mod audit_log;
 pub mod columns;
 pub mod database_privileges;
 pub mod databases;
 pub mod enabled_roles;
+pub mod functions;
 pub mod members;
+pub mod policies;
 pub mod queries;
 pub mod resource_status;
 pub mod roles;
 pub mod tables;
+pub mod tokens;
@@ -0,0 +1,111 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::Expr;
+use meta::model::MetaClientRef;
+use models::auth::privilege::{Privilege, TenantObjectPrivilege};
+use models::auth::user::User;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::policies::{
+    InformationSchemaPoliciesBuilder, POLICY_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_POLICIES: &str = "POLICIES";
+
+/// This view displays the row-level security and column masking policies of the current tenant.
+///
+/// All records of this view are visible to the users who can manage the roles of the tenant.
+///
+/// For other members, only the policies of their role are displayed.
+pub struct PoliciesFactory {}
+
+impl InformationSchemaTableFactory for PoliciesFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_POLICIES
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationPoliciesTable::new(metadata, user.clone()))
+    }
+}
+
+pub struct InformationPoliciesTable {
+    user: User,
+    metadata: MetaClientRef,
+}
+
+impl InformationPoliciesTable {
+    pub fn new(metadata: MetaClientRef, user: User) -> Self {
+        Self { user, metadata }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationPoliciesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        POLICY_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaPoliciesBuilder::default();
+
+        let tenant_id = *self.metadata.tenant().id();
+        let see_all = self.user.check_privilege(&Privilege::TenantObject(
+            TenantObjectPrivilege::RoleFull,
+            Some(tenant_id),
+        ));
+        let role = self.user.role().map(|role| role.name());
+
+        for policy in self.metadata.policies() {
+            if !see_all && role != Some(policy.role()) {
+                continue;
+            }
+            builder.append_row(
+                policy.name(),
+                policy.database(),
+                policy.table(),
+                policy.role(),
+                policy.using(),
+                policy.masked_column(),
+            );
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
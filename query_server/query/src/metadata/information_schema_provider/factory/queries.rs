@@ -13,7 +13,8 @@ use datafusion::physical_plan::ExecutionPlan;
 use meta::model::MetaClientRef;
 use models::auth::user::User;
 use models::oid::{Identifier, Oid};
-use spi::query::execution::QueryExecutionRef;
+use models::schema::query_info::QueryInfo;
+use spi::query::execution::QueryType;
 
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::metadata::information_schema_provider::builder::queries::{
@@ -93,37 +94,52 @@ impl TableProvider for InformationQueriesTable {
         let tenant_id = *self.metadata.tenant().id();
         let all_queries = self.query_tracker.running_queries();
 
-        let running_queries = filter_running_queries(user_id, tenant_id, &self.user, all_queries);
-
-        for query in running_queries {
+        for query in all_queries {
             let info = query.info();
+            if !is_visible(user_id, tenant_id, &self.user, &info) {
+                continue;
+            }
             let status = query.status();
 
-            let query_id = info.query_id().to_string();
-            let query_text = info.query();
-            let user_id = info.user_id().to_string();
-            let user_name = info.user_name();
-            let tenant_id = info.tenant_id().to_string();
-            let tenant_name = info.tenant_name();
-            let database_name = info.database_name();
-            let state = status.query_state();
-            let duration = status.duration().as_secs_f64();
-            let processed_count = status.processed_count();
-            let error_count = status.error_count();
-
             builder.append_row(
-                query_id,
+                info.query_id().to_string(),
                 query.query_type().to_string(),
-                query_text,
-                user_id,
-                user_name,
-                tenant_id,
-                tenant_name,
-                database_name,
-                state,
-                duration,
-                processed_count,
-                error_count,
+                info.query(),
+                info.user_id().to_string(),
+                info.user_name(),
+                info.tenant_id().to_string(),
+                info.tenant_name(),
+                info.database_name(),
+                status.query_state(),
+                status.duration().as_secs_f64(),
+                status.processed_count(),
+                status.error_count(),
+            );
+        }
+
+        // The async queries whose results are kept after they are done
+        let async_queries = self.query_tracker.async_queries();
+        for query in async_queries.queries() {
+            let info = query.info();
+            let status = async_queries.status(&query);
+            if !status.state.is_done() || !is_visible(user_id, tenant_id, &self.user, info) {
+                // a running async query is listed as a running query
+                continue;
+            }
+
+            builder.append_row(
+                info.query_id().to_string(),
+                QueryType::Batch.to_string(),
+                info.query(),
+                info.user_id().to_string(),
+                info.user_name(),
+                info.tenant_id().to_string(),
+                info.tenant_name(),
+                info.database_name(),
+                status.state,
+                status.duration,
+                status.rows,
+                u64::from(status.error.is_some()),
             );
         }
         let rb: RecordBatch = builder.try_into()?;
@@ -136,29 +152,22 @@ impl TableProvider for InformationQueriesTable {
     }
 }
 
-fn filter_running_queries(
-    user_id: Oid,
-    tenant_id: Oid,
-    user: &User,
-    all_queries: Vec<QueryExecutionRef>,
-) -> Vec<QueryExecutionRef> {
-    if user.desc().is_admin() {
+fn is_visible(user_id: Oid, tenant_id: Oid, user: &User, info: &QueryInfo) -> bool {
+    if user.is_admin() {
         // Then user with admin permissions: can see all queries in the cluster
-        return all_queries;
+        return true;
     }
 
     // only current tenant
-    let all_queries_of_tenant = all_queries
-        .into_iter()
-        .filter(|e| e.info().tenant_id() == tenant_id);
+    if info.tenant_id() != tenant_id {
+        return false;
+    }
 
     if user.can_access_system(tenant_id) {
         // The tenant owner: see all queries under the current tenant
-        return all_queries_of_tenant.collect::<Vec<_>>();
+        return true;
     }
 
     // Common user: see the SQL executed by themselves under the current tenant
-    all_queries_of_tenant
-        .filter(|e| e.info().user_id() == user_id)
-        .collect::<Vec<_>>()
+    info.user_id() == user_id
 }
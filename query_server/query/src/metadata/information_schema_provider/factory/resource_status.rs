@@ -88,7 +88,7 @@ impl TableProvider for InformationSchemaResourceStatusTable {
             for resourceinfo in resourceinfos {
                 // Check if the current user has at least read permission on this db, skip if not
                 let tenant_id_and_db = resourceinfo.get_tenant_id_and_db();
-                if (tenant_id_and_db.1.is_empty() && !self.user.desc().is_admin())
+                if (tenant_id_and_db.1.is_empty() && !self.user.is_admin())
                     || (!tenant_id_and_db.1.is_empty()
                         && !self
                             .user
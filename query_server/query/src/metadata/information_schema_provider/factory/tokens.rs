@@ -0,0 +1,106 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Result as DFResult;
+use datafusion::datasource::{TableProvider, TableType};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::logical_plan::AggWithGrouping;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::Expr;
+use meta::model::MetaClientRef;
+use models::auth::user::User;
+
+use crate::dispatcher::query_tracker::QueryTracker;
+use crate::metadata::information_schema_provider::builder::tokens::{
+    InformationSchemaTokensBuilder, TOKEN_SCHEMA,
+};
+use crate::metadata::information_schema_provider::InformationSchemaTableFactory;
+
+pub const INFORMATION_SCHEMA_TOKENS: &str = "TOKENS";
+
+/// This view displays the API tokens of the current tenant, the secrets are never shown.
+///
+/// All records of this view are visible to the Owner of the current tenant.
+///
+/// For non-Owner members, only the tokens of the current member are displayed.
+pub struct TokensFactory {}
+
+impl InformationSchemaTableFactory for TokensFactory {
+    fn table_name(&self) -> &'static str {
+        INFORMATION_SCHEMA_TOKENS
+    }
+
+    fn create(
+        &self,
+        user: &User,
+        metadata: MetaClientRef,
+        _query_tracker: Arc<QueryTracker>,
+    ) -> Arc<dyn TableProvider> {
+        Arc::new(InformationTokensTable::new(metadata, user.clone()))
+    }
+}
+
+pub struct InformationTokensTable {
+    user: User,
+    metadata: MetaClientRef,
+}
+
+impl InformationTokensTable {
+    pub fn new(metadata: MetaClientRef, user: User) -> Self {
+        Self { user, metadata }
+    }
+}
+
+#[async_trait]
+impl TableProvider for InformationTokensTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        TOKEN_SCHEMA.clone()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+
+    async fn scan(
+        &self,
+        _state: &SessionState,
+        projection: Option<&Vec<usize>>,
+        _filters: &[Expr],
+        _agg_with_grouping: Option<&AggWithGrouping>,
+        _limit: Option<usize>,
+    ) -> DFResult<Arc<dyn ExecutionPlan>> {
+        let mut builder = InformationSchemaTokensBuilder::default();
+
+        let user_id = self.user.desc().id();
+        let tenant_id = *self.metadata.tenant().id();
+        let see_all = self.user.is_admin() || self.user.can_access_system(tenant_id);
+
+        for token in self.metadata.tokens() {
+            if !see_all && token.user_id() != user_id {
+                continue;
+            }
+            builder.append_row(
+                token.name(),
+                token.user_name(),
+                token.privileges_string(),
+                token.created_at(),
+                token.expires_at(),
+            );
+        }
+        let rb: RecordBatch = builder.try_into()?;
+
+        Ok(Arc::new(MemoryExec::try_new(
+            &[vec![rb]],
+            self.schema(),
+            projection.cloned(),
+        )?))
+    }
+}
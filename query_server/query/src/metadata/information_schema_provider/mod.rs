@@ -12,27 +12,37 @@ pub use builder::databases::{
     DATABASES_STRICT_WRITE, DATABASES_TENANT_NAME, DATABASES_TTL, DATABASES_VNODE_DURATION,
     DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC,
 };
+pub use builder::functions::FUNCTIONS_FUNCTION_NAME;
+pub use builder::policies::POLICIES_POLICY_NAME;
 pub use builder::tables::{
     TABLES_TABLE_DATABASE, TABLES_TABLE_ENGINE, TABLES_TABLE_NAME, TABLES_TABLE_OPTIONS,
     TABLES_TABLE_TENANT, TABLES_TABLE_TYPE,
 };
+pub use builder::tokens::TOKENS_TOKEN_NAME;
 use datafusion::datasource::TableProvider;
 pub use factory::columns::INFORMATION_SCHEMA_COLUMNS;
 pub use factory::databases::INFORMATION_SCHEMA_DATABASES;
+pub use factory::functions::INFORMATION_SCHEMA_FUNCTIONS;
+pub use factory::policies::INFORMATION_SCHEMA_POLICIES;
 pub use factory::queries::INFORMATION_SCHEMA_QUERIES;
 pub use factory::tables::INFORMATION_SCHEMA_TABLES;
+pub use factory::tokens::INFORMATION_SCHEMA_TOKENS;
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
 use models::auth::user::User;
 
+use self::factory::audit_log::AuditLogFactory;
 use self::factory::columns::ColumnsFactory;
 use self::factory::database_privileges::DatabasePrivilegesFactory;
 use self::factory::databases::DatabasesFactory;
 use self::factory::enabled_roles::EnabledRolesFactory;
+use self::factory::functions::FunctionsFactory;
 use self::factory::members::MembersFactory;
+use self::factory::policies::PoliciesFactory;
 use self::factory::queries::QueriesFactory;
 use self::factory::resource_status::InformationSchemaResourceStatusFactory;
 use self::factory::roles::RolesFactory;
+use self::factory::tokens::TokensFactory;
 use super::INFORMATION_SCHEMA;
 use crate::dispatcher::query_tracker::QueryTracker;
 use crate::metadata::information_schema_provider::factory::tables::TablesFactory;
@@ -62,6 +72,10 @@ impl InformationSchemaProvider {
         provider.register_table_factory(Box::new(MembersFactory {}));
         provider.register_table_factory(Box::new(QueriesFactory {}));
         provider.register_table_factory(Box::new(InformationSchemaResourceStatusFactory {}));
+        provider.register_table_factory(Box::new(FunctionsFactory {}));
+        provider.register_table_factory(Box::new(TokensFactory {}));
+        provider.register_table_factory(Box::new(PoliciesFactory {}));
+        provider.register_table_factory(Box::new(AuditLogFactory {}));
 
         provider
     }
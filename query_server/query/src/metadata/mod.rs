@@ -1,15 +1,17 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use cluster_schema_provider::{CLUSTER_SCHEMA_TENANTS, CLUSTER_SCHEMA_USERS};
+use config::common::PasswordPolicyConfig;
 use coordinator::service::CoordinatorRef;
 use datafusion::arrow::datatypes::DataType;
 use datafusion::common::Result as DFResult;
 use datafusion::config::ConfigOptions;
-use datafusion::datasource::TableProvider;
+use datafusion::datasource::{provider_as_source, TableProvider, ViewTable};
 use datafusion::error::DataFusionError;
-use datafusion::logical_expr::{AggregateUDF, ScalarUDF, TableSource, WindowUDF};
+use datafusion::logical_expr::{AggregateUDF, LogicalPlan, ScalarUDF, TableSource, WindowUDF};
 use datafusion::physical_expr::var_provider::is_system_variables;
 use datafusion::sql::planner::ContextProvider;
 use datafusion::sql::TableReference;
@@ -20,33 +22,49 @@ pub use information_schema_provider::{
     DATABASES_MAX_MEMCACHE_SIZE, DATABASES_MEMCACHE_PARTITIONS, DATABASES_PRECISION,
     DATABASES_REPLICA, DATABASES_SHARD, DATABASES_STRICT_WRITE, DATABASES_TENANT_NAME,
     DATABASES_TTL, DATABASES_VNODE_DURATION, DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC,
-    INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_QUERIES,
-    INFORMATION_SCHEMA_TABLES, TABLES_TABLE_DATABASE, TABLES_TABLE_ENGINE, TABLES_TABLE_NAME,
-    TABLES_TABLE_OPTIONS, TABLES_TABLE_TENANT, TABLES_TABLE_TYPE,
+    FUNCTIONS_FUNCTION_NAME, INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES,
+    INFORMATION_SCHEMA_FUNCTIONS, INFORMATION_SCHEMA_POLICIES, INFORMATION_SCHEMA_QUERIES,
+    INFORMATION_SCHEMA_TABLES, INFORMATION_SCHEMA_TOKENS, POLICIES_POLICY_NAME,
+    TABLES_TABLE_DATABASE, TABLES_TABLE_ENGINE, TABLES_TABLE_NAME, TABLES_TABLE_OPTIONS,
+    TABLES_TABLE_TENANT, TABLES_TABLE_TYPE, TOKENS_TOKEN_NAME,
 };
 use meta::error::MetaError;
 use meta::model::MetaClientRef;
+use models::auth::policy::PolicyDesc;
+use models::auth::token::TokenDesc;
 use models::auth::user::UserDesc;
 use models::meta_data::DatabaseInfo;
 use models::object_reference::{Resolve, ResolvedTable};
+use models::schema::function_schema::{FunctionKind, FunctionSchema};
+use models::schema::table_schema::TableSchema;
 use models::schema::tenant::Tenant;
+use models::schema::view_schema::{ViewSchema, MATERIALIZED_VIEW_STORAGE_PREFIX};
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE};
 use parking_lot::RwLock;
 use spi::query::function::FuncMetaManagerRef;
 use spi::query::session::SessionCtx;
+use spi::QueryError;
 use utils::precision::Precision;
 
 pub use self::base_table::BaseTableProvider;
 use self::cluster_schema_provider::ClusterSchemaProvider;
 use self::information_schema_provider::InformationSchemaProvider;
+pub use self::view::view_query_to_plan;
+use self::view::{
+    materialized_view_plan, parse_view_query, rewrite_with_materialized_views, MAX_VIEW_DEPTH,
+};
 use crate::data_source::table_source::{TableHandle, TableSourceAdapter};
 use crate::dispatcher::query_tracker::QueryTracker;
+use crate::extension::expr::{
+    create_user_udaf, create_user_udf, failed_user_udaf, failed_user_udf, MAX_FUNCTION_DEPTH,
+};
 use crate::metadata::usage_schema_provider::UsageSchemaProvider;
 
 mod base_table;
 mod cluster_schema_provider;
 mod information_schema_provider;
 mod usage_schema_provider;
+mod view;
 
 pub const CLUSTER_SCHEMA: &str = "cluster_schema";
 pub const INFORMATION_SCHEMA: &str = "INFORMATION_SCHEMA";
@@ -74,6 +92,51 @@ pub trait ContextProviderExtension: ContextProvider {
     ) -> Result<(), MetaError> {
         Ok(())
     }
+    /// The names of the tables of the database, used to expand the table wildcards of `DELETE`
+    fn list_tables(&self, _database: &str) -> Result<Vec<String>, MetaError> {
+        Ok(vec![])
+    }
+    /// The function created by the users of the tenant
+    fn get_user_function(&self, _name: &str) -> Option<FunctionSchema> {
+        None
+    }
+    /// The API token of the tenant
+    fn get_token(&self, _name: &str) -> Option<TokenDesc> {
+        None
+    }
+    /// The row-level security and masking policies of the tenant
+    fn policies(&self) -> Vec<PolicyDesc> {
+        vec![]
+    }
+    /// The password policy in effect for the tenant
+    fn password_policy(&self) -> Option<PasswordPolicyConfig> {
+        None
+    }
+    /// The distinct values of a tag of the table, used to discover the columns of `PIVOT ... IN (ANY)`
+    async fn tag_values(
+        &self,
+        _table: TableReference<'_>,
+        _tag: &str,
+    ) -> datafusion::common::Result<Vec<String>> {
+        Err(DataFusionError::NotImplemented(
+            "Discovering tag values".to_string(),
+        ))
+    }
+    /// The materialized view whose rows are stored in the table
+    fn stored_materialized_view(
+        &self,
+        _database: &str,
+        _table: &str,
+    ) -> datafusion::common::Result<Option<ViewSchema>> {
+        Ok(None)
+    }
+    /// Replace the parts of the plan that materialized views can answer with scans of their rows
+    fn rewrite_with_materialized_views(
+        &self,
+        plan: LogicalPlan,
+    ) -> datafusion::common::Result<LogicalPlan> {
+        Ok(plan)
+    }
 }
 
 pub type TableHandleProviderRef = Arc<dyn TableHandleProvider + Send + Sync>;
@@ -93,6 +156,10 @@ pub struct MetadataProvider {
     cluster_schema_provider: ClusterSchemaProvider,
     usage_schema_provider: UsageSchemaProvider,
     access_databases: RwLock<DatabaseSet>,
+    // the number of views being expanded
+    view_depth: AtomicUsize,
+    // the number of sql functions being planned
+    function_depth: AtomicUsize,
     // tskv/external
     current_session_table_provider: TableHandleProviderRef,
 }
@@ -120,6 +187,8 @@ impl MetadataProvider {
             cluster_schema_provider: ClusterSchemaProvider::new(),
             usage_schema_provider: UsageSchemaProvider::new(default_table_provider),
             access_databases: Default::default(),
+            view_depth: AtomicUsize::new(0),
+            function_depth: AtomicUsize::new(0),
         }
     }
 
@@ -173,6 +242,65 @@ impl MetadataProvider {
         Ok(None)
     }
 
+    /// The columns of the table granted to the user, None if the user is not restricted by columns
+    fn granted_columns(&self, database_name: &str, table_name: &str) -> Option<BTreeSet<String>> {
+        let user = self.session.user();
+        let tenant_id = *self.session.tenant_id();
+        if user.can_read_database(tenant_id, database_name)
+            || !user.has_table_privileges(tenant_id, database_name)
+        {
+            return None;
+        }
+
+        // without any granted column, the privilege check reports the missing privilege
+        user.readable_columns(tenant_id, database_name, table_name)
+            .filter(|columns| !columns.is_empty())
+    }
+
+    /// The user must be able to read the tag, and the values of a table with row-level security
+    /// policies or of a masked tag must be listed explicitly, as the scan of the values is not
+    /// filtered or masked.
+    fn check_tag_values_readable(&self, name: &ResolvedTable, tag: &str) -> DFResult<()> {
+        let user = self.session.user();
+        let tenant_id = *self.session.tenant_id();
+        let readable = user.can_read_database(tenant_id, name.database())
+            || user
+                .readable_columns(tenant_id, name.database(), name.table())
+                .map_or(true, |columns| columns.contains(tag));
+        if !readable {
+            return Err(DataFusionError::External(Box::new(
+                QueryError::InsufficientPrivileges {
+                    privilege: format!(
+                        "read column {} of table {}.{}",
+                        tag,
+                        name.database(),
+                        name.table()
+                    ),
+                },
+            )));
+        }
+
+        let Some(role) = user.role() else {
+            return Ok(());
+        };
+        let restricted = self.meta_client.policies().iter().any(|policy| {
+            policy.applies_to(name.database(), name.table(), role.name())
+                || (policy.masks(name.database(), name.table(), role.name()) == Some(tag)
+                    && !user.can_access_role(tenant_id))
+        });
+        if restricted {
+            return Err(DataFusionError::Plan(format!(
+                "The values of {} can not be discovered because of the policies on table {}.{}, \
+                list them in PIVOT ... IN explicitly",
+                tag,
+                name.database(),
+                name.table()
+            )));
+        }
+
+        Ok(())
+    }
+
     fn build_table_handle(&self, name: &ResolvedTable) -> datafusion::common::Result<TableHandle> {
         let tenant_name = name.tenant();
         let database_name = name.database();
@@ -184,9 +312,111 @@ impl MetadataProvider {
             return Ok(source.into());
         }
 
+        if let Some(TableSchema::ViewTableSchema(view)) = self
+            .meta_client
+            .get_table_schema(database_name, table_name)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?
+        {
+            return Ok(self.build_view_table(&view)?.into());
+        }
+
         self.current_session_table_provider
             .build_table_handle(database_name, table_name)
     }
+
+    /// Expand the view into the plan of its query,
+    /// a materialized view is expanded into the scan of its storage table.
+    /// The stored rows are neither filtered nor masked, so the members of a role with policies
+    /// on the base table of a materialized view read it through its query,
+    /// to which the policies are applied.
+    fn build_view_table(&self, view: &ViewSchema) -> DFResult<Arc<dyn TableProvider>> {
+        let depth = self.view_depth.fetch_add(1, Ordering::SeqCst);
+        let plan = if depth >= MAX_VIEW_DEPTH {
+            Err(DataFusionError::Plan(format!(
+                "Views are nested more than {MAX_VIEW_DEPTH} levels when expanding view {}.{}",
+                view.db(),
+                view.name()
+            )))
+        } else if view
+            .materialized()
+            .is_some_and(|materialized| !self.has_policies(view.db(), materialized.base_table()))
+        {
+            self.materialized_view_storage_plan(view)
+        } else {
+            parse_view_query(view.query())
+                .and_then(|query| view_query_to_plan(self, view.db(), query))
+                .map_err(|e| DataFusionError::External(Box::new(e)))
+        };
+        self.view_depth.fetch_sub(1, Ordering::SeqCst);
+
+        let table = ViewTable::try_new(plan?, Some(view.query().to_string()))?;
+        Ok(Arc::new(table))
+    }
+
+    /// Plan a scalar function created by the users of the tenant,
+    /// the body of a sql function may call other functions.
+    /// A function that fails to plan reports its error when it is called.
+    fn user_udf(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        let function = self
+            .meta_client
+            .function(name)
+            .filter(|e| e.kind() == FunctionKind::Scalar)?;
+
+        let depth = self.function_depth.fetch_add(1, Ordering::SeqCst);
+        let udf = if depth >= MAX_FUNCTION_DEPTH {
+            Err(DataFusionError::Plan(format!(
+                "Functions are nested more than {MAX_FUNCTION_DEPTH} levels when planning function {name}"
+            )))
+        } else {
+            create_user_udf(&function, self)
+        };
+        self.function_depth.fetch_sub(1, Ordering::SeqCst);
+
+        let udf = udf.unwrap_or_else(|e| failed_user_udf(&function, e));
+        Some(Arc::new(udf))
+    }
+
+    fn user_udaf(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        let function = self
+            .meta_client
+            .function(name)
+            .filter(|e| e.kind() == FunctionKind::Aggregate)?;
+
+        let udaf = create_user_udaf(&function).unwrap_or_else(|e| failed_user_udaf(&function, e));
+        Some(Arc::new(udaf))
+    }
+
+    /// Whether the role of the user has policies on the table
+    fn has_policies(&self, database: &str, table: &str) -> bool {
+        let Some(role) = self.session.user().role() else {
+            return false;
+        };
+        self.meta_client
+            .policies()
+            .iter()
+            .any(|policy| policy.on(database, table, role.name()))
+    }
+
+    fn materialized_view_storage_plan(&self, view: &ViewSchema) -> DFResult<LogicalPlan> {
+        let materialized = view.materialized().ok_or_else(|| {
+            DataFusionError::Internal(format!("View {} is not materialized", view.name()))
+        })?;
+        let storage = match self
+            .current_session_table_provider
+            .build_table_handle(view.db(), materialized.storage_table())?
+        {
+            TableHandle::Tskv(table) => provider_as_source(table),
+            other => {
+                return Err(DataFusionError::Plan(format!(
+                    "The storage table of materialized view {} must be a tskv table, but found: {}",
+                    view.name(),
+                    other
+                )))
+            }
+        };
+
+        materialized_view_plan(view, materialized, storage)
+    }
 }
 
 #[async_trait::async_trait]
@@ -266,12 +496,136 @@ impl ContextProviderExtension for MetadataProvider {
 
         let table_handle = self.build_table_handle(&name)?;
 
-        Ok(Arc::new(TableSourceAdapter::try_new(
+        let table_source = TableSourceAdapter::try_new(
             table_ref.to_owned_reference(),
             database_name,
             table_name,
             table_handle,
-        )?))
+        )?;
+
+        // The users who are only granted some columns of the table can't see the other columns
+        match self.granted_columns(database_name, table_name) {
+            Some(columns) => Ok(Arc::new(table_source.with_columns(&columns)?)),
+            None => Ok(Arc::new(table_source)),
+        }
+    }
+
+    fn list_tables(&self, database: &str) -> Result<Vec<String>, MetaError> {
+        self.meta_client.list_tables(database)
+    }
+
+    fn get_user_function(&self, name: &str) -> Option<FunctionSchema> {
+        self.meta_client.function(name)
+    }
+
+    fn get_token(&self, name: &str) -> Option<TokenDesc> {
+        self.meta_client.token(name)
+    }
+
+    fn policies(&self) -> Vec<PolicyDesc> {
+        self.meta_client.policies()
+    }
+
+    fn password_policy(&self) -> Option<PasswordPolicyConfig> {
+        self.coord
+            .meta_manager()
+            .password_policy(self.meta_client.tenant())
+    }
+
+    async fn tag_values(
+        &self,
+        table_ref: TableReference<'_>,
+        tag: &str,
+    ) -> datafusion::common::Result<Vec<String>> {
+        let name = table_ref
+            .clone()
+            .resolve_object(self.session.tenant(), self.session.default_database())?;
+        if self.session.tenant() != name.tenant() {
+            return Err(DataFusionError::Plan(format!(
+                "Tenant conflict, the current connection's tenant is {}",
+                self.session.tenant()
+            )));
+        }
+
+        self.access_databases
+            .write()
+            .push_table(name.database(), name.table());
+        // the values are scanned while planning, before the privileges of the query are checked
+        self.check_tag_values_readable(&name, tag)?;
+
+        match self.build_table_handle(&name)? {
+            TableHandle::Tskv(table) => table.tag_values(self.session.inner(), tag).await,
+            _ => Err(DataFusionError::Plan(format!(
+                "Tag values can only be discovered from tskv tables, but found: {}",
+                table_ref
+            ))),
+        }
+    }
+
+    fn stored_materialized_view(
+        &self,
+        database: &str,
+        table: &str,
+    ) -> datafusion::common::Result<Option<ViewSchema>> {
+        let Some(view_name) = table.strip_prefix(MATERIALIZED_VIEW_STORAGE_PREFIX) else {
+            return Ok(None);
+        };
+        let view = match self
+            .meta_client
+            .get_table_schema(database, view_name)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?
+        {
+            Some(TableSchema::ViewTableSchema(view))
+                if view
+                    .materialized()
+                    .is_some_and(|materialized| materialized.storage_table() == table) =>
+            {
+                Some(view.as_ref().clone())
+            }
+            _ => None,
+        };
+        Ok(view)
+    }
+
+    fn rewrite_with_materialized_views(
+        &self,
+        plan: LogicalPlan,
+    ) -> datafusion::common::Result<LogicalPlan> {
+        // The tables of the view queries are resolved against the database of the view,
+        // only the views of the default database are comparable with the unqualified tables of the plan.
+        let database = self.session.default_database();
+        let Some(table_set) = self.access_databases.read().table_set(database).cloned() else {
+            return Ok(plan);
+        };
+
+        let mut views = vec![];
+        let tables = self
+            .meta_client
+            .list_tables(database)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        for table in tables {
+            let Some(TableSchema::ViewTableSchema(view)) = self
+                .meta_client
+                .get_table_schema(database, &table)
+                .map_err(|e| DataFusionError::External(Box::new(e)))?
+            else {
+                continue;
+            };
+            let Some(materialized) = view.materialized() else {
+                continue;
+            };
+            if !table_set.contains(materialized.base_table()) {
+                continue;
+            }
+
+            let definition = parse_view_query(view.query())
+                .and_then(|query| view_query_to_plan(self, view.db(), query))
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            let storage = self.materialized_view_storage_plan(&view)?;
+            views.push((definition, storage));
+        }
+
+        rewrite_with_materialized_views(plan, &views)
     }
 
     fn database_table_exist(
@@ -314,16 +668,18 @@ impl ContextProvider for MetadataProvider {
     }
 
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
-        self.func_manager.udf(name).ok().or(self
-            .session
-            .inner()
-            .scalar_functions()
-            .get(name)
-            .cloned())
+        self.func_manager
+            .udf(name)
+            .ok()
+            .or(self.session.inner().scalar_functions().get(name).cloned())
+            .or_else(|| self.user_udf(name))
     }
 
     fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
-        self.func_manager.udaf(name).ok()
+        self.func_manager
+            .udaf(name)
+            .ok()
+            .or_else(|| self.user_udaf(name))
     }
 
     fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
@@ -386,6 +742,14 @@ impl TableSet {
     pub fn push_table(&mut self, tbl: impl Into<String>) {
         self.tables.insert(tbl.into());
     }
+
+    pub fn contains(&self, tbl: &str) -> bool {
+        self.tables.contains(tbl)
+    }
+
+    pub fn tables(&self) -> impl Iterator<Item = &String> {
+        self.tables.iter()
+    }
 }
 
 // "cnosdb" tenant additional check "public" and "CLUSTER_SCHEMA"
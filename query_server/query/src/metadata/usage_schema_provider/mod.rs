@@ -100,7 +100,7 @@ pub fn create_usage_schema_view_table(
 
     let builder = LogicalPlanBuilder::scan(view_table_name.to_string(), table_source, None)?;
 
-    let builder = if session.user().desc().is_admin() && tenant_name.eq(DEFAULT_CATALOG) {
+    let builder = if session.user().is_admin() && tenant_name.eq(DEFAULT_CATALOG) {
         // do nothing
         builder
     } else {
@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::common::{Column, Result as DFResult};
+use datafusion::config::ConfigOptions;
+use datafusion::logical_expr::{
+    cast, AggregateUDF, LogicalPlan, LogicalPlanBuilder, ScalarUDF, TableSource, WindowUDF,
+};
+use datafusion::prelude::Expr;
+use datafusion::sql::planner::{ContextProvider, SqlToRel};
+use datafusion::sql::sqlparser::ast::Statement;
+use datafusion::sql::TableReference;
+use models::schema::view_schema::{MaterializedView, ViewSchema};
+use snafu::ResultExt;
+use spi::query::ast::ExtStatement;
+use spi::{ParserSnafu, QueryError, QueryResult};
+
+use crate::sql::parser::ExtParser;
+
+/// The maximum number of views expanded into each other, guards against views referencing themselves.
+pub const MAX_VIEW_DEPTH: usize = 32;
+
+/// Resolves the unqualified tables in the query of a view against the database of the view,
+/// everything else is delegated to the inner provider.
+pub struct ViewContextProvider<'a, S> {
+    inner: &'a S,
+    database: &'a str,
+}
+
+impl<'a, S: ContextProvider> ViewContextProvider<'a, S> {
+    pub fn new(inner: &'a S, database: &'a str) -> Self {
+        Self { inner, database }
+    }
+}
+
+impl<'a, S: ContextProvider> ContextProvider for ViewContextProvider<'a, S> {
+    fn get_table_provider(&self, name: TableReference) -> DFResult<Arc<dyn TableSource>> {
+        let name = match name {
+            TableReference::Bare { table } => {
+                TableReference::partial(self.database.to_string(), table.into_owned())
+            }
+            other => other,
+        };
+        self.inner.get_table_provider(name)
+    }
+
+    fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        self.inner.get_function_meta(name)
+    }
+
+    fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        self.inner.get_aggregate_meta(name)
+    }
+
+    fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
+        self.inner.get_variable_type(variable_names)
+    }
+
+    fn options(&self) -> &ConfigOptions {
+        self.inner.options()
+    }
+
+    fn get_window_meta(&self, name: &str) -> Option<Arc<WindowUDF>> {
+        self.inner.get_window_meta(name)
+    }
+}
+
+/// Parse the query of a view.
+pub fn parse_view_query(sql: &str) -> QueryResult<Statement> {
+    let mut statements = ExtParser::parse_sql(sql).context(ParserSnafu)?;
+    match (statements.pop_front(), statements.is_empty()) {
+        (Some(ExtStatement::SqlStatement(stmt)), true) if matches!(*stmt, Statement::Query(_)) => {
+            Ok(*stmt)
+        }
+        _ => Err(QueryError::Semantic {
+            err: format!("The query of a view must be a single SELECT statement: {sql}"),
+        }),
+    }
+}
+
+/// Plan the query of a view whose unqualified tables belong to `database`.
+pub fn view_query_to_plan<S: ContextProvider>(
+    provider: &S,
+    database: &str,
+    query: Statement,
+) -> QueryResult<LogicalPlan> {
+    let provider = ViewContextProvider::new(provider, database);
+    Ok(SqlToRel::new(&provider).sql_statement_to_plan(query)?)
+}
+
+/// Scan the storage table of a materialized view, the columns are casted back to the view schema.
+pub fn materialized_view_plan(
+    view: &ViewSchema,
+    materialized: &MaterializedView,
+    storage: Arc<dyn TableSource>,
+) -> DFResult<LogicalPlan> {
+    let storage_schema = storage.schema();
+    let exprs = view
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| {
+            let column = Expr::Column(Column::from_name(f.name()));
+            let storage_type = storage_schema.field_with_name(f.name())?.data_type();
+            let expr = if storage_type == f.data_type() {
+                column
+            } else {
+                cast(column, f.data_type().clone())
+            };
+            Ok(expr.alias(f.name()))
+        })
+        .collect::<DFResult<Vec<_>>>()?;
+
+    LogicalPlanBuilder::scan(materialized.storage_table(), storage, None)?
+        .project(exprs)?
+        .build()
+}
+
+/// Replace the sub plans equal to the query of a materialized view with the scan of its storage table.
+///
+/// `views` are pairs of the plan of the view query and the plan from [`materialized_view_plan`].
+pub fn rewrite_with_materialized_views(
+    plan: LogicalPlan,
+    views: &[(LogicalPlan, LogicalPlan)],
+) -> DFResult<LogicalPlan> {
+    if views.is_empty() {
+        return Ok(plan);
+    }
+
+    plan.transform_down(&|node| {
+        for (definition, storage) in views {
+            if &node == definition {
+                if let Some(replacement) = replace_with_storage(&node, storage)? {
+                    return Ok(Transformed::Yes(replacement));
+                }
+            }
+        }
+        Ok(Transformed::No(node))
+    })
+}
+
+/// Keep the output columns of `original`, so that the parent plans still resolve them.
+fn replace_with_storage(
+    original: &LogicalPlan,
+    storage: &LogicalPlan,
+) -> DFResult<Option<LogicalPlan>> {
+    let original_fields = original.schema().fields();
+    let storage_fields = storage.schema().fields();
+    if original_fields.len() != storage_fields.len() {
+        return Ok(None);
+    }
+
+    let qualifiers = original_fields
+        .iter()
+        .filter_map(|f| f.qualifier())
+        .collect::<HashSet<_>>();
+    if qualifiers.len() > 1 {
+        return Ok(None);
+    }
+
+    let exprs = original_fields
+        .iter()
+        .zip(storage_fields)
+        .map(|(o, s)| Expr::Column(s.qualified_column()).alias(o.name()))
+        .collect::<Vec<_>>();
+    let builder = LogicalPlanBuilder::from(storage.clone()).project(exprs)?;
+    let builder = match qualifiers.into_iter().next() {
+        Some(qualifier) => builder.alias(qualifier.to_string())?,
+        None => builder,
+    };
+
+    Ok(Some(builder.build()?))
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::datasource::{provider_as_source, MemTable};
+    use datafusion::logical_expr::{col, LogicalPlanBuilder};
+    use datafusion::prelude::avg;
+
+    use super::{parse_view_query, rewrite_with_materialized_views};
+
+    #[test]
+    fn test_parse_view_query() {
+        assert!(parse_view_query("SELECT * FROM air").is_ok());
+        assert!(parse_view_query("DROP TABLE air").is_err());
+        assert!(parse_view_query("SELECT 1; SELECT 2").is_err());
+    }
+
+    #[test]
+    fn test_rewrite_with_materialized_views() {
+        let source = |fields| {
+            let schema = Arc::new(Schema::new(fields));
+            provider_as_source(Arc::new(MemTable::try_new(schema, vec![vec![]]).unwrap()))
+        };
+        let air = source(vec![
+            Field::new("station", DataType::Utf8, true),
+            Field::new("temperature", DataType::Float64, true),
+        ]);
+        let storage = source(vec![
+            Field::new("station", DataType::Utf8, true),
+            Field::new("temperature", DataType::Float64, true),
+        ]);
+
+        let definition = LogicalPlanBuilder::scan("air", air, None)
+            .unwrap()
+            .aggregate(vec![col("station")], vec![avg(col("temperature"))])
+            .unwrap()
+            .build()
+            .unwrap();
+        let storage = LogicalPlanBuilder::scan("storage", storage, None)
+            .unwrap()
+            .build()
+            .unwrap();
+        let plan = LogicalPlanBuilder::from(definition.clone())
+            .limit(0, Some(10))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let rewritten =
+            rewrite_with_materialized_views(plan.clone(), &[(definition, storage)]).unwrap();
+        assert_ne!(rewritten, plan);
+        assert_eq!(rewritten.schema().fields().len(), 2);
+        assert!(format!("{rewritten:?}").contains("TableScan: storage"));
+    }
+}
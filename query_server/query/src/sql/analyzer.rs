@@ -8,6 +8,7 @@ use spi::QueryResult;
 
 use crate::extension::analyse::add_time_for_tsgenfunc::AddTimeForTSGenFunc;
 use crate::extension::analyse::initial_plan_checker::InitialPlanChecker;
+use crate::extension::analyse::transform_asof_join::TransformAsofJoin;
 use crate::extension::analyse::transform_bottom_func_to_topk_node::TransformBottomFuncToTopkNodeRule;
 use crate::extension::analyse::transform_count_gen_time_col::TransformCountGenTimeColRule;
 use crate::extension::analyse::transform_exact_count_to_count::TransformExactCountToCountRule;
@@ -27,6 +28,7 @@ impl DefaultAnalyzer {
         let rules = &mut analyzer.rules;
         rules.insert(0, Arc::new(TransformUpdateRule::new()));
         rules.push(Arc::new(InitialPlanChecker {}));
+        rules.push(Arc::new(TransformAsofJoin));
         rules.push(Arc::new(TransformBottomFuncToTopkNodeRule {}));
         rules.push(Arc::new(TransformTopkFuncToTopkNodeRule {}));
         rules.push(Arc::new(TransformTimeWindowRule {}));
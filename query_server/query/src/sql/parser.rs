@@ -17,14 +17,14 @@ use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use serde_json::Value as JsonValue;
 use snafu::ResultExt;
 use spi::query::ast::{
-    self, parse_string_value, Action, AlterDatabase, AlterTable, AlterTableAction, AlterTenant,
-    AlterTenantOperation, AlterUser, AlterUserOperation, ChecksumGroup, ColumnOption,
-    CompactDatabase, CompactVnode, CopyIntoLocation, CopyIntoTable, CopyTarget, CopyVnode,
-    CreateDatabase, CreateRole, CreateStream, CreateTable, CreateTenant, CreateUser,
-    DatabaseConfig, DatabaseOptions, DescribeDatabase, DescribeTable, DropDatabaseObject,
-    DropGlobalObject, DropTenantObject, DropVnode, Explain, ExtStatement, GrantRevoke, MoveVnode,
-    OutputMode, Privilege, RecoverDatabase, RecoverTenant, ShowSeries, ShowTagBody, ShowTagValues,
-    Trigger, UriLocation, With,
+    self, parse_string_value, Action, AlterDatabase, AlterRoleOperation, AlterTable,
+    AlterTableAction, AlterTenant, AlterTenantOperation, AlterUser, AlterUserOperation,
+    ChecksumGroup, ColumnOption, CompactDatabase, CompactVnode, CopyIntoLocation, CopyIntoTable,
+    CopyTarget, CopyVnode, CreateDatabase, CreateRole, CreateStream, CreateTable, CreateTenant,
+    CreateUser, DatabaseConfig, DatabaseOptions, DescribeDatabase, DescribeTable,
+    DropDatabaseObject, DropGlobalObject, DropTenantObject, DropVnode, Explain, ExtStatement,
+    GrantRevoke, MoveVnode, OutputMode, Privilege, RecoverDatabase, RecoverTenant, ShowSeries,
+    ShowTagBody, ShowTagValues, TablePrivilege, Trigger, UriLocation, With,
 };
 use spi::query::logical_planner::{DatabaseObjectType, GlobalObjectType, TenantObjectType};
 use spi::query::parser::Parser as CnosdbParser;
@@ -32,6 +32,13 @@ use spi::ParserSnafu;
 use trace::debug;
 
 use super::dialect::CnosDBDialect;
+use crate::extension::expr::ASOF_MATCH;
+
+/// The placeholder `PIVOT(... FOR <tag> IN (ANY))` is rewritten into,
+/// the values are discovered from the tag values of the table while planning
+pub const PIVOT_ANY_VALUES: &str = "$pivot_any";
+/// The table function `<relation> UNPIVOT(...)` is rewritten into
+pub const UNPIVOT_FUNCTION: &str = "__unpivot";
 
 // support tag token
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +67,12 @@ enum CnosKeyWord {
     REPLICA,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     PRECISION,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    REPLICATE_TO,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    STANDBY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    CHANGE_FEED,
 
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     QUERIES,
@@ -139,6 +152,22 @@ enum CnosKeyWord {
     STRICT_WRITE,
     #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
     MAX_CACHE_READERS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    AGGREGATE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    TOKEN,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    TOKENS,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EXPIRES,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    EXPIRE,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    POLICY,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    POLICIES,
+    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+    MASKING,
 }
 
 impl FromStr for CnosKeyWord {
@@ -154,6 +183,9 @@ impl FromStr for CnosKeyWord {
             "VNODE_DURATION" => Ok(CnosKeyWord::VNODE_DURATION),
             "REPLICA" => Ok(CnosKeyWord::REPLICA),
             "PRECISION" => Ok(CnosKeyWord::PRECISION),
+            "REPLICATE_TO" => Ok(CnosKeyWord::REPLICATE_TO),
+            "STANDBY" => Ok(CnosKeyWord::STANDBY),
+            "CHANGE_FEED" => Ok(CnosKeyWord::CHANGE_FEED),
             "DATABASES" => Ok(CnosKeyWord::DATABASES),
             "QUERIES" => Ok(CnosKeyWord::QUERIES),
             "TENANT" => Ok(CnosKeyWord::TENANT),
@@ -193,6 +225,14 @@ impl FromStr for CnosKeyWord {
             "WAL_SYNC" => Ok(CnosKeyWord::WAL_SYNC),
             "STRICT_WRITE" => Ok(CnosKeyWord::STRICT_WRITE),
             "MAX_CACHE_READERS" => Ok(CnosKeyWord::MAX_CACHE_READERS),
+            "AGGREGATE" => Ok(CnosKeyWord::AGGREGATE),
+            "TOKEN" => Ok(CnosKeyWord::TOKEN),
+            "TOKENS" => Ok(CnosKeyWord::TOKENS),
+            "EXPIRES" => Ok(CnosKeyWord::EXPIRES),
+            "EXPIRE" => Ok(CnosKeyWord::EXPIRE),
+            "POLICY" => Ok(CnosKeyWord::POLICY),
+            "POLICIES" => Ok(CnosKeyWord::POLICIES),
+            "MASKING" => Ok(CnosKeyWord::MASKING),
             _ => Err(ParserError::ParserError(format!(
                 "fail parse {} to CnosKeyWord",
                 s
@@ -233,7 +273,8 @@ impl<'a> ExtParser<'a> {
     /// Parse the specified tokens with dialect
     fn new_with_dialect(sql: &str, dialect: &'a dyn Dialect) -> Result<Self> {
         let mut tokenizer = Tokenizer::new(dialect, sql);
-        let tokens = tokenizer.tokenize()?;
+        let tokens = rewrite_asof_joins(tokenizer.tokenize()?)?;
+        let tokens = rewrite_unpivots(rewrite_pivot_any(tokens))?;
         Ok(ExtParser {
             parser: Parser::new(dialect).with_tokens(tokens),
         })
@@ -393,6 +434,12 @@ impl<'a> ExtParser<'a> {
             Ok(ExtStatement::ShowStreams(ast::ShowStreams { verbose }))
         } else if self.parse_cnos_keyword(CnosKeyWord::REPLICAS) {
             self.parse_show_replicas()
+        } else if self.parser.parse_keyword(Keyword::FUNCTIONS) {
+            Ok(ExtStatement::ShowFunctions)
+        } else if self.parse_cnos_keyword(CnosKeyWord::TOKENS) {
+            Ok(ExtStatement::ShowTokens)
+        } else if self.parse_cnos_keyword(CnosKeyWord::POLICIES) {
+            Ok(ExtStatement::ShowPolicies)
         } else {
             parser_err!(format!("nonsupport: {}", self.parser.peek_token()))
         }
@@ -590,8 +637,10 @@ impl<'a> ExtParser<'a> {
             self.parse_alter_tenant()
         } else if self.parser.parse_keyword(Keyword::USER) {
             self.parse_alter_user()
+        } else if self.parser.parse_keyword(Keyword::ROLE) {
+            self.parse_alter_role()
         } else {
-            self.expected("TABLE/DATABASE/TENANT/USER", self.parser.peek_token())
+            self.expected("TABLE/DATABASE/TENANT/USER/ROLE", self.parser.peek_token())
         }
     }
 
@@ -684,7 +733,7 @@ impl<'a> ExtParser<'a> {
             ));
         }
         if config.has_some() {
-            return parser_err!("database config is unmodifiable, only can modify database option: TTL, SHARD, VNODE_DURATION, REPLICA".to_string());
+            return parser_err!("database config is unmodifiable, only can modify database option: TTL, SHARD, VNODE_DURATION, REPLICA, REPLICATE_TO, STANDBY, CHANGE_FEED".to_string());
         }
         Ok(ExtStatement::AlterDatabase(
             AlterDatabase {
@@ -864,13 +913,41 @@ impl<'a> ExtParser<'a> {
         } else if self.parser.parse_keyword(Keyword::SET) {
             let sql_option = ExtParser::parse_sql_option(&mut self.parser)?;
             AlterUserOperation::Set(sql_option)
+        } else if self.parser.parse_keyword(Keyword::PASSWORD) {
+            if !self.parse_cnos_keyword(CnosKeyWord::EXPIRE) {
+                return self.expected("EXPIRE", self.parser.peek_token());
+            }
+            // The user has to change the password at the next login
+            AlterUserOperation::Set(SqlOption {
+                name: Ident::new("must_change_password"),
+                value: Value::Boolean(true),
+            })
         } else {
-            self.expected("RENAME,SET", self.parser.peek_token())?
+            self.expected("RENAME,SET,PASSWORD EXPIRE", self.parser.peek_token())?
         };
 
         Ok(ExtStatement::AlterUser(AlterUser { name, operation }))
     }
 
+    /// e.g.
+    /// ALTER ROLE analyst SET max_query_memory = '1GiB';
+    /// ALTER ROLE analyst UNSET max_query_memory;
+    fn parse_alter_role(&mut self) -> Result<ExtStatement> {
+        let name = self.parser.parse_identifier()?;
+
+        let operation = if self.parser.parse_keyword(Keyword::SET) {
+            let sql_option = ExtParser::parse_sql_option(&mut self.parser)?;
+            AlterRoleOperation::Set(sql_option)
+        } else if self.parse_cnos_keyword(CnosKeyWord::UNSET) {
+            let ident = self.parser.parse_identifier()?;
+            AlterRoleOperation::UnSet(ident)
+        } else {
+            self.expected("SET,UNSET", self.parser.peek_token())?
+        };
+
+        Ok(ExtStatement::AlterRole(ast::AlterRole { name, operation }))
+    }
+
     /// Parses the set of
     fn parse_file_compression_type(&mut self) -> Result<CompressionTypeVariant, ParserError> {
         let token = self.parser.next_token();
@@ -1093,6 +1170,15 @@ impl<'a> ExtParser<'a> {
                 return parser_err!("replica number should be greater than 0");
             }
             options.replica = Some(replica);
+        } else if self.parse_cnos_keyword(CnosKeyWord::REPLICATE_TO) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.replicate_to = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::STANDBY) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.standby = Some(self.parse_string_value()?);
+        } else if self.parse_cnos_keyword(CnosKeyWord::CHANGE_FEED) {
+            let _ = self.parser.expect_token(&Token::Eq);
+            options.change_feed = Some(self.parse_string_value()?);
         } else if self.parse_cnos_keyword(CnosKeyWord::PRECISION) {
             let _ = self.parser.expect_token(&Token::Eq);
             config.precision = Some(self.parse_string_value()?);
@@ -1222,19 +1308,42 @@ impl<'a> ExtParser<'a> {
         }
     }
 
-    fn parse_privilege(&mut self) -> Result<Privilege, ParserError> {
-        let action = self.parse_grant_permission()?;
-        self.parser.expect_keyword(Keyword::ON)?;
-        self.parser.expect_keyword(Keyword::DATABASE)?;
-        let database = self.parser.parse_identifier()?;
-        Ok(Privilege { action, database })
+    /// e.g. `read on database db1, write on table db1.t1 (c1, c2)`
+    fn parse_privileges(&mut self) -> Result<(Vec<Privilege>, Vec<TablePrivilege>), ParserError> {
+        let mut privileges = vec![];
+        let mut table_privileges = vec![];
+        loop {
+            let action = self.parse_grant_permission()?;
+            self.parser.expect_keyword(Keyword::ON)?;
+            if self.parser.parse_keyword(Keyword::TABLE) {
+                let table = self.parser.parse_object_name()?;
+                let columns = self
+                    .parser
+                    .parse_parenthesized_column_list(IsOptional::Optional, false)?;
+                table_privileges.push(TablePrivilege {
+                    action,
+                    table,
+                    columns,
+                });
+            } else {
+                self.parser.expect_keyword(Keyword::DATABASE)?;
+                let database = self.parser.parse_identifier()?;
+                privileges.push(Privilege { action, database });
+            }
+
+            if !self.parser.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+        Ok((privileges, table_privileges))
     }
 
     fn parse_grant(&mut self) -> Result<ExtStatement> {
         // grant read on database "db1" to [role] rrr;
         // grant write on database "db2" to rrr;
         // grant all on database "db3" to rrr;
-        let privileges = self.parse_comma_separated(ExtParser::parse_privilege)?;
+        // grant read on table "db1"."t1" (c1, c2) to rrr;
+        let (privileges, table_privileges) = self.parse_privileges()?;
 
         self.parser.expect_keyword(Keyword::TO)?;
         let _ = self.parser.parse_keyword(Keyword::ROLE);
@@ -1244,6 +1353,7 @@ impl<'a> ExtParser<'a> {
         Ok(ExtStatement::GrantRevoke(GrantRevoke {
             is_grant: true,
             privileges,
+            table_privileges,
             role_name,
         }))
     }
@@ -1252,7 +1362,8 @@ impl<'a> ExtParser<'a> {
         // revoke read on database "db1" from [role] rrr;
         // revoke write on database "db2" from rrr;
         // revoke all on database "db3" from rrr;
-        let privileges = self.parse_comma_separated(ExtParser::parse_privilege)?;
+        // revoke read on table "db1"."t1" (c1) from rrr;
+        let (privileges, table_privileges) = self.parse_privileges()?;
 
         self.parser.expect_keyword(Keyword::FROM)?;
         let _ = self.parser.parse_keyword(Keyword::ROLE);
@@ -1262,6 +1373,7 @@ impl<'a> ExtParser<'a> {
         Ok(ExtStatement::GrantRevoke(GrantRevoke {
             is_grant: false,
             privileges,
+            table_privileges,
             role_name,
         }))
     }
@@ -1310,6 +1422,86 @@ impl<'a> ExtParser<'a> {
             inherit,
         }))
     }
+
+    /// e.g.
+    /// CREATE TOKEN telegraf FOR USER writer WITH PRIVILEGES WRITE ON db1, READ ON db2 EXPIRES '90d';
+    fn parse_create_token(&mut self) -> Result<ExtStatement> {
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
+        let name = self.parser.parse_identifier()?;
+        check_name_not_contain_illegal_character(&ObjectName(vec![name.clone()]))?;
+
+        self.parser
+            .expect_keywords(&[Keyword::FOR, Keyword::USER])?;
+        let user = self.parser.parse_identifier()?;
+
+        self.parser
+            .expect_keywords(&[Keyword::WITH, Keyword::PRIVILEGES])?;
+        let privileges = self.parse_comma_separated(|parser| {
+            let action = parser.parse_grant_permission()?;
+            parser.parser.expect_keyword(Keyword::ON)?;
+            let _ = parser.parser.parse_keyword(Keyword::DATABASE);
+            let database = parser.parser.parse_identifier()?;
+            Ok(Privilege { action, database })
+        })?;
+
+        let expires = if self.parse_cnos_keyword(CnosKeyWord::EXPIRES) {
+            Some(self.parser.parse_literal_string()?)
+        } else {
+            None
+        };
+
+        Ok(ExtStatement::CreateToken(ast::CreateToken {
+            if_not_exists,
+            name,
+            user,
+            privileges,
+            expires,
+        }))
+    }
+
+    /// e.g.
+    /// CREATE POLICY tenant_rows ON db1.metrics FOR ROLE customer USING (tag_customer = current_user());
+    /// CREATE MASKING POLICY mask_phone ON db1.users (phone) FOR ROLE analyst USING (mask_partial(phone, 3, 4));
+    fn parse_create_policy(&mut self, masking: bool) -> Result<ExtStatement> {
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+
+        let name = self.parser.parse_identifier()?;
+        check_name_not_contain_illegal_character(&ObjectName(vec![name.clone()]))?;
+
+        self.parser.expect_keyword(Keyword::ON)?;
+        let table = self.parser.parse_object_name()?;
+        let column = if masking {
+            self.parser.expect_token(&Token::LParen)?;
+            let column = self.parser.parse_identifier()?;
+            self.parser.expect_token(&Token::RParen)?;
+            Some(column)
+        } else {
+            None
+        };
+
+        self.parser.expect_keyword(Keyword::FOR)?;
+        let _ = self.parser.parse_keyword(Keyword::ROLE);
+        let role = self.parser.parse_identifier()?;
+
+        self.parser.expect_keyword(Keyword::USING)?;
+        self.parser.expect_token(&Token::LParen)?;
+        let using = self.parser.parse_expr()?;
+        self.parser.expect_token(&Token::RParen)?;
+
+        Ok(ExtStatement::CreatePolicy(ast::CreatePolicy {
+            if_not_exists,
+            name,
+            table,
+            column,
+            role,
+            using,
+        }))
+    }
     // --------------------------------------------------------------------------------------------------------------------------------------------------------------------------------
     fn parse_create_tenant(&mut self) -> Result<ExtStatement> {
         let if_not_exists =
@@ -1674,13 +1866,92 @@ impl<'a> ExtParser<'a> {
             self.parse_create_user()
         } else if self.parser.parse_keyword(Keyword::ROLE) {
             self.parse_create_role()
+        } else if self.parse_cnos_keyword(CnosKeyWord::TOKEN) {
+            self.parse_create_token()
+        } else if self.parse_cnos_keyword(CnosKeyWord::POLICY) {
+            self.parse_create_policy(false)
+        } else if self.parse_cnos_keyword(CnosKeyWord::MASKING) {
+            self.expect_cnos_keyword(CnosKeyWord::POLICY)?;
+            self.parse_create_policy(true)
         } else if self.parse_cnos_keyword(CnosKeyWord::STREAM) {
             self.parse_create_stream()
+        } else if self.parser.parse_keywords(&[Keyword::OR, Keyword::REPLACE]) {
+            if self.parse_cnos_keyword(CnosKeyWord::AGGREGATE) {
+                self.parser.expect_keyword(Keyword::FUNCTION)?;
+                self.parse_create_function(true, true)
+            } else if self.parser.parse_keyword(Keyword::FUNCTION) {
+                self.parse_create_function(true, false)
+            } else {
+                self.parse_create_view(true)
+            }
+        } else if self.parse_cnos_keyword(CnosKeyWord::AGGREGATE) {
+            self.parser.expect_keyword(Keyword::FUNCTION)?;
+            self.parse_create_function(false, true)
+        } else if self.parser.parse_keyword(Keyword::FUNCTION) {
+            self.parse_create_function(false, false)
+        } else if matches!(
+            self.parser.peek_token().token,
+            Token::Word(ref w) if w.keyword == Keyword::MATERIALIZED || w.keyword == Keyword::VIEW
+        ) {
+            self.parse_create_view(false)
         } else {
             self.expected("an object type after CREATE", self.parser.peek_token())
         }
     }
 
+    /// e.g.
+    /// CREATE MATERIALIZED VIEW air_1m WITH (refresh_interval = '1m') AS
+    ///   SELECT date_bin(INTERVAL '1 minute', time) AS time, station, avg(temperature) AS temperature
+    ///   FROM air GROUP BY date_bin(INTERVAL '1 minute', time), station;
+    fn parse_create_view(&mut self, or_replace: bool) -> Result<ExtStatement> {
+        Ok(ExtStatement::SqlStatement(Box::new(
+            self.parser.parse_create_view(or_replace)?,
+        )))
+    }
+
+    /// e.g.
+    /// CREATE FUNCTION c_to_f(c DOUBLE) RETURNS DOUBLE AS 'c * 9 / 5 + 32';
+    /// CREATE AGGREGATE FUNCTION wsum(v DOUBLE, w DOUBLE) RETURNS DOUBLE LANGUAGE WASM AS '<base64>';
+    fn parse_create_function(&mut self, or_replace: bool, aggregate: bool) -> Result<ExtStatement> {
+        let name = self.parser.parse_identifier()?;
+        check_name_not_contain_illegal_character(&ObjectName(vec![name.clone()]))?;
+
+        self.parser.expect_token(&Token::LParen)?;
+        let args = if self.parser.consume_token(&Token::RParen) {
+            vec![]
+        } else {
+            let args = self.parser.parse_comma_separated(|parser| {
+                let name = parser.parse_identifier()?;
+                let data_type = parser.parse_data_type()?;
+                Ok((name, data_type))
+            })?;
+            self.parser.expect_token(&Token::RParen)?;
+            args
+        };
+
+        self.parser.expect_keyword(Keyword::RETURNS)?;
+        let return_type = self.parser.parse_data_type()?;
+
+        let language = if self.parser.parse_keyword(Keyword::LANGUAGE) {
+            Some(self.parser.parse_identifier()?)
+        } else {
+            None
+        };
+
+        self.parser.expect_keyword(Keyword::AS)?;
+        let body = self.parse_string_value()?;
+
+        Ok(ExtStatement::CreateFunction(ast::CreateFunction {
+            or_replace,
+            aggregate,
+            name,
+            args,
+            return_type,
+            language,
+            body,
+        }))
+    }
+
     /// Parse a copy statement
     fn parse_copy(&mut self) -> Result<ExtStatement> {
         if self.parse_cnos_keyword(CnosKeyWord::VNODE) {
@@ -1939,9 +2210,48 @@ impl<'a> ExtParser<'a> {
             let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
             let name = self.parser.parse_identifier()?;
             ExtStatement::DropStream(ast::DropStream { if_exist, name })
+        } else if self.parser.parse_keyword(Keyword::VIEW)
+            || self
+                .parser
+                .parse_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])
+        {
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let object_name = self.parser.parse_object_name()?;
+            ExtStatement::DropDatabaseObject(DropDatabaseObject {
+                object_name,
+                if_exist,
+                obj_type: DatabaseObjectType::View,
+            })
+        } else if self.parser.parse_keyword(Keyword::FUNCTION) {
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let object_name = self.parser.parse_identifier()?;
+            ExtStatement::DropTenantObject(DropTenantObject {
+                object_name,
+                if_exist,
+                obj_type: TenantObjectType::Function,
+                after: None,
+            })
+        } else if self.parse_cnos_keyword(CnosKeyWord::TOKEN) {
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let object_name = self.parser.parse_identifier()?;
+            ExtStatement::DropTenantObject(DropTenantObject {
+                object_name,
+                if_exist,
+                obj_type: TenantObjectType::Token,
+                after: None,
+            })
+        } else if self.parse_cnos_keyword(CnosKeyWord::POLICY) {
+            let if_exist = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+            let object_name = self.parser.parse_identifier()?;
+            ExtStatement::DropTenantObject(DropTenantObject {
+                object_name,
+                if_exist,
+                obj_type: TenantObjectType::Policy,
+                after: None,
+            })
         } else {
             return self.expected(
-                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM after DROP",
+                "TABLE,DATABASE,TENANT,USER,ROLE,VNODE,STREAM,VIEW,FUNCTION,TOKEN,POLICY after DROP",
                 self.parser.peek_token(),
             );
         };
@@ -2202,6 +2512,317 @@ fn check_name_not_contain_illegal_character(object_name: &ObjectName) -> Result<
     Ok(())
 }
 
+/// Rewrites every
+/// `ASOF [LEFT [OUTER]] JOIN <relation> [ON <condition>] MATCH_CONDITION(<time comparison>) [TOLERANCE INTERVAL '<duration>']`
+/// into `[LEFT [OUTER]] JOIN <relation> ON (<condition>) AND asof_match(<time comparison>[, INTERVAL '<duration>'])`,
+/// which is turned into an AsofJoin node during analysis.
+fn rewrite_asof_joins(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    while let Some((asof_idx, join_idx)) = find_asof_join(&tokens) {
+        tokens = rewrite_asof_join(tokens, asof_idx, join_idx)?;
+    }
+    Ok(tokens)
+}
+
+fn is_word(token: &Token, word: &str) -> bool {
+    matches!(token, Token::Word(w) if w.quote_style.is_none() && w.value.eq_ignore_ascii_case(word))
+}
+
+fn next_non_whitespace(tokens: &[Token], idx: usize) -> Option<usize> {
+    (idx..tokens.len()).find(|i| !matches!(tokens[*i], Token::Whitespace(_)))
+}
+
+/// Returns the positions of the first `ASOF` and its `JOIN` keyword.
+fn find_asof_join(tokens: &[Token]) -> Option<(usize, usize)> {
+    tokens.iter().enumerate().find_map(|(idx, token)| {
+        if !is_word(token, "ASOF") {
+            return None;
+        }
+        let mut next = next_non_whitespace(tokens, idx + 1)?;
+        if is_word(&tokens[next], "LEFT") {
+            next = next_non_whitespace(tokens, next + 1)?;
+            if is_word(&tokens[next], "OUTER") {
+                next = next_non_whitespace(tokens, next + 1)?;
+            }
+        }
+        is_word(&tokens[next], "JOIN").then_some((idx, next))
+    })
+}
+
+fn rewrite_asof_join(
+    tokens: Vec<Token>,
+    asof_idx: usize,
+    join_idx: usize,
+) -> Result<Vec<Token>, ParserError> {
+    // Find the MATCH_CONDITION of this join, skipping nested queries
+    let mut depth = 0;
+    let mut on_idx = None;
+    let mut match_idx = None;
+    for (idx, token) in tokens.iter().enumerate().skip(join_idx + 1) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen if depth == 0 => break,
+            Token::RParen => depth -= 1,
+            Token::SemiColon => break,
+            _ if depth > 0 => {}
+            _ if is_word(token, "JOIN") => break,
+            _ if is_word(token, "ON") => on_idx = Some(idx),
+            _ if is_word(token, "MATCH_CONDITION") => {
+                match_idx = Some(idx);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let match_idx = match match_idx {
+        Some(idx) => idx,
+        None => return parser_err!("Expected MATCH_CONDITION after ASOF JOIN"),
+    };
+
+    let lparen = match next_non_whitespace(&tokens, match_idx + 1) {
+        Some(idx) if tokens[idx] == Token::LParen => idx,
+        _ => return parser_err!("Expected ( after MATCH_CONDITION"),
+    };
+    let mut depth = 0;
+    let mut rparen = None;
+    for (idx, token) in tokens.iter().enumerate().skip(lparen) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                depth -= 1;
+                if depth == 0 {
+                    rparen = Some(idx);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let rparen = match rparen {
+        Some(idx) => idx,
+        None => return parser_err!("Expected ) after MATCH_CONDITION"),
+    };
+
+    let mut marker = vec![Token::make_word(ASOF_MATCH, None), Token::LParen];
+    marker.extend_from_slice(&tokens[lparen + 1..rparen]);
+    let mut end = rparen + 1;
+    if let Some(tolerance_idx) =
+        next_non_whitespace(&tokens, end).filter(|idx| is_word(&tokens[*idx], "TOLERANCE"))
+    {
+        let interval_idx = next_non_whitespace(&tokens, tolerance_idx + 1)
+            .filter(|idx| is_word(&tokens[*idx], "INTERVAL"));
+        let value_idx = interval_idx
+            .and_then(|idx| next_non_whitespace(&tokens, idx + 1))
+            .filter(|idx| matches!(tokens[*idx], Token::SingleQuotedString(_)));
+        match (interval_idx, value_idx) {
+            (Some(interval_idx), Some(value_idx)) => {
+                marker.push(Token::Comma);
+                marker.extend_from_slice(&tokens[interval_idx..=value_idx]);
+                end = value_idx + 1;
+            }
+            _ => return parser_err!("Expected INTERVAL '<duration>' after TOLERANCE"),
+        }
+    }
+    marker.push(Token::RParen);
+
+    let mut result = Vec::with_capacity(tokens.len() + 4);
+    result.extend_from_slice(&tokens[..asof_idx]);
+    match on_idx {
+        Some(on_idx) => {
+            result.extend_from_slice(&tokens[asof_idx + 1..=on_idx]);
+            result.push(Token::LParen);
+            result.extend_from_slice(&tokens[on_idx + 1..match_idx]);
+            result.push(Token::RParen);
+            result.push(Token::make_keyword("AND"));
+        }
+        None => {
+            result.extend_from_slice(&tokens[asof_idx + 1..match_idx]);
+            result.push(Token::make_keyword("ON"));
+        }
+    }
+    result.extend(marker);
+    result.extend_from_slice(&tokens[end..]);
+
+    Ok(result)
+}
+
+/// Rewrites every `IN (ANY)` into `IN (<PIVOT_ANY_VALUES placeholder>)`, which is only
+/// accepted as the values of `PIVOT(<aggregate> FOR <tag> IN (ANY))`.
+fn rewrite_pivot_any(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut idx = 0;
+    while idx < tokens.len() {
+        if is_word(&tokens[idx], "IN") {
+            let lparen =
+                next_non_whitespace(&tokens, idx + 1).filter(|i| tokens[*i] == Token::LParen);
+            let any = lparen
+                .and_then(|i| next_non_whitespace(&tokens, i + 1))
+                .filter(|i| is_word(&tokens[*i], "ANY"));
+            let rparen = any
+                .and_then(|i| next_non_whitespace(&tokens, i + 1))
+                .filter(|i| tokens[*i] == Token::RParen);
+            if let (Some(any), Some(_)) = (any, rparen) {
+                tokens[any] = Token::Placeholder(PIVOT_ANY_VALUES.to_string());
+            }
+        }
+        idx += 1;
+    }
+    tokens
+}
+
+/// Rewrites every `<relation> UNPIVOT(<value column> FOR <name column> IN (<columns>))` into
+/// `__unpivot((SELECT * FROM <relation>), <value column>, <name column>, <columns>)`,
+/// which is expanded into a `UNION ALL` of the columns while planning.
+fn rewrite_unpivots(mut tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    while let Some(unpivot_idx) = find_unpivot(&tokens) {
+        tokens = rewrite_unpivot(tokens, unpivot_idx)?;
+    }
+    Ok(tokens)
+}
+
+fn prev_non_whitespace(tokens: &[Token], idx: usize) -> Option<usize> {
+    (0..idx)
+        .rev()
+        .find(|i| !matches!(tokens[*i], Token::Whitespace(_)))
+}
+
+fn find_unpivot(tokens: &[Token]) -> Option<usize> {
+    tokens.iter().enumerate().position(|(idx, token)| {
+        is_word(token, "UNPIVOT")
+            && next_non_whitespace(tokens, idx + 1).is_some_and(|i| tokens[i] == Token::LParen)
+    })
+}
+
+/// Returns the position of the parenthesis matching the one at `lparen`.
+fn matching_rparen(tokens: &[Token], lparen: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (idx, token) in tokens.iter().enumerate().skip(lparen) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the position of the parenthesis matching the one at `rparen`.
+fn matching_lparen(tokens: &[Token], rparen: usize) -> Option<usize> {
+    let mut depth = 0;
+    for idx in (0..=rparen).rev() {
+        match tokens[idx] {
+            Token::RParen => depth += 1,
+            Token::LParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the position of the first token of the relation ending at `end`,
+/// the relation is a table name or a subquery, either with an optional alias.
+fn relation_start(tokens: &[Token], end: usize) -> Option<usize> {
+    let mut idx = end;
+    if matches!(tokens[idx], Token::Word(_)) {
+        if let Some(prev) = prev_non_whitespace(tokens, idx) {
+            if is_word(&tokens[prev], "AS") {
+                idx = prev_non_whitespace(tokens, prev)?;
+            } else if tokens[prev] == Token::RParen
+                || (matches!(tokens[prev], Token::Word(_))
+                    && !is_word(&tokens[prev], "FROM")
+                    && !is_word(&tokens[prev], "JOIN"))
+            {
+                idx = prev;
+            }
+        }
+    }
+
+    if tokens[idx] == Token::RParen {
+        return matching_lparen(tokens, idx);
+    }
+    if !matches!(tokens[idx], Token::Word(_)) {
+        return None;
+    }
+    while let Some(period) =
+        prev_non_whitespace(tokens, idx).filter(|i| tokens[*i] == Token::Period)
+    {
+        idx =
+            prev_non_whitespace(tokens, period).filter(|i| matches!(tokens[*i], Token::Word(_)))?;
+    }
+    Some(idx)
+}
+
+fn rewrite_unpivot(tokens: Vec<Token>, unpivot_idx: usize) -> Result<Vec<Token>, ParserError> {
+    let end = match prev_non_whitespace(&tokens, unpivot_idx) {
+        Some(end) => end,
+        None => return parser_err!("Expected a relation before UNPIVOT"),
+    };
+    let start = match relation_start(&tokens, end) {
+        Some(start) => start,
+        None => return parser_err!("Expected a table or a subquery before UNPIVOT"),
+    };
+
+    let lparen = match next_non_whitespace(&tokens, unpivot_idx + 1) {
+        Some(idx) => idx,
+        None => return parser_err!("Expected ( after UNPIVOT"),
+    };
+    let rparen = match matching_rparen(&tokens, lparen) {
+        Some(idx) => idx,
+        None => return parser_err!("Expected ) after UNPIVOT"),
+    };
+
+    // UNPIVOT(<value column> FOR <name column> IN (<columns>))
+    let for_idx = (lparen + 1..rparen).find(|i| is_word(&tokens[*i], "FOR"));
+    let in_idx = for_idx.and_then(|f| (f + 1..rparen).find(|i| is_word(&tokens[*i], "IN")));
+    let columns_lparen = in_idx
+        .and_then(|i| next_non_whitespace(&tokens, i + 1))
+        .filter(|i| tokens[*i] == Token::LParen);
+    let columns_rparen = columns_lparen.and_then(|i| matching_rparen(&tokens, i));
+    let (for_idx, in_idx, columns_lparen, columns_rparen) =
+        match (for_idx, in_idx, columns_lparen, columns_rparen) {
+            (Some(f), Some(i), Some(l), Some(r))
+                if next_non_whitespace(&tokens, r + 1) == Some(rparen) =>
+            {
+                (f, i, l, r)
+            }
+            _ => {
+                return parser_err!(
+                    "Expected UNPIVOT(<value column> FOR <name column> IN (<columns>))"
+                )
+            }
+        };
+
+    let mut result = Vec::with_capacity(tokens.len() + 8);
+    result.extend_from_slice(&tokens[..start]);
+    result.extend([
+        Token::make_word(UNPIVOT_FUNCTION, None),
+        Token::LParen,
+        Token::LParen,
+        Token::make_keyword("SELECT"),
+        Token::Mul,
+        Token::make_keyword("FROM"),
+    ]);
+    result.extend_from_slice(&tokens[start..=end]);
+    result.extend([Token::RParen, Token::Comma]);
+    result.extend_from_slice(&tokens[lparen + 1..for_idx]);
+    result.push(Token::Comma);
+    result.extend_from_slice(&tokens[for_idx + 1..in_idx]);
+    result.push(Token::Comma);
+    result.extend_from_slice(&tokens[columns_lparen + 1..columns_rparen]);
+    result.push(Token::RParen);
+    result.extend_from_slice(&tokens[rparen + 1..]);
+
+    Ok(result)
+}
+
 /// This is a copy of the equivalent implementation in Datafusion.
 fn parse_file_type(s: &str) -> Result<String, ParserError> {
     Ok(s.to_uppercase())
@@ -2678,6 +3299,9 @@ mod tests {
                         shard_num: Some(5),
                         vnode_duration: Some("3d".to_string()),
                         replica: Some(10),
+                        replicate_to: None,
+                        standby: None,
+                        change_feed: None,
                     },
                     config: DatabaseConfig {
                         precision: Some("us".to_string()),
@@ -2713,6 +3337,9 @@ mod tests {
                         shard_num: Some(6),
                         vnode_duration: Some("730.5d".to_string()),
                         replica: Some(1),
+                        replicate_to: None,
+                        standby: None,
+                        change_feed: None,
                     },
                     config: DatabaseConfig {
                         precision: Some("us".to_string()),
@@ -3252,4 +3879,351 @@ mod tests {
             _ => panic!("impossible"),
         }
     }
+
+    #[test]
+    fn test_create_view() {
+        let statement = parse_sql(
+            "CREATE MATERIALIZED VIEW air_1m WITH (refresh_interval = '1m') AS
+            SELECT time, station, temperature FROM air;",
+        );
+        match statement {
+            ExtStatement::SqlStatement(s) => match *s {
+                Statement::CreateView {
+                    or_replace,
+                    materialized,
+                    name,
+                    with_options,
+                    ..
+                } => {
+                    assert!(!or_replace);
+                    assert!(materialized);
+                    assert_eq!(name.to_string(), "air_1m");
+                    assert_eq!(
+                        with_options,
+                        vec![SqlOption {
+                            name: "refresh_interval".into(),
+                            value: Value::SingleQuotedString("1m".into()),
+                        }]
+                    );
+                }
+                _ => panic!("expected create view"),
+            },
+            _ => panic!("expected create view"),
+        }
+
+        let statement = parse_sql("CREATE OR REPLACE VIEW v AS SELECT * FROM air;");
+        assert!(matches!(
+            statement,
+            ExtStatement::SqlStatement(s) if matches!(
+                *s,
+                Statement::CreateView { or_replace: true, materialized: false, .. }
+            )
+        ));
+    }
+
+    #[test]
+    fn test_drop_view() {
+        let result = parse_sql("drop materialized view if exists db.air_1m;");
+
+        let expected = ExtStatement::DropDatabaseObject(DropDatabaseObject {
+            object_name: ObjectName(vec![Ident::new("db"), Ident::new("air_1m")]),
+            if_exist: true,
+            obj_type: DatabaseObjectType::View,
+        });
+
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_create_function() {
+        let result = parse_sql(
+            "CREATE OR REPLACE FUNCTION c_to_f(c DOUBLE) RETURNS DOUBLE AS 'c * 9 / 5 + 32';",
+        );
+
+        let expected = ExtStatement::CreateFunction(ast::CreateFunction {
+            or_replace: true,
+            aggregate: false,
+            name: Ident::new("c_to_f"),
+            args: vec![(Ident::new("c"), DataType::Double)],
+            return_type: DataType::Double,
+            language: None,
+            body: "c * 9 / 5 + 32".to_string(),
+        });
+        assert_eq!(expected, result);
+
+        let result = parse_sql(
+            "CREATE AGGREGATE FUNCTION wsum(v DOUBLE, w DOUBLE) RETURNS DOUBLE LANGUAGE WASM AS 'AGFzbQ==';",
+        );
+        match result {
+            ExtStatement::CreateFunction(ast::CreateFunction {
+                or_replace,
+                aggregate,
+                args,
+                language,
+                ..
+            }) => {
+                assert!(!or_replace);
+                assert!(aggregate);
+                assert_eq!(args.len(), 2);
+                assert_eq!(language, Some(Ident::new("WASM")));
+            }
+            _ => panic!("expected create function"),
+        }
+    }
+
+    #[test]
+    fn test_drop_function() {
+        let result = parse_sql("drop function if exists c_to_f;");
+
+        let expected = ExtStatement::DropTenantObject(DropTenantObject {
+            object_name: Ident::new("c_to_f"),
+            if_exist: true,
+            obj_type: TenantObjectType::Function,
+            after: None,
+        });
+
+        assert_eq!(expected, result);
+        assert_eq!(parse_sql("show functions;"), ExtStatement::ShowFunctions);
+    }
+
+    #[test]
+    fn test_grant_table() {
+        let result =
+            parse_sql("grant read on table db1.t1 (c1, c2), write on database db2 to role r1;");
+        let expected = ExtStatement::GrantRevoke(GrantRevoke {
+            is_grant: true,
+            privileges: vec![Privilege {
+                action: Action::Write,
+                database: Ident::new("db2"),
+            }],
+            table_privileges: vec![TablePrivilege {
+                action: Action::Read,
+                table: ObjectName(vec![Ident::new("db1"), Ident::new("t1")]),
+                columns: vec![Ident::new("c1"), Ident::new("c2")],
+            }],
+            role_name: Ident::new("r1"),
+        });
+        assert_eq!(expected, result);
+
+        let result = parse_sql("revoke all on table t1 from r1;");
+        let expected = ExtStatement::GrantRevoke(GrantRevoke {
+            is_grant: false,
+            privileges: vec![],
+            table_privileges: vec![TablePrivilege {
+                action: Action::All,
+                table: ObjectName(vec![Ident::new("t1")]),
+                columns: vec![],
+            }],
+            role_name: Ident::new("r1"),
+        });
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_create_token() {
+        let result = parse_sql(
+            "create token telegraf for user writer with privileges write on db1, read on database db2 expires '90d';",
+        );
+
+        let expected = ExtStatement::CreateToken(ast::CreateToken {
+            if_not_exists: false,
+            name: Ident::new("telegraf"),
+            user: Ident::new("writer"),
+            privileges: vec![
+                Privilege {
+                    action: Action::Write,
+                    database: Ident::new("db1"),
+                },
+                Privilege {
+                    action: Action::Read,
+                    database: Ident::new("db2"),
+                },
+            ],
+            expires: Some("90d".to_string()),
+        });
+        assert_eq!(expected, result);
+
+        let result = parse_sql("drop token if exists telegraf;");
+        let expected = ExtStatement::DropTenantObject(DropTenantObject {
+            object_name: Ident::new("telegraf"),
+            if_exist: true,
+            obj_type: TenantObjectType::Token,
+            after: None,
+        });
+        assert_eq!(expected, result);
+        assert_eq!(parse_sql("show tokens;"), ExtStatement::ShowTokens);
+    }
+
+    #[test]
+    fn test_create_policy() {
+        let result = parse_sql(
+            "create policy customer_rows on db1.metrics for role customer using (tag_customer = current_user());",
+        );
+
+        match result {
+            ExtStatement::CreatePolicy(ast::CreatePolicy {
+                if_not_exists,
+                name,
+                table,
+                column,
+                role,
+                using,
+            }) => {
+                assert!(!if_not_exists);
+                assert_eq!(name, Ident::new("customer_rows"));
+                assert_eq!(table.to_string(), "db1.metrics");
+                assert_eq!(column, None);
+                assert_eq!(role, Ident::new("customer"));
+                assert_eq!(using.to_string(), "tag_customer = current_user()");
+            }
+            _ => panic!("failed"),
+        }
+
+        let result = parse_sql("drop policy if exists customer_rows;");
+        let expected = ExtStatement::DropTenantObject(DropTenantObject {
+            object_name: Ident::new("customer_rows"),
+            if_exist: true,
+            obj_type: TenantObjectType::Policy,
+            after: None,
+        });
+        assert_eq!(expected, result);
+        assert_eq!(parse_sql("show policies;"), ExtStatement::ShowPolicies);
+    }
+
+    #[test]
+    fn test_create_masking_policy() {
+        let result = parse_sql(
+            "create masking policy if not exists mask_phone on users (phone) for role analyst using (mask_partial(phone, 3, 4));",
+        );
+
+        match result {
+            ExtStatement::CreatePolicy(ast::CreatePolicy {
+                if_not_exists,
+                name,
+                table,
+                column,
+                role,
+                using,
+            }) => {
+                assert!(if_not_exists);
+                assert_eq!(name, Ident::new("mask_phone"));
+                assert_eq!(table.to_string(), "users");
+                assert_eq!(column, Some(Ident::new("phone")));
+                assert_eq!(role, Ident::new("analyst"));
+                assert_eq!(using.to_string(), "mask_partial(phone, 3, 4)");
+            }
+            _ => panic!("failed"),
+        }
+
+        assert!(ExtParser::parse_sql(
+            "create masking policy mask_phone on users for role analyst using (mask_null(phone));"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_alter_user_password_expire() {
+        let result = parse_sql("alter user writer password expire;");
+        let expected = ExtStatement::AlterUser(ast::AlterUser {
+            name: Ident::new("writer"),
+            operation: AlterUserOperation::Set(SqlOption {
+                name: Ident::new("must_change_password"),
+                value: Value::Boolean(true),
+            }),
+        });
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_alter_role() {
+        let result = parse_sql("alter role analyst set max_query_memory = '1GiB';");
+        let expected = ExtStatement::AlterRole(ast::AlterRole {
+            name: Ident::new("analyst"),
+            operation: AlterRoleOperation::Set(SqlOption {
+                name: Ident::new("max_query_memory"),
+                value: Value::SingleQuotedString("1GiB".to_string()),
+            }),
+        });
+        assert_eq!(expected, result);
+
+        let result = parse_sql("alter role analyst unset max_concurrent_queries;");
+        let expected = ExtStatement::AlterRole(ast::AlterRole {
+            name: Ident::new("analyst"),
+            operation: AlterRoleOperation::UnSet(Ident::new("max_concurrent_queries")),
+        });
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_asof_join() {
+        let result = parse_sql(
+            "select * from a asof left join b on a.t0 = b.t0 and a.t1 = b.t1 \
+            match_condition(a.time >= b.time) tolerance interval '1 second' where a.f0 > 0;",
+        );
+        let expected = parse_sql(
+            "select * from a left join b on (a.t0 = b.t0 and a.t1 = b.t1) \
+            and asof_match(a.time >= b.time, interval '1 second') where a.f0 > 0;",
+        );
+        assert_eq!(result, expected);
+
+        let result = parse_sql(
+            "select * from a asof join (select * from b asof join c match_condition(b.time = c.time)) d \
+            match_condition(a.time <= d.time)",
+        );
+        let expected = parse_sql(
+            "select * from a join (select * from b join c on asof_match(b.time = c.time)) d \
+            on asof_match(a.time <= d.time)",
+        );
+        assert_eq!(result, expected);
+
+        assert!(ExtParser::parse_sql("select * from a asof join b on a.t0 = b.t0").is_err());
+        assert!(ExtParser::parse_sql(
+            "select * from a asof join b match_condition(a.time >= b.time) tolerance 10"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_pivot_any() {
+        let query = match parse_sql("select * from cpu pivot(avg(usage) for host in (any)) p") {
+            ExtStatement::SqlStatement(stmt) => match *stmt {
+                Statement::Query(query) => query,
+                _ => panic!("expect query"),
+            },
+            _ => panic!("expect sql statement"),
+        };
+        let select = match query.body.deref() {
+            SetExpr::Select(select) => select.clone(),
+            _ => panic!("expect select"),
+        };
+        match &select.from[0].relation {
+            TableFactor::Pivot { pivot_values, .. } => {
+                assert_eq!(
+                    pivot_values,
+                    &vec![Value::Placeholder(PIVOT_ANY_VALUES.to_string())]
+                );
+            }
+            _ => panic!("expect pivot"),
+        }
+    }
+
+    #[test]
+    fn test_unpivot() {
+        let result =
+            parse_sql("select * from db.wide unpivot(value for name in (a, b)) u where value > 0");
+        let expected = parse_sql(
+            "select * from __unpivot((select * from db.wide), value, name, a, b) u where value > 0",
+        );
+        assert_eq!(result, expected);
+
+        let result = parse_sql(
+            "select * from (select * from wide where a > 0) w unpivot(value for name in (a, b))",
+        );
+        let expected = parse_sql(
+            "select * from __unpivot((select * from (select * from wide where a > 0) w), value, name, a, b)",
+        );
+        assert_eq!(result, expected);
+
+        assert!(ExtParser::parse_sql("select * from wide unpivot(value in (a, b))").is_err());
+    }
 }
@@ -25,6 +25,7 @@ use spi::QueryResult;
 use super::optimizer::PhysicalOptimizer;
 use crate::extension::physical::optimizer_rule::add_assert::AddAssertExec;
 use crate::extension::physical::optimizer_rule::add_sort::AddSortExec;
+use crate::extension::physical::transform_rule::asof_join::AsofJoinPlanner;
 use crate::extension::physical::transform_rule::expand::ExpandPlanner;
 use crate::extension::physical::transform_rule::table_writer::TableWriterPlanner;
 use crate::extension::physical::transform_rule::tag_scan::TagScanPlanner;
@@ -67,6 +68,7 @@ impl Default for DefaultPhysicalPlanner {
             Arc::new(TagScanPlanner {}),
             Arc::new(ExpandPlanner::new()),
             Arc::new(TsGenFuncPlanner),
+            Arc::new(AsofJoinPlanner),
         ];
 
         // We need to take care of the rule ordering. They may influence each other.
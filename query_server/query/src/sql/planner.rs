@@ -1,15 +1,17 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::option::Option;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{iter, vec};
 
 use async_recursion::async_recursion;
 use async_trait::async_trait;
-use datafusion::arrow::datatypes::{DataType, SchemaRef, TimeUnit};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use datafusion::arrow::datatypes::{DataType, Schema, SchemaRef, TimeUnit};
 use datafusion::arrow::error::ArrowError;
 use datafusion::common::parsers::CompressionTypeVariant;
-use datafusion::common::tree_node::TreeNode;
+use datafusion::common::tree_node::{Transformed, TreeNode, VisitRecursion};
 use datafusion::common::{
     Column, DFField, DFSchema, OwnedTableReference, Result as DFResult, ToDFSchema,
 };
@@ -25,14 +27,15 @@ use datafusion::datasource::listing::{
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::SessionState;
 use datafusion::execution::runtime_env::RuntimeEnv;
-use datafusion::logical_expr::expr::{ScalarFunction, Sort};
+use datafusion::logical_expr::expr::{Exists, InSubquery, ScalarFunction, Sort};
 use datafusion::logical_expr::expr_rewriter::rewrite_preserving_name;
 use datafusion::logical_expr::logical_plan::Analyze;
-use datafusion::logical_expr::utils::expr_to_columns;
+use datafusion::logical_expr::utils::{expr_to_columns, from_plan};
 use datafusion::logical_expr::{
-    lit, BinaryExpr, BuiltinScalarFunction, Case, CreateExternalTable as PlanCreateExternalTable,
-    EmptyRelation, Explain, Expr, Extension, LogicalPlan, LogicalPlanBuilder, Operator, PlanType,
-    SubqueryAlias, TableSource, ToStringifiedPlan, Union,
+    lit, AggregateFunction, BinaryExpr, BuiltinScalarFunction, Case,
+    CreateExternalTable as PlanCreateExternalTable, EmptyRelation, Explain, Expr, ExprSchemable,
+    Extension, LogicalPlan, LogicalPlanBuilder, Operator, PlanType, Subquery, SubqueryAlias,
+    TableScan, TableSource, ToStringifiedPlan, Union,
 };
 use datafusion::optimizer::analyzer::type_coercion::TypeCoercionRewriter;
 use datafusion::optimizer::simplify_expressions::ConstEvaluator;
@@ -42,37 +45,41 @@ use datafusion::scalar::ScalarValue;
 use datafusion::sql::parser::CreateExternalTable as AstCreateExternalTable;
 use datafusion::sql::planner::{object_name_to_table_reference, PlannerContext, SqlToRel};
 use datafusion::sql::sqlparser::ast::{
-    Assignment, DataType as SQLDataType, Expr as SQLExpr, Expr as ASTExpr, Ident, ObjectName,
-    Offset, OrderByExpr, Query, SqlOption, Statement, TableAlias, TableFactor, TableWithJoins,
-    TimezoneInfo,
+    Assignment, BinaryOperator, ColumnDef, Cte, DataType as SQLDataType, Expr as SQLExpr,
+    Expr as ASTExpr, FunctionArg, FunctionArgExpr, Ident, ObjectName, Offset, OrderByExpr, Query,
+    SetExpr, SqlOption, Statement, TableAlias, TableFactor, TableWithJoins, TimezoneInfo, Value,
 };
-use datafusion::sql::sqlparser::parser::ParserError;
+use datafusion::sql::sqlparser::parser::{Parser, ParserError};
 use datafusion::sql::TableReference;
 use lazy_static::__Deref;
 use meta::error::MetaError;
-use models::auth::bcrypt_verify;
+use models::auth::policy::PolicyDesc;
 use models::auth::privilege::{
-    DatabasePrivilege, GlobalPrivilege, Privilege, TenantObjectPrivilege,
+    DatabasePrivilege, GlobalPrivilege, Privilege, TablePrivilege, TenantObjectPrivilege,
 };
 use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
-use models::auth::user::User;
+use models::auth::user::{User, UserOptions};
+use models::auth::{bcrypt_verify, check_password_history, check_password_strength};
 use models::gis::data_type::{Geometry, GeometryType};
 use models::object_reference::{Resolve, ResolvedTable};
 use models::oid::{Identifier, Oid};
 use models::schema::database_schema::{DatabaseConfigBuilder, DatabaseOptionsBuilder};
+use models::schema::function_schema::{FunctionArg, FunctionBody, FunctionKind, FunctionSchema};
 use models::schema::stream_table_schema::Watermark;
 use models::schema::tenant::Tenant;
 use models::schema::tskv_table_schema::{
     ColumnType, TableColumn, TskvTableSchema, TskvTableSchemaRef,
 };
+use models::schema::view_schema::MaterializedView;
 use models::schema::{DEFAULT_CATALOG, TIME_FIELD_NAME};
-use models::utils::SeqIdGenerator;
+use models::utils::{now_timestamp_millis, now_timestamp_secs, SeqIdGenerator};
 use models::{ColumnId, ValueType};
 use object_store::ObjectStore;
+use regex::Regex;
 use snafu::ResultExt;
 use spi::query::ast;
 use spi::query::ast::{
-    AlterDatabase as ASTAlterDatabase, AlterTable as ASTAlterTable,
+    AlterDatabase as ASTAlterDatabase, AlterRoleOperation, AlterTable as ASTAlterTable,
     AlterTableAction as ASTAlterTableAction, AlterTenantOperation, AlterUserOperation,
     ChecksumGroup as ASTChecksumGroup, ColumnOption, CompactDatabase as ASTCompactDatabase,
     CompactVnode as ASTCompactVnode, CopyIntoTable, CopyTarget, CopyVnode as ASTCopyVnode,
@@ -86,21 +93,24 @@ use spi::query::ast::{
 };
 use spi::query::datasource::{self, UriSchema};
 use spi::query::logical_planner::{
-    normalize_sql_object_name_to_string, parse_connection_options,
+    normalize_sql_object_name_to_string, parse_connection_options, sql_option_to_alter_role_action,
     sql_option_to_alter_tenant_action, sql_options_to_map, sql_options_to_tenant_options,
-    sql_options_to_user_options, unset_option_to_alter_tenant_action, AlterDatabase, AlterTable,
-    AlterTableAction, AlterTenant, AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser,
-    AlterUser, AlterUserAction, ChecksumGroup, CompactVnode, CopyOptions, CopyOptionsBuilder,
-    CopyVnode, CreateDatabase, CreateRole, CreateStreamTable, CreateTable, CreateTenant,
-    CreateUser, DDLPlan, DMLPlan, DatabaseObjectType, DeleteFromTable, DropDatabaseObject,
-    DropGlobalObject, DropTenantObject, DropVnode, FileFormatOptions, FileFormatOptionsBuilder,
-    GlobalObjectType, GrantRevoke, LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan,
-    RecoverDatabase, RecoverTenant, ReplicaAdd, ReplicaDestory, ReplicaPromote, ReplicaRemove,
-    SYSPlan, TenantObjectType, TENANT_OPTION_LIMITER,
+    sql_options_to_user_options, unset_option_to_alter_role_action,
+    unset_option_to_alter_tenant_action, AlterDatabase, AlterRole, AlterTable, AlterTableAction,
+    AlterTenant, AlterTenantAction, AlterTenantAddUser, AlterTenantSetUser, AlterUser,
+    AlterUserAction, ChecksumGroup, CompactVnode, CopyOptions, CopyOptionsBuilder, CopyVnode,
+    CreateDatabase, CreateFunction, CreateMaterializedView, CreatePolicy, CreateRole,
+    CreateStreamTable, CreateTable, CreateTenant, CreateToken, CreateUser, CreateView, DDLPlan,
+    DMLPlan, DatabaseObjectType, DeleteFromTable, DropDatabaseObject, DropGlobalObject,
+    DropTenantObject, DropVnode, FileFormatOptions, FileFormatOptionsBuilder, GlobalObjectType,
+    GrantRevoke, LogicalPlanner, MoveVnode, Plan, PlanWithPrivileges, QueryPlan, RecoverDatabase,
+    RecoverTenant, ReplicaAdd, ReplicaDestory, ReplicaPromote, ReplicaRemove, SYSPlan,
+    TenantObjectType, TENANT_OPTION_LIMITER,
 };
 use spi::query::session::SessionCtx;
 use spi::{
-    AnalyzerSnafu, CommonSnafu, MetaSnafu, ObjectStoreSnafu, ParserSnafu, QueryError, QueryResult,
+    AnalyzerSnafu, AuthSnafu, CommonSnafu, MetaSnafu, ObjectStoreSnafu, ParserSnafu, QueryError,
+    QueryResult,
 };
 use trace::span_ext::SpanExt;
 use trace::{debug, warn};
@@ -110,20 +120,32 @@ use utils::duration::CnosDuration;
 use utils::precision::Precision;
 
 use crate::data_source::source_downcast_adapter;
+use crate::data_source::stream::tskv::factory::TSKV_STREAM_PROVIDER;
+use crate::data_source::stream::tskv::{STREAM_DB_KEY, STREAM_TABLE_KEY};
 use crate::data_source::stream::{get_event_time_column, get_watermark_delay};
 use crate::data_source::table_source::{TableHandle, TableSourceAdapter, TEMP_LOCATION_TABLE_NAME};
+use crate::extension::expr::{create_user_udaf, create_user_udf};
 use crate::extension::logical::logical_plan_builder::LogicalPlanBuilderExt;
 use crate::extension::logical::plan_node::update::UpdateNode;
 use crate::metadata::{
-    is_system_database, ContextProviderExtension, DatabaseSet, COLUMNS_COLUMN_NAME,
-    COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC, COLUMNS_DATABASE_NAME, COLUMNS_DATA_TYPE,
-    COLUMNS_TABLE_NAME, DATABASES_DATABASE_NAME, DATABASES_MAX_CACHE_READERS,
+    is_system_database, view_query_to_plan, ContextProviderExtension, DatabaseSet,
+    COLUMNS_COLUMN_NAME, COLUMNS_COLUMN_TYPE, COLUMNS_COMPRESSION_CODEC, COLUMNS_DATABASE_NAME,
+    COLUMNS_DATA_TYPE, COLUMNS_TABLE_NAME, DATABASES_DATABASE_NAME, DATABASES_MAX_CACHE_READERS,
     DATABASES_MAX_MEMCACHE_SIZE, DATABASES_MEMCACHE_PARTITIONS, DATABASES_PRECISION,
     DATABASES_REPLICA, DATABASES_SHARD, DATABASES_STRICT_WRITE, DATABASES_TTL,
-    DATABASES_VNODE_DURATION, DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC, INFORMATION_SCHEMA,
-    INFORMATION_SCHEMA_COLUMNS, INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_QUERIES,
-    INFORMATION_SCHEMA_TABLES, TABLES_TABLE_DATABASE, TABLES_TABLE_NAME,
+    DATABASES_VNODE_DURATION, DATABASES_WAL_MAX_FILE_SIZE, DATABASES_WAL_SYNC,
+    FUNCTIONS_FUNCTION_NAME, INFORMATION_SCHEMA, INFORMATION_SCHEMA_COLUMNS,
+    INFORMATION_SCHEMA_DATABASES, INFORMATION_SCHEMA_FUNCTIONS, INFORMATION_SCHEMA_POLICIES,
+    INFORMATION_SCHEMA_QUERIES, INFORMATION_SCHEMA_TABLES, INFORMATION_SCHEMA_TOKENS,
+    POLICIES_POLICY_NAME, TABLES_TABLE_DATABASE, TABLES_TABLE_NAME, TOKENS_TOKEN_NAME,
 };
+use crate::sql::dialect::CnosDBDialect;
+use crate::sql::parser::{PIVOT_ANY_VALUES, UNPIVOT_FUNCTION};
+use crate::utils::duration::parse_duration;
+
+// Materialized view option keys
+const REFRESH_INTERVAL_OPTION: &str = "refresh_interval";
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
 
 /// CnosDB SQL query planner
 pub struct SqlPlanner<'a, S: ContextProviderExtension> {
@@ -138,8 +160,8 @@ impl<'a, S: ContextProviderExtension + Send + Sync> LogicalPlanner for SqlPlanne
         statement: ExtStatement,
         session: &SessionCtx,
         auth_enable: bool,
-    ) -> QueryResult<Plan> {
-        let PlanWithPrivileges { plan, privileges } = {
+    ) -> QueryResult<PlanWithPrivileges> {
+        let plan = {
             let span = session.get_child_span("statement to logical plan");
             self.statement_to_plan(statement, session, auth_enable)
                 .await
@@ -149,7 +171,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync> LogicalPlanner for SqlPlanne
         };
 
         let _ = session.get_child_span("check privilege");
-        check_privilege(session.user(), privileges)?;
+        check_privilege(session.user(), &plan.privileges)?;
         Ok(plan)
     }
 }
@@ -192,6 +214,9 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             ExtStatement::CreateTenant(stmt) => self.create_tenant_to_plan(stmt),
             ExtStatement::CreateUser(stmt) => self.create_user_to_plan(stmt),
             ExtStatement::CreateRole(stmt) => self.create_role_to_plan(stmt, session),
+            ExtStatement::CreateFunction(stmt) => self.create_function_to_plan(stmt, session),
+            ExtStatement::CreateToken(stmt) => self.create_token_to_plan(stmt, session).await,
+            ExtStatement::CreatePolicy(stmt) => self.create_policy_to_plan(stmt, session),
             ExtStatement::DropDatabaseObject(s) => self.drop_database_object_to_plan(s, session),
             ExtStatement::DropTenantObject(s) => self.drop_tenant_object_to_plan(s, session),
             ExtStatement::DropGlobalObject(s) => self.drop_global_object_to_plan(s),
@@ -199,6 +224,9 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             ExtStatement::DescribeDatabase(stmt) => self.describe_databases_to_plan(stmt, session),
             ExtStatement::ShowDatabases() => self.show_databases_to_plan(session),
             ExtStatement::ShowTables(stmt) => self.show_tables_to_plan(stmt, session),
+            ExtStatement::ShowFunctions => self.show_functions_to_plan(),
+            ExtStatement::ShowTokens => self.show_tokens_to_plan(),
+            ExtStatement::ShowPolicies => self.show_policies_to_plan(),
             ExtStatement::AlterDatabase(stmt) => self.database_to_alter(*stmt, session),
             ExtStatement::ShowSeries(stmt) => self.show_series_to_plan(*stmt, session),
             ExtStatement::Explain(stmt) => {
@@ -217,6 +245,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             ExtStatement::AlterUser(stmt) => {
                 self.alter_user_to_plan(stmt, session.user(), false).await
             }
+            ExtStatement::AlterRole(stmt) => self.alter_role_to_plan(stmt, session),
             ExtStatement::GrantRevoke(stmt) => self.grant_revoke_to_plan(stmt, session),
             ExtStatement::ShowQueries => self.show_queries_to_plan(session),
             ExtStatement::Copy(stmt) => self.copy_to_plan(stmt, session).await,
@@ -254,8 +283,15 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         session: &SessionCtx,
     ) -> QueryResult<PlanWithPrivileges> {
         match stmt {
-            Statement::Query(_) => {
-                let df_plan = self.df_planner.sql_statement_to_plan(stmt)?;
+            Statement::Query(mut query) => {
+                self.rewrite_reshaping_query(&mut query, &[]).await?;
+                let df_plan = self
+                    .df_planner
+                    .sql_statement_to_plan(Statement::Query(query))?;
+                let df_plan = self.apply_policies(df_plan, session)?;
+                let df_plan = self
+                    .schema_provider
+                    .rewrite_with_materialized_views(df_plan)?;
                 let plan = Plan::Query(QueryPlan {
                     df_plan,
                     is_tag_scan: false,
@@ -263,11 +299,8 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
                 // privileges
                 let access_databases = self.schema_provider.reset_access_databases();
-                let privileges = databases_privileges(
-                    DatabasePrivilege::Read,
-                    *session.tenant_id(),
-                    access_databases,
-                );
+                let privileges =
+                    databases_privileges(DatabasePrivilege::Read, session, access_databases);
                 Ok(PlanWithPrivileges { plan, privileges })
             }
             Statement::Insert {
@@ -307,6 +340,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
                 self.delete_to_plan(session, from, selection)
             }
+            Statement::CreateView { .. } => self.create_view_to_plan(stmt, session),
             Statement::Kill { id, .. } => {
                 let plan = Plan::SYSTEM(SYSPlan::KillQuery(id.into()));
                 // TODO privileges
@@ -336,6 +370,372 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         }
     }
 
+    /// Rewrites the PIVOT and UNPIVOT relations of the query into equivalent derived tables,
+    /// `ctes` are the common table expressions visible to the query.
+    #[async_recursion]
+    async fn rewrite_reshaping_query(&self, query: &mut Query, ctes: &[Cte]) -> QueryResult<()> {
+        let mut ctes = ctes.to_vec();
+        if let Some(with) = query.with.as_mut() {
+            for cte in with.cte_tables.iter_mut() {
+                self.rewrite_reshaping_query(&mut cte.query, &ctes).await?;
+                ctes.push(cte.clone());
+            }
+        }
+        self.rewrite_reshaping_set_expr(&mut query.body, &ctes)
+            .await
+    }
+
+    #[async_recursion]
+    async fn rewrite_reshaping_set_expr(
+        &self,
+        set_expr: &mut SetExpr,
+        ctes: &[Cte],
+    ) -> QueryResult<()> {
+        match set_expr {
+            SetExpr::Select(select) => {
+                for table in select.from.iter_mut() {
+                    self.rewrite_reshaping_table_with_joins(table, ctes).await?;
+                }
+                Ok(())
+            }
+            SetExpr::Query(query) => self.rewrite_reshaping_query(query, ctes).await,
+            SetExpr::SetOperation { left, right, .. } => {
+                self.rewrite_reshaping_set_expr(left, ctes).await?;
+                self.rewrite_reshaping_set_expr(right, ctes).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[async_recursion]
+    async fn rewrite_reshaping_table_with_joins(
+        &self,
+        table: &mut TableWithJoins,
+        ctes: &[Cte],
+    ) -> QueryResult<()> {
+        self.rewrite_reshaping_table_factor(&mut table.relation, ctes)
+            .await?;
+        for join in table.joins.iter_mut() {
+            self.rewrite_reshaping_table_factor(&mut join.relation, ctes)
+                .await?;
+        }
+        Ok(())
+    }
+
+    #[async_recursion]
+    async fn rewrite_reshaping_table_factor(
+        &self,
+        relation: &mut TableFactor,
+        ctes: &[Cte],
+    ) -> QueryResult<()> {
+        let rewritten = match relation {
+            TableFactor::Derived { subquery, .. } => {
+                return self.rewrite_reshaping_query(subquery, ctes).await;
+            }
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => {
+                return self
+                    .rewrite_reshaping_table_with_joins(table_with_joins, ctes)
+                    .await;
+            }
+            TableFactor::Pivot {
+                name,
+                table_alias,
+                aggregate_function,
+                value_column,
+                pivot_values,
+                pivot_alias,
+            } => {
+                let alias = pivot_alias
+                    .clone()
+                    .or_else(|| table_alias.clone())
+                    .or_else(|| {
+                        name.0.last().map(|ident| TableAlias {
+                            name: ident.clone(),
+                            columns: vec![],
+                        })
+                    });
+                let subquery = self
+                    .pivot_to_query(
+                        name,
+                        table_alias.as_ref(),
+                        aggregate_function,
+                        value_column,
+                        pivot_values,
+                        ctes,
+                    )
+                    .await?;
+                TableFactor::Derived {
+                    lateral: false,
+                    subquery: Box::new(subquery),
+                    alias,
+                }
+            }
+            TableFactor::Table {
+                name,
+                alias,
+                args: Some(args),
+                ..
+            } if name.0.len() == 1 && name.0[0].value == UNPIVOT_FUNCTION => {
+                let subquery = self.unpivot_to_query(args, ctes)?;
+                TableFactor::Derived {
+                    lateral: false,
+                    subquery: Box::new(subquery),
+                    alias: alias.clone(),
+                }
+            }
+            _ => return Ok(()),
+        };
+
+        *relation = rewritten;
+        Ok(())
+    }
+
+    /// `<table> PIVOT(agg(<field>) FOR <tag> IN (<values>))` is planned as
+    /// `SELECT <other columns>, agg(CASE WHEN <tag> = <value> THEN <field> END) AS "<value>", ...
+    /// FROM <table> GROUP BY <other columns>`,
+    /// the values of `IN (ANY)` are all the values of the tag in the table.
+    async fn pivot_to_query(
+        &self,
+        name: &ObjectName,
+        table_alias: Option<&TableAlias>,
+        aggregate_function: &ASTExpr,
+        value_column: &[Ident],
+        pivot_values: &[Value],
+        ctes: &[Cte],
+    ) -> QueryResult<Query> {
+        let function = match aggregate_function {
+            ASTExpr::Function(function) => function,
+            _ => {
+                return Err(QueryError::Semantic {
+                    err: format!(
+                        "PIVOT expects an aggregate function, but found: {aggregate_function}"
+                    ),
+                })
+            }
+        };
+        let from = match table_alias {
+            Some(alias) => format!("{name} AS {alias}"),
+            None => name.to_string(),
+        };
+        let value_expr = match value_column {
+            [ident] => ASTExpr::Identifier(ident.clone()),
+            idents => ASTExpr::CompoundIdentifier(idents.to_vec()),
+        };
+
+        // The columns that are neither pivoted nor aggregated are the group by columns
+        let mut exprs = vec![value_expr.to_string()];
+        exprs.extend(function.args.iter().filter_map(|arg| match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))
+            | FunctionArg::Named {
+                arg: FunctionArgExpr::Expr(expr),
+                ..
+            } => Some(expr.to_string()),
+            _ => None,
+        }));
+        let referenced = self.referenced_columns(ctes, &from, &exprs)?;
+        let pivot_column = match referenced[0].iter().collect::<Vec<_>>().as_slice() {
+            [column] => column.to_string(),
+            _ => {
+                return Err(QueryError::Semantic {
+                    err: format!("PIVOT expects a column to pivot on, but found: {value_expr}"),
+                })
+            }
+        };
+        let excluded = referenced.iter().flatten().collect::<HashSet<_>>();
+        let group_columns = self
+            .relation_columns(ctes, &from)?
+            .into_iter()
+            .filter(|column| !excluded.contains(column))
+            .map(|column| Ident::with_quote('"', column).to_string())
+            .collect::<Vec<_>>();
+
+        let values = match pivot_values {
+            [Value::Placeholder(placeholder)] if placeholder == PIVOT_ANY_VALUES => {
+                if name.0.len() == 1 && ctes.iter().any(|cte| cte.alias.name == name.0[0]) {
+                    return Err(QueryError::Semantic {
+                        err: format!(
+                            "PIVOT ... IN (ANY) discovers the values from the tags of a table, \
+                            but {name} is a common table expression"
+                        ),
+                    });
+                }
+                let table = normalize_sql_object_name(name.clone())?;
+                self.schema_provider
+                    .tag_values(table, &pivot_column)
+                    .await?
+                    .into_iter()
+                    .map(Value::SingleQuotedString)
+                    .collect()
+            }
+            values => values.to_vec(),
+        };
+        if values.is_empty() {
+            return Err(QueryError::Semantic {
+                err: format!("PIVOT found no values of {value_expr} to pivot"),
+            });
+        }
+
+        let mut projection = group_columns.clone();
+        for value in values {
+            let condition = ASTExpr::BinaryOp {
+                left: Box::new(value_expr.clone()),
+                op: BinaryOperator::Eq,
+                right: Box::new(ASTExpr::Value(value.clone())),
+            };
+            let mut aggregate = function.clone();
+            for arg in aggregate.args.iter_mut() {
+                let arg = match arg {
+                    FunctionArg::Unnamed(arg) | FunctionArg::Named { arg, .. } => arg,
+                };
+                // count(*) counts the rows of the value
+                let result = match arg {
+                    FunctionArgExpr::Expr(expr) => expr.clone(),
+                    _ => ASTExpr::Value(Value::Number("1".to_string(), false)),
+                };
+                *arg = FunctionArgExpr::Expr(ASTExpr::Case {
+                    operand: None,
+                    conditions: vec![condition.clone()],
+                    results: vec![result],
+                    else_result: None,
+                });
+            }
+            projection.push(format!(
+                "{} AS {}",
+                ASTExpr::Function(aggregate),
+                Ident::with_quote('"', pivot_value_name(&value))
+            ));
+        }
+
+        let mut sql = format!("SELECT {} FROM {from}", projection.join(", "));
+        if !group_columns.is_empty() {
+            sql.push_str(&format!(" GROUP BY {}", group_columns.join(", ")));
+        }
+        parse_query(&sql)
+    }
+
+    /// `__unpivot((<query>), <value column>, <name column>, <columns>)` is planned as
+    /// `SELECT <other columns>, '<column>' AS <name column>, <column> AS <value column>
+    /// FROM (<query>) WHERE <column> IS NOT NULL UNION ALL ...`.
+    fn unpivot_to_query(&self, args: &[FunctionArg], ctes: &[Cte]) -> QueryResult<Query> {
+        let exprs = args
+            .iter()
+            .map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Ok(expr),
+                _ => Err(QueryError::Semantic {
+                    err: format!("UNPIVOT expects columns, but found: {arg}"),
+                }),
+            })
+            .collect::<QueryResult<Vec<_>>>()?;
+        let (source, value_column, name_column, columns) = match exprs.as_slice() {
+            [ASTExpr::Subquery(source), ASTExpr::Identifier(value_column), ASTExpr::Identifier(name_column), columns @ ..]
+                if !columns.is_empty() =>
+            {
+                (source, value_column, name_column, columns)
+            }
+            _ => {
+                return Err(QueryError::Semantic {
+                    err: "UNPIVOT expects (<value column> FOR <name column> IN (<columns>))"
+                        .to_string(),
+                })
+            }
+        };
+
+        let from = format!("({source}) AS {}", Ident::with_quote('"', UNPIVOT_FUNCTION));
+        let exprs = columns.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        let unpivoted = self
+            .referenced_columns(ctes, &from, &exprs)?
+            .into_iter()
+            .zip(columns)
+            .map(
+                |(referenced, expr)| match referenced.into_iter().collect::<Vec<_>>().as_slice() {
+                    [column] => Ok(column.clone()),
+                    _ => Err(QueryError::Semantic {
+                        err: format!("UNPIVOT expects columns, but found: {expr}"),
+                    }),
+                },
+            )
+            .collect::<QueryResult<Vec<_>>>()?;
+        let other_columns = self
+            .relation_columns(ctes, &from)?
+            .into_iter()
+            .filter(|column| !unpivoted.contains(column))
+            .map(|column| Ident::with_quote('"', column).to_string())
+            .collect::<Vec<_>>();
+
+        let selects = columns
+            .iter()
+            .zip(unpivoted)
+            .map(|(expr, column)| {
+                let mut projection = other_columns.clone();
+                projection.push(format!(
+                    "{} AS {name_column}",
+                    Value::SingleQuotedString(column)
+                ));
+                projection.push(format!("{expr} AS {value_column}"));
+                format!(
+                    "SELECT {} FROM {from} WHERE {expr} IS NOT NULL",
+                    projection.join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+        parse_query(&selects.join(" UNION ALL "))
+    }
+
+    /// The output columns of `SELECT * FROM <from>`
+    fn relation_columns(&self, ctes: &[Cte], from: &str) -> QueryResult<Vec<String>> {
+        let query = parse_query(&format!("{}SELECT * FROM {from}", with_clause(ctes)))?;
+        let plan = self
+            .df_planner
+            .sql_statement_to_plan(Statement::Query(Box::new(query)))?;
+        Ok(plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect())
+    }
+
+    /// The names of the columns of `<from>` each of the expressions references
+    fn referenced_columns(
+        &self,
+        ctes: &[Cte],
+        from: &str,
+        exprs: &[String],
+    ) -> QueryResult<Vec<HashSet<String>>> {
+        let projection = exprs
+            .iter()
+            .enumerate()
+            .map(|(idx, expr)| format!("{expr} AS \"{idx}\""))
+            .collect::<Vec<_>>();
+        let query = parse_query(&format!(
+            "{}SELECT {} FROM {from}",
+            with_clause(ctes),
+            projection.join(", ")
+        ))?;
+        let plan = self
+            .df_planner
+            .sql_statement_to_plan(Statement::Query(Box::new(query)))?;
+
+        let exprs = match plan {
+            LogicalPlan::Projection(projection) => projection.expr,
+            _ => {
+                return Err(QueryError::Internal {
+                    reason: format!("Expect a projection, but found: {}", plan.display()),
+                })
+            }
+        };
+        exprs
+            .iter()
+            .map(|expr| {
+                let mut columns = HashSet::new();
+                expr_to_columns(expr, &mut columns)?;
+                Ok(columns.into_iter().map(|column| column.name).collect())
+            })
+            .collect()
+    }
+
     async fn update_to_plan(
         &self,
         session: &SessionCtx,
@@ -354,6 +754,9 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
         let table_ref = normalize_sql_object_name(table_name)?;
         let table_owned_reference = table_ref.to_owned_reference();
+        let resolved_table = table_ref
+            .clone()
+            .resolve_object(session.tenant(), session.default_database())?;
         let table_source = self.get_table_source(table_ref)?;
 
         let schema = table_source.schema();
@@ -392,7 +795,23 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         .ok_or_else(|| {
             DataFusionError::Plan("Disable updating of the entire table, if you want to continue, please add `where true`".to_string())
         })?;
+        // only the visible rows are updated
+        let filter = match self.row_policy_predicate(
+            session,
+            resolved_table.database(),
+            resolved_table.table(),
+            df_schema.as_ref(),
+        )? {
+            Some(predicate) => filter.and(predicate),
+            None => filter,
+        };
 
+        // the time column can't be updated and is always granted
+        let updated_columns = assigns
+            .iter()
+            .map(|(column, _)| column.name.clone())
+            .filter(|name| name != TIME_FIELD_NAME)
+            .collect::<BTreeSet<_>>();
         let update_node = Arc::new(UpdateNode::try_new(
             table_owned_reference,
             table_source,
@@ -406,10 +825,10 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         });
 
         // privileges
-        let write_privileges = databases_privileges(
-            DatabasePrivilege::Write,
-            *session.tenant_id(),
+        let write_privileges = write_privileges(
+            session,
             self.schema_provider.reset_access_databases(),
+            Some(updated_columns),
         );
         Ok(PlanWithPrivileges {
             plan,
@@ -478,12 +897,13 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         let source_plan = self
             .df_planner
             .sql_statement_to_plan(Statement::Query(source))?;
+        let source_plan = self.apply_policies(source_plan, session)?;
 
         // save database read privileges
         // This operation must be done before fetching the target table metadata
         let mut read_privileges = databases_privileges(
             DatabasePrivilege::Read,
-            *session.tenant_id(),
+            session,
             self.schema_provider.reset_access_databases(),
         );
 
@@ -511,11 +931,19 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             is_tag_scan: false,
         });
 
-        // privileges
-        let mut write_privileges = databases_privileges(
-            DatabasePrivilege::Write,
-            *session.tenant_id(),
+        // privileges, all the columns are written without the column list,
+        // the time column is always written and granted
+        let written_columns = (!columns.is_empty()).then(|| {
+            columns
+                .iter()
+                .filter(|name| name.as_str() != TIME_FIELD_NAME)
+                .cloned()
+                .collect::<BTreeSet<_>>()
+        });
+        let mut write_privileges = write_privileges(
+            session,
             self.schema_provider.reset_access_databases(),
+            written_columns,
         );
         write_privileges.append(&mut read_privileges);
         Ok(PlanWithPrivileges {
@@ -527,18 +955,21 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
     fn delete_to_plan(
         &self,
         session: &SessionCtx,
-        mut tables: Vec<TableWithJoins>,
+        tables: Vec<TableWithJoins>,
         selections: Option<SQLExpr>,
     ) -> QueryResult<PlanWithPrivileges> {
-        // FROM <table>
-        let table_name = if tables.len() > 1 {
-            return Err(QueryError::NotImplemented {
-                err: "Delete from multi-table".to_string(),
-            });
-        } else {
-            let table = tables.remove(0);
+        // FROM <table>[, <table> ...], the tables may contain wildcards
+        let mut table_names = vec![];
+        for table in tables {
+            if !table.joins.is_empty() {
+                return Err(QueryError::NotImplemented {
+                    err: "Delete from joined tables".to_string(),
+                });
+            }
             match table.relation {
-                TableFactor::Table { name, .. } => name,
+                TableFactor::Table { name, .. } => {
+                    table_names.extend(self.expand_table_wildcard(session, name)?)
+                }
                 TableFactor::Derived { .. } => {
                     return Err(QueryError::NotImplemented {
                         err: "Delete from derived table".to_string(),
@@ -565,47 +996,106 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     });
                 }
             }
-        };
-        let table_ref = normalize_sql_object_name(table_name.clone())?;
-        // only support delete from tskv table
-        let schema = self.get_tskv_schema(table_ref)?;
-        let df_schema = schema.to_arrow_schema().to_dfschema()?;
-
-        // WHERE <selection>
-        let selection = match selections {
-            Some(expr) => {
-                let sel = self
-                    .df_planner
-                    .sql_to_expr(expr, &df_schema, &mut Default::default())?;
+        }
 
-                let mut rewriter = TypeCoercionRewriter::new(Arc::new(df_schema));
-                let expr = rewrite_preserving_name(sel, &mut rewriter)?;
-                let props = ExecutionProps::default();
-                let mut const_evaluator = ConstEvaluator::try_new(&props)?;
-                let expr = expr.rewrite(&mut const_evaluator)?;
-                Some(expr)
-            }
-            None => None,
-        };
+        // the privileges are only required on the deleted tables,
+        // not on the views and external tables matched by the wildcards
+        self.schema_provider.reset_access_databases();
 
-        valid_delete(schema.as_ref(), &selection)?;
+        let mut deletes = Vec::with_capacity(table_names.len());
+        for table_name in table_names {
+            let table_ref = normalize_sql_object_name(table_name.clone())?;
+            // only support delete from tskv table
+            let schema = self.get_tskv_schema(table_ref)?;
+            let df_schema = schema.to_arrow_schema().to_dfschema()?;
+            let table_name = object_name_to_resolved_table(session, table_name)?;
 
-        let table_name = object_name_to_resolved_table(session, table_name)?;
-        let database = table_name.database().to_string();
-        let plan = Plan::DML(DMLPlan::DeleteFromTable(DeleteFromTable {
-            table_name,
-            selection,
-        }));
+            // WHERE <selection>, planned against the schema of each table
+            let selection = selections
+                .clone()
+                .map(|expr| {
+                    self.df_planner
+                        .sql_to_expr(expr, &df_schema, &mut Default::default())
+                })
+                .transpose()?;
+            // only the visible rows are deleted
+            let policy = self.row_policy_predicate(
+                session,
+                table_name.database(),
+                table_name.table(),
+                &df_schema,
+            )?;
+            let selection = match selection.into_iter().chain(policy).reduce(Expr::and) {
+                Some(sel) => {
+                    let mut rewriter = TypeCoercionRewriter::new(Arc::new(df_schema));
+                    let expr = rewrite_preserving_name(sel, &mut rewriter)?;
+                    let props = ExecutionProps::default();
+                    let mut const_evaluator = ConstEvaluator::try_new(&props)?;
+                    let expr = expr.rewrite(&mut const_evaluator)?;
+                    Some(expr)
+                }
+                None => None,
+            };
+
+            valid_delete(schema.as_ref(), &selection)?;
+
+            deletes.push(DeleteFromTable {
+                table_name,
+                selection,
+            });
+        }
+
+        // privileges, the whole rows are deleted with all their columns
+        let privileges =
+            write_privileges(session, self.schema_provider.reset_access_databases(), None);
 
         Ok(PlanWithPrivileges {
-            plan,
-            privileges: vec![Privilege::TenantObject(
-                TenantObjectPrivilege::Database(DatabasePrivilege::Write, Some(database)),
-                Some(*session.tenant_id()),
-            )],
+            plan: Plan::DML(DMLPlan::DeleteFromTable(deletes)),
+            privileges,
         })
     }
 
+    /// Expands a table name whose table part contains `*` into all the matching tables
+    /// of the database, other names are returned as is.
+    fn expand_table_wildcard(
+        &self,
+        session: &SessionCtx,
+        table_name: ObjectName,
+    ) -> QueryResult<Vec<ObjectName>> {
+        let pattern = match table_name.0.last() {
+            Some(ident) if ident.value.contains('*') => ident.value.clone(),
+            _ => return Ok(vec![table_name]),
+        };
+        let table = object_name_to_resolved_table(session, table_name)?;
+        let regex = table_pattern_to_regex(&pattern)?;
+
+        let mut tables = self
+            .schema_provider
+            .list_tables(table.database())
+            .context(MetaSnafu)?
+            .into_iter()
+            .filter(|name| regex.is_match(name))
+            .collect::<Vec<_>>();
+        tables.sort();
+        if tables.is_empty() {
+            return Err(MetaError::TableNotFound {
+                table: table.to_string(),
+            })
+            .context(MetaSnafu);
+        }
+
+        // Only tskv tables can be deleted from, views and external tables are skipped
+        let mut names = vec![];
+        for name in tables {
+            let name = quoted_object_name(table.database(), &name);
+            let table_ref = normalize_sql_object_name(name.clone())?;
+            if matches!(self.get_table_handle(table_ref)?, TableHandle::Tskv(_)) {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+
     fn drop_database_object_to_plan(
         &self,
         stmt: ast::DropDatabaseObject,
@@ -638,6 +1128,24 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     ),
                 )
             }
+            DatabaseObjectType::View => {
+                let view = object_name_to_resolved_table(session, object_name)?;
+                let database_name = view.database().to_string();
+                (
+                    DDLPlan::DropDatabaseObject(DropDatabaseObject {
+                        if_exist,
+                        object_name: view,
+                        obj_type: DatabaseObjectType::View,
+                    }),
+                    Privilege::TenantObject(
+                        TenantObjectPrivilege::Database(
+                            DatabasePrivilege::Full,
+                            Some(database_name),
+                        ),
+                        Some(tenant_id),
+                    ),
+                )
+            }
         };
 
         Ok(PlanWithPrivileges {
@@ -705,6 +1213,49 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     Privilege::TenantObject(TenantObjectPrivilege::RoleFull, Some(tenant_id)),
                 )
             }
+            TenantObjectType::Function => (
+                DDLPlan::DropTenantObject(DropTenantObject {
+                    tenant_name: tenant_name.to_string(),
+                    name: normalize_ident(object_name),
+                    if_exist,
+                    obj_type: TenantObjectType::Function,
+                    after: after_duration,
+                }),
+                Privilege::TenantObject(
+                    TenantObjectPrivilege::Database(DatabasePrivilege::Full, None),
+                    Some(tenant_id),
+                ),
+            ),
+            TenantObjectType::Token => {
+                let token_name = normalize_ident(object_name);
+                // The token can be dropped by its user, the privilege of a missing token
+                // is always granted and the error is reported by the execution.
+                let user_id = self
+                    .schema_provider
+                    .get_token(&token_name)
+                    .map(|token| *token.user_id())
+                    .unwrap_or(*session.user().desc().id());
+                (
+                    DDLPlan::DropTenantObject(DropTenantObject {
+                        tenant_name: tenant_name.to_string(),
+                        name: token_name,
+                        if_exist,
+                        obj_type: TenantObjectType::Token,
+                        after: after_duration,
+                    }),
+                    Privilege::Global(GlobalPrivilege::User(Some(user_id))),
+                )
+            }
+            TenantObjectType::Policy => (
+                DDLPlan::DropTenantObject(DropTenantObject {
+                    tenant_name: tenant_name.to_string(),
+                    name: normalize_ident(object_name),
+                    if_exist,
+                    obj_type: TenantObjectType::Policy,
+                    after: after_duration,
+                }),
+                Privilege::TenantObject(TenantObjectPrivilege::RoleFull, Some(tenant_id)),
+            ),
         };
 
         Ok(PlanWithPrivileges {
@@ -1311,42 +1862,102 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
-    fn show_tag_body(
-        &self,
-        session: &SessionCtx,
-        body: ShowTagBody,
-        projection_function: impl FnOnce(
-            &TskvTableSchema,
-            LogicalPlanBuilder,
-            bool,
-        ) -> QueryResult<LogicalPlan>,
-    ) -> QueryResult<PlanWithPrivileges> {
-        let ShowTagBody {
-            database_name,
-            table,
-            selection,
-            order_by,
-            limit,
-            offset,
-        } = body;
+    fn show_functions_to_plan(&self) -> QueryResult<PlanWithPrivileges> {
+        let table_ref = TableReference::partial(INFORMATION_SCHEMA, INFORMATION_SCHEMA_FUNCTIONS);
+        let table_source = self.get_table_source(table_ref.clone())?;
 
-        let table_name = database_name
-            .map(|e| ObjectName(vec![e, table.clone()]))
-            .unwrap_or_else(|| ObjectName(vec![table]));
-        let table_ref = normalize_sql_object_name(table_name)?;
+        let df_plan = LogicalPlanBuilder::scan(table_ref, table_source, None)?
+            .sort(vec![col(FUNCTIONS_FUNCTION_NAME).sort(true, true)])?
+            .build()?;
 
-        let table_schema = self.get_tskv_schema(table_ref.clone())?;
+        let plan = Plan::Query(QueryPlan {
+            df_plan,
+            is_tag_scan: false,
+        });
 
-        let (source_plan, _) = self.create_table_relation(table_ref, None, &Default::default())?;
+        // the functions are visible to all members of the tenant
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![],
+        })
+    }
 
-        // build from
-        let mut plan_builder = LogicalPlanBuilder::from(source_plan);
+    fn show_tokens_to_plan(&self) -> QueryResult<PlanWithPrivileges> {
+        let table_ref = TableReference::partial(INFORMATION_SCHEMA, INFORMATION_SCHEMA_TOKENS);
+        let table_source = self.get_table_source(table_ref.clone())?;
 
-        // build where
-        let selection = match selection {
-            Some(expr) => Some(self.df_planner.sql_to_expr(
-                expr,
-                plan_builder.schema(),
+        let df_plan = LogicalPlanBuilder::scan(table_ref, table_source, None)?
+            .sort(vec![col(TOKENS_TOKEN_NAME).sort(true, true)])?
+            .build()?;
+
+        let plan = Plan::Query(QueryPlan {
+            df_plan,
+            is_tag_scan: false,
+        });
+
+        // the view only shows the tokens of the user unless the user is an admin
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![],
+        })
+    }
+
+    fn show_policies_to_plan(&self) -> QueryResult<PlanWithPrivileges> {
+        let table_ref = TableReference::partial(INFORMATION_SCHEMA, INFORMATION_SCHEMA_POLICIES);
+        let table_source = self.get_table_source(table_ref.clone())?;
+
+        let df_plan = LogicalPlanBuilder::scan(table_ref, table_source, None)?
+            .sort(vec![col(POLICIES_POLICY_NAME).sort(true, true)])?
+            .build()?;
+
+        let plan = Plan::Query(QueryPlan {
+            df_plan,
+            is_tag_scan: false,
+        });
+
+        // the view only shows the policies of the role of the user unless the user manages roles
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![],
+        })
+    }
+
+    fn show_tag_body(
+        &self,
+        session: &SessionCtx,
+        body: ShowTagBody,
+        projection_function: impl FnOnce(
+            &TskvTableSchema,
+            LogicalPlanBuilder,
+            bool,
+        ) -> QueryResult<LogicalPlan>,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ShowTagBody {
+            database_name,
+            table,
+            selection,
+            order_by,
+            limit,
+            offset,
+        } = body;
+
+        let table_name = database_name
+            .map(|e| ObjectName(vec![e, table.clone()]))
+            .unwrap_or_else(|| ObjectName(vec![table]));
+        let table_ref = normalize_sql_object_name(table_name)?;
+
+        let table_schema = self.get_tskv_schema(table_ref.clone())?;
+
+        let (source_plan, _) = self.create_table_relation(table_ref, None, &Default::default())?;
+
+        // build from
+        let mut plan_builder = LogicalPlanBuilder::from(source_plan);
+
+        // build where
+        let selection = match selection {
+            Some(expr) => Some(self.df_planner.sql_to_expr(
+                expr,
+                plan_builder.schema(),
                 &mut Default::default(),
             )?),
             None => None,
@@ -1365,14 +1976,40 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             plan_builder = plan_builder.filter(selection)?;
         }
 
+        // the series and tag values are filtered by the policies on the role of the user
+        if let Some(predicate) = self.tag_policy_predicate(session, &table_schema, &plan_builder)? {
+            expr_to_columns(&predicate, &mut columns)?;
+            plan_builder = plan_builder.filter(predicate)?;
+        }
+
         // get where has time column
         let where_contain_time = columns
             .iter()
             .flat_map(|c: &Column| table_schema.column(&c.name))
             .any(|c: &TableColumn| c.column_type.is_time());
 
-        // build projection
-        let plan = projection_function(&table_schema, plan_builder, where_contain_time)?;
+        // build projection, the users granted some columns of the table only see their tags
+        let visible_columns = table_schema
+            .columns()
+            .iter()
+            .filter(|c| {
+                plan_builder
+                    .schema()
+                    .has_column_with_unqualified_name(&c.name)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let plan = if visible_columns.len() == table_schema.columns().len() {
+            projection_function(&table_schema, plan_builder, where_contain_time)?
+        } else {
+            let visible_schema = TskvTableSchema::new(
+                table_schema.tenant.clone(),
+                table_schema.db.clone(),
+                table_schema.name.clone(),
+                visible_columns,
+            );
+            projection_function(&visible_schema, plan_builder, where_contain_time)?
+        };
 
         // build order by
         let mut plan_builder = self.order_by(order_by, plan)?;
@@ -1383,20 +2020,68 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         }
 
         let df_plan = plan_builder.build()?;
-        let db_name = &table_schema.db;
+        let privileges = databases_privileges(
+            DatabasePrivilege::Read,
+            session,
+            self.schema_provider.reset_access_databases(),
+        );
 
         Ok(PlanWithPrivileges {
             plan: Plan::Query(QueryPlan {
                 df_plan,
                 is_tag_scan: true,
             }),
-            privileges: vec![Privilege::TenantObject(
-                TenantObjectPrivilege::Database(DatabasePrivilege::Read, Some(db_name.to_string())),
-                Some(*session.tenant_id()),
-            )],
+            privileges,
         })
     }
 
+    /// The predicate of the row-level security policies on the tags of the table.
+    /// The tag scans can't evaluate the policies on the fields, and can't mask the tags,
+    /// so these policies are refused.
+    fn tag_policy_predicate(
+        &self,
+        session: &SessionCtx,
+        table_schema: &TskvTableSchema,
+        plan_builder: &LogicalPlanBuilder,
+    ) -> QueryResult<Option<Expr>> {
+        let Some(role) = session.user().role() else {
+            return Ok(None);
+        };
+        let unsupported = |policy: &str| QueryError::NotImplemented {
+            err: format!(
+                "SHOW SERIES and SHOW TAG VALUES of {}.{} with {}, use SELECT DISTINCT",
+                table_schema.db, table_schema.name, policy
+            ),
+        };
+
+        let masks_tag = !session.user().can_access_role(*session.tenant_id())
+            && self.schema_provider.policies().iter().any(|policy| {
+                policy
+                    .masks(&table_schema.db, &table_schema.name, role.name())
+                    .and_then(|column| table_schema.column(column))
+                    .is_some_and(|column| column.column_type.is_tag())
+            });
+        if masks_tag {
+            return Err(unsupported("masking policies on the tags"));
+        }
+
+        let predicate = self.row_policy_predicate(
+            session,
+            &table_schema.db,
+            &table_schema.name,
+            plan_builder.schema(),
+        )?;
+        if let Some(predicate) = &predicate {
+            let mut columns = HashSet::new();
+            expr_to_columns(predicate, &mut columns)?;
+            if check_show_series_expr(&columns, table_schema).is_err() {
+                return Err(unsupported("row-level security policies on the fields"));
+            }
+        }
+
+        Ok(predicate)
+    }
+
     fn show_series_to_plan(
         &self,
         stmt: ASTShowSeries,
@@ -1588,6 +2273,29 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         if let Some(vnode_duration) = options.vnode_duration {
             plan_options.with_vnode_duration(self.str_to_duration(&vnode_duration)?);
         }
+        if let Some(replicate_to) = options.replicate_to {
+            plan_options.with_replicate_to(replicate_to);
+        }
+        if let Some(standby) = options.standby {
+            plan_options.with_standby(bool::from_str(standby.as_str()).map_err(|_| {
+                QueryError::Parser {
+                    source: ParserError::ParserError(format!(
+                        "{} is not a valid bool value, use like 'true', 'false'",
+                        standby
+                    )),
+                }
+            })?);
+        }
+        if let Some(change_feed) = options.change_feed {
+            plan_options.with_change_feed(bool::from_str(change_feed.as_str()).map_err(|_| {
+                QueryError::Parser {
+                    source: ParserError::ParserError(format!(
+                        "{} is not a valid bool value, use like 'true', 'false'",
+                        change_feed
+                    )),
+                }
+            })?);
+        }
         Ok(plan_options)
     }
 
@@ -1751,7 +2459,27 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         } = stmt;
 
         let name = normalize_ident(name);
-        let (options, _) = sql_options_to_user_options(with_options).context(ParserSnafu)?;
+        let set_password = with_options
+            .iter()
+            .any(|option| normalize_ident(option.name.clone()) == "password");
+        let (mut options, password) =
+            sql_options_to_user_options(with_options).context(ParserSnafu)?;
+        if options.ldap_auth() == Some(true) && options.granted_admin() == Some(true) {
+            return Err(QueryError::InvalidParam {
+                reason: "The admin users can't be authenticated by LDAP".to_string(),
+            });
+        }
+
+        if let Some(policy) = self.schema_provider.password_policy() {
+            if set_password {
+                check_password_strength(&policy, &password).context(AuthSnafu)?;
+                options.rotate_password(
+                    &UserOptions::default(),
+                    now_timestamp_secs(),
+                    policy.history,
+                );
+            }
+        }
 
         let privileges = vec![Privilege::Global(GlobalPrivilege::User(None))];
 
@@ -1764,6 +2492,151 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         Ok(PlanWithPrivileges { plan, privileges })
     }
 
+    async fn create_token_to_plan(
+        &self,
+        stmt: ast::CreateToken,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreateToken {
+            if_not_exists,
+            name,
+            user,
+            privileges,
+            expires,
+        } = stmt;
+
+        let name = normalize_ident(name);
+        let user_name = normalize_ident(user);
+        let user_desc = self
+            .schema_provider
+            .get_user(&user_name)
+            .await
+            .context(MetaSnafu)?;
+        let user_id = *user_desc.id();
+
+        let privileges = privileges
+            .into_iter()
+            .map(|ast::Privilege { action, database }| {
+                let database_privilege = match action {
+                    ast::Action::Read => DatabasePrivilege::Read,
+                    ast::Action::Write => DatabasePrivilege::Write,
+                    ast::Action::All => DatabasePrivilege::Full,
+                };
+                (database_privilege, normalize_ident(database))
+            })
+            .collect::<Vec<(DatabasePrivilege, String)>>();
+
+        let expires_at = expires.map(|e| token_expires_at(&e)).transpose()?;
+
+        let plan = Plan::DDL(DDLPlan::CreateToken(CreateToken {
+            tenant_name: session.tenant().to_string(),
+            name,
+            if_not_exists,
+            user_id,
+            user_name,
+            privileges,
+            expires_at,
+        }));
+
+        // the tokens of a user are managed by the user
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::Global(GlobalPrivilege::User(Some(user_id)))],
+        })
+    }
+
+    fn create_policy_to_plan(
+        &self,
+        stmt: ast::CreatePolicy,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreatePolicy {
+            if_not_exists,
+            name,
+            table,
+            column,
+            role,
+            using,
+        } = stmt;
+
+        let name = normalize_ident(name);
+        let role = normalize_ident(role);
+        let column = column.map(normalize_ident);
+        let table_ref = normalize_sql_object_name(table)?;
+        let table_source = self.get_table_source(table_ref.clone())?;
+        let table = table_ref.resolve_object(session.tenant(), session.default_database())?;
+
+        // The expression is planned against the table whenever it is scanned,
+        // check it now so that a broken policy can't be created.
+        let df_schema = table_source.schema().to_dfschema()?;
+        let expr =
+            self.df_planner
+                .sql_to_expr(using.clone(), &df_schema, &mut PlannerContext::new())?;
+        let data_type = expr.get_type(&df_schema)?;
+
+        let mut policy = PolicyDesc::new(
+            name.clone(),
+            table.database().to_string(),
+            table.table().to_string(),
+            role.clone(),
+            using.to_string(),
+        );
+        match column {
+            // the values of the masked column are replaced by the values of the expression
+            Some(column) => {
+                let field = df_schema
+                    .field_with_unqualified_name(&column)
+                    .map_err(|_| QueryError::Semantic {
+                        err: format!("Column {column} not found in table {table}"),
+                    })?;
+                if data_type != *field.data_type() {
+                    return Err(QueryError::Semantic {
+                        err: format!(
+                            "The USING expression of masking policy {name} must be a {}, but found {data_type}",
+                            field.data_type()
+                        ),
+                    });
+                }
+                if let Some(other) = self.schema_provider.policies().iter().find(|other| {
+                    other.name() != name
+                        && other.masks(table.database(), table.table(), &role)
+                            == Some(column.as_str())
+                }) {
+                    return Err(QueryError::Semantic {
+                        err: format!(
+                            "Column {column} of table {table} is already masked for role {role} by policy {}",
+                            other.name()
+                        ),
+                    });
+                }
+                policy = policy.with_masked_column(column);
+            }
+            None if data_type != DataType::Boolean => {
+                return Err(QueryError::Semantic {
+                    err: format!(
+                        "The USING expression of policy {name} must be a boolean, but found {data_type}"
+                    ),
+                });
+            }
+            None => {}
+        }
+
+        let plan = Plan::DDL(DDLPlan::CreatePolicy(CreatePolicy {
+            tenant_name: session.tenant().to_string(),
+            if_not_exists,
+            policy,
+        }));
+
+        // the policies restrict the members of the roles, they are managed along with the roles
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::RoleFull,
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     fn create_role_to_plan(
         &self,
         stmt: ast::CreateRole,
@@ -1807,6 +2680,101 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         })
     }
 
+    fn create_function_to_plan(
+        &self,
+        stmt: ast::CreateFunction,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::CreateFunction {
+            or_replace,
+            aggregate,
+            name,
+            args,
+            return_type,
+            language,
+            body,
+        } = stmt;
+
+        let name = normalize_ident(name);
+        let is_builtin = BuiltinScalarFunction::from_str(&name).is_ok()
+            || AggregateFunction::from_str(&name).is_ok()
+            || (self.schema_provider.get_user_function(&name).is_none()
+                && (self.schema_provider.get_function_meta(&name).is_some()
+                    || self.schema_provider.get_aggregate_meta(&name).is_some()));
+        if is_builtin {
+            return Err(QueryError::Semantic {
+                err: format!("Function {name} already exists as a built-in function"),
+            });
+        }
+
+        let column = |(name, data_type)| ColumnDef {
+            name,
+            data_type,
+            collation: None,
+            options: vec![],
+        };
+        let args = self
+            .df_planner
+            .build_schema(args.into_iter().map(column).collect())?
+            .fields()
+            .iter()
+            .map(|e| FunctionArg::new(e.name(), e.data_type().clone()))
+            .collect();
+        let return_type = self
+            .df_planner
+            .build_schema(vec![column((Ident::new("return_type"), return_type))])?
+            .field(0)
+            .data_type()
+            .clone();
+
+        let language = language.map(normalize_ident).unwrap_or_default();
+        let body = match language.to_ascii_uppercase().as_str() {
+            "" | "SQL" => FunctionBody::Sql(body),
+            "WASM" => FunctionBody::Wasm(BASE64_STANDARD.decode(body).map_err(|e| {
+                QueryError::Semantic {
+                    err: format!(
+                        "The body of a WASM function must be a base64 encoded module: {e}"
+                    ),
+                }
+            })?),
+            _ => {
+                return Err(QueryError::NotImplemented {
+                    err: format!("Function language {language}"),
+                })
+            }
+        };
+
+        let kind = if aggregate {
+            FunctionKind::Aggregate
+        } else {
+            FunctionKind::Scalar
+        };
+        let function = FunctionSchema::new(session.tenant(), name, kind, args, return_type, body);
+
+        // check the function can be planned before it is saved
+        match kind {
+            FunctionKind::Scalar => {
+                create_user_udf(&function, self.schema_provider)?;
+            }
+            FunctionKind::Aggregate => {
+                create_user_udaf(&function)?;
+            }
+        }
+
+        let plan = Plan::DDL(DDLPlan::CreateFunction(CreateFunction {
+            or_replace,
+            function,
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::Database(DatabasePrivilege::Full, None),
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     async fn construct_alter_tenant_action_with_privilege(
         &self,
         tenant: Tenant,
@@ -1984,6 +2952,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                 AlterUserAction::RenameTo(normalize_ident(new_name))
             }
             AlterUserOperation::Set(sql_option) => {
+                let set_password = normalize_ident(sql_option.name.clone()) == "password";
                 let (mut sql_user_option, password) =
                     sql_options_to_user_options(vec![sql_option]).context(ParserSnafu)?;
                 let user_desc = user.desc();
@@ -2013,6 +2982,18 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                         privilege: "root user".to_string(),
                     });
                 }
+                if let Some(policy) = self.schema_provider.password_policy() {
+                    if set_password {
+                        check_password_strength(&policy, &password).context(AuthSnafu)?;
+                        check_password_history(&policy, &password, sql_user_desc.options())
+                            .context(AuthSnafu)?;
+                        sql_user_option.rotate_password(
+                            sql_user_desc.options(),
+                            now_timestamp_secs(),
+                            policy.history,
+                        );
+                    }
+                }
                 if sql_user_option.granted_admin().is_some() {
                     if sql_user_desc.is_root_admin() {
                         return Err(QueryError::InvalidParam {
@@ -2023,6 +3004,15 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     // 修改admin参数需要系统管理权限
                     privileges = vec![Privilege::Global(GlobalPrivilege::System)];
                 }
+                if let Some(ldap_auth) = sql_user_option.ldap_auth() {
+                    if ldap_auth && sql_user_desc.is_admin() {
+                        return Err(QueryError::InvalidParam {
+                            reason: "The admin users can't be authenticated by LDAP".to_string(),
+                        });
+                    }
+                    // the password of an existing user is only delegated to LDAP by the system admins
+                    privileges = vec![Privilege::Global(GlobalPrivilege::System)];
+                }
                 AlterUserAction::Set(sql_user_option)
             }
         };
@@ -2035,6 +3025,40 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         Ok(PlanWithPrivileges { plan, privileges })
     }
 
+    fn alter_role_to_plan(
+        &self,
+        stmt: ast::AlterRole,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let ast::AlterRole { name, operation } = stmt;
+
+        let role_name = normalize_ident(name);
+        if SystemTenantRole::try_from(role_name.as_str()).is_ok() {
+            let err = QueryError::SystemRoleModification;
+            warn!("{}", err.to_string());
+            return Err(err);
+        }
+
+        let alter_role_action = match operation {
+            AlterRoleOperation::Set(sql_option) => sql_option_to_alter_role_action(sql_option)?,
+            AlterRoleOperation::UnSet(ident) => unset_option_to_alter_role_action(ident)?,
+        };
+
+        let plan = Plan::DDL(DDLPlan::AlterRole(AlterRole {
+            tenant_name: session.tenant().to_string(),
+            role_name,
+            alter_role_action,
+        }));
+
+        Ok(PlanWithPrivileges {
+            plan,
+            privileges: vec![Privilege::TenantObject(
+                TenantObjectPrivilege::RoleFull,
+                Some(*session.tenant_id()),
+            )],
+        })
+    }
+
     fn grant_revoke_to_plan(
         &self,
         stmt: ast::GrantRevoke,
@@ -2047,6 +3071,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         let ast::GrantRevoke {
             is_grant,
             privileges,
+            table_privileges,
             role_name,
         } = stmt;
 
@@ -2074,6 +3099,45 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
             })
             .collect::<Vec<(DatabasePrivilege, String)>>();
 
+        let table_privileges = table_privileges
+            .into_iter()
+            .map(
+                |ast::TablePrivilege {
+                     action,
+                     table,
+                     columns,
+                 }| {
+                    let table_ref = normalize_sql_object_name(table)?;
+                    let columns = columns
+                        .into_iter()
+                        .map(normalize_ident)
+                        .collect::<BTreeSet<_>>();
+                    if is_grant && !columns.is_empty() {
+                        let schema = self.get_table_source(table_ref.clone())?.schema();
+                        if let Some(column) = columns.iter().find(|c| schema.index_of(c).is_err()) {
+                            return Err(QueryError::Semantic {
+                                err: format!("Column {column} not found in table {table_ref}"),
+                            });
+                        }
+                    }
+                    let table =
+                        table_ref.resolve_object(tenant_name, session.default_database())?;
+                    let privilege = match action {
+                        ast::Action::Read => DatabasePrivilege::Read,
+                        ast::Action::Write => DatabasePrivilege::Write,
+                        ast::Action::All => DatabasePrivilege::Full,
+                    };
+
+                    Ok(TablePrivilege::new(
+                        privilege,
+                        table.database().to_string(),
+                        table.table().to_string(),
+                        (!columns.is_empty()).then_some(columns),
+                    ))
+                },
+            )
+            .collect::<QueryResult<Vec<_>>>()?;
+
         let privileges = vec![Privilege::TenantObject(
             TenantObjectPrivilege::RoleFull,
             Some(tenant_id),
@@ -2082,6 +3146,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         let plan = Plan::DDL(DDLPlan::GrantRevoke(GrantRevoke {
             is_grant,
             database_privileges,
+            table_privileges,
             tenant_name: tenant_name.to_string(),
             role_name,
         }));
@@ -2300,29 +3365,292 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
                     .unwrap_or_default(),
             };
 
-            let schema = self.df_planner.build_schema(columns)?;
-
-            let plan = Plan::DDL(DDLPlan::CreateStreamTable(CreateStreamTable {
-                if_not_exists,
-                name: resolved_table,
-                schema,
-                stream_type,
-                watermark,
-                extra_options,
-            }));
+            let schema = self.df_planner.build_schema(columns)?;
+
+            let plan = Plan::DDL(DDLPlan::CreateStreamTable(CreateStreamTable {
+                if_not_exists,
+                name: resolved_table,
+                schema,
+                stream_type,
+                watermark,
+                extra_options,
+            }));
+
+            // privilege
+            return Ok(PlanWithPrivileges {
+                plan,
+                privileges: vec![Privilege::TenantObject(
+                    TenantObjectPrivilege::Database(DatabasePrivilege::Full, Some(database_name)),
+                    Some(*session.tenant_id()),
+                )],
+            });
+        }
+
+        Err(QueryError::Internal {
+            reason: format!("CreateStreamTable: {stmt}"),
+        })
+    }
+
+    fn create_view_to_plan(
+        &self,
+        stmt: Statement,
+        session: &SessionCtx,
+    ) -> QueryResult<PlanWithPrivileges> {
+        let Statement::CreateView {
+            or_replace,
+            materialized,
+            name,
+            columns,
+            query,
+            with_options,
+            ..
+        } = stmt
+        else {
+            return Err(QueryError::Internal {
+                reason: format!("CreateView: {stmt}"),
+            });
+        };
+
+        if !columns.is_empty() {
+            return Err(QueryError::NotImplemented {
+                err: "CREATE VIEW with a column list".to_string(),
+            });
+        }
+        if materialized && or_replace {
+            return Err(QueryError::NotImplemented {
+                err: "CREATE OR REPLACE MATERIALIZED VIEW".to_string(),
+            });
+        }
+
+        let view = object_name_to_resolved_table(session, name)?;
+        let database_name = view.database().to_string();
+
+        // Validate the query, unqualified tables belong to the database of the view
+        let df_plan = view_query_to_plan(
+            self.schema_provider,
+            &database_name,
+            Statement::Query(query.clone()),
+        )?;
+        let access_databases = self.schema_provider.reset_access_databases();
+        if access_databases
+            .table_set(&database_name)
+            .map(|tables| tables.contains(view.table()))
+            .unwrap_or_default()
+        {
+            return Err(QueryError::Semantic {
+                err: format!("View {view} can not reference itself"),
+            });
+        }
+
+        let schema: Schema = df_plan.schema().as_ref().into();
+        let mut column_names = HashSet::new();
+        for f in schema.fields() {
+            if !column_names.insert(f.name()) {
+                return Err(QueryError::SameColumnName {
+                    column: f.name().to_string(),
+                });
+            }
+        }
+
+        let materialized = if materialized {
+            Some(self.materialized_view_to_plan(&view, &query, &schema, &with_options, session)?)
+        } else {
+            None
+        };
+
+        let plan = Plan::DDL(DDLPlan::CreateView(CreateView {
+            name: view,
+            or_replace,
+            query: query.to_string(),
+            schema: Arc::new(schema),
+            materialized,
+        }));
+
+        // privilege
+        let mut privileges =
+            databases_privileges(DatabasePrivilege::Read, session, access_databases);
+        privileges.push(Privilege::TenantObject(
+            TenantObjectPrivilege::Database(DatabasePrivilege::Full, Some(database_name)),
+            Some(*session.tenant_id()),
+        ));
+        Ok(PlanWithPrivileges { plan, privileges })
+    }
+
+    /// A materialized view selects from a single tskv table of its database,
+    /// the new writes to the table are read through a stream table and the result of the query
+    /// is inserted into a tskv table by a stream query.
+    fn materialized_view_to_plan(
+        &self,
+        view: &ResolvedTable,
+        query: &Query,
+        schema: &Schema,
+        with_options: &[SqlOption],
+        session: &SessionCtx,
+    ) -> QueryResult<CreateMaterializedView> {
+        let tenant_name = view.tenant();
+        let database_name = view.database();
+
+        let mut refresh_query = query.clone();
+        let Some(TableFactor::Table {
+            name: base_name,
+            alias,
+            ..
+        }) = single_table_of_query(&mut refresh_query)
+        else {
+            return Err(QueryError::Semantic {
+                err: format!(
+                    "The query of materialized view {view} must select from a single table"
+                ),
+            });
+        };
+        let base_table = normalize_sql_object_name(base_name.clone())?
+            .resolve_object(tenant_name, database_name)?;
+        if base_table.database() != database_name {
+            return Err(QueryError::Semantic {
+                err: format!(
+                    "The table {base_table} of materialized view {view} must belong to database {database_name}"
+                ),
+            });
+        }
+        let base_schema = self.get_tskv_schema(TableReference::partial(
+            base_table.database(),
+            base_table.table(),
+        ))?;
+
+        match schema.field_with_name(TIME_FIELD_NAME) {
+            Ok(f) if matches!(f.data_type(), DataType::Timestamp(_, _)) => {}
+            _ => {
+                return Err(QueryError::Semantic {
+                    err: format!(
+                        "The query of materialized view {view} must output the timestamp column {TIME_FIELD_NAME}"
+                    ),
+                })
+            }
+        }
+
+        // The storage table keeps the tags of the base table as tags
+        let id_generator = SeqIdGenerator::default();
+        let unit: TimeUnit = self.get_db_precision(database_name)?.into();
+        let mut columns = vec![TableColumn::new_time_column(
+            id_generator.next_id() as ColumnId,
+            unit,
+        )];
+        for f in schema.fields() {
+            if f.name() == TIME_FIELD_NAME {
+                continue;
+            }
+            let id = id_generator.next_id() as ColumnId;
+            let is_tag = f.data_type() == &DataType::Utf8
+                && base_schema
+                    .column(f.name())
+                    .map(|c| c.column_type.is_tag())
+                    .unwrap_or_default();
+            let column = if is_tag {
+                TableColumn::new_tag_column(id, f.name().clone())
+            } else {
+                match ColumnType::from(f.data_type().clone()) {
+                    column_type @ ColumnType::Field(ValueType::Float)
+                    | column_type @ ColumnType::Field(ValueType::Integer)
+                    | column_type @ ColumnType::Field(ValueType::Unsigned)
+                    | column_type @ ColumnType::Field(ValueType::Boolean)
+                    | column_type @ ColumnType::Field(ValueType::String) => {
+                        TableColumn::new(id, f.name().clone(), column_type, Default::default())
+                    }
+                    _ => {
+                        return Err(QueryError::Semantic {
+                            err: format!(
+                            "Column {} of type {} can not be stored by materialized view {view}",
+                            f.name(),
+                            f.data_type()
+                        ),
+                        })
+                    }
+                }
+            };
+            columns.push(column);
+        }
+        if !columns.iter().any(|c| c.column_type.is_field()) {
+            return Err(QueryError::AtLeastOneField);
+        }
+
+        let options = sql_options_to_map(with_options);
+        let refresh_interval = options
+            .get(REFRESH_INTERVAL_OPTION)
+            .map(|e| {
+                parse_duration(e).map_err(|err| QueryError::InvalidTableOption {
+                    option_name: REFRESH_INTERVAL_OPTION.into(),
+                    table_name: view.to_string(),
+                    reason: err,
+                })
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_REFRESH_INTERVAL);
+        if refresh_interval.is_zero() {
+            return Err(QueryError::InvalidTableOption {
+                option_name: REFRESH_INTERVAL_OPTION.into(),
+                table_name: view.to_string(),
+                reason: "must be greater than 0".to_string(),
+            });
+        }
+        let watermark_delay = get_watermark_delay(view.table(), &options)?.unwrap_or_default();
 
-            // privilege
-            return Ok(PlanWithPrivileges {
-                plan,
-                privileges: vec![Privilege::TenantObject(
-                    TenantObjectPrivilege::Database(DatabasePrivilege::Full, Some(database_name)),
-                    Some(*session.tenant_id()),
-                )],
+        let storage_table = OwnedTableReference::partial(
+            database_name,
+            MaterializedView::storage_table_name(view.table()),
+        )
+        .resolve_object(tenant_name, database_name)?;
+        let source_table = OwnedTableReference::partial(
+            database_name,
+            MaterializedView::source_table_name(view.table()),
+        )
+        .resolve_object(tenant_name, database_name)?;
+
+        // Read the new writes of the base table from the source table,
+        // the columns qualified by the base table are still resolved through the alias
+        if alias.is_none() {
+            *alias = Some(TableAlias {
+                name: Ident::with_quote('"', base_table.table()),
+                columns: vec![],
             });
         }
+        *base_name = quoted_object_name(source_table.database(), source_table.table());
+        let refresh_sql = format!(
+            "INSERT INTO {} ({}) {refresh_query}",
+            quoted_object_name(storage_table.database(), storage_table.table()),
+            schema
+                .fields()
+                .iter()
+                .map(|f| Ident::with_quote('"', f.name()).to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
 
-        Err(QueryError::Internal {
-            reason: format!("CreateStreamTable: {stmt}"),
+        let extra_options = HashMap::from([
+            (STREAM_DB_KEY.to_string(), database_name.to_string()),
+            (STREAM_TABLE_KEY.to_string(), base_table.table().to_string()),
+        ]);
+
+        Ok(CreateMaterializedView {
+            base_table: base_table.table().to_string(),
+            storage_table: CreateTable {
+                schema: columns,
+                name: storage_table,
+                if_not_exists: false,
+            },
+            source_table: CreateStreamTable {
+                if_not_exists: false,
+                name: source_table,
+                // All columns of the base table
+                schema: Schema::empty(),
+                stream_type: TSKV_STREAM_PROVIDER.to_string(),
+                watermark: Watermark {
+                    column: TIME_FIELD_NAME.to_string(),
+                    delay: watermark_delay,
+                },
+                extra_options,
+            },
+            refresh_sql,
+            refresh_interval,
         })
     }
 
@@ -2398,7 +3726,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
                 let database_set = self.schema_provider.reset_access_databases();
                 let privileges =
-                    databases_privileges(DatabasePrivilege::Read, tenant_id, database_set);
+                    databases_privileges(DatabasePrivilege::Read, session, database_set);
                 Ok(PlanWithPrivileges { plan, privileges })
             }
         }
@@ -2497,6 +3825,7 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
 
         // 2. build source plan
         let source_plan = self.create_relation(from, &Default::default())?;
+        let source_plan = self.apply_policies(source_plan, session)?;
         let source_schem = SchemaRef::new(source_plan.schema().deref().into());
 
         // 3. According to the external path, construct the external table
@@ -2519,6 +3848,238 @@ impl<'a, S: ContextProviderExtension + Send + Sync + 'a> SqlPlanner<'a, S> {
         }))
     }
 
+    /// Applies the policies on the role of the user to the table scans of the plan, including
+    /// the scans of its subqueries and of the views it queries, which are inlined into the plan.
+    /// A materialized view over a table with policies is inlined as its query on the table.
+    /// The rows are filtered by the row-level security policies, then the columns are masked
+    /// by the masking policies.
+    fn apply_policies(&self, plan: LogicalPlan, session: &SessionCtx) -> DFResult<LogicalPlan> {
+        let Some(role) = session.user().role() else {
+            return Ok(plan);
+        };
+        if !self
+            .schema_provider
+            .policies()
+            .iter()
+            .any(|policy| policy.role() == role.name())
+        {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            // the subqueries are in the expressions rather than the inputs of the plan
+            let plan = if plan.expressions().iter().any(contains_subquery) {
+                let exprs = plan
+                    .expressions()
+                    .into_iter()
+                    .map(|expr| {
+                        expr.transform_up(&|expr| {
+                            self.apply_policies_to_subquery(expr, session)
+                                .map(Transformed::Yes)
+                        })
+                    })
+                    .collect::<DFResult<Vec<_>>>()?;
+                let inputs = plan.inputs().into_iter().cloned().collect::<Vec<_>>();
+                from_plan(&plan, &exprs, &inputs)?
+            } else {
+                plan
+            };
+
+            let LogicalPlan::TableScan(scan) = &plan else {
+                return Ok(Transformed::Yes(plan));
+            };
+            let table = scan
+                .table_name
+                .clone()
+                .resolve(session.tenant(), session.default_database());
+            let table_name = scan.table_name.clone();
+            let view = view_plan(scan);
+            if view.is_none() {
+                self.check_materialized_view_storage(session, &table.schema, &table.table)?;
+            }
+
+            // the policies on the tables queried by the view are applied to the plan of the view
+            let plan = match view {
+                Some(view) => LogicalPlanBuilder::from(self.apply_policies(view, session)?)
+                    .alias(table_name.clone())?
+                    .build()?,
+                None => plan,
+            };
+            let schema = plan.schema().clone();
+
+            let plan = match self.row_policy_predicate(
+                session,
+                &table.schema,
+                &table.table,
+                schema.as_ref(),
+            )? {
+                Some(predicate) => LogicalPlanBuilder::from(plan).filter(predicate)?.build()?,
+                None => plan,
+            };
+            // the masked columns keep the names and the qualifier of the table columns
+            let plan = match self.masking_projection(
+                session,
+                &table.schema,
+                &table.table,
+                schema.as_ref(),
+            )? {
+                Some(exprs) => LogicalPlanBuilder::from(plan)
+                    .project(exprs)?
+                    .alias(table_name)?
+                    .build()?,
+                None => plan,
+            };
+            Ok(Transformed::Yes(plan))
+        })
+    }
+
+    /// The storage table of a materialized view keeps the rows of the base table unfiltered
+    /// and unmasked, the members of a role with policies on the base table can only read
+    /// the view, whose query is planned against the base table for them.
+    fn check_materialized_view_storage(
+        &self,
+        session: &SessionCtx,
+        database: &str,
+        table: &str,
+    ) -> DFResult<()> {
+        let Some(role) = session.user().role() else {
+            return Ok(());
+        };
+        let Some(view) = self
+            .schema_provider
+            .stored_materialized_view(database, table)?
+        else {
+            return Ok(());
+        };
+        let Some(base_table) = view.materialized().map(|e| e.base_table()) else {
+            return Ok(());
+        };
+
+        if self
+            .schema_provider
+            .policies()
+            .iter()
+            .any(|policy| policy.on(database, base_table, role.name()))
+        {
+            return Err(DataFusionError::Plan(format!(
+                "Table {database}.{table} stores the rows of materialized view {database}.{} without the policies on table {database}.{base_table}, query the view instead",
+                view.name()
+            )));
+        }
+        Ok(())
+    }
+
+    fn apply_policies_to_subquery(&self, expr: Expr, session: &SessionCtx) -> DFResult<Expr> {
+        let apply = |subquery: Subquery| -> DFResult<Subquery> {
+            let plan = self.apply_policies(subquery.subquery.as_ref().clone(), session)?;
+            Ok(Subquery {
+                subquery: Arc::new(plan),
+                ..subquery
+            })
+        };
+
+        let expr = match expr {
+            Expr::ScalarSubquery(subquery) => Expr::ScalarSubquery(apply(subquery)?),
+            Expr::Exists(Exists { subquery, negated }) => Expr::Exists(Exists {
+                subquery: apply(subquery)?,
+                negated,
+            }),
+            Expr::InSubquery(InSubquery {
+                expr,
+                subquery,
+                negated,
+            }) => Expr::InSubquery(InSubquery {
+                expr,
+                subquery: apply(subquery)?,
+                negated,
+            }),
+            expr => expr,
+        };
+        Ok(expr)
+    }
+
+    /// The predicate on the visible rows of the table for the user,
+    /// a row is visible if it satisfies any of the policies on the role of the user.
+    fn row_policy_predicate(
+        &self,
+        session: &SessionCtx,
+        database: &str,
+        table: &str,
+        schema: &DFSchema,
+    ) -> DFResult<Option<Expr>> {
+        let Some(role) = session.user().role() else {
+            return Ok(None);
+        };
+
+        let predicate = self
+            .schema_provider
+            .policies()
+            .iter()
+            .filter(|policy| policy.applies_to(database, table, role.name()))
+            .map(|policy| {
+                let using = Parser::new(&CnosDBDialect {})
+                    .try_with_sql(policy.using())?
+                    .parse_expr()?;
+                self.df_planner
+                    .sql_to_expr(using, schema, &mut PlannerContext::new())
+            })
+            .collect::<DFResult<Vec<_>>>()?
+            .into_iter()
+            .reduce(Expr::or);
+
+        Ok(predicate)
+    }
+
+    /// The projection of the columns of the table for the user, in which the columns masked by
+    /// the masking policies on the role of the user are replaced by the masked values.
+    /// The users who can manage the roles of the tenant see the raw values.
+    fn masking_projection(
+        &self,
+        session: &SessionCtx,
+        database: &str,
+        table: &str,
+        schema: &DFSchema,
+    ) -> DFResult<Option<Vec<Expr>>> {
+        let Some(role) = session.user().role() else {
+            return Ok(None);
+        };
+        if session.user().can_access_role(*session.tenant_id()) {
+            return Ok(None);
+        }
+
+        let mut masks = HashMap::new();
+        for policy in self.schema_provider.policies() {
+            let Some(column) = policy.masks(database, table, role.name()) else {
+                continue;
+            };
+            if !schema.has_column_with_unqualified_name(column) {
+                continue;
+            }
+            let using = Parser::new(&CnosDBDialect {})
+                .try_with_sql(policy.using())?
+                .parse_expr()?;
+            let masked = self
+                .df_planner
+                .sql_to_expr(using, schema, &mut PlannerContext::new())?;
+            masks.insert(column.to_string(), masked);
+        }
+        if masks.is_empty() {
+            return Ok(None);
+        }
+
+        let exprs = schema
+            .fields()
+            .iter()
+            .map(|field| match masks.remove(field.name()) {
+                Some(masked) => Ok(masked
+                    .cast_to(field.data_type(), schema)?
+                    .alias(field.name())),
+                None => Ok(Expr::Column(field.qualified_column())),
+            })
+            .collect::<DFResult<Vec<_>>>()?;
+        Ok(Some(exprs))
+    }
+
     fn create_table_relation(
         &self,
         table_ref: OwnedTableReference,
@@ -2749,6 +4310,18 @@ fn build_file_format(
 
 // check
 // show series can't include field column
+/// The plan of the view scanned by the table scan, which is not yet inlined
+fn view_plan(scan: &TableScan) -> Option<LogicalPlan> {
+    if scan.projection.is_some() || !scan.filters.is_empty() {
+        return None;
+    }
+    let adapter = scan.source.as_any().downcast_ref::<TableSourceAdapter>()?;
+    match adapter.table_handle() {
+        TableHandle::TableProvider(table) => table.get_logical_plan().cloned(),
+        _ => None,
+    }
+}
+
 fn check_show_series_expr(
     columns: &HashSet<Column>,
     table_schema: &TskvTableSchema,
@@ -2906,7 +4479,7 @@ fn show_tag_value_projections(
     Ok(union_distinct)
 }
 
-fn check_privilege(user: &User, privileges: Vec<Privilege<Oid>>) -> QueryResult<()> {
+fn check_privilege(user: &User, privileges: &[Privilege<Oid>]) -> QueryResult<()> {
     let privileges_str = privileges
         .iter()
         .map(|e| format!("{:?}", e))
@@ -2924,22 +4497,141 @@ fn check_privilege(user: &User, privileges: Vec<Privilege<Oid>>) -> QueryResult<
     Ok(())
 }
 
+/// The privileges on the accessed databases, the users who are only granted
+/// some tables of a database need the privileges on the accessed tables instead.
+/// The privileges on the databases and tables read by the statement,
+/// the users granted some columns of a table only read these columns.
 fn databases_privileges(
     db_priv: DatabasePrivilege,
-    tenant_id: Oid,
+    session: &SessionCtx,
+    databases: DatabaseSet,
+) -> Vec<Privilege<Oid>> {
+    let user = session.user();
+    let tenant_id = *session.tenant_id();
+    table_privileges(db_priv, session, databases, |db, table| {
+        user.readable_columns(tenant_id, db, table)
+            .filter(|columns| !columns.is_empty())
+    })
+}
+
+/// The privileges on the databases and tables written by the statement,
+/// `columns` are the columns written to the tables, None if all of them are written.
+fn write_privileges(
+    session: &SessionCtx,
+    databases: DatabaseSet,
+    columns: Option<BTreeSet<String>>,
+) -> Vec<Privilege<Oid>> {
+    table_privileges(DatabasePrivilege::Write, session, databases, |_, _| {
+        columns.clone()
+    })
+}
+
+/// The privileges on the databases, or on the `columns` of the tables
+/// if the user is only granted privileges on some tables of the databases.
+fn table_privileges(
+    db_priv: DatabasePrivilege,
+    session: &SessionCtx,
     databases: DatabaseSet,
+    columns: impl Fn(&str, &str) -> Option<BTreeSet<String>>,
 ) -> Vec<Privilege<Oid>> {
-    databases
-        .dbs()
-        .into_iter()
-        .cloned()
-        .map(|db| {
-            Privilege::TenantObject(
-                TenantObjectPrivilege::Database(db_priv.clone(), Some(db)),
+    let user = session.user();
+    let tenant_id = *session.tenant_id();
+    let mut privileges = vec![];
+    for db in databases.dbs() {
+        let database_privilege = Privilege::TenantObject(
+            TenantObjectPrivilege::Database(db_priv.clone(), Some(db.clone())),
+            Some(tenant_id),
+        );
+        let table_set = match databases.table_set(db) {
+            Some(table_set) if !user.check_privilege(&database_privilege) => table_set,
+            _ => {
+                privileges.push(database_privilege);
+                continue;
+            }
+        };
+        if !user.has_table_privileges(tenant_id, db) {
+            privileges.push(database_privilege);
+            continue;
+        }
+
+        for table in table_set.tables() {
+            privileges.push(Privilege::TenantObject(
+                TenantObjectPrivilege::Table(TablePrivilege::new(
+                    db_priv.clone(),
+                    db.clone(),
+                    table.clone(),
+                    columns(db, table),
+                )),
                 Some(tenant_id),
-            )
-        })
-        .collect()
+            ));
+        }
+    }
+    privileges
+}
+
+fn contains_subquery(expr: &Expr) -> bool {
+    let mut found = false;
+    let _ = expr.apply(&mut |e| {
+        found = matches!(
+            e,
+            Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_)
+        );
+        if found {
+            Ok(VisitRecursion::Stop)
+        } else {
+            Ok(VisitRecursion::Continue)
+        }
+    });
+    found
+}
+
+fn with_clause(ctes: &[Cte]) -> String {
+    if ctes.is_empty() {
+        return String::new();
+    }
+    let ctes = ctes.iter().map(|cte| cte.to_string()).collect::<Vec<_>>();
+    format!("WITH {} ", ctes.join(", "))
+}
+
+fn parse_query(sql: &str) -> QueryResult<Query> {
+    Parser::new(&CnosDBDialect {})
+        .try_with_sql(sql)
+        .context(ParserSnafu)?
+        .parse_query()
+        .context(ParserSnafu)
+}
+
+/// The column name of a pivoted value
+fn pivot_value_name(value: &Value) -> String {
+    match value {
+        Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => s.clone(),
+        Value::Number(n, _) => n.clone(),
+        Value::Boolean(b) => b.to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// The table of `SELECT ... FROM <table>`
+fn single_table_of_query(query: &mut Query) -> Option<&mut TableFactor> {
+    if query.with.is_some() {
+        return None;
+    }
+    match query.body.as_mut() {
+        SetExpr::Select(select) if select.from.len() == 1 && select.from[0].joins.is_empty() => {
+            match &mut select.from[0].relation {
+                table @ TableFactor::Table { args: None, .. } => Some(table),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn quoted_object_name(database: &str, table: &str) -> ObjectName {
+    ObjectName(vec![
+        Ident::with_quote('"', database),
+        Ident::with_quote('"', table),
+    ])
 }
 
 fn extract_database_table_name<'a>(
@@ -3068,28 +4760,45 @@ fn make_geometry_data_type(params: &[String]) -> std::result::Result<ColumnType,
     Ok(ColumnType::Field(ValueType::Geometry(geo_type)))
 }
 
+/// The expiration of a token is a duration from now, e.g. `90d`, or a RFC 3339 timestamp,
+/// returns the milliseconds since the unix epoch
+fn token_expires_at(text: &str) -> QueryResult<i64> {
+    if let Some(duration) = CnosDuration::new(text) {
+        return Ok(now_timestamp_millis() + duration.to_millisecond());
+    }
+
+    chrono::DateTime::parse_from_rfc3339(text)
+        .map(|time| time.timestamp_millis())
+        .map_err(|_| QueryError::Semantic {
+            err: format!("{text} is not a valid duration or RFC 3339 timestamp"),
+        })
+}
+
+/// Converts a table name pattern, where `*` matches any characters, into an anchored regex
+fn table_pattern_to_regex(pattern: &str) -> QueryResult<Regex> {
+    let regex = pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(".*");
+    Regex::new(&format!("^{regex}$")).map_err(|err| QueryError::Semantic {
+        err: format!("Invalid table pattern {pattern}: {err}"),
+    })
+}
+
 /// 合法性检查
 ///
 /// - 只能对tskv表执行delete操作
-/// - 过滤条件中不能包含field列
+/// - 过滤条件中的列必须存在, 包含field列时按行删除
 fn valid_delete(schema: &TskvTableSchema, selection: &Option<Expr>) -> QueryResult<()> {
     if let Some(expr) = selection {
         let using_columns = expr.to_columns()?;
         for col_name in using_columns.iter() {
-            match schema.column(&col_name.name) {
-                Some(col) => {
-                    if col.column_type.is_field() {
-                        return Err(QueryError::NotImplemented { err:
-                            "Filtering on the field column on the tskv table in delete statement".to_string(),
-                        });
-                    }
-                }
-                None => {
-                    return Err(QueryError::ColumnNotExists {
-                        table: schema.name.to_string(),
-                        column: col_name.name.to_string(),
-                    })
-                }
+            if schema.column(&col_name.name).is_none() {
+                return Err(QueryError::ColumnNotExists {
+                    table: schema.name.to_string(),
+                    column: col_name.name.to_string(),
+                });
             }
         }
     }
@@ -3116,6 +4825,7 @@ mod tests {
     use datafusion::sql::TableReference;
     use lazy_static::__Deref;
     use meta::error::MetaError;
+    use models::auth::role::RoleQuota;
     use models::auth::user::{User, UserDesc, UserOptions};
     use models::codec::Encoding;
     use models::meta_data::DatabaseInfo;
@@ -3180,6 +4890,26 @@ mod tests {
                 table as Arc<dyn TableProvider>,
             )?))
         }
+
+        fn policies(&self) -> Vec<PolicyDesc> {
+            vec![
+                PolicyDesc::new(
+                    "test_policy".to_string(),
+                    "public".to_string(),
+                    "test_tb".to_string(),
+                    "member".to_string(),
+                    "field_string = 'a'".to_string(),
+                ),
+                PolicyDesc::new(
+                    "test_masking_policy".to_string(),
+                    "public".to_string(),
+                    "test_tb".to_string(),
+                    "member".to_string(),
+                    "field_int * 0".to_string(),
+                )
+                .with_masked_column("field_int".to_string()),
+            ]
+        }
     }
 
     impl ContextProvider for MockContext {
@@ -3267,13 +4997,17 @@ mod tests {
     }
 
     fn session() -> SessionCtx {
+        session_with_role(None)
+    }
+
+    fn session_with_role(role: Option<TenantRoleIdentifier>) -> SessionCtx {
         let user_desc = UserDesc::new(
             0_u128,
             "test_name".to_string(),
             UserOptions::default(),
             false,
         );
-        let user = User::new(user_desc, HashSet::default(), None);
+        let user = User::new(user_desc, HashSet::default(), role);
         let context = ContextBuilder::new(user).build();
         let pool = UnboundedMemoryPool::default();
         SessionCtxFactory::default()
@@ -3282,6 +5016,7 @@ mod tests {
                 &context,
                 0_u128,
                 Arc::new(pool),
+                RoleQuota::default(),
                 None,
                 Arc::new(MockCoordinator {}),
             )
@@ -3550,4 +5285,174 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_create_view() {
+        let sql = "create or replace view test_view as select field_int from test_tb";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session(), false)
+            .await
+            .unwrap();
+        match plan.plan {
+            Plan::DDL(DDLPlan::CreateView(view)) => {
+                assert_eq!(view.name.table(), "test_view");
+                assert!(view.or_replace);
+                assert!(view.materialized.is_none());
+                assert_eq!(view.query, "SELECT field_int FROM test_tb");
+                assert_eq!(view.schema.fields().len(), 1);
+                assert_eq!(view.schema.field(0).name(), "field_int");
+            }
+            _ => panic!("expected create view plan"),
+        }
+
+        let sql = "create view test_view (a) as select field_int from test_tb";
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let err = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session(), false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, QueryError::NotImplemented { .. }));
+    }
+
+    async fn query_columns(sql: &str) -> Vec<String> {
+        let mut statements = ExtParser::parse_sql(sql).unwrap();
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let plan = planner
+            .statement_to_plan(statements.pop_back().unwrap(), &session(), false)
+            .await
+            .unwrap();
+        match plan.plan {
+            Plan::Query(QueryPlan { df_plan, .. }) => df_plan
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().clone())
+                .collect(),
+            _ => panic!("expected query plan"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_row_policy() {
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let member =
+            session_with_role(Some(TenantRoleIdentifier::System(SystemTenantRole::Member)));
+
+        for (session, filtered) in [(member, true), (session(), false)] {
+            let sql =
+                "select field_int from test_tb where field_int in (select field_int from test_tb)";
+            let mut statements = ExtParser::parse_sql(sql).unwrap();
+            let plan = planner
+                .statement_to_plan(statements.pop_back().unwrap(), &session, false)
+                .await
+                .unwrap();
+            let df_plan = match plan.plan {
+                Plan::Query(QueryPlan { df_plan, .. }) => df_plan,
+                _ => panic!("expected query plan"),
+            };
+
+            // both the scan of the query and the scan of the subquery are filtered
+            let filters = policy_filters(&df_plan);
+            assert_eq!(filters, if filtered { 2 } else { 0 }, "{df_plan:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_masking_policy() {
+        let test = MockContext {};
+        let planner = SqlPlanner::new(&test);
+        let member =
+            session_with_role(Some(TenantRoleIdentifier::System(SystemTenantRole::Member)));
+
+        for (session, masked) in [(member, true), (session(), false)] {
+            let sql = "select t.field_int, field_string from test_tb t";
+            let mut statements = ExtParser::parse_sql(sql).unwrap();
+            let plan = planner
+                .statement_to_plan(statements.pop_back().unwrap(), &session, false)
+                .await
+                .unwrap();
+            let df_plan = match plan.plan {
+                Plan::Query(QueryPlan { df_plan, .. }) => df_plan,
+                _ => panic!("expected query plan"),
+            };
+
+            let mut masks = 0;
+            let _ = df_plan.apply(&mut |plan| {
+                if let LogicalPlan::Projection(projection) = plan {
+                    masks += projection
+                        .expr
+                        .iter()
+                        .filter(|expr| matches!(expr, Expr::Alias(_, name) if name == "field_int"))
+                        .count();
+                }
+                Ok(VisitRecursion::Continue)
+            });
+            // the masked column keeps its name and type
+            assert_eq!(masks, if masked { 1 } else { 0 }, "{df_plan:?}");
+            assert_eq!(
+                df_plan.schema().field(0).data_type(),
+                &DataType::Int32,
+                "{df_plan:?}"
+            );
+        }
+    }
+
+    fn policy_filters(plan: &LogicalPlan) -> usize {
+        let mut count = 0;
+        let _ = plan.apply(&mut |plan| {
+            if let LogicalPlan::Filter(filter) = plan {
+                if filter.predicate.to_string() == "test_tb.field_string = Utf8(\"a\")" {
+                    count += 1;
+                }
+            }
+            for expr in plan.expressions() {
+                if let Expr::InSubquery(InSubquery { subquery, .. }) = expr {
+                    count += policy_filters(&subquery.subquery);
+                }
+            }
+            Ok(VisitRecursion::Continue)
+        });
+        count
+    }
+
+    #[tokio::test]
+    async fn test_pivot() {
+        let columns = query_columns(
+            "select * from test_tb pivot(max(field_int) for field_string in ('a', 'b')) p",
+        )
+        .await;
+        assert_eq!(columns, vec!["a", "b"]);
+
+        let columns = query_columns(
+            "select p.a from test_tb pivot(count(*) for field_string in ('a', 'b')) p",
+        )
+        .await;
+        assert_eq!(columns, vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_unpivot() {
+        let columns = query_columns(
+            "select * from test_tb unpivot(value for name in (field_int)) u where value > 0",
+        )
+        .await;
+        assert_eq!(columns, vec!["field_string", "name", "value"]);
+    }
+
+    #[test]
+    fn test_table_pattern_to_regex() {
+        let regex = table_pattern_to_regex("sensor_*").unwrap();
+        assert!(regex.is_match("sensor_a"));
+        assert!(regex.is_match("sensor_"));
+        assert!(!regex.is_match("a_sensor_a"));
+
+        let regex = table_pattern_to_regex("*.cpu*").unwrap();
+        assert!(regex.is_match("host.cpu_usage"));
+        assert!(!regex.is_match("host_cpu"));
+    }
 }
@@ -7,6 +7,7 @@ use datafusion::arrow::array::{Array, StringArray, TimestampNanosecondArray};
 use datafusion::arrow::record_batch::RecordBatch;
 use futures::TryStreamExt;
 use models::arrow::TimeUnit;
+use models::consistency_level::ConsistencyLevel;
 use models::predicate::domain::Predicate;
 use models::schema::query_info::QueryId;
 use models::schema::tskv_table_schema::{ColumnType, TableColumn, TskvTableSchema};
@@ -213,6 +214,7 @@ impl WatermarkTracker {
                 CLUSTER_SCHEMA,
                 Precision::NS,
                 vec![line],
+                ConsistencyLevel::default(),
                 None,
             )
             .await
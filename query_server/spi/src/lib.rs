@@ -6,6 +6,7 @@ use datafusion::common::DataFusionError;
 use datafusion::parquet::errors::ParquetError;
 use datafusion::sql::sqlparser::parser::ParserError;
 use error_code::ErrorCoder;
+use memory_pool::MemoryQuotaExceeded;
 use meta::error::MetaError;
 use models::auth::AuthError;
 use models::codec::Encoding;
@@ -590,6 +591,75 @@ pub enum QueryError {
     Models {
         source: ModelError,
     },
+
+    #[snafu(display("Page {} of query {} is not ready yet, retry later", page, query_id))]
+    #[error_code(code = 80)]
+    AsyncQueryResultNotReady {
+        query_id: QueryId,
+        page: usize,
+    },
+
+    #[snafu(display(
+        "Page {} of query {} not found, it has {} pages",
+        page,
+        query_id,
+        pages
+    ))]
+    #[error_code(code = 81)]
+    AsyncQueryPageNotFound {
+        query_id: QueryId,
+        page: usize,
+        pages: usize,
+    },
+
+    #[snafu(display("User {} can run at most {} queries at the same time", user, quota))]
+    #[error_code(code = 82)]
+    ConcurrentQueryQuotaExceeded {
+        user: String,
+        quota: usize,
+    },
+
+    #[snafu(display("The query exceeded its memory quota of {} bytes", quota))]
+    #[error_code(code = 83)]
+    MemoryQuotaExceeded {
+        quota: usize,
+    },
+
+    #[snafu(display("The query scanned more than its quota of {} bytes", quota))]
+    #[error_code(code = 84)]
+    ScanQuotaExceeded {
+        quota: u64,
+    },
+
+    #[snafu(display("The query returned more than its quota of {} rows", quota))]
+    #[error_code(code = 85)]
+    ResultRowsQuotaExceeded {
+        quota: u64,
+    },
+
+    #[snafu(display(
+        "{} already has {} async queries running, remove some of them first",
+        owner,
+        limit
+    ))]
+    #[error_code(code = 86)]
+    AsyncQueryLimitExceeded {
+        owner: String,
+        limit: usize,
+    },
+
+    #[snafu(display(
+        "Failed to delete from {}, the deletes of {} have been done: {}",
+        table,
+        deleted,
+        reason
+    ))]
+    #[error_code(code = 87)]
+    DeletePartiallyFailed {
+        table: String,
+        deleted: String,
+        reason: String,
+    },
 }
 
 impl From<DataFusionError> for QueryError {
@@ -624,6 +694,11 @@ impl From<DataFusionError> for QueryError {
                 ArrowSnafu.into_error(arrow_error)
             }
 
+            DataFusionError::External(e) if e.downcast_ref::<MemoryQuotaExceeded>().is_some() => {
+                let quota = e.downcast::<MemoryQuotaExceeded>().unwrap().quota;
+                QueryError::MemoryQuotaExceeded { quota }
+            }
+
             DataFusionError::ArrowError(e) => ArrowSnafu.into_error(e),
 
             v => DatafusionSnafu.into_error(v),
@@ -27,6 +27,9 @@ pub enum ExtStatement {
     CreateTenant(CreateTenant),
     CreateUser(CreateUser),
     CreateRole(CreateRole),
+    CreateFunction(CreateFunction),
+    CreateToken(CreateToken),
+    CreatePolicy(CreatePolicy),
 
     CreateStream(CreateStream),
     DropStream(DropStream),
@@ -42,6 +45,9 @@ pub enum ExtStatement {
     DescribeDatabase(DescribeDatabase),
     ShowDatabases(),
     ShowTables(Option<Ident>),
+    ShowFunctions,
+    ShowTokens,
+    ShowPolicies,
     ShowSeries(Box<ShowSeries>),
     ShowTagValues(Box<ShowTagValues>),
     Explain(Explain),
@@ -52,6 +58,7 @@ pub enum ExtStatement {
     AlterTable(AlterTable),
     AlterTenant(AlterTenant),
     AlterUser(AlterUser),
+    AlterRole(AlterRole),
 
     // vnode cmd
     DropVnode(DropVnode),
@@ -285,10 +292,27 @@ pub enum AlterUserOperation {
     Set(SqlOption),
 }
 
+/// e.g.
+/// ALTER ROLE role_name SET max_concurrent_queries = 2
+/// ALTER ROLE role_name UNSET max_concurrent_queries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlterRole {
+    /// Role name
+    pub name: Ident,
+    pub operation: AlterRoleOperation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlterRoleOperation {
+    Set(SqlOption),
+    UnSet(Ident),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GrantRevoke {
     pub is_grant: bool,
     pub privileges: Vec<Privilege>,
+    pub table_privileges: Vec<TablePrivilege>,
     pub role_name: Ident,
 }
 
@@ -298,6 +322,15 @@ pub struct Privilege {
     pub database: Ident,
 }
 
+/// e.g. `READ ON TABLE db.t (col1, col2)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TablePrivilege {
+    pub action: Action,
+    pub table: ObjectName,
+    /// Empty means all columns of the table
+    pub columns: Vec<Ident>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
     Read,
@@ -313,6 +346,49 @@ pub struct CreateRole {
     pub inherit: Option<Ident>,
 }
 
+/// e.g.
+/// CREATE TOKEN [IF NOT EXISTS] name FOR USER user_name
+///   WITH PRIVILEGES WRITE ON db1 [, READ ON db2 ...] [EXPIRES '30d']
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateToken {
+    pub if_not_exists: bool,
+    pub name: Ident,
+    pub user: Ident,
+    pub privileges: Vec<Privilege>,
+    /// A duration from now, or a timestamp
+    pub expires: Option<String>,
+}
+
+/// e.g.
+/// CREATE POLICY [IF NOT EXISTS] name ON [db.]table FOR [ROLE] role_name USING (expr)
+/// CREATE MASKING POLICY [IF NOT EXISTS] name ON [db.]table (column) FOR [ROLE] role_name USING (expr)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatePolicy {
+    pub if_not_exists: bool,
+    pub name: Ident,
+    pub table: ObjectName,
+    /// The masked column, `None` for a row-level security policy
+    pub column: Option<Ident>,
+    pub role: Ident,
+    pub using: Expr,
+}
+
+/// e.g.
+/// CREATE [OR REPLACE] [AGGREGATE] FUNCTION name(arg type, ...) RETURNS type
+///   [LANGUAGE {SQL | WASM}] AS 'body'
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateFunction {
+    pub or_replace: bool,
+    pub aggregate: bool,
+    pub name: Ident,
+    pub args: Vec<(Ident, DataType)>,
+    pub return_type: DataType,
+    /// Defaults to SQL
+    pub language: Option<Ident>,
+    /// A sql expression, or a base64 encoded WebAssembly module
+    pub body: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CreateTenant {
     pub name: Ident,
@@ -370,6 +446,10 @@ pub struct DatabaseOptions {
     // shard coverage time range
     pub vnode_duration: Option<String>,
     pub replica: Option<u64>,
+    // remote cluster to ship committed writes to
+    pub replicate_to: Option<String>,
+    pub standby: Option<String>,
+    pub change_feed: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -0,0 +1,89 @@
+use models::schema::query_info::QueryId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AsyncQueryState {
+    /// The query is executing and its results are being spooled
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+impl AsyncQueryState {
+    pub fn is_done(&self) -> bool {
+        !matches!(self, Self::Running)
+    }
+}
+
+impl AsRef<str> for AsyncQueryState {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Running => "RUNNING",
+            Self::Finished => "FINISHED",
+            Self::Failed => "FAILED",
+            Self::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+/// The status of a query submitted through the async query api, returned to the client polling it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsyncQueryStatus {
+    /// Serialized as a string, a u64 does not fit in a javascript number
+    #[serde(with = "query_id_string")]
+    pub query_id: QueryId,
+    pub state: AsyncQueryState,
+    /// Number of rows spooled so far
+    pub rows: u64,
+    /// Number of result pages that can be fetched so far
+    pub pages: usize,
+    pub error: Option<String>,
+    /// Seconds since the query was submitted, or its execution time once done
+    pub duration: f64,
+    /// Seconds until the results are removed, only set once the query is done
+    pub expires_in: Option<u64>,
+}
+
+mod query_id_string {
+    use models::schema::query_info::QueryId;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(id: &QueryId, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&id.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<QueryId, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        id.parse::<u64>()
+            .map(QueryId::from)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use models::schema::query_info::QueryId;
+
+    use super::{AsyncQueryState, AsyncQueryStatus};
+
+    #[test]
+    fn test_status_serde() {
+        let status = AsyncQueryStatus {
+            query_id: QueryId::from(u64::MAX),
+            state: AsyncQueryState::Finished,
+            rows: 10,
+            pages: 1,
+            error: None,
+            duration: 1.5,
+            expires_in: Some(3600),
+        };
+        let json = serde_json::to_string(&status).unwrap();
+        assert!(json.contains(r#""query_id":"18446744073709551615""#));
+        assert!(json.contains(r#""state":"FINISHED""#));
+
+        let status: AsyncQueryStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(status.query_id, QueryId::from(u64::MAX));
+    }
+}
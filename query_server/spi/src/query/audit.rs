@@ -0,0 +1,322 @@
+use std::fmt;
+
+use datafusion::sql::sqlparser::ast::{
+    Ident, ObjectName, Query, SetExpr, Statement, TableFactor, TableWithJoins,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ast::{AlterTenantOperation, CopyTarget, ExtStatement};
+use super::logical_planner::{DatabaseObjectType, GlobalObjectType, TenantObjectType};
+
+/// The categories of the audited statements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditCategory {
+    /// Create, alter and drop the objects
+    Ddl,
+    /// Users, roles, privileges and tokens
+    Dcl,
+    /// DELETE and UPDATE
+    Dml,
+    /// SELECT and the data exported by COPY
+    Query,
+}
+
+impl AuditCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ddl => "ddl",
+            Self::Dcl => "dcl",
+            Self::Dml => "dml",
+            Self::Query => "query",
+        }
+    }
+}
+
+impl fmt::Display for AuditCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Success,
+    /// The user doesn't have the privileges of the statement
+    Denied,
+    Failed,
+}
+
+impl AuditOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Denied => "denied",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for AuditOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A record of the audit log, it's begun when the statement is planned
+/// and finished with the outcome when it's executed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Milliseconds since the unix epoch
+    pub time: i64,
+    pub query_id: String,
+    pub tenant: String,
+    pub user: String,
+    pub client_addr: Option<String>,
+    pub database: String,
+    pub category: AuditCategory,
+    /// e.g. `DROP TABLE`
+    pub statement_type: String,
+    /// The names of the objects touched by the statement
+    pub objects: Vec<String>,
+    /// The privileges checked before the statement is executed
+    pub privileges: Vec<String>,
+    pub sql: String,
+    pub outcome: AuditOutcome,
+    pub error: Option<String>,
+}
+
+/// The category, type and objects of an audited statement,
+/// the statements that are not audited return `None`, e.g. INSERT and SHOW.
+pub fn classify_statement(stmt: &ExtStatement) -> Option<(AuditCategory, String, Vec<String>)> {
+    use AuditCategory::*;
+
+    let (category, statement_type, objects) = match stmt {
+        ExtStatement::SqlStatement(stmt) => return classify_sql_statement(stmt),
+        ExtStatement::Copy(copy) => match &copy.copy_target {
+            // the data is exported out of the database
+            CopyTarget::IntoLocation(target) => {
+                let mut objects = vec![];
+                table_factor_tables(&target.from, &[], &mut objects);
+                (Query, "COPY INTO LOCATION", objects)
+            }
+            CopyTarget::IntoTable(_) => return None,
+        },
+        ExtStatement::CreateExternalTable(stmt) => {
+            (Ddl, "CREATE EXTERNAL TABLE", vec![stmt.name.clone()])
+        }
+        ExtStatement::CreateTable(stmt) => (Ddl, "CREATE TABLE", vec![object_name(&stmt.name)]),
+        ExtStatement::CreateStreamTable(stmt) => {
+            let objects = match stmt {
+                Statement::CreateTable { name, .. } => vec![object_name(name)],
+                _ => vec![],
+            };
+            (Ddl, "CREATE STREAM TABLE", objects)
+        }
+        ExtStatement::CreateDatabase(stmt) => (Ddl, "CREATE DATABASE", vec![ident(&stmt.name)]),
+        ExtStatement::CreateTenant(stmt) => (Ddl, "CREATE TENANT", vec![ident(&stmt.name)]),
+        ExtStatement::CreateUser(stmt) => (Dcl, "CREATE USER", vec![ident(&stmt.name)]),
+        ExtStatement::CreateRole(stmt) => (Dcl, "CREATE ROLE", vec![ident(&stmt.name)]),
+        ExtStatement::CreateFunction(stmt) => (Ddl, "CREATE FUNCTION", vec![ident(&stmt.name)]),
+        ExtStatement::CreateToken(stmt) => (
+            Dcl,
+            "CREATE TOKEN",
+            vec![ident(&stmt.name), ident(&stmt.user)],
+        ),
+        ExtStatement::CreatePolicy(stmt) => (
+            Dcl,
+            if stmt.column.is_some() {
+                "CREATE MASKING POLICY"
+            } else {
+                "CREATE POLICY"
+            },
+            vec![
+                ident(&stmt.name),
+                object_name(&stmt.table),
+                ident(&stmt.role),
+            ],
+        ),
+        ExtStatement::CreateStream(stmt) => (Ddl, "CREATE STREAM", vec![ident(&stmt.name)]),
+        ExtStatement::DropStream(stmt) => (Ddl, "DROP STREAM", vec![ident(&stmt.name)]),
+        ExtStatement::DropDatabaseObject(stmt) => {
+            let statement_type = match stmt.obj_type {
+                DatabaseObjectType::Table => "DROP TABLE",
+                DatabaseObjectType::View => "DROP VIEW",
+            };
+            (Ddl, statement_type, vec![object_name(&stmt.object_name)])
+        }
+        ExtStatement::DropTenantObject(stmt) => {
+            let (category, statement_type) = match stmt.obj_type {
+                TenantObjectType::Role => (Dcl, "DROP ROLE"),
+                TenantObjectType::Database => (Ddl, "DROP DATABASE"),
+                TenantObjectType::Function => (Ddl, "DROP FUNCTION"),
+                TenantObjectType::Token => (Dcl, "DROP TOKEN"),
+                TenantObjectType::Policy => (Dcl, "DROP POLICY"),
+            };
+            (category, statement_type, vec![ident(&stmt.object_name)])
+        }
+        ExtStatement::DropGlobalObject(stmt) => {
+            let (category, statement_type) = match stmt.obj_type {
+                GlobalObjectType::User => (Dcl, "DROP USER"),
+                GlobalObjectType::Tenant => (Ddl, "DROP TENANT"),
+            };
+            (category, statement_type, vec![ident(&stmt.object_name)])
+        }
+        ExtStatement::GrantRevoke(stmt) => {
+            let statement_type = if stmt.is_grant { "GRANT" } else { "REVOKE" };
+            let mut objects = vec![ident(&stmt.role_name)];
+            objects.extend(stmt.privileges.iter().map(|p| ident(&p.database)));
+            objects.extend(stmt.table_privileges.iter().map(|p| object_name(&p.table)));
+            (Dcl, statement_type, objects)
+        }
+        ExtStatement::AlterDatabase(stmt) => (Ddl, "ALTER DATABASE", vec![ident(&stmt.name)]),
+        ExtStatement::AlterTable(stmt) => (Ddl, "ALTER TABLE", vec![object_name(&stmt.table_name)]),
+        ExtStatement::AlterTenant(stmt) => match &stmt.operation {
+            // the members of the tenant
+            AlterTenantOperation::AddUser(user, role)
+            | AlterTenantOperation::SetUser(user, role) => (
+                Dcl,
+                "ALTER TENANT",
+                vec![ident(&stmt.name), ident(user), ident(role)],
+            ),
+            AlterTenantOperation::RemoveUser(user) => {
+                (Dcl, "ALTER TENANT", vec![ident(&stmt.name), ident(user)])
+            }
+            AlterTenantOperation::Set(_) | AlterTenantOperation::UnSet(_) => {
+                (Ddl, "ALTER TENANT", vec![ident(&stmt.name)])
+            }
+        },
+        ExtStatement::AlterUser(stmt) => (Dcl, "ALTER USER", vec![ident(&stmt.name)]),
+        ExtStatement::AlterRole(stmt) => (Dcl, "ALTER ROLE", vec![ident(&stmt.name)]),
+        ExtStatement::DropVnode(stmt) => (Ddl, "DROP VNODE", vec![stmt.vnode_id.to_string()]),
+        ExtStatement::CopyVnode(stmt) => (Ddl, "COPY VNODE", vec![stmt.vnode_id.to_string()]),
+        ExtStatement::MoveVnode(stmt) => (Ddl, "MOVE VNODE", vec![stmt.vnode_id.to_string()]),
+        ExtStatement::CompactVnode(stmt) => (
+            Ddl,
+            "COMPACT VNODE",
+            stmt.vnode_ids.iter().map(|id| id.to_string()).collect(),
+        ),
+        ExtStatement::CompactDatabase(stmt) => {
+            (Ddl, "COMPACT DATABASE", vec![ident(&stmt.database_name)])
+        }
+        ExtStatement::RecoverTenant(stmt) => {
+            (Ddl, "RECOVER TENANT", vec![ident(&stmt.object_name)])
+        }
+        ExtStatement::RecoverDatabase(stmt) => {
+            (Ddl, "RECOVER DATABASE", vec![ident(&stmt.object_name)])
+        }
+        ExtStatement::ReplicaDestory(stmt) => {
+            (Ddl, "REPLICA DESTROY", vec![stmt.replica_id.to_string()])
+        }
+        ExtStatement::ReplicaAdd(stmt) => (Ddl, "REPLICA ADD", vec![stmt.replica_id.to_string()]),
+        ExtStatement::ReplicaRemove(stmt) => {
+            (Ddl, "REPLICA REMOVE", vec![stmt.replica_id.to_string()])
+        }
+        ExtStatement::ReplicaPromote(stmt) => {
+            (Ddl, "REPLICA PROMOTE", vec![stmt.replica_id.to_string()])
+        }
+        ExtStatement::DescribeTable(_)
+        | ExtStatement::DescribeDatabase(_)
+        | ExtStatement::ShowDatabases()
+        | ExtStatement::ShowTables(_)
+        | ExtStatement::ShowFunctions
+        | ExtStatement::ShowTokens
+        | ExtStatement::ShowPolicies
+        | ExtStatement::ShowSeries(_)
+        | ExtStatement::ShowTagValues(_)
+        | ExtStatement::ShowStreams(_)
+        | ExtStatement::ShowQueries
+        | ExtStatement::ShowReplicas
+        | ExtStatement::ChecksumGroup(_)
+        | ExtStatement::Explain(_) => return None,
+    };
+
+    Some((category, statement_type.to_string(), objects))
+}
+
+fn classify_sql_statement(stmt: &Statement) -> Option<(AuditCategory, String, Vec<String>)> {
+    let mut objects = vec![];
+    let (category, statement_type) = match stmt {
+        Statement::Query(query) => {
+            query_tables(query, &[], &mut objects);
+            (AuditCategory::Query, "SELECT")
+        }
+        Statement::Delete { from, .. } => {
+            for table in from {
+                table_with_joins_tables(table, &[], &mut objects);
+            }
+            (AuditCategory::Dml, "DELETE")
+        }
+        Statement::Update { table, .. } => {
+            table_with_joins_tables(table, &[], &mut objects);
+            (AuditCategory::Dml, "UPDATE")
+        }
+        Statement::CreateView { name, .. } => {
+            objects.push(object_name(name));
+            (AuditCategory::Ddl, "CREATE VIEW")
+        }
+        _ => return None,
+    };
+
+    Some((category, statement_type.to_string(), objects))
+}
+
+/// The tables read by the query, the common table expressions are not tables
+fn query_tables(query: &Query, ctes: &[String], tables: &mut Vec<String>) {
+    let mut ctes = ctes.to_vec();
+    if let Some(with) = &query.with {
+        for cte in &with.cte_tables {
+            query_tables(&cte.query, &ctes, tables);
+            ctes.push(ident(&cte.alias.name));
+        }
+    }
+    set_expr_tables(&query.body, &ctes, tables);
+}
+
+fn set_expr_tables(body: &SetExpr, ctes: &[String], tables: &mut Vec<String>) {
+    match body {
+        SetExpr::Select(select) => {
+            for table in &select.from {
+                table_with_joins_tables(table, ctes, tables);
+            }
+        }
+        SetExpr::Query(query) => query_tables(query, ctes, tables),
+        SetExpr::SetOperation { left, right, .. } => {
+            set_expr_tables(left, ctes, tables);
+            set_expr_tables(right, ctes, tables);
+        }
+        _ => {}
+    }
+}
+
+fn table_with_joins_tables(table: &TableWithJoins, ctes: &[String], tables: &mut Vec<String>) {
+    table_factor_tables(&table.relation, ctes, tables);
+    for join in &table.joins {
+        table_factor_tables(&join.relation, ctes, tables);
+    }
+}
+
+fn table_factor_tables(factor: &TableFactor, ctes: &[String], tables: &mut Vec<String>) {
+    match factor {
+        TableFactor::Table { name, .. } => {
+            let name = object_name(name);
+            if !ctes.contains(&name) && !tables.contains(&name) {
+                tables.push(name);
+            }
+        }
+        TableFactor::Derived { subquery, .. } => query_tables(subquery, ctes, tables),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => table_with_joins_tables(table_with_joins, ctes, tables),
+        _ => {}
+    }
+}
+
+fn ident(ident: &Ident) -> String {
+    ident.value.clone()
+}
+
+fn object_name(name: &ObjectName) -> String {
+    name.0.iter().map(ident).collect::<Vec<_>>().join(".")
+}
@@ -13,5 +13,18 @@ pub type AccessControlRef = Arc<dyn AccessControl + Send + Sync>;
 pub trait AccessControl {
     async fn access_check(&self, user_info: &UserInfo, tenant_name: &str) -> Result<User>;
 
+    /// Checks the lockout and the password expiration of a user authenticated
+    /// before, e.g. found in the auth cache
+    async fn recheck(&self, user: User, _tenant_name: &str) -> Result<User> {
+        Ok(user)
+    }
+
+    /// Authenticates an API token of the tenant,
+    /// the privileges of the returned user are limited to the scope of the token
+    async fn token_check(&self, token: &str, tenant_name: &str) -> Result<User>;
+
+    /// Authenticates a JWT issued by the configured OIDC provider
+    async fn jwt_check(&self, token: &str, tenant_name: &str) -> Result<User>;
+
     async fn tenant_id(&self, tenant_name: &str) -> Result<Oid>;
 }
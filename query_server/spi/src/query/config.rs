@@ -1,3 +1,4 @@
+use std::fmt::Display;
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -74,12 +75,34 @@ impl FromStr for StreamTriggerInterval {
     }
 }
 
+impl Display for StreamTriggerInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamTriggerInterval::Once => write!(f, "once"),
+            StreamTriggerInterval::Interval(duration) => write!(f, "{}ms", duration.as_millis()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
 
     use crate::query::config::StreamTriggerInterval;
 
+    #[test]
+    fn test_display() {
+        for interval in [
+            StreamTriggerInterval::Once,
+            StreamTriggerInterval::Interval(std::time::Duration::from_millis(1500)),
+        ] {
+            assert_eq!(
+                StreamTriggerInterval::from_str(&interval.to_string()).unwrap(),
+                interval
+            );
+        }
+    }
+
     #[test]
     fn test() {
         let interval = StreamTriggerInterval::from_str("once").unwrap();
@@ -1,7 +1,7 @@
 use std::fmt::Display;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicPtr, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
 
@@ -21,6 +21,7 @@ use models::oid::{Identifier, Oid};
 use models::schema::query_info::{QueryId, QueryInfo};
 use trace::{debug, warn, SpanContext};
 
+use super::audit::AuditEntry;
 use super::dispatcher::QueryStatus;
 use super::logical_planner::Plan;
 use super::session::SessionCtx;
@@ -206,6 +207,8 @@ pub struct QueryStateMachine {
 
     state: AtomicPtr<QueryState>,
     start: Instant,
+    // the audit record waiting for the outcome of the execution
+    audit_entry: Mutex<Option<AuditEntry>>,
 }
 
 impl QueryStateMachine {
@@ -213,6 +216,7 @@ impl QueryStateMachine {
     pub fn test(query: Query, span_context: Option<SpanContext>) -> Self {
         use coordinator::service_mock::MockCoordinator;
         use datafusion::execution::memory_pool::UnboundedMemoryPool;
+        use models::auth::role::RoleQuota;
 
         use super::session::SessionCtxFactory;
 
@@ -227,6 +231,7 @@ impl QueryStateMachine {
                     &ctx,
                     0,
                     Arc::new(UnboundedMemoryPool::default()),
+                    RoleQuota::default(),
                     span_context,
                     Arc::new(MockCoordinator {}),
                 )
@@ -254,6 +259,7 @@ impl QueryStateMachine {
             auth_cache,
             state: AtomicPtr::new(Box::into_raw(Box::new(QueryState::ACCEPTING))),
             start: Instant::now(),
+            audit_entry: Mutex::new(None),
         }
     }
 
@@ -322,6 +328,27 @@ impl QueryStateMachine {
             auth_cache: self.auth_cache.clone(),
             state,
             start: self.start,
+            audit_entry: Mutex::new(self.audit_entry.lock().unwrap().clone()),
+        }
+    }
+
+    /// Keep the audit record of the planned statement until it's executed
+    pub fn set_audit_entry(&self, entry: AuditEntry) {
+        *self.audit_entry.lock().unwrap() = Some(entry);
+    }
+
+    pub fn take_audit_entry(&self) -> Option<AuditEntry> {
+        self.audit_entry.lock().unwrap().take()
+    }
+
+    /// Copy the audit record kept by `planned` to this state machine, which
+    /// executes the plan of `planned` again as a new query.
+    pub fn inherit_audit_entry(&self, planned: &QueryStateMachine) {
+        let entry = planned.audit_entry.lock().unwrap().clone();
+        if let Some(mut entry) = entry {
+            entry.time = models::utils::now_timestamp_millis();
+            entry.query_id = self.query_id.to_string();
+            self.set_audit_entry(entry);
         }
     }
 
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 use std::io::Write;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
-use config::common::TenantLimiterConfig;
+use config::common::{PasswordPolicyConfig, TenantLimiterConfig};
 use datafusion::arrow::array::ArrayRef;
 use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::datasource::file_format::file_type::{FileCompressionType, FileType};
@@ -19,19 +20,22 @@ use datafusion::prelude::{col, Expr};
 use datafusion::sql::sqlparser::ast::{Ident, ObjectName, SqlOption, Value};
 use datafusion::sql::sqlparser::parser::ParserError;
 use lazy_static::lazy_static;
-use models::auth::privilege::{DatabasePrivilege, GlobalPrivilege, Privilege};
-use models::auth::role::{SystemTenantRole, TenantRoleIdentifier};
+use models::auth::policy::PolicyDesc;
+use models::auth::privilege::{DatabasePrivilege, GlobalPrivilege, Privilege, TablePrivilege};
+use models::auth::role::{RoleQuota, SystemTenantRole, TenantRoleIdentifier};
 use models::auth::user::{UserOptions, UserOptionsBuilder};
 use models::meta_data::{NodeId, ReplicationSetId, VnodeId};
 use models::object_reference::ResolvedTable;
 use models::oid::{Identifier, Oid};
 use models::schema::database_schema::{DatabaseConfigBuilder, DatabaseOptionsBuilder};
+use models::schema::function_schema::FunctionSchema;
 use models::schema::query_info::QueryId;
 use models::schema::stream_table_schema::Watermark;
 use models::schema::tenant::{Tenant, TenantOptions, TenantOptionsBuilder};
 use models::schema::tskv_table_schema::TableColumn;
 use snafu::{IntoError, ResultExt};
 use tempfile::NamedTempFile;
+use utils::byte_nums::CnosByteNumber;
 use utils::duration::CnosDuration;
 
 use super::ast::{parse_bool_value, parse_char_value, parse_string_value, ExtStatement};
@@ -50,6 +54,12 @@ use crate::{
 pub const TENANT_OPTION_LIMITER: &str = "_limiter";
 pub const TENANT_OPTION_COMMENT: &str = "comment";
 pub const TENANT_OPTION_DROP_AFTER: &str = "drop_after";
+pub const TENANT_OPTION_PASSWORD_POLICY: &str = "password_policy";
+
+pub const ROLE_OPTION_MAX_CONCURRENT_QUERIES: &str = "max_concurrent_queries";
+pub const ROLE_OPTION_MAX_QUERY_MEMORY: &str = "max_query_memory";
+pub const ROLE_OPTION_MAX_SCAN_BYTES: &str = "max_scan_bytes";
+pub const ROLE_OPTION_MAX_RESULT_ROWS: &str = "max_result_rows";
 
 lazy_static! {
     static ref TABLE_WRITE_UDF: Arc<ScalarUDF> = Arc::new(ScalarUDF::new(
@@ -128,6 +138,10 @@ pub enum DDLPlan {
 
     CreateStreamTable(CreateStreamTable),
 
+    CreateView(CreateView),
+
+    CreateFunction(CreateFunction),
+
     CreateDatabase(CreateDatabase),
 
     CreateTenant(Box<CreateTenant>),
@@ -136,6 +150,10 @@ pub enum DDLPlan {
 
     CreateRole(CreateRole),
 
+    CreateToken(CreateToken),
+
+    CreatePolicy(CreatePolicy),
+
     AlterDatabase(AlterDatabase),
 
     AlterTable(AlterTable),
@@ -144,6 +162,8 @@ pub enum DDLPlan {
 
     AlterUser(AlterUser),
 
+    AlterRole(AlterRole),
+
     GrantRevoke(GrantRevoke),
 
     DropVnode(DropVnode),
@@ -178,6 +198,10 @@ impl DDLPlan {
                 Field::new("vnode_id", DataType::UInt32, false),
                 Field::new("check_sum", DataType::Utf8, false),
             ])),
+            DDLPlan::CreateToken(_) => Arc::new(Schema::new(vec![
+                Field::new("token_name", DataType::Utf8, false),
+                Field::new("token", DataType::Utf8, false),
+            ])),
             _ => Arc::new(Schema::empty()),
         }
     }
@@ -212,7 +236,8 @@ pub struct DropVnode {
 
 #[derive(Debug, Clone)]
 pub enum DMLPlan {
-    DeleteFromTable(DeleteFromTable),
+    /// The deletes of the tables matched by the `FROM` clause
+    DeleteFromTable(Vec<DeleteFromTable>),
 }
 
 impl DMLPlan {
@@ -253,6 +278,7 @@ pub struct DropDatabaseObject {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DatabaseObjectType {
     Table,
+    View,
 }
 
 #[derive(Debug, Clone)]
@@ -268,6 +294,9 @@ pub struct DropTenantObject {
 pub enum TenantObjectType {
     Role,
     Database,
+    Function,
+    Token,
+    Policy,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -321,6 +350,41 @@ pub struct CreateTable {
     pub if_not_exists: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct CreateView {
+    /// The view name
+    pub name: ResolvedTable,
+    /// Option to replace the view if it already exists
+    pub or_replace: bool,
+    /// The query of the view
+    pub query: String,
+    /// The output schema of the query
+    pub schema: SchemaRef,
+    pub materialized: Option<CreateMaterializedView>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateFunction {
+    /// Option to replace the function if it already exists
+    pub or_replace: bool,
+    /// The checked function, see [`models::schema::function_schema::FunctionSchema`]
+    pub function: FunctionSchema,
+}
+
+/// The tables maintained for a materialized view, see [`models::schema::view_schema::MaterializedView`]
+#[derive(Debug, Clone)]
+pub struct CreateMaterializedView {
+    /// The tskv table the query of the view reads from
+    pub base_table: String,
+    /// The tskv table that stores the rows of the view
+    pub storage_table: CreateTable,
+    /// The stream table that reads the new writes of `base_table`
+    pub source_table: CreateStreamTable,
+    /// The stream query that inserts the rows of the view into `storage_table`
+    pub refresh_sql: String,
+    pub refresh_interval: Duration,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CreateStreamTable {
     /// Option to not error if table already exists
@@ -394,9 +458,13 @@ pub fn unset_option_to_alter_tenant_action(
             tenant_options_builder.unset_drop_after();
             Privilege::Global(GlobalPrivilege::Tenant(Some(tenant_id)))
         }
+        TENANT_OPTION_PASSWORD_POLICY => {
+            tenant_options_builder.unset_password_policy();
+            Privilege::Global(GlobalPrivilege::System)
+        }
         _ => {
             let source = ParserError::ParserError(format!(
-                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}] found [{}]",
+                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}], [{TENANT_OPTION_PASSWORD_POLICY}] found [{}]",
                 ident
             ));
             return Err(ParserSnafu.into_error(source));
@@ -441,10 +509,15 @@ pub fn sql_option_to_alter_tenant_action(
             tenant_options_builder.drop_after(drop_after);
             Privilege::Global(GlobalPrivilege::Tenant(Some(tenant_id)))
         }
+        TENANT_OPTION_PASSWORD_POLICY => {
+            let policy = parse_password_policy(value)?;
+            tenant_options_builder.password_policy(policy);
+            Privilege::Global(GlobalPrivilege::System)
+        }
         _ => {
             return Err(QueryError::Parser {
                 source: ParserError::ParserError(format!(
-                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}] found [{}]",
+                "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}], [{TENANT_OPTION_PASSWORD_POLICY}] found [{}]",
                 name
             )),
             })
@@ -459,6 +532,73 @@ pub fn sql_option_to_alter_tenant_action(
     ))
 }
 
+pub fn sql_option_to_alter_role_action(option: SqlOption) -> QueryResult<AlterRoleAction> {
+    let SqlOption { name, value } = option;
+    let quota_option = ident_to_role_quota_option(&name)?;
+    let is_size = matches!(
+        quota_option,
+        RoleQuotaOption::MaxQueryMemory | RoleQuotaOption::MaxScanBytes
+    );
+
+    Ok(AlterRoleAction::Set(
+        quota_option,
+        parse_quota_value(&name, value, is_size)?,
+    ))
+}
+
+pub fn unset_option_to_alter_role_action(ident: Ident) -> QueryResult<AlterRoleAction> {
+    Ok(AlterRoleAction::Unset(ident_to_role_quota_option(&ident)?))
+}
+
+fn ident_to_role_quota_option(name: &Ident) -> QueryResult<RoleQuotaOption> {
+    match normalize_ident(name).as_str() {
+        ROLE_OPTION_MAX_CONCURRENT_QUERIES => Ok(RoleQuotaOption::MaxConcurrentQueries),
+        ROLE_OPTION_MAX_QUERY_MEMORY => Ok(RoleQuotaOption::MaxQueryMemory),
+        ROLE_OPTION_MAX_SCAN_BYTES => Ok(RoleQuotaOption::MaxScanBytes),
+        ROLE_OPTION_MAX_RESULT_ROWS => Ok(RoleQuotaOption::MaxResultRows),
+        _ => Err(unknown_role_option(name)),
+    }
+}
+
+fn unknown_role_option(name: &Ident) -> QueryError {
+    QueryError::Parser {
+        source: ParserError::ParserError(format!(
+            "Expected option [{ROLE_OPTION_MAX_CONCURRENT_QUERIES}], [{ROLE_OPTION_MAX_QUERY_MEMORY}], [{ROLE_OPTION_MAX_SCAN_BYTES}], [{ROLE_OPTION_MAX_RESULT_ROWS}] found [{}]",
+            name
+        )),
+    }
+}
+
+/// A positive number, the sizes in bytes can also be a string like `'512MiB'`.
+fn parse_quota_value(name: &Ident, value: Value, is_size: bool) -> QueryResult<u64> {
+    let parsed = match &value {
+        Value::Number(n, _) => n.parse::<u64>().ok(),
+        Value::SingleQuotedString(s) if is_size => CnosByteNumber::parse_bytes(s).ok(),
+        _ => None,
+    };
+
+    match parsed {
+        Some(v) if v > 0 => Ok(v),
+        _ => Err(QueryError::Parser {
+            source: ParserError::ParserError(format!(
+                "{} must be a positive {}, but found {}",
+                name,
+                if is_size { "size" } else { "number" },
+                value
+            )),
+        }),
+    }
+}
+
+/// The password policy is a JSON like `'{"min_length": 12, "expire_after": "90d"}'`,
+/// the absent fields take the default values.
+fn parse_password_policy(value: Value) -> QueryResult<PasswordPolicyConfig> {
+    serde_json::from_str::<PasswordPolicyConfig>(
+        parse_string_value(value).context(ParserSnafu)?.as_str(),
+    )
+    .context(SerdeJsonSnafu)
+}
+
 pub fn sql_options_to_tenant_options(options: Vec<SqlOption>) -> QueryResult<TenantOptions> {
     let mut builder = TenantOptionsBuilder::default();
 
@@ -480,10 +620,13 @@ pub fn sql_options_to_tenant_options(options: Vec<SqlOption>) -> QueryResult<Ten
                 })?;
                 builder.drop_after(drop_after);
             }
+            TENANT_OPTION_PASSWORD_POLICY => {
+                builder.password_policy(parse_password_policy(value)?);
+            }
             _ => {
                 return Err(QueryError::Parser {
                     source: ParserError::ParserError(format!(
-                        "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}] found [{}]",
+                        "Expected option [{TENANT_OPTION_COMMENT}], [{TENANT_OPTION_LIMITER}], [{TENANT_OPTION_DROP_AFTER}], [{TENANT_OPTION_PASSWORD_POLICY}] found [{}]",
                         name
                     )),
                 })
@@ -532,9 +675,12 @@ pub fn sql_options_to_user_options(
             "hash_password" => {
                 builder.hash_password(parse_string_value(value)?);
             }
+            "ldap_auth" => {
+                builder.ldap_auth(parse_bool_value(value)?);
+            }
             _ => {
                 return Err(ParserError::ParserError(format!(
-                "Expected option [password | rsa_public_key | comment | granted_admin], found [{}]",
+                "Expected option [password | rsa_public_key | comment | granted_admin | ldap_auth], found [{}]",
                 name
             )))
             }
@@ -556,11 +702,33 @@ pub struct CreateRole {
     pub inherit_tenant_role: Option<SystemTenantRole>,
 }
 
+/// The plain token is generated when the plan is executed, it's only returned once
+#[derive(Debug, Clone)]
+pub struct CreateToken {
+    pub tenant_name: String,
+    pub name: String,
+    pub if_not_exists: bool,
+    pub user_id: Oid,
+    pub user_name: String,
+    // privilege, db name
+    pub privileges: Vec<(DatabasePrivilege, String)>,
+    // milliseconds since the unix epoch
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreatePolicy {
+    pub tenant_name: String,
+    pub if_not_exists: bool,
+    pub policy: PolicyDesc,
+}
+
 #[derive(Debug, Clone)]
 pub struct GrantRevoke {
     pub is_grant: bool,
     // privilege, db name
     pub database_privileges: Vec<(DatabasePrivilege, String)>,
+    pub table_privileges: Vec<TablePrivilege>,
     pub tenant_name: String,
     pub role_name: String,
 }
@@ -591,6 +759,45 @@ pub enum AlterTenantAction {
     SetOption(Box<TenantOptions>),
 }
 
+#[derive(Debug, Clone)]
+pub struct AlterRole {
+    pub tenant_name: String,
+    pub role_name: String,
+    pub alter_role_action: AlterRoleAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleQuotaOption {
+    MaxConcurrentQueries,
+    MaxQueryMemory,
+    MaxScanBytes,
+    MaxResultRows,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlterRoleAction {
+    Set(RoleQuotaOption, u64),
+    Unset(RoleQuotaOption),
+}
+
+impl AlterRoleAction {
+    pub fn apply(&self, quota: &mut RoleQuota) {
+        let (option, value) = match self {
+            Self::Set(option, value) => (option, Some(*value)),
+            Self::Unset(option) => (option, None),
+        };
+
+        match option {
+            RoleQuotaOption::MaxConcurrentQueries => {
+                quota.max_concurrent_queries = value.map(|v| v as usize)
+            }
+            RoleQuotaOption::MaxQueryMemory => quota.max_query_memory = value.map(|v| v as usize),
+            RoleQuotaOption::MaxScanBytes => quota.max_scan_bytes = value,
+            RoleQuotaOption::MaxResultRows => quota.max_result_rows = value,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AlterTenantAddUser {
     pub user_id: Oid,
@@ -635,12 +842,13 @@ pub enum AlterTableAction {
 
 #[async_trait]
 pub trait LogicalPlanner {
+    /// Returns the plan with the privileges it requires, which have been checked
     async fn create_logical_plan(
         &self,
         statement: ExtStatement,
         session: &SessionCtx,
         auth_enable: bool,
-    ) -> QueryResult<Plan>;
+    ) -> QueryResult<PlanWithPrivileges>;
 }
 
 /// Additional output information
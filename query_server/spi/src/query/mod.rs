@@ -2,6 +2,8 @@ use datafusion::arrow::datatypes::DataType;
 
 pub mod analyzer;
 pub mod ast;
+pub mod async_query;
+pub mod audit;
 pub mod auth;
 pub mod config;
 pub mod datasource;
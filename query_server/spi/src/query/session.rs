@@ -1,4 +1,5 @@
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -10,7 +11,10 @@ use datafusion::execution::memory_pool::MemoryPool;
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use datafusion::prelude::{SessionConfig, SessionContext};
 use datafusion::variable::VarType;
+use models::auth::role::RoleQuota;
 use models::auth::user::User;
+use models::consistency_level::ConsistencyLevel;
+use models::follower_read::FollowerReadLag;
 use models::oid::Oid;
 use trace::span_ext::SpanExt;
 use trace::{Span, SpanContext};
@@ -18,7 +22,7 @@ use trace::{Span, SpanContext};
 use super::config::StreamTriggerInterval;
 use super::variable::VarProviderRef;
 use crate::service::protocol::Context;
-use crate::QueryResult;
+use crate::{QueryError, QueryResult};
 
 extensions_options! {
     pub struct SqlExecInfo {
@@ -29,6 +33,34 @@ impl ConfigExtension for SqlExecInfo {
     const PREFIX: &'static str = "sql_exec_info";
 }
 
+/// The bytes the table scans of a query can read, shared by all the partitions of the scans.
+///
+/// The scans charge the bytes of the pages read from the tsm files by tskv, on this node
+/// and on the remote ones, and the tag values returned by the tag scans.
+#[derive(Debug)]
+pub struct ScanQuota {
+    quota: u64,
+    scanned: AtomicU64,
+}
+
+impl ScanQuota {
+    pub fn new(quota: u64) -> Self {
+        Self {
+            quota,
+            scanned: AtomicU64::new(0),
+        }
+    }
+
+    /// Records the bytes read by a scan, fails once the query has read more than the quota.
+    pub fn consume(&self, bytes: u64) -> QueryResult<()> {
+        let scanned = self.scanned.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if scanned > self.quota {
+            return Err(QueryError::ScanQuotaExceeded { quota: self.quota });
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone)]
 pub struct SessionCtx {
     desc: Arc<SessionCtxDesc>,
@@ -57,6 +89,11 @@ impl SessionCtx {
         &self.desc.user
     }
 
+    /// The quota of the role of the user in the tenant of the session
+    pub fn quota(&self) -> &RoleQuota {
+        &self.desc.quota
+    }
+
     pub fn dedicated_hidden_dir(&self) -> &Path {
         self.desc.query_dedicated_hidden_dir.as_path()
     }
@@ -88,6 +125,7 @@ pub struct SessionCtxDesc {
     tenant_id: Oid,
     tenant: String,
     default_database: String,
+    quota: RoleQuota,
 
     query_dedicated_hidden_dir: PathBuf,
 }
@@ -118,11 +156,18 @@ impl SessionCtxFactory {
         context: &Context,
         tenant_id: Oid,
         memory_pool: Arc<dyn MemoryPool>,
+        quota: RoleQuota,
         span_ctx: Option<SpanContext>,
         coord: Arc<dyn Coordinator>,
     ) -> QueryResult<SessionCtx> {
-        let df_session_ctx =
-            self.build_df_session_context(session_id, context, memory_pool, &span_ctx, coord)?;
+        let df_session_ctx = self.build_df_session_context(
+            session_id,
+            context,
+            memory_pool,
+            quota.max_scan_bytes,
+            &span_ctx,
+            coord,
+        )?;
 
         Ok(SessionCtx {
             desc: Arc::new(SessionCtxDesc {
@@ -130,6 +175,7 @@ impl SessionCtxFactory {
                 tenant_id,
                 tenant: context.tenant().to_owned(),
                 default_database: context.database().to_owned(),
+                quota,
                 query_dedicated_hidden_dir: self.query_dedicated_hidden_dir.clone(),
             }),
             inner: df_session_ctx.state(),
@@ -142,6 +188,7 @@ impl SessionCtxFactory {
         session_id: impl Into<String>,
         context: &Context,
         memory_pool: Arc<dyn MemoryPool>,
+        max_scan_bytes: Option<u64>,
         span_ctx: &Option<SpanContext>,
         coord: Arc<dyn Coordinator>,
     ) -> QueryResult<SessionContext> {
@@ -150,6 +197,10 @@ impl SessionCtxFactory {
             // inject span context into datafusion session config, so that it can be used in execution
             config = config.with_extension(Arc::new(*span_ctx))
         }
+        if let Some(max_scan_bytes) = max_scan_bytes {
+            // the table scans of the session count the bytes they read
+            config = config.with_extension(Arc::new(ScanQuota::new(max_scan_bytes)))
+        }
         // inject cnosdb_config into datafusion session_config
         config
             .options_mut()
@@ -212,4 +263,16 @@ impl CnosSessionConfig {
         self.inner = self.inner.with_extension(Arc::new(interval));
         self
     }
+
+    /// Consistency level used by the tskv sink when writing records
+    pub fn with_write_consistency(mut self, consistency: ConsistencyLevel) -> Self {
+        self.inner = self.inner.with_extension(Arc::new(consistency));
+        self
+    }
+
+    /// Max replication lag of followers allowed to serve table scans
+    pub fn with_follower_read_max_lag(mut self, max_lag: FollowerReadLag) -> Self {
+        self.inner = self.inner.with_extension(Arc::new(max_lag));
+        self
+    }
 }
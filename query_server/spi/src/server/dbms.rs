@@ -5,16 +5,17 @@ use datafusion::arrow::array::{Float32Array, Float64Array};
 use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
 use models::auth::role::UserRole;
-use models::auth::user::{User, UserDesc, UserInfo, UserOptionsBuilder};
+use models::auth::user::{User, UserDesc, UserInfo, UserOptions, UserOptionsBuilder};
 use models::schema::query_info::QueryId;
 use trace::span_ext::SpanExt;
 use trace::SpanContext;
 
+use crate::query::async_query::AsyncQueryStatus;
 use crate::query::execution::{Output, QueryStateMachine, QueryStateMachineRef};
 use crate::query::logical_planner::Plan;
 use crate::query::recordbatch::RecordBatchStreamWrapper;
 use crate::service::protocol::{Query, QueryHandle};
-use crate::QueryResult;
+use crate::{QueryError, QueryResult};
 
 pub type DBMSRef = Arc<dyn DatabaseManagerSystem + Send + Sync>;
 
@@ -22,6 +23,11 @@ pub type DBMSRef = Arc<dyn DatabaseManagerSystem + Send + Sync>;
 pub trait DatabaseManagerSystem {
     async fn start(&self) -> QueryResult<()>;
     async fn authenticate(&self, user_info: &UserInfo, tenant_name: &str) -> QueryResult<User>;
+    /// Authenticate an API token, the token is checked on every call
+    /// so that dropped or expired tokens are rejected immediately
+    async fn authenticate_token(&self, token: &str, tenant_name: &str) -> QueryResult<User>;
+    /// Authenticate a JWT, it's verified by the JWKS of the OIDC provider
+    async fn authenticate_jwt(&self, token: &str, tenant_name: &str) -> QueryResult<User>;
     async fn execute(
         &self,
         query: &Query,
@@ -43,6 +49,23 @@ pub trait DatabaseManagerSystem {
     ) -> QueryResult<QueryHandle>;
     fn metrics(&self) -> String;
     fn cancel(&self, query_id: &QueryId);
+    /// Execute a query in the background, its results are spooled and fetched by pages later.
+    async fn execute_async(
+        &self,
+        query: &Query,
+        span_context: Option<&SpanContext>,
+    ) -> QueryResult<QueryId>;
+    /// Errors:
+    ///     [`QueryError::QueryNotFound`] if the async query doesn't exist or is not visible to the user
+    fn async_query_status(&self, user: &User, query_id: &QueryId) -> QueryResult<AsyncQueryStatus>;
+    async fn fetch_async_query_result(
+        &self,
+        user: &User,
+        query_id: &QueryId,
+        page: usize,
+    ) -> QueryResult<Output>;
+    /// Cancel the async query if it is still running, and delete its results
+    async fn remove_async_query(&self, user: &User, query_id: &QueryId) -> QueryResult<()>;
 }
 
 pub struct DatabaseManagerSystemMock {}
@@ -65,6 +88,16 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
         Ok(mock_user)
     }
 
+    async fn authenticate_token(&self, _token: &str, _tenant_name: &str) -> QueryResult<User> {
+        let mock_desc = UserDesc::new(0_u128, "token".to_string(), UserOptions::default(), true);
+        let mock_user = User::new(mock_desc, UserRole::Dba.to_privileges(), None);
+        Ok(mock_user)
+    }
+
+    async fn authenticate_jwt(&self, token: &str, tenant_name: &str) -> QueryResult<User> {
+        self.authenticate_token(token, tenant_name).await
+    }
+
     async fn execute(
         &self,
         query: &Query,
@@ -141,4 +174,43 @@ impl DatabaseManagerSystem for DatabaseManagerSystemMock {
     fn cancel(&self, query_id: &QueryId) {
         println!("DatabaseManagerSystemMock::cancel({:?})", query_id);
     }
+
+    async fn execute_async(
+        &self,
+        query: &Query,
+        _span_context: Option<&SpanContext>,
+    ) -> QueryResult<QueryId> {
+        println!(
+            "DatabaseManagerSystemMock::execute_async({:?})",
+            query.content()
+        );
+        Ok(QueryId::next_id())
+    }
+
+    fn async_query_status(
+        &self,
+        _user: &User,
+        query_id: &QueryId,
+    ) -> QueryResult<AsyncQueryStatus> {
+        Err(QueryError::QueryNotFound {
+            query_id: *query_id,
+        })
+    }
+
+    async fn fetch_async_query_result(
+        &self,
+        _user: &User,
+        query_id: &QueryId,
+        _page: usize,
+    ) -> QueryResult<Output> {
+        Err(QueryError::QueryNotFound {
+            query_id: *query_id,
+        })
+    }
+
+    async fn remove_async_query(&self, _user: &User, query_id: &QueryId) -> QueryResult<()> {
+        Err(QueryError::QueryNotFound {
+            query_id: *query_id,
+        })
+    }
 }
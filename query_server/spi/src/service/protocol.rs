@@ -1,4 +1,6 @@
 use models::auth::user::User;
+use models::consistency_level::ConsistencyLevel;
+use models::follower_read::FollowerReadLag;
 use models::schema::query_info::QueryId;
 use models::schema::{DEFAULT_CATALOG, DEFAULT_DATABASE, DEFAULT_PRECISION};
 
@@ -17,8 +19,10 @@ pub struct Context {
     database: String,
     precision: String,
     chunked: bool,
+    write_consistency: ConsistencyLevel,
     session_config: CnosSessionConfig,
     is_old: bool,
+    client_addr: Option<String>,
 }
 
 impl Context {
@@ -38,6 +42,10 @@ impl Context {
         &self.user
     }
 
+    pub fn write_consistency(&self) -> ConsistencyLevel {
+        self.write_consistency
+    }
+
     pub fn session_config(&self) -> &CnosSessionConfig {
         &self.session_config
     }
@@ -47,6 +55,10 @@ impl Context {
     pub fn is_old(&self) -> bool {
         self.is_old
     }
+    /// The address of the client sending the query, it's audited
+    pub fn client_addr(&self) -> Option<&str> {
+        self.client_addr.as_deref()
+    }
 }
 
 pub struct ContextBuilder {
@@ -55,8 +67,10 @@ pub struct ContextBuilder {
     database: String,
     precision: String,
     chunked: bool,
+    write_consistency: ConsistencyLevel,
     session_config: CnosSessionConfig,
     is_old: bool,
+    client_addr: Option<String>,
 }
 
 impl ContextBuilder {
@@ -67,8 +81,10 @@ impl ContextBuilder {
             tenant: DEFAULT_CATALOG.to_string(),
             database: DEFAULT_DATABASE.to_string(),
             chunked: Default::default(),
+            write_consistency: Default::default(),
             session_config: Default::default(),
             is_old: Default::default(),
+            client_addr: Default::default(),
         }
     }
 
@@ -109,6 +125,21 @@ impl ContextBuilder {
         self
     }
 
+    pub fn with_write_consistency(mut self, consistency: Option<ConsistencyLevel>) -> Self {
+        if let Some(consistency) = consistency {
+            self.write_consistency = consistency;
+            self.session_config = self.session_config.with_write_consistency(consistency);
+        }
+        self
+    }
+
+    pub fn with_follower_read_max_lag(mut self, max_lag: Option<FollowerReadLag>) -> Self {
+        if let Some(max_lag) = max_lag {
+            self.session_config = self.session_config.with_follower_read_max_lag(max_lag);
+        }
+        self
+    }
+
     pub fn with_chunked(mut self, chunked: Option<bool>) -> Self {
         if let Some(chunked) = chunked {
             self.chunked = chunked;
@@ -123,6 +154,13 @@ impl ContextBuilder {
         self
     }
 
+    pub fn with_client_addr(mut self, client_addr: Option<String>) -> Self {
+        if let Some(client_addr) = client_addr {
+            self.client_addr = Some(client_addr);
+        }
+        self
+    }
+
     pub fn build(self) -> Context {
         Context {
             user: self.user,
@@ -130,8 +168,10 @@ impl ContextBuilder {
             database: self.database,
             precision: self.precision,
             chunked: self.chunked,
+            write_consistency: self.write_consistency,
             session_config: self.session_config,
             is_old: self.is_old,
+            client_addr: self.client_addr,
         }
     }
 }
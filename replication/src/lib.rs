@@ -39,6 +39,9 @@ pub struct ReplicationConfig {
     pub send_append_entries_timeout: u64, //ms
     pub install_snapshot_timeout: u64,    //ms
     pub snapshot_policy: openraft::SnapshotPolicy,
+    /// Mutual TLS of the raft traffic, plaintext if not set.
+    #[serde(default)]
+    pub internal_tls: Option<config::common::InternalTlsConfig>,
 }
 
 // #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
@@ -88,6 +88,7 @@ async fn create_raft_node(dir: &str, id_port: RaftNodeId) -> ReplicationResult<R
         install_snapshot_timeout: 300 * 1000,
         //snapshot_policy: SnapshotPolicy::Never,
         snapshot_policy: SnapshotPolicy::LogsSinceLast(200),
+        internal_tls: None,
     };
     let node = RaftNode::new(id_port, info, storage, config).await.unwrap();
 
@@ -9,7 +9,7 @@ use openraft::MessageSummary;
 use parking_lot::RwLock;
 use protos::raft_service::*;
 use protos::{raft_service_time_out_client, DEFAULT_GRPC_SERVER_MESSAGE_LEN};
-use tonic::transport::{Channel, Endpoint};
+use tonic::transport::Channel;
 use trace::debug;
 
 use crate::errors::{GRPCRequestSnafu, ReplicationResult};
@@ -34,12 +34,13 @@ impl NetworkConn {
             return Ok(val.clone());
         }
 
-        let connector = Endpoint::from_shared(format!("http://{}", addr)).map_err(|err| {
-            GRPCRequestSnafu {
-                msg: format!("Connect to({}) error: {}", addr, err),
-            }
-            .build()
-        })?;
+        let connector =
+            protos::tls::endpoint(addr, self.config.internal_tls.as_ref()).map_err(|err| {
+                GRPCRequestSnafu {
+                    msg: format!("Connect to({}) error: {}", addr, err),
+                }
+                .build()
+            })?;
 
         let channel = connector.connect().await.map_err(|err| {
             GRPCRequestSnafu {
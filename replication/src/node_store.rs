@@ -1,4 +1,7 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 use std::io::Cursor;
 use std::ops::RangeBounds;
 use std::sync::Arc;
@@ -8,7 +11,9 @@ use openraft::{
     Entry, EntryPayload, LogId, MessageSummary, RaftLogReader, RaftSnapshotBuilder, RaftStorage,
     RaftTypeConfig, SnapshotMeta, StorageError, StorageIOError, StoredMembership, Vote,
 };
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
 use trace::info;
 use tracing::debug;
 
@@ -16,7 +21,7 @@ use crate::errors::ReplicationResult;
 use crate::state_store::StateStorage;
 use crate::{
     ApplyContext, ApplyStorageRef, EngineMetrics, EntriesMetrics, EntryStorageRef, RaftNodeId,
-    RaftNodeInfo, Response, TypeConfig,
+    RaftNodeInfo, Request, Response, TypeConfig,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -25,6 +30,59 @@ pub struct StoredSnapshot {
     pub meta: SnapshotMeta<RaftNodeId, RaftNodeInfo>,
 }
 
+/// The writers waiting for their entries to be appended to the local log, grouped
+/// by the hash of the entry data and woken in the order they registered.
+#[derive(Default)]
+pub struct AppendWaiters {
+    waiters: Mutex<HashMap<u64, VecDeque<oneshot::Sender<u64>>>>,
+}
+
+impl AppendWaiters {
+    fn hash(data: &Request) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The receiver gets the log index of the entry once an entry with the data is appended.
+    pub fn register(&self, data: &Request) -> oneshot::Receiver<u64> {
+        let (sender, receiver) = oneshot::channel();
+        let mut waiters = self.waiters.lock();
+        // drop the writers that stopped waiting, their entries may never be appended
+        waiters.retain(|_, senders| {
+            senders.retain(|s| !s.is_closed());
+            !senders.is_empty()
+        });
+        waiters
+            .entry(Self::hash(data))
+            .or_default()
+            .push_back(sender);
+
+        receiver
+    }
+
+    fn notify(&self, entries: &[Entry<TypeConfig>]) {
+        let mut waiters = self.waiters.lock();
+        if waiters.is_empty() {
+            return;
+        }
+
+        for entry in entries {
+            if let EntryPayload::Normal(data) = &entry.payload {
+                let hash = Self::hash(data);
+                if let Some(senders) = waiters.get_mut(&hash) {
+                    if let Some(sender) = senders.pop_front() {
+                        let _ = sender.send(entry.log_id.index);
+                    }
+                    if senders.is_empty() {
+                        waiters.remove(&hash);
+                    }
+                }
+            }
+        }
+    }
+}
+
 // #[derive(Clone)]
 pub struct NodeStorage {
     id: RaftNodeId,
@@ -32,6 +90,7 @@ pub struct NodeStorage {
     state: Arc<StateStorage>,
     engine: ApplyStorageRef,
     raft_logs: EntryStorageRef,
+    append_waiters: AppendWaiters,
 }
 
 impl NodeStorage {
@@ -48,6 +107,7 @@ impl NodeStorage {
             state,
             engine,
             raft_logs,
+            append_waiters: AppendWaiters::default(),
         };
 
         storage.create_snapshot().await?;
@@ -59,6 +119,10 @@ impl NodeStorage {
         self.info.group_id
     }
 
+    pub fn append_waiters(&self) -> &AppendWaiters {
+        &self.append_waiters
+    }
+
     pub async fn destory(&self) -> ReplicationResult<()> {
         self.state.del_group(self.group_id())?;
         self.engine.write().await.destory().await?;
@@ -402,6 +466,9 @@ impl RaftStorage<TypeConfig> for Arc<NodeStorage> {
         logs.append(&entries)
             .await
             .map_err(|e| StorageIOError::write_logs(&e))?;
+        drop(logs);
+
+        self.append_waiters.notify(&entries);
 
         Ok(())
     }
@@ -482,10 +549,6 @@ impl RaftStorage<TypeConfig> for Arc<NodeStorage> {
                 entry.summary()
             ); // term-raftid-index:payload type
 
-            self.state
-                .set_last_applied_log(self.group_id(), entry.log_id)
-                .map_err(|e| StorageIOError::write(&e))?;
-
             match entry.payload {
                 EntryPayload::Blank => {
                     res.push(vec![]);
@@ -516,6 +579,11 @@ impl RaftStorage<TypeConfig> for Arc<NodeStorage> {
                     res.push(vec![]);
                 }
             };
+
+            // Set after the entry is applied, a failed entry is applied again after a restart.
+            self.state
+                .set_last_applied_log(self.group_id(), entry.log_id)
+                .map_err(|e| StorageIOError::write(&e))?;
         }
 
         Ok(res)
@@ -527,7 +595,9 @@ mod test {
 
     use tokio::sync::RwLock;
 
-    use super::NodeStorage;
+    use openraft::{CommittedLeaderId, Entry, EntryPayload, LogId};
+
+    use super::{AppendWaiters, NodeStorage};
     use crate::apply_store::HeedApplyStorage;
     use crate::entry_store::HeedEntryStorage;
     use crate::state_store::StateStorage;
@@ -543,6 +613,29 @@ mod test {
         let _ = std::fs::remove_dir_all(path);
     }
 
+    #[test]
+    fn test_append_waiters() {
+        let entry = |index: u64, data: Vec<u8>| Entry {
+            log_id: LogId::new(CommittedLeaderId::new(1, 1), index),
+            payload: EntryPayload::Normal(data),
+        };
+
+        let waiters = AppendWaiters::default();
+        let mut first = waiters.register(&vec![1]);
+        let mut second = waiters.register(&vec![1]);
+        let mut other = waiters.register(&vec![2]);
+
+        // the entries appended by other writers don't wake the waiter
+        waiters.notify(&[entry(5, vec![1]), entry(6, vec![3])]);
+        assert_eq!(first.try_recv().unwrap(), 5);
+        assert!(second.try_recv().is_err());
+        assert!(other.try_recv().is_err());
+
+        waiters.notify(&[entry(7, vec![2]), entry(8, vec![1])]);
+        assert_eq!(other.try_recv().unwrap(), 7);
+        assert_eq!(second.try_recv().unwrap(), 8);
+    }
+
     #[allow(dead_code)]
     pub async fn get_node_store() -> Arc<NodeStorage> {
         let path = tempfile::tempdir_in("/tmp/cnosdb/test_raft_store").unwrap();
@@ -1,9 +1,11 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use openraft::storage::Adaptor;
 use openraft::{OptionalSend, RaftMetrics};
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
 use tracing::info;
 
 use crate::errors::{RaftInternalErrSnafu, ReplicationError, ReplicationResult};
@@ -11,6 +13,7 @@ use crate::network_client::NetworkConn;
 use crate::node_store::NodeStorage;
 use crate::{
     EngineMetrics, EntriesMetrics, OpenRaftNode, RaftNodeId, RaftNodeInfo, ReplicationConfig,
+    Request,
 };
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -20,6 +23,23 @@ pub struct RaftNodeMetrics {
     pub entries: EntriesMetrics,
 }
 
+/// How far a follower is behind the leader, measured on the leader from the
+/// entries replicated to the follower. The follower may not have applied all of
+/// them yet, the lag only holds once its applied index reaches `matched`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FollowerLag {
+    /// Index of the last entry replicated to the follower.
+    pub matched: u64,
+    /// Number of entries applied by the leader but not yet replicated to the follower.
+    pub entries: u64,
+    /// Milliseconds since the oldest entry missing on the follower was applied by
+    /// the leader, `None` if that entry is older than the tracked history.
+    pub millis: Option<u64>,
+}
+
+/// Max number of applied entries whose apply time is remembered by the leader.
+const MAX_TRACKED_APPLIED: usize = 8192;
+
 #[derive(Clone)]
 pub struct RaftNode {
     id: RaftNodeId,
@@ -27,6 +47,7 @@ pub struct RaftNode {
     storage: Arc<NodeStorage>,
 
     raft: OpenRaftNode,
+    applied_times: Arc<Mutex<VecDeque<(u64, Instant)>>>,
 }
 
 impl RaftNode {
@@ -71,6 +92,7 @@ impl RaftNode {
             info,
             storage,
             raft,
+            applied_times: Arc::new(Mutex::new(VecDeque::new())),
         })
     }
 
@@ -86,6 +108,12 @@ impl RaftNode {
         self.raft.clone()
     }
 
+    /// Register before writing `data`, the receiver gets the log index of the entry
+    /// once it is appended to the local log.
+    pub fn wait_appended(&self, data: &Request) -> oneshot::Receiver<u64> {
+        self.storage.append_waiters().register(data)
+    }
+
     /// Initialize a single-node cluster.
     pub async fn raft_init(
         &self,
@@ -204,6 +232,80 @@ impl RaftNode {
         self.raft.metrics().borrow().clone()
     }
 
+    /// Index of the last entry applied by this node.
+    pub fn applied_index(&self) -> u64 {
+        self.raft
+            .metrics()
+            .borrow()
+            .last_applied
+            .map(|log_id| log_id.index)
+            .unwrap_or(0)
+    }
+
+    /// Remember when an entry written through this node was applied, used to
+    /// estimate the replication lag of followers in time.
+    pub fn record_applied(&self, index: u64) {
+        let mut applied_times = self.applied_times.lock();
+        if applied_times.back().is_some_and(|(last, _)| *last >= index) {
+            return;
+        }
+
+        applied_times.push_back((index, Instant::now()));
+        if applied_times.len() > MAX_TRACKED_APPLIED {
+            applied_times.pop_front();
+        }
+    }
+
+    /// Get the replication lag of every voter except this node, `None` if this
+    /// node is not the leader of the raft group.
+    pub fn followers_lag(&self) -> Option<BTreeMap<RaftNodeId, FollowerLag>> {
+        let metrics = self.raft_metrics();
+        if metrics.current_leader != Some(self.id) {
+            return None;
+        }
+        let replication = metrics.replication.as_ref()?;
+        let applied = metrics.last_applied.map(|log_id| log_id.index).unwrap_or(0);
+
+        let applied_times = self.applied_times.lock();
+        let mut lags = BTreeMap::new();
+        for id in metrics.membership_config.membership().voter_ids() {
+            if id == self.id {
+                continue;
+            }
+
+            let matched = replication
+                .get(&id)
+                .and_then(|log_id| log_id.as_ref())
+                .map(|log_id| log_id.index)
+                .unwrap_or(0);
+            let entries = applied.saturating_sub(matched);
+            let millis = if entries == 0 {
+                Some(0)
+            } else {
+                // The first tracked entry after `matched` is the oldest one the
+                // follower is missing, unless entries before it are not tracked.
+                match applied_times.front() {
+                    Some((first, _)) if *first <= matched + 1 => applied_times
+                        .iter()
+                        .find(|(index, _)| *index > matched)
+                        .map(|(_, time)| time.elapsed().as_millis() as u64),
+                    _ => None,
+                }
+            };
+
+            lags.insert(
+                id,
+                FollowerLag {
+                    matched,
+                    entries,
+                    millis,
+                },
+            );
+        }
+
+        Some(lags)
+    }
+
     pub async fn engine_metrics(&self) -> ReplicationResult<EngineMetrics> {
         self.storage.engine_metrics().await
     }
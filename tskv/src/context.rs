@@ -1,15 +1,24 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+
+use parking_lot::RwLock;
+
+use crate::VnodeId;
 
 #[derive(Default, Debug)]
 pub struct GlobalContext {
     /// Database file id
     file_id: AtomicU64,
+    /// Data versions of the opened vnodes, to read them without locking the databases
+    data_versions: RwLock<HashMap<VnodeId, Weak<AtomicU64>>>,
 }
 
 impl GlobalContext {
     pub fn new() -> Self {
         Self {
             file_id: AtomicU64::new(0),
+            data_versions: RwLock::new(HashMap::new()),
         }
     }
 }
@@ -30,6 +39,23 @@ impl GlobalContext {
         self.file_id.store(v, Ordering::Release);
     }
 
+    /// Register the data version of an opened vnode, replaces the version of
+    /// a previous vnode with the same id.
+    pub fn register_data_version(&self, vnode_id: VnodeId, data_version: &Arc<AtomicU64>) {
+        let mut data_versions = self.data_versions.write();
+        data_versions.retain(|_, e| e.strong_count() > 0);
+        data_versions.insert(vnode_id, Arc::downgrade(data_version));
+    }
+
+    /// Get the data version of a vnode, None if the vnode is not opened.
+    pub fn data_version(&self, vnode_id: VnodeId) -> Option<u64> {
+        self.data_versions
+            .read()
+            .get(&vnode_id)
+            .and_then(|e| e.upgrade())
+            .map(|e| e.load(Ordering::SeqCst))
+    }
+
     pub fn mark_file_id_used(&self, v: u64) {
         let mut old = self.file_id.load(Ordering::Acquire);
         while old <= v {
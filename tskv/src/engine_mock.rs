@@ -106,6 +106,10 @@ impl Engine for MockEngine {
         todo!()
     }
 
+    async fn get_vnode_data_version(&self, vnode_id: VnodeId) -> TskvResult<Option<u64>> {
+        Ok(None)
+    }
+
     async fn get_vnode_hash_tree(&self, vnode_ids: VnodeId) -> TskvResult<RecordBatch> {
         todo!()
     }
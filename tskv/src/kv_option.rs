@@ -3,7 +3,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use config::tskv::Config;
+use config::tskv::{AuditConfig, Config, JwtConfig, LdapConfig};
 use models::codec::Encoding;
 use models::meta_data::{NodeId, VnodeId};
 
@@ -125,6 +125,18 @@ pub struct QueryOptions {
     pub write_timeout: Duration,
     pub stream_trigger_cpu: usize,
     pub stream_executor_cpu: usize,
+    pub async_result_dir: String,
+    pub async_result_ttl: Duration,
+    pub async_result_page_rows: usize,
+    pub async_max_queries_per_user: usize,
+    pub async_max_queries_per_tenant: usize,
+    pub result_cache_enabled: bool,
+    pub result_cache_capacity: usize,
+    pub result_cache_max_result_size: u64,
+    pub result_cache_version_ttl: Duration,
+    pub jwt: Option<JwtConfig>,
+    pub audit: Option<AuditConfig>,
+    pub ldap: Option<LdapConfig>,
 }
 
 impl From<&Config> for QueryOptions {
@@ -138,6 +150,18 @@ impl From<&Config> for QueryOptions {
             write_timeout: config.query.write_timeout,
             stream_trigger_cpu: config.query.stream_trigger_cpu,
             stream_executor_cpu: config.query.stream_executor_cpu,
+            async_result_dir: config.query.async_result_dir.clone(),
+            async_result_ttl: config.query.async_result_ttl,
+            async_result_page_rows: config.query.async_result_page_rows,
+            async_max_queries_per_user: config.query.async_max_queries_per_user,
+            async_max_queries_per_tenant: config.query.async_max_queries_per_tenant,
+            result_cache_enabled: config.query.result_cache_enabled,
+            result_cache_capacity: config.query.result_cache_capacity,
+            result_cache_max_result_size: config.query.result_cache_max_result_size,
+            result_cache_version_ttl: config.query.result_cache_version_ttl,
+            jwt: config.security.jwt.clone(),
+            audit: config.security.audit.clone(),
+            ldap: config.security.ldap.clone(),
         }
     }
 }
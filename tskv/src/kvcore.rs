@@ -422,6 +422,10 @@ impl Engine for TsKv {
         }
     }
 
+    async fn get_vnode_data_version(&self, vnode_id: VnodeId) -> TskvResult<Option<u64>> {
+        Ok(self.ctx.global_ctx.data_version(vnode_id))
+    }
+
     fn get_storage_options(&self) -> Arc<StorageOptions> {
         self.ctx.options.storage.clone()
     }
@@ -124,6 +124,10 @@ pub trait Engine: Send + Sync + Debug {
         vnode_id: u32,
     ) -> TskvResult<Option<Arc<SuperVersion>>>;
 
+    /// Get the data version of a storage unit, it changes whenever the data of the
+    /// storage unit may have changed. Returns None if the storage unit does not exist.
+    async fn get_vnode_data_version(&self, vnode_id: VnodeId) -> TskvResult<Option<u64>>;
+
     /// Get the storage options which was used to install the engine.
     fn get_storage_options(&self) -> Arc<StorageOptions>;
 
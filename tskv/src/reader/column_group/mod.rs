@@ -12,7 +12,7 @@ use futures::{ready, Stream, StreamExt};
 use models::arrow::stream::BoxStream;
 use models::{ColumnId, SeriesId};
 
-use super::metrics::BaselineMetrics;
+use super::metrics::{BaselineMetrics, ScanStats};
 use super::{
     BatchReader, BatchReaderRef, SchemableTskvRecordBatchStream,
     SendableSchemableTskvRecordBatchStream,
@@ -29,8 +29,10 @@ pub struct ColumnGroupReader {
     pages_meta: Vec<PageWriteSpec>,
     schema: SchemaRef,
     metrics: Arc<ExecutionPlanMetricsSet>,
+    scan_stats: ScanStats,
 }
 impl ColumnGroupReader {
+    #[allow(clippy::too_many_arguments)]
     pub fn try_new(
         reader: Arc<TsmReader>,
         series_id: SeriesId,
@@ -39,6 +41,7 @@ impl ColumnGroupReader {
         schema_meta: HashMap<String, String>,
         _batch_size: usize,
         metrics: Arc<ExecutionPlanMetricsSet>,
+        scan_stats: ScanStats,
     ) -> TskvResult<Self> {
         let pages_meta = projection
             .iter()
@@ -66,6 +69,7 @@ impl ColumnGroupReader {
             pages_meta,
             schema,
             metrics,
+            scan_stats,
         })
     }
 }
@@ -78,6 +82,7 @@ impl BatchReader for ColumnGroupReader {
             self.pages_meta.clone(),
             self.schema.metadata().clone(),
             ColumnGroupReaderMetrics::new(self.metrics.as_ref()),
+            self.scan_stats.clone(),
         )));
 
         Ok(Box::pin(ColumnGroupRecordBatchStream {
@@ -198,6 +203,7 @@ async fn read(
     pages_meta: Vec<PageWriteSpec>,
     schema_meta: HashMap<String, String>,
     metrics: ColumnGroupReaderMetrics,
+    scan_stats: ScanStats,
 ) -> TskvResult<RecordBatch> {
     let mut sorted_pages = pages_meta.clone();
     sorted_pages.sort_by_key(|p| p.offset());
@@ -210,6 +216,8 @@ async fn read(
         metrics.page_read_count().add(batch.len());
         let total_size: usize = batch.iter().map(|p| p.size() as usize).sum();
         metrics.page_read_bytes().add(total_size);
+        scan_stats.pages_read().add(batch.len());
+        scan_stats.bytes_decoded().add(total_size);
         let batch_pages = reader.read_adjacent_pages(&batch).await?;
         pages.extend(batch_pages);
     }
@@ -259,7 +267,7 @@ mod tests {
     use models::{SeriesKey, ValueType};
 
     use crate::reader::column_group::ColumnGroupReader;
-    use crate::reader::BatchReader;
+    use crate::reader::{BatchReader, ScanStats};
     use crate::tsm::reader::TsmReader;
     use crate::tsm::writer::TsmWriter;
 
@@ -347,6 +355,7 @@ mod tests {
             pages_meta,
             schema: df_schema,
             metrics: Arc::new(ExecutionPlanMetricsSet::new()),
+            scan_stats: ScanStats::new(),
         };
 
         let stream = column_group_reader.process().expect("chunk_reader");
@@ -27,6 +27,7 @@ use trace::{debug, error, Span, SpanContext};
 use super::display::DisplayableBatchReader;
 use super::memcache_reader::MemCacheReader;
 use super::merge::DataMerger;
+use super::metrics::ScanStats;
 use super::pushdown_agg_reader::{PushDownAggregateReader, PushDownAggregateStream};
 use super::series::SeriesReader;
 use super::trace::Recorder;
@@ -170,6 +171,7 @@ impl SeriesGroupBatchReaderFactory {
             let _timer = metrics.elapsed_get_tsm_readers_time().timer();
             for f in column_files {
                 let reader = super_version.version.get_tsm_reader(f.file_path()).await?;
+                self.query_option.scan_stats.files_opened().add(1);
                 column_files_with_reader.push((f, reader));
             }
         }
@@ -366,9 +368,15 @@ impl SeriesGroupBatchReaderFactory {
                     // filter column groups
                     metrics.column_group_nums().add(cgs.len());
                     debug!("All column group nums: {}", cgs.len());
+                    let all_page_nums: usize = cgs.iter().map(|e| e.pages().len()).sum();
                     let cgs = filter_column_groups(cgs, predicate, chunk_schema.clone())?;
                     debug!("Filtered column group nums: {}", cgs.len());
                     metrics.filtered_column_group_nums().add(cgs.len());
+                    let page_nums: usize = cgs.iter().map(|e| e.pages().len()).sum();
+                    self.query_option
+                        .scan_stats
+                        .pages_skipped()
+                        .add(all_page_nums - page_nums);
 
                     let batch_readers = cgs
                         .into_iter()
@@ -381,6 +389,7 @@ impl SeriesGroupBatchReaderFactory {
                                 chunk_schema.metadata().clone(),
                                 batch_size,
                                 self.column_group_reader_metrics_set.clone(),
+                                self.query_option.scan_stats.clone(),
                             )?;
                             Ok(Arc::new(column_group_reader) as BatchReaderRef)
                         })
@@ -394,6 +403,7 @@ impl SeriesGroupBatchReaderFactory {
                     batch_size,
                     projection,
                     self.query_option.schema_meta.clone(),
+                    self.query_option.scan_stats.clone(),
                 )?
                 .map(|e| e as BatchReaderRef),
             };
@@ -718,6 +728,8 @@ pub struct QueryOption {
     pub table_schema: TskvTableSchemaRef,
     pub schema_meta: HashMap<String, String>,
     pub aggregates: Option<Vec<PushedAggregateFunction>>, // TODO: Use PushedAggregateFunction
+    /// Collected by the readers of the scan, not sent to remote nodes
+    pub scan_stats: ScanStats,
 }
 
 impl QueryOption {
@@ -737,6 +749,7 @@ impl QueryOption {
             df_schema,
             table_schema,
             schema_meta,
+            scan_stats: ScanStats::new(),
         }
     }
 
@@ -768,6 +781,7 @@ impl QueryOption {
             args: args_bytes,
             expr: expr_bytes,
             aggs: aggs_bytes,
+            with_scan_stats: true,
         })
     }
 }
@@ -928,6 +942,11 @@ async fn build_stream(
             })?
     };
 
+    query_option
+        .scan_stats
+        .series_matched()
+        .add(series_ids.len());
+
     // TODO 这里需要验证table schema是否正确
     let expr = query_option.split.filter();
     let arrow_schema = query_option.table_schema.to_arrow_schema();
@@ -23,6 +23,7 @@ use crate::mem_cache::row_data::RowData;
 use crate::mem_cache::series_data::SeriesData;
 use crate::reader::array_builder::ArrayBuilderPtr;
 use crate::reader::iterator::RowIterator;
+use crate::reader::metrics::ScanStats;
 use crate::reader::utils::TimeRangeProvider;
 use crate::{TskvError, TskvResult};
 
@@ -37,6 +38,7 @@ pub struct MemCacheReader {
     columns: Vec<TableColumn>,
     schema_meta: HashMap<String, String>,
     read_mode: MemcacheReadMode,
+    scan_stats: ScanStats,
 }
 
 impl TimeRangeProvider for MemCacheReader {
@@ -55,6 +57,7 @@ impl MemCacheReader {
         batch_size: usize,
         projection: &[ColumnId],
         schema_meta: HashMap<String, String>,
+        scan_stats: ScanStats,
     ) -> TskvResult<Option<Arc<Self>>> {
         if let Some(tskv_schema) = series_data.read().get_schema() {
             // filter columns by projection
@@ -80,6 +83,7 @@ impl MemCacheReader {
                 columns,
                 schema_meta,
                 read_mode,
+                scan_stats,
             })))
         } else {
             Ok(None)
@@ -165,6 +169,9 @@ impl MemCacheReader {
 impl BatchReader for MemCacheReader {
     fn process(&self) -> TskvResult<SendableSchemableTskvRecordBatchStream> {
         let builders = self.read_data_and_build_array()?;
+        if let Some(builder) = builders.first() {
+            self.scan_stats.memcache_rows().add(builder.ptr.len());
+        }
         let fields = self.columns.iter().map(Field::from).collect::<Vec<_>>();
         let schema = Arc::new(Schema::new_with_metadata(fields, self.schema_meta.clone()));
 
@@ -262,7 +269,7 @@ mod tests {
     use crate::mem_cache::memcache::MemCache;
     use crate::mem_cache::row_data::{OrderedRowsData, RowData};
     use crate::mem_cache::series_data::RowGroup;
-    use crate::reader::BatchReader;
+    use crate::reader::{BatchReader, ScanStats};
 
     #[tokio::test]
     async fn test_memcache_reader() {
@@ -312,12 +319,14 @@ mod tests {
             .unwrap();
 
         let trs = Arc::new(TimeRanges::new(vec![TimeRange::new(1, 3)]));
+        let scan_stats = ScanStats::new();
         let memcache_reader: Arc<dyn BatchReader> = MemCacheReader::try_new(
             mem_cache.read_all_series_data()[0].1.clone(),
             trs,
             2,
             &[1, 2, 3],
             HashMap::new(),
+            scan_stats.clone(),
         )
         .unwrap()
         .unwrap();
@@ -332,5 +341,6 @@ mod tests {
             "+-------------------------------+----+-----+",
         ];
         assert_batches_eq!(expected, &result);
+        assert_eq!(scan_stats.memcache_rows().value(), 2);
     }
 }
@@ -3,7 +3,8 @@ use std::task::Poll;
 
 use arrow_array::RecordBatch;
 use datafusion::physical_plan::metrics::{
-    BaselineMetrics as DFBaselineMetrics, ExecutionPlanMetricsSet, RecordOutput,
+    BaselineMetrics as DFBaselineMetrics, Count, ExecutionPlanMetricsSet, Gauge, MetricBuilder,
+    RecordOutput, Time,
 };
 
 use crate::TskvResult;
@@ -46,3 +47,151 @@ impl BaselineMetrics {
         poll
     }
 }
+
+/// Storage level I/O statistics of scanning one vnode, shared by all readers
+/// building the scan and reported by EXPLAIN ANALYZE.
+#[derive(Debug, Clone, Default)]
+pub struct ScanStats {
+    /// The vnode that was actually scanned, set once a replica is opened
+    vnode_id: Gauge,
+    files_opened: Count,
+    pages_read: Count,
+    /// Pages of the column groups pruned by statistics
+    pages_skipped: Count,
+    bytes_decoded: Count,
+    memcache_rows: Count,
+    series_matched: Count,
+    /// Time from sending the scan request to a remote node to the end of its response
+    rpc_time: Time,
+}
+
+impl ScanStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn vnode_id(&self) -> &Gauge {
+        &self.vnode_id
+    }
+
+    pub fn files_opened(&self) -> &Count {
+        &self.files_opened
+    }
+
+    pub fn pages_read(&self) -> &Count {
+        &self.pages_read
+    }
+
+    pub fn pages_skipped(&self) -> &Count {
+        &self.pages_skipped
+    }
+
+    pub fn bytes_decoded(&self) -> &Count {
+        &self.bytes_decoded
+    }
+
+    pub fn memcache_rows(&self) -> &Count {
+        &self.memcache_rows
+    }
+
+    pub fn series_matched(&self) -> &Count {
+        &self.series_matched
+    }
+
+    pub fn rpc_time(&self) -> &Time {
+        &self.rpc_time
+    }
+
+    pub fn to_proto(&self) -> protos::kv_service::ScanStats {
+        protos::kv_service::ScanStats {
+            files_opened: self.files_opened.value() as u64,
+            pages_read: self.pages_read.value() as u64,
+            pages_skipped: self.pages_skipped.value() as u64,
+            bytes_decoded: self.bytes_decoded.value() as u64,
+            memcache_rows: self.memcache_rows.value() as u64,
+            series_matched: self.series_matched.value() as u64,
+        }
+    }
+
+    /// Add the statistics reported by a remote node
+    pub fn merge_proto(&self, stats: &protos::kv_service::ScanStats) {
+        self.files_opened.add(stats.files_opened as usize);
+        self.pages_read.add(stats.pages_read as usize);
+        self.pages_skipped.add(stats.pages_skipped as usize);
+        self.bytes_decoded.add(stats.bytes_decoded as usize);
+        self.memcache_rows.add(stats.memcache_rows as usize);
+        self.series_matched.add(stats.series_matched as usize);
+    }
+
+    /// Register the statistics into the metrics of an execution plan partition,
+    /// labeled by the scanned vnode.
+    pub fn register(&self, metrics: &ExecutionPlanMetricsSet, partition: usize) {
+        let vnode_id = self.vnode_id.value().to_string();
+        let builder = || MetricBuilder::new(metrics).with_new_label("vnode", vnode_id.clone());
+
+        builder()
+            .counter("files_opened", partition)
+            .add(self.files_opened.value());
+        builder()
+            .counter("pages_read", partition)
+            .add(self.pages_read.value());
+        builder()
+            .counter("pages_skipped", partition)
+            .add(self.pages_skipped.value());
+        builder()
+            .counter("bytes_decoded", partition)
+            .add(self.bytes_decoded.value());
+        builder()
+            .counter("memcache_rows", partition)
+            .add(self.memcache_rows.value());
+        builder()
+            .counter("series_matched", partition)
+            .add(self.series_matched.value());
+        builder()
+            .subset_time("rpc_time", partition)
+            .add(&self.rpc_time);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::physical_plan::metrics::{ExecutionPlanMetricsSet, Label};
+
+    use super::ScanStats;
+
+    #[test]
+    fn test_scan_stats_register() {
+        let remote = ScanStats::new();
+        remote.files_opened().add(2);
+        remote.pages_read().add(10);
+        remote.pages_skipped().add(4);
+        remote.series_matched().add(3);
+
+        let stats = ScanStats::new();
+        stats.vnode_id().set(7);
+        stats.memcache_rows().add(5);
+        stats.merge_proto(&remote.to_proto());
+
+        let metrics = ExecutionPlanMetricsSet::new();
+        stats.register(&metrics, 1);
+        let metrics = metrics.clone_inner();
+
+        let value = |name: &str| {
+            metrics
+                .iter()
+                .find(|m| m.value().name() == name)
+                .map(|m| {
+                    assert_eq!(m.partition(), Some(1));
+                    assert_eq!(m.labels(), &[Label::new("vnode", "7")]);
+                    m.value().as_usize()
+                })
+                .unwrap()
+        };
+        assert_eq!(value("files_opened"), 2);
+        assert_eq!(value("pages_read"), 10);
+        assert_eq!(value("pages_skipped"), 4);
+        assert_eq!(value("bytes_decoded"), 0);
+        assert_eq!(value("memcache_rows"), 5);
+        assert_eq!(value("series_matched"), 3);
+    }
+}
@@ -14,6 +14,7 @@ use datafusion::physical_plan::PhysicalExpr;
 use futures::stream::BoxStream;
 use futures::{Stream, StreamExt};
 pub use iterator::QueryOption;
+pub use metrics::ScanStats;
 use models::field_value::DataType;
 use models::predicate::domain::{TimeRange, TimeRanges};
 use models::schema::tskv_table_schema::{PhysicalCType, TskvTableSchema};
@@ -33,7 +34,7 @@ mod chunk;
 mod column_group;
 pub mod display;
 pub mod filter;
-mod function_register;
+pub(crate) mod function_register;
 
 mod iterator;
 mod memcache_reader;
@@ -8,17 +8,61 @@ use snafu::IntoError;
 use trace::Span;
 
 use crate::error::{ArrowSnafu, TskvResult};
-use crate::reader::SendableTskvRecordBatchStream;
+use crate::reader::{ScanStats, SendableTskvRecordBatchStream};
 
 pub struct TonicRecordBatchEncoder {
     input: SendableTskvRecordBatchStream,
+    /// Sent in the responses without data before the batches read along with them
+    /// and once the input is exhausted, so that the scan is charged as it goes
+    scan_stats: Option<ScanStats>,
+    /// The statistics already sent
+    reported: protos::kv_service::ScanStats,
+    /// The batch to send after the statistics read along with it
+    pending: Option<BatchBytesResponse>,
+    finished: bool,
     #[allow(unused)]
     span: Span,
 }
 
 impl TonicRecordBatchEncoder {
     pub fn new(input: SendableTskvRecordBatchStream, span: Span) -> Self {
-        Self { input, span }
+        Self {
+            input,
+            scan_stats: None,
+            reported: Default::default(),
+            pending: None,
+            finished: false,
+            span,
+        }
+    }
+
+    pub fn with_scan_stats(mut self, scan_stats: ScanStats) -> Self {
+        self.scan_stats = Some(scan_stats);
+        self
+    }
+
+    /// The response of the statistics since the previous one, None if they are unchanged
+    fn scan_stats_response(&mut self) -> Option<BatchBytesResponse> {
+        let current = self.scan_stats.as_ref()?.to_proto();
+        let reported = std::mem::replace(&mut self.reported, current.clone());
+        let delta = protos::kv_service::ScanStats {
+            files_opened: current.files_opened.saturating_sub(reported.files_opened),
+            pages_read: current.pages_read.saturating_sub(reported.pages_read),
+            pages_skipped: current.pages_skipped.saturating_sub(reported.pages_skipped),
+            bytes_decoded: current.bytes_decoded.saturating_sub(reported.bytes_decoded),
+            memcache_rows: current.memcache_rows.saturating_sub(reported.memcache_rows),
+            series_matched: current
+                .series_matched
+                .saturating_sub(reported.series_matched),
+        };
+        if delta == Default::default() {
+            return None;
+        }
+
+        Some(BatchBytesResponse {
+            scan_stats: Some(delta),
+            ..Default::default()
+        })
     }
 }
 
@@ -26,6 +70,13 @@ impl Stream for TonicRecordBatchEncoder {
     type Item = TskvResult<BatchBytesResponse>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(resp) = self.pending.take() {
+            return Poll::Ready(Some(Ok(resp)));
+        }
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
         match ready!(self.input.poll_next_unpin(cx)) {
             Some(Ok(batch)) => match record_batch_encode(&batch) {
                 Ok(body) => {
@@ -33,12 +84,21 @@ impl Stream for TonicRecordBatchEncoder {
                         data: body,
                         ..Default::default()
                     };
-                    Poll::Ready(Some(Ok(resp)))
+                    match self.scan_stats_response() {
+                        Some(stats) => {
+                            self.pending = Some(resp);
+                            Poll::Ready(Some(Ok(stats)))
+                        }
+                        None => Poll::Ready(Some(Ok(resp))),
+                    }
                 }
                 Err(err) => Poll::Ready(Some(Err(ArrowSnafu.into_error(err)))),
             },
             Some(Err(err)) => Poll::Ready(Some(Err(err))),
-            None => Poll::Ready(None),
+            None => {
+                self.finished = true;
+                Poll::Ready(self.scan_stats_response().map(Ok))
+            }
         }
     }
 }
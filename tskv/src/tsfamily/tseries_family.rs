@@ -11,6 +11,7 @@ use metrics::metric_register::MetricsRegister;
 use models::meta_data::VnodeStatus;
 use models::predicate::domain::{TimeRange, TimeRanges};
 use models::schema::database_schema::{split_owner, DatabaseConfig};
+use models::utils::now_timestamp_nanos;
 use models::{ColumnId, SeriesId, SeriesKey};
 use parking_lot::RwLock;
 use snafu::ResultExt;
@@ -90,6 +91,8 @@ impl TsfFactory {
             0,
         ));
 
+        let data_version = Arc::new(AtomicU64::new(now_timestamp_nanos() as u64));
+        self.ctx.register_data_version(tf_id, &data_version);
         let tsfamily = Arc::new(TokioRwLock::new(TseriesFamily {
             tf_id,
             ctx: self.ctx.clone(),
@@ -98,6 +101,7 @@ impl TsfFactory {
             immut_cache: vec![],
             super_version,
             super_version_id: AtomicU64::new(0),
+            data_version,
             db_config: self.db_config.clone(),
             storage_opt: self.options.storage.clone(),
             last_modified: Arc::new(Default::default()),
@@ -126,6 +130,10 @@ pub struct TseriesFamily {
     immut_cache: Vec<Arc<RwLock<MemCache>>>,
     super_version: Arc<SuperVersion>,
     super_version_id: AtomicU64,
+    /// Increased whenever the data readable from this vnode may have changed, starts
+    /// from the open time so that versions are not reused after a restart,
+    /// registered in the global context
+    data_version: Arc<AtomicU64>,
     db_config: Arc<DatabaseConfig>,
     storage_opt: Arc<StorageOptions>,
     last_modified: Arc<tokio::sync::RwLock<Option<Instant>>>,
@@ -165,6 +173,7 @@ impl TseriesFamily {
                 0,
             )),
             super_version_id: AtomicU64::new(0),
+            data_version: Arc::new(AtomicU64::new(now_timestamp_nanos() as u64)),
             db_config,
             storage_opt,
             last_modified: Arc::new(tokio::sync::RwLock::new(None)),
@@ -176,6 +185,7 @@ impl TseriesFamily {
 
     fn new_super_version(&mut self, version: Arc<Version>) {
         self.super_version_id.fetch_add(1, Ordering::SeqCst);
+        self.bump_data_version();
         self.tsf_metrics.record_disk_storage(self.disk_storage());
         self.tsf_metrics.record_cache_size(self.cache_size());
         self.super_version = Arc::new(SuperVersion::new(
@@ -424,6 +434,16 @@ impl TseriesFamily {
         self.mut_cache.read().seq_no()
     }
 
+    /// A number that changes after every write, delete, flush or compaction of this vnode,
+    /// query results over this vnode can be reused as long as it does not change.
+    pub fn data_version(&self) -> u64 {
+        self.data_version.load(Ordering::SeqCst)
+    }
+
+    pub fn bump_data_version(&self) {
+        self.data_version.fetch_add(1, Ordering::SeqCst);
+    }
+
     pub fn super_version(&self) -> Arc<SuperVersion> {
         self.super_version.clone()
     }
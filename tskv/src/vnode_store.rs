@@ -2,9 +2,15 @@ use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
+use datafusion::arrow::array::{ArrayRef, StringArray};
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion::common::cast::as_boolean_array;
+use datafusion_proto::physical_plan::from_proto::parse_physical_expr;
+use datafusion_proto::protobuf::PhysicalExprNode;
 use metrics::average::U64Average;
 use models::meta_data::VnodeId;
 use models::predicate::domain::{ResolvedPredicate, TimeRange, TimeRanges};
+use models::schema::tskv_table_schema::TskvTableSchema;
 use models::utils::now_timestamp_secs;
 use models::{ColumnId, SeriesId, SeriesKey};
 use protos::kv_service::{raft_write_command, WritePointsResponse, *};
@@ -18,8 +24,11 @@ use utils::precision::Precision;
 use crate::compaction::job::FlushJob;
 use crate::compaction::FlushReq;
 use crate::database::Database;
-use crate::error::{IndexErrSnafu, InvalidParamSnafu, InvalidPointTableSnafu, TskvResult};
+use crate::error::{
+    ArrowSnafu, IndexErrSnafu, InvalidParamSnafu, InvalidPointTableSnafu, ModelSnafu, TskvResult,
+};
 use crate::index::ts_index::TSIndex;
+use crate::reader::function_register::NoRegistry;
 use crate::schema::error::{FieldNotFoundSnafu, TableNotFoundSnafu};
 use crate::tsfamily::tseries_family::TseriesFamily;
 use crate::{TsKvContext, VnodeSnapshot};
@@ -80,6 +89,17 @@ impl VnodeStorage {
         &self,
         ctx: &replication::ApplyContext,
         command: raft_write_command::Command,
+    ) -> TskvResult<Vec<u8>> {
+        let res = self.apply_command(ctx, command).await;
+        // The data may have changed even if the command failed half way.
+        self.ts_family.read().await.bump_data_version();
+        res
+    }
+
+    async fn apply_command(
+        &self,
+        ctx: &replication::ApplyContext,
+        command: raft_write_command::Command,
     ) -> TskvResult<Vec<u8>> {
         match command {
             raft_write_command::Command::WriteData(cmd) => {
@@ -510,11 +530,17 @@ impl VnodeStorage {
                 Some(schema) => schema,
             };
 
-            self.ts_index
-                .read()
-                .await
+            let ts_index = self.ts_index.read().await;
+            let series_ids = ts_index
                 .get_series_ids_by_domains(table_schema.as_ref(), tag_domains)
-                .await?
+                .await?;
+            // Deletes of rows matching field predicates identify the exact series by the filter
+            if predicate.filter().expr_type.is_some() {
+                filter_series_by_tags(&ts_index, &table_schema, predicate.filter(), series_ids)
+                    .await?
+            } else {
+                series_ids
+            }
         };
 
         // 执行delete，删除缓存 & 写墓碑文件
@@ -603,3 +629,51 @@ impl VnodeStorage {
         let _ = self.ts_index.write().await.flush().await;
     }
 }
+
+/// Returns the series whose tags satisfy the filter, which is evaluated against
+/// [`TskvTableSchema::to_tag_arrow_schema`].
+async fn filter_series_by_tags(
+    ts_index: &TSIndex,
+    table_schema: &TskvTableSchema,
+    filter: &PhysicalExprNode,
+    series_ids: Vec<SeriesId>,
+) -> TskvResult<Vec<SeriesId>> {
+    let schema = table_schema.to_tag_arrow_schema();
+    let expr = parse_physical_expr(filter, &NoRegistry, &schema)?;
+
+    let mut series = Vec::with_capacity(series_ids.len());
+    for sid in series_ids {
+        if let Some(key) = ts_index.get_series_key(sid).await.context(IndexErrSnafu)? {
+            series.push((sid, key));
+        }
+    }
+
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for field in schema.fields() {
+        let tag_key = table_schema
+            .column(field.name())
+            .map(|column| column.id.to_string())
+            .unwrap_or_default();
+        let values = series
+            .iter()
+            .map(|(_, key)| key.tag_string_val(&tag_key))
+            .collect::<Result<Vec<_>, _>>()
+            .context(ModelSnafu)?;
+        columns.push(Arc::new(StringArray::from(values)));
+    }
+    let batch = RecordBatch::try_new_with_options(
+        schema,
+        columns,
+        &RecordBatchOptions::new().with_row_count(Some(series.len())),
+    )
+    .context(ArrowSnafu)?;
+
+    let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+    let matched = as_boolean_array(&result)?;
+    Ok(series
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| matched.is_valid(*idx) && matched.value(*idx))
+        .map(|(_, (sid, _))| sid)
+        .collect())
+}